@@ -33,6 +33,7 @@ use sui_types::{
     id::Info,
     messages::{CallArg, EntryArgumentErrorKind, InputObjectKind, ObjectArg},
     object::{self, Data, MoveObject, Object, Owner},
+    protocol_config::ProtocolConfig,
     storage::{DeleteKind, Storage},
     SUI_SYSTEM_STATE_OBJECT_ID,
 };
@@ -42,6 +43,7 @@ use sui_verifier::{
 };
 
 use crate::bytecode_rewriter::ModuleHandleRewriter;
+use crate::execution_limits;
 use crate::object_root_ancestor_map::ObjectRootAncestorMap;
 
 pub fn new_move_vm(natives: NativeFunctionTable) -> Result<MoveVM, SuiError> {
@@ -67,11 +69,16 @@ pub fn execute<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
     args: Vec<CallArg>,
     gas_status: &mut SuiGasStatus,
     ctx: &mut TxContext,
+    protocol_config: &ProtocolConfig,
 ) -> Result<(), ExecutionError> {
+    for type_ in &type_args {
+        execution_limits::check_type_nesting(type_, protocol_config)?;
+    }
     let objects = args
         .iter()
         .filter_map(|arg| match arg {
-            CallArg::Pure(_) => None,
+            // Command results are resolved to the objects they refer to before execution.
+            CallArg::Pure(_) | CallArg::Object(ObjectArg::Result(_)) => None,
             CallArg::Object(ObjectArg::ImmOrOwnedObject((id, _, _)))
             | CallArg::Object(ObjectArg::SharedObject(id)) => {
                 Some((*id, state_view.read_object(id)?))
@@ -227,6 +234,7 @@ pub fn publish<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
     module_bytes: Vec<Vec<u8>>,
     ctx: &mut TxContext,
     gas_status: &mut SuiGasStatus,
+    protocol_config: &ProtocolConfig,
 ) -> Result<(), ExecutionError> {
     gas_status.charge_publish_package(module_bytes.iter().map(|v| v.len()).sum())?;
     let mut modules = module_bytes
@@ -240,9 +248,25 @@ pub fn publish<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error =
     if modules.is_empty() {
         return Err(ExecutionErrorKind::PublishErrorEmptyPackage.into());
     }
+    if modules.len() > protocol_config.max_modules_in_publish() {
+        return Err(ExecutionError::new_with_source(
+            ExecutionErrorKind::SuiMoveVerificationError,
+            format!(
+                "Packages are published with at most {} modules",
+                protocol_config.max_modules_in_publish()
+            ),
+        ));
+    }
 
     let package_id = generate_package_id(&mut modules, ctx)?;
-    let vm = verify_and_link(state_view, &modules, package_id, natives, gas_status)?;
+    let vm = verify_and_link(
+        state_view,
+        &modules,
+        package_id,
+        natives,
+        gas_status,
+        protocol_config,
+    )?;
     state_view.log_event(Event::Publish {
         sender: ctx.sender(),
         package_id,
@@ -315,7 +339,7 @@ fn init_modules<E: Debug, S: ResourceResolver<Error = E> + ModuleResolver<Error
 
 /// Given a list of `modules`, links each module against its
 /// dependencies and runs each module with both the Move VM verifier
-/// and the Sui verifier.
+/// and the Sui verifier, at the protocol version of `protocol_config`.
 pub fn verify_and_link<
     E: Debug,
     S: ResourceResolver<Error = E> + ModuleResolver<Error = E> + Storage,
@@ -325,6 +349,7 @@ pub fn verify_and_link<
     package_id: ObjectID,
     natives: NativeFunctionTable,
     gas_status: &mut SuiGasStatus,
+    protocol_config: &ProtocolConfig,
 ) -> Result<MoveVM, ExecutionError> {
     // Run the Move bytecode verifier and linker.
     // It is important to do this before running the Sui verifier, since the sui
@@ -352,7 +377,7 @@ pub fn verify_and_link<
     // run the Sui verifier
     for module in modules.iter() {
         // Run Sui bytecode verifier, which runs some additional checks that assume the Move bytecode verifier has passed.
-        verifier::verify_module(module)?;
+        verifier::verify_module(module, protocol_config)?;
     }
     Ok(vm)
 }
@@ -367,8 +392,18 @@ pub fn generate_package_id(
     modules: &mut [CompiledModule],
     ctx: &mut TxContext,
 ) -> Result<ObjectID, ExecutionError> {
-    let mut sub_map = BTreeMap::new();
     let package_id = ctx.fresh_id();
+    substitute_package_id(modules, package_id)?;
+    Ok(package_id)
+}
+
+/// Rewrite the address of `modules`, which must all be 0x0, to `package_id`, as done when the
+/// modules are published.
+pub fn substitute_package_id(
+    modules: &mut [CompiledModule],
+    package_id: ObjectID,
+) -> Result<(), ExecutionError> {
+    let mut sub_map = BTreeMap::new();
     for module in modules.iter() {
         let old_module_id = module.self_id();
         let old_address = *old_module_id.address();
@@ -398,7 +433,7 @@ pub fn generate_package_id(
         // rewrite module handles to reflect freshly generated ID's
         rewriter.sub_module_ids(module);
     }
-    Ok(package_id)
+    Ok(())
 }
 
 type MoveEvent = (Vec<u8>, u64, TypeTag, AbilitySet, Vec<u8>);
@@ -688,6 +723,33 @@ pub fn resolve_and_type_check(
     type_args: &[TypeTag],
     args: Vec<CallArg>,
     is_genesis: bool,
+) -> Result<TypeCheckSuccess, ExecutionError> {
+    type_check(
+        objects, module, function, type_args, args, is_genesis, false,
+    )
+}
+
+/// Type check a call of a batch before it executes, when the objects earlier transactions of the
+/// batch create do not exist yet. Arguments using their results are only checked to be passed
+/// where the function expects an object, the rest are checked as by `resolve_and_type_check`.
+pub fn type_check_with_command_results(
+    objects: &BTreeMap<ObjectID, impl Borrow<Object>>,
+    module: &CompiledModule,
+    function: &Identifier,
+    type_args: &[TypeTag],
+    args: Vec<CallArg>,
+) -> Result<(), ExecutionError> {
+    type_check(objects, module, function, type_args, args, false, true).map(|_| ())
+}
+
+fn type_check(
+    objects: &BTreeMap<ObjectID, impl Borrow<Object>>,
+    module: &CompiledModule,
+    function: &Identifier,
+    type_args: &[TypeTag],
+    args: Vec<CallArg>,
+    is_genesis: bool,
+    command_results_unresolved: bool,
 ) -> Result<TypeCheckSuccess, ExecutionError> {
     // Resolve the function we are calling
     let view = &BinaryIndexedView::Module(module);
@@ -795,6 +857,36 @@ pub fn resolve_and_type_check(
                 CallArg::Object(ObjectArg::SharedObject(id)) => {
                     InputObjectKind::SharedMoveObject(id)
                 }
+                CallArg::Object(ObjectArg::Result(result)) if command_results_unresolved => {
+                    if is_primitive(view, type_args, param_type) {
+                        return Err(ExecutionError::new_with_source(
+                            ExecutionErrorKind::entry_argument_error(
+                                idx,
+                                EntryArgumentErrorKind::TypeMismatch,
+                            ),
+                            format!(
+                                "Argument at index {} uses {}, but function expects {:?}",
+                                idx, result, param_type
+                            ),
+                        ));
+                    }
+                    // The object is only known once the transaction using it executes
+                    return Ok(Vec::new());
+                }
+                CallArg::Object(ObjectArg::Result(result)) => {
+                    let msg = format!(
+                        "Argument at index {} uses {}, which must be resolved to an object \
+                        before type checking",
+                        idx, result
+                    );
+                    return Err(ExecutionError::new_with_source(
+                        ExecutionErrorKind::entry_argument_error(
+                            idx,
+                            EntryArgumentErrorKind::InvalidCommandResult,
+                        ),
+                        msg,
+                    ));
+                }
             };
 
             let id = object_kind.object_id();
@@ -0,0 +1,63 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounds on the resources a transaction uses, set by the [`ProtocolConfig`] of the network.
+//! Transactions going past a bound fail with a `LimitExceeded` error and their effects are rolled
+//! back, like any failed transaction. How deeply Move calls nest is bounded by the Move VM.
+
+use move_core_types::language_storage::{StructTag, TypeTag};
+use sui_types::error::{ExecutionError, ExecutionErrorKind};
+use sui_types::messages::ExecutionLimit;
+use sui_types::protocol_config::ProtocolConfig;
+
+#[cfg(test)]
+#[path = "unit_tests/execution_limits_tests.rs"]
+mod execution_limits_tests;
+
+/// Check that `actual` is at most `max`.
+pub fn check_limit(limit: ExecutionLimit, max: usize, actual: usize) -> Result<(), ExecutionError> {
+    if actual > max {
+        return Err(ExecutionErrorKind::limit_exceeded(limit, max, actual).into());
+    }
+    Ok(())
+}
+
+/// How deeply `type_` nests other types: primitive types and structs without type arguments do
+/// not nest, vectors and structs with type arguments nest one level deeper than what they hold.
+pub fn type_nesting(type_: &TypeTag) -> usize {
+    match type_ {
+        TypeTag::Vector(inner) => 1 + type_nesting(inner),
+        TypeTag::Struct(tag) => struct_nesting(tag),
+        _ => 0,
+    }
+}
+
+pub fn struct_nesting(tag: &StructTag) -> usize {
+    tag.type_params
+        .iter()
+        .map(|type_| 1 + type_nesting(type_))
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn check_type_nesting(
+    type_: &TypeTag,
+    protocol_config: &ProtocolConfig,
+) -> Result<(), ExecutionError> {
+    check_limit(
+        ExecutionLimit::TypeNesting,
+        protocol_config.max_type_nesting(),
+        type_nesting(type_),
+    )
+}
+
+pub fn check_struct_nesting(
+    tag: &StructTag,
+    protocol_config: &ProtocolConfig,
+) -> Result<(), ExecutionError> {
+    check_limit(
+        ExecutionLimit::TypeNesting,
+        protocol_config.max_type_nesting(),
+        struct_nesting(tag),
+    )
+}
@@ -3,6 +3,7 @@
 
 pub mod adapter;
 pub mod bytecode_rewriter;
+pub mod execution_limits;
 pub mod genesis;
 pub mod in_memory_storage;
 pub mod object_root_ancestor_map;
@@ -10,8 +10,9 @@ use sui_types::base_types::{
 };
 use sui_types::error::{ExecutionError, SuiError};
 use sui_types::fp_bail;
-use sui_types::messages::{ExecutionStatus, InputObjects, TransactionEffects};
+use sui_types::messages::{ExecutionLimit, ExecutionStatus, InputObjects, TransactionEffects};
 use sui_types::object::{Data, Object};
+use sui_types::protocol_config::ProtocolConfig;
 use sui_types::storage::{BackingPackageStore, DeleteKind, Storage};
 use sui_types::{
     event::Event,
@@ -19,6 +20,8 @@ use sui_types::{
     object::Owner,
 };
 
+use crate::execution_limits::{check_limit, check_struct_nesting};
+
 pub type InnerTemporaryStore = (
     BTreeMap<ObjectID, Object>,
     Vec<ObjectRef>,
@@ -95,6 +98,50 @@ impl<S> TemporaryStore<S> {
         )
     }
 
+    /// Check that the objects written and the events emitted so far stay within the limits of
+    /// `protocol_config`. Objects which were not inputs count as created.
+    pub fn check_limits(&self, protocol_config: &ProtocolConfig) -> Result<(), ExecutionError> {
+        let created = self
+            .written
+            .keys()
+            .filter(|id| !self.objects.contains_key(id))
+            .count();
+        check_limit(
+            ExecutionLimit::CreatedObjects,
+            protocol_config.max_created_objects(),
+            created,
+        )?;
+        check_limit(
+            ExecutionLimit::MutatedObjects,
+            protocol_config.max_mutated_objects(),
+            self.written.len() - created,
+        )?;
+        check_limit(
+            ExecutionLimit::Events,
+            protocol_config.max_events(),
+            self.events.len(),
+        )?;
+        for event in &self.events {
+            if let Event::MoveEvent {
+                type_, contents, ..
+            } = event
+            {
+                check_limit(
+                    ExecutionLimit::EventSize,
+                    protocol_config.max_event_size(),
+                    contents.len(),
+                )?;
+                check_struct_nesting(type_, protocol_config)?;
+            }
+        }
+        for (_, object) in self.written.values() {
+            if let Data::Move(move_object) = &object.data {
+                check_struct_nesting(&move_object.type_, protocol_config)?;
+            }
+        }
+        Ok(())
+    }
+
     /// For every object from active_inputs (i.e. all mutable objects), if they are not
     /// mutated during the transaction execution, force mutating them by incrementing the
     /// sequence number. This is required to achieve safety.
@@ -330,6 +377,11 @@ impl<S> Storage for TemporaryStore<S> {
     }
 
     fn delete_object(&mut self, id: &ObjectID, version: SequenceNumber, kind: DeleteKind) {
+        // An object created by an earlier transaction of the same batch never made it on
+        // chain, so deleting or wrapping it simply forgets about it.
+        if self.created_object_ids.contains(id) && self.written.remove(id).is_some() {
+            return;
+        }
         // there should be no deletion after write
         debug_assert!(self.written.get(id) == None);
         // Check it is not read-only
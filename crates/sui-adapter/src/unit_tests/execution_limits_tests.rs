@@ -0,0 +1,113 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::ident_str;
+use sui_types::base_types::{dbg_addr, TransactionDigest};
+use sui_types::event::Event;
+use sui_types::messages::{ExecutionFailureStatus, InputObjects, LimitExceeded};
+use sui_types::object::Object;
+use sui_types::storage::Storage;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+
+use super::*;
+use crate::temporary_store::TemporaryStore;
+
+fn struct_tag(type_params: Vec<TypeTag>) -> StructTag {
+    StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: ident_str!("test").to_owned(),
+        name: ident_str!("Test").to_owned(),
+        type_params,
+    }
+}
+
+/// A struct nesting `depth` levels of type arguments.
+fn nested_struct(depth: usize) -> StructTag {
+    let mut tag = struct_tag(vec![]);
+    for _ in 0..depth {
+        tag = struct_tag(vec![TypeTag::Struct(tag)]);
+    }
+    tag
+}
+
+fn limit_exceeded(result: Result<(), ExecutionError>) -> LimitExceeded {
+    match result.unwrap_err().to_execution_status() {
+        ExecutionFailureStatus::LimitExceeded(exceeded) => exceeded,
+        status => panic!("Unexpected status {status:?}"),
+    }
+}
+
+fn move_event(type_: StructTag, contents: Vec<u8>) -> Event {
+    Event::move_event(
+        &SUI_FRAMEWORK_ADDRESS,
+        ident_str!("test"),
+        dbg_addr(1),
+        type_,
+        contents,
+    )
+}
+
+#[test]
+fn test_type_nesting() {
+    assert_eq!(type_nesting(&TypeTag::U64), 0);
+    assert_eq!(type_nesting(&TypeTag::Struct(struct_tag(vec![]))), 0);
+    assert_eq!(type_nesting(&TypeTag::Vector(Box::new(TypeTag::U8))), 1);
+    let vector_of_vectors = TypeTag::Vector(Box::new(TypeTag::Vector(Box::new(TypeTag::U8))));
+    assert_eq!(type_nesting(&vector_of_vectors), 2);
+    let tag = struct_tag(vec![TypeTag::Bool, vector_of_vectors]);
+    assert_eq!(struct_nesting(&tag), 3);
+
+    let config = ProtocolConfig::get_for_max_version();
+    let max = config.max_type_nesting();
+    check_struct_nesting(&nested_struct(max), &config).unwrap();
+    let exceeded = limit_exceeded(check_type_nesting(
+        &TypeTag::Struct(nested_struct(max + 1)),
+        &config,
+    ));
+    assert_eq!(exceeded.limit, ExecutionLimit::TypeNesting);
+    assert_eq!(exceeded.max, max as u64);
+    assert_eq!(exceeded.actual, max as u64 + 1);
+}
+
+#[test]
+fn test_limits_of_effects() {
+    let config = ProtocolConfig::get_for_max_version();
+    let new_store =
+        || TemporaryStore::new((), InputObjects::new(vec![]), TransactionDigest::genesis());
+
+    let mut store = new_store();
+    for _ in 0..config.max_created_objects() {
+        store.write_object(Object::with_owner_for_testing(dbg_addr(1)));
+    }
+    store.check_limits(&config).unwrap();
+    store.write_object(Object::with_owner_for_testing(dbg_addr(1)));
+    let exceeded = limit_exceeded(store.check_limits(&config));
+    assert_eq!(exceeded.limit, ExecutionLimit::CreatedObjects);
+
+    let mut store = new_store();
+    store.log_event(move_event(
+        struct_tag(vec![]),
+        vec![0; config.max_event_size()],
+    ));
+    store.check_limits(&config).unwrap();
+    store.log_event(move_event(
+        struct_tag(vec![]),
+        vec![0; config.max_event_size() + 1],
+    ));
+    let exceeded = limit_exceeded(store.check_limits(&config));
+    assert_eq!(exceeded.limit, ExecutionLimit::EventSize);
+
+    let mut store = new_store();
+    let nesting = config.max_type_nesting() + 1;
+    store.log_event(move_event(nested_struct(nesting), vec![]));
+    let exceeded = limit_exceeded(store.check_limits(&config));
+    assert_eq!(exceeded.limit, ExecutionLimit::TypeNesting);
+
+    let mut store = new_store();
+    for _ in 0..=config.max_events() {
+        store.log_event(move_event(struct_tag(vec![]), vec![]));
+    }
+    let exceeded = limit_exceeded(store.check_limits(&config));
+    assert_eq!(exceeded.limit, ExecutionLimit::Events);
+    assert_eq!(exceeded.actual, config.max_events() as u64 + 1);
+}
@@ -17,6 +17,7 @@ use std::{panic, thread, thread::sleep, time::Duration};
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
 use sui_types::{
     batch::UpdateItem,
+    crypto::PublicKeyBytes,
     messages::{BatchInfoRequest, BatchInfoResponseItem},
 };
 use tracing::{error, info};
@@ -110,19 +111,21 @@ fn run_throughout_microbench(
     );
 
     validator_preparer.deploy_validator(address.clone());
+    let network_key = validator_preparer.network_key();
 
     let result = panic::catch_unwind(|| {
         // Follower to observe batches
         let addr = address.clone();
         thread::spawn(move || {
-            get_multithread_runtime().block_on(async move { run_follower(addr).await });
+            get_multithread_runtime()
+                .block_on(async move { run_follower(addr, network_key).await });
         });
 
         sleep(Duration::from_secs(3));
 
         // Run load
         let (elapsed, resp) = get_multithread_runtime()
-            .block_on(async move { send_tx_chunks(txes, address, connections).await });
+            .block_on(async move { send_tx_chunks(txes, address, network_key, connections).await });
 
         let _: Vec<_> = resp
             .into_par_iter()
@@ -177,22 +180,29 @@ fn run_latency_microbench(
         tx_cr.generate_transactions(1, use_move, 1, num_chunks, None, &mut validator_preparer);
 
     validator_preparer.deploy_validator(address.clone());
+    let network_key = validator_preparer.network_key();
 
     let result = panic::catch_unwind(|| {
         let runtime = get_multithread_runtime();
         // Prep the generators
-        let (mut load_gen, mut tracer_gen) =
-            runtime.block_on(async move {
-                join!(
-                    FixedRateLoadGenerator::new(
-                        load_gen_txes,
-                        period_us,
-                        address.clone(),
-                        connections,
-                    ),
-                    FixedRateLoadGenerator::new(tracer_txes, period_us, address.clone(), 1),
-                )
-            });
+        let (mut load_gen, mut tracer_gen) = runtime.block_on(async move {
+            join!(
+                FixedRateLoadGenerator::new(
+                    load_gen_txes,
+                    period_us,
+                    address.clone(),
+                    network_key,
+                    connections,
+                ),
+                FixedRateLoadGenerator::new(
+                    tracer_txes,
+                    period_us,
+                    address.clone(),
+                    network_key,
+                    1
+                ),
+            )
+        });
 
         // Run the load gen and tracers
         let (load_latencies, tracer_latencies) =
@@ -215,10 +225,12 @@ fn run_latency_microbench(
     }
 }
 
-async fn run_follower(address: Multiaddr) {
+async fn run_follower(address: Multiaddr, network_key: PublicKeyBytes) {
     // We spawn a second client that listens to the batch interface
     let _batch_client_handle = tokio::task::spawn(async move {
-        let authority_client = NetworkAuthorityClient::connect(&address).await.unwrap();
+        let authority_client = NetworkAuthorityClient::connect(&address, network_key)
+            .await
+            .unwrap();
 
         let mut start = 0;
 
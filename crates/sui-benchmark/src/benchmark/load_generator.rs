@@ -19,7 +19,8 @@ use sui_core::{
     authority_client::{AuthorityAPI, NetworkAuthorityClient},
     authority_server::{AuthorityServer, AuthorityServerHandle},
 };
-use sui_types::messages::*;
+use sui_network::tls::NodeCertificate;
+use sui_types::{crypto::PublicKeyBytes, messages::*};
 use tokio::{sync::Notify, time};
 use tracing::{error, info};
 
@@ -44,13 +45,14 @@ pub fn check_transaction_response(reply_message: Result<TransactionInfoResponse,
 pub async fn send_tx_chunks(
     tx_chunks: Vec<(Transaction, CertifiedTransaction)>,
     address: Multiaddr,
+    network_key: PublicKeyBytes,
     conn: usize,
 ) -> (u128, Vec<Result<TransactionInfoResponse, io::Error>>) {
     let time_start = Instant::now();
 
     let mut tasks = Vec::new();
     for tx_chunks in tx_chunks.chunks(tx_chunks.len() / conn) {
-        let client = NetworkAuthorityClient::connect_lazy(&address).unwrap();
+        let client = NetworkAuthorityClient::connect_lazy(&address, network_key).unwrap();
         let txns = tx_chunks.to_vec();
 
         let task = tokio::spawn(async move {
@@ -87,13 +89,14 @@ pub async fn send_tx_chunks(
 pub async fn send_transactions(
     tx_chunks: Vec<Transaction>,
     address: Multiaddr,
+    network_key: PublicKeyBytes,
     conn: usize,
 ) -> (u128, Vec<Result<TransactionInfoResponse, io::Error>>) {
     let time_start = Instant::now();
 
     let mut tasks = Vec::new();
     for tx_chunks in tx_chunks.chunks(tx_chunks.len() / conn) {
-        let client = NetworkAuthorityClient::connect_lazy(&address).unwrap();
+        let client = NetworkAuthorityClient::connect_lazy(&address, network_key).unwrap();
         let txns = tx_chunks.to_vec();
 
         let task = tokio::spawn(async move {
@@ -125,13 +128,14 @@ pub async fn send_transactions(
 pub async fn send_confs(
     tx_chunks: Vec<CertifiedTransaction>,
     address: Multiaddr,
+    network_key: PublicKeyBytes,
     conn: usize,
 ) -> (u128, Vec<Result<TransactionInfoResponse, io::Error>>) {
     let time_start = Instant::now();
 
     let mut tasks = Vec::new();
     for tx_chunks in tx_chunks.chunks(tx_chunks.len() / conn) {
-        let client = NetworkAuthorityClient::connect_lazy(&address).unwrap();
+        let client = NetworkAuthorityClient::connect_lazy(&address, network_key).unwrap();
         let txns = tx_chunks.to_vec();
 
         let task = tokio::spawn(async move {
@@ -165,10 +169,11 @@ async fn send_tx_chunks_notif(
     tx_chunk: Vec<(Transaction, CertifiedTransaction)>,
     result_chann_tx: &mut MpscSender<u128>,
     address: Multiaddr,
+    network_key: PublicKeyBytes,
     conn: usize,
 ) {
     notif.notified().await;
-    let r = send_tx_chunks(tx_chunk, address, conn).await;
+    let r = send_tx_chunks(tx_chunk, address, network_key, conn).await;
     result_chann_tx.send(r.0).await.unwrap();
 
     let _: Vec<_> =
@@ -183,6 +188,8 @@ pub struct FixedRateLoadGenerator {
     pub period_us: u64,
     /// The address of the validator to send txns to
     pub address: Multiaddr,
+    /// The network key the validator authenticates with
+    pub network_key: PublicKeyBytes,
 
     pub tick_notifier: Arc<Notify>,
 
@@ -203,6 +210,7 @@ impl FixedRateLoadGenerator {
         transactions: Vec<(Transaction, CertifiedTransaction)>,
         period_us: u64,
         address: Multiaddr,
+        network_key: PublicKeyBytes,
         connections: usize,
     ) -> Self {
         let mut handles = vec![];
@@ -224,7 +232,15 @@ impl FixedRateLoadGenerator {
             let address = address.clone();
 
             handles.push(tokio::spawn(async move {
-                send_tx_chunks_notif(notif, tx_chunk, &mut result_chann_tx, address, conn).await;
+                send_tx_chunks_notif(
+                    notif,
+                    tx_chunk,
+                    &mut result_chann_tx,
+                    address,
+                    network_key,
+                    conn,
+                )
+                .await;
             }));
         }
 
@@ -233,6 +249,7 @@ impl FixedRateLoadGenerator {
         Self {
             period_us,
             address,
+            network_key,
             transactions,
             connections,
             results_chann_rx,
@@ -270,6 +287,7 @@ impl FixedRateLoadGenerator {
 pub async fn spawn_authority_server(
     listen_address: Multiaddr,
     state: AuthorityState,
+    certificate: NodeCertificate,
 ) -> AuthorityServerHandle {
     // The following two fields are only needed for shared objects (not by this bench).
     let consensus_address = "/dns/localhost/tcp/0/http".parse().unwrap();
@@ -280,7 +298,8 @@ pub async fn spawn_authority_server(
         Arc::new(state),
         consensus_address,
         tx_consensus_listener,
-    );
+    )
+    .with_certificate(certificate);
     server.spawn().await.unwrap()
 }
 
@@ -293,11 +312,12 @@ async fn send_tx_chunks_for_quorum_notif(
     tx_chunk: Vec<Transaction>,
     result_chann_tx: &mut MpscSender<(u128, StakeUnit)>,
     address: Multiaddr,
+    network_key: PublicKeyBytes,
     stake: StakeUnit,
     conn: usize,
 ) {
     notif.notified().await;
-    let r = send_transactions(tx_chunk, address.clone(), conn).await;
+    let r = send_transactions(tx_chunk, address.clone(), network_key, conn).await;
 
     match result_chann_tx.send((r.0, stake)).await {
         Ok(_) => (),
@@ -320,7 +340,7 @@ async fn send_tx_for_quorum(
     order_chunk: Vec<Transaction>,
     conf_chunk: Vec<CertifiedTransaction>,
     result_chann_tx: &mut MpscSender<u128>,
-    net_clients: Vec<(Multiaddr, StakeUnit)>,
+    net_clients: Vec<(Multiaddr, PublicKeyBytes, StakeUnit)>,
     conn: usize,
     quorum_threshold: StakeUnit,
 ) {
@@ -329,7 +349,7 @@ async fn send_tx_for_quorum(
 
     // Send intent orders to 3f+1
     let order_start_notifier = Arc::new(Notify::new());
-    for (net_client, stake) in net_clients.clone() {
+    for (net_client, network_key, stake) in net_clients.clone() {
         // This is for sending a start signal to the subtasks
         let notif = order_start_notifier.clone();
         // This is for getting the elapsed time
@@ -343,6 +363,7 @@ async fn send_tx_for_quorum(
                 chunk,
                 &mut ch_tx,
                 net_client.clone(),
+                network_key,
                 stake,
                 conn,
             )
@@ -379,11 +400,11 @@ async fn send_tx_for_quorum(
 
     // Send the confs
     let mut handles = vec![];
-    for (net_client, stake) in net_clients {
+    for (net_client, network_key, stake) in net_clients {
         let chunk = conf_chunk.clone();
         let mut chann_tx = conf_chann_tx.clone();
         handles.push(tokio::spawn(async move {
-            let r = send_confs(chunk, net_client.clone(), conn).await;
+            let r = send_confs(chunk, net_client.clone(), network_key, conn).await;
             match chann_tx.send((r.0, stake)).await {
                 Ok(_) => (),
                 Err(e) => {
@@ -452,10 +473,10 @@ impl MultiFixedRateLoadGenerator {
 
         network_cfg: &NetworkConfig,
     ) -> Self {
-        let network_clients_stake: Vec<(Multiaddr, StakeUnit)> = network_cfg
+        let network_clients_stake: Vec<(Multiaddr, PublicKeyBytes, StakeUnit)> = network_cfg
             .validator_set()
             .iter()
-            .map(|q| (q.network_address().to_owned(), q.stake()))
+            .map(|q| (q.network_address().to_owned(), q.network_key(), q.stake()))
             .collect();
         let committee_quorum_threshold = network_cfg.committee().quorum_threshold();
         let mut handles = vec![];
@@ -87,6 +87,7 @@ fn make_transactions(
     batch_size: usize,
     use_move: bool,
 ) -> Vec<(Transaction, CertifiedTransaction)> {
+    let chain_id = network_config.genesis.chain_id();
     // Make one transaction per account
     // Depending on benchmark_type, this could be the Order and/or Confirmation.
     account_gas_objects
@@ -108,6 +109,7 @@ fn make_transactions(
                     address,
                     gas_object_ref,
                     10000,
+                    chain_id,
                 )
             } else {
                 assert!(single_kinds.len() == batch_size, "Inconsistent batch size");
@@ -116,6 +118,7 @@ fn make_transactions(
                     address,
                     gas_object_ref,
                     2000000,
+                    chain_id,
                 )
             };
 
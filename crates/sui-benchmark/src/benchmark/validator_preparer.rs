@@ -19,6 +19,7 @@ use std::{
     time::Duration,
 };
 use sui_core::authority::*;
+use sui_network::tls::NodeCertificate;
 use sui_types::{
     base_types::{SuiAddress, *},
     committee::*,
@@ -123,6 +124,11 @@ impl ValidatorPreparer {
         }
     }
 
+    /// The network key the validator authenticates with.
+    pub fn network_key(&self) -> PublicKeyBytes {
+        self.network_config.validator_configs()[0].network_public_key()
+    }
+
     pub fn deploy_validator(&mut self, address: Multiaddr) {
         match self.running_mode {
             RunningMode::SingleValidatorProcess => {
@@ -157,10 +163,14 @@ impl ValidatorPreparer {
                 {
                     // Make multi-threaded runtime for the authority
                     let state = authority_state.take().unwrap();
+                    let certificate = NodeCertificate::new(
+                        self.network_config.validator_configs()[0].network_key_pair(),
+                    )
+                    .unwrap();
                     thread::spawn(move || {
                         info!("Spawning a validator thread...");
                         get_multithread_runtime().block_on(async move {
-                            let server = spawn_authority_server(address, state).await;
+                            let server = spawn_authority_server(address, state, certificate).await;
                             if let Err(e) = server.join().await {
                                 error!("Server ended with an error: {e}");
                             }
@@ -186,7 +196,7 @@ impl ValidatorPreparer {
                 let _all_objects: Vec<ObjectConfig> = objects
                     .iter()
                     .map(|object| ObjectConfig {
-                        object_id: object.id(),
+                        object_id: Some(object.id()),
                         gas_value: get_gas_value(object),
                     })
                     .collect();
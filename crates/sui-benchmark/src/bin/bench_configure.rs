@@ -59,8 +59,12 @@ fn main() {
     }
     let genesis_config = GenesisConfig {
         validator_genesis_info: None,
+        validators: vec![],
         committee_size: bch.host_port_stake_triplets.len(),
         accounts: accounts.clone(),
+        epoch_config: None,
+        protocol_version: None,
+        packages: vec![],
     };
 
     let path_str = "distributed_bench_genesis.conf";
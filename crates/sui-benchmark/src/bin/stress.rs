@@ -6,23 +6,28 @@ use futures::future::BoxFuture;
 use futures::FutureExt;
 use futures::{stream::FuturesUnordered, StreamExt};
 use std::collections::{BTreeMap, VecDeque};
-use std::sync::{Arc, Barrier};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 use strum_macros::EnumString;
+use sui_benchmark::report::{BenchmarkReport, BucketHistogram, Stage, StageLatency};
 use sui_benchmark::stress::context::Payload;
 use sui_benchmark::stress::context::StressTestCtx;
+use sui_benchmark::stress::pay::PayTestCtx;
+use sui_benchmark::stress::publish::PublishTestCtx;
 use sui_benchmark::stress::shared_counter::SharedCounterTestCtx;
+use sui_benchmark::stress::stats::{ErrorCounts, LatencyHistogram};
+use sui_benchmark::stress::submitter::Submitter;
 use sui_benchmark::stress::transfer_object::TransferObjectTestCtx;
 use sui_config::NetworkConfig;
+use sui_core::authority::AuthorityMetrics;
 use sui_core::authority_aggregator::AuthorityAggregator;
 use sui_core::authority_client::NetworkAuthorityClient;
 use sui_node::SuiNode;
-use sui_quorum_driver::QuorumDriverHandler;
+use sui_types::base_types::ChainId;
 use sui_types::crypto::EmptySignInfo;
-use sui_types::messages::{
-    ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse,
-    Transaction, TransactionEnvelope,
-};
+use sui_types::messages::{Transaction, TransactionEnvelope};
 use test_utils::authority::{
     spawn_test_authorities, test_and_configure_authority_configs, test_authority_aggregator,
 };
@@ -52,7 +57,7 @@ struct Opts {
     /// Stat collection interval seconds
     #[clap(long, default_value = "10", global = true)]
     pub stat_collection_interval: u64,
-    /// Shared counter or transfer object
+    /// Shared counter, transfer object, pay or publish
     #[clap(arg_enum, default_value = "owned", global = true, ignore_case = true)]
     pub transaction_type: TransactionType,
     /// Num server threads
@@ -62,17 +67,33 @@ struct Opts {
     /// ideally same as number of workers
     #[clap(long, default_value = "3", global = true)]
     pub num_client_threads: usize,
+    /// Send the transactions to a gateway rather than to the validators directly
+    #[clap(long, global = true)]
+    pub use_gateway: bool,
+    /// Seconds after which to stop and report the totals of the benchmark,
+    /// 0 to run until interrupted
+    #[clap(long, default_value = "0", global = true)]
+    pub run_duration: u64,
+    /// Write a report of the benchmark in JSON to this path,
+    /// updated at each stat collection interval
+    #[clap(long, global = true)]
+    pub report_json: Option<PathBuf>,
+    /// Write a report of the benchmark in Markdown to this path,
+    /// updated at each stat collection interval
+    #[clap(long, global = true)]
+    pub report_markdown: Option<PathBuf>,
 }
 
 struct Stats {
     pub id: usize,
     pub num_success: u64,
-    pub num_error: u64,
     pub num_no_gas: u64,
     pub num_submitted: u64,
     pub num_in_flight: u64,
-    pub min_latency: Duration,
-    pub max_latency: Duration,
+    pub errors: ErrorCounts,
+    pub latencies: LatencyHistogram,
+    pub sign_latencies: LatencyHistogram,
+    pub quorum_latencies: LatencyHistogram,
     pub duration: Duration,
 }
 
@@ -83,17 +104,94 @@ enum TransactionType {
     SharedCounter,
     #[clap(name = "owned")]
     TransferObject,
+    #[clap(name = "pay")]
+    Pay,
+    #[clap(name = "publish")]
+    Publish,
 }
 
 type RetryType = Box<(TransactionEnvelope<EmptySignInfo>, Arc<dyn Payload>)>;
 enum NextOp {
-    Response(Option<(Instant, Box<dyn Payload>)>),
-    Retry(Box<(Transaction, Arc<dyn Payload>)>),
+    /// When the transaction was sent, the time to collect its certificate if known, and the
+    /// payload of the next transaction.
+    Response(Option<(Instant, Option<Duration>, Box<dyn Payload>)>),
+    /// A transaction to retry, with the kind of the error it failed with.
+    Retry(RetryType, String),
+}
+
+/// Execute the transaction of `payload` with `submitter`, making the payload of the next
+/// transaction from the new versions of its object and gas.
+fn execute(
+    submitter: &Submitter,
+    tx: Transaction,
+    payload: Arc<dyn Payload>,
+    start: Instant,
+) -> BoxFuture<'static, NextOp> {
+    let submitter = submitter.clone();
+    async move {
+        match submitter.execute(tx.clone()).await {
+            Ok(executed) => {
+                let new_version = executed
+                    .mutated
+                    .iter()
+                    .find(|object_ref| object_ref.0 == payload.get_object_id())
+                    .copied()
+                    .unwrap();
+                NextOp::Response(Some((
+                    start,
+                    executed.quorum_latency,
+                    payload.make_new_payload(new_version, executed.gas),
+                )))
+            }
+            Err(kind) => {
+                error!("Retry due to error: {}", kind);
+                NextOp::Retry(Box::new((tx, payload)), kind)
+            }
+        }
+    }
+    .boxed()
+}
+
+/// The parameters of the benchmark, to tell apart the reports of runs.
+fn report_parameters(opts: &Opts) -> BTreeMap<String, String> {
+    BTreeMap::from([
+        (
+            "committee_size".to_string(),
+            opts.committee_size.to_string(),
+        ),
+        ("target_qps".to_string(), opts.target_qps.to_string()),
+        ("num_workers".to_string(), opts.num_workers.to_string()),
+        (
+            "in_flight_ratio".to_string(),
+            opts.in_flight_ratio.to_string(),
+        ),
+        (
+            "transaction_type".to_string(),
+            format!("{:?}", opts.transaction_type),
+        ),
+        ("use_gateway".to_string(), opts.use_gateway.to_string()),
+    ])
+}
+
+fn write_report(opts: &Opts, report: &BenchmarkReport) {
+    if let Some(path) = &opts.report_json {
+        let json = report.to_json().expect("Reports serialize to JSON");
+        if let Err(e) = fs::write(path, json) {
+            error!("Failed to write the report to {}: {e}", path.display());
+        }
+    }
+    if let Some(path) = &opts.report_markdown {
+        if let Err(e) = fs::write(path, report.to_markdown()) {
+            error!("Failed to write the report to {}: {e}", path.display());
+        }
+    }
 }
 
 async fn run(
     clients: AuthorityAggregator<NetworkAuthorityClient>,
+    chain_id: ChainId,
     payloads: Vec<Arc<dyn Payload>>,
+    validator_metrics: Vec<Arc<AuthorityMetrics>>,
     opts: Opts,
 ) {
     eprintln!("Starting benchmark!");
@@ -106,217 +204,221 @@ async fn run(
     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
     let request_delay_micros = 1_000_000 / (opts.num_workers * opts.target_qps);
     let stat_delay_micros = 1_000_000 * opts.stat_collection_interval;
-    (0..opts.num_workers).for_each(|i| {
-            let mut free_pool = partitioned_payload[i as usize].clone();
-            // Make a per worker quorum driver, otherwise they all share the same task.
-            let quorum_driver_handler = QuorumDriverHandler::new(clients.clone());
-            let qd = quorum_driver_handler.clone_quorum_driver();
-            let tx_cloned = tx.clone();
-            let mut request_interval = time::interval(Duration::from_micros(request_delay_micros));
-            request_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+    for i in 0..opts.num_workers {
+        let mut free_pool = partitioned_payload[i as usize].clone();
+        // Make a per worker submitter, otherwise they all share the same quorum driver task.
+        let submitter = if opts.use_gateway {
+            Submitter::gateway(clients.clone(), chain_id).expect("Cannot create the gateway")
+        } else {
+            Submitter::quorum_driver(clients.clone())
+        };
+        let tx_cloned = tx.clone();
+        let mut request_interval = time::interval(Duration::from_micros(request_delay_micros));
+        request_interval.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
 
-            let mut stat_interval = time::interval(Duration::from_micros(stat_delay_micros));
-            let runner = tokio::spawn(async move {
-                let mut num_success = 0;
-                let mut num_error = 0;
-                let mut min_latency = Duration::MAX;
-                let mut max_latency = Duration::ZERO;
-                let mut num_no_gas = 0;
-                let mut num_in_flight: u64 = 0;
-                let mut num_submitted = 0;
-                let mut futures: FuturesUnordered<BoxFuture<NextOp>> = FuturesUnordered::new();
+        let mut stat_interval = time::interval(Duration::from_micros(stat_delay_micros));
+        let runner = tokio::spawn(async move {
+            let mut num_success = 0;
+            let mut errors = ErrorCounts::default();
+            let mut latencies = LatencyHistogram::default();
+            let mut sign_latencies = LatencyHistogram::default();
+            let mut quorum_latencies = LatencyHistogram::default();
+            let mut num_no_gas = 0;
+            let mut num_in_flight: u64 = 0;
+            let mut num_submitted = 0;
+            let mut futures: FuturesUnordered<BoxFuture<NextOp>> = FuturesUnordered::new();
 
-                let mut retry_queue : VecDeque<RetryType> = VecDeque::new();
+            let mut retry_queue: VecDeque<RetryType> = VecDeque::new();
 
-                loop {
-                    tokio::select! {
-                        _ = stat_interval.tick() => {
-                            if tx_cloned
-                                .send(Stats {
-                                    id: i as usize,
-                                    num_success,
-                                    num_error,
-                                    min_latency,
-                                    max_latency,
-                                    num_no_gas,
-                                    num_in_flight,
-                                    num_submitted,
-                                    duration: Duration::from_micros(stat_delay_micros),
-                                })
-                                .await
-                                .is_err()
-                            {
-                                debug!("Failed to update stat!");
-                            }
-                            num_success = 0;
-                            num_error = 0;
-                            num_no_gas = 0;
-                            num_submitted = 0;
-                            min_latency = Duration::MAX;
-                            max_latency = Duration::ZERO;
+            loop {
+                tokio::select! {
+                    _ = stat_interval.tick() => {
+                        if tx_cloned
+                            .send(Stats {
+                                id: i as usize,
+                                num_success,
+                                num_no_gas,
+                                num_submitted,
+                                num_in_flight,
+                                errors: std::mem::take(&mut errors),
+                                latencies: std::mem::take(&mut latencies),
+                                sign_latencies: std::mem::take(&mut sign_latencies),
+                                quorum_latencies: std::mem::take(&mut quorum_latencies),
+                                duration: Duration::from_micros(stat_delay_micros),
+                            })
+                            .await
+                            .is_err()
+                        {
+                            debug!("Failed to update stat!");
+                        }
+                        num_success = 0;
+                        num_no_gas = 0;
+                        num_submitted = 0;
+                    }
+                    _ = request_interval.tick() => {
+                        // If a retry is available send that
+                        // (sending retries here subjects them to our rate limit)
+                        if let Some(b) = retry_queue.pop_front() {
+                            num_submitted += 1;
+                            let (tx, payload) = *b;
+                            futures.push(execute(&submitter, tx, payload, Instant::now()));
+                            continue;
                         }
-                        _ = request_interval.tick() => {
-
-                            // If a retry is available send that
-                            // (sending retries here subjects them to our rate limit)
-                            if let Some(b) = retry_queue.pop_front() {
-
-                                num_submitted += 1;
-                                num_error += 1;
-                                let res = qd
-                                    .execute_transaction(ExecuteTransactionRequest {
-                                        transaction: b.0.clone(),
-                                        request_type: ExecuteTransactionRequestType::WaitForEffectsCert,
-                                    })
-                                    .map(move |res| {
-                                        match res {
-                                            Ok(ExecuteTransactionResponse::EffectsCert(result)) => {
-                                                let (_, effects) = *result;
-                                                let new_version = effects.effects.mutated.iter().find(|(object_ref, _)| {
-                                                    object_ref.0 == b.1.get_object_id()
-                                                }).map(|x| x.0).unwrap();
-                                                NextOp::Response(Some((
-                                                    Instant::now(),
-                                                    b.1.make_new_payload(new_version, effects.effects.gas_object.0),
-                                                ),
-                                                ))
-                                            }
-                                            Ok(resp) => {
-                                                error!("unexpected_response: {:?}", resp);
-                                                NextOp::Retry(b)
-                                            }
-                                            Err(sui_err) => {
-                                                error!("{}", sui_err);
-                                                NextOp::Retry(b)
-                                            }
-                                        }
-                                    });
-                                futures.push(Box::pin(res));
-                                continue
-                            }
 
-                            // Otherwise send a fresh request
-                            if free_pool.is_empty() {
-                                num_no_gas += 1;
-                            } else {
-                                num_in_flight += 1;
-                                num_submitted += 1;
-                                let payload = free_pool.pop().unwrap();
-                                let tx = payload.make_transaction();
-                                let start = Instant::now();
-                                let res = qd
-                                    .execute_transaction(ExecuteTransactionRequest {
-                                        transaction: tx.clone(),
-                                    request_type: ExecuteTransactionRequestType::WaitForEffectsCert,
-                                })
-                                .map(move |res| {
-                                    match res {
-                                        Ok(ExecuteTransactionResponse::EffectsCert(result)) => {
-                                            let (_, effects) = *result;
-                                            let new_version = effects.effects.mutated.iter().find(|(object_ref, _)| {
-                                                object_ref.0 == payload.get_object_id()
-                                            }).map(|x| x.0).unwrap();
-                                            NextOp::Response(Some((
-                                                start,
-                                                payload.make_new_payload(new_version, effects.effects.gas_object.0),
-                                            )))
-                                        }
-                                        Ok(resp) => {
-                                            error!("unexpected_response: {:?}", resp);
-                                            NextOp::Retry(Box::new((tx, payload)))
-                                        }
-                                        Err(sui_err) => {
-                                            error!("Retry due to error: {}", sui_err);
-                                            NextOp::Retry(Box::new((tx, payload)))
-                                        }
-                                    }
-                                });
-                                futures.push(Box::pin(res));
-                            }
+                        // Otherwise send a fresh request
+                        if free_pool.is_empty() {
+                            num_no_gas += 1;
+                        } else {
+                            num_in_flight += 1;
+                            num_submitted += 1;
+                            let payload = free_pool.pop().unwrap();
+                            let sign_start = Instant::now();
+                            let tx = payload.make_transaction();
+                            sign_latencies.record(sign_start.elapsed());
+                            futures.push(execute(&submitter, tx, payload, Instant::now()));
                         }
-                        Some(op) = futures.next() => {
-                            match op {
-                                NextOp::Retry(b) => {
-                                    retry_queue.push_back(b);
-                                }
-                                NextOp::Response(Some((start, payload))) => {
-                                    free_pool.push(Arc::from(payload));
-                                    let latency = start.elapsed();
-                                    num_success += 1;
-                                    num_in_flight -= 1;
-                                    if latency > max_latency {
-                                        max_latency = latency;
-                                    }
-                                    if latency < min_latency {
-                                        min_latency = latency;
-                                    }
-                                }
-                                NextOp::Response(None) => {
-                                    // num_in_flight -= 1;
-                                    unreachable!();
+                    }
+                    Some(op) = futures.next() => {
+                        match op {
+                            NextOp::Retry(b, kind) => {
+                                errors.record(kind);
+                                retry_queue.push_back(b);
+                            }
+                            NextOp::Response(Some((start, quorum_latency, payload))) => {
+                                free_pool.push(Arc::from(payload));
+                                latencies.record(start.elapsed());
+                                if let Some(quorum_latency) = quorum_latency {
+                                    quorum_latencies.record(quorum_latency);
                                 }
+                                num_success += 1;
+                                num_in_flight -= 1;
+                            }
+                            NextOp::Response(None) => {
+                                unreachable!();
                             }
                         }
                     }
                 }
-            });
-            tasks.push(runner);
+            }
         });
+        tasks.push(runner);
+    }
 
-    tasks.push(tokio::spawn(async move {
-            let mut stat_collection: BTreeMap<usize, Stats> = BTreeMap::new();
-            let mut counter = 0;
-            while let Some(s @ Stats {
-                id,
-                num_success: _,
-                num_error: _,
-                min_latency: _,
-                max_latency: _,
-                num_no_gas: _,
-                num_in_flight: _,
-                num_submitted: _,
-                duration
-            }) = rx.recv().await {
-                stat_collection.insert(id, s);
-                let mut total_qps: f32 = 0.0;
-                let mut num_success: u64 = 0;
-                let mut num_error: u64 = 0;
-                let mut min_latency: Duration = Duration::MAX;
-                let mut max_latency: Duration = Duration::ZERO;
-                let mut num_in_flight: u64 = 0;
-                let mut num_submitted: u64 = 0;
-                let mut num_no_gas = 0;
-                for (_, v) in stat_collection.iter() {
-                    total_qps += v.num_success as f32 / duration.as_secs() as f32;
-                    num_success += v.num_success;
-                    num_error += v.num_error;
-                    num_no_gas += v.num_no_gas;
-                    num_submitted += v.num_submitted;
-                    num_in_flight += v.num_in_flight;
-                    min_latency = if v.min_latency < min_latency {
-                        v.min_latency
-                    } else {
-                        min_latency
-                    };
-                    max_latency = if v.max_latency > max_latency {
-                        v.max_latency
-                    } else {
-                        max_latency
-                    };
-                }
-                let denom = num_success + num_error;
-                let _error_rate = if denom > 0 {
-                    num_error as f32 / denom as f32
-                } else {
-                    0.0
-                };
-                counter += 1;
-                if counter % opts.num_workers == 0 {
-                    eprintln!("Throughput = {}, min_latency_ms = {}, max_latency_ms = {}, num_success = {}, num_error = {}, no_gas = {}, submitted = {}, in_flight = {}", total_qps, min_latency.as_millis(), max_latency.as_millis(), num_success, num_error, num_no_gas, num_submitted, num_in_flight);
-                }
+    let reporter = tokio::spawn(async move {
+        let run_start = Instant::now();
+        let execute_histograms = || {
+            BucketHistogram::collect(
+                validator_metrics
+                    .iter()
+                    .map(|metrics| &metrics.execute_certificate_latency),
+            )
+        };
+        let commit_histograms = || {
+            BucketHistogram::collect(
+                validator_metrics
+                    .iter()
+                    .map(|metrics| &metrics.commit_certificate_latency),
+            )
+        };
+        // The validators count the latencies of making the payloads too
+        let (execute_start, commit_start) = (execute_histograms(), commit_histograms());
+        let mut report = BenchmarkReport::new(report_parameters(&opts));
+        let mut stat_collection: BTreeMap<usize, Stats> = BTreeMap::new();
+        // The totals since the benchmark started
+        let mut total_success: u64 = 0;
+        let mut total_errors = ErrorCounts::default();
+        let mut total_latencies = LatencyHistogram::default();
+        let mut total_sign_latencies = LatencyHistogram::default();
+        let mut total_quorum_latencies = LatencyHistogram::default();
+        let mut counter = 0;
+        while let Some(s) = rx.recv().await {
+            total_success += s.num_success;
+            total_errors.merge(&s.errors);
+            total_latencies.merge(&s.latencies);
+            total_sign_latencies.merge(&s.sign_latencies);
+            total_quorum_latencies.merge(&s.quorum_latencies);
+            let duration = s.duration;
+            stat_collection.insert(s.id, s);
+            counter += 1;
+            if counter % opts.num_workers != 0 {
+                continue;
             }
-        }));
-    try_join_all(tasks).await.unwrap().into_iter().collect()
+
+            let mut total_qps: f32 = 0.0;
+            let mut num_success: u64 = 0;
+            let mut errors = ErrorCounts::default();
+            let mut latencies = LatencyHistogram::default();
+            let mut num_in_flight: u64 = 0;
+            let mut num_submitted: u64 = 0;
+            let mut num_no_gas = 0;
+            for (_, v) in stat_collection.iter() {
+                total_qps += v.num_success as f32 / duration.as_secs() as f32;
+                num_success += v.num_success;
+                num_no_gas += v.num_no_gas;
+                num_submitted += v.num_submitted;
+                num_in_flight += v.num_in_flight;
+                errors.merge(&v.errors);
+                latencies.merge(&v.latencies);
+            }
+            eprintln!(
+                "Throughput = {}, {}, num_success = {}, num_error = {}, no_gas = {}, submitted = {}, in_flight = {}, errors = {}",
+                total_qps,
+                latencies,
+                num_success,
+                errors.total(),
+                num_no_gas,
+                num_submitted,
+                num_in_flight,
+                errors
+            );
+
+            let elapsed = run_start.elapsed();
+            report.add_interval(elapsed, duration, num_success, &errors, &latencies);
+            report.set_totals(elapsed, total_success, &total_errors);
+            report.set_stage(StageLatency::from_histogram(
+                Stage::Sign,
+                &total_sign_latencies,
+            ));
+            if total_quorum_latencies.count() > 0 {
+                report.set_stage(StageLatency::from_histogram(
+                    Stage::Quorum,
+                    &total_quorum_latencies,
+                ));
+            }
+            report.set_stage(StageLatency::from_buckets(
+                Stage::Execute,
+                &execute_histograms().since(&execute_start),
+            ));
+            report.set_stage(StageLatency::from_buckets(
+                Stage::Commit,
+                &commit_histograms().since(&commit_start),
+            ));
+            report.set_stage(StageLatency::from_histogram(Stage::Total, &total_latencies));
+            write_report(&opts, &report);
+
+            if opts.run_duration > 0 && elapsed.as_secs() >= opts.run_duration {
+                eprintln!(
+                    "Final: throughput = {}, {}, num_success = {}, num_error = {}, errors = {}",
+                    total_success as f32 / elapsed.as_secs_f32(),
+                    total_latencies,
+                    total_success,
+                    total_errors.total(),
+                    total_errors
+                );
+                return;
+            }
+        }
+    });
+
+    // Workers run until the end of the benchmark, when the reporter returns.
+    tokio::select! {
+        result = try_join_all(tasks) => {
+            result.unwrap();
+        }
+        result = reporter => {
+            result.unwrap();
+        }
+    }
 }
 
 fn make_test_ctx(
@@ -333,6 +435,8 @@ fn make_test_ctx(
             opts.num_transfer_accounts,
             configs,
         ),
+        TransactionType::Pay => PayTestCtx::make_ctx(max_in_flight_ops as u64, configs),
+        TransactionType::Publish => PublishTestCtx::make_ctx(max_in_flight_ops as u64, configs),
     }
 }
 
@@ -359,9 +463,9 @@ fn main() {
 
     let genesis_objects = ctx.get_gas_objects();
 
-    // Make the client runtime wait until we are done creating genesis objects
-    let barrier = Arc::new(Barrier::new(2));
-    let cloned_barrier = barrier.clone();
+    // Make the client runtime wait until we are done creating genesis objects, and get the
+    // metrics of the validators for the report.
+    let (metrics_sender, metrics_receiver) = mpsc::channel();
     let cloned_config = configs.clone();
     // spawn a thread to spin up sui nodes on the multi-threaded server runtime
     let _ = std::thread::spawn(move || {
@@ -376,15 +480,23 @@ fn main() {
             // Setup the network
             let nodes: Vec<SuiNode> =
                 spawn_test_authorities(genesis_objects.clone(), &cloned_config).await;
+            let metrics: Vec<_> = nodes
+                .iter()
+                .map(|node| node.state().metrics.clone())
+                .collect();
             let handles: Vec<_> = nodes.into_iter().map(move |node| node.wait()).collect();
-            cloned_barrier.wait();
+            metrics_sender
+                .send(metrics)
+                .expect("The client runtime waits for the validators");
             if try_join_all(handles).await.is_err() {
                 error!("Failed while waiting for nodes");
             }
         });
     });
 
-    barrier.wait();
+    let validator_metrics = metrics_receiver
+        .recv()
+        .expect("Failed to start the validators");
     // create client runtime
     let client_runtime = Builder::new_multi_thread()
         .enable_all()
@@ -400,6 +512,13 @@ fn main() {
             let entry: Box<dyn Payload> = payloads.pop().unwrap();
             p.push(Arc::from(entry));
         }
-        run(clients, p, opts).await
+        run(
+            clients,
+            configs.genesis.chain_id(),
+            p,
+            validator_metrics,
+            opts,
+        )
+        .await
     });
 }
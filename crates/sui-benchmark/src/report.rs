@@ -0,0 +1,429 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports of benchmark runs, in JSON to compare runs with each other and in Markdown to read.
+//!
+//! A report breaks the latency of the transactions down into the stages of their execution:
+//! signing them and collecting the certificates of a quorum, timed by the client, then executing
+//! the certificates and committing their effects, timed by the validators, along with the
+//! throughput of each interval of the run. Stages a run does not time, such as the quorum when
+//! the transactions are sent to a gateway, are left out of its report.
+//!
+//! The validators count their latencies in the buckets of prometheus histograms, so that the
+//! percentiles of their stages are the upper bounds of the buckets they fall in.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Write};
+use std::time::Duration;
+
+use prometheus::core::Metric;
+use prometheus::Histogram;
+use serde::{Deserialize, Serialize};
+
+use crate::stress::stats::{ErrorCounts, LatencyHistogram};
+
+/// The width of the bars of the throughput chart, in characters.
+const CHART_WIDTH: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Making and signing the transaction, by the client.
+    Sign,
+    /// From sending the transaction to the validators to holding its certificate.
+    Quorum,
+    /// Executing the certificate, by each validator.
+    Execute,
+    /// Committing the effects of the certificate to the store, by each validator.
+    Commit,
+    /// From sending the transaction to holding the certificate of its effects.
+    Total,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Stage::Sign => "sign",
+            Stage::Quorum => "quorum",
+            Stage::Execute => "execute",
+            Stage::Commit => "commit",
+            Stage::Total => "total",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The latencies of the samples of prometheus histograms of seconds, summed over the histograms,
+/// such as the ones of the validators for [`Stage::Execute`] and [`Stage::Commit`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BucketHistogram {
+    /// The upper bounds of the buckets, in seconds, with the number of latencies up to them.
+    buckets: Vec<(f64, u64)>,
+    count: u64,
+    sum: f64,
+}
+
+impl BucketHistogram {
+    /// Sum `histograms`, which have the same buckets.
+    pub fn collect<'a>(histograms: impl IntoIterator<Item = &'a Histogram>) -> Self {
+        let mut total = Self::default();
+        for histogram in histograms {
+            let metric = histogram.metric();
+            let histogram = metric.get_histogram();
+            if total.buckets.is_empty() {
+                total.buckets = histogram
+                    .get_bucket()
+                    .iter()
+                    .map(|bucket| (bucket.get_upper_bound(), 0))
+                    .collect();
+            }
+            for (total, bucket) in total.buckets.iter_mut().zip(histogram.get_bucket()) {
+                total.1 += bucket.get_cumulative_count();
+            }
+            total.count += histogram.get_sample_count();
+            total.sum += histogram.get_sample_sum();
+        }
+        total
+    }
+
+    /// The latencies counted since `earlier`, collected from the same histograms.
+    pub fn since(&self, earlier: &BucketHistogram) -> Self {
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(i, (bound, count))| {
+                let earlier = earlier.buckets.get(i).map_or(0, |bucket| bucket.1);
+                (*bound, count.saturating_sub(earlier))
+            })
+            .collect();
+        Self {
+            buckets,
+            count: self.count.saturating_sub(earlier.count),
+            sum: (self.sum - earlier.sum).max(0.0),
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(self.sum / self.count as f64)
+        }
+    }
+
+    /// The upper bound of the bucket of the latency under which a `percentile` percent of the
+    /// latencies are, the last bound if it is beyond it, or zero if none was counted.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((percentile / 100.0 * self.count as f64).ceil() as u64).max(1);
+        let bound = self
+            .buckets
+            .iter()
+            .find(|(_, count)| *count >= rank)
+            .or_else(|| self.buckets.last())
+            .map_or(0.0, |(bound, _)| *bound);
+        Duration::from_secs_f64(bound)
+    }
+}
+
+/// The latencies of a stage, in milliseconds.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StageLatency {
+    pub stage: Stage,
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    /// Unknown for the stages timed by the validators.
+    pub max_ms: Option<f64>,
+}
+
+impl StageLatency {
+    pub fn from_histogram(stage: Stage, histogram: &LatencyHistogram) -> Self {
+        Self {
+            stage,
+            count: histogram.count(),
+            mean_ms: millis(histogram.mean()),
+            p50_ms: millis(histogram.percentile(50.0)),
+            p90_ms: millis(histogram.percentile(90.0)),
+            p99_ms: millis(histogram.percentile(99.0)),
+            max_ms: Some(millis(histogram.max())),
+        }
+    }
+
+    pub fn from_buckets(stage: Stage, histogram: &BucketHistogram) -> Self {
+        Self {
+            stage,
+            count: histogram.count(),
+            mean_ms: millis(histogram.mean()),
+            p50_ms: millis(histogram.percentile(50.0)),
+            p90_ms: millis(histogram.percentile(90.0)),
+            p99_ms: millis(histogram.percentile(99.0)),
+            max_ms: None,
+        }
+    }
+}
+
+/// The transactions executed over an interval of the run.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputSample {
+    /// The end of the interval, since the start of the run.
+    pub elapsed_secs: f64,
+    pub tps: f64,
+    pub num_success: u64,
+    pub num_error: u64,
+    pub p50_ms: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// The parameters of the run, to tell apart the runs compared.
+    pub parameters: BTreeMap<String, String>,
+    pub duration_secs: f64,
+    pub num_success: u64,
+    pub num_error: u64,
+    pub tps: f64,
+    /// In the order of the stages.
+    pub stages: Vec<StageLatency>,
+    pub errors: BTreeMap<String, u64>,
+    pub throughput: Vec<ThroughputSample>,
+}
+
+impl BenchmarkReport {
+    pub fn new(parameters: BTreeMap<String, String>) -> Self {
+        Self {
+            parameters,
+            ..Default::default()
+        }
+    }
+
+    /// Set the totals of the run, `elapsed` since its start.
+    pub fn set_totals(&mut self, elapsed: Duration, num_success: u64, errors: &ErrorCounts) {
+        self.duration_secs = elapsed.as_secs_f64();
+        self.num_success = num_success;
+        self.num_error = errors.total();
+        self.tps = rate(num_success, elapsed);
+        self.errors = errors.counts().clone();
+    }
+
+    /// Set the latencies of a stage, replacing the ones set before.
+    pub fn set_stage(&mut self, latency: StageLatency) {
+        self.stages.retain(|stage| stage.stage != latency.stage);
+        self.stages.push(latency);
+        self.stages.sort_by_key(|stage| stage.stage);
+    }
+
+    /// Add the transactions executed over the `interval` ending `elapsed` since the start of the
+    /// run.
+    pub fn add_interval(
+        &mut self,
+        elapsed: Duration,
+        interval: Duration,
+        num_success: u64,
+        errors: &ErrorCounts,
+        latencies: &LatencyHistogram,
+    ) {
+        self.throughput.push(ThroughputSample {
+            elapsed_secs: elapsed.as_secs_f64(),
+            tps: rate(num_success, interval),
+            num_success,
+            num_error: errors.total(),
+            p50_ms: millis(latencies.percentile(50.0)),
+        });
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        // Writing to a string does not fail.
+        self.write_markdown(&mut out).unwrap();
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> fmt::Result {
+        writeln!(out, "# Benchmark report")?;
+        if !self.parameters.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "| Parameter | Value |")?;
+            writeln!(out, "|---|---|")?;
+            for (name, value) in &self.parameters {
+                writeln!(out, "| {name} | {value} |")?;
+            }
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{} transactions succeeded and {} failed in {:.1} s, {:.1} tps.",
+            self.num_success, self.num_error, self.duration_secs, self.tps
+        )?;
+
+        if !self.stages.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## Latency by stage")?;
+            writeln!(out)?;
+            writeln!(
+                out,
+                "| Stage | Count | Mean (ms) | p50 (ms) | p90 (ms) | p99 (ms) | Max (ms) |"
+            )?;
+            writeln!(out, "|---|---:|---:|---:|---:|---:|---:|")?;
+            for stage in &self.stages {
+                let max = stage
+                    .max_ms
+                    .map_or_else(|| "-".to_string(), |max| format!("{max:.1}"));
+                writeln!(
+                    out,
+                    "| {} | {} | {:.1} | {:.1} | {:.1} | {:.1} | {max} |",
+                    stage.stage,
+                    stage.count,
+                    stage.mean_ms,
+                    stage.p50_ms,
+                    stage.p90_ms,
+                    stage.p99_ms
+                )?;
+            }
+        }
+
+        if !self.throughput.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## Throughput over time")?;
+            writeln!(out)?;
+            writeln!(out, "```text")?;
+            let max_tps = self
+                .throughput
+                .iter()
+                .map(|sample| sample.tps)
+                .fold(0.0, f64::max);
+            for sample in &self.throughput {
+                let bar = if max_tps > 0.0 {
+                    (sample.tps / max_tps * CHART_WIDTH as f64).round() as usize
+                } else {
+                    0
+                };
+                writeln!(
+                    out,
+                    "{:>8.1} s |{:<width$}| {:.1} tps, p50 {:.1} ms, {} errors",
+                    sample.elapsed_secs,
+                    "#".repeat(bar),
+                    sample.tps,
+                    sample.p50_ms,
+                    sample.num_error,
+                    width = CHART_WIDTH,
+                )?;
+            }
+            writeln!(out, "```")?;
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "## Errors")?;
+            writeln!(out)?;
+            writeln!(out, "| Error | Count |")?;
+            writeln!(out, "|---|---:|")?;
+            for (kind, count) in &self.errors {
+                writeln!(out, "| {kind} | {count} |")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn rate(count: u64, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        0.0
+    } else {
+        count as f64 / duration.as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{register_histogram_with_registry, Registry};
+
+    fn histogram(registry: &Registry) -> Histogram {
+        register_histogram_with_registry!("latency", "Latency", vec![0.001, 0.01, 0.1], registry)
+            .unwrap()
+    }
+
+    #[test]
+    fn bucket_percentiles() {
+        let (first, second) = (Registry::new(), Registry::new());
+        let (first, second) = (histogram(&first), histogram(&second));
+        first.observe(0.0005);
+        let earlier = BucketHistogram::collect([&first, &second]);
+        for _ in 0..8 {
+            first.observe(0.005);
+        }
+        second.observe(0.05);
+        second.observe(1.0);
+
+        let latencies = BucketHistogram::collect([&first, &second]).since(&earlier);
+        assert_eq!(latencies.count(), 10);
+        assert_eq!(latencies.percentile(50.0), Duration::from_millis(10));
+        assert_eq!(latencies.percentile(90.0), Duration::from_millis(100));
+        // Beyond the last bucket
+        assert_eq!(latencies.percentile(100.0), Duration::from_millis(100));
+        assert!((latencies.mean().as_secs_f64() - 0.109).abs() < 1e-6);
+        assert_eq!(BucketHistogram::default().percentile(50.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn report_in_json_and_markdown() {
+        let parameters = BTreeMap::from([("target_qps".to_string(), "100".to_string())]);
+        let mut report = BenchmarkReport::new(parameters);
+        let mut latencies = LatencyHistogram::default();
+        latencies.record(Duration::from_millis(20));
+        let mut errors = ErrorCounts::default();
+        errors.record("Timeout".to_string());
+        let interval = Duration::from_secs(10);
+        report.add_interval(interval, interval, 1000, &errors, &latencies);
+        report.add_interval(
+            interval * 2,
+            interval,
+            500,
+            &ErrorCounts::default(),
+            &latencies,
+        );
+        report.set_totals(interval * 2, 1500, &errors);
+        report.set_stage(StageLatency::from_histogram(Stage::Total, &latencies));
+        report.set_stage(StageLatency::from_histogram(Stage::Sign, &latencies));
+        report.set_stage(StageLatency::from_buckets(
+            Stage::Execute,
+            &BucketHistogram::default(),
+        ));
+
+        let stages: Vec<_> = report.stages.iter().map(|stage| stage.stage).collect();
+        assert_eq!(stages, vec![Stage::Sign, Stage::Execute, Stage::Total]);
+        assert_eq!(report.tps, 75.0);
+
+        let json = report.to_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<BenchmarkReport>(&json).unwrap(),
+            report
+        );
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| target_qps | 100 |"));
+        assert!(markdown.contains("1500 transactions succeeded and 1 failed in 20.0 s, 75.0 tps."));
+        assert!(markdown.contains("| sign | 1 | 20.0 | 20.0 | 20.0 | 20.0 | 20.0 |"));
+        assert!(markdown.contains("| execute | 0 | 0.0 | 0.0 | 0.0 | 0.0 | - |"));
+        assert!(markdown.contains(&format!("    10.0 s |{}| 100.0 tps", "#".repeat(50))));
+        assert!(markdown.contains(&format!("    20.0 s |{:<50}| 50.0 tps", "#".repeat(25))));
+        assert!(markdown.contains("| Timeout | 1 |"));
+    }
+}
@@ -2,5 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod context;
+pub mod pay;
+pub mod publish;
 pub mod shared_counter;
+pub mod stats;
+pub mod submitter;
 pub mod transfer_object;
@@ -0,0 +1,100 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sui_config::NetworkConfig;
+use sui_types::{
+    base_types::{ChainId, ObjectID, ObjectRef, SuiAddress},
+    crypto::{get_key_pair, EmptySignInfo, KeyPair},
+    messages::TransactionEnvelope,
+    object::Object,
+};
+use test_utils::{messages::make_pay_transaction, objects::generate_gas_objects_with_owner};
+
+use super::context::{Gas, Payload, StressTestCtx};
+
+/// The amount paid by every pay transaction.
+const PAY_AMOUNT: u64 = 1;
+
+pub struct PayTestPayload {
+    coin: ObjectRef,
+    gas: Gas,
+    recipient: SuiAddress,
+    keypair: Arc<KeyPair>,
+    chain_id: ChainId,
+}
+
+impl Payload for PayTestPayload {
+    fn make_new_payload(&self, new_object: ObjectRef, new_gas: ObjectRef) -> Box<dyn Payload> {
+        Box::new(PayTestPayload {
+            coin: new_object,
+            gas: (new_gas, self.gas.1),
+            recipient: self.recipient,
+            keypair: self.keypair.clone(),
+            chain_id: self.chain_id,
+        })
+    }
+    fn make_transaction(&self) -> TransactionEnvelope<EmptySignInfo> {
+        make_pay_transaction(
+            vec![self.coin],
+            self.gas.0,
+            self.gas.1.get_owner_address().unwrap(),
+            &self.keypair,
+            vec![self.recipient],
+            vec![PAY_AMOUNT],
+            self.chain_id,
+        )
+    }
+    fn get_object_id(&self) -> ObjectID {
+        self.coin.0
+    }
+}
+
+pub struct PayTestCtx {
+    coins: Vec<Object>,
+    gas: Vec<Object>,
+    recipient: SuiAddress,
+    keypair: Arc<KeyPair>,
+}
+
+impl PayTestCtx {
+    pub fn make_ctx(count: u64, _configs: &NetworkConfig) -> Box<dyn StressTestCtx<dyn Payload>> {
+        // every payload pays out of its own coin, to the same recipient
+        let (sender, keypair) = get_key_pair();
+        let (recipient, _) = get_key_pair();
+        Box::new(PayTestCtx {
+            coins: generate_gas_objects_with_owner(count as usize, sender),
+            gas: generate_gas_objects_with_owner(count as usize, sender),
+            recipient,
+            keypair: Arc::new(keypair),
+        })
+    }
+}
+
+#[async_trait]
+impl StressTestCtx<dyn Payload> for PayTestCtx {
+    fn get_gas_objects(&mut self) -> Vec<Object> {
+        let mut gas = self.coins.clone();
+        gas.append(&mut self.gas.clone());
+        gas
+    }
+    async fn make_test_payloads(&self, configs: &NetworkConfig) -> Vec<Box<dyn Payload>> {
+        let chain_id = configs.genesis.chain_id();
+        self.coins
+            .iter()
+            .zip(self.gas.iter())
+            .map(|(coin, gas)| {
+                Box::new(PayTestPayload {
+                    coin: coin.compute_object_reference(),
+                    gas: (gas.compute_object_reference(), gas.owner),
+                    recipient: self.recipient,
+                    keypair: self.keypair.clone(),
+                    chain_id,
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+}
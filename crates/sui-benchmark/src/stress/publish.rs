@@ -0,0 +1,97 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use sui_config::NetworkConfig;
+use sui_types::{
+    base_types::{ChainId, ObjectID, ObjectRef, SuiAddress},
+    crypto::{get_key_pair, EmptySignInfo, KeyPair},
+    messages::TransactionEnvelope,
+    object::Object,
+};
+use test_utils::{
+    messages::{compile_basics_package, make_publish_transaction},
+    objects::generate_gas_objects_with_owner,
+};
+
+use super::context::{Payload, StressTestCtx};
+
+/// Publishes the basics example package over and over, the only object a publish mutates being
+/// its gas.
+pub struct PublishTestPayload {
+    gas: ObjectRef,
+    sender: SuiAddress,
+    keypair: Arc<KeyPair>,
+    modules: Arc<Vec<Vec<u8>>>,
+    chain_id: ChainId,
+}
+
+impl Payload for PublishTestPayload {
+    fn make_new_payload(&self, _: ObjectRef, new_gas: ObjectRef) -> Box<dyn Payload> {
+        Box::new(PublishTestPayload {
+            gas: new_gas,
+            sender: self.sender,
+            keypair: self.keypair.clone(),
+            modules: self.modules.clone(),
+            chain_id: self.chain_id,
+        })
+    }
+    fn make_transaction(&self) -> TransactionEnvelope<EmptySignInfo> {
+        make_publish_transaction(
+            self.gas,
+            self.modules.as_ref().clone(),
+            self.sender,
+            &self.keypair,
+            self.chain_id,
+        )
+    }
+    fn get_object_id(&self) -> ObjectID {
+        self.gas.0
+    }
+}
+
+pub struct PublishTestCtx {
+    gas: Vec<Object>,
+    sender: SuiAddress,
+    keypair: Arc<KeyPair>,
+    modules: Arc<Vec<Vec<u8>>>,
+}
+
+impl PublishTestCtx {
+    pub fn make_ctx(count: u64, _configs: &NetworkConfig) -> Box<dyn StressTestCtx<dyn Payload>> {
+        let (sender, keypair) = get_key_pair();
+        // compile the package once, rather than for every transaction
+        eprintln!("Compiling basics package");
+        Box::new(PublishTestCtx {
+            gas: generate_gas_objects_with_owner(count as usize, sender),
+            sender,
+            keypair: Arc::new(keypair),
+            modules: Arc::new(compile_basics_package()),
+        })
+    }
+}
+
+#[async_trait]
+impl StressTestCtx<dyn Payload> for PublishTestCtx {
+    fn get_gas_objects(&mut self) -> Vec<Object> {
+        self.gas.clone()
+    }
+    async fn make_test_payloads(&self, configs: &NetworkConfig) -> Vec<Box<dyn Payload>> {
+        let chain_id = configs.genesis.chain_id();
+        self.gas
+            .iter()
+            .map(|gas| {
+                Box::new(PublishTestPayload {
+                    gas: gas.compute_object_reference(),
+                    sender: self.sender,
+                    keypair: self.keypair.clone(),
+                    modules: self.modules.clone(),
+                    chain_id,
+                })
+            })
+            .map(|b| Box::<dyn Payload>::from(b))
+            .collect()
+    }
+}
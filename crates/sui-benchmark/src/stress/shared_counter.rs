@@ -6,7 +6,7 @@ use futures::future::join_all;
 use sui_config::NetworkConfig;
 use sui_quorum_driver::QuorumDriverHandler;
 use sui_types::{
-    base_types::{ObjectID, ObjectRef},
+    base_types::{ChainId, ObjectID, ObjectRef},
     crypto::EmptySignInfo,
     messages::{
         ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse,
@@ -27,6 +27,7 @@ pub struct SharedCounterTestPayload {
     package_ref: ObjectRef,
     counter_id: ObjectID,
     gas: Gas,
+    chain_id: ChainId,
 }
 
 impl Payload for SharedCounterTestPayload {
@@ -35,10 +36,16 @@ impl Payload for SharedCounterTestPayload {
             package_ref: self.package_ref,
             counter_id: self.counter_id,
             gas: (new_gas, self.gas.1),
+            chain_id: self.chain_id,
         })
     }
     fn make_transaction(&self) -> TransactionEnvelope<EmptySignInfo> {
-        make_counter_increment_transaction(self.gas.0, self.package_ref, self.counter_id)
+        make_counter_increment_transaction(
+            self.gas.0,
+            self.package_ref,
+            self.counter_id,
+            self.chain_id,
+        )
     }
     fn get_object_id(&self) -> ObjectID {
         self.counter_id
@@ -71,11 +78,16 @@ impl StressTestCtx<dyn Payload> for SharedCounterTestCtx {
     }
     async fn make_test_payloads(&self, configs: &NetworkConfig) -> Vec<Box<dyn Payload>> {
         let clients = test_authority_aggregator(configs);
+        let chain_id = configs.genesis.chain_id();
         let quorum_driver_handler = QuorumDriverHandler::new(clients.clone());
         // Publish basics package
         eprintln!("Publishing basics package");
-        let package_ref =
-            publish_counter_package(self.publish_module_gas.clone(), configs.validator_set()).await;
+        let package_ref = publish_counter_package(
+            self.publish_module_gas.clone(),
+            configs.validator_set(),
+            chain_id,
+        )
+        .await;
         let qd_and_gas = self
             .counter_gas
             .clone()
@@ -83,8 +95,11 @@ impl StressTestCtx<dyn Payload> for SharedCounterTestCtx {
             .map(|g| (quorum_driver_handler.clone_quorum_driver(), g));
         // create counters
         let futures = qd_and_gas.map(|(qd, gas_object)| async move {
-            let tx =
-                make_counter_create_transaction(gas_object.compute_object_reference(), package_ref);
+            let tx = make_counter_create_transaction(
+                gas_object.compute_object_reference(),
+                package_ref,
+                chain_id,
+            );
             if let ExecuteTransactionResponse::EffectsCert(result) = qd
                 .execute_transaction(ExecuteTransactionRequest {
                     transaction: tx,
@@ -98,6 +113,7 @@ impl StressTestCtx<dyn Payload> for SharedCounterTestCtx {
                     package_ref,
                     counter_id: effects.effects.created[0].0 .0,
                     gas: effects.effects.gas_object,
+                    chain_id,
                 })
             } else {
                 panic!("Failed to create shared counter!");
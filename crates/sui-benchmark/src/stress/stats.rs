@@ -0,0 +1,212 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Statistics of the transactions executed by a stress test.
+//!
+//! Latencies are counted in buckets of a millisecond, so that the histograms of the workers are
+//! cheap to send and merge however long the test runs, and percentiles are accurate to the
+//! millisecond. Errors are counted by kind, the name of the variant of the error.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display};
+use std::time::Duration;
+
+/// Latencies of this many milliseconds or more are counted in the last bucket.
+const MAX_LATENCY_MS: usize = 10_000;
+
+#[derive(Clone)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    sum: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; MAX_LATENCY_MS + 1],
+            count: 0,
+            sum: Duration::ZERO,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, latency: Duration) {
+        let bucket = (latency.as_millis() as usize).min(MAX_LATENCY_MS);
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum += latency;
+        self.min = self.min.min(latency);
+        self.max = self.max.max(latency);
+    }
+
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *bucket += count;
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            self.min
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.sum.as_nanos() / self.count as u128) as u64)
+        }
+    }
+
+    /// The latency under which a `percentile` percent of the latencies are, or zero if none
+    /// was recorded.
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((percentile / 100.0 * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return if bucket == MAX_LATENCY_MS {
+                    self.max
+                } else {
+                    Duration::from_millis(bucket as u64).clamp(self.min, self.max)
+                };
+            }
+        }
+        self.max
+    }
+}
+
+impl Display for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min_latency_ms = {}, p50_ms = {}, p90_ms = {}, p99_ms = {}, max_latency_ms = {}",
+            self.min().as_millis(),
+            self.percentile(50.0).as_millis(),
+            self.percentile(90.0).as_millis(),
+            self.percentile(99.0).as_millis(),
+            self.max().as_millis(),
+        )
+    }
+}
+
+/// The number of errors of each kind.
+#[derive(Clone, Default)]
+pub struct ErrorCounts(BTreeMap<String, u64>);
+
+impl ErrorCounts {
+    pub fn record(&mut self, kind: String) {
+        *self.0.entry(kind).or_default() += 1;
+    }
+
+    pub fn merge(&mut self, other: &ErrorCounts) {
+        for (kind, count) in &other.0 {
+            *self.0.entry(kind.clone()).or_default() += count;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.0.values().sum()
+    }
+
+    pub fn counts(&self) -> &BTreeMap<String, u64> {
+        &self.0
+    }
+}
+
+impl Display for ErrorCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let counts: Vec<_> = self
+            .0
+            .iter()
+            .map(|(kind, count)| format!("{kind}: {count}"))
+            .collect();
+        write!(f, "{{{}}}", counts.join(", "))
+    }
+}
+
+/// The kind of an error, the name of its variant as debug formatted.
+pub fn error_kind(error: &impl Debug) -> String {
+    let error = format!("{error:?}");
+    error
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .filter(|kind| !kind.is_empty())
+        .unwrap_or("Unknown")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(50.0), Duration::ZERO);
+        for ms in 1..=100 {
+            histogram.record(Duration::from_millis(ms));
+        }
+        let mut slow = LatencyHistogram::default();
+        slow.record(Duration::from_secs(20));
+        histogram.merge(&slow);
+
+        assert_eq!(histogram.count(), 101);
+        assert_eq!(histogram.min(), Duration::from_millis(1));
+        assert_eq!(histogram.mean(), Duration::from_millis(5050 + 20_000) / 101);
+        assert_eq!(histogram.percentile(50.0), Duration::from_millis(51));
+        assert_eq!(histogram.percentile(99.0), Duration::from_millis(100));
+        assert_eq!(histogram.percentile(100.0), Duration::from_secs(20));
+    }
+
+    #[test]
+    fn errors_by_kind() {
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        enum TestError {
+            Timeout,
+            QuorumNotReached { errors: Vec<String> },
+            Rpc(String),
+        }
+        let mut errors = ErrorCounts::default();
+        errors.record(error_kind(&TestError::Timeout));
+        errors.record(error_kind(&TestError::Rpc("refused".to_string())));
+        let mut other = ErrorCounts::default();
+        other.record(error_kind(&TestError::QuorumNotReached {
+            errors: vec!["Timeout".to_string()],
+        }));
+        other.record(error_kind(&TestError::Timeout));
+        errors.merge(&other);
+
+        assert_eq!(errors.total(), 4);
+        assert_eq!(
+            errors.to_string(),
+            "{QuorumNotReached: 1, Rpc: 1, Timeout: 2}"
+        );
+    }
+}
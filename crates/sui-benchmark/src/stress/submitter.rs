@@ -0,0 +1,106 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use prometheus::Registry;
+use sui_core::authority_aggregator::AuthorityAggregator;
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::gateway_state::{GatewayAPI, GatewayClient, GatewayMetrics, GatewayState};
+use sui_json_rpc_types::TransactionResponse;
+use sui_quorum_driver::{QuorumDriver, QuorumDriverHandler};
+use sui_types::base_types::{ChainId, ObjectRef};
+use sui_types::messages::Transaction;
+
+use super::stats::error_kind;
+
+/// The objects a transaction mutated, its gas included, and its gas.
+pub struct ExecutedTransaction {
+    pub mutated: Vec<ObjectRef>,
+    pub gas: ObjectRef,
+    /// The time to collect the certificate of the transaction, unknown through a gateway.
+    pub quorum_latency: Option<Duration>,
+}
+
+/// Where the transactions of a stress test are sent: to the validators through a quorum
+/// driver, or to a gateway in front of them.
+#[derive(Clone)]
+pub enum Submitter {
+    QuorumDriver(Arc<QuorumDriver<NetworkAuthorityClient>>),
+    Gateway(GatewayClient),
+}
+
+impl Submitter {
+    /// A quorum driver of its own, so that the workers do not share the same driver task.
+    pub fn quorum_driver(clients: AuthorityAggregator<NetworkAuthorityClient>) -> Self {
+        Self::QuorumDriver(QuorumDriverHandler::new(clients).clone_quorum_driver())
+    }
+
+    /// A gateway of the validators of `chain_id`, storing its state in a temporary directory.
+    pub fn gateway(
+        clients: AuthorityAggregator<NetworkAuthorityClient>,
+        chain_id: ChainId,
+    ) -> Result<Self, anyhow::Error> {
+        let path = tempfile::tempdir()?.into_path();
+        let metrics = GatewayMetrics::new(&Registry::new());
+        let gateway = GatewayState::new_with_authorities(path, clients, metrics, chain_id)?;
+        Ok(Self::Gateway(Arc::new(gateway)))
+    }
+
+    /// Execute `transaction` until its effects are certified, failing with the kind of the
+    /// error.
+    pub async fn execute(&self, transaction: Transaction) -> Result<ExecutedTransaction, String> {
+        match self {
+            // The certificate and its effects are processed in turn, as when waiting for the
+            // certificate of the effects, to time both.
+            Self::QuorumDriver(qd) => {
+                let start = Instant::now();
+                let certificate = qd
+                    .process_transaction(transaction)
+                    .await
+                    .map_err(|e| error_kind(&e))?;
+                let quorum_latency = start.elapsed();
+                let (_, effects) = qd
+                    .process_certificate(certificate)
+                    .await
+                    .map_err(|e| error_kind(&e))?;
+                Ok(ExecutedTransaction {
+                    mutated: effects.effects.mutated.iter().map(|x| x.0).collect(),
+                    gas: effects.effects.gas_object.0,
+                    quorum_latency: Some(quorum_latency),
+                })
+            }
+            Self::Gateway(gateway) => {
+                let response = gateway
+                    .execute_transaction(transaction)
+                    .await
+                    .map_err(|e| match e.downcast_ref::<sui_types::error::SuiError>() {
+                        Some(e) => error_kind(e),
+                        None => "GatewayError".to_string(),
+                    })?;
+                match response {
+                    TransactionResponse::EffectResponse(response) => Ok(ExecutedTransaction {
+                        mutated: response
+                            .effects
+                            .mutated
+                            .iter()
+                            .map(|x| x.reference.to_object_ref())
+                            .collect(),
+                        gas: response.effects.gas_object.reference.to_object_ref(),
+                        quorum_latency: None,
+                    }),
+                    TransactionResponse::PublishResponse(response) => {
+                        let gas = response.updated_gas.reference.to_object_ref();
+                        Ok(ExecutedTransaction {
+                            mutated: vec![gas],
+                            gas,
+                            quorum_latency: None,
+                        })
+                    }
+                    _ => Err("UnexpectedResponse".to_string()),
+                }
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use rand::seq::IteratorRandom;
 use sui_config::NetworkConfig;
 use sui_types::{
-    base_types::{ObjectID, ObjectRef, SuiAddress},
+    base_types::{ChainId, ObjectID, ObjectRef, SuiAddress},
     crypto::{get_key_pair, EmptySignInfo, KeyPair},
     messages::TransactionEnvelope,
     object::{Object, Owner},
@@ -25,6 +25,7 @@ pub struct TransferObjectTestPayload {
     transfer_to: SuiAddress,
     gas: Vec<Gas>,
     keypairs: Arc<HashMap<SuiAddress, KeyPair>>,
+    chain_id: ChainId,
 }
 
 impl Payload for TransferObjectTestPayload {
@@ -51,6 +52,7 @@ impl Payload for TransferObjectTestPayload {
             transfer_to: recipient.get_owner_address().unwrap(),
             gas: updated_gas,
             keypairs: self.keypairs.clone(),
+            chain_id: self.chain_id,
         })
     }
     fn make_transaction(&self) -> TransactionEnvelope<EmptySignInfo> {
@@ -65,6 +67,7 @@ impl Payload for TransferObjectTestPayload {
             self.transfer_from,
             self.keypairs.get(&self.transfer_from).unwrap(),
             self.transfer_to,
+            self.chain_id,
         )
     }
     fn get_object_id(&self) -> ObjectID {
@@ -117,7 +120,8 @@ impl StressTestCtx<dyn Payload> for TransferObjectTestCtx {
         gas.append(&mut self.transfer_objects.clone());
         gas
     }
-    async fn make_test_payloads(&self, _configs: &NetworkConfig) -> Vec<Box<dyn Payload>> {
+    async fn make_test_payloads(&self, configs: &NetworkConfig) -> Vec<Box<dyn Payload>> {
+        let chain_id = configs.genesis.chain_id();
         let refs: Vec<(Vec<Gas>, ObjectRef)> = self
             .transfer_gas
             .iter()
@@ -144,6 +148,7 @@ impl StressTestCtx<dyn Payload> for TransferObjectTestCtx {
                     transfer_to: to.get_owner_address().unwrap(),
                     gas: g.clone(),
                     keypairs: self.keypairs.clone(),
+                    chain_id,
                 })
             })
             .map(|b| Box::<dyn Payload>::from(b))
@@ -49,7 +49,8 @@ impl TestCaseImpl for CallContractTest {
             "mint",
             vec![],
             Some(*gas_obj.id()),
-            5000,
+            Some(5000),
+            false,
             args,
             wallet_context,
         )
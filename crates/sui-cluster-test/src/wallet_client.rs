@@ -37,6 +37,9 @@ impl WalletClient {
             keystore,
             gateway: GatewayType::RPC(rpc_url.into()),
             active_address: Some(address),
+            envs: vec![],
+            active_env: None,
+            name_registry: None,
         }
         .persisted(&wallet_config_path)
         .save()
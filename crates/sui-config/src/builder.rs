@@ -4,9 +4,10 @@
 use crate::{
     genesis,
     genesis_config::{GenesisConfig, ValidatorGenesisInfo},
-    utils, ConsensusConfig, NetworkConfig, NodeConfig, ValidatorInfo, AUTHORITIES_DB_NAME,
-    CONSENSUS_DB_NAME, DEFAULT_STAKE,
+    utils, ConsensusConfig, NetworkConfig, NodeConfig, AUTHORITIES_DB_NAME, CONSENSUS_DB_NAME,
+    DEFAULT_STAKE,
 };
+use anyhow::Result;
 use rand::rngs::OsRng;
 use std::{
     num::NonZeroUsize,
@@ -24,6 +25,7 @@ pub struct ConfigBuilder<R = OsRng> {
     randomize_ports: bool,
     committee_size: NonZeroUsize,
     initial_accounts_config: Option<GenesisConfig>,
+    quic: bool,
 }
 
 impl ConfigBuilder {
@@ -34,6 +36,7 @@ impl ConfigBuilder {
             randomize_ports: true,
             committee_size: NonZeroUsize::new(1).unwrap(),
             initial_accounts_config: None,
+            quic: false,
         }
     }
 }
@@ -54,6 +57,12 @@ impl<R> ConfigBuilder<R> {
         self
     }
 
+    /// Serve the validators over QUIC instead of gRPC over TCP.
+    pub fn quic(mut self, quic: bool) -> Self {
+        self.quic = quic;
+        self
+    }
+
     pub fn rng<N: ::rand::RngCore + ::rand::CryptoRng>(self, rng: N) -> ConfigBuilder<N> {
         ConfigBuilder {
             rng,
@@ -61,18 +70,24 @@ impl<R> ConfigBuilder<R> {
             randomize_ports: self.randomize_ports,
             committee_size: self.committee_size,
             initial_accounts_config: self.initial_accounts_config,
+            quic: self.quic,
         }
     }
 }
 
 impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
     //TODO right now we always randomize ports, we may want to have a default port configuration
-    pub fn build(mut self) -> NetworkConfig {
+    pub fn build(mut self) -> Result<NetworkConfig> {
+        let quic = self.quic;
         let validators = (0..self.committee_size.get())
             .map(|_| get_key_pair_from_rng(&mut self.rng).1)
             .map(|key_pair| ValidatorGenesisInfo {
                 key_pair,
-                network_address: utils::new_network_address(),
+                network_address: if quic {
+                    utils::new_quic_network_address()
+                } else {
+                    utils::new_network_address()
+                },
                 stake: DEFAULT_STAKE,
                 narwhal_primary_to_primary: utils::new_network_address(),
                 narwhal_worker_to_primary: utils::new_network_address(),
@@ -85,46 +100,43 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
         self.build_with_validators(validators)
     }
 
-    pub fn build_with_validators(mut self, validators: Vec<ValidatorGenesisInfo>) -> NetworkConfig {
+    pub fn build_with_validators(
+        mut self,
+        validators: Vec<ValidatorGenesisInfo>,
+    ) -> Result<NetworkConfig> {
         let validator_set = validators
             .iter()
             .enumerate()
-            .map(|(i, validator)| {
-                let name = format!("validator-{i}");
-                let public_key: PublicKeyBytes = validator.key_pair.public().into();
-                let stake = validator.stake;
-                let network_address = validator.network_address.clone();
-
-                ValidatorInfo {
-                    name,
-                    public_key,
-                    stake,
-                    delegation: 0, // no delegation yet at genesis
-                    network_address,
-                    narwhal_primary_to_primary: validator.narwhal_primary_to_primary.clone(),
-                    narwhal_worker_to_primary: validator.narwhal_worker_to_primary.clone(),
-                    narwhal_primary_to_worker: validator.narwhal_primary_to_worker.clone(),
-                    narwhal_worker_to_worker: validator.narwhal_worker_to_worker.clone(),
-                    narwhal_consensus_address: validator.narwhal_consensus_address.clone(),
-                }
-            })
+            .map(|(i, validator)| validator.to_validator_info(format!("validator-{i}")))
             .collect::<Vec<_>>();
 
         let initial_accounts_config = self
             .initial_accounts_config
             .unwrap_or_else(GenesisConfig::for_local_testing);
-        let (account_keys, objects) = initial_accounts_config
-            .generate_accounts(&mut self.rng)
-            .unwrap();
+        let (account_keys, objects, accounts) =
+            initial_accounts_config.generate_accounts(&mut self.rng)?;
 
         let genesis = {
             let mut builder = genesis::Builder::new().add_objects(objects);
 
+            for (address, gas_budgets) in accounts {
+                builder = builder.add_account(address, gas_budgets);
+            }
+
             for validator in validator_set {
                 builder = builder.add_validator(validator);
             }
+            for path in &initial_accounts_config.packages {
+                builder = builder.add_move_package(path.clone());
+            }
+            if let Some(epoch_config) = &initial_accounts_config.epoch_config {
+                builder = builder.set_epoch_config(epoch_config.clone());
+            }
+            if let Some(protocol_version) = initial_accounts_config.protocol_version {
+                builder = builder.set_protocol_version(protocol_version);
+            }
 
-            builder.build()
+            builder.build()?
         };
 
         let validator_configs = validators
@@ -149,12 +161,30 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
 
                 NodeConfig {
                     key_pair: Arc::new(validator.key_pair),
+                    network_key_pair: None,
+                    worker_key_pair: None,
+                    account_key: None,
                     db_path,
+                    storage_backend: None,
+                    group_commit: None,
+                    object_cache_size: None,
+                    backup_config: None,
+                    migration_config: None,
+                    ttl_config: None,
+                    traffic_capture_path: None,
                     network_address,
+                    quic_config: None,
+                    allowed_clients: None,
+                    discovery_config: None,
                     metrics_address: utils::available_local_socket_address(),
                     admin_interface_port: utils::get_available_port(),
+                    admin_token: None,
+                    logging_config: None,
+                    telemetry_push: None,
+                    tunable_config: None,
                     json_rpc_address: utils::available_local_socket_address(),
                     websocket_address: None,
+                    rest_address: None,
                     consensus_config: Some(consensus_config),
                     enable_event_processing: false,
                     enable_gossip: true,
@@ -164,10 +194,10 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> ConfigBuilder<R> {
             })
             .collect();
 
-        NetworkConfig {
+        Ok(NetworkConfig {
             validator_configs,
             genesis,
             account_keys,
-        }
+        })
     }
 }
@@ -1,47 +1,79 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::ValidatorInfo;
+use crate::genesis_config::GenesisConfig;
+use crate::{EpochConfig, ValidatorInfo};
 use anyhow::{bail, Context, Result};
 use camino::Utf8Path;
+use move_binary_format::access::ModuleAccess;
 use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::account_address::AccountAddress;
 use move_core_types::ident_str;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::value::MoveStruct;
+use move_package::BuildConfig;
 use move_vm_runtime::native_functions::NativeFunctionTable;
+use multiaddr::Multiaddr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::BTreeMap;
-use std::{fs, path::Path};
+use sha3::{Digest, Sha3_256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use sui_adapter::adapter;
 use sui_adapter::adapter::MoveVM;
 use sui_adapter::in_memory_storage::InMemoryStorage;
 use sui_adapter::temporary_store::TemporaryStore;
+use sui_types::base_types::ChainId;
 use sui_types::base_types::ObjectID;
 use sui_types::base_types::TransactionDigest;
 use sui_types::crypto::PublicKey;
 use sui_types::crypto::PublicKeyBytes;
 use sui_types::gas::SuiGasStatus;
+use sui_types::handshake::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
 use sui_types::messages::CallArg;
 use sui_types::messages::InputObjects;
 use sui_types::messages::Transaction;
+use sui_types::protocol_config::ProtocolConfig;
 use sui_types::sui_serde::{Base64, Encoding};
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::MOVE_STDLIB_ADDRESS;
 use sui_types::SUI_FRAMEWORK_ADDRESS;
 use sui_types::{
-    base_types::{encode_bytes_hex, TxContext},
-    committee::{Committee, EpochId},
-    error::SuiResult,
-    object::Object,
+    base_types::{decode_bytes_hex, encode_bytes_hex, SequenceNumber, SuiAddress, TxContext},
+    committee::{Committee, EpochId, StakeUnit},
+    error::{SuiError, SuiResult},
+    gas_coin::GasCoin,
+    object::{MoveObject, Object, ObjectFormatOptions, Owner},
 };
+use thiserror::Error;
 use tracing::trace;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Genesis {
     objects: Vec<Object>,
     validator_set: Vec<ValidatorInfo>,
+    epoch_config: EpochConfig,
+    protocol_version: u32,
 }
 
 impl Genesis {
+    pub(crate) fn new(
+        objects: Vec<Object>,
+        validator_set: Vec<ValidatorInfo>,
+        epoch_config: EpochConfig,
+        protocol_version: u32,
+    ) -> Self {
+        Self {
+            objects,
+            validator_set,
+            epoch_config,
+            protocol_version,
+        }
+    }
+
     pub fn objects(&self) -> &[Object] {
         &self.objects
     }
@@ -54,11 +86,20 @@ impl Genesis {
         &self.validator_set
     }
 
+    /// When the epochs of the network end, which all its validators follow.
+    pub fn epoch_config(&self) -> &EpochConfig {
+        &self.epoch_config
+    }
+
+    /// The protocol version the validators of the network execute transactions at, see
+    /// [`ProtocolConfig`]. Nodes whose binary does not support it refuse to start.
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// The committee of the first epoch, as materialized in the on-chain system state.
     pub fn committee(&self) -> SuiResult<Committee> {
-        Committee::new(
-            self.epoch(),
-            ValidatorInfo::voting_rights(self.validator_set()),
-        )
+        self.sui_system_object().get_current_epoch_committee()
     }
 
     pub fn narwhal_committee(&self) -> narwhal_config::SharedCommittee<PublicKey> {
@@ -115,7 +156,9 @@ impl Genesis {
     }
 
     pub fn get_default_genesis() -> Self {
-        Builder::new().build()
+        Builder::new()
+            .build()
+            .expect("The default genesis is valid")
     }
 
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
@@ -123,21 +166,175 @@ impl Genesis {
         trace!("Reading Genesis from {}", path.display());
         let bytes = fs::read(path)
             .with_context(|| format!("Unable to load Genesis from {}", path.display()))?;
-        Ok(bcs::from_bytes(&bytes)?)
+        Self::from_file_bytes(&bytes)
+            .with_context(|| format!("Unable to load Genesis from {}", path.display()))
     }
 
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), anyhow::Error> {
+        self.save_with_compression(path, GenesisCompression::None)
+    }
+
+    /// Save genesis with its encoding compressed with `compression`, which binaries reading
+    /// version 2 of the file format or later can load.
+    pub fn save_with_compression<P: AsRef<Path>>(
+        &self,
+        path: P,
+        compression: GenesisCompression,
+    ) -> Result<(), anyhow::Error> {
         let path = path.as_ref();
         trace!("Writing Genesis to {}", path.display());
-        let bytes = bcs::to_bytes(&self)?;
+        let bytes = self
+            .to_file_bytes_with_compression(compression)
+            .with_context(|| format!("Unable to compress Genesis for {}", path.display()))?;
         fs::write(path, bytes)
             .with_context(|| format!("Unable to save Genesis to {}", path.display()))?;
         Ok(())
     }
 
+    /// The contents of an uncompressed genesis file: the encoding of [`Genesis::to_bytes`]
+    /// wrapped in an envelope of the current format version.
+    pub fn to_file_bytes(&self) -> Vec<u8> {
+        self.to_file_bytes_with_compression(GenesisCompression::None)
+            .expect("genesis is stored as it is without compression")
+    }
+
+    /// The contents of a genesis file, its encoding compressed with `compression`.
+    pub fn to_file_bytes_with_compression(
+        &self,
+        compression: GenesisCompression,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let payload = compression.compress(self.to_bytes())?;
+        let file = GenesisFile {
+            magic: GENESIS_FILE_MAGIC,
+            version: GENESIS_FILE_FORMAT_VERSION,
+            compression,
+            checksum: Sha3_256::digest(&payload).into(),
+            payload,
+        };
+        Ok(bcs::to_bytes(&file)?)
+    }
+
+    /// Read the contents of a genesis file, refusing the format versions this binary does not
+    /// know. Files written before genesis files were versioned hold the bare BCS encoding, with
+    /// validators of before their network and account keys, which are read too, as are the ones
+    /// of before genesis had epoch parameters and a protocol version.
+    pub fn from_file_bytes(bytes: &[u8]) -> Result<Self, anyhow::Error> {
+        if !bytes.starts_with(&GENESIS_FILE_MAGIC) {
+            return Ok(bcs::from_bytes(bytes)?);
+        }
+        let version_bytes = bytes
+            .get(GENESIS_FILE_MAGIC.len()..GENESIS_FILE_MAGIC.len() + 4)
+            .context("Truncated genesis file")?;
+        let version: u32 = bcs::from_bytes(version_bytes)?;
+        let file = match version {
+            // The first version did not compress
+            1 => {
+                let file: GenesisFileV1 = bcs::from_bytes(bytes)?;
+                GenesisFile {
+                    magic: file.magic,
+                    version: file.version,
+                    compression: GenesisCompression::None,
+                    payload: file.payload,
+                    checksum: file.checksum,
+                }
+            }
+            2 | 3 | GENESIS_FILE_FORMAT_VERSION => bcs::from_bytes(bytes)?,
+            _ => bail!(
+                "Unsupported genesis file format version {version}, expected version \
+                 {GENESIS_FILE_FORMAT_VERSION} at most"
+            ),
+        };
+        if <[u8; 32]>::from(Sha3_256::digest(&file.payload)) != file.checksum {
+            bail!("Genesis file is corrupted, its checksum does not match its contents");
+        }
+        let payload = file.compression.decompress(file.payload)?;
+        Ok(bcs::from_bytes(&payload)?)
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         bcs::to_bytes(self).expect("failed to serialize genesis")
     }
+
+    /// The encoding of this genesis with the objects ordered by ID and the validators by public
+    /// key, so that it does not depend on the order they were added or loaded in.
+    fn to_canonical_bytes(&self) -> Vec<u8> {
+        let mut objects = self.objects.clone();
+        objects.sort_by_key(|object| object.id());
+        let mut validator_set = self.validator_set.clone();
+        validator_set.sort_by_key(|validator| validator.public_key());
+        Genesis {
+            objects,
+            validator_set,
+            epoch_config: self.epoch_config.clone(),
+            protocol_version: self.protocol_version,
+        }
+        .to_bytes()
+    }
+
+    /// A SHA3-256 hash identifying this genesis, over its canonical encoding.
+    pub fn digest(&self) -> [u8; 32] {
+        self.chain_id().0
+    }
+
+    /// The network started from this genesis.
+    pub fn chain_id(&self) -> ChainId {
+        ChainId::from_genesis_bytes(&self.to_canonical_bytes())
+    }
+}
+
+/// The bytes genesis files start with.
+const GENESIS_FILE_MAGIC: [u8; 8] = *b"SUIGNSIS";
+
+/// The version of the format genesis files are written in, to be bumped whenever the encoding of
+/// [`Genesis`] changes so that older binaries refuse the files they cannot read. Version 2 adds
+/// the compression of the payload, version 3 the epoch parameters, version 4 the protocol
+/// version.
+pub const GENESIS_FILE_FORMAT_VERSION: u32 = 4;
+
+/// How the encoding of genesis is compressed in a genesis file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenesisCompression {
+    None,
+    Zstd,
+}
+
+impl GenesisCompression {
+    fn compress(&self, bytes: Vec<u8>) -> std::io::Result<Vec<u8>> {
+        match self {
+            GenesisCompression::None => Ok(bytes),
+            GenesisCompression::Zstd => zstd::stream::encode_all(&bytes[..], ZSTD_LEVEL),
+        }
+    }
+
+    fn decompress(&self, bytes: Vec<u8>) -> std::io::Result<Vec<u8>> {
+        match self {
+            GenesisCompression::None => Ok(bytes),
+            GenesisCompression::Zstd => zstd::stream::decode_all(&bytes[..]),
+        }
+    }
+}
+
+// Genesis is compressed once and shipped many times, most of it is the bytecode of packages.
+const ZSTD_LEVEL: i32 = 19;
+
+/// The envelope genesis is saved to disk in.
+#[derive(Serialize, Deserialize)]
+struct GenesisFile {
+    magic: [u8; 8],
+    version: u32,
+    compression: GenesisCompression,
+    payload: Vec<u8>,
+    /// The SHA3-256 digest of `payload`.
+    checksum: [u8; 32],
+}
+
+/// The envelope of the first version of the format of genesis files.
+#[derive(Deserialize)]
+struct GenesisFileV1 {
+    magic: [u8; 8],
+    version: u32,
+    payload: Vec<u8>,
+    checksum: [u8; 32],
 }
 
 impl Serialize for Genesis {
@@ -151,11 +348,15 @@ impl Serialize for Genesis {
         struct RawGeneis<'a> {
             objects: &'a [Object],
             validator_set: &'a [ValidatorInfo],
+            epoch_config: RawEpochConfig,
+            protocol_version: u32,
         }
 
         let raw_genesis = RawGeneis {
             objects: &self.objects,
             validator_set: &self.validator_set,
+            epoch_config: RawEpochConfig::from(&self.epoch_config),
+            protocol_version: self.protocol_version,
         };
 
         let bytes = bcs::to_bytes(&raw_genesis).map_err(|e| Error::custom(e.to_string()))?;
@@ -180,6 +381,15 @@ impl<'de> Deserialize<'de> for Genesis {
         struct RawGeneis {
             objects: Vec<Object>,
             validator_set: Vec<ValidatorInfo>,
+            epoch_config: RawEpochConfig,
+            protocol_version: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct RawGenesisWithoutProtocolVersion {
+            objects: Vec<Object>,
+            validator_set: Vec<ValidatorInfo>,
+            epoch_config: RawEpochConfig,
         }
 
         let bytes = if deserializer.is_human_readable() {
@@ -190,19 +400,180 @@ impl<'de> Deserialize<'de> for Genesis {
             data
         };
 
-        let raw_genesis: RawGeneis =
-            bcs::from_bytes(&bytes).map_err(|e| Error::custom(e.to_string()))?;
+        // Genesis written before it had a protocol version is the one of networks running the
+        // first version, and the one written before it had epoch parameters has the defaults
+        let raw_genesis: RawGeneis = match bcs::from_bytes(&bytes) {
+            Ok(raw_genesis) => raw_genesis,
+            Err(e) => match bcs::from_bytes::<RawGenesisWithoutProtocolVersion>(&bytes) {
+                Ok(raw_genesis) => RawGeneis {
+                    objects: raw_genesis.objects,
+                    validator_set: raw_genesis.validator_set,
+                    epoch_config: raw_genesis.epoch_config,
+                    protocol_version: MIN_PROTOCOL_VERSION,
+                },
+                Err(_) => match decode_genesis_without_epoch_config(&bytes) {
+                    Some((objects, validator_set)) => RawGeneis {
+                        objects,
+                        validator_set,
+                        epoch_config: RawEpochConfig::default(),
+                        protocol_version: MIN_PROTOCOL_VERSION,
+                    },
+                    None => return Err(Error::custom(e.to_string())),
+                },
+            },
+        };
 
         Ok(Genesis {
             objects: raw_genesis.objects,
             validator_set: raw_genesis.validator_set,
+            epoch_config: raw_genesis.epoch_config.into(),
+            protocol_version: raw_genesis.protocol_version,
         })
     }
 }
 
+/// The [`EpochConfig`] of genesis, whose encoding keeps the fields that are not set.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct RawEpochConfig {
+    checkpoints_per_epoch: Option<u64>,
+    epoch_duration_secs: Option<u64>,
+}
+
+impl From<&EpochConfig> for RawEpochConfig {
+    fn from(config: &EpochConfig) -> Self {
+        Self {
+            checkpoints_per_epoch: config.checkpoints_per_epoch,
+            epoch_duration_secs: config.epoch_duration_secs,
+        }
+    }
+}
+
+impl From<RawEpochConfig> for EpochConfig {
+    fn from(config: RawEpochConfig) -> Self {
+        Self {
+            checkpoints_per_epoch: config.checkpoints_per_epoch,
+            epoch_duration_secs: config.epoch_duration_secs,
+        }
+    }
+}
+
+/// The objects and validators of genesis encoded before it had epoch parameters, including the
+/// encodings of before validators had network and account keys.
+fn decode_genesis_without_epoch_config(bytes: &[u8]) -> Option<(Vec<Object>, Vec<ValidatorInfo>)> {
+    #[derive(Deserialize)]
+    struct RawGenesisWithoutEpochConfig {
+        objects: Vec<Object>,
+        validator_set: Vec<ValidatorInfo>,
+    }
+
+    if let Ok(raw_genesis) = bcs::from_bytes::<RawGenesisWithoutEpochConfig>(bytes) {
+        return Some((raw_genesis.objects, raw_genesis.validator_set));
+    }
+    let legacy = bcs::from_bytes::<LegacyRawGenesis>(bytes).ok()?;
+    let validator_set = legacy
+        .validator_set
+        .into_iter()
+        .map(ValidatorInfo::from)
+        .collect();
+    Some((legacy.objects, validator_set))
+}
+
+/// The encoding of genesis before validators had network and account keys.
+#[derive(Serialize, Deserialize)]
+struct LegacyRawGenesis {
+    objects: Vec<Object>,
+    validator_set: Vec<LegacyValidatorInfo>,
+}
+
+/// A [`ValidatorInfo`] before validators had network and account keys, which are their protocol
+/// key once migrated.
+#[derive(Serialize, Deserialize)]
+struct LegacyValidatorInfo {
+    name: String,
+    public_key: PublicKeyBytes,
+    stake: StakeUnit,
+    delegation: StakeUnit,
+    network_address: Multiaddr,
+    narwhal_primary_to_primary: Multiaddr,
+    narwhal_worker_to_primary: Multiaddr,
+    narwhal_primary_to_worker: Multiaddr,
+    narwhal_worker_to_worker: Multiaddr,
+    narwhal_consensus_address: Multiaddr,
+}
+
+impl From<LegacyValidatorInfo> for ValidatorInfo {
+    fn from(legacy: LegacyValidatorInfo) -> Self {
+        Self {
+            name: legacy.name,
+            public_key: legacy.public_key,
+            network_key: None,
+            worker_key: None,
+            account_key: None,
+            stake: legacy.stake,
+            delegation: legacy.delegation,
+            network_address: legacy.network_address,
+            narwhal_primary_to_primary: legacy.narwhal_primary_to_primary,
+            narwhal_worker_to_primary: legacy.narwhal_worker_to_primary,
+            narwhal_primary_to_worker: legacy.narwhal_primary_to_worker,
+            narwhal_worker_to_worker: legacy.narwhal_worker_to_worker,
+            narwhal_consensus_address: legacy.narwhal_consensus_address,
+        }
+    }
+}
+
+/// Why genesis cannot be built from the inputs of a [`Builder`].
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GenesisError {
+    #[error("Failed to load genesis package {package}: {error}")]
+    ModuleLoad { package: ObjectID, error: String },
+    #[error("Failed to build the Move package at {}: {error}", path.display())]
+    PackageBuild { path: PathBuf, error: String },
+    #[error("Genesis module {module} fails verification: {error}")]
+    ModuleVerification { module: ModuleId, error: String },
+    #[error("Genesis module {module} depends on {dependency}, which is not published before it")]
+    UnlinkedDependency {
+        module: ModuleId,
+        dependency: ModuleId,
+    },
+    #[error("Object {0} is added to genesis more than once")]
+    DuplicateObjectId(ObjectID),
+    #[error("Invalid genesis validator {name}: {reason}")]
+    InvalidValidator { name: String, reason: String },
+    #[error("Invalid genesis object of type {type_}: {reason}")]
+    InvalidObject { type_: StructTag, reason: String },
+    #[error("Failed to create the system state object: {0}")]
+    SystemObject(String),
+    #[error("Genesis cannot start at protocol version {0}, which this binary does not support")]
+    UnsupportedProtocolVersion(u32),
+}
+
+/// Builds the genesis of a network. The Move stdlib and the Sui framework it publishes are the
+/// modules the build script of `sui-framework` embeds in the binary, so building genesis needs no
+/// source paths, and the framework of genesis is the one the natives of the binary match.
 pub struct Builder {
     objects: BTreeMap<ObjectID, Object>,
+    /// The IDs of the objects different objects were added with.
+    duplicate_objects: BTreeSet<ObjectID>,
+    /// The values of the gas coins each account is funded with at genesis.
+    accounts: BTreeMap<SuiAddress, Vec<u64>>,
+    /// The Move objects created at genesis, which are given their ID and version then.
+    move_objects: Vec<GenesisMoveObject>,
+    /// The directories of the Move packages published at genesis after the framework.
+    packages: Vec<PathBuf>,
+    /// The modules of the Move packages loaded with a saved builder, published like `packages`.
+    compiled_packages: Vec<Vec<CompiledModule>>,
     validators: BTreeMap<PublicKeyBytes, ValidatorInfo>,
+    epoch_config: EpochConfig,
+    protocol_version: u32,
+}
+
+/// A Move object of a struct type published at genesis, with the BCS encoding of its fields after
+/// its `info`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct GenesisMoveObject {
+    owner: Owner,
+    type_: StructTag,
+    fields: Vec<u8>,
 }
 
 impl Default for Builder {
@@ -215,75 +586,238 @@ impl Builder {
     pub fn new() -> Self {
         Self {
             objects: Default::default(),
+            duplicate_objects: Default::default(),
+            accounts: Default::default(),
+            move_objects: Default::default(),
+            packages: Default::default(),
+            compiled_packages: Default::default(),
             validators: Default::default(),
+            epoch_config: Default::default(),
+            protocol_version: PROTOCOL_VERSION,
+        }
+    }
+
+    fn insert_object(&mut self, object: Object) {
+        let id = object.id();
+        if self
+            .objects
+            .get(&id)
+            .map_or(false, |added| added != &object)
+        {
+            self.duplicate_objects.insert(id);
         }
+        self.objects.insert(id, object);
+    }
+
+    /// The builder of the genesis `config` describes, the same each time it is called with it.
+    /// The public entries of the validators and the addresses of the accounts must be given, as
+    /// keys generated here would not be known to anyone, and gas objects without an ID get one
+    /// derived from the genesis transaction.
+    pub fn from_config(config: &GenesisConfig) -> Result<Self> {
+        if config.validator_genesis_info.is_some() {
+            bail!(
+                "The genesis config must not hold the key pairs of its validators, \
+                 list their public entries in `validators` instead"
+            );
+        }
+        if config.validators.is_empty() {
+            bail!("The genesis config must list its validators");
+        }
+        if config.committee_size != config.validators.len() {
+            bail!(
+                "The committee size of the genesis config is {}, but it lists {} validators",
+                config.committee_size,
+                config.validators.len()
+            );
+        }
+        if config
+            .accounts
+            .iter()
+            .any(|account| account.address.is_none())
+        {
+            bail!("The accounts of the genesis config must all have an address");
+        }
+        // No key is generated, the accounts all have an address
+        let (_keys, objects, accounts) = config.generate_accounts(rand::rngs::OsRng)?;
+
+        let mut builder = Self::new().add_objects(objects);
+        for (address, gas_budgets) in accounts {
+            builder = builder.add_account(address, gas_budgets);
+        }
+        for validator in &config.validators {
+            builder = builder.add_validator(validator.clone());
+        }
+        for path in &config.packages {
+            builder = builder.add_move_package(path.clone());
+        }
+        if let Some(epoch_config) = &config.epoch_config {
+            builder = builder.set_epoch_config(epoch_config.clone());
+        }
+        if let Some(protocol_version) = config.protocol_version {
+            builder = builder.set_protocol_version(protocol_version);
+        }
+        Ok(builder)
     }
 
     pub fn add_object(mut self, object: Object) -> Self {
-        self.objects.insert(object.id(), object);
+        self.insert_object(object);
         self
     }
 
     pub fn add_objects(mut self, objects: Vec<Object>) -> Self {
         for object in objects {
-            self.objects.insert(object.id(), object);
+            self.insert_object(object);
         }
         self
     }
 
+    /// Fund `address` with a gas coin of each of the values of `gas_budgets`. The coins are
+    /// created when building genesis, with IDs derived from the genesis transaction.
+    pub fn add_account(mut self, address: SuiAddress, gas_budgets: Vec<u64>) -> Self {
+        self.accounts
+            .entry(address)
+            .or_default()
+            .extend(gas_budgets);
+        self
+    }
+
+    /// Fund `owner` with a gas coin of `balance`, see [`Builder::add_account`].
+    pub fn add_coin(self, owner: SuiAddress, balance: u64) -> Self {
+        self.add_account(owner, vec![balance])
+    }
+
+    /// Create an object of type `type_` owned by `owner` at genesis, `fields` being the BCS
+    /// encoding of the fields of the struct after its `info`. The object is given an ID derived
+    /// from the genesis transaction and the initial version when building genesis, which fails
+    /// unless the struct has the `key` ability and `fields` match its layout.
+    /// Only structs of the packages published at their own address, like the framework, can be
+    /// used: packages added with [`Builder::add_move_package`] are only given their ID when they
+    /// are published, after the objects are created.
+    pub fn add_object_owned_by(
+        mut self,
+        owner: SuiAddress,
+        type_: StructTag,
+        fields: Vec<u8>,
+    ) -> Self {
+        self.move_objects.push(GenesisMoveObject {
+            owner: Owner::AddressOwner(owner),
+            type_,
+            fields,
+        });
+        self
+    }
+
+    /// Create an object shared from genesis, see [`Builder::add_object_owned_by`].
+    pub fn add_shared_object(mut self, type_: StructTag, fields: Vec<u8>) -> Self {
+        self.move_objects.push(GenesisMoveObject {
+            owner: Owner::Shared,
+            type_,
+            fields,
+        });
+        self
+    }
+
+    /// Publish the Move package at `path` at genesis. It is compiled and verified when building
+    /// genesis. Saving the builder compiles it too, to save its modules rather than its path,
+    /// which is local to this machine.
+    pub fn add_move_package(mut self, path: PathBuf) -> Self {
+        self.packages.push(path);
+        self
+    }
+
     pub fn add_validator(mut self, validator: ValidatorInfo) -> Self {
         self.validators.insert(validator.public_key(), validator);
         self
     }
 
-    pub fn build(self) -> Genesis {
+    /// When the epochs of the network end, after the default count of checkpoints if not set.
+    pub fn set_epoch_config(mut self, epoch_config: EpochConfig) -> Self {
+        self.epoch_config = epoch_config;
+        self
+    }
+
+    /// The protocol version the network starts at, the latest one this binary supports if not
+    /// set.
+    pub fn set_protocol_version(mut self, protocol_version: u32) -> Self {
+        self.protocol_version = protocol_version;
+        self
+    }
+
+    /// Check the inputs of genesis before building it.
+    fn validate(&self) -> Result<(), GenesisError> {
+        if let Some(id) = self.duplicate_objects.iter().next() {
+            return Err(GenesisError::DuplicateObjectId(*id));
+        }
+        if ProtocolConfig::get_for_version(self.protocol_version).is_err() {
+            return Err(GenesisError::UnsupportedProtocolVersion(
+                self.protocol_version,
+            ));
+        }
+        // The objects the framework creates at genesis
+        let reserved_ids = [
+            ObjectID::from(MOVE_STDLIB_ADDRESS),
+            ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+            sui_types::SUI_SYSTEM_STATE_OBJECT_ID,
+        ];
+        if let Some(id) = reserved_ids.iter().find(|id| self.objects.contains_key(id)) {
+            return Err(GenesisError::DuplicateObjectId(*id));
+        }
+
+        for validator in self.validators.values() {
+            let invalid = |reason: &str| GenesisError::InvalidValidator {
+                name: validator.name().to_owned(),
+                reason: reason.to_owned(),
+            };
+            if PublicKey::try_from(validator.public_key()).is_err() {
+                return Err(invalid("invalid public key"));
+            }
+            if validator.delegation() != 0 {
+                return Err(invalid("validators have no delegations at genesis"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<Genesis, GenesisError> {
+        self.validate()?;
         let mut genesis_ctx = sui_adapter::genesis::get_genesis_context();
 
         // Get Move and Sui Framework
-        let modules = [
+        let mut modules = vec![
             sui_framework::get_move_stdlib(),
             sui_framework::get_sui_framework(),
         ];
-
-        let objects = self.objects.into_iter().map(|(_, o)| o).collect::<Vec<_>>();
+        let mut packages = self
+            .packages
+            .iter()
+            .map(|path| build_package(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        packages.extend(self.compiled_packages);
+        modules.extend(sort_packages(packages));
+        verify_packages(&modules)?;
+
+        let mut objects = self.objects.into_iter().map(|(_, o)| o).collect::<Vec<_>>();
+        objects.extend(create_gas_coins(&mut genesis_ctx, &self.accounts));
+        objects.extend(create_move_objects(
+            &mut genesis_ctx,
+            &modules,
+            self.move_objects,
+        )?);
         let validators = self
             .validators
             .into_iter()
             .map(|(_, v)| v)
             .collect::<Vec<_>>();
-        let objects = create_genesis_objects(&mut genesis_ctx, &modules, &objects, &validators);
+        let objects = create_genesis_objects(&mut genesis_ctx, &modules, &objects, &validators)?;
 
         let genesis = Genesis {
             objects,
             validator_set: validators,
+            epoch_config: self.epoch_config,
+            protocol_version: self.protocol_version,
         };
-
-        // Verify that all the validators were properly created onchain
-        let system_object = genesis.sui_system_object();
-        assert_eq!(system_object.epoch, 0);
-
-        for (validator, onchain_validator) in genesis
-            .validator_set()
-            .iter()
-            .zip(system_object.validators.active_validators.iter())
-        {
-            assert_eq!(validator.stake(), onchain_validator.stake_amount);
-            assert_eq!(
-                validator.sui_address().to_vec(),
-                onchain_validator.metadata.sui_address.to_vec(),
-            );
-            assert_eq!(
-                validator.public_key().as_ref().to_vec(),
-                onchain_validator.metadata.pubkey_bytes,
-            );
-            assert_eq!(validator.name().as_bytes(), onchain_validator.metadata.name);
-            assert_eq!(
-                validator.network_address().to_vec(),
-                onchain_validator.metadata.net_address
-            );
-        }
-
-        genesis
+        verify_system_object(&genesis)?;
+        Ok(genesis)
     }
 
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, anyhow::Error> {
@@ -295,8 +829,9 @@ impl Builder {
             bail!("path must be a directory");
         }
 
+        let mut builder = Self::new();
+
         // Load Objects
-        let mut objects = BTreeMap::new();
         for entry in path.join(GENESIS_BUILDER_OBJECT_DIR).read_dir_utf8()? {
             let entry = entry?;
             if entry.file_name().starts_with('.') {
@@ -306,11 +841,10 @@ impl Builder {
             let path = entry.path();
             let object_bytes = fs::read(path)?;
             let object: Object = serde_yaml::from_slice(&object_bytes)?;
-            objects.insert(object.id(), object);
+            builder.insert_object(object);
         }
 
         // Load validator infos
-        let mut committee = BTreeMap::new();
         for entry in path.join(GENESIS_BUILDER_COMMITTEE_DIR).read_dir_utf8()? {
             let entry = entry?;
             if entry.file_name().starts_with('.') {
@@ -320,13 +854,80 @@ impl Builder {
             let path = entry.path();
             let validator_info_bytes = fs::read(path)?;
             let validator_info: ValidatorInfo = serde_yaml::from_slice(&validator_info_bytes)?;
-            committee.insert(validator_info.public_key(), validator_info);
+            builder
+                .validators
+                .insert(validator_info.public_key(), validator_info);
         }
 
-        Ok(Self {
-            objects,
-            validators: committee,
-        })
+        // Load accounts, which builders saved before they existed have none of
+        let account_dir = path.join(GENESIS_BUILDER_ACCOUNT_DIR);
+        if account_dir.is_dir() {
+            for entry in account_dir.read_dir_utf8()? {
+                let entry = entry?;
+                if entry.file_name().starts_with('.') {
+                    continue;
+                }
+
+                let address: SuiAddress = decode_bytes_hex(entry.file_name())?;
+                let gas_budgets_bytes = fs::read(entry.path())?;
+                let gas_budgets: Vec<u64> = serde_yaml::from_slice(&gas_budgets_bytes)?;
+                builder.accounts.insert(address, gas_budgets);
+            }
+        }
+
+        // Load Move objects, which builders saved before they existed have none of
+        let move_object_dir = path.join(GENESIS_BUILDER_MOVE_OBJECT_DIR);
+        if move_object_dir.is_dir() {
+            for entry in move_object_dir.read_dir_utf8()? {
+                let entry = entry?;
+                if entry.file_name().starts_with('.') {
+                    continue;
+                }
+
+                let move_object_bytes = fs::read(entry.path())?;
+                let move_object: GenesisMoveObject = serde_yaml::from_slice(&move_object_bytes)?;
+                builder.move_objects.push(move_object);
+            }
+        }
+
+        // Load Move packages, a directory of modules each, which older builders have none of
+        let package_dir = path.join(GENESIS_BUILDER_PACKAGE_DIR);
+        if package_dir.is_dir() {
+            for entry in package_dir.read_dir_utf8()? {
+                let entry = entry?;
+                if entry.file_name().starts_with('.') {
+                    continue;
+                }
+
+                let mut modules = Vec::new();
+                for module_entry in entry.path().read_dir_utf8()? {
+                    let module_entry = module_entry?;
+                    if module_entry.file_name().starts_with('.') {
+                        continue;
+                    }
+                    let module_bytes = fs::read(module_entry.path())?;
+                    let module = CompiledModule::deserialize(&module_bytes).map_err(|e| {
+                        anyhow::anyhow!("Cannot deserialize module {}: {e}", module_entry.path())
+                    })?;
+                    modules.push(module);
+                }
+                builder.compiled_packages.push(modules);
+            }
+        }
+
+        // Load the epoch parameters, which older builders have none of
+        let epoch_config_path = path.join(GENESIS_BUILDER_EPOCH_CONFIG_FILE);
+        if epoch_config_path.is_file() {
+            builder.epoch_config = serde_yaml::from_slice(&fs::read(epoch_config_path)?)?;
+        }
+
+        // Load the protocol version, the latest one for older builders
+        let protocol_version_path = path.join(GENESIS_BUILDER_PROTOCOL_VERSION_FILE);
+        if protocol_version_path.is_file() {
+            builder.protocol_version = serde_yaml::from_slice(&fs::read(protocol_version_path)?)?;
+        }
+
+        Ok(builder)
     }
 
     pub fn save<P: AsRef<Path>>(self, path: P) -> Result<(), anyhow::Error> {
@@ -345,6 +946,48 @@ impl Builder {
             fs::write(object_dir.join(hex_digest), object_bytes)?;
         }
 
+        // Write accounts
+        let account_dir = path.join(GENESIS_BUILDER_ACCOUNT_DIR);
+        std::fs::create_dir_all(&account_dir)?;
+
+        for (address, gas_budgets) in self.accounts {
+            let gas_budgets_bytes = serde_yaml::to_vec(&gas_budgets)?;
+            fs::write(
+                account_dir.join(encode_bytes_hex(&address)),
+                gas_budgets_bytes,
+            )?;
+        }
+
+        // Write Move objects, numbered as the same object can be added more than once
+        let move_object_dir = path.join(GENESIS_BUILDER_MOVE_OBJECT_DIR);
+        std::fs::create_dir_all(&move_object_dir)?;
+
+        for (i, move_object) in self.move_objects.iter().enumerate() {
+            let move_object_bytes = serde_yaml::to_vec(move_object)?;
+            fs::write(move_object_dir.join(i.to_string()), move_object_bytes)?;
+        }
+
+        // Write Move packages, compiled as their paths only exist on this machine
+        let package_dir = path.join(GENESIS_BUILDER_PACKAGE_DIR);
+        std::fs::create_dir_all(&package_dir)?;
+
+        let mut packages = self
+            .packages
+            .iter()
+            .map(|path| build_package(path))
+            .collect::<Result<Vec<_>, _>>()?;
+        packages.extend(self.compiled_packages);
+        for (i, modules) in packages.iter().enumerate() {
+            let modules_dir = package_dir.join(i.to_string());
+            std::fs::create_dir_all(&modules_dir)?;
+            for module in modules {
+                let mut module_bytes = Vec::new();
+                module.serialize(&mut module_bytes)?;
+                let file_name = format!("{}.mv", module.self_id().name());
+                fs::write(modules_dir.join(file_name), module_bytes)?;
+            }
+        }
+
         // Write validator infos
         let committee_dir = path.join(GENESIS_BUILDER_COMMITTEE_DIR);
         std::fs::create_dir_all(&committee_dir)?;
@@ -355,50 +998,354 @@ impl Builder {
             fs::write(committee_dir.join(hex_name), validator_info_bytes)?;
         }
 
+        // Write the epoch parameters
+        let epoch_config_bytes = serde_yaml::to_vec(&self.epoch_config)?;
+        fs::write(
+            path.join(GENESIS_BUILDER_EPOCH_CONFIG_FILE),
+            epoch_config_bytes,
+        )?;
+
+        // Write the protocol version
+        let protocol_version_bytes = serde_yaml::to_vec(&self.protocol_version)?;
+        fs::write(
+            path.join(GENESIS_BUILDER_PROTOCOL_VERSION_FILE),
+            protocol_version_bytes,
+        )?;
+
         Ok(())
     }
 }
 
+fn build_package(path: &Path) -> Result<Vec<CompiledModule>, GenesisError> {
+    let build_error = |error: SuiError| GenesisError::PackageBuild {
+        path: path.to_owned(),
+        error: error.to_string(),
+    };
+    sui_framework::build_move_package(path, BuildConfig::default()).map_err(build_error)
+}
+
+/// Order `packages` so that each one is published after the packages it depends on. Packages
+/// that do not depend on each other are ordered by their modules, so that genesis does not
+/// depend on the order they were added in nor on where they are on disk. Packages in a
+/// dependency cycle are kept in that order, for [`verify_packages`] to reject them.
+fn sort_packages(mut packages: Vec<Vec<CompiledModule>>) -> Vec<Vec<CompiledModule>> {
+    for package in &mut packages {
+        package.sort_by_key(|module| module.self_id());
+    }
+    packages.sort_by_cached_key(|package| {
+        package
+            .iter()
+            .map(|module| module.self_id())
+            .collect::<Vec<_>>()
+    });
+
+    let package_of: BTreeMap<ModuleId, usize> = packages
+        .iter()
+        .enumerate()
+        .flat_map(|(i, package)| package.iter().map(move |module| (module.self_id(), i)))
+        .collect();
+    let dependencies: Vec<BTreeSet<usize>> = packages
+        .iter()
+        .enumerate()
+        .map(|(i, package)| {
+            package
+                .iter()
+                .flat_map(|module| module.immediate_dependencies())
+                .filter_map(|id| package_of.get(&id).copied())
+                .filter(|dependency| *dependency != i)
+                .collect()
+        })
+        .collect();
+
+    let mut published = vec![false; packages.len()];
+    let mut order = Vec::with_capacity(packages.len());
+    for _ in 0..packages.len() {
+        let next = (0..packages.len())
+            .find(|i| !published[*i] && dependencies[*i].iter().all(|j| published[*j]))
+            .or_else(|| published.iter().position(|done| !done));
+        if let Some(next) = next {
+            published[next] = true;
+            order.push(next);
+        }
+    }
+
+    let mut packages: Vec<_> = packages.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|i| packages[i].take())
+        .collect()
+}
+
+/// Run the Move and Sui bytecode verifiers on each module of `packages`, and check that the
+/// modules they depend on are published before them.
+fn verify_packages(packages: &[Vec<CompiledModule>]) -> Result<(), GenesisError> {
+    let mut published = BTreeSet::new();
+    for modules in packages {
+        for module in modules {
+            sui_framework::verify_modules(std::slice::from_ref(module)).map_err(|e| {
+                GenesisError::ModuleVerification {
+                    module: module.self_id(),
+                    error: e.to_string(),
+                }
+            })?;
+        }
+        let self_ids: BTreeSet<_> = modules.iter().map(|m| m.self_id()).collect();
+        for module in modules {
+            if let Some(dependency) = module
+                .immediate_dependencies()
+                .into_iter()
+                .find(|id| !self_ids.contains(id) && !published.contains(id))
+            {
+                return Err(GenesisError::UnlinkedDependency {
+                    module: module.self_id(),
+                    dependency,
+                });
+            }
+        }
+        // Packages at 0x0 are published at fresh IDs, which others cannot depend on
+        published.extend(
+            modules
+                .iter()
+                .map(|module| module.self_id())
+                .filter(|id| *id.address() != AccountAddress::ZERO),
+        );
+    }
+    Ok(())
+}
+
+fn create_gas_coins(
+    genesis_ctx: &mut TxContext,
+    accounts: &BTreeMap<SuiAddress, Vec<u64>>,
+) -> Vec<Object> {
+    let mut coins = Vec::new();
+    for (address, gas_budgets) in accounts {
+        // In the same order whatever order the budgets of the account were added in
+        let mut gas_budgets = gas_budgets.clone();
+        gas_budgets.sort_unstable();
+        for gas_budget in gas_budgets {
+            let id = genesis_ctx.fresh_id();
+            let contents = GasCoin::new(id, SequenceNumber::new(), gas_budget).to_bcs_bytes();
+            coins.push(Object::new_move(
+                MoveObject::new_gas_coin(contents),
+                Owner::AddressOwner(*address),
+                genesis_ctx.digest(),
+            ));
+        }
+    }
+    coins
+}
+
+fn create_move_objects(
+    genesis_ctx: &mut TxContext,
+    modules: &[Vec<CompiledModule>],
+    mut move_objects: Vec<GenesisMoveObject>,
+) -> Result<Vec<Object>, GenesisError> {
+    // In the same order whatever order the objects were added in
+    move_objects.sort();
+    let mut objects = Vec::new();
+    for GenesisMoveObject {
+        owner,
+        type_,
+        fields,
+    } in move_objects
+    {
+        let has_public_transfer = struct_has_public_transfer(modules, &type_)?;
+        let id = genesis_ctx.fresh_id();
+        let mut contents = id.as_ref().to_vec();
+        contents.extend(SequenceNumber::new().value().to_le_bytes());
+        contents.extend(fields);
+        check_layout(modules, &type_, &contents)?;
+        // Safe as whether the object has public transfer is read from the abilities of its type,
+        // and its contents were checked against the layout of the type
+        let object =
+            unsafe { MoveObject::new_from_execution(type_, has_public_transfer, contents) };
+        objects.push(Object::new_move(object, owner, genesis_ctx.digest()));
+    }
+    Ok(objects)
+}
+
+/// Whether objects of `type_` can be transferred by anyone, checking that `type_` is a struct of
+/// `modules` which objects can be of.
+fn struct_has_public_transfer(
+    modules: &[Vec<CompiledModule>],
+    type_: &StructTag,
+) -> Result<bool, GenesisError> {
+    let invalid = |reason: &str| GenesisError::InvalidObject {
+        type_: type_.clone(),
+        reason: reason.to_owned(),
+    };
+    let module_id = ModuleId::new(type_.address, type_.module.clone());
+    let module = modules
+        .iter()
+        .flatten()
+        .find(|module| module.self_id() == module_id)
+        .ok_or_else(|| invalid("its module is not published at genesis"))?;
+    if module.self_id().address() == &AccountAddress::ZERO {
+        return Err(invalid(
+            "its package is built from source, and is only given an ID when it is published",
+        ));
+    }
+    let handle = module
+        .struct_defs()
+        .iter()
+        .map(|def| module.struct_handle_at(def.struct_handle))
+        .find(|handle| module.identifier_at(handle.name) == type_.name.as_ident_str())
+        .ok_or_else(|| invalid("its module defines no such struct"))?;
+    if !handle.abilities.has_key() {
+        return Err(invalid("the struct does not have the key ability"));
+    }
+    if handle.type_parameters.len() != type_.type_params.len() {
+        return Err(invalid("wrong number of type arguments"));
+    }
+    Ok(handle.abilities.has_store())
+}
+
+/// Check that `contents` are a value of `type_`, as laid out by the modules of genesis.
+fn check_layout(
+    modules: &[Vec<CompiledModule>],
+    type_: &StructTag,
+    contents: &[u8],
+) -> Result<(), GenesisError> {
+    let invalid = |reason: String| GenesisError::InvalidObject {
+        type_: type_.clone(),
+        reason,
+    };
+    let layout = MoveObject::get_layout_from_struct_tag(
+        type_.clone(),
+        ObjectFormatOptions::default(),
+        &GenesisModules(modules),
+    )
+    .map_err(|e| invalid(e.to_string()))?;
+    MoveStruct::simple_deserialize(contents, &layout).map_err(|e| {
+        invalid(format!(
+            "its fields do not match the layout of the struct: {e}"
+        ))
+    })?;
+    Ok(())
+}
+
+/// The modules published at genesis, to resolve the layouts of the objects created at genesis.
+struct GenesisModules<'a>(&'a [Vec<CompiledModule>]);
+
+impl<'a> GetModule for GenesisModules<'a> {
+    type Error = anyhow::Error;
+    type Item = &'a CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self
+            .0
+            .iter()
+            .flatten()
+            .find(|module| &module.self_id() == id))
+    }
+}
+
 fn create_genesis_objects(
     genesis_ctx: &mut TxContext,
     modules: &[Vec<CompiledModule>],
     input_objects: &[Object],
     validators: &[ValidatorInfo],
-) -> Vec<Object> {
+) -> Result<Vec<Object>, GenesisError> {
     let mut store = InMemoryStorage::new(Vec::new());
 
-    let native_functions =
-        sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+    // Genesis is the same whatever version the network starts at, it is created by the first one
+    let protocol_config = ProtocolConfig::get_for_version(MIN_PROTOCOL_VERSION)
+        .expect("The first version is supported");
+    let native_functions = sui_framework::natives::natives_for_protocol(
+        MOVE_STDLIB_ADDRESS,
+        SUI_FRAMEWORK_ADDRESS,
+        &protocol_config,
+    );
     let move_vm = adapter::new_move_vm(native_functions.clone())
         .expect("We defined natives to not fail here");
 
     for modules in modules {
+        let mut modules = modules.to_owned();
+        // Unlike the framework, packages built from source are published at a fresh ID
+        let package = if *modules[0].self_id().address() == AccountAddress::ZERO {
+            adapter::generate_package_id(&mut modules, genesis_ctx).map_err(|e| {
+                GenesisError::ModuleLoad {
+                    package: ObjectID::ZERO,
+                    error: e.to_string(),
+                }
+            })?
+        } else {
+            ObjectID::from(*modules[0].self_id().address())
+        };
         process_package(
             &mut store,
             &native_functions,
+            &protocol_config,
             genesis_ctx,
-            modules.to_owned(),
+            modules,
         )
-        .unwrap();
+        .map_err(|e| GenesisError::ModuleLoad {
+            package,
+            error: e.to_string(),
+        })?;
     }
 
     for object in input_objects {
         store.insert_object(object.to_owned());
     }
 
-    generate_genesis_system_object(&mut store, &move_vm, validators, genesis_ctx).unwrap();
+    generate_genesis_system_object(
+        &mut store,
+        &move_vm,
+        validators,
+        genesis_ctx,
+        &protocol_config,
+    )
+    .map_err(|e| GenesisError::SystemObject(e.to_string()))?;
 
-    store
+    Ok(store
         .into_inner()
         .into_iter()
         .map(|(_id, object)| object)
-        .collect()
+        .collect())
+}
+
+/// Check that all the validators of `genesis` were properly created on chain.
+fn verify_system_object(genesis: &Genesis) -> Result<(), GenesisError> {
+    let system_object = genesis.sui_system_object();
+    if system_object.epoch != 0 {
+        return Err(GenesisError::SystemObject(format!(
+            "created at epoch {}",
+            system_object.epoch
+        )));
+    }
+    let onchain_validators = &system_object.validators.active_validators;
+    if genesis.validator_set().len() != onchain_validators.len() {
+        return Err(GenesisError::SystemObject(format!(
+            "created with {} validators instead of {}",
+            onchain_validators.len(),
+            genesis.validator_set().len()
+        )));
+    }
+
+    for (validator, onchain_validator) in genesis.validator_set().iter().zip(onchain_validators) {
+        let metadata = &onchain_validator.metadata;
+        if validator.stake() != onchain_validator.stake_amount
+            || validator.delegation() != onchain_validator.delegation
+            || validator.sui_address().to_vec() != metadata.sui_address.to_vec()
+            || validator.public_key().as_ref().to_vec() != metadata.pubkey_bytes
+            || validator.name().as_bytes() != metadata.name
+            || validator.network_address().to_vec() != metadata.net_address
+        {
+            return Err(GenesisError::SystemObject(format!(
+                "validator {} differs on chain",
+                validator.name()
+            )));
+        }
+    }
+    Ok(())
 }
 
 fn process_package(
     store: &mut InMemoryStorage,
     // mv: &MoveVM,
     native_functions: &NativeFunctionTable,
+    protocol_config: &ProtocolConfig,
     ctx: &mut TxContext,
     modules: Vec<CompiledModule>,
 ) -> Result<()> {
@@ -443,6 +1390,7 @@ fn process_package(
         package_id,
         natives,
         &mut gas_status,
+        protocol_config,
     )?;
     adapter::store_package_and_init_modules(
         &mut temporary_store,
@@ -464,6 +1412,7 @@ pub fn generate_genesis_system_object(
     move_vm: &MoveVM,
     committee: &[ValidatorInfo],
     genesis_ctx: &mut TxContext,
+    protocol_config: &ProtocolConfig,
 ) -> Result<()> {
     let genesis_digest = genesis_ctx.digest();
     let mut temporary_store =
@@ -498,6 +1447,7 @@ pub fn generate_genesis_system_object(
         ],
         &mut SuiGasStatus::new_unmetered(),
         genesis_ctx,
+        protocol_config,
     )?;
 
     let (_objects, _mutable_inputs, written, deleted, _events) = temporary_store.into_inner();
@@ -509,29 +1459,213 @@ pub fn generate_genesis_system_object(
 
 const GENESIS_BUILDER_OBJECT_DIR: &str = "objects";
 const GENESIS_BUILDER_COMMITTEE_DIR: &str = "committee";
+const GENESIS_BUILDER_ACCOUNT_DIR: &str = "accounts";
+const GENESIS_BUILDER_MOVE_OBJECT_DIR: &str = "move-objects";
+const GENESIS_BUILDER_PACKAGE_DIR: &str = "packages";
+const GENESIS_BUILDER_EPOCH_CONFIG_FILE: &str = "epoch-config.yaml";
+const GENESIS_BUILDER_PROTOCOL_VERSION_FILE: &str = "protocol-version.yaml";
 
 #[cfg(test)]
 mod test {
-    use super::Builder;
-    use crate::{genesis_config::GenesisConfig, utils, ValidatorInfo};
+    use super::{
+        sort_packages, verify_packages, Builder, Genesis, GenesisCompression, GenesisError,
+        LegacyRawGenesis, LegacyValidatorInfo, RawEpochConfig, GENESIS_FILE_MAGIC,
+    };
+    use crate::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
+    use crate::{utils, EpochConfig, ValidatorInfo};
+    use move_binary_format::access::ModuleAccess;
+    use move_binary_format::file_format::{
+        empty_module, AddressIdentifierIndex, IdentifierIndex, ModuleHandle,
+    };
+    use move_binary_format::CompiledModule;
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::ident_str;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::StructTag;
     use narwhal_crypto::traits::KeyPair;
+    use sha3::{Digest, Sha3_256};
+    use std::path::PathBuf;
+    use sui_types::base_types::{dbg_addr, ObjectID, SequenceNumber};
+    use sui_types::committee::StakeUnit;
     use sui_types::crypto::get_key_pair_from_rng;
+    use sui_types::gas_coin::GasCoin;
+    use sui_types::handshake::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+    use sui_types::object::{Object, Owner};
+    use sui_types::{MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID};
 
     #[test]
     fn roundtrip() {
-        let genesis = Builder::new().build();
+        let genesis = Builder::new().build().unwrap();
 
         let s = serde_yaml::to_string(&genesis).unwrap();
         let from_s = serde_yaml::from_str(&s).unwrap();
         assert_eq!(genesis, from_s);
     }
 
+    #[test]
+    fn file_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("genesis.blob");
+        let genesis = Builder::new().build().unwrap();
+        genesis.save(&path).unwrap();
+        assert_eq!(Genesis::load(&path).unwrap(), genesis);
+
+        // Files of before the envelope are still read
+        std::fs::write(&path, genesis.to_bytes()).unwrap();
+        assert_eq!(Genesis::load(&path).unwrap(), genesis);
+
+        let bytes = genesis.to_file_bytes();
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() ^= 1;
+        assert!(Genesis::from_file_bytes(&corrupted).is_err());
+
+        let mut unknown_version = bytes.clone();
+        unknown_version[8] += 1;
+        assert!(Genesis::from_file_bytes(&unknown_version).is_err());
+
+        // Files of the first version, which did not compress, are still read
+        let payload = genesis.to_bytes();
+        let checksum: [u8; 32] = Sha3_256::digest(&payload).into();
+        let first_version = bcs::to_bytes(&(GENESIS_FILE_MAGIC, 1u32, payload, checksum)).unwrap();
+        assert_eq!(Genesis::from_file_bytes(&first_version).unwrap(), genesis);
+
+        genesis
+            .save_with_compression(&path, GenesisCompression::Zstd)
+            .unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < bytes.len() as u64);
+        assert_eq!(Genesis::load(&path).unwrap(), genesis);
+    }
+
+    #[test]
+    fn legacy_validator_info() {
+        let validators: Vec<_> = (0..2).map(|i| validator(&format!("{i}"), 1)).collect();
+        let genesis = validators
+            .iter()
+            .cloned()
+            .fold(
+                Builder::new().set_protocol_version(MIN_PROTOCOL_VERSION),
+                Builder::add_validator,
+            )
+            .build()
+            .unwrap();
+
+        // Genesis written before validators had network and account keys, with the layout of
+        // their info then
+        let legacy = LegacyRawGenesis {
+            objects: genesis.objects().to_vec(),
+            validator_set: genesis
+                .validator_set()
+                .iter()
+                .map(|validator| LegacyValidatorInfo {
+                    name: validator.name.clone(),
+                    public_key: validator.public_key,
+                    stake: validator.stake,
+                    delegation: validator.delegation,
+                    network_address: validator.network_address.clone(),
+                    narwhal_primary_to_primary: validator.narwhal_primary_to_primary.clone(),
+                    narwhal_worker_to_primary: validator.narwhal_worker_to_primary.clone(),
+                    narwhal_primary_to_worker: validator.narwhal_primary_to_worker.clone(),
+                    narwhal_worker_to_worker: validator.narwhal_worker_to_worker.clone(),
+                    narwhal_consensus_address: validator.narwhal_consensus_address.clone(),
+                })
+                .collect(),
+        };
+        let legacy_bytes = bcs::to_bytes(&bcs::to_bytes(&legacy).unwrap()).unwrap();
+        assert_ne!(legacy_bytes, genesis.to_bytes());
+
+        // Their network and account keys are their protocol key
+        let loaded = Genesis::from_file_bytes(&legacy_bytes).unwrap();
+        assert_eq!(loaded, genesis);
+        for validator in loaded.validator_set() {
+            assert_eq!(validator.network_key(), validator.public_key());
+            assert_eq!(validator.worker_key(), validator.public_key());
+            assert_eq!(validator.account_key(), validator.public_key());
+        }
+        assert_eq!(loaded.committee().unwrap(), genesis.committee().unwrap());
+    }
+
+    #[test]
+    fn digest() {
+        let genesis = Builder::new().build().unwrap();
+        let mut reordered = genesis.clone();
+        reordered.objects.reverse();
+        assert_ne!(reordered.to_bytes(), genesis.to_bytes());
+        assert_eq!(reordered.digest(), genesis.digest());
+        assert_eq!(reordered.chain_id(), genesis.chain_id());
+
+        let funded = Builder::new()
+            .add_account(dbg_addr(1), vec![100])
+            .build()
+            .unwrap();
+        assert_ne!(funded.chain_id(), genesis.chain_id());
+    }
+
+    #[test]
+    fn from_config() {
+        let mut config = GenesisConfig {
+            validators: vec![validator("0", 1), validator("1", 1)],
+            committee_size: 2,
+            accounts: vec![AccountConfig {
+                address: Some(dbg_addr(1)),
+                gas_objects: vec![
+                    ObjectConfig {
+                        object_id: None,
+                        gas_value: 100,
+                    },
+                    ObjectConfig {
+                        object_id: Some(ObjectID::from_single_byte(1)),
+                        gas_value: 200,
+                    },
+                ],
+                gas_object_ranges: None,
+            }],
+            ..Default::default()
+        };
+
+        // The config is read back from the file operators edit
+        let config_yaml = serde_yaml::to_string(&config).unwrap();
+        let loaded: GenesisConfig = serde_yaml::from_str(&config_yaml).unwrap();
+        let genesis = Builder::from_config(&loaded).unwrap().build().unwrap();
+        assert_eq!(genesis.validator_set().len(), 2);
+        // The same config always builds the same genesis
+        assert_eq!(
+            Builder::from_config(&loaded).unwrap().build().unwrap(),
+            genesis
+        );
+        let mut values = genesis
+            .objects()
+            .iter()
+            .filter(|object| object.owner == Owner::AddressOwner(dbg_addr(1)))
+            .map(|coin| (coin.id(), GasCoin::try_from(coin).unwrap().value()))
+            .collect::<Vec<_>>();
+        values.sort_by_key(|(_, value)| *value);
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].1, 100);
+        assert_eq!(values[1], (ObjectID::from_single_byte(1), 200));
+
+        // The committee size must match the validators
+        config.committee_size = 3;
+        assert!(Builder::from_config(&config).is_err());
+        config.committee_size = 2;
+
+        // Key pairs are never read from the config
+        config.validator_genesis_info = Some(vec![]);
+        assert!(Builder::from_config(&config).is_err());
+        config.validator_genesis_info = None;
+
+        config.accounts[0].address = None;
+        assert!(Builder::from_config(&config).is_err());
+        config.accounts[0].address = Some(dbg_addr(1));
+        config.validators.clear();
+        assert!(Builder::from_config(&config).is_err());
+    }
+
     #[test]
     fn ceremony() {
         let dir = tempfile::TempDir::new().unwrap();
 
         let genesis_config = GenesisConfig::for_local_testing();
-        let (_account_keys, objects) = genesis_config
+        let (_account_keys, objects, _) = genesis_config
             .generate_accounts(&mut rand::rngs::OsRng)
             .unwrap();
 
@@ -539,6 +1673,9 @@ mod test {
         let validator = ValidatorInfo {
             name: "0".into(),
             public_key: key.public().into(),
+            network_key: None,
+            worker_key: None,
+            account_key: None,
             stake: 1,
             delegation: 0,
             network_address: utils::new_network_address(),
@@ -553,4 +1690,416 @@ mod test {
         builder.save(dir.path()).unwrap();
         Builder::load(dir.path()).unwrap();
     }
+
+    fn validator(name: &str, stake: StakeUnit) -> ValidatorInfo {
+        let key = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+        ValidatorInfo {
+            name: name.into(),
+            public_key: key.public().into(),
+            network_key: None,
+            worker_key: None,
+            account_key: None,
+            stake,
+            delegation: 0,
+            network_address: utils::new_network_address(),
+            narwhal_primary_to_primary: utils::new_network_address(),
+            narwhal_worker_to_primary: utils::new_network_address(),
+            narwhal_primary_to_worker: utils::new_network_address(),
+            narwhal_worker_to_worker: utils::new_network_address(),
+            narwhal_consensus_address: utils::new_network_address(),
+        }
+    }
+
+    #[test]
+    fn validator_set() {
+        let validators: Vec<_> = (0..4)
+            .map(|stake| validator(&format!("{stake}"), stake + 1))
+            .collect();
+        let genesis = validators
+            .iter()
+            .cloned()
+            .fold(Builder::new(), Builder::add_validator)
+            .build()
+            .unwrap();
+
+        let system_object = genesis.sui_system_object();
+        assert_eq!(system_object.validators.active_validators.len(), 4);
+        assert_eq!(system_object.validators.validator_stake, 10);
+        let committee = genesis.committee().unwrap();
+        assert_eq!(committee.epoch, 0);
+        assert_eq!(committee.total_votes, 10);
+        for validator in &validators {
+            assert_eq!(committee.weight(&validator.public_key()), validator.stake());
+        }
+    }
+
+    #[test]
+    fn accounts() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let address = dbg_addr(1);
+        let builder = Builder::new()
+            .add_account(address, vec![100, 200])
+            .add_account(address, vec![300]);
+        builder.save(dir.path()).unwrap();
+
+        let genesis = Builder::load(dir.path()).unwrap().build().unwrap();
+        let mut values: Vec<_> = genesis
+            .objects()
+            .iter()
+            .filter(|object| object.owner == Owner::AddressOwner(address))
+            .map(|object| GasCoin::try_from(object).unwrap().value())
+            .collect();
+        values.sort_unstable();
+        assert_eq!(values, vec![100, 200, 300]);
+
+        // The coins get the same IDs whenever genesis is built
+        let rebuilt = Builder::load(dir.path()).unwrap().build().unwrap();
+        assert_eq!(genesis, rebuilt);
+    }
+
+    #[test]
+    fn epoch_config() {
+        let epoch_config = EpochConfig {
+            checkpoints_per_epoch: Some(100),
+            epoch_duration_secs: Some(300),
+        };
+        let dir = tempfile::TempDir::new().unwrap();
+        Builder::new()
+            .set_epoch_config(epoch_config.clone())
+            .save(dir.path())
+            .unwrap();
+        let genesis = Builder::load(dir.path()).unwrap().build().unwrap();
+        assert_eq!(genesis.epoch_config(), &epoch_config);
+        assert_eq!(
+            Genesis::from_file_bytes(&genesis.to_file_bytes()).unwrap(),
+            genesis
+        );
+
+        // The epoch parameters are part of the identity of the chain
+        let default = Builder::new().build().unwrap();
+        assert_eq!(default.epoch_config(), &EpochConfig::default());
+        assert_ne!(genesis.chain_id(), default.chain_id());
+
+        // Genesis written before it had epoch parameters has the defaults
+        let first_version = Builder::new()
+            .set_protocol_version(MIN_PROTOCOL_VERSION)
+            .build()
+            .unwrap();
+        let bytes =
+            bcs::to_bytes(&(first_version.objects(), first_version.validator_set())).unwrap();
+        let bytes = bcs::to_bytes(&bytes).unwrap();
+        assert_eq!(Genesis::from_file_bytes(&bytes).unwrap(), first_version);
+    }
+
+    #[test]
+    fn protocol_version() {
+        let dir = tempfile::TempDir::new().unwrap();
+        Builder::new()
+            .set_protocol_version(MIN_PROTOCOL_VERSION)
+            .save(dir.path())
+            .unwrap();
+        let genesis = Builder::load(dir.path()).unwrap().build().unwrap();
+        assert_eq!(genesis.protocol_version(), MIN_PROTOCOL_VERSION);
+        assert_eq!(
+            Genesis::from_file_bytes(&genesis.to_file_bytes()).unwrap(),
+            genesis
+        );
+
+        // New networks start at the latest version, which is part of the identity of the chain
+        let latest = Builder::new().build().unwrap();
+        assert_eq!(latest.protocol_version(), PROTOCOL_VERSION);
+        assert_ne!(genesis.chain_id(), latest.chain_id());
+
+        // Genesis written before it had a protocol version runs the first one
+        let bytes = bcs::to_bytes(&(
+            genesis.objects(),
+            genesis.validator_set(),
+            RawEpochConfig::from(genesis.epoch_config()),
+        ))
+        .unwrap();
+        let bytes = bcs::to_bytes(&bytes).unwrap();
+        assert_eq!(Genesis::from_file_bytes(&bytes).unwrap(), genesis);
+
+        assert_eq!(
+            Builder::new()
+                .set_protocol_version(PROTOCOL_VERSION + 1)
+                .build(),
+            Err(GenesisError::UnsupportedProtocolVersion(
+                PROTOCOL_VERSION + 1
+            ))
+        );
+    }
+
+    #[test]
+    fn move_package() {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../sui_programmability/examples/basics");
+        let genesis = Builder::new().add_move_package(path).build().unwrap();
+        let package = genesis
+            .objects()
+            .iter()
+            .filter_map(|object| object.data.try_as_package())
+            .find(|package| package.serialized_module_map().contains_key("sandwich"))
+            .unwrap();
+        assert_ne!(package.id(), ObjectID::ZERO);
+
+        let missing = PathBuf::from("/nonexistent/package");
+        assert!(matches!(
+            Builder::new().add_move_package(missing).build(),
+            Err(GenesisError::PackageBuild { .. })
+        ));
+    }
+
+    #[test]
+    fn move_package_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let examples =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../sui_programmability/examples");
+        let builder = || {
+            Builder::new()
+                .add_move_package(examples.join("basics"))
+                .add_move_package(examples.join("objects_tutorial"))
+        };
+        builder().save(dir.path()).unwrap();
+
+        // The packages are saved compiled and published the same once loaded
+        let loaded = Builder::load(dir.path()).unwrap();
+        assert!(loaded.packages.is_empty());
+        assert_eq!(loaded.compiled_packages.len(), 2);
+        let genesis = builder().build().unwrap();
+        assert_eq!(loaded.build().unwrap(), genesis);
+
+        // And saved again with the builder
+        let resaved = tempfile::TempDir::new().unwrap();
+        Builder::load(dir.path())
+            .unwrap()
+            .save(resaved.path())
+            .unwrap();
+        assert_eq!(
+            Builder::load(resaved.path()).unwrap().build().unwrap(),
+            genesis
+        );
+    }
+
+    #[test]
+    fn framework_packages() {
+        let genesis = Builder::new().build().unwrap();
+        for (address, modules) in [
+            (MOVE_STDLIB_ADDRESS, sui_framework::get_move_stdlib()),
+            (SUI_FRAMEWORK_ADDRESS, sui_framework::get_sui_framework()),
+        ] {
+            // The framework is published as package objects, which stores are initialized from
+            let package = genesis
+                .objects()
+                .iter()
+                .find(|object| object.id() == ObjectID::from(address))
+                .and_then(|object| object.data.try_as_package())
+                .unwrap();
+            let names: Vec<_> = modules
+                .iter()
+                .map(|module| module.self_id().name().to_string())
+                .collect();
+            assert!(names
+                .iter()
+                .all(|name| package.serialized_module_map().contains_key(name)));
+            assert_eq!(package.serialized_module_map().len(), names.len());
+        }
+    }
+
+    #[test]
+    fn package_linkage() {
+        let framework = [
+            sui_framework::get_move_stdlib(),
+            sui_framework::get_sui_framework(),
+        ];
+        verify_packages(&framework).unwrap();
+
+        let reversed = [framework[1].clone(), framework[0].clone()];
+        assert!(matches!(
+            verify_packages(&reversed),
+            Err(GenesisError::UnlinkedDependency { .. })
+        ));
+    }
+
+    /// A module without code named `name` at `address`, that depends on the `dependencies`.
+    fn module(address: u8, name: &str, dependencies: &[(u8, &str)]) -> CompiledModule {
+        let mut module = empty_module();
+        module.address_identifiers[0] = AccountAddress::new([address; AccountAddress::LENGTH]);
+        module.identifiers[0] = Identifier::new(name).unwrap();
+        for (address, name) in dependencies {
+            module.module_handles.push(ModuleHandle {
+                address: AddressIdentifierIndex(module.address_identifiers.len() as u16),
+                name: IdentifierIndex(module.identifiers.len() as u16),
+            });
+            module
+                .address_identifiers
+                .push(AccountAddress::new([*address; AccountAddress::LENGTH]));
+            module.identifiers.push(Identifier::new(*name).unwrap());
+        }
+        module
+    }
+
+    #[test]
+    fn package_order() {
+        let names = |packages: &[Vec<CompiledModule>]| -> Vec<String> {
+            packages
+                .iter()
+                .map(|package| package[0].self_id().name().to_string())
+                .collect()
+        };
+        // `a` sorts first but depends on `b`, which depends on `c`
+        let a = vec![module(3, "a", &[(4, "b")])];
+        let b = vec![module(4, "b", &[(5, "c")])];
+        let c = vec![module(5, "c", &[])];
+        let d = vec![module(6, "d", &[])];
+        let sorted = sort_packages(vec![d.clone(), a.clone(), b.clone(), c.clone()]);
+        assert_eq!(names(&sorted), vec!["c", "b", "a", "d"]);
+        assert_eq!(
+            names(&sort_packages(vec![c, b.clone(), d, a.clone()])),
+            names(&sorted)
+        );
+
+        // Packages depending on each other are kept, ordered as far as possible
+        let cycle = vec![module(5, "c", &[(3, "a")])];
+        assert_eq!(
+            names(&sort_packages(vec![b, cycle, a])),
+            vec!["a", "c", "b"]
+        );
+    }
+
+    #[test]
+    fn reproducible() {
+        let examples =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../sui_programmability/examples");
+        let objects: Vec<_> = (0..4)
+            .map(|i| Object::with_owner_for_testing(dbg_addr(i)))
+            .collect();
+        let validators: Vec<_> = (0..4).map(|i| validator(&format!("{i}"), 1)).collect();
+
+        let genesis = Builder::new()
+            .add_objects(objects.clone())
+            .add_account(dbg_addr(1), vec![100])
+            .add_account(dbg_addr(2), vec![200])
+            .add_move_package(examples.join("basics"))
+            .add_move_package(examples.join("objects_tutorial"));
+        let genesis = validators
+            .iter()
+            .cloned()
+            .fold(genesis, Builder::add_validator)
+            .build()
+            .unwrap();
+
+        let reordered = Builder::new()
+            .add_move_package(examples.join("objects_tutorial"))
+            .add_move_package(examples.join("../examples/basics"))
+            .add_account(dbg_addr(2), vec![200])
+            .add_account(dbg_addr(1), vec![100])
+            .add_objects(objects.into_iter().rev().collect());
+        let reordered = validators
+            .into_iter()
+            .rev()
+            .fold(reordered, Builder::add_validator)
+            .build()
+            .unwrap();
+
+        assert_eq!(reordered.to_bytes(), genesis.to_bytes());
+        assert_eq!(reordered.to_file_bytes(), genesis.to_file_bytes());
+    }
+
+    #[test]
+    fn move_objects() {
+        let balance = bcs::to_bytes(&100u64).unwrap();
+        let builder = Builder::new()
+            .add_coin(dbg_addr(1), 100)
+            .add_object_owned_by(dbg_addr(2), GasCoin::type_(), balance.clone())
+            .add_shared_object(GasCoin::type_(), balance.clone());
+        let dir = tempfile::TempDir::new().unwrap();
+        builder.save(dir.path()).unwrap();
+        let genesis = Builder::load(dir.path()).unwrap().build().unwrap();
+
+        for owner in [
+            Owner::AddressOwner(dbg_addr(1)),
+            Owner::AddressOwner(dbg_addr(2)),
+            Owner::Shared,
+        ] {
+            let object = genesis
+                .objects()
+                .iter()
+                .find(|object| object.owner == owner)
+                .unwrap();
+            assert_eq!(object.version(), SequenceNumber::new());
+            let coin = GasCoin::try_from(object).unwrap();
+            assert_eq!(coin.value(), 100);
+            assert_eq!(coin.id(), &object.id());
+        }
+
+        let reordered = Builder::new()
+            .add_shared_object(GasCoin::type_(), balance.clone())
+            .add_object_owned_by(dbg_addr(2), GasCoin::type_(), balance)
+            .add_coin(dbg_addr(1), 100)
+            .build()
+            .unwrap();
+        assert_eq!(reordered, genesis);
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let id = ObjectID::random();
+        let duplicates = Builder::new()
+            .add_object(Object::with_id_owner_for_testing(id, dbg_addr(1)))
+            .add_object(Object::with_id_owner_for_testing(id, dbg_addr(2)));
+        assert_eq!(duplicates.build(), Err(GenesisError::DuplicateObjectId(id)));
+
+        let system_state = Builder::new().add_object(Object::with_id_owner_for_testing(
+            SUI_SYSTEM_STATE_OBJECT_ID,
+            dbg_addr(1),
+        ));
+        assert_eq!(
+            system_state.build(),
+            Err(GenesisError::DuplicateObjectId(SUI_SYSTEM_STATE_OBJECT_ID))
+        );
+
+        let mut not_an_object = GasCoin::type_();
+        not_an_object.name = ident_str!("Balance").to_owned();
+        not_an_object.module = ident_str!("balance").to_owned();
+        assert!(matches!(
+            Builder::new()
+                .add_shared_object(not_an_object, bcs::to_bytes(&100u64).unwrap())
+                .build(),
+            Err(GenesisError::InvalidObject { .. })
+        ));
+
+        // The fields of a coin are its balance, a u64
+        for fields in [bcs::to_bytes(&100u32).unwrap(), vec![0; 9]] {
+            assert!(matches!(
+                Builder::new()
+                    .add_object_owned_by(dbg_addr(1), GasCoin::type_(), fields)
+                    .build(),
+                Err(GenesisError::InvalidObject { .. })
+            ));
+        }
+
+        let basics = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../sui_programmability/examples/basics");
+        let ham = StructTag {
+            address: AccountAddress::ZERO,
+            module: ident_str!("sandwich").to_owned(),
+            name: ident_str!("Ham").to_owned(),
+            type_params: vec![],
+        };
+        assert!(matches!(
+            Builder::new()
+                .add_move_package(basics)
+                .add_object_owned_by(dbg_addr(1), ham, Vec::new())
+                .build(),
+            Err(GenesisError::InvalidObject { .. })
+        ));
+
+        let mut delegated = validator("0", 1);
+        delegated.delegation = 1;
+        assert!(matches!(
+            Builder::new().add_validator(delegated).build(),
+            Err(GenesisError::InvalidValidator { .. })
+        ));
+    }
 }
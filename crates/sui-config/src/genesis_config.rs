@@ -6,31 +6,49 @@ use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::committee::StakeUnit;
-use sui_types::crypto::{get_key_pair_from_rng, KeyPair};
+use sui_types::crypto::{get_key_pair_from_rng, KeyPair, KeypairTraits};
 use sui_types::object::Object;
 use sui_types::sui_serde::KeyPairBase64;
 use tracing::info;
 
-use crate::Config;
+use crate::{Config, EpochConfig, ValidatorInfo};
 
 #[derive(Serialize, Deserialize)]
 pub struct GenesisConfig {
     pub validator_genesis_info: Option<Vec<ValidatorGenesisInfo>>,
+    /// The public entries of the validators, which genesis is built with by
+    /// [`crate::genesis::Builder::from_config`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub validators: Vec<ValidatorInfo>,
     pub committee_size: usize,
     pub accounts: Vec<AccountConfig>,
+    /// When the epochs of the network end, set in its genesis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_config: Option<EpochConfig>,
+    /// The protocol version the network starts at, the latest one if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<u32>,
+    /// The directories of the Move packages to publish at genesis, built from source.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<PathBuf>,
 }
 
 impl Config for GenesisConfig {}
 
 impl GenesisConfig {
+    /// The keys of the accounts without an address, the gas objects given an ID, and the values
+    /// of the gas coins of each account left for genesis to give an ID, see
+    /// [`crate::genesis::Builder::add_account`].
     pub fn generate_accounts<R: ::rand::RngCore + ::rand::CryptoRng>(
         &self,
         mut rng: R,
-    ) -> Result<(Vec<KeyPair>, Vec<Object>)> {
+    ) -> Result<(Vec<KeyPair>, Vec<Object>, BTreeMap<SuiAddress, Vec<u64>>)> {
         let mut addresses = Vec::new();
         let mut preload_objects = Vec::new();
+        let mut gas_budgets = BTreeMap::<_, Vec<_>>::new();
         let mut all_preload_objects_set = BTreeSet::new();
 
         info!("Creating accounts and gas objects...");
@@ -49,10 +67,13 @@ impl GenesisConfig {
             let mut preload_objects_map = BTreeMap::new();
 
             // Populate gas itemized objects
-            account.gas_objects.iter().for_each(|q| {
-                if !all_preload_objects_set.contains(&q.object_id) {
-                    preload_objects_map.insert(q.object_id, q.gas_value);
+            account.gas_objects.iter().for_each(|q| match q.object_id {
+                Some(object_id) => {
+                    if !all_preload_objects_set.contains(&object_id) {
+                        preload_objects_map.insert(object_id, q.gas_value);
+                    }
                 }
+                None => gas_budgets.entry(address).or_default().push(q.gas_value),
             });
 
             // Populate ranged gas objects
@@ -77,7 +98,7 @@ impl GenesisConfig {
             }
         }
 
-        Ok((keys, preload_objects))
+        Ok((keys, preload_objects, gas_budgets))
     }
 }
 
@@ -95,6 +116,27 @@ pub struct ValidatorGenesisInfo {
     pub narwhal_consensus_address: Multiaddr,
 }
 
+impl ValidatorGenesisInfo {
+    /// The genesis entry of this validator, named `name`.
+    pub fn to_validator_info(&self, name: String) -> ValidatorInfo {
+        ValidatorInfo {
+            name,
+            public_key: self.key_pair.public().into(),
+            network_key: None,
+            worker_key: None,
+            account_key: None,
+            stake: self.stake,
+            delegation: 0, // no delegation yet at genesis
+            network_address: self.network_address.clone(),
+            narwhal_primary_to_primary: self.narwhal_primary_to_primary.clone(),
+            narwhal_worker_to_primary: self.narwhal_worker_to_primary.clone(),
+            narwhal_primary_to_worker: self.narwhal_primary_to_worker.clone(),
+            narwhal_worker_to_worker: self.narwhal_worker_to_worker.clone(),
+            narwhal_consensus_address: self.narwhal_consensus_address.clone(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AccountConfig {
     #[serde(
@@ -119,8 +161,9 @@ pub struct ObjectConfigRange {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ObjectConfig {
-    #[serde(default = "ObjectID::random")]
-    pub object_id: ObjectID,
+    /// The ID of the gas object, derived from the genesis transaction if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_id: Option<ObjectID>,
     #[serde(default = "default_gas_value")]
     pub gas_value: u64,
 }
@@ -158,7 +201,7 @@ impl GenesisConfig {
             let mut objects = Vec::new();
             for _ in 0..num_objects_per_account {
                 objects.push(ObjectConfig {
-                    object_id: ObjectID::random(),
+                    object_id: Some(ObjectID::random()),
                     gas_value: DEFAULT_GAS_AMOUNT,
                 })
             }
@@ -180,8 +223,12 @@ impl Default for GenesisConfig {
     fn default() -> Self {
         Self {
             validator_genesis_info: None,
+            validators: vec![],
             committee_size: DEFAULT_NUMBER_OF_AUTHORITIES,
             accounts: vec![],
+            epoch_config: None,
+            protocol_version: None,
+            packages: vec![],
         }
     }
 }
@@ -0,0 +1,166 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! How two genesis differ, for validators which do not agree on the genesis of their network.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use sha3::{Digest, Sha3_256};
+use sui_types::base_types::{encode_bytes_hex, ObjectID};
+
+use crate::genesis::Genesis;
+
+/// A change of an entry of genesis, as the digest of its contents in each genesis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Changed { from: String, to: String },
+}
+
+/// The entries of a genesis which are not the same in another, see [`Genesis::diff`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GenesisDiff {
+    /// The modules of packages as `address::name`, with the SHA3-256 digest of their bytecode.
+    pub modules: BTreeMap<String, Change>,
+    /// The objects, with their digest.
+    pub objects: BTreeMap<ObjectID, Change>,
+    /// The validators by public key, with the SHA3-256 digest of their BCS encoding.
+    pub validators: BTreeMap<String, Change>,
+}
+
+impl GenesisDiff {
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.objects.is_empty() && self.validators.is_empty()
+    }
+}
+
+impl Genesis {
+    /// How `other` differs from this genesis. Both start from the same genesis transaction, so
+    /// their objects and validators are all there is to compare.
+    pub fn diff(&self, other: &Genesis) -> GenesisDiff {
+        GenesisDiff {
+            modules: diff_entries(&module_digests(self), &module_digests(other)),
+            objects: diff_entries(&object_digests(self), &object_digests(other)),
+            validators: diff_entries(&validator_digests(self), &validator_digests(other)),
+        }
+    }
+}
+
+fn module_digests(genesis: &Genesis) -> BTreeMap<String, String> {
+    genesis
+        .objects()
+        .iter()
+        .filter_map(|object| object.data.try_as_package())
+        .flat_map(|package| {
+            package
+                .serialized_module_map()
+                .iter()
+                .map(move |(name, bytes)| (format!("{}::{name}", package.id()), hash(bytes)))
+        })
+        .collect()
+}
+
+fn object_digests(genesis: &Genesis) -> BTreeMap<ObjectID, String> {
+    genesis
+        .objects()
+        .iter()
+        .map(|object| (object.id(), encode_bytes_hex(object.digest())))
+        .collect()
+}
+
+fn validator_digests(genesis: &Genesis) -> BTreeMap<String, String> {
+    genesis
+        .validator_set()
+        .iter()
+        .map(|validator| {
+            let bytes = bcs::to_bytes(validator).expect("failed to serialize validator");
+            (encode_bytes_hex(validator.public_key()), hash(&bytes))
+        })
+        .collect()
+}
+
+fn hash(bytes: &[u8]) -> String {
+    encode_bytes_hex(Sha3_256::digest(bytes))
+}
+
+fn diff_entries<K: Ord + Clone>(
+    from: &BTreeMap<K, String>,
+    to: &BTreeMap<K, String>,
+) -> BTreeMap<K, Change> {
+    let keys: BTreeSet<_> = from.keys().chain(to.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let change = match (from.get(key), to.get(key)) {
+                (None, Some(digest)) => Change::Added(digest.clone()),
+                (Some(digest), None) => Change::Removed(digest.clone()),
+                (Some(from), Some(to)) if from != to => Change::Changed {
+                    from: from.clone(),
+                    to: to.clone(),
+                },
+                _ => return None,
+            };
+            Some((key.clone(), change))
+        })
+        .collect()
+}
+
+impl fmt::Display for GenesisDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_changes<K: fmt::Display>(
+            f: &mut fmt::Formatter<'_>,
+            kind: &str,
+            changes: &BTreeMap<K, Change>,
+        ) -> fmt::Result {
+            for (key, change) in changes {
+                match change {
+                    Change::Added(digest) => writeln!(f, "+ {kind} {key} {digest}")?,
+                    Change::Removed(digest) => writeln!(f, "- {kind} {key} {digest}")?,
+                    Change::Changed { from, to } => writeln!(f, "~ {kind} {key} {from} -> {to}")?,
+                }
+            }
+            Ok(())
+        }
+
+        if self.is_empty() {
+            return writeln!(f, "The genesis are the same");
+        }
+        write_changes(f, "module", &self.modules)?;
+        write_changes(f, "object", &self.objects)?;
+        write_changes(f, "validator", &self.validators)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Change;
+    use crate::genesis::Builder;
+    use sui_types::base_types::dbg_addr;
+    use sui_types::object::{Object, Owner};
+
+    #[test]
+    fn diff() {
+        let object = Object::with_owner_for_testing(dbg_addr(1));
+        let genesis = Builder::new().add_object(object.clone()).build().unwrap();
+        assert!(genesis.diff(&genesis).is_empty());
+
+        let other = Builder::new()
+            .add_account(dbg_addr(2), vec![100])
+            .build()
+            .unwrap();
+        let diff = genesis.diff(&other);
+        assert!(diff.modules.is_empty());
+        assert!(diff.validators.is_empty());
+        assert!(matches!(diff.objects[&object.id()], Change::Removed(_)));
+        let coin = other
+            .objects()
+            .iter()
+            .find(|object| object.owner == Owner::AddressOwner(dbg_addr(2)))
+            .unwrap();
+        assert!(matches!(diff.objects[&coin.id()], Change::Added(_)));
+        assert!(diff
+            .to_string()
+            .contains(&format!("- object {}", object.id())));
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A layout of genesis files whose objects are read one at a time, for networks starting from a
+//! snapshot of a state too large to hold in memory.
+//!
+//! The file starts with [`GENESIS_STREAM_MAGIC`] and the version of the layout, followed by the
+//! validator set, the epoch parameters, the protocol version and then each object in turn, BCS
+//! encoded and prefixed with their length as a little endian `u32`. Objects are written until the
+//! file ends.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use sui_types::handshake::MIN_PROTOCOL_VERSION;
+use sui_types::object::Object;
+use tracing::trace;
+
+use crate::genesis::{Genesis, RawEpochConfig};
+use crate::{EpochConfig, ValidatorInfo};
+
+/// The bytes streamed genesis files start with.
+pub const GENESIS_STREAM_MAGIC: [u8; 8] = *b"SUIGSTRM";
+
+/// The version of the layout of streamed genesis files. Version 2 adds the epoch parameters,
+/// which files of the first version have the defaults of, and version 3 the protocol version,
+/// which is the first one for files of earlier versions.
+pub const GENESIS_STREAM_VERSION: u32 = 3;
+
+/// Writes genesis objects to a streamed genesis file as they come.
+pub struct GenesisWriter<W: Write> {
+    writer: W,
+}
+
+impl GenesisWriter<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        validator_set: &[ValidatorInfo],
+        epoch_config: &EpochConfig,
+        protocol_version: u32,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        trace!("Writing streamed Genesis to {}", path.display());
+        let file = File::create(path)
+            .with_context(|| format!("Unable to save Genesis to {}", path.display()))?;
+        Self::new(
+            BufWriter::new(file),
+            validator_set,
+            epoch_config,
+            protocol_version,
+        )
+    }
+}
+
+impl<W: Write> GenesisWriter<W> {
+    pub fn new(
+        mut writer: W,
+        validator_set: &[ValidatorInfo],
+        epoch_config: &EpochConfig,
+        protocol_version: u32,
+    ) -> Result<Self> {
+        writer.write_all(&GENESIS_STREAM_MAGIC)?;
+        writer.write_all(&GENESIS_STREAM_VERSION.to_le_bytes())?;
+        write_frame(&mut writer, &bcs::to_bytes(validator_set)?)?;
+        write_frame(
+            &mut writer,
+            &bcs::to_bytes(&RawEpochConfig::from(epoch_config))?,
+        )?;
+        write_frame(&mut writer, &bcs::to_bytes(&protocol_version)?)?;
+        Ok(Self { writer })
+    }
+
+    pub fn write_object(&mut self, object: &Object) -> Result<()> {
+        write_frame(&mut self.writer, &bcs::to_bytes(object)?)
+    }
+
+    /// Flush the file, which ends at the last object written.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads a streamed genesis file, holding its validator set in memory but not its objects.
+pub struct GenesisReader {
+    path: PathBuf,
+    validator_set: Vec<ValidatorInfo>,
+    epoch_config: EpochConfig,
+    protocol_version: u32,
+    /// Where the first object starts in the file.
+    objects_offset: u64,
+}
+
+impl GenesisReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        trace!("Reading streamed Genesis from {}", path.display());
+        let file = File::open(path)
+            .with_context(|| format!("Unable to load Genesis from {}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let mut header = [0; GENESIS_STREAM_MAGIC.len() + 4];
+        reader
+            .read_exact(&mut header)
+            .context("Truncated streamed genesis file")?;
+        if header[..GENESIS_STREAM_MAGIC.len()] != GENESIS_STREAM_MAGIC {
+            bail!("{} is not a streamed genesis file", path.display());
+        }
+        let version = u32::from_le_bytes(header[GENESIS_STREAM_MAGIC.len()..].try_into()?);
+        if !(1..=GENESIS_STREAM_VERSION).contains(&version) {
+            bail!(
+                "Unsupported streamed genesis version {version}, expected version \
+                 {GENESIS_STREAM_VERSION} at most"
+            );
+        }
+        let validator_set =
+            read_frame(&mut reader)?.context("Streamed genesis file without a validator set")?;
+        let validator_set = bcs::from_bytes(&validator_set)?;
+        let epoch_config = if version == 1 {
+            EpochConfig::default()
+        } else {
+            let epoch_config = read_frame(&mut reader)?
+                .context("Streamed genesis file without epoch parameters")?;
+            bcs::from_bytes::<RawEpochConfig>(&epoch_config)?.into()
+        };
+        let protocol_version = if version < 3 {
+            MIN_PROTOCOL_VERSION
+        } else {
+            let protocol_version = read_frame(&mut reader)?
+                .context("Streamed genesis file without a protocol version")?;
+            bcs::from_bytes(&protocol_version)?
+        };
+        let objects_offset = reader.stream_position()?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            validator_set,
+            epoch_config,
+            protocol_version,
+            objects_offset,
+        })
+    }
+
+    pub fn validator_set(&self) -> &[ValidatorInfo] {
+        &self.validator_set
+    }
+
+    pub fn epoch_config(&self) -> &EpochConfig {
+        &self.epoch_config
+    }
+
+    pub fn protocol_version(&self) -> u32 {
+        self.protocol_version
+    }
+
+    /// The objects of genesis, read from the file as the iterator advances. It ends after the
+    /// first error.
+    pub fn objects_iter(&self) -> impl Iterator<Item = Result<Object>> {
+        let mut reader = File::open(&self.path)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                let mut reader = BufReader::new(file);
+                reader.seek(SeekFrom::Start(self.objects_offset))?;
+                Ok(reader)
+            });
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let next = match reader.as_mut() {
+                Ok(reader) => match read_frame(reader) {
+                    Ok(Some(bytes)) => Some(bcs::from_bytes(&bytes).map_err(Into::into)),
+                    Ok(None) => None,
+                    Err(e) => Some(Err(e)),
+                },
+                Err(e) => Some(Err(anyhow!("Unable to read the objects of genesis: {e}"))),
+            };
+            done = !matches!(next, Some(Ok(_)));
+            next
+        })
+    }
+
+    /// Read all the objects of genesis in memory.
+    pub fn into_genesis(self) -> Result<Genesis> {
+        let objects = self.objects_iter().collect::<Result<Vec<_>>>()?;
+        Ok(Genesis::new(
+            objects,
+            self.validator_set,
+            self.epoch_config,
+            self.protocol_version,
+        ))
+    }
+}
+
+impl Genesis {
+    /// Save genesis as a streamed genesis file, see [`GenesisReader`].
+    pub fn save_streamed<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = GenesisWriter::create(
+            path,
+            self.validator_set(),
+            self.epoch_config(),
+            self.protocol_version(),
+        )?;
+        for object in self.objects() {
+            writer.write_object(object)?;
+        }
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+fn write_frame<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    let len = u32::try_from(bytes.len()).context("Genesis entry too large")?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+/// The next length prefixed entry of `reader`, if it has not ended.
+fn read_frame<R: BufRead>(reader: &mut R) -> Result<Option<Vec<u8>>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+    let mut len = [0; 4];
+    reader
+        .read_exact(&mut len)
+        .context("Truncated streamed genesis file")?;
+    let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+    reader
+        .read_exact(&mut bytes)
+        .context("Truncated streamed genesis file")?;
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::GenesisReader;
+    use crate::genesis::Builder;
+    use crate::EpochConfig;
+    use sui_types::base_types::dbg_addr;
+    use sui_types::handshake::MIN_PROTOCOL_VERSION;
+
+    #[test]
+    fn roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("genesis.stream");
+        let genesis = Builder::new()
+            .add_account(dbg_addr(1), vec![100, 200])
+            .set_epoch_config(EpochConfig {
+                checkpoints_per_epoch: Some(10),
+                epoch_duration_secs: None,
+            })
+            .set_protocol_version(MIN_PROTOCOL_VERSION)
+            .build()
+            .unwrap();
+        genesis.save_streamed(&path).unwrap();
+
+        let reader = GenesisReader::open(&path).unwrap();
+        assert_eq!(reader.validator_set(), genesis.validator_set());
+        assert_eq!(reader.epoch_config(), genesis.epoch_config());
+        assert_eq!(reader.protocol_version(), MIN_PROTOCOL_VERSION);
+        let objects: Vec<_> = reader.objects_iter().map(Result::unwrap).collect();
+        assert_eq!(objects, genesis.objects());
+        assert_eq!(reader.into_genesis().unwrap(), genesis);
+
+        // A truncated object fails the iterator, which then ends
+        let len = std::fs::metadata(&path).unwrap().len();
+        let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(len - 1).unwrap();
+        let reader = GenesisReader::open(&path).unwrap();
+        let results: Vec<_> = reader.objects_iter().collect();
+        assert!(results.last().unwrap().is_err());
+        assert_eq!(results.len(), genesis.objects().len());
+
+        std::fs::write(&path, genesis.to_file_bytes()).unwrap();
+        assert!(GenesisReader::open(&path).is_err());
+    }
+}
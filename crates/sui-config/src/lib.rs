@@ -13,11 +13,16 @@ use tracing::trace;
 pub mod builder;
 pub mod genesis;
 pub mod genesis_config;
+pub mod genesis_diff;
+pub mod genesis_stream;
 pub mod node;
 mod swarm;
+pub mod testbed;
 pub mod utils;
 
-pub use node::{ConsensusConfig, NodeConfig, ValidatorInfo};
+pub use node::{
+    ConsensusConfig, EpochConfig, LoggingConfig, NodeConfig, TunableConfig, ValidatorInfo,
+};
 pub use swarm::NetworkConfig;
 
 const SUI_DIR: &str = ".sui";
@@ -101,6 +106,10 @@ where
     pub fn into_inner(self) -> C {
         self.inner
     }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 impl<C> std::ops::Deref for PersistedConfig<C> {
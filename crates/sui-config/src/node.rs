@@ -6,12 +6,20 @@ use crate::Config;
 use anyhow::Result;
 use multiaddr::Multiaddr;
 use narwhal_config::Parameters as ConsensusParameters;
-use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
-use std::collections::BTreeMap;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, DeserializeAs, SerializeAs};
+use std::collections::{BTreeMap, BTreeSet};
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use sui_network::address::{Endpoint, Transport};
+use sui_network::discovery::DiscoveryConfig;
+use sui_network::quic::QuicConfig;
+use sui_storage::backing_store::{GroupCommitConfig, StorageBackend};
+use sui_storage::backup_sink::BackupConfig;
+use sui_storage::migration::MigrationConfig;
+use sui_storage::ttl::TtlConfig;
 use sui_types::base_types::SuiAddress;
 use sui_types::committee::StakeUnit;
 use sui_types::crypto::KeypairTraits;
@@ -22,21 +30,104 @@ use sui_types::sui_serde::KeyPairBase64;
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct NodeConfig {
+    /// The protocol key of the node, which signs its votes, effects and checkpoints and is its
+    /// name in the committee. The consensus primary and workers of a validator sign with it too.
     #[serde(default = "default_key_pair")]
     #[serde_as(as = "Arc<KeyPairBase64>")]
     pub key_pair: Arc<KeyPair>,
+    /// Authenticates the node on the connections it serves and opens, and signs its peer
+    /// records. The protocol key if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<Arc<KeyPairBase64>>")]
+    pub network_key_pair: Option<Arc<KeyPair>>,
+    /// The key of the consensus workers of a validator. The protocol key if not set. The
+    /// workers of the consensus version in use are still named after their primary, so it is
+    /// only carried into genesis for now.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<Arc<KeyPairBase64>>")]
+    pub worker_key_pair: Option<Arc<KeyPair>>,
+    /// The public key of the account of the validator, which holds its stake. Its private key
+    /// stays with the operator rather than on the node. The protocol key if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_key: Option<PublicKeyBytes>,
     pub db_path: PathBuf,
+    /// The store the tables of the authority store are kept in, RocksDB if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage_backend: Option<StorageBackend>,
+    /// Writes of the authority store are synced to disk in groups if set, see
+    /// `sui_storage::backing_store::group_commit`, and written one by one without being synced
+    /// otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_commit: Option<GroupCommitConfig>,
+    /// The number of objects cached in front of the authority store, 0 to disable the cache.
+    /// The default of the store if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_cache_size: Option<usize>,
+    /// Periodic backups of the authority store, and where they are mirrored to, disabled if
+    /// not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_config: Option<BackupConfig>,
+    /// How the authority store is migrated to the latest version of its schema when the node
+    /// starts, migrated with no backup if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub migration_config: Option<MigrationConfig>,
+    /// The times to live of the transient rows of the stores of the node, which are kept until
+    /// they are no longer needed if not set. Reloaded with the [`TunableConfig`] of a node
+    /// started with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_config: Option<TtlConfig>,
+    /// Where the transactions and certificates served by the validator are recorded, for
+    /// replaying them with `sui-tool replay-traffic`, not recorded if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traffic_capture_path: Option<PathBuf>,
+    /// Served over QUIC if this is a QUIC address, e.g. `/ip4/0.0.0.0/udp/8080/quic`, over gRPC
+    /// over TCP otherwise. Both transports are authenticated with the network key.
     #[serde(default = "default_grpc_address")]
     pub network_address: Multiaddr,
+    /// Settings of the QUIC transport, the defaults if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quic_config: Option<QuicConfig>,
+    /// Only accept the clients authenticated with one of these network keys, or with the one of
+    /// a validator of the current committee. Any authenticated client is accepted if not set, as
+    /// the gateways of users send their transactions to the validators directly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_clients: Option<Vec<PublicKeyBytes>>,
+    /// Discovery of the peers of the node, disabled if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discovery_config: Option<DiscoveryConfig>,
+    /// The addresses of the servers below are given as `ip:port`, e.g. `[::]:9000`, or as TCP
+    /// multiaddrs, e.g. `/dns4/localhost/tcp/9000`, resolved when the config is loaded.
     #[serde(default = "default_json_rpc_address")]
+    #[serde_as(as = "SocketAddress")]
     pub json_rpc_address: SocketAddr,
     #[serde(default = "default_websocket_address")]
+    #[serde_as(as = "Option<SocketAddress>")]
     pub websocket_address: Option<SocketAddr>,
+    /// Address of the REST facade over the read APIs, disabled if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde_as(as = "Option<SocketAddress>")]
+    pub rest_address: Option<SocketAddr>,
 
     #[serde(default = "default_metrics_address")]
+    #[serde_as(as = "SocketAddress")]
     pub metrics_address: SocketAddr,
     #[serde(default = "default_admin_interface_port")]
     pub admin_interface_port: u16,
+    /// The token the requests reloading the config through the admin interface bear, as
+    /// `Authorization: Bearer <token>`. These requests are refused if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
+    /// How the node writes its logs, see [`LoggingConfig`]. Logged by `telemetry-subscribers` as
+    /// configured by the environment if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging_config: Option<LoggingConfig>,
+    /// Periodic pushes of the health of the node to the operators of the network, see
+    /// [`TelemetryPushConfig`]. Nothing is pushed if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry_push: Option<TelemetryPushConfig>,
+    /// The settings changed while the node runs, see [`TunableConfig`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tunable_config: Option<TunableConfig>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_config: Option<ConsensusConfig>,
@@ -81,6 +172,38 @@ pub fn default_websocket_address() -> Option<SocketAddr> {
     Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 9001))
 }
 
+/// A socket address serialized as `ip:port`, and deserialized from `ip:port` or a TCP multiaddr.
+pub struct SocketAddress;
+
+impl SocketAddress {
+    pub fn parse(address: &str) -> Result<SocketAddr> {
+        if let Ok(address) = address.parse() {
+            return Ok(address);
+        }
+        let multiaddr: Multiaddr = address.parse().map_err(|e| {
+            anyhow::anyhow!("Invalid address {address}, expected ip:port or a multiaddr: {e}")
+        })?;
+        let endpoint = Endpoint::parse(&multiaddr)?;
+        if endpoint.transport != Transport::Tcp {
+            anyhow::bail!("Invalid address {address}, expected a TCP address");
+        }
+        Ok(endpoint.resolve()?)
+    }
+}
+
+impl SerializeAs<SocketAddr> for SocketAddress {
+    fn serialize_as<S: Serializer>(source: &SocketAddr, serializer: S) -> Result<S::Ok, S::Error> {
+        source.serialize(serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, SocketAddr> for SocketAddress {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<SocketAddr, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        Self::parse(&address).map_err(D::Error::custom)
+    }
+}
+
 impl Config for NodeConfig {}
 
 impl NodeConfig {
@@ -92,8 +215,29 @@ impl NodeConfig {
         self.key_pair.public().into()
     }
 
+    pub fn network_key_pair(&self) -> &KeyPair {
+        self.network_key_pair.as_deref().unwrap_or(&self.key_pair)
+    }
+
+    pub fn network_public_key(&self) -> PublicKeyBytes {
+        self.network_key_pair().public().into()
+    }
+
+    pub fn worker_key_pair(&self) -> &KeyPair {
+        self.worker_key_pair.as_deref().unwrap_or(&self.key_pair)
+    }
+
+    pub fn worker_public_key(&self) -> PublicKeyBytes {
+        self.worker_key_pair().public().into()
+    }
+
+    pub fn account_key(&self) -> PublicKeyBytes {
+        self.account_key.unwrap_or_else(|| self.public_key())
+    }
+
+    /// The address of the account of the node.
     pub fn sui_address(&self) -> SuiAddress {
-        (&self.public_key()).into()
+        (&self.account_key()).into()
     }
 
     pub fn db_path(&self) -> &Path {
@@ -104,6 +248,22 @@ impl NodeConfig {
         &self.network_address
     }
 
+    pub fn storage_backend(&self) -> StorageBackend {
+        self.storage_backend.unwrap_or_default()
+    }
+
+    pub fn quic_config(&self) -> QuicConfig {
+        self.quic_config.clone().unwrap_or_default()
+    }
+
+    pub fn tunable_config(&self) -> TunableConfig {
+        self.tunable_config.clone().unwrap_or_default()
+    }
+
+    pub fn discovery_config(&self) -> Option<&DiscoveryConfig> {
+        self.discovery_config.as_ref()
+    }
+
     pub fn consensus_config(&self) -> Option<&ConsensusConfig> {
         self.consensus_config.as_ref()
     }
@@ -113,6 +273,103 @@ impl NodeConfig {
     }
 }
 
+/// The logs of a node written by the node itself, as text or as JSON lines for aggregation
+/// systems to ingest, to stderr or to files it rotates.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Written to stderr if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<LogFileConfig>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// A JSON object per line, with the fields of the spans the event is in, such as the digest
+    /// of the transaction being processed.
+    Json,
+}
+
+/// The log is written to `<directory>/<prefix>.log`, which is renamed to
+/// `<directory>/<prefix>.<milliseconds since the epoch>.log` and started again when it
+/// rotates.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LogFileConfig {
+    pub directory: PathBuf,
+    #[serde(default = "default_log_file_prefix")]
+    pub prefix: String,
+    /// Rotated before growing past this size, never rotated on size if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size_bytes: Option<u64>,
+    /// Rotated once written to for this long, never rotated on time if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotation_interval_secs: Option<u64>,
+    /// The rotated files kept, the oldest are removed first. All are kept if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<usize>,
+}
+
+fn default_log_file_prefix() -> String {
+    "sui-node".to_string()
+}
+
+/// Where and how often a node pushes reports of its health: its version, uptime, checkpoint lag
+/// behind the network and number of peers. Reports carry an id drawn at random each time the
+/// node starts and no key or address of the node, so that they cannot be traced back to it.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TelemetryPushConfig {
+    /// The URL reports are posted to, as JSON.
+    pub endpoint: String,
+    #[serde(default = "default_telemetry_push_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_telemetry_push_interval_secs() -> u64 {
+    300
+}
+
+/// When epochs end: after a number of checkpoints, or at the first checkpoint past their
+/// duration if that comes first. Networks set them at genesis, see [`crate::genesis::Genesis`],
+/// validators can override them through the admin interface and only reconfigure if
+/// `enable-reconfig` is set.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct EpochConfig {
+    /// The checkpoints of each epoch, the default count of the node if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoints_per_epoch: Option<u64>,
+    /// How long epochs last at most, from the end of the previous one. Epochs only end after
+    /// their checkpoints if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub epoch_duration_secs: Option<u64>,
+}
+
+/// The settings of a node its operator changes while it runs: they are reloaded from the config
+/// file on SIGHUP or through the admin interface, along with the `ttl-config` of the node, and
+/// apply to the requests served from then on. The other settings apply at the next start.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TunableConfig {
+    /// The filter of the logs, in the syntax of `RUST_LOG`, the one the node started with if
+    /// not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_filter: Option<String>,
+    /// The transactions a validator signs each second at most, unlimited if not set. The ones
+    /// over the limit are refused, certificates are always executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions_per_second: Option<u32>,
+    /// The senders whose transactions a validator refuses to sign.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub denied_senders: BTreeSet<SuiAddress>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct ConsensusConfig {
@@ -142,7 +399,14 @@ impl ConsensusConfig {
 #[serde(rename_all = "kebab-case")]
 pub struct ValidatorInfo {
     pub name: String,
+    /// The protocol key of the validator, its name in the committee.
     pub public_key: PublicKeyBytes,
+    /// The key the validator authenticates with on the network, its protocol key if not set.
+    pub network_key: Option<PublicKeyBytes>,
+    /// The key of the consensus workers of the validator, its protocol key if not set.
+    pub worker_key: Option<PublicKeyBytes>,
+    /// The key of the account holding the stake of the validator, its protocol key if not set.
+    pub account_key: Option<PublicKeyBytes>,
     pub stake: StakeUnit,
     pub delegation: StakeUnit,
     pub network_address: Multiaddr,
@@ -160,14 +424,27 @@ impl ValidatorInfo {
         &self.name
     }
 
+    /// The address of the account of the validator.
     pub fn sui_address(&self) -> SuiAddress {
-        (&self.public_key()).into()
+        (&self.account_key()).into()
     }
 
     pub fn public_key(&self) -> PublicKeyBytes {
         self.public_key
     }
 
+    pub fn network_key(&self) -> PublicKeyBytes {
+        self.network_key.unwrap_or(self.public_key)
+    }
+
+    pub fn worker_key(&self) -> PublicKeyBytes {
+        self.worker_key.unwrap_or(self.public_key)
+    }
+
+    pub fn account_key(&self) -> PublicKeyBytes {
+        self.account_key.unwrap_or(self.public_key)
+    }
+
     pub fn stake(&self) -> StakeUnit {
         self.stake
     }
@@ -245,8 +522,13 @@ enum GenesisLocation {
 
 #[cfg(test)]
 mod tests {
-    use super::Genesis;
+    use super::{
+        Genesis, LogFileConfig, LogFormat, LoggingConfig, SocketAddress, TelemetryPushConfig,
+        TunableConfig,
+    };
     use crate::{genesis, NodeConfig};
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+    use sui_types::base_types::dbg_addr;
 
     #[test]
     fn serialize_genesis_config_from_file() {
@@ -290,4 +572,72 @@ mod tests {
 
         let _template: NodeConfig = serde_yaml::from_str(TEMPLATE).unwrap();
     }
+
+    #[test]
+    fn parse_logging_config() {
+        let config: LoggingConfig = serde_yaml::from_str(
+            "format: json\nfile:\n  directory: /var/log/sui\n  max-file-size-bytes: 1000\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            LoggingConfig {
+                format: LogFormat::Json,
+                file: Some(LogFileConfig {
+                    directory: "/var/log/sui".into(),
+                    prefix: "sui-node".into(),
+                    max_file_size_bytes: Some(1000),
+                    rotation_interval_secs: None,
+                    max_files: None,
+                }),
+            }
+        );
+        assert_eq!(
+            serde_yaml::from_str::<LoggingConfig>("{}").unwrap(),
+            LoggingConfig::default()
+        );
+    }
+
+    #[test]
+    fn parse_telemetry_push_config() {
+        let config: TelemetryPushConfig =
+            serde_yaml::from_str("endpoint: https://telemetry.example.com/report\n").unwrap();
+        assert_eq!(config.endpoint, "https://telemetry.example.com/report");
+        assert_eq!(config.interval_secs, 300);
+    }
+
+    #[test]
+    fn parse_tunable_config() {
+        assert_eq!(
+            serde_yaml::from_str::<TunableConfig>("{}").unwrap(),
+            TunableConfig::default()
+        );
+
+        let config = TunableConfig {
+            log_filter: Some("info,sui_core=debug".into()),
+            max_transactions_per_second: Some(100),
+            denied_senders: [dbg_addr(1), dbg_addr(2)].into_iter().collect(),
+        };
+        let s = serde_yaml::to_string(&config).unwrap();
+        assert!(s.contains("max-transactions-per-second: 100"));
+        assert_eq!(serde_yaml::from_str::<TunableConfig>(&s).unwrap(), config);
+    }
+
+    #[test]
+    fn parse_socket_addresses() {
+        assert_eq!(
+            SocketAddress::parse("[::]:9000").unwrap(),
+            SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 9000)
+        );
+        assert_eq!(
+            SocketAddress::parse("/ip6/::1/tcp/9000").unwrap(),
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 9000)
+        );
+        assert_eq!(
+            SocketAddress::parse("/dns4/localhost/tcp/9000").unwrap(),
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 9000)
+        );
+        assert!(SocketAddress::parse("/ip4/127.0.0.1/udp/9000/quic").is_err());
+        assert!(SocketAddress::parse("localhost").is_err());
+    }
 }
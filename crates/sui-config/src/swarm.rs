@@ -51,6 +51,7 @@ impl NetworkConfig {
             .committee_size(NonZeroUsize::new(quorum_size).unwrap())
             .rng(rng)
             .build()
+            .expect("A generated network has a valid genesis")
     }
 
     pub fn generate(config_dir: &Path, quorum_size: usize) -> Self {
@@ -68,12 +69,30 @@ impl NetworkConfig {
 
         NodeConfig {
             key_pair,
+            network_key_pair: None,
+            worker_key_pair: None,
+            account_key: None,
             db_path: db_path.join(FULL_NODE_DB_PATH),
+            storage_backend: validator_config.storage_backend,
+            group_commit: validator_config.group_commit,
+            object_cache_size: validator_config.object_cache_size,
+            backup_config: None,
+            migration_config: None,
+            ttl_config: validator_config.ttl_config,
+            traffic_capture_path: None,
             network_address: utils::new_network_address(),
+            quic_config: None,
+            allowed_clients: None,
+            discovery_config: None,
             metrics_address: utils::available_local_socket_address(),
             admin_interface_port: utils::get_available_port(),
+            admin_token: None,
+            logging_config: None,
+            telemetry_push: None,
+            tunable_config: None,
             json_rpc_address: utils::available_local_socket_address(),
             websocket_address: Some(utils::available_local_socket_address()),
+            rest_address: None,
             consensus_config: None,
             enable_event_processing: true,
             enable_gossip: true,
@@ -0,0 +1,319 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The configs of a test network of validators deployed on remote hosts, possibly in several
+//! regions.
+//!
+//! A [`TestbedConfig`] lists the hosts of the validators, each given by an address the other
+//! validators reach it at and that it can listen on, such as its private address in a network
+//! peered across the regions. [`TestbedConfig::build`] makes their keys and the genesis, and
+//! gives each validator consecutive ports from the base port, after the ports of the validators
+//! before it on the same host. [`Testbed::save`] writes the genesis, the config of each
+//! validator, the committee and the network config, to be copied to the working directory of
+//! the validators on their hosts, where the configs expect the genesis and keep the stores.
+
+use anyhow::{bail, Result};
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use sui_types::base_types::SuiAddress;
+use sui_types::committee::StakeUnit;
+use sui_types::crypto::{get_key_pair_from_rng, KeypairTraits, PublicKeyBytes};
+
+use crate::builder::ConfigBuilder;
+use crate::genesis_config::{GenesisConfig, ValidatorGenesisInfo};
+use crate::{Config, NetworkConfig, NodeConfig, DEFAULT_STAKE, SUI_GENESIS_FILENAME};
+
+pub const TESTBED_COMMITTEE_FILENAME: &str = "committee.yaml";
+pub const TESTBED_NETWORK_CONFIG: &str = "network.yaml";
+
+/// The ports of a validator: its network address, the five of consensus, metrics, JSON-RPC
+/// and the admin interface.
+const PORTS_PER_VALIDATOR: u16 = 9;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestbedConfig {
+    pub validators: Vec<TestbedHost>,
+    /// The first port of the validators of each host.
+    #[serde(default = "default_base_port")]
+    pub base_port: u16,
+    /// Where the validators find the genesis and keep their stores on their hosts.
+    #[serde(default = "default_working_dir")]
+    pub working_dir: PathBuf,
+    /// Serve the validators over QUIC instead of gRPC over TCP.
+    #[serde(default)]
+    pub quic: bool,
+    /// The accounts and objects of the genesis, a few accounts funded with gas coins if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<GenesisConfig>,
+}
+
+impl Config for TestbedConfig {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestbedHost {
+    /// An IP address or DNS name.
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default = "default_stake")]
+    pub stake: StakeUnit,
+}
+
+fn default_base_port() -> u16 {
+    8080
+}
+
+fn default_working_dir() -> PathBuf {
+    PathBuf::from("/opt/sui")
+}
+
+fn default_stake() -> StakeUnit {
+    DEFAULT_STAKE
+}
+
+/// A validator of the testbed, as listed in its committee file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TestbedValidator {
+    pub name: String,
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub public_key: PublicKeyBytes,
+    pub sui_address: SuiAddress,
+    pub stake: StakeUnit,
+    pub network_address: Multiaddr,
+    pub consensus_address: Multiaddr,
+    pub metrics_address: SocketAddr,
+    pub json_rpc_address: SocketAddr,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TestbedCommittee {
+    pub validators: Vec<TestbedValidator>,
+}
+
+impl Config for TestbedCommittee {}
+
+pub struct Testbed {
+    pub network_config: NetworkConfig,
+    pub committee: TestbedCommittee,
+}
+
+impl TestbedConfig {
+    pub fn build<R: ::rand::RngCore + ::rand::CryptoRng>(self, mut rng: R) -> Result<Testbed> {
+        if self.validators.is_empty() {
+            bail!("A testbed needs at least one validator");
+        }
+
+        let mut next_slot: HashMap<&str, u16> = HashMap::new();
+        let mut ports = Vec::new();
+        for validator in &self.validators {
+            if validator.stake == 0 {
+                bail!("The validator on {} has no stake", validator.host);
+            }
+            let slot = next_slot.entry(validator.host.as_str()).or_default();
+            let first =
+                u32::from(self.base_port) + u32::from(*slot) * u32::from(PORTS_PER_VALIDATOR);
+            if first + u32::from(PORTS_PER_VALIDATOR) > u32::from(u16::MAX) + 1 {
+                bail!(
+                    "Not enough ports above {} for the validators on {}",
+                    self.base_port,
+                    validator.host
+                );
+            }
+            *slot += 1;
+            ports.push(first as u16);
+        }
+
+        let genesis_info = self
+            .validators
+            .iter()
+            .zip(&ports)
+            .map(|(validator, first)| {
+                let tcp = |port: u16| host_address(&validator.host, &format!("tcp/{port}/http"));
+                ValidatorGenesisInfo {
+                    key_pair: get_key_pair_from_rng(&mut rng).1,
+                    network_address: if self.quic {
+                        host_address(&validator.host, &format!("udp/{first}/quic"))
+                    } else {
+                        tcp(*first)
+                    },
+                    stake: validator.stake,
+                    narwhal_primary_to_primary: tcp(first + 1),
+                    narwhal_worker_to_primary: tcp(first + 2),
+                    narwhal_primary_to_worker: tcp(first + 3),
+                    narwhal_worker_to_worker: tcp(first + 4),
+                    narwhal_consensus_address: tcp(first + 5),
+                }
+            })
+            .collect();
+
+        let mut builder = ConfigBuilder::new(&self.working_dir).rng(rng);
+        if let Some(accounts) = self.accounts {
+            builder = builder.initial_accounts_config(accounts);
+        }
+        let mut network_config = builder.build_with_validators(genesis_info)?;
+
+        let genesis_path = self.working_dir.join(SUI_GENESIS_FILENAME);
+        let mut validators = Vec::new();
+        for ((config, host), first) in network_config
+            .validator_configs
+            .iter_mut()
+            .zip(&self.validators)
+            .zip(ports)
+        {
+            config.metrics_address = listen_address(first + 6);
+            config.json_rpc_address = listen_address(first + 7);
+            config.admin_interface_port = first + 8;
+            config.genesis = crate::node::Genesis::new_from_file(&genesis_path);
+
+            let public_key: PublicKeyBytes = config.key_pair.public().into();
+            validators.push(TestbedValidator {
+                name: format!("validator-{}", validators.len()),
+                host: host.host.clone(),
+                region: host.region.clone(),
+                public_key,
+                sui_address: (&public_key).into(),
+                stake: host.stake,
+                network_address: config.network_address.clone(),
+                consensus_address: config.consensus_config.as_ref().unwrap().address().clone(),
+                metrics_address: config.metrics_address,
+                json_rpc_address: config.json_rpc_address,
+            });
+        }
+
+        Ok(Testbed {
+            network_config,
+            committee: TestbedCommittee { validators },
+        })
+    }
+}
+
+impl Testbed {
+    /// Write the genesis, the configs of the validators, named as in the committee, the committee
+    /// and the network config to `dir`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        self.network_config
+            .genesis
+            .save(dir.join(SUI_GENESIS_FILENAME))?;
+        for (config, validator) in self
+            .network_config
+            .validator_configs
+            .iter()
+            .zip(&self.committee.validators)
+        {
+            config.save(dir.join(format!("{}.yaml", validator.name)))?;
+        }
+        self.committee.save(dir.join(TESTBED_COMMITTEE_FILENAME))?;
+        self.network_config.save(dir.join(TESTBED_NETWORK_CONFIG))
+    }
+
+    pub fn validator_configs(&self) -> &[NodeConfig] {
+        self.network_config.validator_configs()
+    }
+}
+
+/// The multiaddr of `host` followed by `protocols`, such as `tcp/8080/http`.
+fn host_address(host: &str, protocols: &str) -> Multiaddr {
+    let host = match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => format!("/ip4/{ip}"),
+        Ok(IpAddr::V6(ip)) => format!("/ip6/{ip}"),
+        Err(_) => format!("/dns/{host}"),
+    };
+    format!("{host}/{protocols}").parse().unwrap()
+}
+
+fn listen_address(port: u16) -> SocketAddr {
+    SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn host(host: &str, region: &str) -> TestbedHost {
+        TestbedHost {
+            host: host.to_string(),
+            region: Some(region.to_string()),
+            stake: 1,
+        }
+    }
+
+    #[test]
+    fn testbed_addresses() {
+        let config = TestbedConfig {
+            validators: vec![
+                host("10.0.0.1", "us-east-1"),
+                host("validator.eu.example.com", "eu-west-1"),
+                host("10.0.0.1", "us-east-1"),
+            ],
+            base_port: 9000,
+            working_dir: PathBuf::from("/sui"),
+            quic: false,
+            accounts: None,
+        };
+        let testbed = config.build(StdRng::from_seed([0; 32])).unwrap();
+
+        let validators = &testbed.committee.validators;
+        assert_eq!(validators.len(), 3);
+        assert_eq!(
+            validators[0].network_address.to_string(),
+            "/ip4/10.0.0.1/tcp/9000/http"
+        );
+        assert_eq!(
+            validators[1].consensus_address.to_string(),
+            "/dns/validator.eu.example.com/tcp/9005/http"
+        );
+        // The second validator of a host takes the ports after the first
+        assert_eq!(
+            validators[2].network_address.to_string(),
+            "/ip4/10.0.0.1/tcp/9009/http"
+        );
+        assert_eq!(validators[2].json_rpc_address.port(), 9016);
+        assert_eq!(validators[1].region.as_deref(), Some("eu-west-1"));
+
+        let committee = testbed.network_config.committee();
+        for (validator, config) in validators.iter().zip(testbed.validator_configs()) {
+            assert_eq!(validator.public_key, config.public_key());
+            assert_eq!(committee.weight(&validator.public_key), 1);
+            assert!(config.db_path.starts_with("/sui"));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        testbed.save(dir.path()).unwrap();
+        let saved = TestbedCommittee::load(dir.path().join(TESTBED_COMMITTEE_FILENAME)).unwrap();
+        assert_eq!(&saved.validators, validators);
+        NodeConfig::load(dir.path().join("validator-2.yaml")).unwrap();
+        crate::genesis::Genesis::load(dir.path().join(SUI_GENESIS_FILENAME)).unwrap();
+    }
+
+    #[test]
+    fn testbed_template() {
+        const TEMPLATE: &str = include_str!("../data/testbed-template.yaml");
+
+        let config: TestbedConfig = serde_yaml::from_str(TEMPLATE).unwrap();
+        assert_eq!(config.validators[2].stake, 2);
+        assert_eq!(config.validators[3].stake, DEFAULT_STAKE);
+    }
+
+    #[test]
+    fn testbed_without_ports() {
+        let config = TestbedConfig {
+            validators: vec![host("10.0.0.1", "us-east-1"), host("10.0.0.1", "us-east-1")],
+            base_port: u16::MAX - PORTS_PER_VALIDATOR,
+            working_dir: default_working_dir(),
+            quic: true,
+            accounts: None,
+        };
+        assert!(config.build(StdRng::from_seed([0; 32])).is_err());
+    }
+}
@@ -38,6 +38,12 @@ pub fn new_network_address() -> multiaddr::Multiaddr {
         .unwrap()
 }
 
+pub fn new_quic_network_address() -> multiaddr::Multiaddr {
+    format!("/dns/localhost/udp/{}/quic", get_available_port())
+        .parse()
+        .unwrap()
+}
+
 pub fn available_local_socket_address() -> std::net::SocketAddr {
     format!("127.0.0.1:{}", get_available_port())
         .parse()
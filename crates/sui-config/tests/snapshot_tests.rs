@@ -41,7 +41,7 @@ fn genesis_config_snapshot_matches() {
     for account in &mut genesis_config.accounts {
         account.address = Some(fake_addr);
         for gas_obj in &mut account.gas_objects {
-            gas_obj.object_id = fake_obj_id;
+            gas_obj.object_id = Some(fake_obj_id);
         }
     }
     assert_yaml_snapshot!(genesis_config);
@@ -49,20 +49,23 @@ fn genesis_config_snapshot_matches() {
 
 #[test]
 fn empty_genesis_snapshot_matches() {
-    let genesis = Builder::new().build();
+    let genesis = Builder::new().build().unwrap();
     assert_yaml_snapshot!(genesis);
 }
 
 #[test]
 fn populated_genesis_snapshot_matches() {
     let genesis_config = GenesisConfig::for_local_testing();
-    let (_account_keys, objects) = genesis_config
+    let (_account_keys, objects, _) = genesis_config
         .generate_accounts(&mut StdRng::from_seed([0; 32]))
         .unwrap();
     let key = get_key_pair_from_rng(&mut StdRng::from_seed([0; 32])).1;
     let validator = ValidatorInfo {
         name: "0".into(),
         public_key: key.public().into(),
+        network_key: None,
+        worker_key: None,
+        account_key: None,
         stake: 1,
         delegation: 0,
         network_address: Multiaddr::empty(),
@@ -76,7 +79,8 @@ fn populated_genesis_snapshot_matches() {
     let genesis = Builder::new()
         .add_objects(objects)
         .add_validator(validator)
-        .build();
+        .build()
+        .unwrap();
     assert_yaml_snapshot!(genesis.validator_set());
     assert_yaml_snapshot!(genesis.committee().unwrap());
     assert_yaml_snapshot!(genesis.narwhal_committee());
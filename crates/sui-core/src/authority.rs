@@ -7,7 +7,7 @@ use crate::{
     authority_batch::{BroadcastReceiver, BroadcastSender},
     checkpoints::CheckpointStore,
     event_handler::EventHandler,
-    execution_engine,
+    execution_engine, node_faults,
     query_helpers::QueryHelpers,
     transaction_input_checker,
 };
@@ -15,7 +15,10 @@ use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use chrono::prelude::*;
 use move_bytecode_utils::module_cache::SyncModuleCache;
-use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use move_core_types::{
+    language_storage::{ModuleId, StructTag},
+    resolver::ModuleResolver,
+};
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use narwhal_executor::ExecutionStateError;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
@@ -26,12 +29,14 @@ use prometheus::{
 };
 use std::ops::Deref;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
+    path::Path,
     pin::Pin,
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
+    time::Instant,
 };
 use sui_adapter::adapter;
 use sui_config::genesis::Genesis;
@@ -49,7 +54,8 @@ use sui_types::{
     error::{SuiError, SuiResult},
     fp_ensure,
     messages::*,
-    object::{Object, ObjectFormatOptions, ObjectRead},
+    object::{Object, ObjectFormatOptions, ObjectRead, PastObjectRead},
+    object_change::ObjectChange,
     storage::{BackingPackageStore, DeleteKind, Storage},
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS, SUI_SYSTEM_STATE_OBJECT_ID,
 };
@@ -74,19 +80,31 @@ pub mod move_integration_tests;
 #[path = "unit_tests/gas_tests.rs"]
 mod gas_tests;
 
+#[cfg(test)]
+#[path = "unit_tests/gas_golden_tests.rs"]
+mod gas_golden_tests;
+
+#[cfg(test)]
+#[path = "unit_tests/compatibility_tests.rs"]
+mod compatibility_tests;
+
 pub use sui_adapter::temporary_store::TemporaryStore;
 
+pub mod authority_store_cache;
+pub mod authority_store_integrity;
+pub mod authority_store_migrations;
 pub mod authority_store_tables;
 
 mod authority_store;
 pub use authority_store::{
-    AuthorityStore, GatewayStore, ResolverWrapper, SuiDataStore, UpdateType,
+    AuthorityStore, GatewayStore, ReadStore, ResolverWrapper, StoreBackup, SuiDataStore, UpdateType,
 };
 use sui_types::committee::EpochId;
 use sui_types::messages_checkpoint::{
     CheckpointRequest, CheckpointRequestType, CheckpointResponse, CheckpointSequenceNumber,
 };
 use sui_types::object::Owner;
+use sui_types::protocol_config::ProtocolConfig;
 use sui_types::sui_system_state::SuiSystemState;
 
 pub mod authority_notifier;
@@ -110,6 +128,8 @@ pub struct AuthorityMetrics {
     num_input_objs: Histogram,
     num_shared_objects: Histogram,
     batch_size: Histogram,
+    pub execute_certificate_latency: Histogram,
+    pub commit_certificate_latency: Histogram,
 
     total_consensus_txns: IntCounter,
 
@@ -129,6 +149,11 @@ const POSITIVE_INT_BUCKETS: &[f64] = &[
     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000., 10000., 20000., 50000.,
 ];
 
+// Latencies in seconds, from a tenth of a millisecond to ten seconds
+const LATENCY_SEC_BUCKETS: &[f64] = &[
+    0.0001, 0.0002, 0.0005, 0.001, 0.002, 0.005, 0.01, 0.02, 0.05, 0.1, 0.2, 0.5, 1., 2., 5., 10.,
+];
+
 impl AuthorityMetrics {
     pub fn new(registry: &prometheus::Registry) -> AuthorityMetrics {
         Self {
@@ -202,6 +227,20 @@ impl AuthorityMetrics {
                 registry,
             )
             .unwrap(),
+            execute_certificate_latency: register_histogram_with_registry!(
+                "execute_certificate_latency",
+                "Latency of executing a certificate, in seconds",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+            commit_certificate_latency: register_histogram_with_registry!(
+                "commit_certificate_latency",
+                "Latency of committing the effects of a certificate to the store, in seconds",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             total_consensus_txns: register_int_counter_with_registry!(
                 "total_consensus_txns",
                 "Total number of consensus transactions received from narwhal",
@@ -281,9 +320,13 @@ pub struct AuthorityState {
     // Epoch related information.
     /// Committee of this Sui instance.
     pub committee: ArcSwap<Committee>,
+    /// The network of this Sui instance, as identified by its genesis.
+    pub chain_id: ChainId,
     /// A global lock to halt all transaction/cert processing.
     halted: AtomicBool,
 
+    /// The protocol version the authority executes transactions at.
+    protocol_config: ProtocolConfig,
     /// Move native functions that are available to invoke
     pub(crate) _native_functions: NativeFunctionTable,
     pub(crate) move_vm: Arc<MoveVM>,
@@ -338,6 +381,10 @@ impl AuthorityState {
         self.committee.load().epoch
     }
 
+    pub fn protocol_config(&self) -> &ProtocolConfig {
+        &self.protocol_config
+    }
+
     async fn handle_transaction_impl(
         &self,
         transaction: Transaction,
@@ -361,9 +408,12 @@ impl AuthorityState {
             return Err(SuiError::ValidatorHaltedAtEpochEnd);
         }
 
-        let (_gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.database, &transaction)
-                .await?;
+        let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.database,
+            &transaction,
+            &self.protocol_config,
+        )
+        .await?;
 
         let owned_objects = input_objects.filter_owned_objects();
 
@@ -392,6 +442,7 @@ impl AuthorityState {
             self.metrics.signature_errors.inc();
             e
         })?;
+        transaction.data.check_chain_id(self.chain_id)?;
         let transaction_digest = *transaction.digest();
 
         let response = self.handle_transaction_impl(transaction).await;
@@ -431,6 +482,7 @@ impl AuthorityState {
                 err: "effects/tx digest mismatch".to_string()
             }
         );
+        certificate.data.check_chain_id(self.chain_id)?;
 
         let tx_guard = self.database.acquire_tx_guard(&certificate).await?;
 
@@ -475,6 +527,7 @@ impl AuthorityState {
 
         let digest = certificate.digest();
         debug!(?digest, "handle_confirmation_transaction");
+        certificate.data.check_chain_id(self.chain_id)?;
 
         // This acquires a lock on the tx digest to prevent multiple concurrent executions of the
         // same tx. While we don't need this for safety (tx sequencing is ultimately atomic), it is
@@ -583,6 +636,7 @@ impl AuthorityState {
         // non-transient (transaction input is invalid, move vm errors). However, all errors from
         // this function occur before we have written anything to the db, so we commit the tx
         // guard and rely on the client to retry the tx (if it was transient).
+        let execute_start = Instant::now();
         let (temporary_store, signed_effects) =
             match self.prepare_certificate(certificate, digest).await {
                 Err(e) => {
@@ -592,15 +646,24 @@ impl AuthorityState {
                 }
                 Ok(res) => res,
             };
+        self.metrics
+            .execute_certificate_latency
+            .observe(execute_start.elapsed().as_secs_f64());
 
         let input_object_count = temporary_store.objects().len();
         let shared_object_count = signed_effects.effects.shared_objects.len();
 
         // If commit_certificate returns an error, tx_guard will be dropped and the certificate
         // will be persisted in the log for later recovery.
+        node_faults::crash_point(&self.name, node_faults::BEFORE_COMMIT_CERTIFICATE);
+        let commit_start = Instant::now();
         self.commit_certificate(temporary_store, certificate, &signed_effects)
             .await
             .tap_err(|e| error!(?digest, "commit_certificate failed: {}", e))?;
+        self.metrics
+            .commit_certificate_latency
+            .observe(commit_start.elapsed().as_secs_f64());
+        node_faults::crash_point(&self.name, node_faults::AFTER_COMMIT_CERTIFICATE);
 
         // commit_certificate finished, the tx is fully committed to the store.
         tx_guard.commit_tx();
@@ -648,8 +711,12 @@ impl AuthorityState {
         TemporaryStore<Arc<AuthorityStore>>,
         SignedTransactionEffects,
     )> {
-        let (gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.database, certificate).await?;
+        let (gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.database,
+            certificate,
+            &self.protocol_config,
+        )
+        .await?;
 
         // At this point we need to check if any shared objects need locks,
         // and whether they have them.
@@ -680,6 +747,7 @@ impl AuthorityState {
             transaction_dependencies,
             &self.move_vm,
             &self._native_functions,
+            &self.protocol_config,
             gas_status,
             self.epoch(),
         );
@@ -725,6 +793,19 @@ impl AuthorityState {
         )
     }
 
+    /// The owners of the owned input objects of `cert` before it was executed.
+    fn previous_owners(&self, cert: &CertifiedTransaction) -> SuiResult<BTreeMap<ObjectID, Owner>> {
+        let mut owners = BTreeMap::new();
+        for kind in cert.data.input_objects()? {
+            if let InputObjectKind::ImmOrOwnedMoveObject((object_id, version, _)) = kind {
+                if let Some(object) = self.database.get_object_by_key(&object_id, version)? {
+                    owners.insert(object_id, object.owner);
+                }
+            }
+        }
+        Ok(owners)
+    }
+
     async fn process_one_tx(&self, seq: TxSequenceNumber, digest: &TransactionDigest) -> SuiResult {
         // Load cert and effects.
         let info = self.make_transaction_info(digest).await?;
@@ -763,6 +844,11 @@ impl AuthorityState {
             self.metrics
                 .total_events
                 .inc_by(effects.effects.events.len() as u64);
+
+            let previous_owners = self.previous_owners(&cert)?;
+            let changes =
+                ObjectChange::from_effects(&effects.effects, &previous_owners, timestamp_ms);
+            event_handler.process_object_changes(changes).await;
         }
 
         Ok(())
@@ -1013,10 +1099,45 @@ impl AuthorityState {
         checkpoints: Option<Arc<Mutex<CheckpointStore>>>,
         genesis: &Genesis,
         prometheus_registry: &prometheus::Registry,
+    ) -> Self {
+        let protocol_config = ProtocolConfig::get_for_version(genesis.protocol_version())
+            .expect("The binary supports the protocol version of genesis");
+        Self::new_with_protocol_config(
+            genesis_committee,
+            name,
+            secret,
+            store,
+            indexes,
+            event_store,
+            checkpoints,
+            genesis,
+            prometheus_registry,
+            protocol_config,
+        )
+        .await
+    }
+
+    /// Same as [`AuthorityState::new`], but executes transactions at the protocol version of
+    /// `protocol_config` instead of the one of `genesis`.
+    pub async fn new_with_protocol_config(
+        genesis_committee: Committee,
+        name: AuthorityName,
+        secret: StableSyncAuthoritySigner,
+        store: Arc<AuthorityStore>,
+        indexes: Option<Arc<IndexStore>>,
+        event_store: Option<Arc<EventStoreType>>,
+        checkpoints: Option<Arc<Mutex<CheckpointStore>>>,
+        genesis: &Genesis,
+        prometheus_registry: &prometheus::Registry,
+        protocol_config: ProtocolConfig,
     ) -> Self {
         let (tx, _rx) = tokio::sync::broadcast::channel(BROADCAST_CAPACITY);
-        let native_functions =
-            sui_framework::natives::all_natives(MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS);
+        store.object_cache.register_metrics(prometheus_registry);
+        let native_functions = sui_framework::natives::natives_for_protocol(
+            MOVE_STDLIB_ADDRESS,
+            SUI_FRAMEWORK_ADDRESS,
+            &protocol_config,
+        );
         let move_vm = Arc::new(
             adapter::new_move_vm(native_functions.clone())
                 .expect("We defined natives to not fail here"),
@@ -1044,7 +1165,9 @@ impl AuthorityState {
             name,
             secret,
             committee: ArcSwap::from(Arc::new(committee)),
+            chain_id: genesis.chain_id(),
             halted: AtomicBool::new(false),
+            protocol_config,
             _native_functions: native_functions,
             move_vm,
             database: store.clone(),
@@ -1187,6 +1310,24 @@ impl AuthorityState {
         self.database.clone()
     }
 
+    /// Back the store of the authority up into `dir` while it runs, see
+    /// [`SuiDataStore::create_backup`].
+    pub fn create_backup(&self, dir: &Path) -> SuiResult<StoreBackup> {
+        self.database.create_backup(dir)
+    }
+
+    /// Prune the transactions the authority executed before `seq`, see
+    /// [`SuiDataStore::prune_transactions`].
+    pub fn prune_transactions(&self, seq: TxSequenceNumber) -> SuiResult<usize> {
+        self.database.prune_transactions(seq)
+    }
+
+    /// Remove the transactions the authority signed before `signed_before_ms` that were not
+    /// executed, see [`SuiDataStore::remove_expired_transactions`].
+    pub fn remove_expired_transactions(&self, signed_before_ms: u64) -> SuiResult<usize> {
+        self.database.remove_expired_transactions(signed_before_ms)
+    }
+
     pub fn clone_committee(&self) -> Committee {
         self.committee.load().clone().deref().clone()
     }
@@ -1232,10 +1373,44 @@ impl AuthorityState {
         }
     }
 
+    /// Read an object at a past version, unless that version is no longer kept.
+    pub async fn get_past_object_read(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<PastObjectRead, SuiError> {
+        match self.database.get_past_object(object_id, version)? {
+            PastObjectRead::VersionFound(obj_ref, object, _) => {
+                let layout =
+                    object.get_layout(ObjectFormatOptions::default(), &self.module_cache)?;
+                Ok(PastObjectRead::VersionFound(obj_ref, object, layout))
+            }
+            read => Ok(read),
+        }
+    }
+
     pub fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>> {
         self.database.get_owner_objects(owner)
     }
 
+    pub fn get_owner_objects_page(
+        &self,
+        owner: Owner,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<Vec<ObjectInfo>> {
+        self.database.get_owner_objects_page(owner, cursor, limit)
+    }
+
+    pub fn get_objects_by_type_page(
+        &self,
+        type_: &StructTag,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> SuiResult<Vec<ObjectInfo>> {
+        self.database.get_objects_by_type_page(type_, cursor, limit)
+    }
+
     pub fn get_total_transaction_number(&self) -> Result<u64, anyhow::Error> {
         QueryHelpers::get_total_transaction_number(&self.database)
     }
@@ -1321,6 +1496,39 @@ impl AuthorityState {
         Ok(self.get_indexes()?.get_transactions_to_addr(address)?)
     }
 
+    pub async fn get_transactions_from_addr_page(
+        &self,
+        address: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<(TxSequenceNumber, TransactionDigest)>, anyhow::Error> {
+        Ok(self
+            .get_indexes()?
+            .get_transactions_from_addr_page(address, cursor, limit)?)
+    }
+
+    pub async fn get_transactions_to_addr_page(
+        &self,
+        address: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<(TxSequenceNumber, TransactionDigest)>, anyhow::Error> {
+        Ok(self
+            .get_indexes()?
+            .get_transactions_to_addr_page(address, cursor, limit)?)
+    }
+
+    pub async fn get_transactions_for_addr_page(
+        &self,
+        address: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> Result<Vec<(TxSequenceNumber, TransactionDigest)>, anyhow::Error> {
+        Ok(self
+            .get_indexes()?
+            .get_transactions_for_addr_page(address, cursor, limit)?)
+    }
+
     /// Returns a full handle to the event store, including inserts... so be careful!
     fn get_event_store(&self) -> Option<Arc<EventStoreType>> {
         self.event_handler
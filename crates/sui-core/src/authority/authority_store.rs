@@ -1,8 +1,13 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{authority_store_tables::StoreTables, *};
+use super::{
+    authority_store_cache::{ObjectCache, DEFAULT_OBJECT_CACHE_SIZE},
+    authority_store_tables::{StoreTables, PRUNED_SEQUENCE_ADDR},
+    *,
+};
 use crate::gateway_state::GatewayTxSeqNumber;
+use move_core_types::language_storage::StructTag;
 use narwhal_executor::ExecutionIndices;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
@@ -12,6 +17,10 @@ use std::iter;
 use std::path::Path;
 use std::sync::atomic::AtomicU64;
 use sui_storage::{
+    backing_store::{GroupCommitConfig, StorageBackend, StoreBatch, StoreMap},
+    backup::{self, BackupInfo},
+    db_metrics::Database,
+    migration::{MigrationConfig, MigrationReport},
     mutex_table::{LockGuard, MutexTable},
     write_ahead_log::{DBWriteAheadLog, WriteAheadLog},
     LockService,
@@ -21,12 +30,10 @@ use sui_types::batch::{SignedBatch, TxSequenceNumber};
 use sui_types::committee::EpochId;
 use sui_types::crypto::{AuthoritySignInfo, EmptySignInfo};
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
-use sui_types::object::{Owner, OBJECT_START_VERSION};
+use sui_types::object::{Owner, PastObjectRead, OBJECT_START_VERSION};
 use tokio::sync::Notify;
 use tokio_retry::strategy::{jitter, ExponentialBackoff};
 use tracing::{debug, error, info, trace};
-use typed_store::rocks::{DBBatch, DBMap};
-use typed_store::traits::Map;
 
 pub type AuthorityStore = SuiDataStore<AuthoritySignInfo>;
 pub type GatewayStore = SuiDataStore<EmptySignInfo>;
@@ -41,6 +48,18 @@ const NUM_SHARDS: usize = 4096;
 // TODO: Make a single table (e.g., called `variables`) storing all our lonely variables in one place.
 const LAST_CONSENSUS_INDEX_ADDR: u64 = 0;
 
+/// The number of transactions pruned at once by [`SuiDataStore::prune_transactions`], or
+/// removed at once by [`SuiDataStore::remove_expired_transactions`].
+const PRUNE_BATCH_SIZE: usize = 1000;
+
+/// The backups of the databases of a store, see [`SuiDataStore::create_backup`].
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StoreBackup {
+    pub tables: BackupInfo,
+    pub locks: BackupInfo,
+    pub recovery_log: BackupInfo,
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum AuthenticatedEpoch {
     Signed(SignedEpoch),
@@ -86,12 +105,32 @@ pub struct SuiDataStore<S> {
     pending_notifier: Arc<Notify>,
 
     pub(crate) tables: StoreTables<S>,
+
+    /// The latest versions of the objects recently read or written.
+    pub(crate) object_cache: ObjectCache,
 }
 
 impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
     /// Open an authority store by directory path
     pub fn open<P: AsRef<Path> + Clone>(path: P, db_options: Option<Options>) -> Self {
-        let tables = StoreTables::open_read_write(path.clone(), db_options);
+        Self::open_with_backend(path, StorageBackend::RocksDb, None, db_options)
+    }
+
+    /// Open an authority store keeping its tables in the `backend` store, committing their
+    /// writes in groups if `group_commit` is set. The locks and the recovery log are kept in
+    /// RocksDB whatever the backend.
+    pub fn open_with_backend<P: AsRef<Path>>(
+        path: P,
+        backend: StorageBackend,
+        group_commit: Option<GroupCommitConfig>,
+        db_options: Option<Options>,
+    ) -> Self {
+        // RocksDB keeps its files along the ones of the locks and of the recovery log.
+        let tables_path = match backend {
+            StorageBackend::Sled => path.as_ref().join("sled"),
+            _ => path.as_ref().to_path_buf(),
+        };
+        let tables = StoreTables::open_with_backend(tables_path, backend, group_commit, db_options);
 
         // For now, create one LockService for each SuiDataStore, and we use a specific
         // subdir of the data store directory
@@ -102,6 +141,48 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         let wal_path = path.as_ref().join("recovery_log");
         let wal = Arc::new(DBWriteAheadLog::new(wal_path));
 
+        Self::from_parts(tables, lock_service, wal)
+    }
+
+    /// Open the store at `path` in read only mode, as a secondary of the store of a running
+    /// node keeping its own files at `secondary_path`, so that tools can inspect it while the
+    /// node runs, without taking the locks of its databases. The tables must be kept in
+    /// RocksDB. Writes to the store fail, and it only sees the writes the node made before it
+    /// was opened or last caught up with it, see [`Self::try_catch_up_with_primary`]. No objects
+    /// are cached, so that they are read as of the last catch up.
+    pub fn open_read_only<P: AsRef<Path>>(
+        path: P,
+        secondary_path: P,
+        db_options: Option<Options>,
+    ) -> Self {
+        let (path, secondary_path) = (path.as_ref(), secondary_path.as_ref());
+        let tables = StoreTables::open_read_only(
+            path.to_path_buf(),
+            secondary_path.join("tables"),
+            db_options,
+        );
+        let lock_service =
+            LockService::open_read_only(path.join("lockdb"), &secondary_path.join("lockdb"), None)
+                .expect("Could not open lockdb");
+        let wal = Arc::new(DBWriteAheadLog::open_read_only(
+            path.join("recovery_log"),
+            &secondary_path.join("recovery_log"),
+        ));
+        Self::from_parts(tables, lock_service, wal).with_object_cache_size(0)
+    }
+
+    /// Catch up with the writes of the node, for the stores opened read only.
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        self.tables.try_catch_up_with_primary()?;
+        self.lock_service.try_catch_up_with_primary()?;
+        self.wal.try_catch_up_with_primary()
+    }
+
+    fn from_parts(
+        tables: StoreTables<S>,
+        lock_service: LockService,
+        wal: Arc<DBWriteAheadLog<CertifiedTransaction>>,
+    ) -> Self {
         // Get the last sequence item
         let pending_seq = tables
             .pending_execution
@@ -121,7 +202,190 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
             next_pending_seq,
             pending_notifier: Arc::new(Notify::new()),
             tables,
+            object_cache: ObjectCache::new(DEFAULT_OBJECT_CACHE_SIZE),
+        }
+    }
+
+    /// Cache up to `capacity` objects read from the store, none if it is 0.
+    pub fn with_object_cache_size(mut self, capacity: usize) -> Self {
+        self.object_cache = ObjectCache::new(capacity);
+        self
+    }
+
+    /// The RocksDB databases of the store, with their names: its tables if they are kept in
+    /// RocksDB, its locks and its recovery log.
+    pub fn databases(&self) -> Vec<(&'static str, Database)> {
+        let mut databases = vec![];
+        if let Some(tables) = self.tables.objects.store().rocksdb() {
+            databases.push(("store", tables));
+        }
+        databases.push(("lockdb", self.lock_service.db()));
+        databases.push(("recovery_log", self.wal.db()));
+        databases
+    }
+
+    /// Back the store up into `dir` while it is in use: its tables into `dir/tables`, its locks
+    /// into `dir/lockdb` and its recovery log into `dir/recovery_log`. Backing up into the same
+    /// directory again only copies the files written since the previous backups.
+    ///
+    /// The databases are backed up one after the other, the recovery log last so that it holds
+    /// the certificates that were being executed while the tables were backed up. Only stores
+    /// keeping their tables in RocksDB can be backed up.
+    pub fn create_backup(&self, dir: &Path) -> SuiResult<StoreBackup> {
+        let tables = self
+            .tables
+            .objects
+            .store()
+            .create_backup(&dir.join("tables"))?;
+        let locks = self.lock_service.create_backup(&dir.join("lockdb"))?;
+        let recovery_log = self.wal.create_backup(&dir.join("recovery_log"))?;
+        let backup = StoreBackup {
+            tables,
+            locks,
+            recovery_log,
+        };
+        info!(dir =% dir.display(), ?backup, "Backed up the authority store");
+        Ok(backup)
+    }
+
+    /// Delete all but the `keep` latest backups kept in `dir` by [`Self::create_backup`].
+    pub fn purge_old_backups(dir: &Path, keep: usize) -> SuiResult {
+        for db in ["tables", "lockdb", "recovery_log"] {
+            backup::purge_old_backups(&dir.join(db), keep)?;
+        }
+        Ok(())
+    }
+
+    /// Restore the latest backups kept in `dir` by [`Self::create_backup`] into a store at
+    /// `path`, replacing its databases. The store must not be open while it is being restored.
+    pub fn restore_backup(dir: &Path, path: &Path) -> SuiResult<StoreBackup> {
+        let tables = backup::restore_backup(&dir.join("tables"), path)?;
+        let locks = backup::restore_backup(&dir.join("lockdb"), &path.join("lockdb"))?;
+        let recovery_log =
+            backup::restore_backup(&dir.join("recovery_log"), &path.join("recovery_log"))?;
+        Ok(StoreBackup {
+            tables,
+            locks,
+            recovery_log,
+        })
+    }
+
+    /// Prune the transactions executed before `seq`, returning the number of transactions
+    /// pruned. Their certificates and effects are deleted, as are the versions of the objects
+    /// they mutated, deleted or wrapped. Their entries in the sequence of executed transactions
+    /// and the batches before `seq` are dropped by the compactions of these tables as the prune
+    /// watermark is raised, or deleted for the stores with no compaction filters. The tables are
+    /// then compacted, so that the pruned data is removed from disk rather than left behind
+    /// tombstones.
+    ///
+    /// The transactions after the latest batch are never pruned, nor is the latest batch, which
+    /// the next batches are made from.
+    pub fn prune_transactions(&self, seq: TxSequenceNumber) -> SuiResult<usize> {
+        let tables = &self.tables;
+        let last_batch = tables
+            .batches
+            .iter()
+            .skip_prior_to(&TxSequenceNumber::MAX)?
+            .next()
+            .map(|(seq, _)| seq)
+            .unwrap_or_default();
+        let seq = seq.min(last_batch);
+        let from = tables.prune_watermark.get();
+        if seq <= from {
+            return Ok(0);
+        }
+        let filtered = tables.objects.store().rocksdb().is_some();
+
+        let mut pruned = 0;
+        let mut executed = tables
+            .executed_sequence
+            .iter()
+            .skip_to(&from)?
+            .take_while(|(executed_seq, _)| *executed_seq < seq)
+            .peekable();
+        while executed.peek().is_some() {
+            let chunk: Vec<_> = executed.by_ref().take(PRUNE_BATCH_SIZE).collect();
+            let digests: Vec<_> = chunk
+                .iter()
+                .map(|(_, digests)| digests.transaction)
+                .collect();
+            let effects = tables.effects.multi_get(&digests)?;
+            let superseded = effects.iter().flatten().flat_map(|effects| {
+                let effects = &effects.effects;
+                effects
+                    .mutated
+                    .iter()
+                    .map(|(object_ref, _)| object_ref)
+                    .chain(effects.deleted.iter())
+                    .chain(effects.wrapped.iter())
+                    .filter_map(|(id, version, _)| Some(ObjectKey(*id, version.decrement().ok()?)))
+            });
+            // The chunk ends right before the first transaction that is not pruned yet.
+            let next_seq = match executed.peek() {
+                Some((next_seq, _)) => *next_seq,
+                None => seq,
+            };
+
+            let mut write_batch = tables.certificates.batch();
+            write_batch = write_batch.delete_batch(&tables.certificates, &digests)?;
+            write_batch = write_batch.delete_batch(&tables.effects, &digests)?;
+            write_batch = write_batch.delete_batch(&tables.objects, superseded)?;
+            if !filtered {
+                write_batch = write_batch.delete_batch(
+                    &tables.executed_sequence,
+                    chunk.iter().map(|(executed_seq, _)| executed_seq),
+                )?;
+            }
+            write_batch = write_batch.insert_batch(
+                &tables.pruned_sequence,
+                iter::once((PRUNED_SEQUENCE_ADDR, next_seq)),
+            )?;
+            write_batch.write()?;
+            tables.prune_watermark.raise(next_seq);
+            pruned += chunk.len();
+        }
+        drop(executed);
+
+        let mut write_batch = tables.batches.batch();
+        if !filtered {
+            let batches: Vec<_> = tables
+                .batches
+                .iter()
+                .skip_to(&from)?
+                .map(|(batch_seq, _)| batch_seq)
+                .take_while(|batch_seq| *batch_seq < seq)
+                .collect();
+            write_batch = write_batch.delete_batch(&tables.batches, batches)?;
+        }
+        write_batch
+            .insert_batch(
+                &tables.pruned_sequence,
+                iter::once((PRUNED_SEQUENCE_ADDR, seq)),
+            )?
+            .write()?;
+        tables.prune_watermark.raise(seq);
+
+        tables.certificates.compact()?;
+        tables.effects.compact()?;
+        tables.objects.compact()?;
+        tables.executed_sequence.compact()?;
+        tables.batches.compact()?;
+        info!(seq, pruned, "Pruned the executed transactions");
+        Ok(pruned)
+    }
+
+    /// Migrate the tables of the store to the latest version of their schema, see
+    /// [`StoreTables::migrate`]. The store is backed up first into the backup directory of
+    /// `config`, if any, unless it needs no migration.
+    pub fn migrate(&self, config: &MigrationConfig) -> SuiResult<MigrationReport> {
+        let report = self.tables.migrate(true)?;
+        if config.dry_run || report.migrations.is_empty() {
+            return Ok(report);
+        }
+        if let Some(backup_dir) = &config.backup_dir {
+            self.create_backup(backup_dir)?;
         }
+        self.tables.migrate(false)
     }
 
     pub async fn acquire_tx_guard<'a, 'b>(
@@ -205,6 +469,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
     }
 
     pub fn next_sequence_number(&self) -> Result<TxSequenceNumber, SuiError> {
+        // The pruned transactions may all have been compacted away.
         Ok(self
             .tables
             .executed_sequence
@@ -212,7 +477,8 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
             .skip_prior_to(&TxSequenceNumber::MAX)?
             .next()
             .map(|(v, _)| v + 1u64)
-            .unwrap_or(0))
+            .unwrap_or(0)
+            .max(self.tables.prune_watermark.get()))
     }
 
     #[cfg(test)]
@@ -315,6 +581,46 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
             .collect())
     }
 
+    /// Returns up to `limit` objects of `owner` in object id order, starting at `cursor`
+    /// (inclusive) if given.
+    pub fn get_owner_objects_page(
+        &self,
+        owner: Owner,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectInfo>, SuiError> {
+        debug!(?owner, ?cursor, limit, "get_owner_objects_page");
+        Ok(self
+            .tables
+            .owner_index
+            .iter()
+            .skip_to(&(owner, cursor.unwrap_or(ObjectID::ZERO)))?
+            .take_while(|((object_owner, _), _)| (object_owner == &owner))
+            .take(limit)
+            .map(|(_, object_info)| object_info)
+            .collect())
+    }
+
+    /// Return a page of the objects of type `type_`, ordered by object ID, starting at `cursor`
+    /// (inclusive) if given.
+    pub fn get_objects_by_type_page(
+        &self,
+        type_: &StructTag,
+        cursor: Option<ObjectID>,
+        limit: usize,
+    ) -> Result<Vec<ObjectInfo>, SuiError> {
+        debug!(?type_, ?cursor, limit, "get_objects_by_type_page");
+        Ok(self
+            .tables
+            .type_index
+            .iter()
+            .skip_to(&(type_.clone(), cursor.unwrap_or(ObjectID::ZERO)))?
+            .take_while(|((object_type, _), _)| object_type == type_)
+            .take(limit)
+            .map(|(_, object_info)| object_info)
+            .collect())
+    }
+
     pub fn get_object_by_key(
         &self,
         object_id: &ObjectID,
@@ -323,8 +629,56 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         Ok(self.tables.objects.get(&ObjectKey(*object_id, version))?)
     }
 
+    /// Read an object at a past version, along with the transaction that produced it. The
+    /// layout of the object is left for the caller to fill in.
+    pub fn get_past_object(
+        &self,
+        object_id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<PastObjectRead, SuiError> {
+        let (object_ref, transaction_digest) =
+            match self.get_parent_iterator(*object_id, Some(version))?.next() {
+                Some(parent) => parent,
+                None => {
+                    return Ok(match self.get_latest_parent_entry(*object_id)? {
+                        None => PastObjectRead::ObjectNotExists(*object_id),
+                        Some((latest_ref, _)) if latest_ref.1 < version => {
+                            PastObjectRead::VersionTooHigh {
+                                object_id: *object_id,
+                                asked_version: version,
+                                latest_version: latest_ref.1,
+                            }
+                        }
+                        Some(_) => PastObjectRead::VersionNotFound(*object_id, version),
+                    })
+                }
+            };
+        if !object_ref.2.is_alive() {
+            return Ok(PastObjectRead::ObjectDeleted(
+                object_ref,
+                transaction_digest,
+            ));
+        }
+        Ok(match self.get_object_by_key(object_id, version)? {
+            Some(object) => PastObjectRead::VersionFound(object_ref, object, None),
+            None => PastObjectRead::VersionNotFound(*object_id, version),
+        })
+    }
+
     /// Read an object and return it, or Err(ObjectNotFound) if the object was not found.
     pub fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        if let Some(object) = self.object_cache.get(object_id) {
+            return Ok(object);
+        }
+        let object = self.read_object(object_id)?;
+        if let Some(object) = &object {
+            self.object_cache.insert_read(object);
+        }
+        Ok(object)
+    }
+
+    /// Read the latest version of an object from the tables, bypassing the object cache.
+    fn read_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
         let obj_entry = self
             .tables
             .objects
@@ -509,7 +863,13 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         // Insert object
         self.tables.objects.insert(&object_ref.into(), object)?;
 
-        // Update the index
+        // Update the indexes
+        if let Some(type_) = object.type_() {
+            self.tables.type_index.insert(
+                &(type_.clone(), object_ref.0),
+                &ObjectInfo::new(&object_ref, object),
+            )?;
+        }
         if object.get_single_owner().is_some() {
             self.tables.owner_index.insert(
                 &(object.owner, object_ref.0),
@@ -526,6 +886,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         self.tables
             .parent_sync
             .insert(&object_ref, &object.previous_transaction)?;
+        self.object_cache.insert_written([object], iter::empty());
 
         Ok(())
     }
@@ -553,6 +914,13 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
                     .iter()
                     .map(|(oref, o)| ((o.owner, oref.0), ObjectInfo::new(oref, o))),
             )?
+            .insert_batch(
+                &self.tables.type_index,
+                ref_and_objects.iter().filter_map(|(oref, o)| {
+                    o.type_()
+                        .map(|type_| ((type_.clone(), oref.0), ObjectInfo::new(oref, o)))
+                }),
+            )?
             .insert_batch(
                 &self.tables.parent_sync,
                 ref_and_objects
@@ -560,6 +928,8 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
                     .map(|(oref, o)| (oref, o.previous_transaction)),
             )?
             .write()?;
+        self.object_cache
+            .insert_written(objects.iter().copied(), iter::empty());
 
         let refs: Vec<_> = ref_and_objects.iter().map(|(oref, _)| *oref).collect();
         self.lock_service
@@ -588,11 +958,65 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         // For now write transactions after because if we write before, there is a chance the lock can fail
         // and this can cause invalid transactions to be inserted in the table.
         // https://github.com/MystenLabs/sui/issues/1990
-        self.tables.transactions.insert(&tx_digest, &transaction)?;
+        let signed_at = AuthorityState::unixtime_now_ms();
+        self.tables
+            .transactions
+            .batch()
+            .insert_batch(
+                &self.tables.transactions,
+                iter::once((tx_digest, transaction)),
+            )?
+            .insert_batch(
+                &self.tables.transactions_signed_at,
+                iter::once(((signed_at, tx_digest), ())),
+            )?
+            .write()?;
 
         Ok(())
     }
 
+    /// Remove the transactions written before `signed_before_ms` that are not executed yet,
+    /// returning the number of them removed. Their locks are kept, so that the objects they
+    /// lock cannot be used by other transactions: a client can still have them certified, or
+    /// sign them again. The transactions that have a certificate are kept, only their
+    /// `transactions_signed_at` entries are dropped.
+    pub fn remove_expired_transactions(&self, signed_before_ms: u64) -> SuiResult<usize> {
+        let mut removed = 0;
+        loop {
+            let expired: Vec<_> = self
+                .tables
+                .transactions_signed_at
+                .keys()
+                .take_while(|(signed_at, _)| *signed_at < signed_before_ms)
+                .take(PRUNE_BATCH_SIZE)
+                .collect();
+            if expired.is_empty() {
+                return Ok(removed);
+            }
+            let digests: Vec<_> = expired.iter().map(|(_, digest)| *digest).collect();
+            let certified = self.tables.certificates.multi_get(&digests)?;
+            let uncertified: Vec<_> = digests
+                .into_iter()
+                .zip(certified)
+                .filter(|(_, certificate)| certificate.is_none())
+                .map(|(digest, _)| digest)
+                .collect();
+            removed += self
+                .tables
+                .transactions
+                .multi_get(&uncertified)?
+                .iter()
+                .filter(|transaction| transaction.is_some())
+                .count();
+            self.tables
+                .transactions
+                .batch()
+                .delete_batch(&self.tables.transactions, &uncertified)?
+                .delete_batch(&self.tables.transactions_signed_at, &expired)?
+                .write()?;
+        }
+    }
+
     /// This function should only be used by the gateway.
     /// It's called when we could not get a transaction to successfully execute,
     /// and have to roll back.
@@ -706,7 +1130,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
 
     async fn sequence_tx<BackingPackageStore>(
         &self,
-        write_batch: DBBatch,
+        write_batch: StoreBatch,
         temporary_store: TemporaryStore<BackingPackageStore>,
         transaction_digest: &TransactionDigest,
         proposed_seq: TxSequenceNumber,
@@ -755,7 +1179,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
     /// Helper function for updating the objects in the state
     async fn batch_update_objects<BackingPackageStore>(
         &self,
-        mut write_batch: DBBatch,
+        mut write_batch: StoreBatch,
         temporary_store: TemporaryStore<BackingPackageStore>,
         transaction_digest: TransactionDigest,
         update_type: UpdateType,
@@ -795,6 +1219,17 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         // Delete the old owner index entries
         write_batch = write_batch.delete_batch(&self.tables.owner_index, old_object_owners)?;
 
+        // Delete the type index entries of the objects deleted or wrapped, the type of the
+        // objects written does not change
+        write_batch = write_batch.delete_batch(
+            &self.tables.type_index,
+            deleted.iter().filter_map(|(id, _)| {
+                objects
+                    .get(id)
+                    .and_then(|object| object.type_().map(|type_| (type_.clone(), *id)))
+            }),
+        )?;
+
         // Index the certificate by the objects mutated
         write_batch = write_batch.insert_batch(
             &self.tables.parent_sync,
@@ -843,6 +1278,18 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
                 }),
         )?;
 
+        write_batch = write_batch.insert_batch(
+            &self.tables.type_index,
+            written.iter().filter_map(|(id, (object_ref, new_object))| {
+                new_object.type_().map(|type_| {
+                    (
+                        (type_.clone(), *id),
+                        ObjectInfo::new(object_ref, new_object),
+                    )
+                })
+            }),
+        )?;
+
         // Insert each output object into the stores
         write_batch = write_batch.insert_batch(
             &self.tables.objects,
@@ -854,6 +1301,12 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
         // Atomic write of all data other than locks
         write_batch.write()?;
         trace!("Finished writing batch");
+        self.object_cache.insert_written(
+            written.values().map(|(_, object)| object),
+            deleted
+                .iter()
+                .map(|(object_id, (version, _))| (*object_id, *version)),
+        );
 
         // Need to have a critical section for now because we need to prevent execution of older
         // certs which may overwrite newer objects with older ones.  This can be removed once we have
@@ -973,21 +1426,63 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> SuiDataStore<S> {
                         .expect("version revert should never fail"),
                 )
             });
-        let old_objects = self
+        let old_objects: Vec<_> = self
             .tables
             .objects
             .multi_get(mutated_objects)?
             .into_iter()
             .map(|obj_opt| {
                 let obj = obj_opt.expect("Older object version not found");
-                (
-                    (obj.owner, obj.id()),
-                    ObjectInfo::new(&obj.compute_object_reference(), &obj),
-                )
-            });
-        write_batch = write_batch.insert_batch(&self.tables.owner_index, old_objects)?;
+                let info = ObjectInfo::new(&obj.compute_object_reference(), &obj);
+                (obj, info)
+            })
+            .collect();
+        write_batch = write_batch.insert_batch(
+            &self.tables.owner_index,
+            old_objects
+                .iter()
+                .map(|(obj, info)| ((obj.owner, obj.id()), info)),
+        )?;
+
+        // The type index is reverted the same way, the types of the newly created objects are
+        // read from their new versions, which are only deleted by this batch.
+        let new_objects = effects
+            .created
+            .iter()
+            .chain(effects.unwrapped.iter())
+            .map(|((id, version, _), _)| ObjectKey(*id, *version));
+        let types_to_delete: Vec<_> = self
+            .tables
+            .objects
+            .multi_get(new_objects)?
+            .into_iter()
+            .flatten()
+            .filter_map(|obj| obj.type_().map(|type_| (type_.clone(), obj.id())))
+            .collect();
+        write_batch = write_batch.delete_batch(&self.tables.type_index, types_to_delete)?;
+        write_batch = write_batch.insert_batch(
+            &self.tables.type_index,
+            old_objects.iter().filter_map(|(obj, info)| {
+                obj.type_().map(|type_| ((type_.clone(), obj.id()), info))
+            }),
+        )?;
 
         write_batch.write()?;
+        self.object_cache.invalidate(
+            effects
+                .mutated
+                .iter()
+                .chain(effects.created.iter())
+                .chain(effects.unwrapped.iter())
+                .map(|((id, _, _), _)| *id)
+                .chain(
+                    effects
+                        .deleted
+                        .iter()
+                        .chain(effects.wrapped.iter())
+                        .map(|(id, _, _)| *id),
+                ),
+        );
         Ok(())
     }
 
@@ -1407,7 +1902,7 @@ impl SuiDataStore<AuthoritySignInfo> {
 }
 
 impl SuiDataStore<EmptySignInfo> {
-    pub fn pending_transactions(&self) -> &DBMap<TransactionDigest, Transaction> {
+    pub fn pending_transactions(&self) -> &StoreMap<TransactionDigest, Transaction> {
         &self.tables.transactions
     }
 }
@@ -1451,6 +1946,148 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> ModuleResolver for S
     }
 }
 
+/// The reads shared by the authority and gateway stores, which are needed to check the inputs
+/// of transactions and to answer the transaction queries of the read API.
+pub trait ReadStore {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>>;
+
+    fn get_objects(&self, objects: &[ObjectID]) -> SuiResult<Vec<Option<Object>>> {
+        objects.iter().map(|id| self.get_object(id)).collect()
+    }
+
+    fn get_sui_system_state_object(&self) -> SuiResult<SuiSystemState> {
+        let sui_system_object = self
+            .get_object(&SUI_SYSTEM_STATE_OBJECT_ID)?
+            .expect("Sui System State object must always exist");
+        let move_object = sui_system_object
+            .data
+            .try_as_move()
+            .expect("Sui System State object must be a Move object");
+        let result = bcs::from_bytes::<SuiSystemState>(move_object.contents())
+            .expect("Sui System State object deserialization cannot fail");
+        Ok(result)
+    }
+
+    fn next_sequence_number(&self) -> SuiResult<TxSequenceNumber>;
+
+    fn transactions_in_seq_range(
+        &self,
+        start: TxSequenceNumber,
+        end: TxSequenceNumber,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>>;
+
+    fn get_certified_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<CertifiedTransaction>>;
+
+    /// Returns Err(TransactionNotFound) if there are no effects for this transaction digest.
+    fn get_effects(&self, transaction_digest: &TransactionDigest) -> SuiResult<TransactionEffects>;
+
+    fn get_latest_parent_entry(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<(ObjectRef, TransactionDigest)>>;
+
+    fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>>;
+}
+
+impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> ReadStore for SuiDataStore<S> {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        SuiDataStore::get_object(self, object_id)
+    }
+
+    fn get_objects(&self, objects: &[ObjectID]) -> SuiResult<Vec<Option<Object>>> {
+        SuiDataStore::get_objects(self, objects)
+    }
+
+    fn get_sui_system_state_object(&self) -> SuiResult<SuiSystemState> {
+        SuiDataStore::get_sui_system_state_object(self)
+    }
+
+    fn next_sequence_number(&self) -> SuiResult<TxSequenceNumber> {
+        SuiDataStore::next_sequence_number(self)
+    }
+
+    fn transactions_in_seq_range(
+        &self,
+        start: TxSequenceNumber,
+        end: TxSequenceNumber,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        SuiDataStore::transactions_in_seq_range(self, start, end)
+    }
+
+    fn get_certified_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<CertifiedTransaction>> {
+        SuiDataStore::get_certified_transaction(self, transaction_digest)
+    }
+
+    fn get_effects(&self, transaction_digest: &TransactionDigest) -> SuiResult<TransactionEffects> {
+        SuiDataStore::get_effects(self, transaction_digest)
+    }
+
+    fn get_latest_parent_entry(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<(ObjectRef, TransactionDigest)>> {
+        SuiDataStore::get_latest_parent_entry(self, object_id)
+    }
+
+    fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>> {
+        SuiDataStore::get_owner_objects(self, owner)
+    }
+}
+
+impl<T: ReadStore + ?Sized> ReadStore for Arc<T> {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        self.as_ref().get_object(object_id)
+    }
+
+    fn get_objects(&self, objects: &[ObjectID]) -> SuiResult<Vec<Option<Object>>> {
+        self.as_ref().get_objects(objects)
+    }
+
+    fn get_sui_system_state_object(&self) -> SuiResult<SuiSystemState> {
+        self.as_ref().get_sui_system_state_object()
+    }
+
+    fn next_sequence_number(&self) -> SuiResult<TxSequenceNumber> {
+        self.as_ref().next_sequence_number()
+    }
+
+    fn transactions_in_seq_range(
+        &self,
+        start: TxSequenceNumber,
+        end: TxSequenceNumber,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        self.as_ref().transactions_in_seq_range(start, end)
+    }
+
+    fn get_certified_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<CertifiedTransaction>> {
+        self.as_ref().get_certified_transaction(transaction_digest)
+    }
+
+    fn get_effects(&self, transaction_digest: &TransactionDigest) -> SuiResult<TransactionEffects> {
+        self.as_ref().get_effects(transaction_digest)
+    }
+
+    fn get_latest_parent_entry(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<(ObjectRef, TransactionDigest)>> {
+        self.as_ref().get_latest_parent_entry(object_id)
+    }
+
+    fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>> {
+        self.as_ref().get_owner_objects(owner)
+    }
+}
+
 /// A wrapper to make Orphan Rule happy
 pub struct ResolverWrapper<T: ModuleResolver>(pub Arc<T>);
 
@@ -0,0 +1,124 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of the latest versions of the objects read from an authority store.
+//!
+//! Packages are objects too, so that the frameworks, which every execution reads, stay cached
+//! once read. The store updates the cache with the objects it writes and deletes, and the
+//! objects read from the store only replace cached ones of lower versions: a read that races
+//! with a write never caches an object older than the one written. Deleted and wrapped objects
+//! are cached as such, at the version they were deleted at.
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use prometheus::{IntCounter, Registry};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::Object;
+use tracing::warn;
+
+/// The number of objects cached by default.
+pub const DEFAULT_OBJECT_CACHE_SIZE: usize = 10_000;
+
+struct CachedObject {
+    version: SequenceNumber,
+    /// None if the object was deleted or wrapped at this version.
+    object: Option<Object>,
+}
+
+pub struct ObjectCache {
+    /// None if the cache is disabled.
+    objects: Option<Mutex<LruCache<ObjectID, CachedObject>>>,
+    hits: IntCounter,
+    misses: IntCounter,
+}
+
+impl ObjectCache {
+    /// A cache of `capacity` objects, disabled if the capacity is 0.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            objects: (capacity > 0).then(|| Mutex::new(LruCache::new(capacity))),
+            hits: IntCounter::new(
+                "object_cache_hits",
+                "Number of objects read from the object cache",
+            )
+            .unwrap(),
+            misses: IntCounter::new(
+                "object_cache_misses",
+                "Number of objects read from the store, missing from the object cache",
+            )
+            .unwrap(),
+        }
+    }
+
+    /// Register the hits and misses of the cache with `registry`, the cache being created
+    /// along with its store before the metrics registry is known.
+    pub fn register_metrics(&self, registry: &Registry) {
+        for counter in [&self.hits, &self.misses] {
+            if let Err(e) = registry.register(Box::new(counter.clone())) {
+                warn!("Cannot register the metrics of the object cache: {e}");
+            }
+        }
+    }
+
+    /// The latest version of the object if it is cached, None if it is deleted.
+    pub fn get(&self, object_id: &ObjectID) -> Option<Option<Object>> {
+        let objects = self.objects.as_ref()?;
+        match objects.lock().get(object_id) {
+            Some(cached) => {
+                self.hits.inc();
+                Some(cached.object.clone())
+            }
+            None => {
+                self.misses.inc();
+                None
+            }
+        }
+    }
+
+    fn insert(&self, object_id: ObjectID, version: SequenceNumber, object: Option<Object>) {
+        let objects = match &self.objects {
+            Some(objects) => objects,
+            None => return,
+        };
+        let mut objects = objects.lock();
+        match objects.peek(&object_id) {
+            Some(cached) if cached.version > version => (),
+            _ => {
+                objects.put(object_id, CachedObject { version, object });
+            }
+        }
+    }
+
+    /// Cache an object read from the store, unless a later version of it is cached.
+    pub fn insert_read(&self, object: &Object) {
+        self.insert(object.id(), object.version(), Some(object.clone()));
+    }
+
+    /// Cache the objects written to the store, and the deletion of the objects deleted or
+    /// wrapped with the versions they were deleted at.
+    pub fn insert_written<'a>(
+        &self,
+        written: impl IntoIterator<Item = &'a Object>,
+        deleted: impl IntoIterator<Item = (ObjectID, SequenceNumber)>,
+    ) {
+        if self.objects.is_none() {
+            return;
+        }
+        for object in written {
+            self.insert_read(object);
+        }
+        for (object_id, version) in deleted {
+            self.insert(object_id, version, None);
+        }
+    }
+
+    /// Forget the objects, whose versions in the store went back.
+    pub fn invalidate(&self, object_ids: impl IntoIterator<Item = ObjectID>) {
+        if let Some(objects) = &self.objects {
+            let mut objects = objects.lock();
+            for object_id in object_ids {
+                objects.pop(&object_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Integrity checks of the tables of an authority store, and the rebuild of the indexes derived
+//! from them.
+//!
+//! The objects, certificates, effects and the sequence of executed transactions are the base
+//! tables of a store. The owner and type indexes, and the transaction indexes of the
+//! `IndexStore` (by sender, recipient, object and Move function), are derived from them and can
+//! be rebuilt should they be found inconsistent.
+
+use super::{authority_store::ObjectKey, authority_store_tables::StoreTables, *};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+use sui_storage::backing_store::StoreMap;
+use sui_types::base_types::{ExecutionDigests, SequenceNumber};
+use tracing::warn;
+
+/// The number of entries written to an index at once while rebuilding it.
+const REBUILD_BATCH_SIZE: usize = 1000;
+
+/// The inconsistencies found by [`StoreTables::check_integrity`].
+#[derive(Debug, Default, Serialize)]
+pub struct IntegrityReport {
+    /// The number of entries that cannot be decoded, by table.
+    pub decode_failures: BTreeMap<&'static str, usize>,
+    /// Owner index entries of objects that do not exist, or whose latest version has another
+    /// owner or reference.
+    pub orphaned_owner_entries: Vec<(Owner, ObjectID)>,
+    /// The latest versions of objects owned by an address or an object missing from the owner
+    /// index. Shared and immutable objects are not always indexed.
+    pub unindexed_objects: Vec<ObjectRef>,
+    /// The latest versions of Move objects missing from the type index.
+    pub untyped_objects: Vec<ObjectRef>,
+    /// Objects whose consecutive versions in `parent_sync` skip some, with the versions on both
+    /// sides of the gap.
+    pub version_gaps: Vec<(ObjectID, SequenceNumber, SequenceNumber)>,
+    /// The sequence numbers on both sides of the gaps of the sequence of executed transactions.
+    pub sequence_gaps: Vec<(TxSequenceNumber, TxSequenceNumber)>,
+    /// Executed transactions whose certificate or effects are missing.
+    pub incomplete_transactions: Vec<TransactionDigest>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.decode_failures.is_empty()
+            && self.orphaned_owner_entries.is_empty()
+            && self.unindexed_objects.is_empty()
+            && self.untyped_objects.is_empty()
+            && self.version_gaps.is_empty()
+            && self.sequence_gaps.is_empty()
+            && self.incomplete_transactions.is_empty()
+    }
+
+    /// Whether rebuilding the owner index would fix the inconsistencies found in it.
+    pub fn owner_index_is_inconsistent(&self) -> bool {
+        !self.orphaned_owner_entries.is_empty()
+            || !self.unindexed_objects.is_empty()
+            || self.decode_failures.contains_key("owner_index")
+    }
+
+    /// Whether rebuilding the type index would fix the inconsistencies found in it.
+    pub fn type_index_is_inconsistent(&self) -> bool {
+        !self.untyped_objects.is_empty() || self.decode_failures.contains_key("objects_by_type")
+    }
+}
+
+/// The entries of `map` that can be decoded, counting the others in `report`.
+fn decoded_entries<'a, K, V>(
+    map: &'a StoreMap<K, V>,
+    table: &'static str,
+    report: &'a mut IntegrityReport,
+) -> SuiResult<impl Iterator<Item = (K, V)> + 'a>
+where
+    K: Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    Ok(map.checked_iter()?.filter_map(move |entry| match entry {
+        Ok(entry) => Some(entry),
+        Err(e) => {
+            warn!("Cannot decode an entry of {table}: {e}");
+            *report.decode_failures.entry(table).or_default() += 1;
+            None
+        }
+    }))
+}
+
+/// The latest version of each object of `objects`, in the order of their IDs.
+fn latest_objects(
+    objects: impl Iterator<Item = (ObjectKey, Object)>,
+) -> impl Iterator<Item = Object> {
+    let mut objects = objects.map(|(_, object)| object).peekable();
+    std::iter::from_fn(move || {
+        let mut latest = objects.next()?;
+        while let Some(object) = objects.next_if(|object| object.id() == latest.id()) {
+            latest = object;
+        }
+        Some(latest)
+    })
+}
+
+impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
+    /// Scan the tables for entries that cannot be decoded, owner index entries that do not
+    /// match the objects, gaps in the versions of objects and in the sequence of executed
+    /// transactions, and executed transactions whose certificate or effects are missing.
+    pub fn check_integrity(&self) -> SuiResult<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+
+        // The tables that are not checked otherwise are only checked for decode failures.
+        decoded_entries(&self.transactions, "transactions", &mut report)?.for_each(drop);
+        decoded_entries(
+            &self.transactions_signed_at,
+            "transactions_signed_at",
+            &mut report,
+        )?
+        .for_each(drop);
+        decoded_entries(&self.pending_execution, "pending_execution", &mut report)?.for_each(drop);
+        decoded_entries(
+            &self.assigned_object_versions,
+            "assigned_object_versions",
+            &mut report,
+        )?
+        .for_each(drop);
+        decoded_entries(
+            &self.next_object_versions,
+            "next_object_versions",
+            &mut report,
+        )?
+        .for_each(drop);
+        decoded_entries(
+            &self.consensus_message_processed,
+            "consensus_message_processed",
+            &mut report,
+        )?
+        .for_each(drop);
+        decoded_entries(&self.batches, "batches", &mut report)?.for_each(drop);
+        decoded_entries(
+            &self.last_consensus_index,
+            "last_consensus_index",
+            &mut report,
+        )?
+        .for_each(drop);
+        decoded_entries(&self.epochs, "epochs", &mut report)?.for_each(drop);
+        decoded_entries(&self.pruned_sequence, "pruned_sequence", &mut report)?.for_each(drop);
+        decoded_entries(&self.schema_version, "schema_version", &mut report)?.for_each(drop);
+        let certificates = decoded_entries(&self.certificates, "certificates", &mut report)?
+            .map(|(digest, _)| digest)
+            .collect::<BTreeSet<_>>();
+        let effects = decoded_entries(&self.effects, "effects", &mut report)?
+            .map(|(digest, _)| digest)
+            .collect::<BTreeSet<_>>();
+
+        // Owner index entries of objects that do not exist, or that are indexed under another
+        // owner or reference.
+        let mut orphaned = vec![];
+        for ((owner, object_id), info) in
+            decoded_entries(&self.owner_index, "owner_index", &mut report)?
+        {
+            let latest = self
+                .objects
+                .iter()
+                .skip_prior_to(&ObjectKey::max_for_id(&object_id))?
+                .next()
+                .filter(|(key, _)| key.0 == object_id)
+                .map(|(_, object)| object);
+            let expected = latest.map(|object| {
+                let object_ref = object.compute_object_reference();
+                (object.owner, ObjectInfo::new(&object_ref, &object))
+            });
+            if expected != Some((owner, info)) {
+                orphaned.push((owner, object_id));
+            }
+        }
+        report.orphaned_owner_entries = orphaned;
+
+        // Owned objects missing from the owner index, and Move objects missing from the type
+        // index.
+        let mut unindexed = vec![];
+        let mut untyped = vec![];
+        for object in latest_objects(decoded_entries(&self.objects, "objects", &mut report)?) {
+            if object.get_single_owner().is_some()
+                && !self
+                    .owner_index
+                    .contains_key(&(object.owner, object.id()))?
+            {
+                unindexed.push(object.compute_object_reference());
+            }
+            if let Some(type_) = object.type_() {
+                if !self
+                    .type_index
+                    .contains_key(&(type_.clone(), object.id()))?
+                {
+                    untyped.push(object.compute_object_reference());
+                }
+            }
+        }
+        decoded_entries(&self.type_index, "objects_by_type", &mut report)?.for_each(drop);
+        report.unindexed_objects = unindexed;
+        report.untyped_objects = untyped;
+
+        // The versions of an object follow each other, deleting or wrapping the object being
+        // recorded as its next version.
+        let mut gaps = vec![];
+        let mut previous: Option<(ObjectID, SequenceNumber)> = None;
+        for ((object_id, version, _), _) in
+            decoded_entries(&self.parent_sync, "parent_sync", &mut report)?
+        {
+            match previous {
+                Some((previous_id, previous_version))
+                    if previous_id == object_id && previous_version.increment() < version =>
+                {
+                    gaps.push((object_id, previous_version, version))
+                }
+                _ => (),
+            }
+            previous = Some((object_id, version));
+        }
+        report.version_gaps = gaps;
+
+        let mut gaps = vec![];
+        let mut incomplete = vec![];
+        let mut previous: Option<TxSequenceNumber> = None;
+        let pruned = self.prune_watermark.get();
+        for (seq, digests) in
+            decoded_entries(&self.executed_sequence, "executed_sequence", &mut report)?
+        {
+            // Pruned transactions are only left until they are compacted away.
+            if seq < pruned {
+                continue;
+            }
+            if let Some(previous) = previous {
+                if previous + 1 < seq {
+                    gaps.push((previous, seq));
+                }
+            }
+            previous = Some(seq);
+            if !certificates.contains(&digests.transaction)
+                || !effects.contains(&digests.transaction)
+            {
+                incomplete.push(digests.transaction);
+            }
+        }
+        report.sequence_gaps = gaps;
+        report.incomplete_transactions = incomplete;
+
+        Ok(report)
+    }
+
+    /// Rebuild the owner index from the latest versions of the objects, returning the number
+    /// of objects indexed.
+    pub fn rebuild_owner_index(&self) -> SuiResult<usize> {
+        self.rebuild_index(&self.owner_index, |object, info| {
+            Some(((object.owner, object.id()), info))
+        })
+    }
+
+    /// Rebuild the type index from the latest versions of the objects, returning the number of
+    /// objects indexed.
+    pub fn rebuild_type_index(&self) -> SuiResult<usize> {
+        self.rebuild_index(&self.type_index, |object, info| {
+            object
+                .type_()
+                .map(|type_| ((type_.clone(), object.id()), info))
+        })
+    }
+
+    /// Replace the entries of `index` with the ones `entry` makes of the latest versions of the
+    /// objects.
+    fn rebuild_index<K: Serialize + DeserializeOwned>(
+        &self,
+        index: &StoreMap<K, ObjectInfo>,
+        entry: impl Fn(&Object, ObjectInfo) -> Option<(K, ObjectInfo)>,
+    ) -> SuiResult<usize> {
+        index.clear()?;
+        let mut indexed = 0;
+        let mut entries = Vec::with_capacity(REBUILD_BATCH_SIZE);
+        let write = |entries: &mut Vec<_>| -> SuiResult {
+            index
+                .batch()
+                .insert_batch(index, entries.drain(..))?
+                .write()?;
+            Ok(())
+        };
+        for object in latest_objects(self.objects.iter()) {
+            let info = ObjectInfo::new(&object.compute_object_reference(), &object);
+            if let Some(entry) = entry(&object, info) {
+                entries.push(entry);
+                indexed += 1;
+            }
+            if entries.len() == REBUILD_BATCH_SIZE {
+                write(&mut entries)?;
+            }
+        }
+        write(&mut entries)?;
+        Ok(indexed)
+    }
+
+    /// Rebuild the transaction indexes of `indexes` from the sequence of executed transactions,
+    /// returning the number of transactions indexed. The transactions keep their timestamps, or
+    /// are given the current time if they had none.
+    pub fn rebuild_transaction_indexes(&self, indexes: &IndexStore) -> SuiResult<usize> {
+        indexes.clear_transaction_indexes()?;
+        let now = AuthorityState::unixtime_now_ms();
+        let mut indexed = 0;
+        for (seq, ExecutionDigests { transaction, .. }) in self
+            .executed_sequence
+            .iter()
+            .skip_to(&self.prune_watermark.get())?
+        {
+            let (cert, effects) = match (
+                self.certificates.get(&transaction)?,
+                self.effects.get(&transaction)?,
+            ) {
+                (Some(cert), Some(effects)) => (cert, effects),
+                _ => {
+                    warn!(digest = ?transaction, "Cannot index a transaction missing its certificate or effects");
+                    continue;
+                }
+            };
+            indexes.index_tx(
+                cert.sender_address(),
+                cert.data.input_objects()?.iter().map(|o| o.object_id()),
+                effects.effects.all_mutated(),
+                cert.data
+                    .move_calls()?
+                    .iter()
+                    .map(|mc| (mc.package.0, mc.module.clone(), mc.function.clone())),
+                seq,
+                &transaction,
+                indexes.get_timestamp_ms(&transaction)?.unwrap_or(now),
+            )?;
+            indexed += 1;
+        }
+        Ok(indexed)
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The migrations of the schema of the tables of an authority store, see
+//! [`sui_storage::migration`].
+//!
+//! A store created empty has the latest version of the schema. The stores created before their
+//! version was recorded have version 0, and are migrated through every version. A layout change
+//! of the tables adds its migration at the end of [`migrations`]: column families are created
+//! when the store is opened, so that the migrations of new tables fill them from the others.
+
+use super::{
+    authority_store_tables::{StoreTables, SCHEMA_VERSION_ADDR},
+    *,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use sui_storage::migration::{Migration, MigrationReport, Migrations};
+
+/// The migrations of the tables, in order.
+pub fn migrations<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>>(
+) -> Migrations<StoreTables<S>> {
+    Migrations::new(vec![
+        Migration {
+            version: 1,
+            description: "Index the objects by their Move type",
+            migrate: |tables| tables.rebuild_type_index().map(drop),
+        },
+        Migration {
+            version: 2,
+            description: "Record when the signed transactions were written, as of now",
+            migrate: |tables| {
+                let now = AuthorityState::unixtime_now_ms();
+                let signed_at: Vec<_> = tables
+                    .transactions
+                    .keys()
+                    .map(|digest| (now, digest))
+                    .collect();
+                tables
+                    .transactions_signed_at
+                    .batch()
+                    .insert_batch(
+                        &tables.transactions_signed_at,
+                        signed_at.into_iter().map(|key| (key, ())),
+                    )?
+                    .write()?;
+                Ok(())
+            },
+        },
+    ])
+}
+
+impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
+    /// The version of the schema the tables follow.
+    pub fn schema_version(&self) -> SuiResult<u64> {
+        Ok(self
+            .schema_version
+            .get(&SCHEMA_VERSION_ADDR)?
+            .unwrap_or_default())
+    }
+
+    /// Run the migrations from the version of the tables to the latest one, only reporting
+    /// them for dry runs. Empty tables with no version are given the latest one.
+    pub fn migrate(&self, dry_run: bool) -> SuiResult<MigrationReport> {
+        let migrations = migrations();
+        let record_version = |version| -> SuiResult {
+            Ok(self.schema_version.insert(&SCHEMA_VERSION_ADDR, &version)?)
+        };
+        if self.schema_version.is_empty() && self.objects.is_empty() {
+            let latest = migrations.latest_version();
+            if !dry_run {
+                record_version(latest)?;
+            }
+            return Ok(MigrationReport {
+                from_version: latest,
+                to_version: latest,
+                migrations: vec![],
+                dry_run,
+            });
+        }
+        migrations.run(self, self.schema_version()?, dry_run, record_version)
+    }
+}
@@ -5,21 +5,27 @@ use super::{
     authority_store::{AuthenticatedEpoch, InternalSequenceNumber, ObjectKey},
     *,
 };
+use move_core_types::language_storage::StructTag;
 use narwhal_executor::ExecutionIndices;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::path::Path;
+use std::sync::Arc;
+use sui_storage::backing_store::{
+    BackingStore, GroupCommitConfig, GroupCommitStore, RocksStore, StorageBackend, StoreMap,
+};
+use sui_storage::compaction::PruneWatermark;
 use sui_storage::default_db_options;
 use sui_types::base_types::{ExecutionDigests, SequenceNumber};
 use sui_types::batch::{SignedBatch, TxSequenceNumber};
-use typed_store::rocks::DBMap;
-use typed_store::{reopen, traits::Map};
 
 const OBJECTS_TABLE_NAME: &str = "objects";
 const OWNER_INDEX_TABLE_NAME: &str = "owner_index";
+const TYPE_INDEX_TABLE_NAME: &str = "objects_by_type";
 const TX_TABLE_NAME: &str = "transactions";
+const TX_SIGNED_AT_TABLE_NAME: &str = "transactions_signed_at";
 const CERTS_TABLE_NAME: &str = "certificates";
 const PENDING_EXECUTION: &str = "pending_execution";
 const PARENT_SYNC_TABLE_NAME: &str = "parent_sync";
@@ -31,6 +37,14 @@ const EXEC_SEQ_TABLE_NAME: &str = "executed_sequence";
 const BATCHES_TABLE_NAME: &str = "batches";
 const LAST_CONSENSUS_TABLE_NAME: &str = "last_consensus_index";
 const EPOCH_TABLE_NAME: &str = "epochs";
+const PRUNED_SEQUENCE_TABLE_NAME: &str = "pruned_sequence";
+const SCHEMA_VERSION_TABLE_NAME: &str = "schema_version";
+
+/// The key of the single entry of `pruned_sequence`.
+pub(crate) const PRUNED_SEQUENCE_ADDR: u64 = 0;
+
+/// The key of the single entry of `schema_version`.
+pub(crate) const SCHEMA_VERSION_ADDR: u64 = 0;
 
 pub struct StoreTables<S> {
     /// This is a map between the object (ID, version) and the latest state of the object, namely the
@@ -39,22 +53,32 @@ pub struct StoreTables<S> {
     ///
     /// Note that while this map can store all versions of an object, in practice it only stores
     /// the most recent version.
-    pub(crate) objects: DBMap<ObjectKey, Object>,
+    pub(crate) objects: StoreMap<ObjectKey, Object>,
 
     /// This is a an index of object references to currently existing objects, indexed by the
     /// composite key of the SuiAddress of their owner and the object ID of the object.
     /// This composite index allows an efficient iterator to list all objected currently owned
     /// by a specific user, and their object reference.
-    pub(crate) owner_index: DBMap<(Owner, ObjectID), ObjectInfo>,
+    pub(crate) owner_index: StoreMap<(Owner, ObjectID), ObjectInfo>,
+
+    /// This is an index of currently existing Move objects, indexed by the composite key of their
+    /// struct type and their object ID, to list all the objects of a type, e.g. all the
+    /// `Coin<SUI>`. Packages are not indexed.
+    pub(crate) type_index: StoreMap<(StructTag, ObjectID), ObjectInfo>,
 
     /// This is map between the transaction digest and transactions found in the `transaction_lock`.
-    pub(crate) transactions: DBMap<TransactionDigest, TransactionEnvelope<S>>,
+    pub(crate) transactions: StoreMap<TransactionDigest, TransactionEnvelope<S>>,
+
+    /// The transactions written to `transactions`, by the time they were written at in
+    /// milliseconds since the Unix epoch, to remove the ones that are never certified once they
+    /// expire. Entries are left behind when the transactions are executed, to expire in turn.
+    pub(crate) transactions_signed_at: StoreMap<(u64, TransactionDigest), ()>,
 
     /// This is a map between the transaction digest and the corresponding certificate for all
     /// certificates that have been successfully processed by this authority. This set of certificates
     /// along with the genesis allows the reconstruction of all other state, and a full sync to this
     /// authority.
-    pub(crate) certificates: DBMap<TransactionDigest, CertifiedTransaction>,
+    pub(crate) certificates: StoreMap<TransactionDigest, CertifiedTransaction>,
 
     /// The pending execution table holds a sequence of transactions that are present
     /// in the certificates table, but may not have yet been executed, and should be executed.
@@ -63,27 +87,27 @@ pub struct StoreTables<S> {
     /// reads this table and executes the certificates. The order is a hint as to their
     /// causal dependencies. Note that there is no guarantee digests are unique. Once executed, and
     /// effects are written the entry should be deleted.
-    pub(crate) pending_execution: DBMap<InternalSequenceNumber, TransactionDigest>,
+    pub(crate) pending_execution: StoreMap<InternalSequenceNumber, TransactionDigest>,
 
     /// The map between the object ref of objects processed at all versions and the transaction
     /// digest of the certificate that lead to the creation of this version of the object.
     ///
     /// When an object is deleted we include an entry into this table for its next version and
     /// a digest of ObjectDigest::deleted(), along with a link to the transaction that deleted it.
-    pub(crate) parent_sync: DBMap<ObjectRef, TransactionDigest>,
+    pub(crate) parent_sync: StoreMap<ObjectRef, TransactionDigest>,
 
     /// A map between the transaction digest of a certificate that was successfully processed
     /// (ie in `certificates`) and the effects its execution has on the authority state. This
     /// structure is used to ensure we do not double process a certificate, and that we can return
     /// the same response for any call after the first (ie. make certificate processing idempotent).
-    pub(crate) effects: DBMap<TransactionDigest, TransactionEffectsEnvelope<S>>,
+    pub(crate) effects: StoreMap<TransactionDigest, TransactionEffectsEnvelope<S>>,
 
     /// Hold the lock for shared objects. These locks are written by a single task: upon receiving a valid
     /// certified transaction from consensus, the authority assigns a lock to each shared objects of the
     /// transaction. Note that all authorities are guaranteed to assign the same lock to these objects.
     /// TODO: These two maps should be merged into a single one (no reason to have two).
-    pub(crate) assigned_object_versions: DBMap<(TransactionDigest, ObjectID), SequenceNumber>,
-    pub(crate) next_object_versions: DBMap<ObjectID, SequenceNumber>,
+    pub(crate) assigned_object_versions: StoreMap<(TransactionDigest, ObjectID), SequenceNumber>,
+    pub(crate) next_object_versions: StoreMap<ObjectID, SequenceNumber>,
 
     /// Track which transactions have been processed in handle_consensus_transaction. We must be
     /// sure to advance next_object_versions exactly once for each transaction we receive from
@@ -93,111 +117,163 @@ pub struct StoreTables<S> {
     /// Entries in this table can be garbage collected whenever we can prove that we won't receive
     /// another handle_consensus_transaction call for the given digest. This probably means at
     /// epoch change.
-    pub(crate) consensus_message_processed: DBMap<TransactionDigest, bool>,
+    pub(crate) consensus_message_processed: StoreMap<TransactionDigest, bool>,
 
     // Tables used for authority batch structure
     /// A sequence on all executed certificates and effects.
-    pub executed_sequence: DBMap<TxSequenceNumber, ExecutionDigests>,
+    pub executed_sequence: StoreMap<TxSequenceNumber, ExecutionDigests>,
 
     /// A sequence of batches indexing into the sequence of executed transactions.
-    pub batches: DBMap<TxSequenceNumber, SignedBatch>,
+    pub batches: StoreMap<TxSequenceNumber, SignedBatch>,
 
     /// The following table is used to store a single value (the corresponding key is a constant). The value
     /// represents the index of the latest consensus message this authority processed. This field is written
     /// by a single process acting as consensus (light) client. It is used to ensure the authority processes
     /// every message output by consensus (and in the right order).
-    pub(crate) last_consensus_index: DBMap<u64, ExecutionIndices>,
+    pub(crate) last_consensus_index: StoreMap<u64, ExecutionIndices>,
 
     /// Map from each epoch ID to the epoch information. The epoch is either signed by this node,
     /// or is certified (signed by a quorum).
-    pub(crate) epochs: DBMap<EpochId, AuthenticatedEpoch>,
+    pub(crate) epochs: StoreMap<EpochId, AuthenticatedEpoch>,
+
+    /// A single value (the corresponding key is a constant): the sequence number the executed
+    /// transactions were pruned before. The entries of `executed_sequence` and `batches` below
+    /// it are dropped by the compactions of their column families, which follow the
+    /// `prune_watermark` raised to this value when the tables are opened.
+    pub(crate) pruned_sequence: StoreMap<u64, TxSequenceNumber>,
+    pub(crate) prune_watermark: PruneWatermark,
+
+    /// A single value (the corresponding key is a constant): the version of the schema the
+    /// tables follow, see [`super::authority_store_migrations`]. Stores that did not record it
+    /// have version 0.
+    pub(crate) schema_version: StoreMap<u64, u64>,
 }
 impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
+    /// The tables of the store, with their RocksDB options. The tables keyed by the sequence of
+    /// executed transactions drop the entries below `prune_watermark` when they are compacted.
+    fn table_options(
+        db_options: Option<Options>,
+        prune_watermark: &PruneWatermark,
+    ) -> (Options, Vec<(&'static str, Options)>) {
+        let (options, point_lookup) = default_db_options(db_options, None);
+        let pruned = || {
+            let mut options = options.clone();
+            prune_watermark.set_compaction_filter("prune_watermark", &mut options);
+            options
+        };
+        let tables = vec![
+            (OBJECTS_TABLE_NAME, point_lookup.clone()),
+            (TX_TABLE_NAME, point_lookup.clone()),
+            (TX_SIGNED_AT_TABLE_NAME, options.clone()),
+            (OWNER_INDEX_TABLE_NAME, options.clone()),
+            (TYPE_INDEX_TABLE_NAME, options.clone()),
+            (CERTS_TABLE_NAME, point_lookup.clone()),
+            (PENDING_EXECUTION, options.clone()),
+            (PARENT_SYNC_TABLE_NAME, options.clone()),
+            (EFFECTS_TABLE_NAME, point_lookup.clone()),
+            (ASSIGNED_OBJECT_VERSIONS_TABLE_NAME, options.clone()),
+            (NEXT_OBJECT_VERSIONS_TABLE_NAME, options.clone()),
+            (CONSENSUS_MESSAGE_PROCESSED_TABLE_NAME, options.clone()),
+            (EXEC_SEQ_TABLE_NAME, pruned()),
+            (BATCHES_TABLE_NAME, pruned()),
+            (LAST_CONSENSUS_TABLE_NAME, options.clone()),
+            (EPOCH_TABLE_NAME, point_lookup),
+            (PRUNED_SEQUENCE_TABLE_NAME, options.clone()),
+            (SCHEMA_VERSION_TABLE_NAME, options.clone()),
+        ];
+        (options, tables)
+    }
+
     /// If with_secondary_path is set, the DB is opened in read only mode with the path specified
     pub fn open_impl<P: AsRef<Path>>(
         path: P,
         db_options: Option<Options>,
         with_secondary_path: Option<P>,
     ) -> Self {
-        let (options, point_lookup) = default_db_options(db_options, None);
-
-        let db = {
-            let path = &path;
-            let db_options = Some(options.clone());
-            let opt_cfs: &[(&str, &rocksdb::Options)] = &[
-                (OBJECTS_TABLE_NAME, &point_lookup),
-                (TX_TABLE_NAME, &point_lookup),
-                (OWNER_INDEX_TABLE_NAME, &options),
-                (CERTS_TABLE_NAME, &point_lookup),
-                (PENDING_EXECUTION, &options),
-                (PARENT_SYNC_TABLE_NAME, &options),
-                (EFFECTS_TABLE_NAME, &point_lookup),
-                (ASSIGNED_OBJECT_VERSIONS_TABLE_NAME, &options),
-                (NEXT_OBJECT_VERSIONS_TABLE_NAME, &options),
-                (CONSENSUS_MESSAGE_PROCESSED_TABLE_NAME, &options),
-                (EXEC_SEQ_TABLE_NAME, &options),
-                (BATCHES_TABLE_NAME, &options),
-                (LAST_CONSENSUS_TABLE_NAME, &options),
-                (EPOCH_TABLE_NAME, &point_lookup),
-            ];
-            if let Some(p) = with_secondary_path {
-                typed_store::rocks::open_cf_opts_secondary(path, Some(&p), db_options, opt_cfs)
-            } else {
-                typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
-            }
+        let prune_watermark = PruneWatermark::default();
+        let (options, tables) = Self::table_options(db_options, &prune_watermark);
+        let opt_cfs: Vec<_> = tables
+            .iter()
+            .map(|(name, options)| (*name, options))
+            .collect();
+        let store = if let Some(p) = with_secondary_path {
+            RocksStore::open_secondary(path.as_ref(), p.as_ref(), Some(options), &opt_cfs)
+        } else {
+            RocksStore::open(path.as_ref(), Some(options), &opt_cfs)
         }
         .expect("Cannot open DB.");
+        Self::from_store_with_watermark(Arc::new(store), prune_watermark)
+    }
+
+    /// Open the tables of an authority store kept in the `backend` store at `path`, committing
+    /// their writes in groups if `group_commit` is set.
+    pub fn open_with_backend<P: AsRef<Path>>(
+        path: P,
+        backend: StorageBackend,
+        group_commit: Option<GroupCommitConfig>,
+        db_options: Option<Options>,
+    ) -> Self {
+        let prune_watermark = PruneWatermark::default();
+        let (options, tables) = Self::table_options(db_options, &prune_watermark);
+        let opt_cfs: Vec<_> = tables
+            .iter()
+            .map(|(name, options)| (*name, options))
+            .collect();
+        let store = backend
+            .open(path.as_ref(), Some(options), &opt_cfs)
+            .expect("Cannot open DB.");
+        let store = match group_commit {
+            Some(config) => Arc::new(GroupCommitStore::new(store, config)),
+            None => store,
+        };
+        Self::from_store_with_watermark(store, prune_watermark)
+    }
 
-        let executed_sequence =
-            DBMap::reopen(&db, Some(EXEC_SEQ_TABLE_NAME)).expect("Cannot open CF.");
-
-        let (
-            objects,
-            owner_index,
-            transactions,
-            certificates,
-            pending_execution,
-            parent_sync,
-            effects,
-            assigned_object_versions,
-            next_object_versions,
-            consensus_message_processed,
-            batches,
-            last_consensus_index,
-            epochs,
-        ) = reopen! (
-            &db,
-            OBJECTS_TABLE_NAME;<ObjectKey, Object>,
-            OWNER_INDEX_TABLE_NAME;<(Owner, ObjectID), ObjectInfo>,
-            TX_TABLE_NAME;<TransactionDigest, TransactionEnvelope<S>>,
-            CERTS_TABLE_NAME;<TransactionDigest, CertifiedTransaction>,
-            PENDING_EXECUTION;<InternalSequenceNumber, TransactionDigest>,
-            PARENT_SYNC_TABLE_NAME;<ObjectRef, TransactionDigest>,
-            EFFECTS_TABLE_NAME;<TransactionDigest, TransactionEffectsEnvelope<S>>,
-            ASSIGNED_OBJECT_VERSIONS_TABLE_NAME;<(TransactionDigest, ObjectID), SequenceNumber>,
-            NEXT_OBJECT_VERSIONS_TABLE_NAME;<ObjectID, SequenceNumber>,
-            CONSENSUS_MESSAGE_PROCESSED_TABLE_NAME;<TransactionDigest, bool>,
-            BATCHES_TABLE_NAME;<TxSequenceNumber, SignedBatch>,
-            LAST_CONSENSUS_TABLE_NAME;<u64, ExecutionIndices>,
-            EPOCH_TABLE_NAME;<EpochId, AuthenticatedEpoch>
-        );
-
-        Self {
-            objects,
-            owner_index,
-            transactions,
-            certificates,
-            pending_execution,
-            parent_sync,
-            effects,
-            assigned_object_versions,
-            next_object_versions,
-            consensus_message_processed,
-            executed_sequence,
-            batches,
-            last_consensus_index,
-            epochs,
+    pub fn from_store(store: Arc<dyn BackingStore>) -> Self {
+        Self::from_store_with_watermark(store, PruneWatermark::default())
+    }
+
+    /// The tables of `store`, whose compaction filters follow `prune_watermark` if it has
+    /// any. The watermark is raised to the sequence number the tables were last pruned before.
+    fn from_store_with_watermark(
+        store: Arc<dyn BackingStore>,
+        prune_watermark: PruneWatermark,
+    ) -> Self {
+        let tables = Self {
+            objects: StoreMap::new(store.clone(), OBJECTS_TABLE_NAME),
+            owner_index: StoreMap::new(store.clone(), OWNER_INDEX_TABLE_NAME),
+            type_index: StoreMap::new(store.clone(), TYPE_INDEX_TABLE_NAME),
+            transactions: StoreMap::new(store.clone(), TX_TABLE_NAME),
+            transactions_signed_at: StoreMap::new(store.clone(), TX_SIGNED_AT_TABLE_NAME),
+            certificates: StoreMap::new(store.clone(), CERTS_TABLE_NAME),
+            pending_execution: StoreMap::new(store.clone(), PENDING_EXECUTION),
+            parent_sync: StoreMap::new(store.clone(), PARENT_SYNC_TABLE_NAME),
+            effects: StoreMap::new(store.clone(), EFFECTS_TABLE_NAME),
+            assigned_object_versions: StoreMap::new(
+                store.clone(),
+                ASSIGNED_OBJECT_VERSIONS_TABLE_NAME,
+            ),
+            next_object_versions: StoreMap::new(store.clone(), NEXT_OBJECT_VERSIONS_TABLE_NAME),
+            consensus_message_processed: StoreMap::new(
+                store.clone(),
+                CONSENSUS_MESSAGE_PROCESSED_TABLE_NAME,
+            ),
+            executed_sequence: StoreMap::new(store.clone(), EXEC_SEQ_TABLE_NAME),
+            batches: StoreMap::new(store.clone(), BATCHES_TABLE_NAME),
+            last_consensus_index: StoreMap::new(store.clone(), LAST_CONSENSUS_TABLE_NAME),
+            epochs: StoreMap::new(store.clone(), EPOCH_TABLE_NAME),
+            pruned_sequence: StoreMap::new(store.clone(), PRUNED_SEQUENCE_TABLE_NAME),
+            prune_watermark,
+            schema_version: StoreMap::new(store, SCHEMA_VERSION_TABLE_NAME),
+        };
+        if let Some(seq) = tables
+            .pruned_sequence
+            .get(&PRUNED_SEQUENCE_ADDR)
+            .expect("Cannot read the pruned sequence number")
+        {
+            tables.prune_watermark.raise(seq);
         }
+        tables
     }
 
     /// Open an authority store by directory path in read-write mode
@@ -214,6 +290,12 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
         Self::open_impl(path, db_options, Some(secondary_path))
     }
 
+    /// Catch up with the writes of the primary, for the tables opened in read only mode.
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        // All the tables are kept in the same store.
+        Ok(self.objects.try_catch_up_with_primary()?)
+    }
+
     // TODO: condense with macros
     pub fn dump(&self, table_name: &str) -> anyhow::Result<BTreeMap<String, String>> {
         Ok(match table_name {
@@ -232,6 +314,13 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
                     .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
                     .collect::<BTreeMap<_, _>>()
             }
+            TYPE_INDEX_TABLE_NAME => {
+                self.type_index.try_catch_up_with_primary()?;
+                self.type_index
+                    .iter()
+                    .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
+                    .collect::<BTreeMap<_, _>>()
+            }
             TX_TABLE_NAME => {
                 self.transactions.try_catch_up_with_primary()?;
                 self.transactions
@@ -240,6 +329,14 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
                     .collect::<BTreeMap<_, _>>()
             }
 
+            TX_SIGNED_AT_TABLE_NAME => {
+                self.transactions_signed_at.try_catch_up_with_primary()?;
+                self.transactions_signed_at
+                    .iter()
+                    .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
+                    .collect::<BTreeMap<_, _>>()
+            }
+
             CERTS_TABLE_NAME => {
                 self.certificates.try_catch_up_with_primary()?;
                 self.certificates
@@ -328,6 +425,22 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> StoreTables<S> {
                     .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
                     .collect::<BTreeMap<_, _>>()
             }
+
+            PRUNED_SEQUENCE_TABLE_NAME => {
+                self.pruned_sequence.try_catch_up_with_primary()?;
+                self.pruned_sequence
+                    .iter()
+                    .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
+                    .collect::<BTreeMap<_, _>>()
+            }
+
+            SCHEMA_VERSION_TABLE_NAME => {
+                self.schema_version.try_catch_up_with_primary()?;
+                self.schema_version
+                    .iter()
+                    .map(|(k, v)| (format!("{:?}", k), format!("{:?}", v)))
+                    .collect::<BTreeMap<_, _>>()
+            }
             _ => anyhow::bail!("No such table name: {}", table_name),
         })
     }
@@ -207,10 +207,19 @@ async fn test_parent_cert_exec() {
         .collect();
 
     let framework_obj_ref = genesis::get_framework_object_ref();
+    let chain_id = authorities[0].chain_id;
 
     // Make a schedule of transactions
     let gas_ref_1 = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let tx1 = crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let tx1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     // create an object and execute the cert on 3 authorities
     do_transaction(authority_clients[0], &tx1).await;
@@ -234,6 +243,7 @@ async fn test_parent_cert_exec() {
         effects1.created[0].0,
         framework_obj_ref,
         effects1.gas_object.0,
+        chain_id,
     );
 
     do_transaction(authority_clients[0], &tx2).await;
@@ -262,8 +262,15 @@ pub async fn init_configurable_authorities(
         if let BatchAction::EmitUpdateItem() = action {
             let temp_client = clients[0].borrow();
             let gas_ref = get_latest_ref(temp_client, gas_object.id()).await;
-            let transaction =
-                crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref);
+            let transaction = crate_object_move_transaction(
+                addr1,
+                &key1,
+                addr1,
+                100,
+                framework_obj_ref,
+                gas_ref,
+                states[0].chain_id,
+            );
 
             // TODO: `take` here only works when each validator has equal stake.
             for tx_client in clients
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::authority_client::AuthorityAPI;
+use crate::retry::RetryConfig;
 use crate::safe_client::SafeClient;
 use async_trait::async_trait;
 
@@ -143,6 +144,8 @@ pub struct AuthorityAggregator<A> {
     // Metrics
     pub metrics: AuthAggMetrics,
     pub timeouts: TimeoutConfig,
+    /// How requests needing a single answer are retried when no authority answered.
+    pub retry_config: RetryConfig,
 }
 
 impl<A> AuthorityAggregator<A> {
@@ -168,9 +171,15 @@ impl<A> AuthorityAggregator<A> {
                 .collect(),
             metrics,
             timeouts,
+            retry_config: RetryConfig::default(),
         }
     }
 
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
     pub fn clone_client(&self, name: &AuthorityName) -> SafeClient<A>
     where
         A: Clone,
@@ -649,8 +658,9 @@ where
         S: Send,
     {
         let start = tokio::time::Instant::now();
-        let mut delay = Duration::from_secs(1);
+        let mut attempts = 0;
         loop {
+            attempts += 1;
             let authorities_shuffled = self.committee.shuffle_by_stake(preferences, restrict_to);
             let mut authorities_shuffled = authorities_shuffled.iter();
 
@@ -741,12 +751,26 @@ where
                 }
             }
 
+            let retry = self.retry_config.has_attempts_left(attempts)
+                && authority_errors
+                    .values()
+                    .any(|error| self.retry_config.is_retryable(error));
+            if !retry {
+                return Err(SuiError::TooManyIncorrectAuthorities {
+                    errors: authority_errors
+                        .iter()
+                        .map(|(name, error)| (*name, error.clone()))
+                        .collect(),
+                });
+            }
+            let delay = self.retry_config.backoff(attempts);
             info!(
                 ?authority_errors,
-                "quorum_once_with_timeout failed on all authorities, retrying in {:?}", delay
+                attempts,
+                "quorum_once_with_timeout failed on all authorities, retrying in {:?}",
+                delay
             );
             sleep(delay).await;
-            delay = std::cmp::min(delay * 2, Duration::from_secs(5 * 60));
         }
     }
 
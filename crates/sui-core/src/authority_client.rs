@@ -4,27 +4,31 @@
 
 use crate::authority::AuthorityState;
 use async_trait::async_trait;
-use futures::{stream::BoxStream, TryStreamExt};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
 use multiaddr::Multiaddr;
 use std::sync::Arc;
 
-use sui_network::{api::ValidatorClient, tonic};
-use sui_types::{error::SuiError, messages::*};
+use sui_network::{
+    api::{validator, ValidatorClient},
+    convert::DecodeError,
+    metrics::RequestObserver,
+    pool::{Connection, ConnectionPool, PooledConnection},
+    prost::Message,
+    quic::{is_quic_address, QuicClient},
+    tcp,
+    tls::NodeCertificate,
+    tonic,
+};
+use sui_types::{base_types::AuthorityName, crypto::PublicKeyBytes, error::SuiError, messages::*};
 
 use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
 
 #[cfg(test)]
-use sui_types::{
-    base_types::ObjectID,
-    committee::Committee,
-    crypto::{KeyPair, PublicKeyBytes},
-    object::Object,
-};
+use sui_types::{base_types::ObjectID, committee::Committee, crypto::KeyPair, object::Object};
 
 use crate::epoch::reconfiguration::Reconfigurable;
 #[cfg(test)]
 use sui_config::genesis::Genesis;
-use sui_network::tonic::transport::Channel;
 
 #[async_trait]
 pub trait AuthorityAPI {
@@ -73,28 +77,100 @@ pub type BatchInfoResponseItemStream = BoxStream<'static, Result<BatchInfoRespon
 
 #[derive(Clone)]
 pub struct NetworkAuthorityClient {
-    client: ValidatorClient<tonic::transport::Channel>,
+    client: Transport,
+}
+
+#[derive(Clone)]
+enum Transport {
+    Direct(Connection),
+    Pooled {
+        pool: Arc<ConnectionPool>,
+        address: Multiaddr,
+        name: AuthorityName,
+        /// The key the validator authenticates with.
+        network_key: PublicKeyBytes,
+    },
 }
 
 impl NetworkAuthorityClient {
-    pub async fn connect(address: &Multiaddr) -> anyhow::Result<Self> {
-        let channel = mysten_network::client::connect(address).await?;
+    /// Connect over gRPC to the validator at `address`, which must authenticate with the
+    /// `network_key`. The client authenticates with a key generated for it, clients over QUIC
+    /// and the ones of nodes go through a pool instead, see [`Self::pooled`].
+    pub async fn connect(address: &Multiaddr, network_key: PublicKeyBytes) -> anyhow::Result<Self> {
+        ensure_grpc_address(address)?;
+        let certificate = NodeCertificate::ephemeral();
+        let net_config = mysten_network::config::Config::new();
+        let channel = tcp::connect(&net_config, address, &certificate, Some(network_key)).await?;
         Ok(Self::new(channel))
     }
 
-    pub fn connect_lazy(address: &Multiaddr) -> anyhow::Result<Self> {
-        let channel = mysten_network::client::connect_lazy(address)?;
+    pub fn connect_lazy(address: &Multiaddr, network_key: PublicKeyBytes) -> anyhow::Result<Self> {
+        ensure_grpc_address(address)?;
+        let certificate = NodeCertificate::ephemeral();
+        let net_config = mysten_network::config::Config::new();
+        let channel = tcp::connect_lazy(&net_config, address, &certificate, Some(network_key))?;
         Ok(Self::new(channel))
     }
 
+    /// A client of the validator `name` at `address`, sending its requests over the connection
+    /// of `pool` to the validator, which is only opened by the first request.
+    pub fn pooled(pool: Arc<ConnectionPool>, address: Multiaddr, name: AuthorityName) -> Self {
+        Self::pooled_with_network_key(pool, address, name, name)
+    }
+
+    /// A pooled client of the validator `name`, which authenticates with a network key of its
+    /// own rather than with its protocol key.
+    pub fn pooled_with_network_key(
+        pool: Arc<ConnectionPool>,
+        address: Multiaddr,
+        name: AuthorityName,
+        network_key: PublicKeyBytes,
+    ) -> Self {
+        Self {
+            client: Transport::Pooled {
+                pool,
+                address,
+                name,
+                network_key,
+            },
+        }
+    }
+
     pub fn new(channel: tonic::transport::Channel) -> Self {
         Self {
-            client: ValidatorClient::new(channel),
+            client: Transport::Direct(Connection::Grpc(channel)),
         }
     }
 
-    fn client(&self) -> ValidatorClient<tonic::transport::Channel> {
-        self.client.clone()
+    pub fn new_quic(client: QuicClient) -> Self {
+        Self {
+            client: Transport::Direct(Connection::Quic(client)),
+        }
+    }
+
+    /// Start measuring a request, for clients of a pool recording metrics.
+    fn observe<T: Message>(&self, method: &'static str, request: &T) -> Option<RequestObserver> {
+        match &self.client {
+            Transport::Direct(_) => None,
+            Transport::Pooled { pool, name, .. } => pool
+                .metrics()
+                .map(|metrics| metrics.start(name.to_string(), method, request)),
+        }
+    }
+
+    async fn connection(&self) -> Result<PooledConnection, SuiError> {
+        match &self.client {
+            Transport::Direct(connection) => Ok(connection.clone().into()),
+            Transport::Pooled {
+                pool,
+                address,
+                network_key,
+                ..
+            } => pool
+                .checkout(address, *network_key)
+                .await
+                .map_err(|error| SuiError::RpcError(error.to_string())),
+        }
     }
 }
 
@@ -104,9 +180,44 @@ impl Reconfigurable for NetworkAuthorityClient {
         true
     }
 
-    fn recreate(channel: tonic::transport::Channel) -> Self {
-        NetworkAuthorityClient::new(channel)
+    fn recreate(
+        address: &Multiaddr,
+        name: AuthorityName,
+        pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self> {
+        Ok(NetworkAuthorityClient::pooled(
+            pool.clone(),
+            address.clone(),
+            name,
+        ))
+    }
+}
+
+fn observed<T: Message>(
+    observer: Option<RequestObserver>,
+    result: Result<T, SuiError>,
+) -> Result<T, SuiError> {
+    if let Some(observer) = observer {
+        observer.finish(&result);
     }
+    result
+}
+
+/// The value carried by the response message of a request.
+fn decoded<M, T>(result: Result<M, SuiError>) -> Result<T, SuiError>
+where
+    T: TryFrom<M, Error = DecodeError>,
+{
+    Ok(T::try_from(result?)?)
+}
+
+fn ensure_grpc_address(address: &Multiaddr) -> anyhow::Result<()> {
+    if is_quic_address(address) {
+        return Err(anyhow::anyhow!(
+            "Connecting to {address} over QUIC requires a connection pool"
+        ));
+    }
+    Ok(())
 }
 
 #[async_trait]
@@ -116,11 +227,17 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         transaction: Transaction,
     ) -> Result<TransactionInfoResponse, SuiError> {
-        self.client()
-            .transaction(transaction)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::Transaction::from(transaction);
+        let observer = self.observe("transaction", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .transaction(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.transaction(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 
     /// Execute a certificate.
@@ -128,33 +245,51 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         certificate: CertifiedTransaction,
     ) -> Result<TransactionInfoResponse, SuiError> {
-        self.client()
-            .handle_certificate(certificate)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::CertifiedTransaction::from(certificate);
+        let observer = self.observe("handle_certificate", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .handle_certificate(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.handle_certificate(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 
     async fn handle_account_info_request(
         &self,
         request: AccountInfoRequest,
     ) -> Result<AccountInfoResponse, SuiError> {
-        self.client()
-            .account_info(request)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::AccountInfoRequest::from(request);
+        let observer = self.observe("account_info", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .account_info(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.account_info(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 
     async fn handle_object_info_request(
         &self,
         request: ObjectInfoRequest,
     ) -> Result<ObjectInfoResponse, SuiError> {
-        self.client()
-            .object_info(request)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::ObjectInfoRequest::from(request);
+        let observer = self.observe("object_info", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .object_info(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.object_info(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 
     /// Handle Object information requests for this account.
@@ -162,11 +297,17 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         request: TransactionInfoRequest,
     ) -> Result<TransactionInfoResponse, SuiError> {
-        self.client()
-            .transaction_info(request)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::TransactionInfoRequest::from(request);
+        let observer = self.observe("transaction_info", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .transaction_info(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.transaction_info(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 
     /// Handle Batch information requests for this authority.
@@ -174,14 +315,41 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         request: BatchInfoRequest,
     ) -> Result<BatchInfoResponseItemStream, SuiError> {
-        let stream = self
-            .client()
-            .batch_info(request)
-            .await
-            .map(tonic::Response::into_inner)?
-            .map_err(Into::into);
+        let request = validator::BatchInfoRequest::from(request);
+        let observer = self.observe("batch_info", &request);
+        let connection = self.connection().await?;
+        let stream: Result<
+            BoxStream<'static, Result<validator::BatchInfoResponseItem, SuiError>>,
+            SuiError,
+        > = match &*connection {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .batch_info(request)
+                .await
+                .map(|response| response.into_inner().map_err(Into::into).boxed())
+                .map_err(Into::into),
+            Connection::Quic(client) => client
+                .batch_info(request)
+                .await
+                .map(|stream| stream.map_err(Into::into).boxed())
+                .map_err(Into::into),
+        };
+        if let Some(observer) = &observer {
+            observer.finish(&stream.as_ref().map(|_| ()));
+        }
 
-        Ok(Box::pin(stream))
+        // The stream is in flight until it is dropped
+        Ok(stream?
+            .map(move |item| {
+                let _in_flight = &connection;
+                if let Some(observer) = &observer {
+                    match &item {
+                        Ok(item) => observer.record_received(item),
+                        Err(_) => observer.record_error(),
+                    }
+                }
+                decoded::<_, BatchInfoResponseItem>(item)
+            })
+            .boxed())
     }
 
     /// Handle Object information requests for this account.
@@ -189,11 +357,17 @@ impl AuthorityAPI for NetworkAuthorityClient {
         &self,
         request: CheckpointRequest,
     ) -> Result<CheckpointResponse, SuiError> {
-        self.client()
-            .checkpoint(request)
-            .await
-            .map(tonic::Response::into_inner)
-            .map_err(Into::into)
+        let request = validator::CheckpointRequest::from(request);
+        let observer = self.observe("checkpoint", &request);
+        let result = match &*self.connection().await? {
+            Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+                .checkpoint(request)
+                .await
+                .map(tonic::Response::into_inner),
+            Connection::Quic(client) => client.checkpoint(request).await,
+        }
+        .map_err(Into::into);
+        decoded(observed(observer, result))
     }
 }
 
@@ -222,7 +396,11 @@ impl Reconfigurable for LocalAuthorityClient {
         false
     }
 
-    fn recreate(_channel: Channel) -> Self {
+    fn recreate(
+        _address: &Multiaddr,
+        _name: AuthorityName,
+        _pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self> {
         unreachable!(); // this function should not get called because the above function returns false
     }
 }
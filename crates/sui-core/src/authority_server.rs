@@ -8,29 +8,36 @@ use crate::{
         CheckpointConsensusAdapter, CheckpointSender, ConsensusAdapter, ConsensusListener,
         ConsensusListenerMessage,
     },
+    node_faults,
+    serving_policy::{PolicyViolation, ServingPolicy},
+    traffic_capture::TrafficRecorder,
 };
 use anyhow::anyhow;
 use anyhow::Result;
 use async_trait::async_trait;
-use futures::{stream::BoxStream, TryStreamExt};
+use futures::{stream::BoxStream, TryFutureExt, TryStreamExt};
 use multiaddr::Multiaddr;
 use narwhal_crypto::traits::KeyPair;
 use prometheus::Registry;
 use std::{io, sync::Arc, time::Duration};
-use sui_config::NodeConfig;
+use sui_config::{NodeConfig, TunableConfig};
 use sui_network::{
-    api::{Validator, ValidatorServer},
+    api::{validator, Validator, ValidatorServer},
+    quic::{is_quic_address, QuicConfig, QuicServer},
+    tcp::TlsListener,
+    tls::{AllowList, NodeCertificate},
     tonic,
 };
 
-use sui_types::{error::*, messages::*};
+use sui_types::{
+    base_types::ObjectID, crypto::PublicKeyBytes, error::*, handshake::Handshake, messages::*,
+};
 use tokio::{
     sync::mpsc::{channel, Sender},
     task::JoinHandle,
 };
 
 use sui_types::messages_checkpoint::CheckpointRequest;
-use sui_types::messages_checkpoint::CheckpointResponse;
 
 use tracing::{info, Instrument};
 
@@ -44,15 +51,14 @@ const MAX_DELAY_MILLIS: u64 = 5_000; // 5 sec
 pub struct AuthorityServerHandle {
     tx_cancellation: tokio::sync::oneshot::Sender<()>,
     local_addr: Multiaddr,
-    handle: tokio::task::JoinHandle<Result<(), tonic::transport::Error>>,
+    public_key: PublicKeyBytes,
+    handle: tokio::task::JoinHandle<Result<(), io::Error>>,
 }
 
 impl AuthorityServerHandle {
     pub async fn join(self) -> Result<(), std::io::Error> {
         // Note that dropping `self.complete` would terminate the server.
-        self.handle
-            .await?
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.handle.await??;
         Ok(())
     }
 
@@ -60,15 +66,18 @@ impl AuthorityServerHandle {
         self.tx_cancellation.send(()).map_err(|_e| {
             std::io::Error::new(io::ErrorKind::Other, "could not send cancellation signal!")
         })?;
-        self.handle
-            .await?
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.handle.await??;
         Ok(())
     }
 
     pub fn address(&self) -> &Multiaddr {
         &self.local_addr
     }
+
+    /// The key the server authenticates with.
+    pub fn public_key(&self) -> PublicKeyBytes {
+        self.public_key
+    }
 }
 
 pub struct AuthorityServer {
@@ -77,6 +86,8 @@ pub struct AuthorityServer {
     consensus_adapter: ConsensusAdapter,
     min_batch_size: u64,
     max_delay: Duration,
+    certificate: NodeCertificate,
+    quic_config: QuicConfig,
 }
 
 impl AuthorityServer {
@@ -99,9 +110,22 @@ impl AuthorityServer {
             consensus_adapter,
             min_batch_size: MIN_BATCH_SIZE,
             max_delay: Duration::from_millis(MAX_DELAY_MILLIS),
+            certificate: NodeCertificate::ephemeral(),
+            quic_config: QuicConfig::default(),
         }
     }
 
+    /// The certificate to authenticate with, one for a key generated on the fly if not set.
+    pub fn with_certificate(mut self, certificate: NodeCertificate) -> Self {
+        self.certificate = certificate;
+        self
+    }
+
+    pub fn with_quic_config(mut self, config: QuicConfig) -> Self {
+        self.quic_config = config;
+        self
+    }
+
     /// Create a batch subsystem, register it with the authority state, and
     /// launch a task that manages it. Return the join handle of this task.
     pub async fn spawn_batch_subsystem(
@@ -133,22 +157,54 @@ impl AuthorityServer {
             .spawn_batch_subsystem(self.min_batch_size, self.max_delay)
             .await;
 
-        let mut server = mysten_network::config::Config::new()
-            .server_builder()
-            .add_service(ValidatorServer::new(ValidatorService {
-                state: self.state,
-                consensus_adapter: self.consensus_adapter,
-                _checkpoint_consensus_handle: None,
-            }))
-            .bind(&address)
-            .await
-            .unwrap();
-        let local_addr = server.local_addr().to_owned();
+        let service = ValidatorService {
+            state: self.state,
+            consensus_adapter: self.consensus_adapter,
+            traffic_recorder: None,
+            serving_policy: Arc::new(ServingPolicy::new(&TunableConfig::default())),
+            _checkpoint_consensus_handle: None,
+        };
+
+        let public_key = self.certificate.public_key;
+        let (tx_cancellation, rx_cancellation) = tokio::sync::oneshot::channel();
+        if is_quic_address(&address) {
+            let server = QuicServer::bind(
+                &address,
+                &self.quic_config,
+                &self.certificate,
+                AllowList::any(),
+            )
+            .await?;
+            let local_addr = server.local_addr().to_owned();
+            let handle = tokio::spawn(async move {
+                tokio::select! {
+                    result = server.serve(Some(service)) => result,
+                    _ = rx_cancellation => Ok(()),
+                }
+            });
+            return Ok(AuthorityServerHandle {
+                tx_cancellation,
+                local_addr,
+                public_key,
+                handle,
+            });
+        }
+
+        let listener = TlsListener::bind(&address, &self.certificate, AllowList::any()).await?;
+        let local_addr = listener.local_addr().to_owned();
         info!("Listening to traffic on {local_addr}");
+        let server = tonic::transport::Server::builder()
+            .add_service(ValidatorServer::new(service))
+            .serve_with_incoming_shutdown(listener.incoming(), async {
+                let _ = rx_cancellation.await;
+            });
         let handle = AuthorityServerHandle {
-            tx_cancellation: server.take_cancel_handle().unwrap(),
+            tx_cancellation,
             local_addr,
-            handle: tokio::spawn(server.serve()),
+            public_key,
+            handle: tokio::spawn(
+                server.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)),
+            ),
         };
         Ok(handle)
     }
@@ -157,6 +213,8 @@ impl AuthorityServer {
 pub struct ValidatorService {
     state: Arc<AuthorityState>,
     consensus_adapter: ConsensusAdapter,
+    traffic_recorder: Option<TrafficRecorder>,
+    serving_policy: Arc<ServingPolicy>,
     _checkpoint_consensus_handle: Option<JoinHandle<()>>,
 }
 
@@ -236,27 +294,64 @@ impl ValidatorService {
             None
         };
 
+        let traffic_recorder = config
+            .traffic_capture_path
+            .as_deref()
+            .map(TrafficRecorder::create)
+            .transpose()?;
+
         Ok(Self {
             state,
             consensus_adapter,
+            traffic_recorder,
+            serving_policy: Arc::new(ServingPolicy::new(&config.tunable_config())),
             _checkpoint_consensus_handle: checkpoint_consensus_handle,
         })
     }
+
+    /// The policy the service signs transactions under, updated when the config is reloaded.
+    pub fn serving_policy(&self) -> Arc<ServingPolicy> {
+        self.serving_policy.clone()
+    }
 }
 
 #[async_trait]
 impl Validator for ValidatorService {
+    async fn handshake(
+        &self,
+        request: tonic::Request<validator::Handshake>,
+    ) -> Result<tonic::Response<validator::Handshake>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let handshake = Handshake::new(self.state.chain_id);
+        handshake
+            .negotiate(&Handshake::try_from(request.into_inner())?)
+            .map_err(|e| tonic::Status::failed_precondition(format!("Handshake rejected: {e}")))?;
+
+        Ok(tonic::Response::new(handshake.into()))
+    }
+
     async fn transaction(
         &self,
-        request: tonic::Request<Transaction>,
-    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        let mut transaction = request.into_inner();
+        request: tonic::Request<validator::Transaction>,
+    ) -> Result<tonic::Response<validator::TransactionInfoResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let peer = request.remote_addr();
+        let mut transaction = Transaction::try_from(request.into_inner())?;
 
         transaction
             .verify()
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
         //TODO This is really really bad, we should have different types for signature-verified transactions
         transaction.is_verified = true;
+        self.serving_policy
+            .check(&transaction.sender_address())
+            .map_err(|e| match e {
+                PolicyViolation::DeniedSender(_) => tonic::Status::permission_denied(e.to_string()),
+                PolicyViolation::RateLimited(_) => tonic::Status::resource_exhausted(e.to_string()),
+            })?;
+        if let Some(recorder) = &self.traffic_recorder {
+            recorder.record_transaction(&transaction);
+        }
 
         let tx_digest = transaction.digest();
 
@@ -264,7 +359,9 @@ impl Validator for ValidatorService {
         let span = tracing::debug_span!(
             "process_tx",
             ?tx_digest,
-            tx_kind = transaction.data.kind_as_str()
+            tx_kind = transaction.data.kind_as_str(),
+            objects = ?input_object_ids(&transaction.data),
+            ?peer
         );
 
         let info = self
@@ -274,20 +371,25 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        Ok(tonic::Response::new(info))
+        Ok(tonic::Response::new(info.into()))
     }
 
     async fn handle_certificate(
         &self,
-        request: tonic::Request<CertifiedTransaction>,
-    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        let mut certificate = request.into_inner();
+        request: tonic::Request<validator::CertifiedTransaction>,
+    ) -> Result<tonic::Response<validator::TransactionInfoResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let peer = request.remote_addr();
+        let mut certificate = CertifiedTransaction::try_from(request.into_inner())?;
         // 1) Verify certificate
         certificate
             .verify(&self.state.committee.load())
             .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
         //TODO This is really really bad, we should have different types for signature verified transactions
         certificate.is_verified = true;
+        if let Some(recorder) = &self.traffic_recorder {
+            recorder.record_certificate(&certificate);
+        }
 
         // 2) Check idempotency
         let digest = certificate.digest();
@@ -297,7 +399,7 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?
         {
-            return Ok(tonic::Response::new(response));
+            return Ok(tonic::Response::new(response.into()));
         }
 
         // 3) If it's a shared object transaction and requires consensus, we need to do so.
@@ -322,7 +424,9 @@ impl Validator for ValidatorService {
         let span = tracing::debug_span!(
             "execute_transaction",
             ?tx_digest,
-            tx_kind = certificate.data.kind_as_str()
+            tx_kind = certificate.data.kind_as_str(),
+            objects = ?input_object_ids(&certificate.data),
+            ?peer
         );
 
         let response = self
@@ -332,14 +436,15 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        Ok(tonic::Response::new(response))
+        Ok(tonic::Response::new(response.into()))
     }
 
     async fn account_info(
         &self,
-        request: tonic::Request<AccountInfoRequest>,
-    ) -> Result<tonic::Response<AccountInfoResponse>, tonic::Status> {
-        let request = request.into_inner();
+        request: tonic::Request<validator::AccountInfoRequest>,
+    ) -> Result<tonic::Response<validator::AccountInfoResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let request = AccountInfoRequest::try_from(request.into_inner())?;
 
         let response = self
             .state
@@ -347,14 +452,15 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        Ok(tonic::Response::new(response))
+        Ok(tonic::Response::new(response.into()))
     }
 
     async fn object_info(
         &self,
-        request: tonic::Request<ObjectInfoRequest>,
-    ) -> Result<tonic::Response<ObjectInfoResponse>, tonic::Status> {
-        let request = request.into_inner();
+        request: tonic::Request<validator::ObjectInfoRequest>,
+    ) -> Result<tonic::Response<validator::ObjectInfoResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let request = ObjectInfoRequest::try_from(request.into_inner())?;
 
         let response = self
             .state
@@ -362,14 +468,15 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        Ok(tonic::Response::new(response))
+        Ok(tonic::Response::new(response.into()))
     }
 
     async fn transaction_info(
         &self,
-        request: tonic::Request<TransactionInfoRequest>,
-    ) -> Result<tonic::Response<TransactionInfoResponse>, tonic::Status> {
-        let request = request.into_inner();
+        request: tonic::Request<validator::TransactionInfoRequest>,
+    ) -> Result<tonic::Response<validator::TransactionInfoResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let request = TransactionInfoRequest::try_from(request.into_inner())?;
 
         let response = self
             .state
@@ -377,16 +484,18 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        Ok(tonic::Response::new(response))
+        Ok(tonic::Response::new(response.into()))
     }
 
-    type BatchInfoStream = BoxStream<'static, Result<BatchInfoResponseItem, tonic::Status>>;
+    type BatchInfoStream =
+        BoxStream<'static, Result<validator::BatchInfoResponseItem, tonic::Status>>;
 
     async fn batch_info(
         &self,
-        request: tonic::Request<BatchInfoRequest>,
+        request: tonic::Request<validator::BatchInfoRequest>,
     ) -> Result<tonic::Response<Self::BatchInfoStream>, tonic::Status> {
-        let request = request.into_inner();
+        node_faults::network_lag(&self.state.name).await;
+        let request = BatchInfoRequest::from(request.into_inner());
 
         let xstream = self
             .state
@@ -394,22 +503,32 @@ impl Validator for ValidatorService {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        let response = xstream.map_err(|e| tonic::Status::internal(e.to_string()));
+        let response = xstream
+            .map_ok(validator::BatchInfoResponseItem::from)
+            .map_err(|e| tonic::Status::internal(e.to_string()));
 
         Ok(tonic::Response::new(Box::pin(response)))
     }
 
     async fn checkpoint(
         &self,
-        request: tonic::Request<CheckpointRequest>,
-    ) -> Result<tonic::Response<CheckpointResponse>, tonic::Status> {
-        let request = request.into_inner();
+        request: tonic::Request<validator::CheckpointRequest>,
+    ) -> Result<tonic::Response<validator::CheckpointResponse>, tonic::Status> {
+        node_faults::network_lag(&self.state.name).await;
+        let request = CheckpointRequest::try_from(request.into_inner())?;
 
         let response = self
             .state
             .handle_checkpoint_request(&request)
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
-        return Ok(tonic::Response::new(response));
+        return Ok(tonic::Response::new(response.into()));
     }
 }
+
+/// The objects read by a transaction, for the logs of the spans of the requests serving it.
+fn input_object_ids(data: &TransactionData) -> Vec<ObjectID> {
+    data.input_objects()
+        .map(|inputs| inputs.iter().map(InputObjectKind::object_id).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,271 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Byzantine authorities, for tests.
+//!
+//! A [`ByzantineAuthorityClient`] wraps the client of an authority and signs with the key of the
+//! authority as a faulty one would, following the [`Misbehavior`]s of its [`ByzantineConfig`]:
+//! it signs transactions conflicting with the ones it locked objects for, answers with stale
+//! objects, withholds its votes or returns signatures of other messages. The config is shared
+//! with the test, which turns misbehaviors on and off while the authority runs, so that the
+//! checks of the clients and the aggregator see authorities turn byzantine and honest again.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use sui_network::pool::ConnectionPool;
+use sui_types::base_types::{AuthorityName, ObjectID, TransactionDigest};
+use sui_types::committee::EpochId;
+use sui_types::crypto::AuthoritySignature;
+use sui_types::error::SuiError;
+use sui_types::messages::*;
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+
+use crate::authority::StableSyncAuthoritySigner;
+use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream, LocalAuthorityClient};
+use crate::epoch::reconfiguration::Reconfigurable;
+
+#[cfg(test)]
+#[path = "unit_tests/byzantine_tests.rs"]
+mod byzantine_tests;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Misbehavior {
+    /// Sign every transaction, even one spending objects locked by another transaction.
+    SignConflicting,
+    /// Answer the requests for the latest version of an object with the first answer given.
+    StaleObjects,
+    /// Process transactions and certificates, but return neither of their signatures.
+    WithholdVotes,
+    /// Return the signatures of other transactions and effects than the ones returned.
+    CorruptSignatures,
+}
+
+/// The misbehaviors of an authority, shared by its client and the test. An authority is honest
+/// until told otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct ByzantineConfig {
+    misbehaviors: Arc<Mutex<BTreeSet<Misbehavior>>>,
+}
+
+impl ByzantineConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(misbehaviors: impl IntoIterator<Item = Misbehavior>) -> Self {
+        let config = Self::new();
+        for misbehavior in misbehaviors {
+            config.set(misbehavior);
+        }
+        config
+    }
+
+    pub fn set(&self, misbehavior: Misbehavior) {
+        debug!("Byzantine authority: {misbehavior:?}");
+        self.misbehaviors.lock().unwrap().insert(misbehavior);
+    }
+
+    pub fn unset(&self, misbehavior: Misbehavior) {
+        self.misbehaviors.lock().unwrap().remove(&misbehavior);
+    }
+
+    /// Turn the authority honest again.
+    pub fn clear(&self) {
+        self.misbehaviors.lock().unwrap().clear();
+    }
+
+    pub fn has(&self, misbehavior: Misbehavior) -> bool {
+        self.misbehaviors.lock().unwrap().contains(&misbehavior)
+    }
+}
+
+/// The client of an authority misbehaving as told by its [`ByzantineConfig`].
+#[derive(Clone)]
+pub struct ByzantineAuthorityClient<A> {
+    name: AuthorityName,
+    epoch: EpochId,
+    secret: StableSyncAuthoritySigner,
+    inner: A,
+    config: ByzantineConfig,
+    /// The first answer to a request for the latest version of each object.
+    first_objects: Arc<Mutex<BTreeMap<ObjectID, ObjectInfoResponse>>>,
+}
+
+impl<A> ByzantineAuthorityClient<A> {
+    /// Wrap `inner`, the client of the authority `name` of the committee of `epoch` that signs
+    /// with `secret`.
+    pub fn new(
+        name: AuthorityName,
+        epoch: EpochId,
+        secret: StableSyncAuthoritySigner,
+        inner: A,
+        config: ByzantineConfig,
+    ) -> Self {
+        Self {
+            name,
+            epoch,
+            secret,
+            inner,
+            config,
+            first_objects: Default::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn config(&self) -> &ByzantineConfig {
+        &self.config
+    }
+
+    fn sign_transaction(&self, transaction: Transaction) -> SignedTransaction {
+        SignedTransaction::new(self.epoch, transaction, self.name, &*self.secret)
+    }
+
+    /// Apply the misbehaviors on the votes of `response`.
+    fn misbehave(&self, mut response: TransactionInfoResponse) -> TransactionInfoResponse {
+        if self.config.has(Misbehavior::CorruptSignatures) {
+            if let Some(signed) = &mut response.signed_transaction {
+                let mut data = signed.data.clone();
+                data.gas_budget += 1;
+                signed.auth_sign_info.signature = AuthoritySignature::new(&data, &*self.secret);
+            }
+            if let Some(signed) = &mut response.signed_effects {
+                let mut effects = signed.effects.clone();
+                effects.dependencies.push(TransactionDigest::genesis());
+                signed.auth_signature.signature = AuthoritySignature::new(&effects, &*self.secret);
+            }
+        }
+        if self.config.has(Misbehavior::WithholdVotes) {
+            response.signed_transaction = None;
+            response.signed_effects = None;
+        }
+        response
+    }
+}
+
+impl ByzantineAuthorityClient<LocalAuthorityClient> {
+    /// Wrap the client of a local authority, signing with its key in its current epoch.
+    pub fn new_local(client: LocalAuthorityClient, config: ByzantineConfig) -> Self {
+        let state = client.state.clone();
+        Self::new(
+            state.name,
+            state.committee.load().epoch,
+            state.secret.clone(),
+            client,
+            config,
+        )
+    }
+}
+
+impl<A> Reconfigurable for ByzantineAuthorityClient<A> {
+    fn needs_network_recreation() -> bool {
+        false
+    }
+
+    fn recreate(
+        _address: &Multiaddr,
+        _name: AuthorityName,
+        _pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self> {
+        unreachable!(); // byzantine clients keep the clients they wrap and their key across epochs
+    }
+}
+
+#[async_trait]
+impl<A> AuthorityAPI for ByzantineAuthorityClient<A>
+where
+    A: AuthorityAPI + Send + Sync,
+{
+    async fn handle_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        let response = match self.inner.handle_transaction(transaction.clone()).await {
+            Ok(response) => response,
+            Err(error) if self.config.has(Misbehavior::SignConflicting) => {
+                debug!(
+                    "Byzantine authority {} signs {:?} rejected with {error}",
+                    self.name,
+                    transaction.digest()
+                );
+                TransactionInfoResponse {
+                    signed_transaction: Some(self.sign_transaction(transaction)),
+                    certified_transaction: None,
+                    signed_effects: None,
+                }
+            }
+            Err(error) => return Err(error),
+        };
+        Ok(self.misbehave(response))
+    }
+
+    async fn handle_certificate(
+        &self,
+        certificate: CertifiedTransaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        let response = self.inner.handle_certificate(certificate).await?;
+        Ok(self.misbehave(response))
+    }
+
+    async fn handle_account_info_request(
+        &self,
+        request: AccountInfoRequest,
+    ) -> Result<AccountInfoResponse, SuiError> {
+        self.inner.handle_account_info_request(request).await
+    }
+
+    async fn handle_object_info_request(
+        &self,
+        request: ObjectInfoRequest,
+    ) -> Result<ObjectInfoResponse, SuiError> {
+        let response = self
+            .inner
+            .handle_object_info_request(request.clone())
+            .await?;
+        if !matches!(
+            request.request_kind,
+            ObjectInfoRequestKind::LatestObjectInfo(_)
+        ) {
+            return Ok(response);
+        }
+        let mut first_objects = self.first_objects.lock().unwrap();
+        let first = first_objects
+            .entry(request.object_id)
+            .or_insert_with(|| response.clone());
+        if self.config.has(Misbehavior::StaleObjects) {
+            return Ok(first.clone());
+        }
+        Ok(response)
+    }
+
+    async fn handle_transaction_info_request(
+        &self,
+        request: TransactionInfoRequest,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        let response = self.inner.handle_transaction_info_request(request).await?;
+        Ok(self.misbehave(response))
+    }
+
+    async fn handle_batch_stream(
+        &self,
+        request: BatchInfoRequest,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        self.inner.handle_batch_stream(request).await
+    }
+
+    async fn handle_checkpoint(
+        &self,
+        request: CheckpointRequest,
+    ) -> Result<CheckpointResponse, SuiError> {
+        self.inner.handle_checkpoint(request).await
+    }
+}
@@ -12,6 +12,7 @@ use narwhal_executor::ExecutionIndices;
 use rocksdb::Options;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, path::Path, sync::Arc};
+use sui_config::EpochConfig;
 use sui_storage::default_db_options;
 use sui_types::messages_checkpoint::CheckpointProposal;
 use sui_types::{
@@ -35,6 +36,7 @@ use typed_store::{
 
 use crate::authority_active::checkpoint_driver::CheckpointMetrics;
 use crate::checkpoints::causal_order_effects::CausalOrder;
+use crate::epoch::epoch_timer::{EpochProgress, EpochTimer};
 use crate::{
     authority::StableSyncAuthoritySigner,
     authority_active::execution_driver::PendCertificateForExecution,
@@ -65,6 +67,9 @@ pub struct CheckpointLocals {
     // True if no more fragments are to be added.
     pub no_more_fragments: bool,
 
+    // Where the current epoch and its change started.
+    pub epoch_progress: EpochProgress,
+
     // The current checkpoint proposal if any
     #[serde(skip)]
     pub current_proposal: Option<CheckpointProposal>,
@@ -133,6 +138,9 @@ pub struct CheckpointStore {
     /// A single entry table to store locals.
     pub locals: DBMap<DBLabel, CheckpointLocals>,
 
+    /// When the epochs end.
+    pub epoch_timer: EpochTimer,
+
     // Consensus sender
     sender: Option<Box<dyn ConsensusSender>>,
 }
@@ -279,6 +287,7 @@ impl CheckpointStore {
             fragments,
             memory_locals: None,
             locals,
+            epoch_timer: EpochTimer::new(&EpochConfig::default()),
             sender: None,
         };
 
@@ -812,13 +821,33 @@ impl CheckpointStore {
     }
 
     pub fn is_ready_to_start_epoch_change(&mut self) -> bool {
-        let next_seq = self.next_checkpoint();
-        next_seq % CHECKPOINT_COUNT_PER_EPOCH == 0 && next_seq != 0
+        let locals = self.get_locals();
+        self.epoch_timer
+            .is_ready_to_start_epoch_change(&locals.epoch_progress, locals.next_checkpoint)
     }
 
     pub fn is_ready_to_finish_epoch_change(&mut self) -> bool {
-        let next_seq = self.next_checkpoint();
-        next_seq % CHECKPOINT_COUNT_PER_EPOCH == 1 && next_seq != 1
+        let locals = self.get_locals();
+        self.epoch_timer
+            .is_ready_to_finish_epoch_change(&locals.epoch_progress, locals.next_checkpoint)
+    }
+
+    /// Record that the change of epoch started at the next checkpoint.
+    pub fn epoch_change_started(&mut self) -> Result<(), SuiError> {
+        let locals = self.get_locals();
+        let mut new_locals = locals.as_ref().clone();
+        self.epoch_timer
+            .epoch_change_started(&mut new_locals.epoch_progress, locals.next_checkpoint);
+        self.set_locals(locals, new_locals)
+    }
+
+    /// Record that the change of epoch finished, the next epoch starting at the next checkpoint.
+    pub fn epoch_change_finished(&mut self) -> Result<(), SuiError> {
+        let locals = self.get_locals();
+        let mut new_locals = locals.as_ref().clone();
+        self.epoch_timer
+            .epoch_change_finished(&mut new_locals.epoch_progress, locals.next_checkpoint);
+        self.set_locals(locals, new_locals)
     }
 
     // Helper write functions
@@ -167,6 +167,51 @@ fn crash_recovery() {
     );
 }
 
+#[test]
+fn epoch_change_crash_recovery() {
+    let mut rng = StdRng::from_seed(RNG_SEED);
+    let (keys, committee) = make_committee_key(&mut rng);
+    let k = keys[0].copy();
+
+    let path = env::temp_dir().join(format!("SC_{:?}", ObjectID::random()));
+    fs::create_dir(&path).unwrap();
+    let open = || {
+        CheckpointStore::open(
+            path.clone(),
+            None,
+            committee.epoch,
+            k.public().into(),
+            Arc::pin(k.copy()),
+        )
+        .unwrap()
+    };
+
+    // The change of epoch starts at the last checkpoint of the epoch
+    let mut cps = open();
+    let mut locals = cps.get_locals().as_ref().clone();
+    locals.next_checkpoint = CHECKPOINT_COUNT_PER_EPOCH;
+    cps.set_locals_for_testing(locals).unwrap();
+    assert!(cps.is_ready_to_start_epoch_change());
+    cps.epoch_change_started().unwrap();
+    assert!(!cps.is_ready_to_start_epoch_change());
+    drop(cps);
+
+    // It is still ongoing after a restart, and finishes at the next checkpoint
+    let mut cps = open();
+    assert!(!cps.is_ready_to_start_epoch_change());
+    assert!(!cps.is_ready_to_finish_epoch_change());
+    let mut locals = cps.get_locals().as_ref().clone();
+    locals.next_checkpoint += 1;
+    cps.set_locals_for_testing(locals).unwrap();
+    assert!(cps.is_ready_to_finish_epoch_change());
+    cps.epoch_change_finished().unwrap();
+    drop(cps);
+
+    let mut cps = open();
+    assert!(!cps.is_ready_to_finish_epoch_change());
+    assert!(!cps.is_ready_to_start_epoch_change());
+}
+
 #[test]
 fn make_checkpoint_db() {
     let (_committee, _keys, mut stores) = random_ckpoint_store();
@@ -1589,6 +1634,7 @@ pub async fn checkpoint_tests_setup(
 ) -> TestSetup {
     let mut rng = StdRng::from_seed(RNG_SEED);
     let (keys, committee) = make_committee_key(&mut rng);
+    let genesis = sui_config::genesis::Genesis::get_default_genesis();
 
     let mut genesis_objects = Vec::new();
     let mut transactions = Vec::new();
@@ -1606,6 +1652,7 @@ pub async fn checkpoint_tests_setup(
             addr2,
             gas_object1.compute_object_reference(),
             gas_object2.compute_object_reference(),
+            genesis.chain_id(),
         );
 
         genesis_objects.push(gas_object1);
@@ -1621,7 +1668,6 @@ pub async fn checkpoint_tests_setup(
     let mut authorities = Vec::new();
 
     // Make all authorities and their services.
-    let genesis = sui_config::genesis::Genesis::get_default_genesis();
     for k in &keys {
         let dir = env::temp_dir();
         let path = dir.join(format!("SC_{:?}", ObjectID::random()));
@@ -0,0 +1,127 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! When epochs end.
+//!
+//! Epochs last [`CHECKPOINT_COUNT_PER_EPOCH`] checkpoints unless genesis sets otherwise: the
+//! change of epoch starts at each multiple of the count and finishes at the next checkpoint.
+//! Epochs given a duration also end at the first checkpoint past it if that comes first, and the
+//! count of the next epoch then starts from the checkpoint the change finished at. Where the
+//! epoch and its change started is kept in the checkpoint locals, see [`EpochProgress`], so that
+//! restarts resume them. Durations are measured on the clock of each validator and start over on
+//! restarts, so that epochs ending on time are meant for test networks.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use sui_config::EpochConfig;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+use crate::checkpoints::CHECKPOINT_COUNT_PER_EPOCH;
+
+/// Where the current epoch and its change started, persisted along with the checkpoints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochProgress {
+    /// The first checkpoint of the epoch if the previous one ended on time, epochs are aligned
+    /// on multiples of the count otherwise.
+    pub first_checkpoint: Option<CheckpointSequenceNumber>,
+    /// The checkpoint the change of epoch started at, while it is ongoing.
+    pub change_started_at: Option<CheckpointSequenceNumber>,
+}
+
+pub struct EpochTimer {
+    checkpoints_per_epoch: u64,
+    duration: Option<Duration>,
+    started_at: Instant,
+}
+
+impl EpochTimer {
+    pub fn new(config: &EpochConfig) -> Self {
+        let mut timer = Self {
+            checkpoints_per_epoch: CHECKPOINT_COUNT_PER_EPOCH,
+            duration: None,
+            started_at: Instant::now(),
+        };
+        timer.set_config(config);
+        timer
+    }
+
+    /// Apply `config` from the current epoch on, which keeps the start it had.
+    pub fn set_config(&mut self, config: &EpochConfig) {
+        self.checkpoints_per_epoch = config
+            .checkpoints_per_epoch
+            .unwrap_or(CHECKPOINT_COUNT_PER_EPOCH)
+            .max(1);
+        self.duration = config.epoch_duration_secs.map(Duration::from_secs);
+    }
+
+    pub fn config(&self) -> EpochConfig {
+        EpochConfig {
+            checkpoints_per_epoch: Some(self.checkpoints_per_epoch),
+            epoch_duration_secs: self.duration.map(|duration| duration.as_secs()),
+        }
+    }
+
+    fn ends_on_count(
+        &self,
+        progress: &EpochProgress,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) -> bool {
+        match progress.first_checkpoint {
+            Some(first) => next_checkpoint >= first + self.checkpoints_per_epoch,
+            None => next_checkpoint % self.checkpoints_per_epoch == 0,
+        }
+    }
+
+    fn ends_on_time(&self) -> bool {
+        self.duration
+            .map_or(false, |duration| self.started_at.elapsed() >= duration)
+    }
+
+    pub fn is_ready_to_start_epoch_change(
+        &self,
+        progress: &EpochProgress,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) -> bool {
+        progress.change_started_at.is_none()
+            && next_checkpoint != 0
+            && (self.ends_on_count(progress, next_checkpoint) || self.ends_on_time())
+    }
+
+    pub fn is_ready_to_finish_epoch_change(
+        &self,
+        progress: &EpochProgress,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) -> bool {
+        progress
+            .change_started_at
+            .map_or(false, |started| next_checkpoint == started + 1)
+    }
+
+    pub fn epoch_change_started(
+        &self,
+        progress: &mut EpochProgress,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) {
+        progress.change_started_at = Some(next_checkpoint);
+    }
+
+    pub fn epoch_change_finished(
+        &mut self,
+        progress: &mut EpochProgress,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) {
+        let on_count = match progress.change_started_at.take() {
+            Some(started) => {
+                progress.first_checkpoint.is_none() && started % self.checkpoints_per_epoch == 0
+            }
+            None => true,
+        };
+        progress.first_checkpoint = if on_count {
+            None
+        } else {
+            Some(next_checkpoint)
+        };
+        self.started_at = Instant::now();
+    }
+}
@@ -1,8 +1,13 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod epoch_timer;
 pub mod reconfiguration;
 
+#[cfg(test)]
+#[path = "./tests/epoch_timer_tests.rs"]
+mod epoch_timer_tests;
+
 #[cfg(test)]
 #[path = "./tests/reconfiguration_tests.rs"]
 mod reconfiguration_tests;
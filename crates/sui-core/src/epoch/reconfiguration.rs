@@ -10,10 +10,13 @@ use narwhal_crypto::traits::ToFromBytes;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
-use sui_network::tonic;
+use sui_network::pool::{ConnectionPool, PoolConfig};
+use sui_network::quic::{QuicConfig, QuicConnector};
+use sui_types::base_types::AuthorityName;
 use sui_types::committee::Committee;
 use sui_types::crypto::PublicKeyBytes;
 use sui_types::error::{SuiError, SuiResult};
+use sui_types::handshake::Handshake;
 use sui_types::messages::SignedTransaction;
 use sui_types::sui_system_state::SuiSystemState;
 use tracing::{debug, info, warn};
@@ -23,7 +26,13 @@ use typed_store::Map;
 pub trait Reconfigurable {
     fn needs_network_recreation() -> bool;
 
-    fn recreate(channel: tonic::transport::Channel) -> Self;
+    fn recreate(
+        address: &Multiaddr,
+        name: AuthorityName,
+        pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized;
 }
 
 const WAIT_BETWEEN_EPOCH_TX_QUERY_RETRY: Duration = Duration::from_millis(300);
@@ -39,10 +48,12 @@ where
         let epoch = self.state.committee.load().epoch;
         info!(?epoch, "Starting epoch change");
         if let Some(checkpoints) = &self.state.checkpoints {
+            let mut checkpoints = checkpoints.lock();
             assert!(
-                checkpoints.lock().is_ready_to_start_epoch_change(),
+                checkpoints.is_ready_to_start_epoch_change(),
                 "start_epoch_change called at the wrong checkpoint",
             );
+            checkpoints.epoch_change_started()?;
         } else {
             unreachable!();
         }
@@ -136,6 +147,7 @@ where
             0, // TODO: fill in computation_charge
             self.state.name,
             &*self.state.secret,
+            self.state.chain_id,
         );
         debug!(
             ?epoch,
@@ -170,6 +182,10 @@ where
             tokio::time::sleep(WAIT_BETWEEN_EPOCH_TX_QUERY_RETRY).await;
         }
 
+        if let Some(checkpoints) = &self.state.checkpoints {
+            checkpoints.lock().epoch_change_finished()?;
+        }
+
         // Resume the validator to start accepting transactions for the new epoch.
         self.state.unhalt_validator();
         info!(?epoch, "Validator unhalted. Epoch change finished");
@@ -190,6 +206,13 @@ where
         net_config.connect_timeout = Some(Duration::from_secs(5));
         net_config.request_timeout = Some(Duration::from_secs(5));
         net_config.http2_keepalive_interval = Some(Duration::from_secs(5));
+        // TODO: authenticate with the key of the node, validators which restrict their clients
+        // reject these connections.
+        let quic_connector = QuicConnector::ephemeral(QuicConfig::default());
+        let pool = Arc::new(
+            ConnectionPool::new(PoolConfig::default(), net_config, quic_connector)
+                .with_handshake(Handshake::new(self.state.chain_id)),
+        );
 
         for validator in next_epoch_validators {
             let address = Multiaddr::try_from(validator.net_address).map_err(|e| {
@@ -198,20 +221,18 @@ where
                 }
             })?; //TODO: handle what happens if a validator registers with a faulty address
 
-            let channel =
-                net_config
-                    .connect_lazy(&address)
-                    .map_err(|e| SuiError::GenericAuthorityError {
-                        error: e.to_string(),
-                    })?;
-            let client: A = A::recreate(channel);
-
             let pub_key_raw: &[u8] = &validator.pubkey_bytes;
             let public_key_bytes = PublicKeyBytes::from_bytes(pub_key_raw).map_err(|e| {
                 SuiError::GenericAuthorityError {
                     error: e.to_string(),
                 }
             })?;
+
+            let client: A = A::recreate(&address, public_key_bytes, &pool).map_err(|e| {
+                SuiError::GenericAuthorityError {
+                    error: e.to_string(),
+                }
+            })?;
             new_clients.insert(public_key_bytes, client);
         }
 
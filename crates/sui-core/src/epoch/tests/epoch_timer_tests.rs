@@ -0,0 +1,74 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::epoch_timer::{EpochProgress, EpochTimer};
+use crate::checkpoints::CHECKPOINT_COUNT_PER_EPOCH;
+use sui_config::EpochConfig;
+
+#[test]
+fn test_epochs_end_on_checkpoint_count() {
+    let mut timer = EpochTimer::new(&EpochConfig::default());
+    let mut progress = EpochProgress::default();
+    assert!(!timer.is_ready_to_start_epoch_change(&progress, 0));
+    assert!(!timer.is_ready_to_start_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH - 1));
+    assert!(timer.is_ready_to_start_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH));
+    assert!(!timer.is_ready_to_finish_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH + 1));
+
+    timer.epoch_change_started(&mut progress, CHECKPOINT_COUNT_PER_EPOCH);
+    assert!(!timer.is_ready_to_start_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH));
+    assert!(timer.is_ready_to_finish_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH + 1));
+    timer.epoch_change_finished(&mut progress, CHECKPOINT_COUNT_PER_EPOCH + 1);
+    assert_eq!(progress, EpochProgress::default());
+
+    // The next epoch stays aligned on the count
+    assert!(!timer.is_ready_to_start_epoch_change(&progress, CHECKPOINT_COUNT_PER_EPOCH + 1));
+    assert!(timer.is_ready_to_start_epoch_change(&progress, 2 * CHECKPOINT_COUNT_PER_EPOCH));
+}
+
+#[test]
+fn test_epochs_end_on_time() {
+    let mut timer = EpochTimer::new(&EpochConfig {
+        checkpoints_per_epoch: Some(10),
+        epoch_duration_secs: Some(0),
+    });
+    let mut progress = EpochProgress::default();
+    assert!(timer.is_ready_to_start_epoch_change(&progress, 2));
+    timer.epoch_change_started(&mut progress, 2);
+    assert!(!timer.is_ready_to_finish_epoch_change(&progress, 4));
+    assert!(timer.is_ready_to_finish_epoch_change(&progress, 3));
+    timer.epoch_change_finished(&mut progress, 3);
+
+    // The count of the next epoch starts from the end of the previous one
+    timer.set_config(&EpochConfig {
+        checkpoints_per_epoch: Some(10),
+        epoch_duration_secs: None,
+    });
+    assert!(!timer.is_ready_to_start_epoch_change(&progress, 10));
+    assert!(timer.is_ready_to_start_epoch_change(&progress, 13));
+    assert_eq!(timer.config().checkpoints_per_epoch, Some(10));
+}
+
+#[test]
+fn test_progress_survives_restarts() {
+    let config = EpochConfig {
+        checkpoints_per_epoch: Some(10),
+        epoch_duration_secs: Some(0),
+    };
+    let mut progress = EpochProgress::default();
+    EpochTimer::new(&config).epoch_change_started(&mut progress, 2);
+
+    // A change started on time is finished by the restarted node, at the checkpoint after
+    let mut restarted = EpochTimer::new(&config);
+    assert!(!restarted.is_ready_to_start_epoch_change(&progress, 3));
+    assert!(!restarted.is_ready_to_finish_epoch_change(&progress, 11));
+    assert!(restarted.is_ready_to_finish_epoch_change(&progress, 3));
+    restarted.epoch_change_finished(&mut progress, 3);
+
+    // And the count of the next epoch still starts from its end
+    let restarted = EpochTimer::new(&EpochConfig {
+        epoch_duration_secs: None,
+        ..config
+    });
+    assert!(!restarted.is_ready_to_start_epoch_change(&progress, 10));
+    assert!(restarted.is_ready_to_start_epoch_change(&progress, 13));
+}
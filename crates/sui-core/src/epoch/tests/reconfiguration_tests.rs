@@ -51,6 +51,7 @@ async fn test_start_epoch_change() {
             next_transaction_sequence: 0,
             no_more_fragments: true,
             current_proposal: None,
+            epoch_progress: Default::default(),
         })
         .unwrap();
     // Create an active authority for the first authority state.
@@ -84,6 +85,7 @@ async fn test_start_epoch_change() {
         sender,
         gas_object.compute_object_reference(),
         1000,
+        state.chain_id,
     );
     let signature = Signature::new(&tx_data, &sender_key);
     let transaction = Transaction::new(tx_data, signature);
@@ -139,6 +141,7 @@ async fn test_start_epoch_change() {
         BTreeSet::new(),
         &state.move_vm,
         &state._native_functions,
+        state.protocol_config(),
         SuiGasStatus::new_with_budget(1000, 1, 1),
         state.epoch(),
     );
@@ -175,6 +178,7 @@ async fn test_finish_epoch_change() {
                     next_transaction_sequence: 0,
                     no_more_fragments: true,
                     current_proposal: None,
+                    epoch_progress: Default::default(),
                 };
                 state
                     .checkpoints
@@ -186,6 +190,15 @@ async fn test_finish_epoch_change() {
 
                 active.start_epoch_change().await.unwrap();
 
+                // The change of epoch finishes at the checkpoint after the one it started at
+                locals = state
+                    .checkpoints
+                    .as_ref()
+                    .unwrap()
+                    .lock()
+                    .get_locals()
+                    .as_ref()
+                    .clone();
                 locals.next_checkpoint += 1;
                 state
                     .checkpoints
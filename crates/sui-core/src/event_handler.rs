@@ -14,6 +14,7 @@ use sui_types::{
     error::{SuiError, SuiResult},
     event::{Event, EventEnvelope},
     messages::TransactionEffects,
+    object_change::{ObjectChange, ObjectChangeFilter},
 };
 
 use crate::authority::{AuthorityStore, ResolverWrapper};
@@ -29,6 +30,7 @@ pub const EVENT_DISPATCH_BUFFER_SIZE: usize = 1000;
 pub struct EventHandler {
     module_cache: SyncModuleCache<ResolverWrapper<AuthorityStore>>,
     event_streamer: Streamer<EventEnvelope, EventFilter>,
+    object_change_streamer: Streamer<ObjectChange, ObjectChangeFilter>,
     pub(crate) event_store: Arc<EventStoreType>,
 }
 
@@ -38,6 +40,7 @@ impl EventHandler {
         Self {
             module_cache: SyncModuleCache::new(ResolverWrapper(validator_store)),
             event_streamer: streamer,
+            object_change_streamer: Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE),
             event_store,
         }
     }
@@ -76,6 +79,15 @@ impl EventHandler {
         Ok(())
     }
 
+    /// Dispatch the object changes of a transaction to the subscribers of the changed objects.
+    pub async fn process_object_changes(&self, changes: Vec<ObjectChange>) {
+        for change in changes {
+            if let Err(e) = self.object_change_streamer.send(change).await {
+                error!(error =? e, "Failed to send ObjectChange to dispatch");
+            }
+        }
+    }
+
     fn create_envelope(
         &self,
         event: &Event,
@@ -113,4 +125,11 @@ impl EventHandler {
     pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = EventEnvelope> {
         self.event_streamer.subscribe(filter)
     }
+
+    pub fn subscribe_object_changes(
+        &self,
+        filter: ObjectChangeFilter,
+    ) -> impl Stream<Item = ObjectChange> {
+        self.object_change_streamer.subscribe(filter)
+    }
 }
@@ -1,27 +1,33 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use move_binary_format::file_format::LocalIndex;
 use move_core_types::ident_str;
 use move_core_types::identifier::Identifier;
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, ops::Range, sync::Arc};
 
 use crate::authority::TemporaryStore;
 use move_core_types::language_storage::ModuleId;
 use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use sui_adapter::adapter;
+use sui_types::coin::Coin;
 use sui_types::committee::EpochId;
-use sui_types::error::ExecutionError;
+use sui_types::error::{ExecutionError, ExecutionErrorKind};
 use sui_types::gas::GasCostSummary;
 use sui_types::gas_coin::GasCoin;
+use sui_types::id::Info;
 use sui_types::messages::ObjectArg;
 use sui_types::object::{MoveObject, Owner, OBJECT_START_VERSION};
+use sui_types::protocol_config::ProtocolConfig;
+use sui_types::storage::DeleteKind;
 use sui_types::{
     base_types::{ObjectID, ObjectRef, SuiAddress, TransactionDigest, TxContext},
     event::{Event, TransferType},
     gas::{self, SuiGasStatus},
     messages::{
-        CallArg, ChangeEpoch, ExecutionStatus, MoveCall, MoveModulePublish, SingleTransactionKind,
-        TransactionData, TransactionEffects, TransferObject, TransferSui,
+        CallArg, ChangeEpoch, EntryArgumentErrorKind, ExecutionStatus, MoveCall, MoveModulePublish,
+        Pay, SingleTransactionKind, TransactionData, TransactionEffects, TransferObject,
+        TransferSui,
     },
     object::Object,
     storage::{BackingPackageStore, Storage},
@@ -39,6 +45,7 @@ pub fn execute_transaction_to_effects<S: BackingPackageStore>(
     mut transaction_dependencies: BTreeSet<TransactionDigest>,
     move_vm: &Arc<MoveVM>,
     native_functions: &NativeFunctionTable,
+    protocol_config: &ProtocolConfig,
     gas_status: SuiGasStatus,
     epoch: EpochId,
 ) -> (TransactionEffects, Option<ExecutionError>) {
@@ -52,6 +59,7 @@ pub fn execute_transaction_to_effects<S: BackingPackageStore>(
         &mut tx_ctx,
         move_vm,
         native_functions,
+        protocol_config,
         gas_status,
     );
 
@@ -107,6 +115,7 @@ fn execute_transaction<S: BackingPackageStore>(
     tx_ctx: &mut TxContext,
     move_vm: &Arc<MoveVM>,
     native_functions: &NativeFunctionTable,
+    protocol_config: &ProtocolConfig,
     mut gas_status: SuiGasStatus,
 ) -> (GasCostSummary, Result<(), ExecutionError>) {
     // We must charge object read gas inside here during transaction execution, because if this fails
@@ -115,7 +124,11 @@ fn execute_transaction<S: BackingPackageStore>(
     if result.is_ok() {
         // TODO: Since we require all mutable objects to not show up more than
         // once across single tx, we should be able to run them in parallel.
+        // The ranges of object ids created by each single tx executed so far, so that
+        // later single txs of a batch can use them as arguments.
+        let mut created_id_ranges = Vec::new();
         for single_tx in transaction_data.kind.into_single_transactions() {
+            let ids_created = tx_ctx.ids_created();
             result = match single_tx {
                 SingleTransactionKind::TransferObject(TransferObject {
                     recipient,
@@ -137,6 +150,18 @@ fn execute_transaction<S: BackingPackageStore>(
                         .clone();
                     transfer_sui(temporary_store, gas_object, recipient, amount, tx_ctx)
                 }
+                SingleTransactionKind::Pay(Pay {
+                    coins,
+                    recipients,
+                    amounts,
+                }) => {
+                    // unwrap is is safe because we built the object map from the transactions
+                    let coins = coins
+                        .iter()
+                        .map(|(id, _, _)| temporary_store.objects().get(id).unwrap().clone())
+                        .collect();
+                    pay(temporary_store, coins, recipients, amounts, tx_ctx)
+                }
                 SingleTransactionKind::Call(MoveCall {
                     package,
                     module,
@@ -144,17 +169,21 @@ fn execute_transaction<S: BackingPackageStore>(
                     type_arguments,
                     arguments,
                 }) => {
-                    let module_id = ModuleId::new(package.0.into(), module);
-                    adapter::execute(
-                        move_vm,
-                        temporary_store,
-                        module_id,
-                        &function,
-                        type_arguments,
-                        arguments,
-                        &mut gas_status,
-                        tx_ctx,
-                    )
+                    resolve_command_results(temporary_store, tx_ctx, &created_id_ranges, arguments)
+                        .and_then(|arguments| {
+                            let module_id = ModuleId::new(package.0.into(), module);
+                            adapter::execute(
+                                move_vm,
+                                temporary_store,
+                                module_id,
+                                &function,
+                                type_arguments,
+                                arguments,
+                                &mut gas_status,
+                                tx_ctx,
+                                protocol_config,
+                            )
+                        })
                 }
                 SingleTransactionKind::Publish(MoveModulePublish { modules }) => adapter::publish(
                     temporary_store,
@@ -162,6 +191,7 @@ fn execute_transaction<S: BackingPackageStore>(
                     modules,
                     tx_ctx,
                     &mut gas_status,
+                    protocol_config,
                 ),
                 SingleTransactionKind::ChangeEpoch(ChangeEpoch {
                     epoch,
@@ -185,12 +215,17 @@ fn execute_transaction<S: BackingPackageStore>(
                         ],
                         &mut gas_status,
                         tx_ctx,
+                        protocol_config,
                     )
                 }
             };
             if result.is_err() {
                 break;
             }
+            created_id_ranges.push(ids_created..tx_ctx.ids_created());
+        }
+        if result.is_ok() {
+            result = temporary_store.check_limits(protocol_config);
         }
         if result.is_err() {
             // Roll back the temporary store if execution failed.
@@ -241,6 +276,194 @@ fn execute_transaction<S: BackingPackageStore>(
     (cost_summary, result)
 }
 
+/// Pay `amounts` to `recipients` out of `coins`, which must all be coins of the same type.
+/// Each recipient receives a new coin. The other coins are merged into the first one, which
+/// keeps the remaining balance, or is deleted if nothing remains.
+fn pay<S>(
+    temporary_store: &mut TemporaryStore<S>,
+    coins: Vec<Object>,
+    recipients: Vec<SuiAddress>,
+    amounts: Vec<u64>,
+    tx_ctx: &mut TxContext,
+) -> Result<(), ExecutionError> {
+    let invalid =
+        |message: String| ExecutionError::new_with_source(ExecutionErrorKind::InvalidPay, message);
+    if coins.is_empty() {
+        return Err(invalid("No coins to pay with".to_string()));
+    }
+    if recipients.len() != amounts.len() {
+        return Err(invalid(format!(
+            "Found {} recipients but {} amounts",
+            recipients.len(),
+            amounts.len()
+        )));
+    }
+    let (coin_type, has_public_transfer) = match coins[0].data.try_as_move() {
+        Some(object) if Coin::is_coin(&object.type_) => {
+            (object.type_.clone(), object.has_public_transfer())
+        }
+        _ => return Err(invalid(format!("Object {} is not a coin", coins[0].id()))),
+    };
+    let mut total: u128 = 0;
+    for object in &coins {
+        let coin = match object.data.try_as_move() {
+            Some(move_object) if move_object.type_ == coin_type => {
+                Coin::from_bcs_bytes(move_object.contents())
+                    .map_err(|e| invalid(format!("Invalid coin {}: {e}", object.id())))?
+            }
+            _ => {
+                return Err(invalid(format!(
+                    "Object {} is not a coin of type {coin_type}",
+                    object.id()
+                )))
+            }
+        };
+        total += coin.value() as u128;
+    }
+    let paid: u128 = amounts.iter().map(|amount| *amount as u128).sum();
+    if paid > total {
+        return Err(ExecutionError::new_with_source(
+            ExecutionErrorKind::InvalidPayInsufficientBalance,
+            format!("balance: {total} required: {paid}"),
+        ));
+    }
+
+    let sender = tx_ctx.sender();
+    for (recipient, amount) in recipients.into_iter().zip(amounts) {
+        let id = tx_ctx.fresh_id();
+        let contents = Coin::new(Info::new(id, OBJECT_START_VERSION), amount).to_bcs_bytes();
+        // Safe because the new coin has the type, and so the abilities, of the input coins.
+        let move_object = unsafe {
+            MoveObject::new_from_execution(coin_type.clone(), has_public_transfer, contents)
+        };
+        temporary_store.write_object(Object::new_move(
+            move_object,
+            Owner::AddressOwner(recipient),
+            tx_ctx.digest(),
+        ));
+        temporary_store.log_event(Event::new_object(
+            &SUI_FRAMEWORK_ADDRESS,
+            ident_str!("native"),
+            sender,
+            Owner::AddressOwner(recipient),
+            id,
+        ));
+    }
+    // This is necessary for the temporary store to know the new coins are not unwrapped.
+    let newly_generated_ids = tx_ctx.recreate_all_ids();
+    temporary_store.set_create_object_ids(newly_generated_ids);
+
+    let mut coins = coins.into_iter();
+    // unwrap is safe, the coins are not empty
+    let mut primary = coins.next().unwrap();
+    for coin in coins {
+        temporary_store.log_event(Event::delete_object(
+            &SUI_FRAMEWORK_ADDRESS,
+            ident_str!("native"),
+            sender,
+            coin.id(),
+        ));
+        temporary_store.delete_object(&coin.id(), coin.version(), DeleteKind::Normal);
+    }
+    // The remaining balance fits in a u64, as does the total supply of the coin type.
+    let remaining = (total - paid) as u64;
+    if remaining == 0 {
+        temporary_store.log_event(Event::delete_object(
+            &SUI_FRAMEWORK_ADDRESS,
+            ident_str!("native"),
+            sender,
+            primary.id(),
+        ));
+        temporary_store.delete_object(&primary.id(), primary.version(), DeleteKind::Normal);
+    } else {
+        let contents = Coin::new(Info::new(primary.id(), primary.version()), remaining);
+        // unwrap is safe, the primary coin was checked to be a Move object
+        primary
+            .data
+            .try_as_move_mut()
+            .unwrap()
+            .update_contents_and_increment_version(contents.to_bcs_bytes());
+        temporary_store.write_object(primary);
+    }
+    Ok(())
+}
+
+/// Replace the results of earlier single transactions of the batch used as arguments with
+/// the objects they refer to.
+fn resolve_command_results<S>(
+    temporary_store: &TemporaryStore<S>,
+    tx_ctx: &TxContext,
+    created_id_ranges: &[Range<u64>],
+    arguments: Vec<CallArg>,
+) -> Result<Vec<CallArg>, ExecutionError> {
+    arguments
+        .into_iter()
+        .enumerate()
+        .map(|(idx, arg)| {
+            let result = match arg {
+                CallArg::Object(ObjectArg::Result(result)) => result,
+                arg => return Ok(arg),
+            };
+            let invalid_result = |msg: String| {
+                ExecutionError::new_with_source(
+                    ExecutionErrorKind::entry_argument_error(
+                        idx as LocalIndex,
+                        EntryArgumentErrorKind::InvalidCommandResult,
+                    ),
+                    msg,
+                )
+            };
+            let range = created_id_ranges
+                .get(result.command as usize)
+                .ok_or_else(|| {
+                    invalid_result(format!(
+                        "Argument at index {idx} uses {result}, but transaction {} has not been \
+                        executed yet",
+                        result.command
+                    ))
+                })?;
+            let seq = range.start + result.index as u64;
+            if !range.contains(&seq) {
+                return Err(invalid_result(format!(
+                    "Argument at index {idx} uses {result}, but transaction {} only created {} \
+                    objects",
+                    result.command,
+                    range.end - range.start
+                )));
+            }
+            let id = tx_ctx.digest().derive_id(seq);
+            // The object may have been deleted or wrapped by a transaction in between.
+            let object = temporary_store
+                .written()
+                .get(&id)
+                .map(|(_, object)| object)
+                .ok_or_else(|| {
+                    invalid_result(format!(
+                        "Argument at index {idx} uses {result}, but object {id} is no longer \
+                        available"
+                    ))
+                })?;
+            // Results skip the checks of the inputs of the transaction, so the sender must be
+            // allowed to use them here.
+            match object.owner {
+                Owner::AddressOwner(owner) if owner == tx_ctx.sender() => (),
+                Owner::Immutable | Owner::Shared => (),
+                owner => {
+                    return Err(invalid_result(format!(
+                        "Argument at index {idx} uses {result}, but object {id} is owned by \
+                        {owner}, not by the sender"
+                    )))
+                }
+            }
+            Ok(CallArg::Object(if object.is_shared() {
+                ObjectArg::SharedObject(id)
+            } else {
+                ObjectArg::ImmOrOwnedObject(object.compute_object_reference())
+            }))
+        })
+        .collect()
+}
+
 fn transfer_object<S>(
     temporary_store: &mut TemporaryStore<S>,
     mut object: Object,
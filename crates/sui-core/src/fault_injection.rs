@@ -0,0 +1,359 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Network faults between clients and authorities, for tests.
+//!
+//! A [`FaultyAuthorityClient`] wraps the client of an authority and misbehaves as told by the
+//! [`NetworkScenario`] shared by all the clients of a test: it delays requests, loses requests
+//! or their responses, delivers requests twice, and cuts off the authorities of a partition.
+//! Jitter reorders the requests in flight to an authority. A [`ScenarioScript`] changes the
+//! faults over time, e.g. to partition an authority for a while and heal the network again.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use multiaddr::Multiaddr;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use sui_network::pool::ConnectionPool;
+use sui_types::base_types::AuthorityName;
+use sui_types::error::SuiError;
+use sui_types::messages::*;
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+
+use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream};
+use crate::epoch::reconfiguration::Reconfigurable;
+
+#[cfg(test)]
+#[path = "unit_tests/fault_injection_tests.rs"]
+mod fault_injection_tests;
+
+/// The faults of the link to an authority. Rates are probabilities between 0 and 1.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LinkFaults {
+    /// Added to every request.
+    #[serde(default)]
+    pub latency: Duration,
+    /// A random delay up to this long is added to every request on top of the latency.
+    #[serde(default)]
+    pub jitter: Duration,
+    /// Requests lost before reaching the authority.
+    #[serde(default)]
+    pub drop_request_rate: f64,
+    /// Responses lost after the authority processed the request.
+    #[serde(default)]
+    pub drop_response_rate: f64,
+    /// Requests the authority receives twice.
+    #[serde(default)]
+    pub duplicate_rate: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScenarioStep {
+    /// The faults of the links to the authorities without faults of their own.
+    DefaultFaults(LinkFaults),
+    Faults {
+        name: AuthorityName,
+        faults: LinkFaults,
+    },
+    ClearFaults(AuthorityName),
+    /// Cut off these authorities, on top of the ones already partitioned.
+    Partition(Vec<AuthorityName>),
+    /// Reconnect all the partitioned authorities.
+    Heal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TimedStep {
+    /// Since the start of the script.
+    pub at: Duration,
+    pub step: ScenarioStep,
+}
+
+/// Steps applied in the order of their time, from the moment the script is played.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ScenarioScript {
+    pub steps: Vec<TimedStep>,
+}
+
+impl ScenarioScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn at(mut self, at: Duration, step: ScenarioStep) -> Self {
+        self.steps.push(TimedStep { at, step });
+        self
+    }
+}
+
+#[derive(Default)]
+struct ScenarioState {
+    default_faults: LinkFaults,
+    faults: BTreeMap<AuthorityName, LinkFaults>,
+    partitioned: BTreeSet<AuthorityName>,
+}
+
+/// The faults of the network, shared by the clients it is injected into. The network has no
+/// faults until told otherwise.
+#[derive(Clone, Default)]
+pub struct NetworkScenario {
+    state: Arc<Mutex<ScenarioState>>,
+}
+
+impl NetworkScenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&self, step: &ScenarioStep) {
+        debug!("Network scenario: {step:?}");
+        let mut state = self.state.lock().unwrap();
+        match step {
+            ScenarioStep::DefaultFaults(faults) => state.default_faults = faults.clone(),
+            ScenarioStep::Faults { name, faults } => {
+                state.faults.insert(*name, faults.clone());
+            }
+            ScenarioStep::ClearFaults(name) => {
+                state.faults.remove(name);
+            }
+            ScenarioStep::Partition(names) => state.partitioned.extend(names.iter().copied()),
+            ScenarioStep::Heal => state.partitioned.clear(),
+        }
+    }
+
+    pub fn set_default_faults(&self, faults: LinkFaults) {
+        self.apply(&ScenarioStep::DefaultFaults(faults));
+    }
+
+    pub fn set_faults(&self, name: AuthorityName, faults: LinkFaults) {
+        self.apply(&ScenarioStep::Faults { name, faults });
+    }
+
+    pub fn partition(&self, names: impl IntoIterator<Item = AuthorityName>) {
+        self.apply(&ScenarioStep::Partition(names.into_iter().collect()));
+    }
+
+    pub fn heal(&self) {
+        self.apply(&ScenarioStep::Heal);
+    }
+
+    pub fn is_partitioned(&self, name: &AuthorityName) -> bool {
+        self.state.lock().unwrap().partitioned.contains(name)
+    }
+
+    /// Apply the steps of `script` at their time, until the returned task is aborted.
+    pub fn play(&self, script: ScenarioScript) -> JoinHandle<()> {
+        let scenario = self.clone();
+        let mut steps = script.steps;
+        steps.sort_by_key(|step| step.at);
+        tokio::spawn(async move {
+            let start = tokio::time::Instant::now();
+            for TimedStep { at, step } in steps {
+                tokio::time::sleep_until(start + at).await;
+                scenario.apply(&step);
+            }
+        })
+    }
+
+    /// The faults of the link to `name`, `None` if the authority is partitioned.
+    fn faults(&self, name: &AuthorityName) -> Option<LinkFaults> {
+        let state = self.state.lock().unwrap();
+        if state.partitioned.contains(name) {
+            return None;
+        }
+        Some(
+            state
+                .faults
+                .get(name)
+                .unwrap_or(&state.default_faults)
+                .clone(),
+        )
+    }
+}
+
+/// The client of an authority behind a faulty network.
+#[derive(Clone)]
+pub struct FaultyAuthorityClient<A> {
+    name: AuthorityName,
+    inner: A,
+    scenario: NetworkScenario,
+}
+
+impl<A> FaultyAuthorityClient<A> {
+    pub fn new(name: AuthorityName, inner: A, scenario: NetworkScenario) -> Self {
+        Self {
+            name,
+            inner,
+            scenario,
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    pub fn inner_mut(&mut self) -> &mut A {
+        &mut self.inner
+    }
+}
+
+/// Put the clients of a committee behind the faults of `scenario`.
+pub fn faulty_clients<A>(
+    clients: BTreeMap<AuthorityName, A>,
+    scenario: &NetworkScenario,
+) -> BTreeMap<AuthorityName, FaultyAuthorityClient<A>> {
+    clients
+        .into_iter()
+        .map(|(name, client)| {
+            let client = FaultyAuthorityClient::new(name, client, scenario.clone());
+            (name, client)
+        })
+        .collect()
+}
+
+impl<A> FaultyAuthorityClient<A> {
+    async fn call<T, F, Fut>(&self, method: &str, request: F) -> Result<T, SuiError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, SuiError>>,
+    {
+        let lost = |what: &str| {
+            debug!(
+                "Fault injection lost the {what} of {method} to {}",
+                self.name
+            );
+            SuiError::RpcError(format!(
+                "The {what} of {method} to {} was lost by fault injection",
+                self.name
+            ))
+        };
+
+        let faults = self.scenario.faults(&self.name).ok_or_else(|| {
+            SuiError::RpcError(format!("{} is partitioned by fault injection", self.name))
+        })?;
+        let (delay, drop_request, drop_response, duplicate) = {
+            let mut rng = rand::thread_rng();
+            let jitter = rng.gen_range(0, faults.jitter.as_micros() as u64 + 1);
+            (
+                faults.latency + Duration::from_micros(jitter),
+                rng.gen_bool(faults.drop_request_rate),
+                rng.gen_bool(faults.drop_response_rate),
+                rng.gen_bool(faults.duplicate_rate),
+            )
+        };
+
+        tokio::time::sleep(delay).await;
+        if drop_request {
+            return Err(lost("request"));
+        }
+        let result = request().await;
+        if duplicate {
+            let _ = request().await;
+        }
+        // Partitions also cut off the requests in flight.
+        if drop_response || self.scenario.is_partitioned(&self.name) {
+            return Err(lost("response"));
+        }
+        result
+    }
+}
+
+impl<A> Reconfigurable for FaultyAuthorityClient<A> {
+    fn needs_network_recreation() -> bool {
+        false
+    }
+
+    fn recreate(
+        _address: &Multiaddr,
+        _name: AuthorityName,
+        _pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self> {
+        unreachable!(); // faulty clients keep the clients they wrap, and their scenario, across epochs
+    }
+}
+
+#[async_trait]
+impl<A> AuthorityAPI for FaultyAuthorityClient<A>
+where
+    A: AuthorityAPI + Send + Sync,
+{
+    async fn handle_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call("handle_transaction", || {
+            self.inner.handle_transaction(transaction.clone())
+        })
+        .await
+    }
+
+    async fn handle_certificate(
+        &self,
+        certificate: CertifiedTransaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call("handle_certificate", || {
+            self.inner.handle_certificate(certificate.clone())
+        })
+        .await
+    }
+
+    async fn handle_account_info_request(
+        &self,
+        request: AccountInfoRequest,
+    ) -> Result<AccountInfoResponse, SuiError> {
+        self.call("handle_account_info_request", || {
+            self.inner.handle_account_info_request(request.clone())
+        })
+        .await
+    }
+
+    async fn handle_object_info_request(
+        &self,
+        request: ObjectInfoRequest,
+    ) -> Result<ObjectInfoResponse, SuiError> {
+        self.call("handle_object_info_request", || {
+            self.inner.handle_object_info_request(request.clone())
+        })
+        .await
+    }
+
+    async fn handle_transaction_info_request(
+        &self,
+        request: TransactionInfoRequest,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call("handle_transaction_info_request", || {
+            self.inner.handle_transaction_info_request(request.clone())
+        })
+        .await
+    }
+
+    /// Faults only apply to opening the stream, not to its items.
+    async fn handle_batch_stream(
+        &self,
+        request: BatchInfoRequest,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        self.call("handle_batch_stream", || {
+            self.inner.handle_batch_stream(request.clone())
+        })
+        .await
+    }
+
+    async fn handle_checkpoint(
+        &self,
+        request: CheckpointRequest,
+    ) -> Result<CheckpointResponse, SuiError> {
+        self.call("handle_checkpoint", || {
+            self.inner.handle_checkpoint(request.clone())
+        })
+        .await
+    }
+}
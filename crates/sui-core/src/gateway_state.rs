@@ -15,6 +15,7 @@ use move_binary_format::access::ModuleAccess;
 use move_bytecode_utils::module_cache::SyncModuleCache;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
+use move_vm_runtime::{move_vm::MoveVM, native_functions::NativeFunctionTable};
 use prometheus::{
     register_histogram_with_registry, register_int_counter_with_registry, Histogram, IntCounter,
     Registry,
@@ -22,9 +23,13 @@ use prometheus::{
 use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
 use tracing::{debug, error, Instrument};
 
-use sui_adapter::adapter::resolve_and_type_check;
+use sui_adapter::adapter::{self, resolve_and_type_check, type_check_with_command_results};
+use sui_adapter::temporary_store::TemporaryStore;
+use sui_types::crypto::sha3_hash;
+use sui_types::gas::{MAX_GAS_BUDGET, MIN_GAS_BUDGET};
 use sui_types::gas_coin::GasCoin;
 use sui_types::object::{Data, ObjectFormatOptions, Owner};
+use sui_types::protocol_config::ProtocolConfig;
 use sui_types::{
     base_types::*,
     coin,
@@ -33,11 +38,13 @@ use sui_types::{
     fp_ensure,
     messages::*,
     object::{Object, ObjectRead},
-    SUI_FRAMEWORK_ADDRESS,
+    MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
 };
 
-use crate::authority::ResolverWrapper;
+use crate::authority::{ReadStore, ResolverWrapper};
 use crate::authority_aggregator::AuthAggMetrics;
+use crate::execution_engine;
+use crate::retry::{RetryConfig, RetryOn};
 use crate::transaction_input_checker;
 use crate::{
     authority::GatewayStore, authority_aggregator::AuthorityAggregator,
@@ -45,10 +52,12 @@ use crate::{
 };
 use sui_json::{resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
 use sui_json_rpc_types::{
-    GetObjectDataResponse, GetRawObjectDataResponse, MergeCoinResponse, MoveCallParams,
-    PublishResponse, RPCTransactionRequestParams, SplitCoinResponse, SuiMoveObject, SuiObject,
-    SuiObjectInfo, SuiTransactionEffects, SuiTypeTag, TransactionEffectsResponse,
-    TransactionResponse, TransferObjectParams,
+    normalize_package, GetObjectDataResponse, GetRawObjectDataResponse, MergeCoinResponse,
+    MoveCallParams, PublishResponse, RPCTransactionRequestParams, SplitCoinResponse,
+    SuiBalanceChange, SuiGasEstimate, SuiMoveNormalizedModule, SuiMoveObject, SuiObject,
+    SuiObjectInfo, SuiPublishPreview, SuiRawMovePackage, SuiTransactionEffects,
+    SuiTransactionPreview, SuiTypeTag, TransactionEffectsResponse, TransactionResponse,
+    TransferObjectParams,
 };
 use sui_types::error::SuiError::ConflictingTransaction;
 
@@ -58,14 +67,30 @@ use tap::TapFallible;
 #[path = "unit_tests/gateway_state_tests.rs"]
 mod gateway_state_tests;
 
+mod client_store;
+pub use client_store::{ClientStore, InMemoryClientStore};
+
 pub type AsyncResult<'a, T, E> = future::BoxFuture<'a, Result<T, E>>;
 
 pub type GatewayClient = Arc<dyn GatewayAPI + Sync + Send>;
 
 pub type GatewayTxSeqNumber = u64;
 
-/// Number of times to retry failed TX
-const MAX_NUM_TX_RETRIES: usize = 5;
+/// How failed transactions are retried, unless configured otherwise.
+pub fn default_transaction_retry_config() -> RetryConfig {
+    RetryConfig {
+        max_attempts: Some(6),
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(2),
+        multiplier: 2,
+        jitter: 0.2,
+        retry_on: RetryOn::AnyError,
+    }
+}
+
+/// Extra gas, in percent of the dry-run usage, added to the recommended budget of a gas estimate.
+/// The state of the objects may change between the estimate and the actual execution.
+const GAS_ESTIMATE_SAFETY_MARGIN_PERCENT: u64 = 20;
 
 /// Prometheus metrics which can be displayed in Grafana, queried and alerted on
 #[derive(Clone)]
@@ -76,6 +101,7 @@ pub struct GatewayMetrics {
     num_tx_movecall: IntCounter,
     num_tx_splitcoin: IntCounter,
     num_tx_mergecoin: IntCounter,
+    num_gas_estimates: IntCounter,
     total_tx_retries: IntCounter,
     shared_obj_tx: IntCounter,
     pub total_tx_certificates: IntCounter,
@@ -122,6 +148,12 @@ impl GatewayMetrics {
                 registry,
             )
             .unwrap(),
+            num_gas_estimates: register_int_counter_with_registry!(
+                "num_gas_estimates",
+                "Number of gas estimation requests",
+                registry,
+            )
+            .unwrap(),
             total_tx_certificates: register_int_counter_with_registry!(
                 "total_tx_certificates",
                 "Total number of certificates made from validators",
@@ -155,9 +187,11 @@ impl GatewayMetrics {
     }
 }
 
-pub struct GatewayState<A> {
+/// A gateway to the authorities, keeping the state of the accounts it manages in a
+/// [`ClientStore`], RocksDB by default.
+pub struct GatewayState<A, S: ClientStore = GatewayStore> {
     authorities: AuthorityAggregator<A>,
-    store: Arc<GatewayStore>,
+    store: Arc<S>,
     /// Every transaction committed in authorities (and hence also committed in the Gateway)
     /// will have a unique sequence number. This number is specific to this gateway,
     /// and hence will not be compatible with authorities or other gateways.
@@ -165,7 +199,16 @@ pub struct GatewayState<A> {
     /// from a gateway.
     next_tx_seq_number: AtomicU64,
     metrics: GatewayMetrics,
-    module_cache: SyncModuleCache<ResolverWrapper<GatewayStore>>,
+    module_cache: SyncModuleCache<ResolverWrapper<S>>,
+    /// Used to dry-run transactions locally, e.g. for gas estimation.
+    move_vm: Arc<MoveVM>,
+    native_functions: NativeFunctionTable,
+    /// The protocol version transactions are dry-run and checked at, the latest one.
+    protocol_config: ProtocolConfig,
+    /// How failed transactions are retried.
+    retry_config: RetryConfig,
+    /// The chain of the validators, which the transactions built by the gateway are bound to.
+    chain_id: ChainId,
 }
 
 impl<A> GatewayState<A> {
@@ -175,6 +218,7 @@ impl<A> GatewayState<A> {
         committee: Committee,
         authority_clients: BTreeMap<AuthorityName, A>,
         prometheus_registry: &Registry,
+        chain_id: ChainId,
     ) -> SuiResult<Self> {
         let gateway_metrics = GatewayMetrics::new(prometheus_registry);
         let auth_agg_metrics = AuthAggMetrics::new(prometheus_registry);
@@ -182,6 +226,7 @@ impl<A> GatewayState<A> {
             path,
             AuthorityAggregator::new(committee, authority_clients, auth_agg_metrics),
             gateway_metrics,
+            chain_id,
         )
     }
 
@@ -189,18 +234,80 @@ impl<A> GatewayState<A> {
         path: PathBuf,
         authorities: AuthorityAggregator<A>,
         metrics: GatewayMetrics,
+        chain_id: ChainId,
     ) -> SuiResult<Self> {
         let store = Arc::new(GatewayStore::open(path, None));
+        Self::new_with_store_and_authorities(store, authorities, metrics, chain_id)
+    }
+}
+
+impl<A, S: ClientStore> GatewayState<A, S> {
+    /// Create a new manager which stores its managed addresses in `store`
+    pub fn new_with_store(
+        store: Arc<S>,
+        committee: Committee,
+        authority_clients: BTreeMap<AuthorityName, A>,
+        prometheus_registry: &Registry,
+        chain_id: ChainId,
+    ) -> SuiResult<Self> {
+        let gateway_metrics = GatewayMetrics::new(prometheus_registry);
+        let auth_agg_metrics = AuthAggMetrics::new(prometheus_registry);
+        Self::new_with_store_and_authorities(
+            store,
+            AuthorityAggregator::new(committee, authority_clients, auth_agg_metrics),
+            gateway_metrics,
+            chain_id,
+        )
+    }
+
+    pub fn new_with_store_and_authorities(
+        store: Arc<S>,
+        authorities: AuthorityAggregator<A>,
+        metrics: GatewayMetrics,
+        chain_id: ChainId,
+    ) -> SuiResult<Self> {
         let next_tx_seq_number = AtomicU64::new(store.next_sequence_number()?);
+        let protocol_config = ProtocolConfig::get_for_max_version();
+        let native_functions = sui_framework::natives::natives_for_protocol(
+            MOVE_STDLIB_ADDRESS,
+            SUI_FRAMEWORK_ADDRESS,
+            &protocol_config,
+        );
+        let move_vm = Arc::new(
+            adapter::new_move_vm(native_functions.clone())
+                .expect("We defined natives to not fail here"),
+        );
         Ok(Self {
             store: store.clone(),
             authorities,
             next_tx_seq_number,
             metrics,
             module_cache: SyncModuleCache::new(ResolverWrapper(store)),
+            move_vm,
+            native_functions,
+            protocol_config,
+            retry_config: default_transaction_retry_config(),
+            chain_id,
         })
     }
 
+    /// Retry failed transactions with `retry_config` instead of the
+    /// [default](default_transaction_retry_config).
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Whether to execute a transaction again after `attempts` attempts, the last one failing
+    /// with `error`. Errors which are not `SuiError`s are never transient.
+    fn should_retry_transaction(&self, attempts: u32, error: &anyhow::Error) -> bool {
+        let retryable = match error.downcast_ref::<SuiError>() {
+            Some(error) => self.retry_config.is_retryable(error),
+            None => self.retry_config.retry_on == RetryOn::AnyError,
+        };
+        retryable && self.retry_config.has_attempts_left(attempts)
+    }
+
     // Given a list of inputs from a transaction, fetch the objects
     // from the db.
     async fn read_objects_from_store(
@@ -218,7 +325,7 @@ impl<A> GatewayState<A> {
     }
 
     #[cfg(test)]
-    pub fn store(&self) -> &Arc<GatewayStore> {
+    pub fn store(&self) -> &Arc<S> {
         &self.store
     }
 }
@@ -251,6 +358,28 @@ pub trait GatewayAPI {
         amount: Option<u64>,
     ) -> Result<TransactionData, anyhow::Error>;
 
+    /// Pay `amounts` to `recipients` out of `input_coins`, which must all have the same coin
+    /// type, in one transaction.
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error>;
+
+    /// Send all the SUI of `input_coins` to `recipient`. The first coin pays for the gas, the
+    /// recipient receives what is left of it once the gas is charged.
+    async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error>;
+
     /// Synchronise account state with a random authorities, updates all object_ids
     /// from account_addr, request only goes out to one authority.
     /// this method doesn't guarantee data correctness, caller will have to handle potential byzantine authority
@@ -321,6 +450,27 @@ pub trait GatewayAPI {
         gas_budget: u64,
     ) -> Result<TransactionData, anyhow::Error>;
 
+    /// Estimate the gas cost of a transaction by executing it locally against the latest
+    /// state of its input objects, without submitting it to the authorities.
+    /// The gas budget of `tx_data` is used as the upper bound of the dry run.
+    async fn estimate_gas(&self, tx_data: TransactionData)
+        -> Result<SuiGasEstimate, anyhow::Error>;
+
+    /// Execute the transaction against the gateway store like `estimate_gas`, and report
+    /// its effects and the balance changes it causes, to preview it before signing.
+    async fn dry_run_transaction(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionPreview, anyhow::Error>;
+
+    /// Dry-run a publish transaction like `dry_run_transaction`: the package is verified and
+    /// its module initializers are run, and the ID the package would be published with is
+    /// reported along with the objects the initializers would create.
+    async fn dry_run_publish(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiPublishPreview, anyhow::Error>;
+
     /// Get the object data
     async fn get_object(&self, object_id: ObjectID)
         -> Result<GetObjectDataResponse, anyhow::Error>;
@@ -331,6 +481,18 @@ pub trait GatewayAPI {
         object_id: ObjectID,
     ) -> Result<GetRawObjectDataResponse, anyhow::Error>;
 
+    /// Get the bytecode of the modules of a package
+    async fn get_raw_package(
+        &self,
+        package_id: ObjectID,
+    ) -> Result<SuiRawMovePackage, anyhow::Error>;
+
+    /// Get the normalized form of the modules of a package, keyed by module name
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, anyhow::Error>;
+
     /// Get refs of all objects we own from local cache.
     async fn get_objects_owned_by_address(
         &self,
@@ -366,9 +528,10 @@ pub trait GatewayAPI {
     ) -> Result<TransactionEffectsResponse, anyhow::Error>;
 }
 
-impl<A> GatewayState<A>
+impl<A, S> GatewayState<A, S>
 where
     A: AuthorityAPI + Send + Sync + 'static + Clone,
+    S: ClientStore,
 {
     pub async fn get_framework_object_ref(&self) -> Result<ObjectRef, anyhow::Error> {
         Ok(self
@@ -460,7 +623,14 @@ where
         for t in type_params {
             used_packages(&mut queue, t)
         }
+        self.load_packages_transitive_deps(queue).await
+    }
 
+    // Loads the given packages and all the packages their modules depend on.
+    async fn load_packages_transitive_deps(
+        &self,
+        mut queue: Vec<ObjectID>,
+    ) -> Result<(), anyhow::Error> {
         let mut seen: HashSet<ObjectID> = HashSet::new();
         while let Some(cur) = queue.pop() {
             if seen.contains(&cur) {
@@ -494,6 +664,133 @@ where
         Ok(())
     }
 
+    /// Dry-run the transaction against the gateway store and report its effects and the gas
+    /// it consumed, along with the objects it would write. All input objects are first synced
+    /// with the authorities so that the execution reflects their latest state.
+    /// A transaction priced below the reference gas price is dry-run at the reference price,
+    /// the price the gas estimate is made at.
+    async fn dry_run_impl(
+        &self,
+        mut tx_data: TransactionData,
+    ) -> Result<(SuiTransactionPreview, BTreeMap<ObjectID, Object>), anyhow::Error> {
+        let mut packages = vec![];
+        for kind in tx_data.input_objects()? {
+            match kind {
+                InputObjectKind::MovePackage(id) => packages.push(id),
+                _ => {
+                    self.get_object_internal(&kind.object_id()).await?;
+                }
+            }
+        }
+        // Move calls and publishes need every package they (transitively) depend on.
+        self.load_packages_transitive_deps(packages).await?;
+        self.download_object_from_authorities(SUI_SYSTEM_STATE_OBJECT_ID)
+            .await?;
+        let system_state = self.store.get_sui_system_state_object()?;
+        // The storage gas price of the system is the reference gas price of the epoch.
+        let reference_gas_price = system_state.parameters.storage_gas_price;
+        tx_data.gas_price = std::cmp::max(tx_data.gas_price, reference_gas_price);
+
+        let (gas_status, input_objects) = transaction_input_checker::check_transaction_data_input(
+            &self.store,
+            &tx_data,
+            &self.protocol_config,
+        )
+        .await?;
+
+        let tx_digest = TransactionDigest::new(sha3_hash(&tx_data));
+        let shared_object_refs = input_objects.filter_shared_objects();
+        let transaction_dependencies = input_objects.transaction_dependencies();
+        // The temporary store is dropped at the end of the dry run, nothing is written to the gateway store.
+        let mut temporary_store = TemporaryStore::new(self.store.clone(), input_objects, tx_digest);
+        let (effects, execution_error) = execution_engine::execute_transaction_to_effects(
+            shared_object_refs,
+            &mut temporary_store,
+            tx_data.clone(),
+            tx_digest,
+            transaction_dependencies,
+            &self.move_vm,
+            &self.native_functions,
+            &self.protocol_config,
+            gas_status,
+            system_state.epoch,
+        );
+        if let Some(error) = execution_error {
+            return Err(anyhow!("Dry run of the transaction failed: {error}"));
+        }
+
+        // Computation is charged at the transaction gas price, storage at the
+        // reference gas price, convert both back to gas units.
+        let gas_used = effects.gas_used.clone();
+        let gas_units = gas_used.computation_cost / std::cmp::max(tx_data.gas_price, 1)
+            + gas_used.storage_cost / std::cmp::max(reference_gas_price, 1);
+        let recommended_budget = (gas_units * (100 + GAS_ESTIMATE_SAFETY_MARGIN_PERCENT) / 100)
+            .clamp(*MIN_GAS_BUDGET, *MAX_GAS_BUDGET);
+
+        let gas_estimate = SuiGasEstimate {
+            gas_used: gas_used.into(),
+            gas_price: tx_data.gas_price,
+            gas_units,
+            recommended_budget,
+        };
+
+        // The coins written by the transaction count for their new balance, and the input
+        // coins it wrote or deleted for their previous balance.
+        let mut balances: BTreeMap<(SuiAddress, String), i128> = BTreeMap::new();
+        for (_, object) in temporary_store.written().values() {
+            if let Some((owner, coin_type, value)) = coin_balance(object) {
+                *balances.entry((owner, coin_type)).or_default() += value as i128;
+            }
+        }
+        for (id, object) in temporary_store.objects() {
+            if !temporary_store.written().contains_key(id)
+                && !temporary_store.deleted().contains_key(id)
+            {
+                continue;
+            }
+            if let Some((owner, coin_type, value)) = coin_balance(object) {
+                *balances.entry((owner, coin_type)).or_default() -= value as i128;
+            }
+        }
+        let balance_changes = balances
+            .into_iter()
+            .filter(|(_, amount)| *amount != 0)
+            .map(|((owner, coin_type), amount)| SuiBalanceChange {
+                owner,
+                coin_type,
+                amount,
+            })
+            .collect();
+
+        let effects = SuiTransactionEffects::try_from(effects, &self.module_cache)?;
+        let transferred = effects
+            .mutated
+            .iter()
+            .filter(|oref| {
+                temporary_store
+                    .objects()
+                    .get(&oref.reference.object_id)
+                    .map_or(false, |input| input.owner != oref.owner)
+            })
+            .cloned()
+            .collect();
+        let written = temporary_store
+            .written()
+            .iter()
+            .map(|(id, (_, object))| (*id, object.clone()))
+            .collect();
+
+        Ok((
+            SuiTransactionPreview {
+                effects,
+                transferred,
+                balance_changes,
+                gas_estimate,
+            },
+            written,
+        ))
+    }
+
     async fn get_object_ref(&self, object_id: &ObjectID) -> SuiResult<ObjectRef> {
         let object = self.get_object_internal(object_id).await?;
         Ok(object.compute_object_reference())
@@ -602,12 +899,13 @@ where
         Ok((new_certificate, effects))
     }
 
-    /// Execute (or retry) a transaction and execute the Confirmation Transaction.
+    /// Execute (or retry) a transaction and execute the Confirmation Transaction, as its
+    /// attempt number `attempts`.
     /// Update local object states using newly created certificate and ObjectInfoResponse from the Confirmation step.
     async fn execute_transaction_impl(
         &self,
         transaction: Transaction,
-        is_last_retry: bool,
+        attempts: u32,
     ) -> Result<(CertifiedTransaction, CertifiedTransactionEffects), anyhow::Error> {
         transaction.verify()?;
 
@@ -620,8 +918,12 @@ where
         self.download_object_from_authorities(SUI_SYSTEM_STATE_OBJECT_ID)
             .await?;
 
-        let (_gas_status, input_objects) =
-            transaction_input_checker::check_transaction_input(&self.store, &transaction).await?;
+        let (_gas_status, input_objects) = transaction_input_checker::check_transaction_input(
+            &self.store,
+            &transaction,
+            &self.protocol_config,
+        )
+        .await?;
 
         let owned_objects = input_objects.filter_owned_objects();
         if let Err(err) = self
@@ -664,10 +966,12 @@ where
                 }
             });
 
-        if exec_result.is_err() && is_last_retry {
-            // If we cannot successfully execute this transaction, even after all the retries,
-            // we have to give up. Here we reset all transaction locks for each input object.
-            self.store.reset_transaction_lock(&owned_objects).await?;
+        if let Err(error) = &exec_result {
+            if !self.should_retry_transaction(attempts, error) {
+                // If we cannot successfully execute this transaction, even after all the retries,
+                // we have to give up. Here we reset all transaction locks for each input object.
+                self.store.reset_transaction_lock(&owned_objects).await?;
+            }
         }
 
         exec_result
@@ -1024,29 +1328,45 @@ where
                     objects.insert(id, obj);
                     arg
                 }
+                SuiJsonCallArg::Result(result) => CallArg::Object(ObjectArg::Result(result)),
                 SuiJsonCallArg::Pure(bytes) => CallArg::Pure(bytes),
             })
         }
 
-        // Pass in the objects for a deeper check
-        let is_genesis = false;
         let type_arguments = type_arguments
             .into_iter()
             .map(|arg| arg.try_into())
             .collect::<Result<Vec<_>, _>>()?;
+        // Pass in the objects for a deeper check
         let compiled_module = package_obj
             .data
             .try_as_package()
             .ok_or_else(|| anyhow!("Cannot get package from object"))?
             .deserialize_module(&module)?;
-        resolve_and_type_check(
-            &objects,
-            &compiled_module,
-            &function,
-            &type_arguments,
-            args.clone(),
-            is_genesis,
-        )?;
+        // Objects created by earlier transactions of a batch do not exist yet, so the arguments
+        // using them are checked again once they are resolved during execution.
+        let uses_command_results = args
+            .iter()
+            .any(|arg| matches!(arg, CallArg::Object(ObjectArg::Result(_))));
+        if uses_command_results {
+            type_check_with_command_results(
+                &objects,
+                &compiled_module,
+                &function,
+                &type_arguments,
+                args.clone(),
+            )?;
+        } else {
+            let is_genesis = false;
+            resolve_and_type_check(
+                &objects,
+                &compiled_module,
+                &function,
+                &type_arguments,
+                args.clone(),
+                is_genesis,
+            )?;
+        }
         used_object_ids.extend(objects.keys());
 
         Ok(SingleTransactionKind::Call(MoveCall {
@@ -1076,9 +1396,10 @@ where
 }
 
 #[async_trait]
-impl<A> GatewayAPI for GatewayState<A>
+impl<A, S> GatewayAPI for GatewayState<A, S>
 where
     A: AuthorityAPI + Send + Sync + Clone + 'static,
+    S: ClientStore,
 {
     async fn execute_transaction(
         &self,
@@ -1101,36 +1422,35 @@ where
 
                 // Use start_coarse_time() if the below turns out to have a perf impact
                 let timer = self.metrics.transaction_latency.start_timer();
+                let mut attempts = 1;
                 let mut res = self
-                    .execute_transaction_impl(tx.clone(), false)
+                    .execute_transaction_impl(tx.clone(), attempts)
                     .instrument(span.clone())
                     .await;
                 // NOTE: below only records latency if this completes.
                 timer.stop_and_record();
 
-                let mut remaining_retries = MAX_NUM_TX_RETRIES;
-                while res.is_err() {
-                    if remaining_retries == 0 {
-                        error!(
-                            num_retries = MAX_NUM_TX_RETRIES,
-                            ?tx_digest,
-                            "All transaction retries failed"
-                        );
+                while let Err(err) = &res {
+                    if !self.should_retry_transaction(attempts, err) {
+                        error!(attempts, ?tx_digest, "All transaction retries failed");
                         // Okay to unwrap since we checked that this is an error
                         return Err(res.unwrap_err());
                     }
-                    remaining_retries -= 1;
+                    let delay = self.retry_config.backoff(attempts);
+                    attempts += 1;
                     self.metrics.total_tx_retries.inc();
 
                     debug!(
-                        remaining_retries,
+                        attempts,
                         ?tx_digest,
                         ?res,
-                        "Retrying failed transaction"
+                        "Retrying failed transaction in {:?}",
+                        delay
                     );
+                    tokio::time::sleep(delay).await;
 
                     res = self
-                        .execute_transaction_impl(tx.clone(), remaining_retries == 0)
+                        .execute_transaction_impl(tx.clone(), attempts)
                         .instrument(span.clone())
                         .await;
                 }
@@ -1198,7 +1518,13 @@ where
         let gas_payment = self
             .choose_gas_for_address(signer, gas_budget, gas, used_object_ids)
             .await?;
-        Ok(TransactionData::new(kind, signer, gas_payment, gas_budget))
+        Ok(TransactionData::new(
+            kind,
+            signer,
+            gas_payment,
+            gas_budget,
+            self.chain_id,
+        ))
     }
 
     async fn transfer_sui(
@@ -1211,9 +1537,104 @@ where
     ) -> Result<TransactionData, anyhow::Error> {
         let object = self.get_object_internal(&sui_object_id).await?;
         let object_ref = object.compute_object_reference();
-        let data =
-            TransactionData::new_transfer_sui(recipient, signer, amount, object_ref, gas_budget);
-        Ok(data)
+        Ok(TransactionData::new_transfer_sui(
+            recipient,
+            signer,
+            amount,
+            object_ref,
+            gas_budget,
+            self.chain_id,
+        ))
+    }
+
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if input_coins.is_empty() {
+            return Err(anyhow!("No coins to pay with"));
+        }
+        if recipients.len() != amounts.len() {
+            return Err(anyhow!(
+                "Found {} recipients but {} amounts",
+                recipients.len(),
+                amounts.len()
+            ));
+        }
+        let mut coins = Vec::with_capacity(input_coins.len());
+        for id in &input_coins {
+            coins.push(
+                self.get_object_internal(id)
+                    .await?
+                    .compute_object_reference(),
+            );
+        }
+        let gas_payment = self
+            .choose_gas_for_address(signer, gas_budget, gas, input_coins.into_iter().collect())
+            .await?;
+        Ok(TransactionData::new_pay(
+            signer,
+            coins,
+            recipients,
+            amounts,
+            gas_payment,
+            gas_budget,
+            self.chain_id,
+        ))
+    }
+
+    async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        let (gas, input_coins) = input_coins
+            .split_first()
+            .ok_or_else(|| anyhow!("No coins to pay with"))?;
+        let gas_payment = self
+            .get_object_internal(gas)
+            .await?
+            .compute_object_reference();
+        // The gas coin is transferred last, its balance is only known once the gas is charged.
+        let transfer_gas = SingleTransactionKind::TransferSui(TransferSui {
+            recipient,
+            amount: None,
+        });
+        if input_coins.is_empty() {
+            return Ok(TransactionData::new(
+                TransactionKind::Single(transfer_gas),
+                signer,
+                gas_payment,
+                gas_budget,
+                self.chain_id,
+            ));
+        }
+        let mut coins = Vec::with_capacity(input_coins.len());
+        let mut total: u64 = 0;
+        for id in input_coins {
+            let object = self.get_object_internal(id).await?;
+            total += GasCoin::try_from(&object)?.value();
+            coins.push(object.compute_object_reference());
+        }
+        let pay = SingleTransactionKind::Pay(Pay {
+            coins,
+            recipients: vec![recipient],
+            amounts: vec![total],
+        });
+        Ok(TransactionData::new(
+            TransactionKind::Batch(vec![pay, transfer_gas]),
+            signer,
+            gas_payment,
+            gas_budget,
+            self.chain_id,
+        ))
     }
 
     async fn batch_transaction(
@@ -1242,6 +1663,15 @@ where
                     self.create_move_call_transaction_kind(m, &mut used_object_ids)
                         .await?
                 }
+                RPCTransactionRequestParams::PublishRequestParams(p) => {
+                    SingleTransactionKind::Publish(MoveModulePublish {
+                        modules: p
+                            .compiled_modules
+                            .into_iter()
+                            .map(|data| data.to_vec())
+                            .collect::<Result<Vec<_>, _>>()?,
+                    })
+                }
             };
             all_tx_kind.push(kind);
         }
@@ -1253,6 +1683,7 @@ where
             signer,
             gas,
             gas_budget,
+            self.chain_id,
         ))
     }
 
@@ -1312,7 +1743,7 @@ where
         let gas = self
             .choose_gas_for_address(signer, gas_budget, gas, used_object_ids)
             .await?;
-        let data = TransactionData::new(kind, signer, gas, gas_budget);
+        let data = TransactionData::new(kind, signer, gas, gas_budget, self.chain_id);
         debug!(?data, "Created Move Call transaction data");
         Ok(data)
     }
@@ -1327,8 +1758,13 @@ where
         let gas = self
             .choose_gas_for_address(signer, gas_budget, gas, BTreeSet::new())
             .await?;
-        let data = TransactionData::new_module(signer, gas, package_bytes, gas_budget);
-        Ok(data)
+        Ok(TransactionData::new_module(
+            signer,
+            gas,
+            package_bytes,
+            gas_budget,
+            self.chain_id,
+        ))
     }
 
     async fn split_coin(
@@ -1357,6 +1793,7 @@ where
                 CallArg::Pure(bcs::to_bytes(&split_amounts)?),
             ],
             gas_budget,
+            self.chain_id,
         );
         debug!(?data, "Created Split Coin transaction data");
         Ok(data)
@@ -1395,11 +1832,68 @@ where
                 CallArg::Object(ObjectArg::ImmOrOwnedObject(coin_to_merge_ref)),
             ],
             gas_budget,
+            self.chain_id,
         );
         debug!(?data, "Created Merge Coin transaction data");
         Ok(data)
     }
 
+    async fn estimate_gas(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiGasEstimate, anyhow::Error> {
+        self.metrics.num_gas_estimates.inc();
+        Ok(self.dry_run_impl(tx_data).await?.0.gas_estimate)
+    }
+
+    async fn dry_run_transaction(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionPreview, anyhow::Error> {
+        Ok(self.dry_run_impl(tx_data).await?.0)
+    }
+
+    async fn dry_run_publish(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiPublishPreview, anyhow::Error> {
+        let is_publish = matches!(
+            tx_data.kind,
+            TransactionKind::Single(SingleTransactionKind::Publish(_))
+        );
+        if !is_publish {
+            return Err(anyhow!("Transaction is not a package publication"));
+        }
+        self.metrics.num_gas_estimates.inc();
+        let (preview, written) = self.dry_run_impl(tx_data).await?;
+
+        // The package is not in the store yet, the types of the created objects are
+        // reported without their contents.
+        let mut package = None;
+        let mut created_objects = vec![];
+        for created in &preview.effects.created {
+            let object = written.get(&created.reference.object_id).ok_or_else(|| {
+                anyhow!(
+                    "Created object {} is missing from the dry run",
+                    created.reference.object_id
+                )
+            })?;
+            if object.is_package() {
+                package = Some(created.reference.clone());
+            } else {
+                let object_ref = object.compute_object_reference();
+                created_objects.push(ObjectInfo::new(&object_ref, object).into());
+            }
+        }
+        let package = package.ok_or_else(|| anyhow!("No package created by the dry run"))?;
+
+        Ok(SuiPublishPreview {
+            package,
+            created_objects,
+            gas_estimate: preview.gas_estimate,
+        })
+    }
+
     async fn get_object(
         &self,
         object_id: ObjectID,
@@ -1416,6 +1910,22 @@ where
         Ok(result.try_into()?)
     }
 
+    async fn get_raw_package(
+        &self,
+        package_id: ObjectID,
+    ) -> Result<SuiRawMovePackage, anyhow::Error> {
+        let result = self.download_object_from_authorities(package_id).await?;
+        result.try_into()
+    }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, anyhow::Error> {
+        let result = self.download_object_from_authorities(package).await?;
+        normalize_package(result)
+    }
+
     async fn get_objects_owned_by_address(
         &self,
         account_addr: SuiAddress,
@@ -1474,3 +1984,18 @@ where
         })
     }
 }
+
+/// The owner, coin type and value of `object` if it is a coin owned by an address.
+fn coin_balance(object: &Object) -> Option<(SuiAddress, String, u64)> {
+    let move_object = object.data.try_as_move()?;
+    if !coin::Coin::is_coin(&move_object.type_) {
+        return None;
+    }
+    let owner = match object.owner {
+        Owner::AddressOwner(address) => address,
+        _ => return None,
+    };
+    let coin_type = move_object.type_.type_params.first()?.to_string();
+    let coin = coin::Coin::from_bcs_bytes(move_object.contents()).ok()?;
+    Some((owner, coin_type, coin.value()))
+}
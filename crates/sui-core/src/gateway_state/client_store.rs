@@ -0,0 +1,446 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap};
+
+use async_trait::async_trait;
+use move_core_types::language_storage::ModuleId;
+use move_core_types::resolver::ModuleResolver;
+use parking_lot::RwLock;
+
+use sui_adapter::temporary_store::TemporaryStore;
+use sui_types::base_types::{
+    ExecutionDigests, ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber,
+    TransactionDigest, TransactionEffectsDigest,
+};
+use sui_types::batch::TxSequenceNumber;
+use sui_types::error::{SuiError, SuiResult};
+use sui_types::fp_ensure;
+use sui_types::messages::{
+    CertifiedTransaction, InputObjects, Transaction, TransactionEffects, UnsignedTransactionEffects,
+};
+use sui_types::object::{Object, Owner};
+use sui_types::storage::{BackingPackageStore, DeleteKind, Storage};
+
+use crate::authority::{GatewayStore, ReadStore};
+
+/// The persistence backend of a [`GatewayState`](super::GatewayState): the objects of the
+/// accounts it manages, the locks of its pending transactions and the certificates and effects
+/// of the transactions it executed.
+#[async_trait]
+pub trait ClientStore:
+    ReadStore + BackingPackageStore + ModuleResolver<Error = SuiError> + Send + Sync + 'static
+{
+    /// Returns the pending transaction with this digest, if the gateway holds its locks.
+    fn get_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<Transaction>>;
+
+    /// Acquires the locks of `owned_input_objects` for `transaction` and records it as pending.
+    async fn lock_and_write_transaction(
+        &self,
+        owned_input_objects: &[ObjectRef],
+        transaction: Transaction,
+    ) -> SuiResult;
+
+    /// Releases the locks of a transaction that could not be executed.
+    async fn reset_transaction_lock(&self, owned_input_objects: &[ObjectRef]) -> SuiResult;
+
+    /// Inserts an object synced from the authorities, without a certificate.
+    async fn insert_object_direct(&self, object_ref: ObjectRef, object: &Object) -> SuiResult;
+
+    /// Applies the effects of a transaction executed by the authorities, and assigns it the
+    /// gateway sequence number `proposed_seq` unless it already has one.
+    async fn update_gateway_state(
+        &self,
+        input_objects: InputObjects,
+        mutated_objects: HashMap<ObjectRef, Object>,
+        certificate: CertifiedTransaction,
+        proposed_seq: TxSequenceNumber,
+        effects: UnsignedTransactionEffects,
+        effects_digest: &TransactionEffectsDigest,
+    ) -> SuiResult;
+}
+
+#[async_trait]
+impl ClientStore for GatewayStore {
+    fn get_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<Transaction>> {
+        GatewayStore::get_transaction(self, transaction_digest)
+    }
+
+    async fn lock_and_write_transaction(
+        &self,
+        owned_input_objects: &[ObjectRef],
+        transaction: Transaction,
+    ) -> SuiResult {
+        GatewayStore::lock_and_write_transaction(self, owned_input_objects, transaction).await
+    }
+
+    async fn reset_transaction_lock(&self, owned_input_objects: &[ObjectRef]) -> SuiResult {
+        GatewayStore::reset_transaction_lock(self, owned_input_objects).await
+    }
+
+    async fn insert_object_direct(&self, object_ref: ObjectRef, object: &Object) -> SuiResult {
+        GatewayStore::insert_object_direct(self, object_ref, object).await
+    }
+
+    async fn update_gateway_state(
+        &self,
+        input_objects: InputObjects,
+        mutated_objects: HashMap<ObjectRef, Object>,
+        certificate: CertifiedTransaction,
+        proposed_seq: TxSequenceNumber,
+        effects: UnsignedTransactionEffects,
+        effects_digest: &TransactionEffectsDigest,
+    ) -> SuiResult {
+        GatewayStore::update_gateway_state(
+            self,
+            input_objects,
+            mutated_objects,
+            certificate,
+            proposed_seq,
+            effects,
+            effects_digest,
+        )
+        .await
+    }
+}
+
+#[derive(Default)]
+struct InMemoryTables {
+    objects: BTreeMap<(ObjectID, SequenceNumber), Object>,
+    owner_index: BTreeMap<(Owner, ObjectID), ObjectInfo>,
+    parent_sync: BTreeMap<ObjectRef, TransactionDigest>,
+    /// Pending transactions, removed once executed.
+    transactions: BTreeMap<TransactionDigest, Transaction>,
+    certificates: BTreeMap<TransactionDigest, CertifiedTransaction>,
+    effects: BTreeMap<TransactionDigest, UnsignedTransactionEffects>,
+    executed_sequence: BTreeMap<TxSequenceNumber, ExecutionDigests>,
+    tx_sequence: BTreeMap<TransactionDigest, TxSequenceNumber>,
+    /// The initialized locks of the owned objects, set to the pending transaction using them.
+    locks: BTreeMap<ObjectRef, Option<TransactionDigest>>,
+}
+
+/// A [`ClientStore`] holding the gateway state in memory, with the same semantics as the
+/// RocksDB [`GatewayStore`]. The state is lost when the store is dropped, which suits tests and
+/// short-lived integrations.
+#[derive(Default)]
+pub struct InMemoryClientStore {
+    tables: RwLock<InMemoryTables>,
+}
+
+impl InMemoryClientStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ReadStore for InMemoryClientStore {
+    fn get_object(&self, object_id: &ObjectID) -> SuiResult<Option<Object>> {
+        let tables = self.tables.read();
+        let object = tables
+            .objects
+            .range(..=(*object_id, SequenceNumber::MAX))
+            .next_back()
+            .filter(|((id, _), _)| id == object_id)
+            .map(|(_, object)| object);
+        let alive = object.is_some()
+            && matches!(
+                latest_parent_entry(&tables, *object_id),
+                Some((obj_ref, _)) if obj_ref.2.is_alive()
+            );
+        Ok(if alive { object.cloned() } else { None })
+    }
+
+    fn next_sequence_number(&self) -> SuiResult<TxSequenceNumber> {
+        Ok(self
+            .tables
+            .read()
+            .executed_sequence
+            .keys()
+            .next_back()
+            .map(|seq| seq + 1)
+            .unwrap_or(0))
+    }
+
+    fn transactions_in_seq_range(
+        &self,
+        start: TxSequenceNumber,
+        end: TxSequenceNumber,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        if start >= end {
+            return Ok(vec![]);
+        }
+        Ok(self
+            .tables
+            .read()
+            .executed_sequence
+            .range(start..end)
+            .map(|(seq, digests)| (*seq, digests.transaction))
+            .collect())
+    }
+
+    fn get_certified_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<CertifiedTransaction>> {
+        Ok(self
+            .tables
+            .read()
+            .certificates
+            .get(transaction_digest)
+            .cloned())
+    }
+
+    fn get_effects(&self, transaction_digest: &TransactionDigest) -> SuiResult<TransactionEffects> {
+        self.tables
+            .read()
+            .effects
+            .get(transaction_digest)
+            .map(|envelope| envelope.effects.clone())
+            .ok_or(SuiError::TransactionNotFound {
+                digest: *transaction_digest,
+            })
+    }
+
+    fn get_latest_parent_entry(
+        &self,
+        object_id: ObjectID,
+    ) -> SuiResult<Option<(ObjectRef, TransactionDigest)>> {
+        Ok(latest_parent_entry(&self.tables.read(), object_id))
+    }
+
+    fn get_owner_objects(&self, owner: Owner) -> SuiResult<Vec<ObjectInfo>> {
+        Ok(self
+            .tables
+            .read()
+            .owner_index
+            .range((owner, ObjectID::ZERO)..)
+            .take_while(|((object_owner, _), _)| object_owner == &owner)
+            .map(|(_, info)| info.clone())
+            .collect())
+    }
+}
+
+fn latest_parent_entry(
+    tables: &InMemoryTables,
+    object_id: ObjectID,
+) -> Option<(ObjectRef, TransactionDigest)> {
+    tables
+        .parent_sync
+        .range(..=(object_id, SequenceNumber::MAX, ObjectDigest::MAX))
+        .next_back()
+        .filter(|(obj_ref, _)| obj_ref.0 == object_id)
+        .map(|(obj_ref, digest)| (*obj_ref, *digest))
+}
+
+#[async_trait]
+impl ClientStore for InMemoryClientStore {
+    fn get_transaction(
+        &self,
+        transaction_digest: &TransactionDigest,
+    ) -> SuiResult<Option<Transaction>> {
+        Ok(self
+            .tables
+            .read()
+            .transactions
+            .get(transaction_digest)
+            .cloned())
+    }
+
+    async fn lock_and_write_transaction(
+        &self,
+        owned_input_objects: &[ObjectRef],
+        transaction: Transaction,
+    ) -> SuiResult {
+        let tx_digest = *transaction.digest();
+        let mut tables = self.tables.write();
+        for obj_ref in owned_input_objects {
+            match tables.locks.get(obj_ref) {
+                None => return Err(SuiError::TransactionLockDoesNotExist),
+                Some(Some(previous_tx_digest)) if previous_tx_digest != &tx_digest => {
+                    return Err(SuiError::ConflictingTransaction {
+                        pending_transaction: *previous_tx_digest,
+                    });
+                }
+                Some(_) => (),
+            }
+        }
+        for obj_ref in owned_input_objects {
+            tables.locks.insert(*obj_ref, Some(tx_digest));
+        }
+        tables.transactions.insert(tx_digest, transaction);
+        Ok(())
+    }
+
+    async fn reset_transaction_lock(&self, owned_input_objects: &[ObjectRef]) -> SuiResult {
+        let mut tables = self.tables.write();
+        for obj_ref in owned_input_objects {
+            tables.locks.insert(*obj_ref, None);
+        }
+        Ok(())
+    }
+
+    async fn insert_object_direct(&self, object_ref: ObjectRef, object: &Object) -> SuiResult {
+        let mut tables = self.tables.write();
+        if object.get_single_owner().is_some() {
+            if let Some(Some(_)) = tables.locks.get(&object_ref) {
+                return Err(SuiError::TransactionLockExists {
+                    refs: vec![object_ref],
+                });
+            }
+            tables.locks.insert(object_ref, None);
+            tables.owner_index.insert(
+                (object.owner, object_ref.0),
+                ObjectInfo::new(&object_ref, object),
+            );
+        }
+        tables
+            .objects
+            .insert((object_ref.0, object_ref.1), object.clone());
+        tables
+            .parent_sync
+            .insert(object_ref, object.previous_transaction);
+        Ok(())
+    }
+
+    async fn update_gateway_state(
+        &self,
+        input_objects: InputObjects,
+        mutated_objects: HashMap<ObjectRef, Object>,
+        certificate: CertifiedTransaction,
+        proposed_seq: TxSequenceNumber,
+        effects: UnsignedTransactionEffects,
+        effects_digest: &TransactionEffectsDigest,
+    ) -> SuiResult {
+        let transaction_digest = *certificate.digest();
+        let mut temporary_store = TemporaryStore::new(self, input_objects, transaction_digest);
+        for (_, object) in mutated_objects {
+            temporary_store.write_object(object);
+        }
+        for obj_ref in &effects.effects.deleted {
+            temporary_store.delete_object(&obj_ref.0, obj_ref.1, DeleteKind::Normal);
+        }
+        for obj_ref in &effects.effects.wrapped {
+            temporary_store.delete_object(&obj_ref.0, obj_ref.1, DeleteKind::Wrap);
+        }
+        let (objects, active_inputs, written, deleted, _events) = temporary_store.into_inner();
+
+        let owned_inputs: Vec<_> = active_inputs
+            .into_iter()
+            .filter(|(id, _, _)| objects[id].is_owned_or_quasi_shared())
+            .collect();
+
+        // Unlike the RocksDB store, all the writes happen under the same lock, so the locks are
+        // checked before anything is written.
+        let mut tables = self.tables.write();
+        let assigned_seq = match tables.tx_sequence.get(&transaction_digest) {
+            Some(seq) => *seq,
+            None => {
+                fp_ensure!(
+                    owned_inputs
+                        .iter()
+                        .all(|obj_ref| tables.locks.contains_key(obj_ref)),
+                    SuiError::TransactionLockDoesNotExist
+                );
+                tables.tx_sequence.insert(transaction_digest, proposed_seq);
+                for (object_ref, new_object) in written.values() {
+                    if new_object.is_owned_or_quasi_shared() {
+                        tables.locks.entry(*object_ref).or_insert(None);
+                    }
+                }
+                proposed_seq
+            }
+        };
+        for obj_ref in &owned_inputs {
+            tables.locks.remove(obj_ref);
+        }
+
+        // Objects that are deleted, wrapped or have changed owner leave the index of their
+        // old owner.
+        for (id, _) in deleted.iter() {
+            if let Some(owner_id) = objects.get(id).and_then(Object::get_owner_and_id) {
+                tables.owner_index.remove(&owner_id);
+            }
+        }
+        for (id, (_, new_object)) in written.iter() {
+            match objects.get(id) {
+                Some(old_object) if old_object.owner != new_object.owner => {
+                    if let Some(owner_id) = old_object.get_owner_and_id() {
+                        tables.owner_index.remove(&owner_id);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for (object_id, (version, kind)) in deleted {
+            let digest = if kind == DeleteKind::Wrap {
+                ObjectDigest::OBJECT_DIGEST_WRAPPED
+            } else {
+                ObjectDigest::OBJECT_DIGEST_DELETED
+            };
+            tables
+                .parent_sync
+                .insert((object_id, version, digest), transaction_digest);
+        }
+        for (_, (object_ref, new_object)) in written {
+            tables.parent_sync.insert(object_ref, transaction_digest);
+            if let Some(owner_id) = new_object.get_owner_and_id() {
+                tables
+                    .owner_index
+                    .insert(owner_id, ObjectInfo::new(&object_ref, &new_object));
+            }
+            tables
+                .objects
+                .insert((object_ref.0, object_ref.1), new_object);
+        }
+
+        tables.transactions.remove(&transaction_digest);
+        tables.certificates.insert(transaction_digest, certificate);
+        tables.effects.insert(transaction_digest, effects);
+        tables.executed_sequence.insert(
+            assigned_seq,
+            ExecutionDigests::new(transaction_digest, *effects_digest),
+        );
+        Ok(())
+    }
+}
+
+impl BackingPackageStore for InMemoryClientStore {
+    fn get_package(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        let package = self.get_object(package_id)?;
+        if let Some(obj) = &package {
+            fp_ensure!(
+                obj.is_package(),
+                SuiError::BadObjectType {
+                    error: format!("Package expected, Move object found: {package_id}"),
+                }
+            );
+        }
+        Ok(package)
+    }
+}
+
+impl ModuleResolver for InMemoryClientStore {
+    type Error = SuiError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .get_package(&ObjectID::from(*module_id.address()))?
+            .and_then(|package| {
+                // unwrap safe since get_package() ensures it's a package object.
+                package
+                    .data
+                    .try_as_package()
+                    .unwrap()
+                    .serialized_module_map()
+                    .get(module_id.name().as_str())
+                    .cloned()
+            }))
+    }
+}
@@ -16,8 +16,8 @@ use sui_types::{
     batch::UpdateItem,
     crypto::{get_key_pair, AuthoritySignature, KeypairTraits, PublicKeyBytes, Signature},
     messages::{
-        CallArg, EntryArgumentErrorKind, ExecutionFailureStatus, ExecutionStatus, ObjectArg,
-        ObjectInfoRequestKind, SingleTransactionKind, TransactionKind,
+        CallArg, EntryArgumentErrorKind, ExecutionFailureStatus, ExecutionLimit, ExecutionStatus,
+        ObjectArg, ObjectInfoRequestKind, SingleTransactionKind, TransactionKind,
     },
     object::{Data, Owner},
 };
@@ -64,6 +64,7 @@ fn get_registry() -> Result<Registry> {
     tracer.trace_type::<ExecutionFailureStatus>(&samples)?;
     tracer.trace_type::<AbortLocation>(&samples)?;
     tracer.trace_type::<EntryArgumentErrorKind>(&samples)?;
+    tracer.trace_type::<ExecutionLimit>(&samples)?;
     tracer.trace_type::<CallArg>(&samples)?;
     tracer.trace_type::<ObjectArg>(&samples)?;
     tracer.trace_type::<Data>(&samples)?;
@@ -7,15 +7,22 @@ pub mod authority_aggregator;
 pub mod authority_batch;
 pub mod authority_client;
 pub mod authority_server;
+pub mod byzantine;
 pub mod checkpoints;
 pub mod consensus_adapter;
 pub mod epoch;
 pub mod event_handler;
 pub mod execution_engine;
+pub mod fault_injection;
 pub mod gateway_state;
+pub mod node_faults;
+pub mod retry;
 pub mod safe_client;
+pub mod serving_policy;
+pub mod simulator;
 pub mod streamer;
 pub mod test_utils;
+pub mod traffic_capture;
 pub mod transaction_input_checker;
 
 mod node_sync;
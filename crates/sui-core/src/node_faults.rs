@@ -0,0 +1,152 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Faults of the nodes of a test cluster: crashes at given points and lagging networking.
+//!
+//! The nodes of a cluster running in one process share these faults, each set for the authority
+//! of a node. A crash point armed for an authority fires the next time the authority reaches
+//! it: the task reaching it panics, so that it goes no further, and the [crash
+//! listener](crash_listener) of the authority is notified, for the node to shut down as if it
+//! crashed there. Lag delays every request the validator of an authority serves.
+//!
+//! Until a fault is set, reaching a crash point or serving a request only reads a flag.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use tracing::error;
+
+use sui_types::base_types::AuthorityName;
+
+/// Reached before the effects of a certificate are committed to the store.
+pub const BEFORE_COMMIT_CERTIFICATE: &str = "before_commit_certificate";
+/// Reached once the effects of a certificate are committed, before the response is sent.
+pub const AFTER_COMMIT_CERTIFICATE: &str = "after_commit_certificate";
+
+#[derive(Default)]
+struct NodeFaults {
+    armed: HashMap<AuthorityName, HashSet<&'static str>>,
+    crash_listeners: HashMap<AuthorityName, Arc<Notify>>,
+    lag: HashMap<AuthorityName, Duration>,
+}
+
+/// Whether any fault was ever set, for the nodes without faults to skip the lock.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FAULTS: Lazy<Mutex<NodeFaults>> = Lazy::new(Default::default);
+
+fn faults() -> parking_lot::MutexGuard<'static, NodeFaults> {
+    ENABLED.store(true, Ordering::Relaxed);
+    FAULTS.lock()
+}
+
+/// Crash `authority` the next time it reaches `point`.
+pub fn arm_crash_point(authority: AuthorityName, point: &'static str) {
+    faults().armed.entry(authority).or_default().insert(point);
+}
+
+/// Disarm the crash points of `authority` not reached yet.
+pub fn disarm_crash_points(authority: &AuthorityName) {
+    faults().armed.remove(authority);
+}
+
+/// Notified when `authority` reaches one of its armed crash points.
+pub fn crash_listener(authority: AuthorityName) -> Arc<Notify> {
+    faults()
+        .crash_listeners
+        .entry(authority)
+        .or_default()
+        .clone()
+}
+
+/// Crash if `point` is armed for `authority`, see the [module documentation](self).
+pub fn crash_point(authority: &AuthorityName, point: &'static str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut faults = FAULTS.lock();
+    let fired = faults
+        .armed
+        .get_mut(authority)
+        .map_or(false, |armed| armed.remove(point));
+    if !fired {
+        return;
+    }
+    if let Some(listener) = faults.crash_listeners.get(authority) {
+        listener.notify_one();
+    }
+    drop(faults);
+    error!(?authority, "Crashing at crash point {point}");
+    panic!("{authority:?} crashed at crash point {point}");
+}
+
+/// Delay the requests served by the validator of `authority` by `lag`, none if zero.
+pub fn set_network_lag(authority: AuthorityName, lag: Duration) {
+    let mut faults = faults();
+    if lag.is_zero() {
+        faults.lag.remove(&authority);
+    } else {
+        faults.lag.insert(authority, lag);
+    }
+}
+
+/// Wait for the lag of the networking of `authority`.
+pub async fn network_lag(authority: &AuthorityName) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let lag = FAULTS.lock().lag.get(authority).copied();
+    if let Some(lag) = lag {
+        tokio::time::sleep(lag).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::crypto::{get_key_pair, KeypairTraits};
+
+    #[tokio::test]
+    async fn crash_points_fire_once() {
+        let (_, key_pair) = get_key_pair();
+        let authority: AuthorityName = key_pair.public().into();
+        let listener = crash_listener(authority);
+
+        crash_point(&authority, BEFORE_COMMIT_CERTIFICATE);
+        arm_crash_point(authority, BEFORE_COMMIT_CERTIFICATE);
+        crash_point(&authority, AFTER_COMMIT_CERTIFICATE);
+        let crash = std::panic::catch_unwind(|| {
+            crash_point(&authority, BEFORE_COMMIT_CERTIFICATE);
+        });
+        assert!(crash.is_err());
+        listener.notified().await;
+        // Crash points are disarmed once reached
+        crash_point(&authority, BEFORE_COMMIT_CERTIFICATE);
+
+        arm_crash_point(authority, AFTER_COMMIT_CERTIFICATE);
+        disarm_crash_points(&authority);
+        crash_point(&authority, AFTER_COMMIT_CERTIFICATE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn network_lags() {
+        let (_, key_pair) = get_key_pair();
+        let authority: AuthorityName = key_pair.public().into();
+        let start = tokio::time::Instant::now();
+        network_lag(&authority).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        set_network_lag(authority, Duration::from_secs(1));
+        network_lag(&authority).await;
+        assert!(start.elapsed() >= Duration::from_secs(1));
+
+        set_network_lag(authority, Duration::ZERO);
+        let start = tokio::time::Instant::now();
+        network_lag(&authority).await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+}
@@ -1,10 +1,8 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::SuiDataStore;
+use crate::authority::ReadStore;
 use anyhow::anyhow;
-use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
 use sui_types::messages::{CertifiedTransaction, TransactionEffects};
 use sui_types::{base_types::*, batch::TxSequenceNumber, error::SuiError, fp_ensure};
 use tracing::debug;
@@ -19,13 +17,13 @@ pub struct QueryHelpers<S> {
 // be duplicated between AuthorityState and GatewayState. The gateway read API will be removed
 // soon, since nodes will be handling that. At that point we should delete this struct and move the
 // code back to AuthorityState.
-impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> QueryHelpers<S> {
-    pub fn get_total_transaction_number(database: &SuiDataStore<S>) -> Result<u64, anyhow::Error> {
+impl<S: ReadStore> QueryHelpers<S> {
+    pub fn get_total_transaction_number(database: &S) -> Result<u64, anyhow::Error> {
         Ok(database.next_sequence_number()?)
     }
 
     pub fn get_transactions_in_range(
-        database: &SuiDataStore<S>,
+        database: &S,
         start: TxSequenceNumber,
         end: TxSequenceNumber,
     ) -> Result<Vec<(TxSequenceNumber, TransactionDigest)>, anyhow::Error> {
@@ -56,7 +54,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> QueryHelpers<S> {
     }
 
     pub fn get_recent_transactions(
-        database: &SuiDataStore<S>,
+        database: &S,
         count: u64,
     ) -> Result<Vec<(TxSequenceNumber, TransactionDigest)>, anyhow::Error> {
         fp_ensure!(
@@ -75,7 +73,7 @@ impl<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>> QueryHelpers<S> {
     }
 
     pub fn get_transaction(
-        database: &SuiDataStore<S>,
+        database: &S,
         digest: &TransactionDigest,
     ) -> Result<(CertifiedTransaction, TransactionEffects), anyhow::Error> {
         let opt = database.get_certified_transaction(digest)?;
@@ -0,0 +1,172 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sui_types::error::SuiError;
+
+/// How failed requests to the authorities are retried: after an exponentially growing backoff,
+/// up to a number of attempts, and only for some errors.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RetryConfig {
+    /// The number of attempts, the first one included, before giving up. Unlimited if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    /// The backoff after the first failed attempt, multiplied by `multiplier` after every other.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: u32,
+    /// The fraction of every backoff which is random, from 0 for none to 1 for a backoff
+    /// anywhere between zero and the computed one. Spreads the retries of concurrent clients.
+    #[serde(default)]
+    pub jitter: f64,
+    #[serde(default)]
+    pub retry_on: RetryOn,
+}
+
+/// Which errors an attempt is retried after.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    AnyError,
+    /// Only the errors which may not happen again, like timeouts or unreachable authorities,
+    /// see [`is_transient`].
+    TransientErrors,
+}
+
+impl Default for RetryOn {
+    fn default() -> Self {
+        RetryOn::AnyError
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5 * 60),
+            multiplier: 2,
+            jitter: 0.0,
+            retry_on: RetryOn::AnyError,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Whether to attempt again after `attempts` attempts, the last one failing with `error`.
+    pub fn should_retry(&self, attempts: u32, error: &SuiError) -> bool {
+        self.has_attempts_left(attempts) && self.is_retryable(error)
+    }
+
+    pub fn has_attempts_left(&self, attempts: u32) -> bool {
+        self.max_attempts
+            .map_or(true, |max_attempts| attempts < max_attempts)
+    }
+
+    pub fn is_retryable(&self, error: &SuiError) -> bool {
+        match self.retry_on {
+            RetryOn::AnyError => true,
+            RetryOn::TransientErrors => is_transient(error),
+        }
+    }
+
+    /// How long to wait after `attempts` failed attempts before the next one.
+    pub fn backoff(&self, attempts: u32) -> Duration {
+        let exponent = attempts.saturating_sub(1);
+        let factor = self.multiplier.checked_pow(exponent).unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        if self.jitter > 0.0 {
+            let jitter = self.jitter.min(1.0) * rand::thread_rng().gen::<f64>();
+            backoff.mul_f64(1.0 - jitter)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Whether `error` may not happen again when retrying the same request: timeouts, network
+/// errors, and authorities which are busy or temporarily halted.
+pub fn is_transient(error: &SuiError) -> bool {
+    match error {
+        SuiError::TimeoutError
+        | SuiError::RpcError(_)
+        | SuiError::ClientIoError { .. }
+        | SuiError::AuthorityInformationUnavailable
+        | SuiError::AuthorityUpdateFailure
+        | SuiError::ValidatorHaltedAtEpochEnd
+        | SuiError::ConcurrentTransactionError
+        | SuiError::ConsensusConnectionBroken(_)
+        | SuiError::FailedToHearBackFromConsensus(_)
+        | SuiError::ListenerCapacityExceeded => true,
+        // Failures of a quorum are transient if any authority failed transiently, as it may
+        // answer when retrying.
+        SuiError::QuorumNotReached { errors }
+        | SuiError::QuorumFailedToExecuteCertificate { errors } => errors.iter().any(is_transient),
+        SuiError::TooManyIncorrectAuthorities { errors } => {
+            errors.iter().any(|(_, error)| is_transient(error))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let config = RetryConfig {
+            max_attempts: Some(4),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            ..Default::default()
+        };
+        assert_eq!(config.backoff(1), Duration::from_millis(100));
+        assert_eq!(config.backoff(2), Duration::from_millis(200));
+        assert_eq!(config.backoff(3), Duration::from_millis(400));
+        assert_eq!(config.backoff(4), Duration::from_millis(500));
+        assert_eq!(config.backoff(100), Duration::from_millis(500));
+
+        assert!(config.should_retry(3, &SuiError::InvalidAddress));
+        assert!(!config.should_retry(4, &SuiError::TimeoutError));
+
+        let jittered = RetryConfig {
+            jitter: 0.5,
+            ..config.clone()
+        };
+        for attempts in 1..10 {
+            let backoff = jittered.backoff(attempts);
+            let max = config.backoff(attempts);
+            assert!(backoff <= max && backoff >= max / 2);
+        }
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        let config = RetryConfig {
+            retry_on: RetryOn::TransientErrors,
+            ..Default::default()
+        };
+        assert!(config.should_retry(1_000, &SuiError::RpcError("unavailable".to_owned())));
+        assert!(!config.should_retry(1, &SuiError::InvalidAddress));
+        assert!(config.should_retry(
+            1,
+            &SuiError::QuorumNotReached {
+                errors: vec![SuiError::InvalidAddress, SuiError::TimeoutError],
+            }
+        ));
+        assert!(!config.should_retry(
+            1,
+            &SuiError::QuorumNotReached {
+                errors: vec![SuiError::InvalidAddress],
+            }
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The policy a validator signs transactions under: the senders it refuses, and the number of
+//! transactions it signs each second at most. The policy is built from the [`TunableConfig`] of
+//! the validator and updated when its operator reloads the config, without restarting the
+//! server or dropping the requests it is serving.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+use tracing::info;
+
+use sui_config::TunableConfig;
+use sui_types::base_types::SuiAddress;
+
+#[cfg(test)]
+#[path = "unit_tests/serving_policy_tests.rs"]
+mod serving_policy_tests;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PolicyViolation {
+    DeniedSender(SuiAddress),
+    /// More transactions than the limit arrived during the last second.
+    RateLimited(u32),
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DeniedSender(sender) => {
+                write!(f, "Transactions of {sender} are refused by this validator")
+            }
+            Self::RateLimited(limit) => write!(
+                f,
+                "This validator signs at most {limit} transactions per second"
+            ),
+        }
+    }
+}
+
+pub struct ServingPolicy {
+    denied_senders: ArcSwap<BTreeSet<SuiAddress>>,
+    rate: Mutex<RateWindow>,
+}
+
+struct RateWindow {
+    limit: Option<u32>,
+    start: Instant,
+    count: u32,
+}
+
+impl ServingPolicy {
+    pub fn new(config: &TunableConfig) -> Self {
+        Self {
+            denied_senders: ArcSwap::from_pointee(config.denied_senders.clone()),
+            rate: Mutex::new(RateWindow {
+                limit: config.max_transactions_per_second,
+                start: Instant::now(),
+                count: 0,
+            }),
+        }
+    }
+
+    /// Apply a reloaded config to the transactions arriving from now on.
+    pub fn update(&self, config: &TunableConfig) {
+        self.denied_senders
+            .store(Arc::new(config.denied_senders.clone()));
+        self.rate.lock().limit = config.max_transactions_per_second;
+        info!(
+            denied_senders = config.denied_senders.len(),
+            max_transactions_per_second = ?config.max_transactions_per_second,
+            "Updated the serving policy"
+        );
+    }
+
+    /// Check a transaction of `sender` about to be signed, counting it against the rate limit if
+    /// it is allowed.
+    pub fn check(&self, sender: &SuiAddress) -> Result<(), PolicyViolation> {
+        self.check_at(sender, Instant::now())
+    }
+
+    fn check_at(&self, sender: &SuiAddress, now: Instant) -> Result<(), PolicyViolation> {
+        if self.denied_senders.load().contains(sender) {
+            return Err(PolicyViolation::DeniedSender(*sender));
+        }
+        let mut rate = self.rate.lock();
+        if let Some(limit) = rate.limit {
+            if now.saturating_duration_since(rate.start) >= RATE_WINDOW {
+                rate.start = now;
+                rate.count = 0;
+            }
+            if rate.count >= limit {
+                return Err(PolicyViolation::RateLimited(limit));
+            }
+            rate.count += 1;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulation of a committee, for tests.
+//!
+//! A [`Simulation`] runs the authority states of a committee in process, behind a simulated
+//! network: every request to an authority waits in the queue of a [`Scheduler`] until it is
+//! delivered, and the scheduler delivers one request at a time, picked among the waiting ones
+//! by a rng seeded by the simulation. The keys of the committee are derived from the seed too.
+//!
+//! Simulations must run on a current thread runtime with the clock paused, e.g. in tests
+//! marked `#[tokio::test(flavor = "current_thread", start_paused = true)]`. The clock then only
+//! advances once every task waits, so that the requests waiting for delivery, the order they
+//! are delivered in and the time of the simulation are the same in every run of a seed: a
+//! scenario that fails can be replayed from its seed, and the [log](Simulation::deliveries) of
+//! its deliveries compared between runs.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use multiaddr::Multiaddr;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::debug;
+
+use sui_config::genesis::Genesis;
+use sui_config::ValidatorInfo;
+use sui_network::pool::ConnectionPool;
+use sui_types::base_types::{AuthorityName, SuiAddress};
+use sui_types::crypto::{get_key_pair_from_rng, KeyPair, KeypairTraits};
+use sui_types::error::SuiError;
+use sui_types::messages::*;
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+use sui_types::object::Object;
+
+use crate::authority::AuthorityState;
+use crate::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
+use crate::authority_client::{AuthorityAPI, BatchInfoResponseItemStream, LocalAuthorityClient};
+use crate::byzantine::{ByzantineAuthorityClient, ByzantineConfig};
+use crate::epoch::reconfiguration::Reconfigurable;
+
+#[cfg(test)]
+#[path = "unit_tests/simulator_tests.rs"]
+mod simulator_tests;
+
+/// How long the scheduler waits between two deliveries, in the time of the simulation.
+pub const DELIVERY_INTERVAL: Duration = Duration::from_millis(1);
+
+/// A request delivered to an authority.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    /// Since the start of the simulation.
+    pub at: Duration,
+    pub authority: AuthorityName,
+    pub method: &'static str,
+}
+
+struct PendingRequest {
+    authority: AuthorityName,
+    method: &'static str,
+    deliver: oneshot::Sender<()>,
+}
+
+struct SchedulerState {
+    rng: StdRng,
+    pending: Vec<PendingRequest>,
+    deliveries: Vec<Delivery>,
+}
+
+/// The queue of the requests waiting for delivery, shared by the clients of a simulation.
+#[derive(Clone)]
+pub struct Scheduler {
+    state: Arc<Mutex<SchedulerState>>,
+    start: Instant,
+}
+
+impl Scheduler {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                rng: StdRng::seed_from_u64(seed),
+                pending: vec![],
+                deliveries: vec![],
+            })),
+            start: Instant::now(),
+        }
+    }
+
+    /// Wait until the scheduler delivers a request of `method` to `authority`.
+    async fn wait_for_delivery(&self, authority: AuthorityName, method: &'static str) {
+        let (deliver, delivered) = oneshot::channel();
+        self.state.lock().unwrap().pending.push(PendingRequest {
+            authority,
+            method,
+            deliver,
+        });
+        // The scheduler only drops the requests it delivers.
+        let _ = delivered.await;
+    }
+
+    /// Deliver one of the waiting requests, picked by the rng of the scheduler. Returns false
+    /// if no request is waiting.
+    pub fn deliver_next(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.pending.is_empty() {
+            return false;
+        }
+        let index = state.rng.gen_range(0, state.pending.len());
+        let request = state.pending.remove(index);
+        debug!(
+            "Simulation delivers {} to {}",
+            request.method, request.authority
+        );
+        state.deliveries.push(Delivery {
+            at: self.start.elapsed(),
+            authority: request.authority,
+            method: request.method,
+        });
+        // The request may have been given up on, e.g. after a timeout.
+        let _ = request.deliver.send(());
+        true
+    }
+
+    /// Deliver the waiting requests every [`DELIVERY_INTERVAL`], until the returned task is
+    /// aborted.
+    pub fn start(&self) -> JoinHandle<()> {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(DELIVERY_INTERVAL).await;
+                scheduler.deliver_next();
+            }
+        })
+    }
+
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        self.state.lock().unwrap().deliveries.clone()
+    }
+}
+
+/// The client of an authority whose requests wait to be delivered by a [`Scheduler`].
+#[derive(Clone)]
+pub struct SimulatedAuthorityClient<A> {
+    name: AuthorityName,
+    inner: A,
+    scheduler: Scheduler,
+}
+
+impl<A> SimulatedAuthorityClient<A> {
+    pub fn new(name: AuthorityName, inner: A, scheduler: Scheduler) -> Self {
+        Self {
+            name,
+            inner,
+            scheduler,
+        }
+    }
+
+    pub fn inner(&self) -> &A {
+        &self.inner
+    }
+
+    async fn call<T, Fut>(&self, method: &'static str, request: Fut) -> Result<T, SuiError>
+    where
+        Fut: Future<Output = Result<T, SuiError>>,
+    {
+        self.scheduler.wait_for_delivery(self.name, method).await;
+        request.await
+    }
+}
+
+impl<A> Reconfigurable for SimulatedAuthorityClient<A> {
+    fn needs_network_recreation() -> bool {
+        false
+    }
+
+    fn recreate(
+        _address: &Multiaddr,
+        _name: AuthorityName,
+        _pool: &Arc<ConnectionPool>,
+    ) -> anyhow::Result<Self> {
+        unreachable!(); // simulated clients keep their clients and scheduler across epochs
+    }
+}
+
+#[async_trait]
+impl<A> AuthorityAPI for SimulatedAuthorityClient<A>
+where
+    A: AuthorityAPI + Send + Sync,
+{
+    async fn handle_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call(
+            "handle_transaction",
+            self.inner.handle_transaction(transaction),
+        )
+        .await
+    }
+
+    async fn handle_certificate(
+        &self,
+        certificate: CertifiedTransaction,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call(
+            "handle_certificate",
+            self.inner.handle_certificate(certificate),
+        )
+        .await
+    }
+
+    async fn handle_account_info_request(
+        &self,
+        request: AccountInfoRequest,
+    ) -> Result<AccountInfoResponse, SuiError> {
+        self.call(
+            "handle_account_info_request",
+            self.inner.handle_account_info_request(request),
+        )
+        .await
+    }
+
+    async fn handle_object_info_request(
+        &self,
+        request: ObjectInfoRequest,
+    ) -> Result<ObjectInfoResponse, SuiError> {
+        self.call(
+            "handle_object_info_request",
+            self.inner.handle_object_info_request(request),
+        )
+        .await
+    }
+
+    async fn handle_transaction_info_request(
+        &self,
+        request: TransactionInfoRequest,
+    ) -> Result<TransactionInfoResponse, SuiError> {
+        self.call(
+            "handle_transaction_info_request",
+            self.inner.handle_transaction_info_request(request),
+        )
+        .await
+    }
+
+    /// Only opening the stream is scheduled, not its items.
+    async fn handle_batch_stream(
+        &self,
+        request: BatchInfoRequest,
+    ) -> Result<BatchInfoResponseItemStream, SuiError> {
+        self.call(
+            "handle_batch_stream",
+            self.inner.handle_batch_stream(request),
+        )
+        .await
+    }
+
+    async fn handle_checkpoint(
+        &self,
+        request: CheckpointRequest,
+    ) -> Result<CheckpointResponse, SuiError> {
+        self.call("handle_checkpoint", self.inner.handle_checkpoint(request))
+            .await
+    }
+}
+
+/// The client of an authority of a simulation, which turns byzantine when told by
+/// [`Simulation::byzantine`].
+pub type SimulatedAuthority = ByzantineAuthorityClient<LocalAuthorityClient>;
+
+/// A committee of authority states behind a simulated network, see the
+/// [module documentation](self).
+pub struct Simulation {
+    seed: u64,
+    rng: StdRng,
+    scheduler: Scheduler,
+    genesis: Genesis,
+    states: Vec<Arc<AuthorityState>>,
+    byzantine: BTreeMap<AuthorityName, ByzantineConfig>,
+    authorities: AuthorityAggregator<SimulatedAuthorityClient<SimulatedAuthority>>,
+    delivery_handle: JoinHandle<()>,
+}
+
+impl Simulation {
+    /// Simulate a committee of `committee_size` authorities of equal stake, starting with
+    /// `genesis_objects`.
+    pub async fn new(seed: u64, committee_size: usize, genesis_objects: Vec<Object>) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut builder = sui_config::genesis::Builder::new().add_objects(genesis_objects);
+        let mut key_pairs = Vec::new();
+        for i in 0..committee_size {
+            let (_, key_pair) = get_key_pair_from_rng(&mut rng);
+            let authority_name = key_pair.public().into();
+            builder = builder.add_validator(ValidatorInfo {
+                name: format!("validator-{i}"),
+                public_key: authority_name,
+                network_key: None,
+                worker_key: None,
+                account_key: None,
+                stake: 1,
+                delegation: 0,
+                network_address: sui_config::utils::new_network_address(),
+                narwhal_primary_to_primary: sui_config::utils::new_network_address(),
+                narwhal_worker_to_primary: sui_config::utils::new_network_address(),
+                narwhal_primary_to_worker: sui_config::utils::new_network_address(),
+                narwhal_worker_to_worker: sui_config::utils::new_network_address(),
+                narwhal_consensus_address: sui_config::utils::new_network_address(),
+            });
+            key_pairs.push((authority_name, key_pair));
+        }
+        let genesis = builder.build().unwrap();
+        let committee = genesis.committee().unwrap();
+
+        let scheduler = Scheduler::new(rng.gen::<u64>());
+        let mut states = Vec::new();
+        let mut byzantine = BTreeMap::new();
+        let mut clients = BTreeMap::new();
+        for (authority_name, secret) in key_pairs {
+            let client = LocalAuthorityClient::new_with_objects(
+                committee.clone(),
+                authority_name,
+                secret,
+                genesis.objects().to_owned(),
+                &genesis,
+            )
+            .await;
+            states.push(client.state.clone());
+            let config = ByzantineConfig::new();
+            byzantine.insert(authority_name, config.clone());
+            let client = ByzantineAuthorityClient::new_local(client, config);
+            clients.insert(
+                authority_name,
+                SimulatedAuthorityClient::new(authority_name, client, scheduler.clone()),
+            );
+        }
+        states.sort_by_key(|state| state.name);
+        let authorities =
+            AuthorityAggregator::new(committee, clients, AuthAggMetrics::new_for_tests());
+        let delivery_handle = scheduler.start();
+        Self {
+            seed,
+            rng,
+            scheduler,
+            genesis,
+            states,
+            byzantine,
+            authorities,
+            delivery_handle,
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// A rng derived from the seed, for the randomness of the scenario itself.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// A key pair derived from the seed.
+    pub fn key_pair(&mut self) -> (SuiAddress, KeyPair) {
+        get_key_pair_from_rng(&mut self.rng)
+    }
+
+    pub fn genesis(&self) -> &Genesis {
+        &self.genesis
+    }
+
+    /// The states of the authorities, in the order of their names.
+    pub fn states(&self) -> &[Arc<AuthorityState>] {
+        &self.states
+    }
+
+    /// An aggregator of the authorities, whose requests go through the simulated network.
+    pub fn authorities(
+        &self,
+    ) -> &AuthorityAggregator<SimulatedAuthorityClient<SimulatedAuthority>> {
+        &self.authorities
+    }
+
+    /// The misbehaviors of the authority `name`, honest until set.
+    pub fn byzantine(&self, name: &AuthorityName) -> &ByzantineConfig {
+        &self.byzantine[name]
+    }
+
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    /// The time elapsed in the simulation.
+    pub fn now(&self) -> Duration {
+        self.scheduler.start.elapsed()
+    }
+
+    /// Let the simulation run for `duration` of its time.
+    pub async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+
+    /// The requests delivered so far, in their order.
+    pub fn deliveries(&self) -> Vec<Delivery> {
+        self.scheduler.deliveries()
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        self.delivery_handle.abort();
+    }
+}
@@ -10,15 +10,22 @@ use sui_types::base_types::ObjectID;
 use sui_types::error::SuiError;
 use sui_types::event_filter::Filter;
 use tokio::runtime::Handle;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::{mpsc, RwLock};
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::{debug, warn};
 
+#[cfg(test)]
+#[path = "unit_tests/streamer_tests.rs"]
+mod streamer_tests;
+
 type Subscribers<T, F> = Arc<RwLock<BTreeMap<String, (Sender<T>, F)>>>;
 
 /// The Streamer splits a mpsc channel into multiple mpsc channels using the subscriber's `Filter<T>` object.
-/// Data will be sent to the subscribers in parallel and the subscription will be dropped if it received a send error.
+/// Data will be sent to the subscribers in order, and the subscription will be dropped if it received a send error.
+/// Each subscriber has a bounded buffer, a subscriber that falls too far behind is dropped instead of
+/// holding up the other subscribers.
 pub struct Streamer<T, F: Filter<T>> {
     streamer_queue: Sender<T>,
     subscribers: Subscribers<T, F>,
@@ -46,23 +53,30 @@ where
     }
 
     async fn send_to_all_subscribers(subscribers: Subscribers<T, F>, data: T) {
-        for (id, (subscriber, filter)) in subscribers.read().await.clone() {
+        let mut dropped_subscribers = vec![];
+        for (id, (subscriber, filter)) in subscribers.read().await.iter() {
             if !(filter.matches(&data)) {
                 continue;
             }
-            let data = data.clone();
-            let subscribers = subscribers.clone();
-            tokio::spawn(async move {
-                match subscriber.send(data).await {
-                    Ok(_) => {
-                        debug!("Sending Move event to subscriber [{id}].")
-                    }
-                    Err(e) => {
-                        subscribers.write().await.remove(&id);
-                        warn!("Error sending event, removing subscriber [{id}] from subscriber list. Error: {e}");
-                    }
+            match subscriber.try_send(data.clone()) {
+                Ok(_) => {
+                    debug!("Sending Move event to subscriber [{id}].")
+                }
+                Err(TrySendError::Full(_)) => {
+                    warn!("Subscriber [{id}] is lagging behind, removing subscriber from subscriber list.");
+                    dropped_subscribers.push(id.clone());
+                }
+                Err(e) => {
+                    warn!("Error sending event, removing subscriber [{id}] from subscriber list. Error: {e}");
+                    dropped_subscribers.push(id.clone());
                 }
-            });
+            }
+        }
+        if !dropped_subscribers.is_empty() {
+            let mut subscribers = subscribers.write().await;
+            for id in dropped_subscribers {
+                subscribers.remove(&id);
+            }
         }
     }
 
@@ -0,0 +1,310 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Capture of the traffic of a validator, and its replay into another authority state.
+//!
+//! A validator configured with a capture path records the transactions and certificates it
+//! serves, once their signatures are checked, with the time they arrived at. Requests are
+//! written to the log by a thread of the recorder, so that serving them does not wait for the
+//! disk: requests arriving faster than they are written are dropped from the log rather than
+//! delayed.
+//!
+//! The log is a header followed by frames of a little-endian u32 length and the bincode encoded
+//! [`RecordedRequest`]. A log cut short, e.g. by the validator crashing, ends at its last whole
+//! frame.
+//!
+//! [`replay`] feeds a log back into an authority state as fast as it can or at a multiple of the
+//! pace it was captured at, to compare the performance of versions of the authority on the
+//! traffic of a real network. For the requests to succeed rather than fail on missing objects,
+//! the state must start from the genesis of the captured network and the capture from its
+//! start. Certificates of transactions on shared objects fail without consensus to sequence
+//! them, and are counted with the other failures.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tracing::{debug, error, info, warn};
+
+use sui_config::genesis::Genesis;
+use sui_types::crypto::{get_key_pair, KeypairTraits};
+use sui_types::error::SuiResult;
+use sui_types::messages::{CertifiedTransaction, Transaction};
+
+use crate::authority::{AuthorityState, AuthorityStore};
+
+#[cfg(test)]
+#[path = "unit_tests/traffic_capture_tests.rs"]
+mod traffic_capture_tests;
+
+const MAGIC: &[u8; 8] = b"SUITRAF1";
+/// The requests waiting to be written to the log before new ones are dropped.
+const RECORDER_CAPACITY: usize = 10_000;
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum CapturedRequest {
+    Transaction(Transaction),
+    Certificate(CertifiedTransaction),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    /// Since the recorder was started, in microseconds.
+    pub at_micros: u64,
+    pub request: CapturedRequest,
+}
+
+/// Records the requests served by a validator to a log, see the
+/// [module documentation](self).
+pub struct TrafficRecorder {
+    start: Instant,
+    sender: mpsc::SyncSender<RecordedRequest>,
+    dropped: AtomicU64,
+    writer: std::thread::JoinHandle<()>,
+}
+
+impl TrafficRecorder {
+    /// Record to a new log at `path`, replacing any file there.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.flush()?;
+        let (sender, receiver) = mpsc::sync_channel(RECORDER_CAPACITY);
+        let path = path.to_owned();
+        let writer = std::thread::Builder::new()
+            .name("traffic-recorder".into())
+            .spawn(move || {
+                if let Err(e) = write_log(&mut writer, receiver) {
+                    error!(?path, "Stopped recording traffic: {e}");
+                }
+            })?;
+        info!(?path, "Recording the traffic of the validator");
+        Ok(Self {
+            start: Instant::now(),
+            sender,
+            dropped: AtomicU64::new(0),
+            writer,
+        })
+    }
+
+    /// Stop recording, once the requests recorded so far are written.
+    pub fn finish(self) {
+        let Self { sender, writer, .. } = self;
+        drop(sender);
+        if writer.join().is_err() {
+            error!("The traffic recorder panicked");
+        }
+    }
+
+    pub fn record_transaction(&self, transaction: &Transaction) {
+        self.record(CapturedRequest::Transaction(transaction.clone()));
+    }
+
+    pub fn record_certificate(&self, certificate: &CertifiedTransaction) {
+        self.record(CapturedRequest::Certificate(certificate.clone()));
+    }
+
+    /// The requests dropped from the log so far, for arriving faster than they were written or
+    /// after the log failed.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, request: CapturedRequest) {
+        let recorded = RecordedRequest {
+            at_micros: self.start.elapsed().as_micros() as u64,
+            request,
+        };
+        if self.sender.try_send(recorded).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn write_log(
+    writer: &mut BufWriter<File>,
+    receiver: mpsc::Receiver<RecordedRequest>,
+) -> io::Result<()> {
+    while let Ok(request) = receiver.recv() {
+        write_frame(writer, &request)?;
+        // Flush once the requests waiting are written, rather than after each of them.
+        while let Ok(request) = receiver.try_recv() {
+            write_frame(writer, &request)?;
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn write_frame(writer: &mut impl Write, request: &RecordedRequest) -> io::Result<()> {
+    let bytes = bincode::serialize(request).map_err(invalid_data)?;
+    let length = u32::try_from(bytes.len()).map_err(invalid_data)?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&bytes)
+}
+
+/// The requests of a log, in the order they were recorded.
+pub struct TrafficLog {
+    reader: BufReader<File>,
+}
+
+impl TrafficLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not a traffic log", path.display()),
+            ));
+        }
+        Ok(Self { reader })
+    }
+
+    fn read_frame(&mut self) -> io::Result<Option<RecordedRequest>> {
+        let mut length = [0; 4];
+        if let Err(e) = self.reader.read_exact(&mut length) {
+            return match e.kind() {
+                io::ErrorKind::UnexpectedEof => Ok(None),
+                _ => Err(e),
+            };
+        }
+        let mut bytes = vec![0; u32::from_le_bytes(length) as usize];
+        if let Err(e) = self.reader.read_exact(&mut bytes) {
+            return match e.kind() {
+                io::ErrorKind::UnexpectedEof => {
+                    warn!("The traffic log ends with a partial frame, ignored");
+                    Ok(None)
+                }
+                _ => Err(e),
+            };
+        }
+        bincode::deserialize(&bytes).map(Some).map_err(invalid_data)
+    }
+}
+
+impl Iterator for TrafficLog {
+    type Item = io::Result<RecordedRequest>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReplayConfig {
+    /// How many times faster than they were captured the requests are replayed, as fast as
+    /// they can be if not set.
+    pub speed: Option<f64>,
+    /// The requests handled at once, beyond which the replay waits for one to finish. Requests
+    /// handled at once may reach the state in another order than they reached the validator.
+    pub max_in_flight: usize,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            speed: None,
+            max_in_flight: 100,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    pub transactions: u64,
+    pub certificates: u64,
+    /// The requests the state failed to handle.
+    pub failed: u64,
+    pub elapsed: Duration,
+}
+
+/// Feed the requests of `log` into `state`, see the [module documentation](self).
+pub async fn replay(
+    state: Arc<AuthorityState>,
+    log: TrafficLog,
+    config: ReplayConfig,
+) -> io::Result<ReplayStats> {
+    let start = tokio::time::Instant::now();
+    let in_flight = Arc::new(Semaphore::new(config.max_in_flight.max(1)));
+    let failed = Arc::new(AtomicU64::new(0));
+    let mut stats = ReplayStats::default();
+    let mut handles = Vec::new();
+    for recorded in log {
+        let recorded = recorded?;
+        if let Some(speed) = config.speed {
+            let at = Duration::from_micros(recorded.at_micros).div_f64(speed);
+            tokio::time::sleep_until(start + at).await;
+        }
+        match &recorded.request {
+            CapturedRequest::Transaction(_) => stats.transactions += 1,
+            CapturedRequest::Certificate(_) => stats.certificates += 1,
+        }
+        let permit = in_flight.clone().acquire_owned().await.unwrap();
+        let state = state.clone();
+        let failed = failed.clone();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = handle_request(&state, recorded.request).await {
+                debug!("Replayed request failed: {e}");
+                failed.fetch_add(1, Ordering::Relaxed);
+            }
+            drop(permit);
+        }));
+    }
+    for handle in handles {
+        handle.await.expect("Replayed requests do not panic");
+    }
+    stats.failed = failed.load(Ordering::Relaxed);
+    stats.elapsed = start.elapsed();
+    Ok(stats)
+}
+
+/// Handle `request` as the validator serving it does, checking its signatures again.
+async fn handle_request(state: &AuthorityState, request: CapturedRequest) -> SuiResult {
+    match request {
+        CapturedRequest::Transaction(mut transaction) => {
+            transaction.verify()?;
+            transaction.is_verified = true;
+            state.handle_transaction(transaction).await?;
+        }
+        CapturedRequest::Certificate(mut certificate) => {
+            certificate.verify(&state.committee.load())?;
+            certificate.is_verified = true;
+            state.handle_certificate(certificate).await?;
+        }
+    }
+    Ok(())
+}
+
+/// A state starting from `genesis`, with its store at `db_path`, to replay the traffic of the
+/// network of `genesis` into. The state signs with a key of its own, not one of the committee.
+pub async fn replay_state(genesis: &Genesis, db_path: &Path) -> SuiResult<Arc<AuthorityState>> {
+    let committee = genesis.committee()?;
+    let (_, key_pair) = get_key_pair();
+    let name = key_pair.public().into();
+    let store = Arc::new(AuthorityStore::open(db_path, None));
+    let state = AuthorityState::new(
+        committee,
+        name,
+        Arc::pin(key_pair),
+        store,
+        None,
+        None,
+        None,
+        genesis,
+        &prometheus::Registry::new(),
+    )
+    .await;
+    Ok(Arc::new(state))
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
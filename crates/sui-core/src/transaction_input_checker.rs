@@ -1,10 +1,8 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::authority::SuiDataStore;
-use serde::{Deserialize, Serialize};
+use crate::authority::ReadStore;
 use std::collections::HashSet;
-use std::fmt::Debug;
 use sui_types::{
     base_types::{ObjectID, SequenceNumber, SuiAddress},
     error::{SuiError, SuiResult},
@@ -14,29 +12,52 @@ use sui_types::{
         InputObjectKind, InputObjects, SingleTransactionKind, TransactionData, TransactionEnvelope,
     },
     object::{Object, Owner},
+    protocol_config::ProtocolConfig,
 };
 use tracing::instrument;
 
 #[instrument(level = "trace", skip_all)]
 pub async fn check_transaction_input<S, T>(
-    store: &SuiDataStore<S>,
+    store: &S,
     transaction: &TransactionEnvelope<T>,
+    protocol_config: &ProtocolConfig,
 ) -> Result<(SuiGasStatus<'static>, InputObjects), SuiError>
 where
-    S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
+    S: ReadStore,
 {
+    check_transaction_data_input(store, &transaction.data, protocol_config).await
+}
+
+/// Same as [`check_transaction_input`], but operates on transaction data that
+/// has not been signed yet. This is used when dry-running a transaction locally,
+/// e.g. to estimate how much gas it will consume.
+#[instrument(level = "trace", skip_all)]
+pub async fn check_transaction_data_input<S>(
+    store: &S,
+    transaction: &TransactionData,
+    protocol_config: &ProtocolConfig,
+) -> Result<(SuiGasStatus<'static>, InputObjects), SuiError>
+where
+    S: ReadStore,
+{
+    protocol_config.check_transaction_kind(&transaction.kind)?;
+
     let mut gas_status = check_gas(
         store,
         transaction.gas_payment_object_ref().0,
-        transaction.data.gas_budget,
-        transaction.data.gas_price,
-        transaction.data.kind.is_system_tx(),
+        transaction.gas_budget,
+        transaction.gas_price,
+        transaction.kind.is_system_tx(),
     )
     .await?;
 
-    let input_objects = check_locks(store, &transaction.data).await?;
+    let input_objects = check_locks(store, transaction).await?;
 
-    if transaction.contains_shared_object() {
+    if transaction
+        .kind
+        .single_transactions()
+        .any(|s| s.contains_shared_object())
+    {
         // It's important that we do this here to make sure there is enough
         // gas to cover shared objects, before we lock all objects.
         gas_status.charge_consensus()?;
@@ -51,14 +72,14 @@ where
 /// that will be used in the entire lifecycle of the transaction execution.
 #[instrument(level = "trace", skip_all)]
 async fn check_gas<S>(
-    store: &SuiDataStore<S>,
+    store: &S,
     gas_payment_id: ObjectID,
     gas_budget: u64,
     computation_gas_price: u64,
     is_system_tx: bool,
 ) -> SuiResult<SuiGasStatus<'static>>
 where
-    S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
+    S: ReadStore,
 {
     if is_system_tx {
         Ok(SuiGasStatus::new_unmetered())
@@ -84,11 +105,11 @@ where
 
 #[instrument(level = "trace", skip_all, fields(num_objects = input_objects.len()))]
 async fn fetch_objects<S>(
-    store: &SuiDataStore<S>,
+    store: &S,
     input_objects: &[InputObjectKind],
 ) -> Result<Vec<Option<Object>>, SuiError>
 where
-    S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
+    S: ReadStore,
 {
     let ids: Vec<_> = input_objects.iter().map(|kind| kind.object_id()).collect();
     store.get_objects(&ids[..])
@@ -97,12 +118,9 @@ where
 /// Check all the objects used in the transaction against the database, and ensure
 /// that they are all the correct version and number.
 #[instrument(level = "trace", skip_all)]
-async fn check_locks<S>(
-    store: &SuiDataStore<S>,
-    transaction: &TransactionData,
-) -> Result<InputObjects, SuiError>
+async fn check_locks<S>(store: &S, transaction: &TransactionData) -> Result<InputObjects, SuiError>
 where
-    S: Eq + Debug + Serialize + for<'de> Deserialize<'de>,
+    S: ReadStore,
 {
     let input_objects = transaction.input_objects()?;
     // These IDs act as authenticators that can own other objects.
@@ -22,6 +22,7 @@ use crate::authority_client::{
     LocalAuthorityClientFaultConfig,
 };
 
+use crate::retry::RetryOn;
 use tokio::time::Instant;
 
 pub async fn init_local_authorities(
@@ -39,6 +40,9 @@ pub async fn init_local_authorities(
         let validator_info = ValidatorInfo {
             name: format!("validator-{i}"),
             public_key: authority_name,
+            network_key: None,
+            worker_key: None,
+            account_key: None,
             stake: 1,
             delegation: 0,
             network_address: sui_config::utils::new_network_address(),
@@ -51,7 +55,7 @@ pub async fn init_local_authorities(
         builder = builder.add_validator(validator_info);
         key_pairs.push((authority_name, key_pair));
     }
-    let genesis = builder.build();
+    let genesis = builder.build().unwrap();
     init_local_authorities_with_genesis(&genesis, key_pairs).await
 }
 
@@ -116,6 +120,7 @@ pub fn transfer_coin_transaction(
     dest: SuiAddress,
     object_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     to_transaction(
         TransactionData::new_transfer(
@@ -124,6 +129,7 @@ pub fn transfer_coin_transaction(
             src,
             gas_object_ref,
             GAS_VALUE_FOR_TESTING / 2,
+            chain_id,
         ),
         secret,
     )
@@ -136,6 +142,7 @@ pub fn transfer_object_move_transaction(
     object_ref: ObjectRef,
     framework_obj_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     let args = vec![
         CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref)),
@@ -152,6 +159,7 @@ pub fn transfer_object_move_transaction(
             gas_object_ref,
             args,
             GAS_VALUE_FOR_TESTING / 2,
+            chain_id,
         ),
         secret,
     )
@@ -164,6 +172,7 @@ pub fn crate_object_move_transaction(
     value: u64,
     framework_obj_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     // When creating an object_basics object, we provide the value (u64) and address which will own the object
     let arguments = vec![
@@ -181,6 +190,7 @@ pub fn crate_object_move_transaction(
             gas_object_ref,
             arguments,
             GAS_VALUE_FOR_TESTING / 2,
+            chain_id,
         ),
         secret,
     )
@@ -192,6 +202,7 @@ pub fn delete_object_move_transaction(
     object_ref: ObjectRef,
     framework_obj_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     to_transaction(
         TransactionData::new_move_call(
@@ -203,6 +214,7 @@ pub fn delete_object_move_transaction(
             gas_object_ref,
             vec![CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref))],
             GAS_VALUE_FOR_TESTING / 2,
+            chain_id,
         ),
         secret,
     )
@@ -215,6 +227,7 @@ pub fn set_object_move_transaction(
     value: u64,
     framework_obj_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     let args = vec![
         CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref)),
@@ -231,6 +244,7 @@ pub fn set_object_move_transaction(
             gas_object_ref,
             args,
             GAS_VALUE_FOR_TESTING / 2,
+            chain_id,
         ),
         secret,
     )
@@ -345,9 +359,9 @@ async fn execute_transaction_with_fault_configs(
     let (addr2, _) = get_key_pair();
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_object2 = Object::with_owner_for_testing(addr1);
-    let mut authorities = init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()])
-        .await
-        .0;
+    let (mut authorities, states) =
+        init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
 
     for (index, config) in configs_before_process_transaction {
         get_local_client(&mut authorities, *index).fault_config = *config;
@@ -359,6 +373,7 @@ async fn execute_transaction_with_fault_configs(
         addr2,
         gas_object1.compute_object_reference(),
         gas_object2.compute_object_reference(),
+        chain_id,
     );
     let cert = authorities.process_transaction(tx).await?;
 
@@ -504,14 +519,22 @@ async fn test_get_all_owned_objects() {
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_ref_1 = gas_object1.compute_object_reference();
     let gas_object2 = Object::with_owner_for_testing(addr2);
-    let (authorities, _) =
+    let (authorities, states) =
         init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
     let authority_clients: Vec<_> = authorities.authority_clients.values().collect();
 
     // Make a schedule of transactions
     let framework_obj_ref = genesis::get_framework_object_ref();
-    let create1 =
-        crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let create1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     // Submit to 3 authorities, but not 4th
     do_transaction(authority_clients[0], &create1).await;
@@ -551,8 +574,14 @@ async fn test_get_all_owned_objects() {
 
     // Make a delete transaction
     let gas_ref_del = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let delete1 =
-        delete_object_move_transaction(addr1, &key1, created_ref, framework_obj_ref, gas_ref_del);
+    let delete1 = delete_object_move_transaction(
+        addr1,
+        &key1,
+        created_ref,
+        framework_obj_ref,
+        gas_ref_del,
+        chain_id,
+    );
 
     // Get cert for delete transaction, and submit to first authority
     do_transaction(authority_clients[0], &delete1).await;
@@ -591,19 +620,34 @@ async fn test_sync_all_owned_objects() {
     let (addr2, _) = get_key_pair();
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_object2 = Object::with_owner_for_testing(addr1);
-    let (authorities, _) =
+    let (authorities, states) =
         init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
     let authority_clients: Vec<_> = authorities.authority_clients.values().collect();
 
     let framework_obj_ref = genesis::get_framework_object_ref();
     // Make a schedule of transactions
     let gas_ref_1 = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create1 =
-        crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let create1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     let gas_ref_2 = get_latest_ref(authority_clients[0], gas_object2.id()).await;
-    let create2 =
-        crate_object_move_transaction(addr1, &key1, addr1, 101, framework_obj_ref, gas_ref_2);
+    let create2 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        101,
+        framework_obj_ref,
+        gas_ref_2,
+        chain_id,
+    );
 
     // Submit to 3 authorities, but not 4th
     do_transaction(authority_clients[0], &create1).await;
@@ -641,8 +685,14 @@ async fn test_sync_all_owned_objects() {
 
     // Make a delete transaction
     let gas_ref_del = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let delete1 =
-        delete_object_move_transaction(addr1, &key1, new_ref_1, framework_obj_ref, gas_ref_del);
+    let delete1 = delete_object_move_transaction(
+        addr1,
+        &key1,
+        new_ref_1,
+        framework_obj_ref,
+        gas_ref_del,
+        chain_id,
+    );
 
     // Make a transfer transaction
     let gas_ref_trans = get_latest_ref(authority_clients[0], gas_object2.id()).await;
@@ -653,6 +703,7 @@ async fn test_sync_all_owned_objects() {
         new_ref_2,
         framework_obj_ref,
         gas_ref_trans,
+        chain_id,
     );
 
     do_transaction(authority_clients[0], &delete1).await;
@@ -701,16 +752,24 @@ async fn test_process_transaction1() {
     let (addr1, key1) = get_key_pair();
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_object2 = Object::with_owner_for_testing(addr1);
-    let (authorities, _) =
+    let (authorities, states) =
         init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
     let authority_clients: Vec<_> = authorities.authority_clients.values().collect();
 
     let framework_obj_ref = genesis::get_framework_object_ref();
 
     // Make a schedule of transactions
     let gas_ref_1 = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create1 =
-        crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let create1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     do_transaction(authority_clients[0], &create1).await;
     do_transaction(authority_clients[1], &create1).await;
@@ -724,8 +783,15 @@ async fn test_process_transaction1() {
 
     // Make a schedule of transactions
     let gas_ref_set = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create2 =
-        set_object_move_transaction(addr1, &key1, new_ref_1, 100, framework_obj_ref, gas_ref_set);
+    let create2 = set_object_move_transaction(
+        addr1,
+        &key1,
+        new_ref_1,
+        100,
+        framework_obj_ref,
+        gas_ref_set,
+        chain_id,
+    );
 
     // Test 1: When we call process transaction on the second transaction, the process_transaction
     // updates all authorities with latest objects, and then the transaction goes through
@@ -761,16 +827,24 @@ async fn test_process_certificate() {
     let (addr1, key1) = get_key_pair();
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_object2 = Object::with_owner_for_testing(addr1);
-    let (authorities, _) =
+    let (authorities, states) =
         init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
     let authority_clients: Vec<_> = authorities.authority_clients.values().collect();
 
     let framework_obj_ref = genesis::get_framework_object_ref();
 
     // Make a schedule of transactions
     let gas_ref_1 = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create1 =
-        crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let create1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     do_transaction(authority_clients[0], &create1).await;
     do_transaction(authority_clients[1], &create1).await;
@@ -791,8 +865,15 @@ async fn test_process_certificate() {
 
     // Make a schedule of transactions
     let gas_ref_set = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create2 =
-        set_object_move_transaction(addr1, &key1, new_ref_1, 100, framework_obj_ref, gas_ref_set);
+    let create2 = set_object_move_transaction(
+        addr1,
+        &key1,
+        new_ref_1,
+        100,
+        framework_obj_ref,
+        gas_ref_set,
+        chain_id,
+    );
 
     do_transaction(authority_clients[0], &create2).await;
     do_transaction(authority_clients[1], &create2).await;
@@ -819,16 +900,24 @@ async fn test_execute_cert_to_true_effects() {
     let (addr1, key1) = get_key_pair();
     let gas_object1 = Object::with_owner_for_testing(addr1);
     let gas_object2 = Object::with_owner_for_testing(addr1);
-    let (authorities, _) =
+    let (authorities, states) =
         init_local_authorities(4, vec![gas_object1.clone(), gas_object2.clone()]).await;
+    let chain_id = states[0].chain_id;
     let authority_clients: Vec<_> = authorities.authority_clients.values().collect();
 
     let framework_obj_ref = genesis::get_framework_object_ref();
 
     // Make a schedule of transactions
     let gas_ref_1 = get_latest_ref(authority_clients[0], gas_object1.id()).await;
-    let create1 =
-        crate_object_move_transaction(addr1, &key1, addr1, 100, framework_obj_ref, gas_ref_1);
+    let create1 = crate_object_move_transaction(
+        addr1,
+        &key1,
+        addr1,
+        100,
+        framework_obj_ref,
+        gas_ref_1,
+        chain_id,
+    );
 
     do_transaction(authority_clients[0], &create1).await;
     do_transaction(authority_clients[1], &create1).await;
@@ -1037,7 +1126,7 @@ async fn test_quorum_once_with_timeout() {
             None,
             None,
             |_name, client| {
-                let digest = TransactionDigest::new([0u8; 32]);
+                let digest = TransactionDigest::deterministic_for_testing(0);
                 let log = log.clone();
                 Box::pin(async move {
                     // log the start time of the request
@@ -1073,3 +1162,117 @@ async fn test_quorum_once_with_timeout() {
             .collect::<Vec<Duration>>()
     );
 }
+
+#[tokio::test(start_paused = true)]
+async fn test_quorum_once_retries() {
+    #[derive(Clone)]
+    struct FailingAuthorityApi {
+        error: SuiError,
+        count: Arc<Mutex<u32>>,
+    }
+
+    #[async_trait]
+    impl AuthorityAPI for FailingAuthorityApi {
+        async fn handle_transaction(
+            &self,
+            _transaction: Transaction,
+        ) -> Result<TransactionInfoResponse, SuiError> {
+            unreachable!();
+        }
+
+        async fn handle_certificate(
+            &self,
+            _certificate: CertifiedTransaction,
+        ) -> Result<TransactionInfoResponse, SuiError> {
+            unreachable!()
+        }
+
+        async fn handle_account_info_request(
+            &self,
+            _request: AccountInfoRequest,
+        ) -> Result<AccountInfoResponse, SuiError> {
+            unreachable!();
+        }
+
+        async fn handle_object_info_request(
+            &self,
+            _request: ObjectInfoRequest,
+        ) -> Result<ObjectInfoResponse, SuiError> {
+            unreachable!();
+        }
+
+        async fn handle_transaction_info_request(
+            &self,
+            _request: TransactionInfoRequest,
+        ) -> Result<TransactionInfoResponse, SuiError> {
+            *self.count.lock().unwrap() += 1;
+            Err(self.error.clone())
+        }
+
+        async fn handle_batch_stream(
+            &self,
+            _request: BatchInfoRequest,
+        ) -> Result<BatchInfoResponseItemStream, SuiError> {
+            unreachable!();
+        }
+
+        async fn handle_checkpoint(
+            &self,
+            _request: CheckpointRequest,
+        ) -> Result<CheckpointResponse, SuiError> {
+            unreachable!();
+        }
+    }
+
+    let count = Arc::new(Mutex::new(0));
+    let aggregator = |error: SuiError, retry_config: RetryConfig| {
+        let mut authorities = BTreeMap::new();
+        let mut clients = BTreeMap::new();
+        for _ in 0..4 {
+            let (_, sec) = get_key_pair();
+            let name: AuthorityName = sec.public().into();
+            authorities.insert(name, 1);
+            let count = count.clone();
+            let error = error.clone();
+            clients.insert(name, FailingAuthorityApi { error, count });
+        }
+        let committee = Committee::new(0, authorities).unwrap();
+        AuthorityAggregator::new(committee, clients, AuthAggMetrics::new_for_tests())
+            .with_retry_config(retry_config)
+    };
+    let case = |agg: AuthorityAggregator<FailingAuthorityApi>| async move {
+        agg.quorum_once_with_timeout(
+            None,
+            None,
+            |_name, client| {
+                let digest = TransactionDigest::deterministic_for_testing(0);
+                Box::pin(async move { client.handle_transaction_info_request(digest.into()).await })
+            },
+            Duration::from_secs(1),
+            None,
+        )
+        .await
+    };
+
+    // Every authority is asked at every attempt, until there is no attempt left
+    let retry_config = RetryConfig {
+        max_attempts: Some(3),
+        ..Default::default()
+    };
+    let agg = aggregator(SuiError::TimeoutError, retry_config.clone());
+    assert!(matches!(
+        case(agg).await,
+        Err(SuiError::TooManyIncorrectAuthorities { .. })
+    ));
+    assert_eq!(*count.lock().unwrap(), 12);
+
+    // Errors which are not transient are not retried
+    *count.lock().unwrap() = 0;
+    let retry_config = RetryConfig {
+        retry_on: RetryOn::TransientErrors,
+        ..retry_config
+    };
+    let agg = aggregator(SuiError::InvalidAddress, retry_config);
+    assert!(case(agg).await.is_err());
+    assert_eq!(*count.lock().unwrap(), 4);
+}
@@ -112,6 +112,7 @@ async fn test_handle_transfer_transaction_bad_signature() {
         recipient,
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
 
     let num_orders = authority_state.metrics.tx_orders.get();
@@ -180,6 +181,7 @@ async fn test_handle_transfer_transaction_with_max_sequence_number() {
         recipient,
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
     let res = authority_state
         .handle_transaction(transfer_transaction)
@@ -232,6 +234,7 @@ async fn test_handle_shared_object_with_max_sequence_number() {
             CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
         ],
         MAX_GAS,
+        authority.chain_id,
     );
     let signature = Signature::new(&data, &keypair);
     let transaction = Transaction::new(data, signature);
@@ -272,6 +275,7 @@ async fn test_handle_transfer_transaction_unknown_sender() {
         recipient,
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
 
     assert!(authority_state
@@ -349,6 +353,7 @@ async fn test_handle_transfer_transaction_ok() {
         recipient,
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
 
     let test_object = authority_state
@@ -406,6 +411,59 @@ async fn test_handle_transfer_transaction_ok() {
     );
 }
 
+#[tokio::test]
+async fn test_handle_transaction_of_other_chain() {
+    let (sender, sender_key) = get_key_pair();
+    let object_id = ObjectID::random();
+    let gas_object_id = ObjectID::random();
+    let authority_state =
+        init_state_with_ids(vec![(sender, object_id), (sender, gas_object_id)]).await;
+    let object = authority_state
+        .get_object(&object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let gas_object = authority_state
+        .get_object(&gas_object_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let transfer = |chain_id| {
+        let data = TransactionData::new_transfer(
+            dbg_addr(2),
+            object.compute_object_reference(),
+            sender,
+            gas_object.compute_object_reference(),
+            10000,
+            chain_id,
+        );
+        let signature = Signature::new(&data, &sender_key);
+        Transaction::new(data, signature)
+    };
+
+    // The signature of a transaction of another chain does not make it valid on this one
+    let other_chain = ChainId([0; 32]);
+    let result = authority_state
+        .handle_transaction(transfer(other_chain))
+        .await;
+    assert!(matches!(
+        result,
+        Err(SuiError::WrongChain { expected, actual })
+            if expected == authority_state.chain_id && actual == other_chain
+    ));
+    assert!(authority_state
+        .get_transaction_lock(&object.compute_object_reference())
+        .await
+        .unwrap()
+        .is_none());
+
+    let response = authority_state
+        .handle_transaction(transfer(authority_state.chain_id))
+        .await
+        .unwrap();
+    assert!(response.signed_transaction.is_some());
+}
+
 #[tokio::test]
 async fn test_transfer_package() {
     let (sender, sender_key) = get_key_pair();
@@ -425,6 +483,7 @@ async fn test_transfer_package() {
         recipient,
         package_object_ref,
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
     authority_state
         .handle_transaction(transfer_transaction.clone())
@@ -456,6 +515,7 @@ async fn test_immutable_gas() {
         recipient,
         mut_object.compute_object_reference(),
         imm_object.compute_object_reference(),
+        authority_state.chain_id,
     );
     let result = authority_state
         .handle_transaction(transfer_transaction.clone())
@@ -485,6 +545,7 @@ async fn test_objected_owned_gas() {
         None,
         child_object.compute_object_reference(),
         10000,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transfer_transaction = Transaction::new(data, signature);
@@ -562,6 +623,7 @@ async fn test_publish_dependent_module_ok() {
         gas_payment_object_ref,
         vec![dependent_module_bytes],
         MAX_GAS,
+        authority.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
@@ -598,7 +660,13 @@ async fn test_publish_module_no_dependencies_ok() {
     let mut module_bytes = Vec::new();
     module.serialize(&mut module_bytes).unwrap();
     let module_bytes = vec![module_bytes];
-    let data = TransactionData::new_module(sender, gas_payment_object_ref, module_bytes, MAX_GAS);
+    let data = TransactionData::new_module(
+        sender,
+        gas_payment_object_ref,
+        module_bytes,
+        MAX_GAS,
+        authority.chain_id,
+    );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
     let _module_object_id = TxContext::new(&sender, transaction.digest(), 0).fresh_id();
@@ -647,6 +715,7 @@ async fn test_publish_non_existing_dependent_module() {
         gas_payment_object_ref,
         vec![dependent_module_bytes],
         MAX_GAS,
+        authority.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
@@ -722,6 +791,7 @@ async fn test_handle_transfer_transaction_double_spend() {
         recipient,
         object.compute_object_reference(),
         gas_object.compute_object_reference(),
+        authority_state.chain_id,
     );
 
     let signed_transaction = authority_state
@@ -1319,6 +1389,327 @@ async fn test_authority_persist() {
     assert_eq!(obj2.owner, recipient);
 }
 
+#[tokio::test]
+async fn test_authority_store_backends() {
+    use sui_storage::backing_store::StorageBackend;
+
+    for backend in [StorageBackend::Sled, StorageBackend::Memory] {
+        let seed = [1u8; 32];
+        let dir = tempfile::tempdir().unwrap();
+        let open = || {
+            let (committee, _, authority_key) =
+                crate::authority_batch::batch_tests::init_state_parameters_from_rng(
+                    &mut StdRng::from_seed(seed),
+                );
+            let store = Arc::new(AuthorityStore::open_with_backend(
+                dir.path(),
+                backend,
+                None,
+                None,
+            ));
+            crate::authority_batch::batch_tests::init_state(committee, authority_key, store)
+        };
+
+        let authority = open().await;
+        let recipient = dbg_addr(2);
+        let object_id = ObjectID::random();
+        let obj = Object::with_id_owner_for_testing(object_id, recipient);
+        authority.insert_genesis_object(obj).await;
+        let obj = authority.get_object(&object_id).await.unwrap().unwrap();
+        assert_eq!(obj.owner, recipient);
+        drop(authority);
+
+        // Only the objects of the memory backend are lost when reopening the store
+        let authority = open().await;
+        let obj = authority.get_object(&object_id).await.unwrap();
+        assert_eq!(obj.is_some(), backend != StorageBackend::Memory);
+    }
+}
+
+#[tokio::test]
+async fn test_authority_store_group_commit() {
+    use sui_storage::backing_store::{GroupCommitConfig, StorageBackend};
+
+    let dir = tempfile::tempdir().unwrap();
+    let (committee, _, authority_key) =
+        crate::authority_batch::batch_tests::init_state_parameters_from_rng(
+            &mut StdRng::from_seed([1u8; 32]),
+        );
+    let store = Arc::new(AuthorityStore::open_with_backend(
+        dir.path(),
+        StorageBackend::RocksDb,
+        Some(GroupCommitConfig {
+            max_delay_micros: 1000,
+            ..Default::default()
+        }),
+        None,
+    ));
+    let authority =
+        crate::authority_batch::batch_tests::init_state(committee, authority_key, store).await;
+
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    authority.insert_genesis_object(object).await;
+    authority.insert_genesis_object(gas_object).await;
+
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    let info = authority.handle_certificate(certificate).await.unwrap();
+    info.signed_effects.unwrap().effects.status.unwrap();
+    let object = authority.get_object(&object_ref.0).await.unwrap().unwrap();
+    assert_eq!(object.owner, dbg_addr(2));
+}
+
+#[tokio::test]
+async fn test_get_objects_by_type() {
+    use sui_types::{gas_coin::GasCoin, SUI_FRAMEWORK_ADDRESS};
+
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+
+    let coin_type = GasCoin::type_();
+    let coins = |cursor, limit| {
+        authority
+            .get_objects_by_type_page(&coin_type, cursor, limit)
+            .unwrap()
+            .into_iter()
+            .map(|info| (info.object_id, info.version))
+            .collect::<Vec<_>>()
+    };
+    let all_coins = coins(None, usize::MAX);
+    assert!(all_coins.contains(&(object_ref.0, object_ref.1)));
+    assert!(all_coins.contains(&(gas_object_ref.0, gas_object_ref.1)));
+
+    // Pages start at their cursor
+    let second = coins(Some(all_coins[1].0), 1);
+    assert_eq!(second, vec![all_coins[1]]);
+
+    // The index follows the versions of the objects
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    authority.handle_certificate(certificate).await.unwrap();
+    let all_coins = coins(None, usize::MAX);
+    assert!(all_coins.contains(&(object_ref.0, object_ref.1.increment())));
+    assert!(!all_coins.contains(&(object_ref.0, object_ref.1)));
+
+    let other_type = StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: ident_str!("coin").to_owned(),
+        name: ident_str!("TreasuryCap").to_owned(),
+        type_params: vec![],
+    };
+    assert!(authority
+        .get_objects_by_type_page(&other_type, None, usize::MAX)
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_get_past_object_read() {
+    use sui_types::object::PastObjectRead;
+
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    let transaction_digest = *certificate.digest();
+    authority.handle_certificate(certificate).await.unwrap();
+
+    // Both versions of the object can be read, each with the transaction that produced it
+    let past = authority
+        .get_past_object_read(&object_ref.0, object_ref.1)
+        .await
+        .unwrap();
+    assert!(matches!(
+        past,
+        PastObjectRead::VersionFound(past_ref, object, _)
+            if past_ref == object_ref && object.owner == Owner::AddressOwner(sender)
+    ));
+    let next_version = object_ref.1.increment();
+    let latest = authority
+        .get_past_object_read(&object_ref.0, next_version)
+        .await
+        .unwrap();
+    assert!(matches!(
+        latest,
+        PastObjectRead::VersionFound(_, object, _)
+            if object.owner == Owner::AddressOwner(recipient)
+                && object.previous_transaction == transaction_digest
+    ));
+
+    assert!(matches!(
+        authority
+            .get_past_object_read(&object_ref.0, next_version.increment())
+            .await
+            .unwrap(),
+        PastObjectRead::VersionTooHigh { latest_version, .. } if latest_version == next_version
+    ));
+    assert!(matches!(
+        authority
+            .get_past_object_read(&ObjectID::random(), object_ref.1)
+            .await
+            .unwrap(),
+        PastObjectRead::ObjectNotExists(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_store_integrity_check_and_repair() {
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    authority.handle_certificate(certificate).await.unwrap();
+
+    let tables = &authority.database.tables;
+    let report = tables.check_integrity().unwrap();
+    assert!(report.is_ok(), "{report:?}");
+
+    // Drop the owner index entry of the transferred object
+    let (owner_key, _) = tables
+        .owner_index
+        .iter()
+        .find(|((_, id), _)| *id == object_ref.0)
+        .unwrap();
+    tables.owner_index.remove(&owner_key).unwrap();
+    let report = tables.check_integrity().unwrap();
+    assert!(report.owner_index_is_inconsistent());
+    assert_eq!(report.unindexed_objects.len(), 1);
+    assert_eq!(report.unindexed_objects[0].0, object_ref.0);
+
+    tables.rebuild_owner_index().unwrap();
+    assert!(tables.check_integrity().unwrap().is_ok());
+    let owned = authority
+        .get_owner_objects(Owner::AddressOwner(dbg_addr(2)))
+        .unwrap();
+    assert_eq!(owned.len(), 1);
+    assert_eq!(owned[0].object_id, object_ref.0);
+}
+
+#[tokio::test]
+async fn test_get_owner_objects_page() {
+    let owner = dbg_addr(1);
+    let mut ids: Vec<_> = (0..5).map(|_| ObjectID::random()).collect();
+    ids.sort();
+    let authority = init_state_with_objects(
+        ids.iter()
+            .map(|id| Object::with_id_owner_for_testing(*id, owner))
+            .chain([Object::with_owner_for_testing(dbg_addr(2))]),
+    )
+    .await;
+    let page = |cursor, limit| {
+        authority
+            .database
+            .get_owner_objects_page(Owner::AddressOwner(owner), cursor, limit)
+            .unwrap()
+    };
+    let page_ids = |cursor, limit| {
+        page(cursor, limit)
+            .into_iter()
+            .map(|info| info.object_id)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(page_ids(None, 3), ids[..3].to_vec());
+    // Pages end with the objects of the owner
+    assert_eq!(page_ids(None, 10), ids);
+    // The cursor is the first object of the page
+    assert_eq!(page_ids(Some(ids[2]), 2), ids[2..4].to_vec());
+    assert_eq!(page_ids(Some(ids[4]), 2), ids[4..].to_vec());
+
+    // Queries get one more object than the limit, which is the cursor of the next page
+    let first = sui_json_rpc_types::Page::new(page(None, 3), 2, |info| info.object_id);
+    assert_eq!(first.data.len(), 2);
+    assert!(first.has_more);
+    assert_eq!(first.next_cursor, Some(ids[2]));
+    // Resuming from it, the objects left fit in the page
+    let last = sui_json_rpc_types::Page::new(page(first.next_cursor, 4), 3, |info| info.object_id);
+    assert_eq!(last.data.len(), 3);
+    assert_eq!(last.data[0].object_id, ids[2]);
+    assert!(!last.has_more);
+    assert_eq!(last.next_cursor, None);
+}
+
+#[tokio::test]
+async fn test_authority_store_backup() {
+    let seed = [1u8; 32];
+    let dir = tempfile::tempdir().unwrap();
+    let backup_dir = dir.path().join("backups");
+    let open = |path: std::path::PathBuf| {
+        let (committee, _, authority_key) =
+            crate::authority_batch::batch_tests::init_state_parameters_from_rng(
+                &mut StdRng::from_seed(seed),
+            );
+        let store = Arc::new(AuthorityStore::open(path, None));
+        crate::authority_batch::batch_tests::init_state(committee, authority_key, store)
+    };
+
+    let authority = open(dir.path().join("store")).await;
+    let first_id = ObjectID::random();
+    authority
+        .insert_genesis_object(Object::with_id_owner_for_testing(first_id, dbg_addr(1)))
+        .await;
+    let first = authority.create_backup(&backup_dir).unwrap();
+
+    // Backing up again while the authority runs adds a backup of the objects written since
+    let second_id = ObjectID::random();
+    authority
+        .insert_genesis_object(Object::with_id_owner_for_testing(second_id, dbg_addr(2)))
+        .await;
+    let second = authority.create_backup(&backup_dir).unwrap();
+    assert!(second.tables.backup_id > first.tables.backup_id);
+    drop(authority);
+
+    let restored_path = dir.path().join("restored");
+    let restored = AuthorityStore::restore_backup(&backup_dir, &restored_path).unwrap();
+    assert_eq!(restored, second);
+    let authority = open(restored_path).await;
+    for object_id in [first_id, second_id] {
+        assert!(authority.get_object(&object_id).await.unwrap().is_some());
+    }
+}
+
 #[tokio::test]
 async fn test_idempotent_reversed_confirmation() {
     // In this test we exercise the case where an authority first receive the certificate,
@@ -1380,6 +1771,7 @@ async fn test_change_epoch_transaction() {
         100,
         authority_state.name,
         &*authority_state.secret,
+        authority_state.chain_id,
     );
     // Make sure that the raw transaction will never be accepted by the validator.
     assert_eq!(
@@ -1423,6 +1815,7 @@ async fn test_transfer_sui_no_amount() {
         None,
         gas_object.compute_object_reference(),
         MAX_GAS,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&tx_data, &sender_key);
     let transaction = Transaction::new(tx_data, signature);
@@ -1474,6 +1867,7 @@ async fn test_transfer_sui_with_amount() {
         Some(500),
         gas_object.compute_object_reference(),
         MAX_GAS,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&tx_data, &sender_key);
     let transaction = Transaction::new(tx_data, signature);
@@ -1512,6 +1906,105 @@ async fn test_transfer_sui_with_amount() {
     );
 }
 
+#[tokio::test]
+async fn test_pay() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient1 = dbg_addr(2);
+    let recipient2 = dbg_addr(3);
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let coin1 = Object::with_id_owner_gas_for_testing(ObjectID::random(), sender, 300);
+    let coin2 = Object::with_id_owner_gas_for_testing(ObjectID::random(), sender, 400);
+    let authority_state =
+        init_state_with_objects(vec![gas_object.clone(), coin1.clone(), coin2.clone()]).await;
+
+    let tx_data = TransactionData::new_pay(
+        sender,
+        vec![
+            coin1.compute_object_reference(),
+            coin2.compute_object_reference(),
+        ],
+        vec![recipient1, recipient2],
+        vec![100, 500],
+        gas_object.compute_object_reference(),
+        MAX_GAS,
+        authority_state.chain_id,
+    );
+    let signature = Signature::new(&tx_data, &sender_key);
+    let transaction = Transaction::new(tx_data, signature);
+
+    let certificate = init_certified_transaction(transaction, &authority_state);
+    let response = authority_state
+        .handle_certificate(certificate)
+        .await
+        .unwrap();
+    let effects = response.signed_effects.unwrap().effects;
+    // The first coin keeps the remainder, the second one is merged into it and deleted, and
+    // each recipient receives a new coin.
+    assert!(effects.status.is_ok());
+    assert_eq!(effects.created.len(), 2);
+    assert_eq!(effects.deleted.len(), 1);
+    assert_eq!(effects.deleted[0].0, coin2.id());
+    for (object_ref, owner) in &effects.created {
+        let coin = authority_state
+            .get_object(&object_ref.0)
+            .await
+            .unwrap()
+            .unwrap();
+        let amount = sui_types::gas::get_gas_balance(&coin).unwrap();
+        match owner {
+            Owner::AddressOwner(address) if *address == recipient1 => assert_eq!(amount, 100),
+            Owner::AddressOwner(address) if *address == recipient2 => assert_eq!(amount, 500),
+            _ => panic!("Unexpected owner {owner} of created coin"),
+        }
+    }
+    let remainder = authority_state
+        .get_object(&coin1.id())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(remainder.owner, Owner::AddressOwner(sender));
+    assert_eq!(sui_types::gas::get_gas_balance(&remainder).unwrap(), 100);
+}
+
+#[tokio::test]
+async fn test_pay_insufficient_balance() {
+    let (sender, sender_key) = get_key_pair();
+    let gas_object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let coin = Object::with_id_owner_gas_for_testing(ObjectID::random(), sender, 300);
+    let authority_state = init_state_with_objects(vec![gas_object.clone(), coin.clone()]).await;
+
+    let tx_data = TransactionData::new_pay(
+        sender,
+        vec![coin.compute_object_reference()],
+        vec![dbg_addr(2), dbg_addr(3)],
+        vec![200, 200],
+        gas_object.compute_object_reference(),
+        MAX_GAS,
+        authority_state.chain_id,
+    );
+    let signature = Signature::new(&tx_data, &sender_key);
+    let transaction = Transaction::new(tx_data, signature);
+
+    let certificate = init_certified_transaction(transaction, &authority_state);
+    let response = authority_state
+        .handle_certificate(certificate)
+        .await
+        .unwrap();
+    let effects = response.signed_effects.unwrap().effects;
+    // Nothing is paid, the coin is only bumped to a new version.
+    assert_eq!(
+        effects.status.unwrap_err(),
+        ExecutionFailureStatus::InvalidPayInsufficientBalance
+    );
+    assert!(effects.created.is_empty());
+    let coin = authority_state
+        .get_object(&coin.id())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sui_types::gas::get_gas_balance(&coin).unwrap(), 300);
+}
+
 #[tokio::test]
 async fn test_store_revert_state_update() {
     // This test checks the correctness of revert_state_update in SuiDataStore.
@@ -1528,6 +2021,7 @@ async fn test_store_revert_state_update() {
         None,
         gas_object.compute_object_reference(),
         MAX_GAS,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&tx_data, &sender_key);
     let transaction = Transaction::new(tx_data, signature);
@@ -1581,6 +2075,326 @@ async fn test_store_revert_state_update() {
     assert!(authority_state.database.get_effects(&tx_digest).is_err());
 }
 
+#[tokio::test]
+async fn test_object_cache_follows_writes() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+    let cache = &authority.database.object_cache;
+
+    // Objects are cached once read
+    authority.get_object(&object_ref.0).await.unwrap().unwrap();
+    assert_eq!(
+        cache.get(&object_ref.0).flatten().map(|o| o.version()),
+        Some(object_ref.1)
+    );
+
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    let tx_digest = *certificate.digest();
+    authority.handle_certificate(certificate).await.unwrap();
+
+    // The objects written replace the cached ones
+    let cached = cache.get(&object_ref.0).flatten().unwrap();
+    assert_eq!(cached.version(), object_ref.1.increment());
+    assert_eq!(cached.owner, Owner::AddressOwner(recipient));
+
+    // An older version read concurrently does not replace them
+    authority.database.object_cache.insert_read(
+        &authority
+            .database
+            .get_object_by_key(&object_ref.0, object_ref.1)
+            .unwrap()
+            .unwrap(),
+    );
+    assert_eq!(
+        authority
+            .get_object(&object_ref.0)
+            .await
+            .unwrap()
+            .unwrap()
+            .owner,
+        Owner::AddressOwner(recipient)
+    );
+
+    // Reverting the transaction forgets the objects it wrote
+    authority.database.revert_state_update(&tx_digest).unwrap();
+    assert!(cache.get(&object_ref.0).is_none());
+    assert_eq!(
+        authority
+            .get_object(&object_ref.0)
+            .await
+            .unwrap()
+            .unwrap()
+            .owner,
+        Owner::AddressOwner(sender)
+    );
+}
+
+#[tokio::test]
+async fn test_authority_store_read_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let secondary_dir = tempfile::tempdir().unwrap();
+    let (committee, _, authority_key) =
+        crate::authority_batch::batch_tests::init_state_parameters_from_rng(
+            &mut StdRng::from_seed([1u8; 32]),
+        );
+    let store = Arc::new(AuthorityStore::open(dir.path(), None));
+    let authority =
+        crate::authority_batch::batch_tests::init_state(committee, authority_key, store).await;
+
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    authority.insert_genesis_object(object).await;
+    authority.insert_genesis_object(gas_object.clone()).await;
+
+    // The store is opened while the authority keeps it open
+    let read_only = AuthorityStore::open_read_only(dir.path(), secondary_dir.path(), None);
+    let object = read_only.get_object(&object_ref.0).unwrap().unwrap();
+    assert_eq!(object.owner, Owner::AddressOwner(sender));
+
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    authority.handle_certificate(certificate).await.unwrap();
+
+    // The writes of the authority are seen once caught up with
+    read_only.try_catch_up_with_primary().unwrap();
+    let object = read_only.get_object(&object_ref.0).unwrap().unwrap();
+    assert_eq!(object.owner, Owner::AddressOwner(dbg_addr(2)));
+
+    // And the store cannot be written to
+    assert!(read_only
+        .insert_object_direct(gas_object_ref, &gas_object)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_authority_store_migrations() {
+    use crate::authority::authority_store_migrations::migrations;
+    use sui_storage::migration::MigrationConfig;
+
+    // Empty stores have the latest version of the schema
+    let store = init_store();
+    let latest = migrations::<sui_types::crypto::AuthoritySignInfo>().latest_version();
+    assert!(store
+        .migrate(&MigrationConfig::default())
+        .unwrap()
+        .migrations
+        .is_empty());
+    assert_eq!(store.tables.schema_version().unwrap(), latest);
+
+    // A store from before the type index, which did not record its version
+    let object = Object::with_owner_for_testing(dbg_addr(1));
+    let authority = init_state_with_objects([object.clone()]).await;
+    let tables = &authority.database.tables;
+    tables.type_index.clear().unwrap();
+    assert_eq!(tables.schema_version().unwrap(), 0);
+
+    let dry_run = MigrationConfig {
+        dry_run: true,
+        backup_dir: None,
+    };
+    let report = authority.database.migrate(&dry_run).unwrap();
+    assert_eq!(report.migrations.len() as u64, latest);
+    assert!(tables.type_index.is_empty());
+    assert_eq!(tables.schema_version().unwrap(), 0);
+
+    let backup_dir = tempfile::tempdir().unwrap();
+    let config = MigrationConfig {
+        dry_run: false,
+        backup_dir: Some(backup_dir.path().to_path_buf()),
+    };
+    let report = authority.database.migrate(&config).unwrap();
+    assert_eq!((report.from_version, report.to_version), (0, latest));
+    assert_eq!(tables.schema_version().unwrap(), latest);
+    assert!(tables
+        .type_index
+        .contains_key(&(object.type_().unwrap().clone(), object.id()))
+        .unwrap());
+    assert!(backup_dir.path().join("tables").exists());
+
+    assert!(authority
+        .database
+        .migrate(&config)
+        .unwrap()
+        .migrations
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_remove_expired_transactions() {
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        authority.chain_id,
+    );
+    authority
+        .handle_transaction(transaction.clone())
+        .await
+        .unwrap();
+    assert!(authority
+        .get_transaction_lock(&object_ref)
+        .await
+        .unwrap()
+        .is_some());
+
+    // Transactions signed recently are kept
+    let an_hour_ago = AuthorityState::unixtime_now_ms() - 60 * 60 * 1000;
+    assert_eq!(
+        authority.remove_expired_transactions(an_hour_ago).unwrap(),
+        0
+    );
+    assert_eq!(authority.remove_expired_transactions(u64::MAX).unwrap(), 1);
+    assert!(authority
+        .get_transaction_lock(&object_ref)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(authority.database.tables.transactions_signed_at.is_empty());
+
+    // The objects stay locked by the removed transaction
+    let conflicting = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(3),
+        object_ref,
+        gas_object_ref,
+        authority.chain_id,
+    );
+    assert!(authority.handle_transaction(conflicting).await.is_err());
+    // Which can be signed again
+    authority.handle_transaction(transaction).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_remove_expired_transactions_keeps_executed() {
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let authority = init_state_with_objects([object, gas_object]).await;
+
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        dbg_addr(2),
+        object_ref,
+        gas_object_ref,
+        authority.chain_id,
+    );
+    let tx_digest = *transaction.digest();
+    authority
+        .handle_transaction(transaction.clone())
+        .await
+        .unwrap();
+    let certificate = init_certified_transaction(transaction, &authority);
+    authority.handle_certificate(certificate).await.unwrap();
+
+    // The executed transaction is not counted nor removed, only its expiry entry is
+    assert_eq!(authority.remove_expired_transactions(u64::MAX).unwrap(), 0);
+    assert!(authority
+        .database
+        .tables
+        .transactions
+        .get(&tx_digest)
+        .unwrap()
+        .is_some());
+    assert!(authority.database.tables.transactions_signed_at.is_empty());
+}
+
+#[tokio::test]
+async fn test_prune_transactions() {
+    let (sender, sender_key) = get_key_pair();
+    let recipient = dbg_addr(2);
+    let object = Object::with_owner_for_testing(sender);
+    let object_ref = object.compute_object_reference();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let gas_object_ref = gas_object.compute_object_reference();
+    let mut authority = init_state_with_objects([object, gas_object]).await;
+
+    let certificate = init_certified_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        object_ref,
+        gas_object_ref,
+        &authority,
+    );
+    let tx_digest = *certificate.digest();
+    authority.handle_certificate(certificate).await.unwrap();
+
+    // Transactions are only pruned once they are in a batch that is not the latest one
+    assert_eq!(
+        authority.prune_transactions(TxSequenceNumber::MAX).unwrap(),
+        0
+    );
+    authority.init_batches_from_database().unwrap();
+    assert_eq!(
+        authority.prune_transactions(TxSequenceNumber::MAX).unwrap(),
+        1
+    );
+    assert_eq!(
+        authority.prune_transactions(TxSequenceNumber::MAX).unwrap(),
+        0
+    );
+
+    let tables = &authority.database.tables;
+    assert!(tables.certificates.get(&tx_digest).unwrap().is_none());
+    assert!(tables.effects.get(&tx_digest).unwrap().is_none());
+    // Compacting the sequence of executed transactions dropped the pruned one
+    assert!(tables.executed_sequence.is_empty());
+    assert_eq!(tables.prune_watermark.get(), 1);
+
+    // The versions of the objects the transaction superseded are gone, not the latest ones
+    assert!(authority
+        .database
+        .get_object_by_key(&object_ref.0, object_ref.1)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        authority
+            .get_object(&object_ref.0)
+            .await
+            .unwrap()
+            .unwrap()
+            .owner,
+        Owner::AddressOwner(recipient)
+    );
+    // The next transactions are sequenced after the pruned ones
+    assert_eq!(authority.database.next_sequence_number().unwrap(), 1);
+}
+
 // helpers
 #[cfg(test)]
 fn init_store() -> Arc<AuthorityStore> {
@@ -1596,6 +2410,19 @@ pub async fn init_state() -> AuthorityState {
 
 #[cfg(test)]
 pub async fn init_state_with_committee(committee: Option<(Committee, KeyPair)>) -> AuthorityState {
+    init_state_with_committee_and_genesis(
+        committee,
+        &sui_config::genesis::Genesis::get_default_genesis(),
+    )
+    .await
+}
+
+/// Same as [`init_state_with_committee`], for the network started from `genesis`.
+#[cfg(test)]
+pub async fn init_state_with_committee_and_genesis(
+    committee: Option<(Committee, KeyPair)>,
+    genesis: &sui_config::genesis::Genesis,
+) -> AuthorityState {
     let (committee, authority_key) = match committee {
         Some(c) => c,
         None => {
@@ -1619,7 +2446,7 @@ pub async fn init_state_with_committee(committee: Option<(Committee, KeyPair)>)
         None,
         None,
         None,
-        &sui_config::genesis::Genesis::get_default_genesis(),
+        genesis,
         &prometheus::Registry::new(),
     )
     .await
@@ -1690,8 +2517,16 @@ pub fn init_transfer_transaction(
     recipient: SuiAddress,
     object_ref: ObjectRef,
     gas_object_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
-    let data = TransactionData::new_transfer(recipient, object_ref, sender, gas_object_ref, 10000);
+    let data = TransactionData::new_transfer(
+        recipient,
+        object_ref,
+        sender,
+        gas_object_ref,
+        10000,
+        chain_id,
+    );
     let signature = Signature::new(&data, secret);
     Transaction::new(data, signature)
 }
@@ -1705,8 +2540,14 @@ fn init_certified_transfer_transaction(
     gas_object_ref: ObjectRef,
     authority_state: &AuthorityState,
 ) -> CertifiedTransaction {
-    let transfer_transaction =
-        init_transfer_transaction(sender, secret, recipient, object_ref, gas_object_ref);
+    let transfer_transaction = init_transfer_transaction(
+        sender,
+        secret,
+        recipient,
+        object_ref,
+        gas_object_ref,
+        authority_state.chain_id,
+    );
     init_certified_transaction(transfer_transaction, authority_state)
 }
 
@@ -1755,6 +2596,7 @@ pub async fn call_move(
         gas_object_ref,
         args,
         MAX_GAS,
+        authority.chain_id,
     );
 
     let signature = Signature::new(&data, sender_key);
@@ -1836,6 +2678,7 @@ async fn make_test_transaction(
             CallArg::Pure(arg_value.to_le_bytes().to_vec()),
         ],
         MAX_GAS,
+        authorities[0].chain_id,
     );
     let signature = Signature::new(&data, sender_key);
     let transaction = Transaction::new(data, signature);
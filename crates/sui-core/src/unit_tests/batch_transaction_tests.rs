@@ -4,10 +4,13 @@
 use super::*;
 use bcs;
 
-use authority_tests::{init_state_with_ids, send_and_confirm_transaction};
+use authority_tests::{
+    init_state_with_committee_and_genesis, init_state_with_ids, send_and_confirm_transaction,
+};
 use move_binary_format::file_format;
 use move_core_types::{account_address::AccountAddress, ident_str};
 use sui_types::{
+    batch_transaction::BatchTransactionBuilder,
     crypto::{get_key_pair, Signature},
     messages::Transaction,
     object::Owner,
@@ -57,6 +60,7 @@ async fn test_batch_transaction_ok() -> anyhow::Result<()> {
             .unwrap()
             .compute_object_reference(),
         100000,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let tx = Transaction::new(data, signature);
@@ -120,6 +124,7 @@ async fn test_batch_transaction_last_one_fail() -> anyhow::Result<()> {
             .unwrap()
             .compute_object_reference(),
         100000,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let tx = Transaction::new(data, signature);
@@ -133,7 +138,7 @@ async fn test_batch_transaction_last_one_fail() -> anyhow::Result<()> {
 
 #[tokio::test]
 async fn test_batch_contains_publish() -> anyhow::Result<()> {
-    // Test that a batch transaction containing publish will fail.
+    // Test that a batch transaction containing publish is accepted and executed.
     let (sender, sender_key) = get_key_pair();
     let gas_object_id = ObjectID::random();
     let authority_state = init_state_with_ids([(sender, gas_object_id)]).await;
@@ -153,13 +158,152 @@ async fn test_batch_contains_publish() -> anyhow::Result<()> {
             .unwrap()
             .compute_object_reference(),
         100000,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let tx = Transaction::new(data, signature);
-    let response = send_and_confirm_transaction(&authority_state, tx).await;
+    let response = send_and_confirm_transaction(&authority_state, tx).await?;
+    assert!(response.signed_effects.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_features_of_later_protocol_versions_are_rejected() -> anyhow::Result<()> {
+    // A network running the first protocol version rejects Pay transactions, batched publishes
+    // and the results of earlier transactions as arguments, which activate at version 2.
+    let (sender, sender_key) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let genesis = sui_config::genesis::Builder::new()
+        .set_protocol_version(1)
+        .build()?;
+    let authority_state = init_state_with_committee_and_genesis(None, &genesis).await;
+    assert_eq!(authority_state.protocol_config().version(), 1);
+    let gas_object = Object::with_id_owner_for_testing(gas_object_id, sender);
+    authority_state
+        .insert_genesis_object(gas_object.clone())
+        .await;
+    let gas_object_ref = gas_object.compute_object_reference();
+    let package_object_ref = authority_state.get_framework_object_ref().await?;
+
+    let pay = TransactionKind::Single(SingleTransactionKind::Pay(Pay {
+        coins: vec![gas_object_ref],
+        recipients: vec![sender],
+        amounts: vec![10],
+    }));
+    let mut module_bytes = Vec::new();
+    file_format::empty_module().serialize(&mut module_bytes)?;
+    let batched_publish =
+        TransactionKind::Batch(vec![SingleTransactionKind::Publish(MoveModulePublish {
+            modules: vec![module_bytes],
+        })]);
+    let mut builder = BatchTransactionBuilder::new();
+    let create = builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("create").to_owned(),
+        vec![],
+        vec![
+            CallArg::Pure(16u64.to_le_bytes().to_vec()),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+    );
+    let created = builder.result(create, 0);
+    builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            created,
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+    );
+    let result_argument = builder
+        .finish(sender, gas_object_ref, 100000, authority_state.chain_id)?
+        .kind;
+
+    for kind in [pay, batched_publish, result_argument] {
+        let data = TransactionData::new(
+            kind,
+            sender,
+            gas_object_ref,
+            100000,
+            authority_state.chain_id,
+        );
+        let signature = Signature::new(&data, &sender_key);
+        let result = authority_state
+            .handle_transaction(Transaction::new(data, signature))
+            .await;
+        assert!(
+            matches!(result, Err(SuiError::UnsupportedFeatureError { .. })),
+            "{result:?}"
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_batch_transaction_command_results() -> anyhow::Result<()> {
+    // This test creates an object and transfers it to the recipient within the same batch,
+    // feeding the result of the first transaction into the second one.
+    let (sender, sender_key) = get_key_pair();
+    let (recipient, _) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let authority_state = init_state_with_ids([(sender, gas_object_id)]).await;
+    let package_object_ref = authority_state.get_framework_object_ref().await?;
+    let gas_object_ref = authority_state
+        .get_object(&gas_object_id)
+        .await?
+        .unwrap()
+        .compute_object_reference();
+
+    let mut builder = BatchTransactionBuilder::new();
+    let create = builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("create").to_owned(),
+        vec![],
+        vec![
+            CallArg::Pure(16u64.to_le_bytes().to_vec()),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+    );
+    let created = builder.result(create, 0);
+    builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            created,
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(recipient)).unwrap()),
+        ],
+    );
+    let data = builder.finish(sender, gas_object_ref, 100000, authority_state.chain_id)?;
+    let signature = Signature::new(&data, &sender_key);
+    let tx = Transaction::new(data, signature);
+    let response = send_and_confirm_transaction(&authority_state, tx).await?;
+    let effects = response.signed_effects.unwrap().effects;
+    assert!(effects.status.is_ok());
+    assert_eq!(effects.created.len(), 1);
+    assert_eq!(effects.created[0].1, Owner::AddressOwner(recipient));
+
+    // A transaction cannot use the results of itself or of later transactions.
+    let mut builder = BatchTransactionBuilder::new();
+    let created = builder.result(0, 0);
+    builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            created,
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(recipient)).unwrap()),
+        ],
+    );
     assert!(matches!(
-        response.unwrap_err(),
-        SuiError::InvalidBatchTransaction { .. }
+        builder.finish(sender, gas_object_ref, 100000, authority_state.chain_id),
+        Err(SuiError::InvalidBatchTransaction { .. })
     ));
     Ok(())
 }
@@ -200,6 +344,7 @@ async fn test_batch_insufficient_gas_balance() -> anyhow::Result<()> {
         sender,
         gas_object.compute_object_reference(),
         100000,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let tx = Transaction::new(data, signature);
@@ -211,3 +356,64 @@ async fn test_batch_insufficient_gas_balance() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_batch_transaction_command_result_of_other_owner() -> anyhow::Result<()> {
+    // This test creates an object, transfers it to a third party and then tries to mutate it
+    // within the same batch, which the sender no longer owns the object for.
+    let (sender, sender_key) = get_key_pair();
+    let (third_party, _) = get_key_pair();
+    let gas_object_id = ObjectID::random();
+    let authority_state = init_state_with_ids([(sender, gas_object_id)]).await;
+    let package_object_ref = authority_state.get_framework_object_ref().await?;
+    let gas_object_ref = authority_state
+        .get_object(&gas_object_id)
+        .await?
+        .unwrap()
+        .compute_object_reference();
+
+    let mut builder = BatchTransactionBuilder::new();
+    let create = builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("create").to_owned(),
+        vec![],
+        vec![
+            CallArg::Pure(16u64.to_le_bytes().to_vec()),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
+        ],
+    );
+    let created = builder.result(create, 0);
+    builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("transfer").to_owned(),
+        vec![],
+        vec![
+            created.clone(),
+            CallArg::Pure(bcs::to_bytes(&AccountAddress::from(third_party)).unwrap()),
+        ],
+    );
+    builder.move_call(
+        package_object_ref,
+        ident_str!("object_basics").to_owned(),
+        ident_str!("set_value").to_owned(),
+        vec![],
+        vec![created, CallArg::Pure(32u64.to_le_bytes().to_vec())],
+    );
+    let data = builder.finish(sender, gas_object_ref, 100000, authority_state.chain_id)?;
+    let signature = Signature::new(&data, &sender_key);
+    let tx = Transaction::new(data, signature);
+    let response = send_and_confirm_transaction(&authority_state, tx).await?;
+    let effects = response.signed_effects.unwrap().effects;
+    assert_eq!(
+        effects.status.unwrap_err(),
+        ExecutionFailureStatus::entry_argument_error(
+            0,
+            EntryArgumentErrorKind::InvalidCommandResult
+        )
+    );
+    // The whole batch is rolled back
+    assert!(effects.created.is_empty());
+    Ok(())
+}
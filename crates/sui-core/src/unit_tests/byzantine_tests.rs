@@ -0,0 +1,155 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::base_types::{dbg_addr, ChainId, SequenceNumber, SuiAddress};
+use sui_types::crypto::{get_key_pair, KeyPair};
+use sui_types::object::Object;
+
+use super::*;
+use crate::authority_aggregator::authority_aggregator_tests::{
+    init_local_authorities, transfer_coin_transaction,
+};
+use crate::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
+
+async fn init_byzantine_authorities(
+    committee_size: usize,
+    genesis_objects: Vec<Object>,
+) -> (
+    AuthorityAggregator<ByzantineAuthorityClient<LocalAuthorityClient>>,
+    Vec<ByzantineConfig>,
+    ChainId,
+) {
+    let (authorities, states) = init_local_authorities(committee_size, genesis_objects).await;
+    let chain_id = states[0].chain_id;
+    let mut configs = BTreeMap::new();
+    let clients = states
+        .into_iter()
+        .map(|state| {
+            let config = ByzantineConfig::new();
+            configs.insert(state.name, config.clone());
+            let name = state.name;
+            let client = LocalAuthorityClient::new_from_authority(state);
+            (name, ByzantineAuthorityClient::new_local(client, config))
+        })
+        .collect();
+    let authorities = AuthorityAggregator::new(
+        authorities.committee,
+        clients,
+        AuthAggMetrics::new_for_tests(),
+    );
+    // In the order of the clients of the aggregator
+    (authorities, configs.into_values().collect(), chain_id)
+}
+
+fn transfer(
+    sender: SuiAddress,
+    key: &KeyPair,
+    to: u8,
+    object: &Object,
+    gas: &Object,
+    chain_id: ChainId,
+) -> Transaction {
+    transfer_coin_transaction(
+        sender,
+        key,
+        dbg_addr(to),
+        object.compute_object_reference(),
+        gas.compute_object_reference(),
+        chain_id,
+    )
+}
+
+async fn latest_version(
+    client: &ByzantineAuthorityClient<LocalAuthorityClient>,
+    object_id: ObjectID,
+) -> SequenceNumber {
+    let request = ObjectInfoRequest::latest_object_info_request(object_id, None);
+    let response = client.handle_object_info_request(request).await.unwrap();
+    response.object().unwrap().version()
+}
+
+#[tokio::test]
+async fn test_conflicting_signatures_do_not_certify() {
+    let (sender, key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let gas_object = Object::with_owner_for_testing(sender);
+    let (authorities, configs, chain_id) =
+        init_byzantine_authorities(4, vec![object.clone(), gas_object.clone()]).await;
+    configs[0].set(Misbehavior::SignConflicting);
+
+    let transaction = transfer(sender, &key, 2, &object, &gas_object, chain_id);
+    authorities.process_transaction(transaction).await.unwrap();
+
+    // Only the byzantine authority signs a transaction spending the same objects
+    let conflicting = transfer(sender, &key, 3, &object, &gas_object, chain_id);
+    let mut clients = authorities.authority_clients.values();
+    let byzantine = clients.next().unwrap();
+    let response = byzantine
+        .handle_transaction(conflicting.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        response.signed_transaction.unwrap().digest(),
+        conflicting.digest()
+    );
+    let honest = clients.next().unwrap();
+    assert!(honest
+        .handle_transaction(conflicting.clone())
+        .await
+        .is_err());
+    assert!(authorities.process_transaction(conflicting).await.is_err());
+}
+
+#[tokio::test]
+async fn test_corrupt_and_withheld_votes() {
+    let (sender, key) = get_key_pair();
+    let objects: Vec<_> = (0..4)
+        .map(|_| Object::with_owner_for_testing(sender))
+        .collect();
+    let (authorities, configs, chain_id) = init_byzantine_authorities(4, objects.clone()).await;
+
+    // Corrupt signatures fail the checks of the client, a quorum is left without them
+    configs[0].set(Misbehavior::CorruptSignatures);
+    let transaction = transfer(sender, &key, 2, &objects[0], &objects[1], chain_id);
+    let byzantine = authorities.authority_clients.values().next().unwrap();
+    assert!(byzantine
+        .handle_transaction(transaction.clone())
+        .await
+        .is_err());
+    let (_, effects) = authorities.execute_transaction(&transaction).await.unwrap();
+    assert!(effects.effects.status.is_ok());
+
+    // Without the votes of two of the four authorities, no quorum is left
+    configs[0].clear();
+    configs[1].set(Misbehavior::WithholdVotes);
+    configs[2].set(Misbehavior::WithholdVotes);
+    let transaction = transfer(sender, &key, 2, &objects[2], &objects[3], chain_id);
+    assert!(authorities.execute_transaction(&transaction).await.is_err());
+
+    configs[2].unset(Misbehavior::WithholdVotes);
+    let (_, effects) = authorities.execute_transaction(&transaction).await.unwrap();
+    assert!(effects.effects.status.is_ok());
+}
+
+#[tokio::test]
+async fn test_stale_objects() {
+    let (sender, key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let gas_object = Object::with_owner_for_testing(sender);
+    let (authorities, configs, chain_id) =
+        init_byzantine_authorities(4, vec![object.clone(), gas_object.clone()]).await;
+    let mut clients = authorities.authority_clients.values();
+    let byzantine = clients.next().unwrap().authority_client();
+    let honest = clients.next().unwrap().authority_client();
+
+    let first = latest_version(byzantine, object.id()).await;
+    configs[0].set(Misbehavior::StaleObjects);
+    let transaction = transfer(sender, &key, 2, &object, &gas_object, chain_id);
+    authorities.execute_transaction(&transaction).await.unwrap();
+
+    assert_eq!(latest_version(byzantine, object.id()).await, first);
+    let latest = latest_version(honest, object.id()).await;
+    assert!(latest > first);
+    configs[0].clear();
+    assert_eq!(latest_version(byzantine, object.id()).await, latest);
+}
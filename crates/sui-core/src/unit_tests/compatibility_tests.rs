@@ -0,0 +1,420 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The data written by each version of the protocol, checked to be read by the current code.
+//!
+//! The fixtures of a version, under `compatibility/v<version>`, hold the messages of each kind
+//! validators and clients exchange, encoded with BCS, a genesis blob, and a snapshot of an
+//! authority store started from that genesis with the objects and transaction it holds. The
+//! messages of every version must decode, verify, and encode back to the same bytes, the
+//! genesis must load, and the store must migrate to the latest schema and return what it held.
+//!
+//! The fixtures of the current version are recorded with `UPDATE_COMPATIBILITY_FIXTURES=1`, and
+//! committed with the change bumping the version. The test fails while they are missing, as it
+//! would otherwise only check the code against its own output. For the same reason, the fixtures
+//! of a released version are recorded by the code of that release, see the README of the
+//! fixtures. Its messages, signed with keys of fixed seeds, must also encode exactly as recorded,
+//! so that a released node of the same version can read them: a change of their encoding fails
+//! the test until the protocol version is bumped, which records the fixtures of the new version
+//! next to the ones the code must keep reading.
+
+use super::*;
+
+use rand::{rngs::StdRng, SeedableRng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use sui_config::builder::ConfigBuilder;
+use sui_config::genesis::Genesis;
+use sui_storage::migration::MigrationConfig;
+use sui_types::crypto::{
+    get_key_pair_from_rng, AuthoritySignInfo, KeyPair, KeypairTraits, Signature,
+};
+use sui_types::gas::GasCostSummary;
+use sui_types::handshake::PROTOCOL_VERSION;
+use sui_types::sui_serde::{Base64, Encoding};
+
+use crate::authority::authority_store_migrations::migrations;
+
+const UPDATE_FIXTURES: &str = "UPDATE_COMPATIBILITY_FIXTURES";
+const MESSAGES_FILENAME: &str = "messages.yaml";
+const GENESIS_FILENAME: &str = "genesis.blob";
+const STORE_DIR: &str = "store";
+const STORE_CONTENTS_FILENAME: &str = "store.yaml";
+
+fn key_pair(seed: u8) -> KeyPair {
+    get_key_pair_from_rng(&mut StdRng::from_seed([seed; 32])).1
+}
+
+fn object_ref(object: &Object) -> ObjectRef {
+    object.compute_object_reference()
+}
+
+/// A message of each kind, with the committee signing them.
+struct Messages {
+    committee: Committee,
+    transaction: Transaction,
+    signed_transaction: SignedTransaction,
+    certificate: CertifiedTransaction,
+    effects: TransactionEffects,
+    signed_effects: SignedTransactionEffects,
+    certified_effects: CertifiedTransactionEffects,
+    object: Object,
+}
+
+impl Messages {
+    /// The messages of a transfer of an object, signed by a committee of four validators.
+    fn new() -> Self {
+        let keys: Vec<_> = (1..=4).map(key_pair).collect();
+        let names: Vec<AuthorityName> = keys.iter().map(|key| key.public().into()).collect();
+        let committee = Committee::new(0, names.iter().map(|name| (*name, 1)).collect()).unwrap();
+
+        let sender_key = key_pair(10);
+        let sender = SuiAddress::from(sender_key.public());
+        let recipient = SuiAddress::from(key_pair(11).public());
+        let object = Object::with_id_owner_gas_for_testing(
+            ObjectID::deterministic_for_testing(1),
+            sender,
+            1000,
+        );
+        let gas = Object::with_id_owner_for_testing(ObjectID::deterministic_for_testing(2), sender);
+        let data = TransactionData::new_transfer(
+            recipient,
+            object_ref(&object),
+            sender,
+            object_ref(&gas),
+            1000,
+            ChainId::deterministic_for_testing(0),
+        );
+        let transaction = Transaction::new(data.clone(), Signature::new(&data, &sender_key));
+        let signed_transaction = SignedTransaction::new(0, transaction.clone(), names[0], &keys[0]);
+        let signatures = names
+            .iter()
+            .zip(&keys)
+            .take(3)
+            .map(|(name, key)| (*name, AuthoritySignature::new(&data, key)))
+            .collect();
+        let certificate = CertifiedTransaction::new_with_signatures(
+            0,
+            transaction.clone(),
+            signatures,
+            &committee,
+        )
+        .unwrap();
+
+        let mut transferred = object.clone();
+        transferred.transfer_and_increment_version(recipient);
+        let mut gas_used = gas.clone();
+        gas_used.data.try_as_move_mut().unwrap().increment_version();
+        let gas_object = (object_ref(&gas_used), Owner::AddressOwner(sender));
+        let effects = TransactionEffects {
+            status: ExecutionStatus::Success,
+            gas_used: GasCostSummary {
+                computation_cost: 100,
+                storage_cost: 40,
+                storage_rebate: 20,
+            },
+            shared_objects: vec![],
+            transaction_digest: *transaction.digest(),
+            created: vec![],
+            mutated: vec![
+                (object_ref(&transferred), Owner::AddressOwner(recipient)),
+                gas_object,
+            ],
+            unwrapped: vec![],
+            deleted: vec![],
+            wrapped: vec![],
+            gas_object,
+            events: vec![],
+            dependencies: vec![TransactionDigest::genesis()],
+        };
+        let signed_effects = effects.clone().to_sign_effects(0, &names[0], &keys[0]);
+        let signatures = names
+            .iter()
+            .zip(&keys)
+            .take(3)
+            .map(|(name, key)| (*name, AuthoritySignature::new(&effects, key)))
+            .collect();
+        let certified_effects =
+            CertifiedTransactionEffects::new(0, effects.clone(), signatures, &committee).unwrap();
+
+        Self {
+            committee,
+            transaction,
+            signed_transaction,
+            certificate,
+            effects,
+            signed_effects,
+            certified_effects,
+            object,
+        }
+    }
+
+    fn encode(&self) -> BTreeMap<String, String> {
+        let mut encoded = BTreeMap::new();
+        let mut put = |name: &str, bytes: Vec<u8>| {
+            encoded.insert(name.to_owned(), Base64::encode(bytes));
+        };
+        put("committee", bcs::to_bytes(&self.committee).unwrap());
+        put("transaction", bcs::to_bytes(&self.transaction).unwrap());
+        put(
+            "signed_transaction",
+            bcs::to_bytes(&self.signed_transaction).unwrap(),
+        );
+        put("certificate", bcs::to_bytes(&self.certificate).unwrap());
+        put("effects", bcs::to_bytes(&self.effects).unwrap());
+        put(
+            "signed_effects",
+            bcs::to_bytes(&self.signed_effects).unwrap(),
+        );
+        put(
+            "certified_effects",
+            bcs::to_bytes(&self.certified_effects).unwrap(),
+        );
+        put("object", bcs::to_bytes(&self.object).unwrap());
+        encoded
+    }
+
+    fn decode(encoded: &BTreeMap<String, String>) -> Result<Self, anyhow::Error> {
+        fn take<T: DeserializeOwned>(
+            encoded: &BTreeMap<String, String>,
+            name: &str,
+        ) -> Result<T, anyhow::Error> {
+            let bytes = encoded
+                .get(name)
+                .ok_or_else(|| anyhow::anyhow!("No {name} in the fixtures"))?;
+            bcs::from_bytes(&Base64::decode(bytes)?)
+                .map_err(|e| anyhow::anyhow!("Cannot decode the {name}: {e}"))
+        }
+        let committee: Committee = take(encoded, "committee")?;
+        // The keys of the committee are not encoded, they are loaded again.
+        let committee = Committee::new(
+            committee.epoch,
+            committee.voting_rights.into_iter().collect(),
+        )?;
+        Ok(Self {
+            committee,
+            transaction: take(encoded, "transaction")?,
+            signed_transaction: take(encoded, "signed_transaction")?,
+            certificate: take(encoded, "certificate")?,
+            effects: take(encoded, "effects")?,
+            signed_effects: take(encoded, "signed_effects")?,
+            certified_effects: take(encoded, "certified_effects")?,
+            object: take(encoded, "object")?,
+        })
+    }
+
+    fn verify(&self) -> SuiResult {
+        self.transaction.verify()?;
+        self.signed_transaction.verify(&self.committee)?;
+        self.certificate.verify(&self.committee)?;
+        self.signed_effects.verify(&self.committee)?;
+        fp_ensure!(
+            self.certified_effects.effects == self.effects
+                && self.effects.transaction_digest == *self.transaction.digest(),
+            SuiError::UnexpectedMessage
+        );
+        Ok(())
+    }
+}
+
+/// What the store of the fixtures holds.
+#[derive(Debug, Serialize, Deserialize)]
+struct StoreContents {
+    objects: Vec<ObjectRef>,
+    transaction: TransactionDigest,
+}
+
+/// Record the fixtures of the current version in `dir`.
+async fn record_fixtures(dir: &Path) {
+    if dir.exists() {
+        fs::remove_dir_all(dir).unwrap();
+    }
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join(MESSAGES_FILENAME),
+        serde_yaml::to_string(&Messages::new().encode()).unwrap(),
+    )
+    .unwrap();
+
+    let config_dir = tempfile::tempdir().unwrap();
+    let network_config = ConfigBuilder::new(config_dir.path())
+        .rng(StdRng::from_seed([0; 32]))
+        .committee_size(NonZeroUsize::new(4).unwrap())
+        .build()
+        .unwrap();
+    let genesis = network_config.genesis;
+    genesis.save(dir.join(GENESIS_FILENAME)).unwrap();
+
+    let store = Arc::new(AuthorityStore::open(dir.join(STORE_DIR), None));
+    store.migrate(&MigrationConfig::default()).unwrap();
+    let key = key_pair(20);
+    let state = AuthorityState::new(
+        genesis.committee().unwrap(),
+        key.public().into(),
+        Arc::pin(key),
+        store.clone(),
+        None,
+        None,
+        None,
+        &genesis,
+        &prometheus::Registry::new(),
+    )
+    .await;
+
+    let sender_key = key_pair(10);
+    let sender = SuiAddress::from(sender_key.public());
+    let object = Object::with_id_owner_for_testing(ObjectID::deterministic_for_testing(1), sender);
+    let gas = Object::with_id_owner_for_testing(ObjectID::deterministic_for_testing(2), sender);
+    state.insert_genesis_object(object.clone()).await;
+    state.insert_genesis_object(gas.clone()).await;
+    let data = TransactionData::new_transfer(
+        SuiAddress::from(key_pair(11).public()),
+        object_ref(&object),
+        sender,
+        object_ref(&gas),
+        1000,
+        state.chain_id,
+    );
+    let transaction = Transaction::new(data.clone(), Signature::new(&data, &sender_key));
+    let digest = *transaction.digest();
+    state.handle_transaction(transaction).await.unwrap();
+
+    let contents = StoreContents {
+        objects: genesis
+            .objects()
+            .iter()
+            .chain([&object, &gas])
+            .map(object_ref)
+            .collect(),
+        transaction: digest,
+    };
+    fs::write(
+        dir.join(STORE_CONTENTS_FILENAME),
+        serde_yaml::to_string(&contents).unwrap(),
+    )
+    .unwrap();
+}
+
+fn check_messages(dir: &Path) {
+    let path = dir.join(MESSAGES_FILENAME);
+    let encoded: BTreeMap<String, String> =
+        serde_yaml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let messages = Messages::decode(&encoded)
+        .unwrap_or_else(|e| panic!("Cannot read {}: {e}", path.display()));
+    messages
+        .verify()
+        .unwrap_or_else(|e| panic!("Cannot verify {}: {e}", path.display()));
+    assert_eq!(
+        messages.encode(),
+        encoded,
+        "The messages of {} do not encode back to the same bytes",
+        path.display()
+    );
+}
+
+fn check_genesis(dir: &Path) {
+    let path = dir.join(GENESIS_FILENAME);
+    let genesis =
+        Genesis::load(&path).unwrap_or_else(|e| panic!("Cannot load {}: {e}", path.display()));
+    assert!(genesis.committee().is_ok(), "{}", path.display());
+    assert_eq!(fs::read(&path).unwrap(), genesis.to_file_bytes());
+    genesis.sui_system_object();
+}
+
+fn check_store(dir: &Path) {
+    let contents: StoreContents =
+        serde_yaml::from_str(&fs::read_to_string(dir.join(STORE_CONTENTS_FILENAME)).unwrap())
+            .unwrap();
+    // Opening and migrating the store writes to it, a copy is opened instead.
+    let copy = tempfile::tempdir().unwrap();
+    copy_dir(&dir.join(STORE_DIR), copy.path());
+    let store = AuthorityStore::open(copy.path(), None);
+    store.migrate(&MigrationConfig::default()).unwrap();
+    assert_eq!(
+        store.tables.schema_version().unwrap(),
+        migrations::<AuthoritySignInfo>().latest_version()
+    );
+
+    for expected in &contents.objects {
+        let object = store
+            .get_object(&expected.0)
+            .unwrap()
+            .unwrap_or_else(|| panic!("Object {} not in {}", expected.0, dir.display()));
+        assert_eq!(object_ref(&object), *expected);
+    }
+    let transaction = store
+        .get_transaction(&contents.transaction)
+        .unwrap()
+        .expect("The signed transaction is in the store");
+    Transaction::new(transaction.data, transaction.tx_signature)
+        .verify()
+        .unwrap();
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// The directories of the fixtures of each version, from the first.
+fn fixture_dirs(root: &Path) -> Vec<(u32, PathBuf)> {
+    let mut dirs: Vec<_> = fs::read_dir(root)
+        .unwrap()
+        .filter_map(|entry| {
+            let entry = entry.unwrap();
+            let name = entry.file_name();
+            let version = name.to_str()?.strip_prefix('v')?.parse().ok()?;
+            Some((version, entry.path()))
+        })
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[tokio::test]
+async fn test_read_data_of_previous_versions() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/unit_tests/compatibility");
+    let current = root.join(format!("v{PROTOCOL_VERSION}"));
+    if std::env::var_os(UPDATE_FIXTURES).is_some() {
+        record_fixtures(&current).await;
+    }
+    assert!(
+        current.exists(),
+        "No fixtures of protocol version {PROTOCOL_VERSION} in {}, run with {UPDATE_FIXTURES}=1 \
+        to record them and commit them.",
+        root.display()
+    );
+
+    let encoded: BTreeMap<String, String> =
+        serde_yaml::from_str(&fs::read_to_string(current.join(MESSAGES_FILENAME)).unwrap())
+            .unwrap();
+    assert_eq!(
+        Messages::new().encode(),
+        encoded,
+        "The encoding of messages of protocol version {PROTOCOL_VERSION} changed, bump the \
+        version, or run with {UPDATE_FIXTURES}=1 if it was not released yet."
+    );
+
+    for (version, dir) in fixture_dirs(&root) {
+        assert!(
+            version <= PROTOCOL_VERSION,
+            "{} is from a later version",
+            dir.display()
+        );
+        check_messages(&dir);
+        check_genesis(&dir);
+        check_store(&dir);
+    }
+}
@@ -9,13 +9,12 @@ use narwhal_types::TransactionsServer;
 use narwhal_types::{Empty, TransactionProto};
 use sui_network::tonic;
 use sui_types::{
-    base_types::{ObjectID, TransactionDigest},
+    base_types::ObjectID,
     crypto::Signature,
-    gas_coin::GasCoin,
     messages::{
         CallArg, CertifiedTransaction, ObjectArg, SignatureAggregator, Transaction, TransactionData,
     },
-    object::{MoveObject, Object, Owner, OBJECT_START_VERSION},
+    object::Object,
 };
 use test_utils::test_keys;
 use tokio::sync::mpsc::channel;
@@ -36,9 +35,7 @@ pub fn test_gas_objects() -> Vec<Object> {
 pub fn test_shared_object() -> Object {
     let seed = "0x6666666666666660";
     let shared_object_id = ObjectID::from_hex_literal(seed).unwrap();
-    let content = GasCoin::new(shared_object_id, OBJECT_START_VERSION, 10);
-    let obj = MoveObject::new_gas_coin(content.to_bcs_bytes());
-    Object::new_move(obj, Owner::Shared, TransactionDigest::genesis())
+    Object::shared_for_testing(shared_object_id, 10)
 }
 
 /// Fixture: a few test certificates containing a shared object.
@@ -67,6 +64,7 @@ pub async fn test_certificates(authority: &AuthorityState) -> Vec<CertifiedTrans
                 CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
             ],
             /* max_gas */ 10_000,
+            authority.chain_id,
         );
         let signature = Signature::new(&data, &keypair);
         let transaction = Transaction::new(data, signature);
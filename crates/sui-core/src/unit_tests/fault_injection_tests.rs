@@ -0,0 +1,131 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Instant;
+
+use sui_types::base_types::{dbg_addr, ChainId};
+use sui_types::crypto::{get_key_pair, KeypairTraits};
+use sui_types::object::Object;
+
+use super::*;
+use crate::authority_aggregator::authority_aggregator_tests::{
+    init_local_authorities, transfer_coin_transaction,
+};
+use crate::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
+use crate::authority_client::LocalAuthorityClient;
+
+async fn init_faulty_authorities(
+    committee_size: usize,
+    genesis_objects: Vec<Object>,
+    scenario: &NetworkScenario,
+) -> (
+    AuthorityAggregator<FaultyAuthorityClient<LocalAuthorityClient>>,
+    ChainId,
+) {
+    let (authorities, states) = init_local_authorities(committee_size, genesis_objects).await;
+    let chain_id = states[0].chain_id;
+    let clients = states
+        .into_iter()
+        .map(|state| (state.name, LocalAuthorityClient::new_from_authority(state)))
+        .collect();
+    let authorities = AuthorityAggregator::new(
+        authorities.committee,
+        faulty_clients(clients, scenario),
+        AuthAggMetrics::new_for_tests(),
+    );
+    (authorities, chain_id)
+}
+
+#[tokio::test]
+async fn test_link_faults() {
+    let (sender, _) = get_key_pair();
+    let gas_object = Object::with_owner_for_testing(sender);
+    let scenario = NetworkScenario::new();
+    let (authorities, _) = init_faulty_authorities(1, vec![gas_object.clone()], &scenario).await;
+    let (name, client) = authorities.authority_clients.iter().next().unwrap();
+    let client = client.authority_client();
+    let request = || ObjectInfoRequest::latest_object_info_request(gas_object.id(), None);
+
+    client.handle_object_info_request(request()).await.unwrap();
+
+    scenario.set_faults(
+        *name,
+        LinkFaults {
+            latency: Duration::from_millis(100),
+            ..LinkFaults::default()
+        },
+    );
+    let start = Instant::now();
+    client.handle_object_info_request(request()).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    scenario.set_faults(
+        *name,
+        LinkFaults {
+            drop_response_rate: 1.0,
+            ..LinkFaults::default()
+        },
+    );
+    assert!(client.handle_object_info_request(request()).await.is_err());
+
+    // Default faults only apply to the authorities without faults of their own
+    scenario.apply(&ScenarioStep::ClearFaults(*name));
+    scenario.set_default_faults(LinkFaults {
+        drop_request_rate: 1.0,
+        ..LinkFaults::default()
+    });
+    assert!(client.handle_object_info_request(request()).await.is_err());
+    scenario.set_default_faults(LinkFaults::default());
+
+    scenario.partition([*name]);
+    assert!(client.handle_object_info_request(request()).await.is_err());
+    scenario.heal();
+    client.handle_object_info_request(request()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_scenario_script() {
+    let (_, key_pair) = get_key_pair();
+    let name: AuthorityName = key_pair.public().into();
+    let scenario = NetworkScenario::new();
+    let script = ScenarioScript::new()
+        .at(Duration::from_millis(200), ScenarioStep::Heal)
+        .at(Duration::ZERO, ScenarioStep::Partition(vec![name]));
+
+    let handle = scenario.play(script);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(scenario.is_partitioned(&name));
+    handle.await.unwrap();
+    assert!(!scenario.is_partitioned(&name));
+}
+
+#[tokio::test]
+async fn test_transactions_go_through_a_faulty_network() {
+    let (sender, key) = get_key_pair();
+    let object = Object::with_owner_for_testing(sender);
+    let gas_object = Object::with_owner_for_testing(sender);
+    let scenario = NetworkScenario::new();
+    let (authorities, chain_id) =
+        init_faulty_authorities(4, vec![object.clone(), gas_object.clone()], &scenario).await;
+
+    // A quorum is left out of a partition of one authority, requests to the others are reordered
+    // and delivered twice.
+    let partitioned = *authorities.authority_clients.keys().next().unwrap();
+    scenario.partition([partitioned]);
+    scenario.set_default_faults(LinkFaults {
+        jitter: Duration::from_millis(50),
+        duplicate_rate: 0.5,
+        ..LinkFaults::default()
+    });
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        chain_id,
+    );
+    let (_, effects) = authorities.execute_transaction(&transaction).await.unwrap();
+    assert!(effects.effects.status.is_ok());
+}
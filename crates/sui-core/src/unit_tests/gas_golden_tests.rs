@@ -0,0 +1,241 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The gas used by a fixed corpus of transactions, checked against the golden file of the
+//! protocol version, `gas_golden/v<version>.yaml`.
+//!
+//! Gas is part of the protocol: a change to the gas a transaction uses fails this test until the
+//! golden file is updated, by running the test with `UPDATE_GAS_GOLDEN=1`, so that the change
+//! shows in review as a diff of the file. Changes in a released version should come with a bump
+//! of the protocol version, whose golden file is recorded the same way and committed next to the
+//! ones of the previous versions. The test fails while the file of the current version is
+//! missing, rather than recording it, so that it cannot pass without a committed baseline.
+
+use super::authority_tests::{init_state, send_and_confirm_transaction};
+use super::move_integration_tests::build_and_try_publish_test_package;
+use super::*;
+
+use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use sui_types::crypto::{get_key_pair, KeyPair, Signature};
+use sui_types::handshake::PROTOCOL_VERSION;
+use sui_types::object::GAS_VALUE_FOR_TESTING;
+
+const GAS_BUDGET: u64 = 10_000;
+const UPDATE_GOLDEN: &str = "UPDATE_GAS_GOLDEN";
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct GasUsage {
+    status: String,
+    computation_cost: u64,
+    storage_cost: u64,
+    storage_rebate: u64,
+}
+
+impl From<&TransactionEffects> for GasUsage {
+    fn from(effects: &TransactionEffects) -> Self {
+        let status = match &effects.status {
+            ExecutionStatus::Success => "success".to_owned(),
+            ExecutionStatus::Failure { error } => format!("{error:?}"),
+        };
+        Self {
+            status,
+            computation_cost: effects.gas_used.computation_cost,
+            storage_cost: effects.gas_used.storage_cost,
+            storage_rebate: effects.gas_used.storage_rebate,
+        }
+    }
+}
+
+/// Executes the transactions of the corpus, each paid with a gas coin of its own so that the
+/// gas it uses does not depend on the ones before.
+struct Corpus {
+    state: AuthorityState,
+    sender: SuiAddress,
+    key: KeyPair,
+    usage: BTreeMap<String, GasUsage>,
+}
+
+impl Corpus {
+    async fn new() -> Self {
+        let (sender, key) = get_key_pair();
+        Self {
+            state: init_state().await,
+            sender,
+            key,
+            usage: BTreeMap::new(),
+        }
+    }
+
+    async fn new_coin(&self) -> ObjectRef {
+        let coin = Object::with_id_owner_for_testing(ObjectID::random(), self.sender);
+        let coin_ref = coin.compute_object_reference();
+        self.state.insert_genesis_object(coin).await;
+        coin_ref
+    }
+
+    async fn latest_ref(&self, object_id: &ObjectID) -> ObjectRef {
+        self.state
+            .get_object(object_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .compute_object_reference()
+    }
+
+    async fn execute(&mut self, name: &str, data: TransactionData) -> TransactionEffects {
+        let signature = Signature::new(&data, &self.key);
+        let effects = send_and_confirm_transaction(&self.state, Transaction::new(data, signature))
+            .await
+            .unwrap()
+            .signed_effects
+            .unwrap()
+            .effects;
+        self.usage.insert(name.to_owned(), GasUsage::from(&effects));
+        effects
+    }
+
+    async fn call_object_basics(
+        &mut self,
+        name: &str,
+        function: &str,
+        arguments: Vec<CallArg>,
+    ) -> TransactionEffects {
+        let package = self.state.get_framework_object_ref().await.unwrap();
+        let gas = self.new_coin().await;
+        let data = TransactionData::new_move_call(
+            self.sender,
+            package,
+            Identifier::new("object_basics").unwrap(),
+            Identifier::new(function).unwrap(),
+            vec![],
+            gas,
+            arguments,
+            GAS_BUDGET,
+            self.state.chain_id,
+        );
+        self.execute(name, data).await
+    }
+}
+
+fn pure<T: Serialize>(value: &T) -> CallArg {
+    CallArg::Pure(bcs::to_bytes(value).unwrap())
+}
+
+fn owned(object_ref: ObjectRef) -> CallArg {
+    CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref))
+}
+
+#[tokio::test]
+async fn gas_usage_matches_golden_file() {
+    let mut corpus = Corpus::new().await;
+    let sender = corpus.sender;
+    let chain_id = corpus.state.chain_id;
+    let (recipient, _) = get_key_pair();
+
+    let (object, gas) = (corpus.new_coin().await, corpus.new_coin().await);
+    let data = TransactionData::new_transfer(recipient, object, sender, gas, GAS_BUDGET, chain_id);
+    corpus.execute("transfer_object", data).await;
+
+    let gas = corpus.new_coin().await;
+    let data =
+        TransactionData::new_transfer_sui(recipient, sender, Some(100), gas, GAS_BUDGET, chain_id);
+    corpus.execute("transfer_sui", data).await;
+
+    let gas = corpus.new_coin().await;
+    let amount = Some(GAS_VALUE_FOR_TESTING * 2);
+    let data =
+        TransactionData::new_transfer_sui(recipient, sender, amount, gas, GAS_BUDGET, chain_id);
+    corpus
+        .execute("transfer_sui_insufficient_balance", data)
+        .await;
+
+    let (coin, gas) = (corpus.new_coin().await, corpus.new_coin().await);
+    let data = TransactionData::new_pay(
+        sender,
+        vec![coin],
+        vec![recipient, sender],
+        vec![100, 200],
+        gas,
+        GAS_BUDGET,
+        chain_id,
+    );
+    corpus.execute("pay", data).await;
+
+    let gas = corpus.new_coin().await;
+    let response = build_and_try_publish_test_package(
+        &corpus.state,
+        &sender,
+        &corpus.key,
+        &gas.0,
+        "object_wrapping",
+        GAS_BUDGET,
+    )
+    .await;
+    let effects = response.signed_effects.unwrap().effects;
+    corpus
+        .usage
+        .insert("publish".to_owned(), GasUsage::from(&effects));
+
+    let arguments = vec![pure(&16u64), pure(&AccountAddress::from(sender))];
+    let effects = corpus
+        .call_object_basics("move_call_create", "create", arguments)
+        .await;
+    let object_id = effects.created[0].0 .0;
+
+    let arguments = vec![owned(corpus.latest_ref(&object_id).await), pure(&17u64)];
+    corpus
+        .call_object_basics("move_call_mutate", "set_value", arguments)
+        .await;
+
+    let arguments = vec![owned(corpus.latest_ref(&object_id).await)];
+    corpus
+        .call_object_basics("move_call_wrap", "wrap", arguments)
+        .await;
+
+    let arguments = vec![pure(&18u64), pure(&AccountAddress::from(sender))];
+    let effects = corpus
+        .call_object_basics("move_call_create_to_delete", "create", arguments)
+        .await;
+    let object_id = effects.created[0].0 .0;
+    let arguments = vec![owned(corpus.latest_ref(&object_id).await)];
+    corpus
+        .call_object_basics("move_call_delete", "delete", arguments)
+        .await;
+
+    check_golden_file(&corpus.usage);
+}
+
+fn check_golden_file(usage: &BTreeMap<String, GasUsage>) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/unit_tests/gas_golden")
+        .join(format!("v{PROTOCOL_VERSION}.yaml"));
+    if std::env::var_os(UPDATE_GOLDEN).is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_yaml::to_string(usage).unwrap()).unwrap();
+        return;
+    }
+
+    let golden = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "Cannot read the gas golden file {}: {e}\nRun with {UPDATE_GOLDEN}=1 to record it.",
+            path.display()
+        )
+    });
+    let golden: BTreeMap<String, GasUsage> = serde_yaml::from_str(&golden).unwrap();
+    let names: BTreeSet<_> = golden.keys().chain(usage.keys()).collect();
+    let changes: Vec<_> = names
+        .into_iter()
+        .filter(|name| golden.get(*name) != usage.get(*name))
+        .map(|name| format!("{name}: {:?} -> {:?}", golden.get(name), usage.get(name)))
+        .collect();
+    assert!(
+        changes.is_empty(),
+        "Gas usage differs from {}:\n{}\nRun with {UPDATE_GOLDEN}=1 to update it if intended.",
+        path.display(),
+        changes.join("\n"),
+    );
+}
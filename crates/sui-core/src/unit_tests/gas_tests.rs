@@ -354,6 +354,7 @@ async fn test_move_call_gas() -> SuiResult {
         gas_object.compute_object_reference(),
         args.clone(),
         GAS_VALUE_FOR_TESTING,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
@@ -416,6 +417,7 @@ async fn test_move_call_gas() -> SuiResult {
             created_object_ref,
         ))],
         expected_gas_balance,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
@@ -442,6 +444,7 @@ async fn test_move_call_gas() -> SuiResult {
         gas_object.compute_object_reference(),
         args,
         budget,
+        authority_state.chain_id,
     );
     let signature = Signature::new(&data, &sender_key);
     let transaction = Transaction::new(data, signature);
@@ -512,8 +515,14 @@ async fn execute_transfer_with_price(
         recipient,
         object_ref: object.compute_object_reference(),
     }));
-    let data =
-        TransactionData::new_with_gas_price(kind, sender, gas_object_ref, gas_budget, gas_price);
+    let data = TransactionData::new_with_gas_price(
+        kind,
+        sender,
+        gas_object_ref,
+        gas_budget,
+        gas_price,
+        authority_state.chain_id,
+    );
     let signature = Signature::new(&data, &sender_key);
     let tx = Transaction::new(data, signature);
 
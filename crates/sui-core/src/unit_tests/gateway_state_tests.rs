@@ -20,7 +20,7 @@ use crate::authority_aggregator::authority_aggregator_tests::{
     crate_object_move_transaction, get_local_client, init_local_authorities,
 };
 use crate::authority_client::LocalAuthorityClient;
-use crate::gateway_state::{GatewayAPI, GatewayState};
+use crate::gateway_state::{ClientStore, GatewayAPI, GatewayState, InMemoryClientStore};
 
 use super::*;
 
@@ -29,19 +29,23 @@ async fn create_gateway_state(genesis_objects: Vec<Object>) -> GatewayState<Loca
         .iter()
         .map(|o| o.get_single_owner().unwrap())
         .collect();
-    let (authorities, _) = init_local_authorities(4, genesis_objects).await;
+    let (authorities, states) = init_local_authorities(4, genesis_objects).await;
     let path = tempfile::tempdir().unwrap().into_path();
-    let gateway =
-        GatewayState::new_with_authorities(path, authorities, GatewayMetrics::new_for_tests())
-            .unwrap();
+    let gateway = GatewayState::new_with_authorities(
+        path,
+        authorities,
+        GatewayMetrics::new_for_tests(),
+        states[0].chain_id,
+    )
+    .unwrap();
     for owner in all_owners {
         gateway.sync_account_state(owner).await.unwrap();
     }
     gateway
 }
 
-async fn public_transfer_object(
-    gateway: &GatewayState<LocalAuthorityClient>,
+async fn public_transfer_object<S: ClientStore>(
+    gateway: &GatewayState<LocalAuthorityClient, S>,
     signer: SuiAddress,
     key: &KeyPair,
     coin_object_id: ObjectID,
@@ -96,6 +100,199 @@ async fn test_public_transfer_object() {
     assert_eq!(gateway.get_total_transaction_number().unwrap(), 1);
 }
 
+#[tokio::test]
+async fn test_public_transfer_object_in_memory_store() {
+    let (addr1, key1) = get_key_pair();
+    let (addr2, _key2) = get_key_pair();
+
+    let coin_object = Object::with_owner_for_testing(addr1);
+    let gas_object = Object::with_owner_for_testing(addr1);
+
+    let genesis_objects = vec![coin_object.clone(), gas_object.clone()];
+    let (authorities, states) = init_local_authorities(4, genesis_objects).await;
+    let gateway = GatewayState::new_with_store_and_authorities(
+        Arc::new(InMemoryClientStore::new()),
+        authorities,
+        GatewayMetrics::new_for_tests(),
+        states[0].chain_id,
+    )
+    .unwrap();
+    gateway.sync_account_state(addr1).await.unwrap();
+    assert_eq!(
+        gateway
+            .get_objects_owned_by_address(addr1)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let response = public_transfer_object(
+        &gateway,
+        addr1,
+        &key1,
+        coin_object.id(),
+        gas_object.id(),
+        addr2,
+    )
+    .await
+    .unwrap();
+    let effects = response.effects;
+    assert_eq!(effects.mutated.len(), 2);
+    assert_eq!(gateway.get_total_transaction_number().unwrap(), 1);
+
+    // The store applied the effects: the coin changed owner and the transaction is no
+    // longer pending.
+    let owned_by_addr1 = gateway.get_objects_owned_by_address(addr1).await.unwrap();
+    assert_eq!(owned_by_addr1.len(), 1);
+    assert_eq!(owned_by_addr1[0].object_id, gas_object.id());
+    let owned_by_addr2 = gateway.get_objects_owned_by_address(addr2).await.unwrap();
+    assert_eq!(owned_by_addr2.len(), 1);
+    assert_eq!(owned_by_addr2[0].object_id, coin_object.id());
+    let digest = effects.transaction_digest;
+    assert!(gateway.store().get_transaction(&digest).unwrap().is_none());
+    assert!(gateway.store().get_effects(&digest).is_ok());
+    assert_eq!(
+        gateway
+            .get_transaction(digest)
+            .await
+            .unwrap()
+            .effects
+            .status,
+        effects.status
+    );
+}
+
+#[tokio::test]
+async fn test_estimate_gas() {
+    let (addr1, key1) = get_key_pair();
+    let (addr2, _key2) = get_key_pair();
+
+    let coin_object = Object::with_owner_for_testing(addr1);
+    let gas_object = Object::with_owner_for_testing(addr1);
+
+    let genesis_objects = vec![coin_object.clone(), gas_object.clone()];
+    let gateway = create_gateway_state(genesis_objects).await;
+
+    let mut data = gateway
+        .public_transfer_object(
+            addr1,
+            coin_object.id(),
+            Some(gas_object.id()),
+            GAS_VALUE_FOR_TESTING / 10,
+            addr2,
+        )
+        .await
+        .unwrap();
+    let estimate = gateway.estimate_gas(data.clone()).await.unwrap();
+    assert!(estimate.gas_units > 0);
+    assert!(estimate.recommended_budget >= estimate.gas_units);
+    assert!(estimate.recommended_budget < data.gas_budget);
+    // The dry run must not touch the gateway state.
+    assert_eq!(gateway.get_total_transaction_number().unwrap(), 0);
+
+    // Executing with the recommended budget succeeds and costs exactly what was estimated.
+    data.gas_budget = estimate.recommended_budget;
+    let signature = key1.sign(&data.to_bytes());
+    let effects = gateway
+        .execute_transaction(Transaction::new(data, signature))
+        .await
+        .unwrap()
+        .to_effect_response()
+        .unwrap()
+        .effects;
+    assert!(effects.status.is_ok());
+    assert_eq!(effects.gas_used, estimate.gas_used);
+}
+
+#[tokio::test]
+async fn test_estimate_gas_below_reference_gas_price() {
+    let (addr1, _key1) = get_key_pair();
+    let (addr2, _key2) = get_key_pair();
+
+    let coin_object = Object::with_owner_for_testing(addr1);
+    let gas_object = Object::with_owner_for_testing(addr1);
+
+    let genesis_objects = vec![coin_object.clone(), gas_object.clone()];
+    let gateway = create_gateway_state(genesis_objects).await;
+
+    let data = gateway
+        .public_transfer_object(
+            addr1,
+            coin_object.id(),
+            Some(gas_object.id()),
+            GAS_VALUE_FOR_TESTING / 10,
+            addr2,
+        )
+        .await
+        .unwrap();
+    let reference_estimate = gateway.estimate_gas(data.clone()).await.unwrap();
+    // The dry run synced the system state object, which holds the reference gas price.
+    let reference_gas_price = gateway
+        .store()
+        .get_sui_system_state_object()
+        .unwrap()
+        .parameters
+        .storage_gas_price;
+    assert_eq!(data.gas_price, reference_gas_price);
+    assert_eq!(reference_estimate.gas_price, reference_gas_price);
+
+    // A transaction priced below the reference gas price is estimated at the reference price.
+    let mut underpriced = data;
+    underpriced.gas_price = reference_gas_price - 1;
+    let estimate = gateway.estimate_gas(underpriced).await.unwrap();
+    assert_eq!(estimate.gas_price, reference_gas_price);
+    assert!(estimate.gas_units > 0);
+    assert_eq!(estimate, reference_estimate);
+}
+
+#[tokio::test]
+async fn test_dry_run_transaction() {
+    let (addr1, _key1) = get_key_pair();
+    let (addr2, _key2) = get_key_pair();
+
+    let coin_object = Object::with_owner_for_testing(addr1);
+    let gas_object = Object::with_owner_for_testing(addr1);
+
+    let genesis_objects = vec![coin_object.clone(), gas_object.clone()];
+    let gateway = create_gateway_state(genesis_objects).await;
+
+    let data = gateway
+        .public_transfer_object(
+            addr1,
+            coin_object.id(),
+            Some(gas_object.id()),
+            GAS_VALUE_FOR_TESTING / 10,
+            addr2,
+        )
+        .await
+        .unwrap();
+    let preview = gateway.dry_run_transaction(data).await.unwrap();
+    assert!(preview.effects.status.is_ok());
+    assert_eq!(gateway.get_total_transaction_number().unwrap(), 0);
+
+    // The coin moves from addr1 to addr2, and addr1 also pays for gas.
+    assert_eq!(preview.transferred.len(), 1);
+    assert_eq!(preview.transferred[0].reference.object_id, coin_object.id());
+    assert_eq!(preview.transferred[0].owner, Owner::AddressOwner(addr2));
+    let gas_used = &preview.gas_estimate.gas_used;
+    let gas_cost = gas_used.computation_cost as i128 + gas_used.storage_cost as i128
+        - gas_used.storage_rebate as i128;
+    let change_of = |address| {
+        preview
+            .balance_changes
+            .iter()
+            .find(|change| change.owner == address)
+            .unwrap()
+            .amount
+    };
+    assert_eq!(
+        change_of(addr1),
+        -(GAS_VALUE_FOR_TESTING as i128) - gas_cost
+    );
+    assert_eq!(change_of(addr2), GAS_VALUE_FOR_TESTING as i128);
+}
+
 #[tokio::test]
 async fn test_move_call() {
     let (addr1, key1) = get_key_pair();
@@ -370,6 +567,7 @@ async fn test_equivocation_resilient() {
             None,
             coin_object.compute_object_reference(),
             1000,
+            gateway.chain_id,
         );
         let signature = key1.sign(&data.to_bytes());
         let handle = tokio::task::spawn({
@@ -648,6 +846,7 @@ async fn test_multiple_gateways() {
         path,
         gateway1.authorities.clone(),
         GatewayMetrics::new_for_tests(),
+        gateway1.chain_id,
     )
     .unwrap();
     let response = public_transfer_object(
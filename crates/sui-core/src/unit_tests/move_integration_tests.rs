@@ -472,7 +472,13 @@ pub async fn build_and_try_publish_test_package(
     let gas_object = authority.get_object(gas_object_id).await.unwrap();
     let gas_object_ref = gas_object.unwrap().compute_object_reference();
 
-    let data = TransactionData::new_module(*sender, gas_object_ref, all_module_bytes, gas_budget);
+    let data = TransactionData::new_module(
+        *sender,
+        gas_object_ref,
+        all_module_bytes,
+        gas_budget,
+        authority.chain_id,
+    );
     let signature = Signature::new(&data, sender_key);
     let transaction = Transaction::new(data, signature);
     send_and_confirm_transaction(authority, transaction)
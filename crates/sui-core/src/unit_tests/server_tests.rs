@@ -9,10 +9,16 @@ use crate::{
     },
 };
 use futures::StreamExt;
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use sui_network::compression::Compression;
+use sui_network::pool::{ConnectionPool, PoolConfig};
+use sui_network::quic::{QuicConfig, QuicConnector, RequestKind};
+use sui_network::tls::NodeCertificate;
 use sui_types::{
-    base_types::{dbg_addr, dbg_object_id, ExecutionDigests},
+    base_types::{dbg_addr, dbg_object_id, ChainId, ExecutionDigests},
     batch::UpdateItem,
+    handshake::{Handshake, PROTOCOL_VERSION},
     object::ObjectFormatOptions,
 };
 
@@ -73,9 +79,18 @@ async fn test_simple_request() {
 
     let server_handle = server.spawn().await.unwrap();
 
-    let client = NetworkAuthorityClient::connect(server_handle.address())
-        .await
-        .unwrap();
+    // Clients only accept the server they expect
+    let impostor = NodeCertificate::ephemeral().public_key;
+    assert!(
+        NetworkAuthorityClient::connect(server_handle.address(), impostor)
+            .await
+            .is_err()
+    );
+
+    let client =
+        NetworkAuthorityClient::connect(server_handle.address(), server_handle.public_key())
+            .await
+            .unwrap();
 
     let req = ObjectInfoRequest::latest_object_info_request(
         object_id,
@@ -85,6 +100,218 @@ async fn test_simple_request() {
     client.handle_object_info_request(req).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_simple_request_over_quic() {
+    let sender = dbg_addr(1);
+    let object_id = dbg_object_id(1);
+    let authority_state = init_state_with_object_id(sender, object_id).await;
+
+    // The following two fields are only needed for shared objects (not by this bench).
+    let consensus_address = "/ip4/127.0.0.1/tcp/0/http".parse().unwrap();
+    let (tx_consensus_listener, _rx_consensus_listener) = tokio::sync::mpsc::channel(1);
+
+    let certificate = NodeCertificate::ephemeral();
+    let server = AuthorityServer::new(
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+        Arc::new(authority_state),
+        consensus_address,
+        tx_consensus_listener,
+    )
+    .with_certificate(certificate.clone());
+
+    let server_handle = server.spawn().await.unwrap();
+
+    // Clients only accept the server they expect
+    let connector = QuicConnector::ephemeral(QuicConfig::default());
+    let impostor = NodeCertificate::ephemeral().public_key;
+    assert!(connector
+        .connect(server_handle.address(), impostor)
+        .await
+        .is_err());
+
+    let client = NetworkAuthorityClient::new_quic(
+        connector
+            .connect(server_handle.address(), certificate.public_key)
+            .await
+            .unwrap(),
+    );
+
+    // Requests are multiplexed over the connection
+    let requests = (0..10).map(|_| {
+        let req = ObjectInfoRequest::latest_object_info_request(
+            object_id,
+            Some(ObjectFormatOptions::default()),
+        );
+        client.handle_object_info_request(req)
+    });
+    for response in futures::future::join_all(requests).await {
+        let response = response.unwrap();
+        assert_eq!(
+            response.object().unwrap().id(),
+            object_id,
+            "Unexpected object in {response:?}"
+        );
+    }
+
+    // Errors of the validator are reported as with gRPC, this authority has no checkpoints
+    let req = CheckpointRequest::proposal(false);
+    assert!(matches!(
+        client.handle_checkpoint(req).await,
+        Err(SuiError::RpcError(message)) if message.contains("Checkpoint not supported")
+    ));
+
+    server_handle.kill().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_handshake() {
+    let pool = |handshake| {
+        ConnectionPool::new(
+            PoolConfig::default(),
+            mysten_network::config::Config::new(),
+            QuicConnector::ephemeral(QuicConfig::default()),
+        )
+        .with_handshake(handshake)
+    };
+
+    for address in ["/ip4/127.0.0.1/tcp/0/http", "/ip4/127.0.0.1/udp/0/quic"] {
+        let authority_state = init_state_with_object_id(dbg_addr(1), dbg_object_id(1)).await;
+        let chain_id = authority_state.chain_id;
+
+        let consensus_address = "/ip4/127.0.0.1/tcp/0/http".parse().unwrap();
+        let (tx_consensus_listener, _rx_consensus_listener) = tokio::sync::mpsc::channel(1);
+
+        let certificate = NodeCertificate::ephemeral();
+        let server = AuthorityServer::new(
+            address.parse().unwrap(),
+            Arc::new(authority_state),
+            consensus_address,
+            tx_consensus_listener,
+        )
+        .with_certificate(certificate.clone());
+        let handle = server.spawn().await.unwrap();
+        let address = handle.address();
+        // The server authenticates with the key of its certificate
+        let name = certificate.public_key;
+
+        let same_chain = pool(Handshake::new(chain_id));
+        same_chain.checkout(address, name).await.unwrap();
+        assert_eq!(
+            same_chain.protocol_version(address, name),
+            Some(PROTOCOL_VERSION)
+        );
+
+        let other_chain = pool(Handshake::new(ChainId([0; 32])));
+        let error = other_chain.checkout(address, name).await.err().unwrap();
+        assert!(error.to_string().contains("expected chain"), "{error}");
+
+        let newer = pool(Handshake {
+            min_protocol_version: PROTOCOL_VERSION + 1,
+            max_protocol_version: PROTOCOL_VERSION + 1,
+            ..Handshake::new(chain_id)
+        });
+        let error = newer.checkout(address, name).await.err().unwrap();
+        assert!(error.to_string().contains("protocol versions"), "{error}");
+        assert_eq!(newer.protocol_version(address, name), None);
+
+        handle.kill().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_compressed_request_over_quic() {
+    let sender = dbg_addr(1);
+    let object_id = dbg_object_id(1);
+    let authority_state = init_state_with_object_id(sender, object_id).await;
+
+    let consensus_address = "/ip4/127.0.0.1/tcp/0/http".parse().unwrap();
+    let (tx_consensus_listener, _rx_consensus_listener) = tokio::sync::mpsc::channel(1);
+
+    let certificate = NodeCertificate::ephemeral();
+    let server = AuthorityServer::new(
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+        Arc::new(authority_state),
+        consensus_address,
+        tx_consensus_listener,
+    )
+    .with_certificate(certificate.clone())
+    .with_quic_config(QuicConfig {
+        compression: vec![Compression::Zstd, Compression::Snappy],
+        compression_threshold: 0,
+        ..QuicConfig::default()
+    });
+
+    let server_handle = server.spawn().await.unwrap();
+
+    // The server picks the compression it prefers among the ones offered, if any
+    for compression in [vec![Compression::Snappy], vec![Compression::Zstd], vec![]] {
+        let connector = QuicConnector::ephemeral(QuicConfig {
+            compression,
+            compression_threshold: 0,
+            ..QuicConfig::default()
+        });
+        let client = NetworkAuthorityClient::new_quic(
+            connector
+                .connect(server_handle.address(), certificate.public_key)
+                .await
+                .unwrap(),
+        );
+        let req = ObjectInfoRequest::latest_object_info_request(
+            object_id,
+            Some(ObjectFormatOptions::default()),
+        );
+        let response = client.handle_object_info_request(req).await.unwrap();
+        assert_eq!(response.object().unwrap().id(), object_id);
+    }
+
+    server_handle.kill().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_oversized_request_over_quic() {
+    let sender = dbg_addr(1);
+    let object_id = dbg_object_id(1);
+    let authority_state = init_state_with_object_id(sender, object_id).await;
+
+    let consensus_address = "/ip4/127.0.0.1/tcp/0/http".parse().unwrap();
+    let (tx_consensus_listener, _rx_consensus_listener) = tokio::sync::mpsc::channel(1);
+
+    let small_object_info = || QuicConfig {
+        max_request_sizes: BTreeMap::from([(RequestKind::ObjectInfo, 8)]),
+        ..QuicConfig::default()
+    };
+    let certificate = NodeCertificate::ephemeral();
+    let server = AuthorityServer::new(
+        "/ip4/127.0.0.1/udp/0/quic".parse().unwrap(),
+        Arc::new(authority_state),
+        consensus_address,
+        tx_consensus_listener,
+    )
+    .with_certificate(certificate.clone())
+    .with_quic_config(small_object_info());
+
+    let server_handle = server.spawn().await.unwrap();
+
+    // Rejected by the server, or by the client before sending it if it has the same limits
+    for config in [QuicConfig::default(), small_object_info()] {
+        let client = NetworkAuthorityClient::new_quic(
+            QuicConnector::ephemeral(config)
+                .connect(server_handle.address(), certificate.public_key)
+                .await
+                .unwrap(),
+        );
+        let req = ObjectInfoRequest::latest_object_info_request(object_id, None);
+        let error = client.handle_object_info_request(req).await.unwrap_err();
+        assert!(error.to_string().contains("exceeds its maximum size"));
+
+        // The connection is still usable for the requests within their limits
+        let req = AccountInfoRequest::from(sender);
+        client.handle_account_info_request(req).await.unwrap();
+    }
+
+    server_handle.kill().await.unwrap();
+}
+
 #[tokio::test(flavor = "current_thread", start_paused = true)]
 async fn test_subscription() {
     let sender = dbg_addr(1);
@@ -112,9 +339,10 @@ async fn test_subscription() {
 
     let server_handle = server.spawn().await.unwrap();
 
-    let client = NetworkAuthorityClient::connect(server_handle.address())
-        .await
-        .unwrap();
+    let client =
+        NetworkAuthorityClient::connect(server_handle.address(), server_handle.public_key())
+            .await
+            .unwrap();
 
     tokio::time::sleep(Duration::from_millis(10)).await;
 
@@ -0,0 +1,54 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::base_types::dbg_addr;
+
+use super::*;
+
+#[test]
+fn test_denied_senders() {
+    let policy = ServingPolicy::new(&TunableConfig {
+        denied_senders: [dbg_addr(1)].into_iter().collect(),
+        ..Default::default()
+    });
+    assert_eq!(
+        policy.check(&dbg_addr(1)),
+        Err(PolicyViolation::DeniedSender(dbg_addr(1)))
+    );
+    assert!(policy.check(&dbg_addr(2)).is_ok());
+
+    policy.update(&TunableConfig::default());
+    assert!(policy.check(&dbg_addr(1)).is_ok());
+}
+
+#[test]
+fn test_rate_limit() {
+    let policy = ServingPolicy::new(&TunableConfig {
+        max_transactions_per_second: Some(2),
+        ..Default::default()
+    });
+    let start = Instant::now();
+    let sender = dbg_addr(1);
+    assert!(policy.check_at(&sender, start).is_ok());
+    assert!(policy.check_at(&sender, start).is_ok());
+    assert_eq!(
+        policy.check_at(&sender, start),
+        Err(PolicyViolation::RateLimited(2))
+    );
+
+    // The limit applies to each second
+    let next = start + RATE_WINDOW;
+    assert!(policy.check_at(&sender, next).is_ok());
+
+    // A reloaded limit applies to the current second
+    policy.update(&TunableConfig {
+        max_transactions_per_second: Some(3),
+        ..Default::default()
+    });
+    assert!(policy.check_at(&sender, next).is_ok());
+    assert!(policy.check_at(&sender, next).is_ok());
+    assert!(policy.check_at(&sender, next).is_err());
+
+    policy.update(&TunableConfig::default());
+    assert!(policy.check_at(&sender, next).is_ok());
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_types::base_types::{dbg_addr, dbg_object_id};
+
+use super::*;
+use crate::authority_aggregator::authority_aggregator_tests::transfer_coin_transaction;
+use crate::byzantine::Misbehavior;
+
+/// Transfer an object in a simulation of `seed`, returning the deliveries of the simulation.
+async fn simulate_transfer(seed: u64) -> Vec<Delivery> {
+    let (sender, key) = get_key_pair_from_rng(&mut StdRng::seed_from_u64(0));
+    let object = Object::with_id_owner_for_testing(dbg_object_id(1), sender);
+    let gas_object = Object::with_id_owner_for_testing(dbg_object_id(2), sender);
+    let simulation = Simulation::new(seed, 4, vec![object.clone(), gas_object.clone()]).await;
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        simulation.genesis().chain_id(),
+    );
+    let (_, effects) = simulation
+        .authorities()
+        .execute_transaction(&transaction)
+        .await
+        .unwrap();
+    assert!(effects.effects.status.is_ok());
+    simulation.deliveries()
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn test_scheduler_delivers_in_seeded_order() {
+    let order = |seed| async move {
+        let scheduler = Scheduler::new(seed);
+        let (_, key_pair) = get_key_pair_from_rng(&mut StdRng::seed_from_u64(0));
+        let name: AuthorityName = key_pair.public().into();
+        let methods = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let waits: Vec<_> = methods
+            .into_iter()
+            .map(|method| {
+                let scheduler = scheduler.clone();
+                tokio::spawn(async move { scheduler.wait_for_delivery(name, method).await })
+            })
+            .collect();
+        // The clock only advances once every request waits for delivery
+        tokio::time::sleep(DELIVERY_INTERVAL).await;
+        while scheduler.deliver_next() {}
+        for wait in waits {
+            wait.await.unwrap();
+        }
+        assert!(!scheduler.deliver_next());
+        scheduler
+            .deliveries()
+            .into_iter()
+            .map(|delivery| delivery.method)
+            .collect::<Vec<_>>()
+    };
+
+    let first = order(1).await;
+    assert_eq!(first.len(), 8);
+    assert_eq!(first, order(1).await);
+    assert_ne!(first, order(2).await);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn test_simulations_replay_from_their_seed() {
+    let deliveries = simulate_transfer(42).await;
+    assert!(deliveries
+        .iter()
+        .any(|delivery| delivery.method == "handle_certificate"));
+    assert_eq!(deliveries, simulate_transfer(42).await);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn test_simulated_authorities_turn_byzantine() {
+    let (sender, key) = get_key_pair_from_rng(&mut StdRng::seed_from_u64(0));
+    let object = Object::with_id_owner_for_testing(dbg_object_id(1), sender);
+    let gas_object = Object::with_id_owner_for_testing(dbg_object_id(2), sender);
+    let simulation = Simulation::new(7, 4, vec![object.clone(), gas_object.clone()]).await;
+    let byzantine = simulation.states()[0].name;
+    simulation
+        .byzantine(&byzantine)
+        .set(Misbehavior::CorruptSignatures);
+
+    let transaction = transfer_coin_transaction(
+        sender,
+        &key,
+        dbg_addr(2),
+        object.compute_object_reference(),
+        gas_object.compute_object_reference(),
+        simulation.genesis().chain_id(),
+    );
+    let (certificate, effects) = simulation
+        .authorities()
+        .execute_transaction(&transaction)
+        .await
+        .unwrap();
+    assert!(effects.effects.status.is_ok());
+    let committee = simulation.genesis().committee().unwrap();
+    assert!(certificate
+        .auth_sign_info
+        .authorities(&committee)
+        .all(|name| *name.unwrap() != byzantine));
+}
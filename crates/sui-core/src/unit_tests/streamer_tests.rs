@@ -0,0 +1,47 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::StreamExt;
+
+use super::*;
+
+#[derive(Clone)]
+struct EvenNumberFilter;
+
+impl Filter<u64> for EvenNumberFilter {
+    fn matches(&self, item: &u64) -> bool {
+        item % 2 == 0
+    }
+}
+
+#[tokio::test]
+async fn test_subscriber_receives_matching_data_in_order() {
+    let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE);
+    let stream = streamer.subscribe(EvenNumberFilter);
+
+    for i in 0..10 {
+        streamer.send(i).await.unwrap();
+    }
+    let received: Vec<_> = stream.take(5).collect().await;
+    assert_eq!(received, vec![0, 2, 4, 6, 8]);
+}
+
+#[tokio::test]
+async fn test_lagging_subscriber_is_dropped() {
+    let streamer = Streamer::spawn(EVENT_DISPATCH_BUFFER_SIZE);
+    let mut active = Box::pin(streamer.subscribe(EvenNumberFilter));
+    let lagging = streamer.subscribe(EvenNumberFilter);
+
+    // The lagging subscriber never reads, it overflows its buffer on the last send.
+    for i in 0..=EVENT_DISPATCH_BUFFER_SIZE as u64 {
+        streamer.send(i * 2).await.unwrap();
+        assert_eq!(active.next().await, Some(i * 2));
+    }
+
+    // The lagging subscriber gets what was buffered before it was dropped, then the stream ends.
+    assert_eq!(lagging.count().await, EVENT_DISPATCH_BUFFER_SIZE);
+
+    // The active subscriber is unaffected.
+    streamer.send(42).await.unwrap();
+    assert_eq!(active.next().await, Some(42));
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::authority::authority_tests::{
+    init_state_with_objects_and_committee, init_transfer_transaction,
+};
+use std::collections::BTreeMap;
+use sui_types::base_types::{ChainId, ObjectID};
+use sui_types::committee::Committee;
+use sui_types::messages::SignatureAggregator;
+use sui_types::object::{Object, Owner};
+
+#[tokio::test]
+async fn replay_captured_traffic() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("traffic.log");
+
+    let (sender, sender_key) = get_key_pair();
+    let (recipient, _) = get_key_pair();
+    let objects = vec![
+        Object::with_id_owner_for_testing(ObjectID::random(), sender),
+        Object::with_id_owner_for_testing(ObjectID::random(), sender),
+    ];
+    let object_id = objects[0].id();
+    let (_, authority_key) = get_key_pair();
+    let committee =
+        Committee::new(0, BTreeMap::from([(authority_key.public().into(), 1)])).unwrap();
+    let state = init_state_with_objects_and_committee(
+        objects.clone(),
+        Some((committee.clone(), authority_key.copy())),
+    )
+    .await;
+
+    let recorder = TrafficRecorder::create(&path).unwrap();
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        recipient,
+        objects[0].compute_object_reference(),
+        objects[1].compute_object_reference(),
+        state.chain_id,
+    );
+    recorder.record_transaction(&transaction);
+    let vote = state
+        .handle_transaction(transaction.clone())
+        .await
+        .unwrap()
+        .signed_transaction
+        .unwrap();
+    let certificate = SignatureAggregator::try_new(transaction, &committee)
+        .unwrap()
+        .append(vote.auth_sign_info.authority, vote.auth_sign_info.signature)
+        .unwrap()
+        .unwrap();
+    recorder.record_certificate(&certificate);
+    assert_eq!(recorder.dropped(), 0);
+    recorder.finish();
+
+    // The traffic is replayed into a state starting from the same objects.
+    let fresh_state =
+        init_state_with_objects_and_committee(objects, Some((committee, authority_key))).await;
+    let fresh_state = Arc::new(fresh_state);
+    let stats = replay(
+        fresh_state.clone(),
+        TrafficLog::open(&path).unwrap(),
+        ReplayConfig {
+            speed: None,
+            max_in_flight: 1,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        (stats.transactions, stats.certificates, stats.failed),
+        (1, 1, 0)
+    );
+    let object = fresh_state.db().get_object(&object_id).unwrap().unwrap();
+    assert_eq!(object.owner, Owner::AddressOwner(recipient));
+}
+
+#[tokio::test]
+async fn partial_frames_end_the_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("traffic.log");
+    let (sender, sender_key) = get_key_pair();
+    let object = Object::with_id_owner_for_testing(ObjectID::random(), sender);
+    let transaction = init_transfer_transaction(
+        sender,
+        &sender_key,
+        sender,
+        object.compute_object_reference(),
+        object.compute_object_reference(),
+        ChainId::deterministic_for_testing(0),
+    );
+
+    let recorder = TrafficRecorder::create(&path).unwrap();
+    recorder.record_transaction(&transaction);
+    recorder.record_transaction(&transaction);
+    recorder.finish();
+    let length = std::fs::metadata(&path).unwrap().len();
+    let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(length - 10).unwrap();
+
+    let requests = TrafficLog::open(&path)
+        .unwrap()
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(matches!(
+        &requests[0].request,
+        CapturedRequest::Transaction(recorded) if recorded.digest() == transaction.digest()
+    ));
+
+    std::fs::write(&path, b"not a log").unwrap();
+    assert!(TrafficLog::open(&path).is_err());
+}
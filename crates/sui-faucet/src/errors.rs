@@ -17,6 +17,9 @@ pub enum FaucetError {
     #[error("Coin Transfer Failed `{0}`")]
     Transfer(String),
 
+    #[error("Too many requests, retry in {0} seconds")]
+    RateLimited(u64),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
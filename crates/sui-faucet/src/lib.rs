@@ -3,10 +3,14 @@
 
 mod errors;
 mod faucet;
+mod metrics;
+mod rate_limiter;
 mod requests;
 mod responses;
 
 pub use errors::FaucetError;
 pub use faucet::*;
+pub use metrics::FaucetMetrics;
+pub use rate_limiter::RateLimiter;
 pub use requests::*;
 pub use responses::*;
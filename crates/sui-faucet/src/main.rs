@@ -3,6 +3,7 @@
 
 use axum::{
     error_handling::HandleErrorLayer,
+    extract::ConnectInfo,
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
@@ -10,6 +11,7 @@ use axum::{
 };
 use clap::Parser;
 use http::Method;
+use prometheus::{Registry, TextEncoder};
 use std::{
     borrow::Cow,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -18,7 +20,10 @@ use std::{
 };
 use sui::client_commands::{SuiClientCommands, WalletContext};
 use sui_config::{sui_config_dir, SUI_CLIENT_CONFIG};
-use sui_faucet::{Faucet, FaucetRequest, FaucetResponse, SimpleFaucet};
+use sui_faucet::{
+    Faucet, FaucetError, FaucetMetrics, FaucetRequest, FaucetResponse, RateLimiter, SimpleFaucet,
+};
+use sui_types::base_types::SuiAddress;
 use tower::ServiceBuilder;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, warn};
@@ -51,12 +56,26 @@ struct FaucetConfig {
 
     #[clap(long, default_value_t = 120)]
     timeout_in_seconds: u64,
+
+    /// Maximum number of requests for a recipient address within the rate limit window
+    #[clap(long, default_value_t = 10)]
+    max_requests_per_address: usize,
+
+    /// Maximum number of requests from a client IP within the rate limit window
+    #[clap(long, default_value_t = 50)]
+    max_requests_per_ip: usize,
+
+    #[clap(long, default_value_t = 3600)]
+    rate_limit_window_in_seconds: u64,
 }
 
 struct AppState<F = SimpleFaucet> {
     faucet: F,
     config: FaucetConfig,
-    // TODO: add counter
+    address_limiter: RateLimiter<SuiAddress>,
+    ip_limiter: RateLimiter<IpAddr>,
+    registry: Registry,
+    metrics: FaucetMetrics,
 }
 
 #[tokio::main]
@@ -75,12 +94,33 @@ async fn main() -> Result<(), anyhow::Error> {
         port,
         request_buffer_size,
         timeout_in_seconds,
+        max_requests_per_address,
+        max_requests_per_ip,
+        rate_limit_window_in_seconds,
         ..
     } = config;
 
+    let window = Duration::from_secs(rate_limit_window_in_seconds);
+    let registry = Registry::new();
     let app_state = Arc::new(AppState {
         faucet: SimpleFaucet::new(context).await.unwrap(),
         config,
+        address_limiter: RateLimiter::new(max_requests_per_address, window),
+        ip_limiter: RateLimiter::new(max_requests_per_ip, window),
+        metrics: FaucetMetrics::new(&registry),
+        registry,
+    });
+
+    // Requests are only pruned for the keys they are checked for, so drop the keys that stopped
+    // making requests once per window.
+    let limiters = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(window.max(Duration::from_secs(1)));
+        loop {
+            interval.tick().await;
+            limiters.address_limiter.evict_expired();
+            limiters.ip_limiter.evict_expired();
+        }
     });
 
     // TODO: restrict access if needed
@@ -92,6 +132,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let app = Router::new()
         .route("/", get(health))
         .route("/gas", post(request_gas))
+        .route("/metrics", get(metrics))
         .layer(
             ServiceBuilder::new()
                 .layer(HandleErrorLayer::new(handle_error))
@@ -106,7 +147,7 @@ async fn main() -> Result<(), anyhow::Error> {
     let addr = SocketAddr::new(IpAddr::V4(host_ip), port);
     info!("listening on {}", addr);
     axum::Server::bind(&addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
     Ok(())
 }
@@ -118,31 +159,57 @@ async fn health() -> &'static str {
 
 /// handler for all the request_gas requests
 async fn request_gas(
-    Json(payload): Json<FaucetRequest>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
     Extension(state): Extension<Arc<AppState>>,
+    Json(payload): Json<FaucetRequest>,
 ) -> impl IntoResponse {
     // ID for traceability
     let id = Uuid::new_v4();
-    info!(uuid = ?id, "Got new gas request.");
+    info!(uuid = ?id, ?client, "Got new gas request.");
+    state.metrics.total_requests.inc();
     let result = match payload {
         FaucetRequest::FixedAmountRequest(requests) => {
-            state
-                .faucet
-                .send(
-                    id,
-                    requests.recipient,
-                    &vec![state.config.amount; state.config.num_coins],
-                )
-                .await
+            // A request rejected by either limiter is not counted by the other one.
+            let rate_limit = state
+                .ip_limiter
+                .check(&client.ip())
+                .and_then(|_| state.address_limiter.check(&requests.recipient));
+            match rate_limit {
+                Ok(()) => {
+                    state.ip_limiter.record(&client.ip());
+                    state.address_limiter.record(&requests.recipient);
+                    state
+                        .faucet
+                        .send(
+                            id,
+                            requests.recipient,
+                            &vec![state.config.amount; state.config.num_coins],
+                        )
+                        .await
+                }
+                Err(e) => Err(e),
+            }
         }
     };
     match result {
         Ok(v) => {
             info!(uuid =?id, "Request is successfully served");
+            state.metrics.successful_requests.inc();
+            state.metrics.coins_sent.inc_by(v.sent.len() as u64);
+            state
+                .metrics
+                .amount_sent
+                .inc_by(v.sent.iter().map(|coin| coin.amount).sum());
             (StatusCode::CREATED, Json(FaucetResponse::from(v)))
         }
+        Err(v @ FaucetError::RateLimited(_)) => {
+            info!(uuid =?id, ?client, "Gas request rate limited");
+            state.metrics.rate_limited_requests.inc();
+            (StatusCode::TOO_MANY_REQUESTS, Json(FaucetResponse::from(v)))
+        }
         Err(v) => {
             warn!(uuid =?id, "Failed to request gas: {:?}", v);
+            state.metrics.failed_requests.inc();
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(FaucetResponse::from(v)),
@@ -151,6 +218,17 @@ async fn request_gas(
     }
 }
 
+async fn metrics(Extension(state): Extension<Arc<AppState>>) -> (StatusCode, String) {
+    let metrics_families = state.registry.gather();
+    match TextEncoder.encode_to_string(&metrics_families) {
+        Ok(metrics) => (StatusCode::OK, metrics),
+        Err(error) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("unable to encode metrics: {error}"),
+        ),
+    }
+}
+
 async fn create_wallet_context() -> Result<WalletContext, anyhow::Error> {
     // Create Wallet context.
     let wallet_conf = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
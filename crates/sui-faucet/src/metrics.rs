@@ -0,0 +1,57 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use prometheus::{register_int_counter_with_registry, IntCounter, Registry};
+
+/// Prometheus metrics exposed by the faucet service.
+pub struct FaucetMetrics {
+    pub total_requests: IntCounter,
+    pub successful_requests: IntCounter,
+    pub failed_requests: IntCounter,
+    pub rate_limited_requests: IntCounter,
+    pub coins_sent: IntCounter,
+    pub amount_sent: IntCounter,
+}
+
+impl FaucetMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            total_requests: register_int_counter_with_registry!(
+                "faucet_total_requests",
+                "Total number of gas requests received",
+                registry,
+            )
+            .unwrap(),
+            successful_requests: register_int_counter_with_registry!(
+                "faucet_successful_requests",
+                "Number of gas requests served successfully",
+                registry,
+            )
+            .unwrap(),
+            failed_requests: register_int_counter_with_registry!(
+                "faucet_failed_requests",
+                "Number of gas requests that failed",
+                registry,
+            )
+            .unwrap(),
+            rate_limited_requests: register_int_counter_with_registry!(
+                "faucet_rate_limited_requests",
+                "Number of gas requests rejected by rate limiting",
+                registry,
+            )
+            .unwrap(),
+            coins_sent: register_int_counter_with_registry!(
+                "faucet_coins_sent",
+                "Number of gas coins sent to requesters",
+                registry,
+            )
+            .unwrap(),
+            amount_sent: register_int_counter_with_registry!(
+                "faucet_amount_sent",
+                "Total amount of SUI sent to requesters",
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::FaucetError;
+
+/// A sliding window rate limiter, allowing each key at most `max_requests` requests within
+/// any `window` long period.
+pub struct RateLimiter<K> {
+    max_requests: usize,
+    window: Duration,
+    requests: Mutex<HashMap<K, VecDeque<Instant>>>,
+}
+
+impl<K: Hash + Eq + Clone> RateLimiter<K> {
+    pub fn new(max_requests: usize, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+            requests: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return `FaucetError::RateLimited` if `key` already made `max_requests` requests within
+    /// the window. The request is only counted once it is recorded with [`RateLimiter::record`].
+    pub fn check(&self, key: &K) -> Result<(), FaucetError> {
+        self.check_at(key, Instant::now())
+    }
+
+    /// Count a request for `key` in the window.
+    pub fn record(&self, key: &K) {
+        self.record_at(key, Instant::now())
+    }
+
+    /// Drop the keys whose requests all fell out of the window, so the limiter does not grow
+    /// with every key that ever made a request. Checks only prune the key they check.
+    pub fn evict_expired(&self) {
+        self.evict_expired_at(Instant::now())
+    }
+
+    fn check_at(&self, key: &K, now: Instant) -> Result<(), FaucetError> {
+        let mut requests = self.requests.lock().unwrap();
+        let times = match requests.get_mut(key) {
+            Some(times) => times,
+            None => return Ok(()),
+        };
+        self.prune(times, now);
+        if times.len() >= self.max_requests {
+            let retry_after = times
+                .front()
+                .map(|t| self.window - now.duration_since(*t))
+                .unwrap_or(self.window);
+            return Err(FaucetError::RateLimited(retry_after.as_secs().max(1)));
+        }
+        Ok(())
+    }
+
+    fn record_at(&self, key: &K, now: Instant) {
+        let mut requests = self.requests.lock().unwrap();
+        let times = requests.entry(key.clone()).or_default();
+        self.prune(times, now);
+        times.push_back(now);
+    }
+
+    fn evict_expired_at(&self, now: Instant) {
+        let mut requests = self.requests.lock().unwrap();
+        requests.retain(|_, times| {
+            self.prune(times, now);
+            !times.is_empty()
+        });
+    }
+
+    /// Drop the requests of `times` that fell out of the window.
+    fn prune(&self, times: &mut VecDeque<Instant>, now: Instant) {
+        while matches!(times.front(), Some(t) if now.duration_since(*t) >= self.window) {
+            times.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_should_reject_over_limit() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+        for _ in 0..2 {
+            assert!(limiter.check_at(&"a", now).is_ok());
+            limiter.record_at(&"a", now);
+        }
+        assert!(matches!(
+            limiter.check_at(&"a", now),
+            Err(FaucetError::RateLimited(_))
+        ));
+        // Other keys have their own budget
+        assert!(limiter.check_at(&"b", now).is_ok());
+        // The window slides
+        assert!(limiter
+            .check_at(&"a", now + Duration::from_secs(60))
+            .is_ok());
+    }
+
+    #[test]
+    fn rate_limiter_should_only_count_recorded_requests() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(limiter.check_at(&"a", now).is_ok());
+        assert!(limiter.check_at(&"a", now).is_ok());
+        limiter.record_at(&"a", now);
+        assert!(limiter.check_at(&"a", now).is_err());
+    }
+
+    #[test]
+    fn rate_limiter_should_evict_expired_keys() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let now = Instant::now();
+        limiter.record_at(&"a", now);
+        limiter.record_at(&"b", now + Duration::from_secs(30));
+
+        // Checking a key does not touch the others
+        assert!(limiter
+            .check_at(&"b", now + Duration::from_secs(60))
+            .is_err());
+        assert_eq!(limiter.requests.lock().unwrap().len(), 2);
+
+        limiter.evict_expired_at(now + Duration::from_secs(60));
+        let requests = limiter.requests.lock().unwrap();
+        assert_eq!(requests.keys().collect::<Vec<_>>(), vec![&"b"]);
+    }
+}
@@ -1,12 +1,16 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use move_binary_format::CompiledModule;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_core_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId};
 use move_package::BuildConfig;
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::Path,
+};
 use sui_types::error::{SuiError, SuiResult};
+use sui_types::protocol_config::ProtocolConfig;
 use sui_verifier::verifier as sui_bytecode_verifier;
 
 const SUI_PACKAGE_NAME: &str = "Sui";
@@ -30,23 +34,44 @@ pub fn build_move_stdlib_modules(lib_dir: &Path) -> SuiResult<Vec<CompiledModule
 }
 
 pub fn verify_modules(modules: &[CompiledModule]) -> SuiResult {
+    let config = ProtocolConfig::get_for_max_version();
     for m in modules {
         move_bytecode_verifier::verify_module(m).map_err(|err| {
             SuiError::ModuleVerificationFailure {
                 error: err.to_string(),
             }
         })?;
-        sui_bytecode_verifier::verify_module(m)?;
+        sui_bytecode_verifier::verify_module(m, &config)?;
     }
     Ok(())
     // TODO(https://github.com/MystenLabs/sui/issues/69): Run Move linker
 }
+/// Returns the modules that `modules` depend on but that are not part of `modules`. When
+/// publishing a package, these are the already published modules the package links against.
+pub fn published_dependencies(modules: &[CompiledModule]) -> BTreeSet<ModuleId> {
+    let self_modules: HashSet<ModuleId> = modules.iter().map(|m| m.self_id()).collect();
+    modules
+        .iter()
+        .flat_map(|m| m.immediate_dependencies())
+        .filter(|id| !self_modules.contains(id))
+        .collect()
+}
+
 /// Given a `path` and a `build_config`, build the package in that path.
 /// If we are building the Sui framework, we skip the check that the addresses should be 0
 pub fn build_move_package(
     path: &Path,
     build_config: BuildConfig,
 ) -> SuiResult<Vec<CompiledModule>> {
+    build_move_package_with_deps(path, build_config).map(|(modules, _)| modules)
+}
+
+/// Build the package in `path` like [`build_move_package`], also returning the modules of its
+/// dependencies the package links against, as they were compiled from their local source.
+pub fn build_move_package_with_deps(
+    path: &Path,
+    build_config: BuildConfig,
+) -> SuiResult<(Vec<CompiledModule>, Vec<CompiledModule>)> {
     match build_config.compile_package_no_exit(path, &mut Vec::new()) {
         Err(error) => Err(SuiError::ModuleBuildFailure {
             error: error.to_string(),
@@ -99,14 +124,28 @@ pub fn build_move_package(
             {
                 return Err(SuiError::ModulePublishFailure { error: format!("Dependent modules must have been published on-chain with non-0 addresses, unlike module {:?}", m.self_id()) });
             }
-            Ok(package
+            let modules: Vec<CompiledModule> = package
                 .all_modules_map()
                 .compute_dependency_graph()
                 .compute_topological_order()
                 .unwrap()
                 .filter(|m| self_modules.contains(&m.self_id()))
                 .cloned()
-                .collect())
+                .collect();
+            let linked = published_dependencies(&modules);
+            let dependencies = package
+                .deps_compiled_units
+                .iter()
+                .filter_map(|(_, unit)| match &unit.unit {
+                    CompiledUnit::Module(NamedCompiledModule { module: m, .. })
+                        if linked.contains(&m.self_id()) =>
+                    {
+                        Some(m.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            Ok((modules, dependencies))
         }
     }
 }
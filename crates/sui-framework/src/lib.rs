@@ -14,7 +14,9 @@ pub mod cost_calib;
 pub mod natives;
 
 pub use sui_framework_build::build_move_stdlib_modules as get_move_stdlib_modules;
-pub use sui_framework_build::{build_move_package, verify_modules};
+pub use sui_framework_build::{
+    build_move_package, build_move_package_with_deps, published_dependencies, verify_modules,
+};
 use sui_types::sui_serde::{Base64, Encoding};
 
 // Move unit tests will halt after executing this many steps. This is a protection to avoid divergence
@@ -88,15 +90,19 @@ pub fn build_move_package_to_bytes(
     path: &Path,
     build_config: BuildConfig,
 ) -> Result<Vec<Vec<u8>>, SuiError> {
-    build_move_package(path, build_config).map(|mods| {
-        mods.iter()
-            .map(|m| {
-                let mut bytes = Vec::new();
-                m.serialize(&mut bytes).unwrap();
-                bytes
-            })
-            .collect::<Vec<_>>()
-    })
+    build_move_package(path, build_config).map(|mods| serialize_modules(&mods))
+}
+
+/// Serialize compiled modules to the format expected by a publish transaction.
+pub fn serialize_modules(modules: &[CompiledModule]) -> Vec<Vec<u8>> {
+    modules
+        .iter()
+        .map(|m| {
+            let mut bytes = Vec::new();
+            m.serialize(&mut bytes).unwrap();
+            bytes
+        })
+        .collect()
 }
 
 pub fn build_and_verify_package(
@@ -108,6 +114,17 @@ pub fn build_and_verify_package(
     Ok(modules)
 }
 
+/// Build and verify the package at `path` like [`build_and_verify_package`], also returning the
+/// modules of the dependencies it links against, as compiled locally.
+pub fn build_and_verify_package_with_deps(
+    path: &Path,
+    build_config: BuildConfig,
+) -> SuiResult<(Vec<CompiledModule>, Vec<CompiledModule>)> {
+    let (modules, dependencies) = build_move_package_with_deps(path, build_config)?;
+    verify_modules(&modules)?;
+    Ok((modules, dependencies))
+}
+
 pub fn run_move_unit_tests(
     path: &Path,
     build_config: BuildConfig,
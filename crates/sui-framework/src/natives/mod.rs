@@ -11,6 +11,11 @@ use move_binary_format::errors::PartialVMError;
 use move_core_types::{account_address::AccountAddress, identifier::Identifier};
 use move_vm_runtime::native_functions::{NativeFunction, NativeFunctionTable};
 use move_vm_types::values::{Struct, Value};
+use sui_types::protocol_config::ProtocolConfig;
+
+/// The natives added after the first version of the protocol, as their module, their name and
+/// the version they activate at.
+const NATIVE_VERSIONS: &[(&str, &str, u32)] = &[];
 
 pub fn all_natives(
     move_stdlib_addr: AccountAddress,
@@ -77,6 +82,24 @@ pub fn all_natives(
         .collect()
 }
 
+/// The natives of [`all_natives`] which are active at the protocol version of `config`.
+pub fn natives_for_protocol(
+    move_stdlib_addr: AccountAddress,
+    sui_framework_addr: AccountAddress,
+    config: &ProtocolConfig,
+) -> NativeFunctionTable {
+    all_natives(move_stdlib_addr, sui_framework_addr)
+        .into_iter()
+        .filter(|(_, module_name, func_name, _)| {
+            NATIVE_VERSIONS.iter().all(|(module, func, since)| {
+                module_name.as_str() != *module
+                    || func_name.as_str() != *func
+                    || *since <= config.version()
+            })
+        })
+        .collect()
+}
+
 // Object { info: Info { id: ID { bytes: address } } .. }
 // Extract the first field of the struct 3 times to get the id bytes.
 pub fn get_object_id(object: Value) -> Result<Value, PartialVMError> {
@@ -1,7 +1,9 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::failover_gateway_client::FailoverGatewayClient;
 use crate::rpc_gateway_client::RpcGatewayClient;
+use prometheus::Registry;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::{
@@ -13,13 +15,21 @@ use std::{
 use sui_config::Config;
 use sui_config::ValidatorInfo;
 use sui_core::{
+    authority_aggregator::{AuthAggMetrics, AuthorityAggregator},
     authority_client::NetworkAuthorityClient,
-    gateway_state::{GatewayClient, GatewayState},
+    gateway_state::{
+        default_transaction_retry_config, GatewayClient, GatewayMetrics, GatewayState,
+        InMemoryClientStore,
+    },
+    retry::RetryConfig,
 };
+use sui_network::pool::{ConnectionPool, PoolConfig};
+use sui_network::quic::{QuicConfig, QuicConnector};
 use sui_types::{
-    base_types::AuthorityName,
+    base_types::{AuthorityName, ChainId},
     committee::{Committee, EpochId},
     error::SuiResult,
+    handshake::Handshake,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -27,6 +37,8 @@ use sui_types::{
 pub enum GatewayType {
     Embedded(GatewayConfig),
     RPC(String),
+    /// Several RPC endpoints, failing over to the next one when an endpoint is unreachable.
+    Failover(FailoverConfig),
 }
 
 impl Display for GatewayType {
@@ -36,11 +48,14 @@ impl Display for GatewayType {
         match self {
             GatewayType::Embedded(config) => {
                 writeln!(writer, "Gateway Type : Embedded")?;
-                writeln!(
-                    writer,
-                    "Gateway state DB folder path : {:?}",
-                    config.db_folder_path
-                )?;
+                writeln!(writer, "Gateway state store : {:?}", config.store)?;
+                if config.store == GatewayStoreType::RocksDb {
+                    writeln!(
+                        writer,
+                        "Gateway state DB folder path : {:?}",
+                        config.db_folder_path
+                    )?;
+                }
                 let authorities = config
                     .validator_set
                     .iter()
@@ -55,6 +70,11 @@ impl Display for GatewayType {
                 writeln!(writer, "Gateway Type : JSON-RPC")?;
                 writeln!(writer, "Gateway URL : {}", url)?;
             }
+            GatewayType::Failover(config) => {
+                writeln!(writer, "Gateway Type : JSON-RPC with failover")?;
+                writeln!(writer, "Gateway URLs : {:?}", config.urls)?;
+                writeln!(writer, "Race reads : {}", config.race_reads)?;
+            }
         }
         write!(f, "{}", writer)
     }
@@ -64,22 +84,47 @@ impl GatewayType {
     pub fn init(&self) -> Result<GatewayClient, anyhow::Error> {
         Ok(match self {
             GatewayType::Embedded(config) => {
-                let path = config.db_folder_path.clone();
-                let committee = config.make_committee()?;
-                let authority_clients = config.make_authority_clients();
                 let registry = prometheus::Registry::new();
-                Arc::new(GatewayState::new(
-                    path,
-                    committee,
-                    authority_clients,
-                    &registry,
-                )?)
+                config.make_gateway(&registry)?
             }
             GatewayType::RPC(url) => Arc::new(RpcGatewayClient::new(url.clone())?),
+            GatewayType::Failover(config) => Arc::new(FailoverGatewayClient::new(config)?),
         })
     }
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct FailoverConfig {
+    /// The RPC endpoints, in order of preference.
+    pub urls: Vec<String>,
+    /// Send read requests to all healthy endpoints at once and use the first answer.
+    #[serde(default)]
+    pub race_reads: bool,
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: Duration,
+    #[serde(default = "default_health_check_interval")]
+    pub health_check_interval: Duration,
+}
+
+impl FailoverConfig {
+    pub fn new(urls: Vec<String>) -> Self {
+        Self {
+            urls,
+            race_reads: false,
+            request_timeout: default_request_timeout(),
+            health_check_interval: default_health_check_interval(),
+        }
+    }
+}
+
+fn default_request_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct GatewayConfig {
     pub epoch: EpochId,
@@ -88,11 +133,56 @@ pub struct GatewayConfig {
     pub recv_timeout: Duration,
     pub buffer_size: usize,
     pub db_folder_path: PathBuf,
+    #[serde(default)]
+    pub store: GatewayStoreType,
+    /// How failed requests to the validators and failed transactions are retried, each with
+    /// their own defaults if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_config: Option<RetryConfig>,
+    /// The chain of the validators, as given by their genesis. The gateway only talks to
+    /// validators of that chain and binds the transactions it builds to it.
+    pub chain_id: ChainId,
+}
+
+/// Where an embedded gateway keeps the state of the accounts it manages.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GatewayStoreType {
+    /// A RocksDB database in `db_folder_path`.
+    RocksDb,
+    /// Memory only, the state is synced again from the validators after a restart.
+    Memory,
+}
+
+impl Default for GatewayStoreType {
+    fn default() -> Self {
+        GatewayStoreType::RocksDb
+    }
 }
 
 impl Config for GatewayConfig {}
 
 impl GatewayConfig {
+    /// A gateway to the validators `validator_set` of the chain `chain_id`, keeping its state
+    /// in `db_folder_path`, with the default timeouts.
+    pub fn new(
+        validator_set: Vec<ValidatorInfo>,
+        chain_id: ChainId,
+        db_folder_path: PathBuf,
+    ) -> Self {
+        Self {
+            epoch: 0,
+            validator_set,
+            send_timeout: Duration::from_micros(4000000),
+            recv_timeout: Duration::from_micros(4000000),
+            buffer_size: 650000,
+            db_folder_path,
+            store: Default::default(),
+            retry_config: None,
+            chain_id,
+        }
+    }
+
     pub fn make_committee(&self) -> SuiResult<Committee> {
         Committee::new(
             self.epoch,
@@ -105,24 +195,60 @@ impl GatewayConfig {
         let mut config = mysten_network::config::Config::new();
         config.connect_timeout = Some(self.send_timeout);
         config.request_timeout = Some(self.recv_timeout);
+        let quic_connector = QuicConnector::ephemeral(QuicConfig::default());
+        let pool = Arc::new(
+            ConnectionPool::new(PoolConfig::default(), config, quic_connector)
+                .with_handshake(Handshake::new(self.chain_id)),
+        );
         for authority in &self.validator_set {
-            let channel = config.connect_lazy(authority.network_address()).unwrap();
-            let client = NetworkAuthorityClient::new(channel);
+            let client = NetworkAuthorityClient::pooled_with_network_key(
+                pool.clone(),
+                authority.network_address().clone(),
+                authority.public_key(),
+                authority.network_key(),
+            );
             authority_clients.insert(authority.public_key(), client);
         }
         authority_clients
     }
-}
 
-impl Default for GatewayConfig {
-    fn default() -> Self {
-        Self {
-            epoch: 0,
-            validator_set: vec![],
-            send_timeout: Duration::from_micros(4000000),
-            recv_timeout: Duration::from_micros(4000000),
-            buffer_size: 650000,
-            db_folder_path: Default::default(),
+    /// Create a gateway to the validators of this config, backed by the configured store.
+    pub fn make_gateway(&self, prometheus_registry: &Registry) -> SuiResult<GatewayClient> {
+        let committee = self.make_committee()?;
+        let authority_clients = self.make_authority_clients();
+        let mut authorities = AuthorityAggregator::new(
+            committee,
+            authority_clients,
+            AuthAggMetrics::new(prometheus_registry),
+        );
+        let metrics = GatewayMetrics::new(prometheus_registry);
+        let retry_config = self.retry_config.clone();
+        if let Some(retry_config) = &retry_config {
+            authorities = authorities.with_retry_config(retry_config.clone());
         }
+        let transaction_retry_config =
+            retry_config.unwrap_or_else(default_transaction_retry_config);
+        Ok(match self.store {
+            GatewayStoreType::RocksDb => {
+                let gateway = GatewayState::new_with_authorities(
+                    self.db_folder_path.clone(),
+                    authorities,
+                    metrics,
+                    self.chain_id,
+                )?
+                .with_retry_config(transaction_retry_config);
+                Arc::new(gateway)
+            }
+            GatewayStoreType::Memory => {
+                let gateway = GatewayState::new_with_store_and_authorities(
+                    Arc::new(InMemoryClientStore::new()),
+                    authorities,
+                    metrics,
+                    self.chain_id,
+                )?
+                .with_retry_config(transaction_retry_config);
+                Arc::new(gateway)
+            }
+        })
     }
 }
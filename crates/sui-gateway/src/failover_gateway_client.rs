@@ -0,0 +1,480 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use futures::future::select_ok;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tokio::time::error::Elapsed;
+use tokio::time::timeout;
+use tracing::{debug, warn};
+
+use sui_core::gateway_state::{GatewayAPI, GatewayTxSeqNumber};
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{
+    GetObjectDataResponse, GetRawObjectDataResponse, RPCTransactionRequestParams, SuiGasEstimate,
+    SuiMoveNormalizedModule, SuiObjectInfo, SuiPublishPreview, SuiRawMovePackage,
+    SuiTransactionPreview, SuiTypeTag, TransactionEffectsResponse, TransactionResponse,
+};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::messages::{Transaction, TransactionData};
+
+use crate::config::FailoverConfig;
+use crate::rpc_gateway_client::RpcGatewayClient;
+
+struct Endpoint {
+    url: String,
+    client: RpcGatewayClient,
+    healthy: AtomicBool,
+}
+
+/// A gateway client spreading requests over several RPC endpoints. Requests go to the last
+/// endpoint that answered, and move on to the next healthy endpoint when it fails or times out.
+/// Endpoints are health-checked in the background, and read requests can optionally be sent to
+/// all healthy endpoints at once, keeping the first answer.
+pub struct FailoverGatewayClient {
+    endpoints: Arc<Vec<Endpoint>>,
+    active: AtomicUsize,
+    request_timeout: Duration,
+    race_reads: bool,
+    health_check: Option<JoinHandle<()>>,
+}
+
+impl FailoverGatewayClient {
+    pub fn new(config: &FailoverConfig) -> Result<Self, Error> {
+        if config.urls.is_empty() {
+            return Err(anyhow!("At least one RPC endpoint must be configured"));
+        }
+        let endpoints = config
+            .urls
+            .iter()
+            .map(|url| {
+                Ok(Endpoint {
+                    url: url.clone(),
+                    client: RpcGatewayClient::new(url.clone())?,
+                    healthy: AtomicBool::new(true),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let endpoints = Arc::new(endpoints);
+
+        // The health check needs a runtime, without one unhealthy endpoints are only retried
+        // once all the healthy ones failed.
+        let health_check = if endpoints.len() > 1 {
+            Handle::try_current().ok().map(|handle| {
+                handle.spawn(health_check_loop(
+                    endpoints.clone(),
+                    config.health_check_interval,
+                    config.request_timeout,
+                ))
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+            request_timeout: config.request_timeout,
+            race_reads: config.race_reads,
+            health_check,
+        })
+    }
+
+    /// The url of the endpoint requests are currently sent to.
+    pub fn active_url(&self) -> &str {
+        &self.endpoints[self.active.load(Ordering::Relaxed)].url
+    }
+
+    /// Indices of the endpoints in the order they should be tried: the active endpoint, the
+    /// other healthy endpoints, then the unhealthy ones as a last resort.
+    fn candidates(&self) -> Vec<usize> {
+        let active = self.active.load(Ordering::Relaxed);
+        let count = self.endpoints.len();
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = (0..count)
+            .map(|i| (active + i) % count)
+            .partition(|i| self.endpoints[*i].healthy.load(Ordering::Relaxed));
+        healthy.into_iter().chain(unhealthy).collect()
+    }
+
+    fn record_failure(&self, index: usize, error: &Error) {
+        let endpoint = &self.endpoints[index];
+        warn!(url = %endpoint.url, "RPC endpoint failed, failing over: {}", error);
+        endpoint.healthy.store(false, Ordering::Relaxed);
+    }
+
+    fn record_success(&self, index: usize) {
+        self.endpoints[index].healthy.store(true, Ordering::Relaxed);
+        self.active.store(index, Ordering::Relaxed);
+    }
+
+    /// Send the request built by `f` to the endpoints in turn until one of them answers.
+    /// Errors returned by the server itself are not retried, they would fail the same way on
+    /// every endpoint.
+    async fn call<'a, T, F, Fut>(&'a self, f: F) -> Result<T, Error>
+    where
+        F: Fn(&'a RpcGatewayClient) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut last_error = None;
+        for index in self.candidates() {
+            let result = timeout(self.request_timeout, f(&self.endpoints[index].client))
+                .await
+                .unwrap_or_else(|e| Err(timeout_error(e, self.request_timeout)));
+            match result {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.record_failure(index, &e);
+                    last_error = Some(e);
+                }
+                result => {
+                    self.record_success(index);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No RPC endpoint available")))
+    }
+
+    /// Blocking version of [`Self::call`], for the synchronous methods of [`GatewayAPI`].
+    fn call_blocking<'a, T, F>(&'a self, f: F) -> Result<T, Error>
+    where
+        F: Fn(&'a RpcGatewayClient) -> Result<T, Error>,
+    {
+        let mut last_error = None;
+        for index in self.candidates() {
+            match f(&self.endpoints[index].client) {
+                Err(e) if is_endpoint_failure(&e) => {
+                    self.record_failure(index, &e);
+                    last_error = Some(e);
+                }
+                result => {
+                    self.record_success(index);
+                    return result;
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No RPC endpoint available")))
+    }
+
+    /// Send a read request built by `f`. With `race_reads` set, the request goes to all the
+    /// healthy endpoints at once and the first successful answer wins.
+    async fn read<'a, T, F, Fut>(&'a self, f: F) -> Result<T, Error>
+    where
+        F: Fn(&'a RpcGatewayClient) -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let healthy = self
+            .candidates()
+            .into_iter()
+            .filter(|i| self.endpoints[*i].healthy.load(Ordering::Relaxed))
+            .collect::<Vec<_>>();
+        if !self.race_reads || healthy.len() < 2 {
+            return self.call(f).await;
+        }
+
+        let f = &f;
+        let requests = healthy.into_iter().map(|index| {
+            Box::pin(async move {
+                let result = timeout(self.request_timeout, f(&self.endpoints[index].client))
+                    .await
+                    .unwrap_or_else(|e| Err(timeout_error(e, self.request_timeout)));
+                match result {
+                    Ok(result) => {
+                        self.record_success(index);
+                        Ok((index, result))
+                    }
+                    Err(e) => {
+                        if is_endpoint_failure(&e) {
+                            self.record_failure(index, &e);
+                        }
+                        Err(e)
+                    }
+                }
+            })
+        });
+        let ((index, result), _) = select_ok(requests).await?;
+        debug!(url = %self.endpoints[index].url, "Read request answered");
+        Ok(result)
+    }
+}
+
+impl Drop for FailoverGatewayClient {
+    fn drop(&mut self) {
+        if let Some(health_check) = &self.health_check {
+            health_check.abort();
+        }
+    }
+}
+
+/// Periodically probe every endpoint, so failed endpoints come back into rotation once they
+/// recover.
+async fn health_check_loop(
+    endpoints: Arc<Vec<Endpoint>>,
+    interval: Duration,
+    request_timeout: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        for endpoint in endpoints.iter() {
+            let healthy = matches!(
+                timeout(request_timeout, endpoint.client.health_check()).await,
+                Ok(Ok(_))
+            );
+            if healthy != endpoint.healthy.swap(healthy, Ordering::Relaxed) {
+                debug!(url = %endpoint.url, healthy, "RPC endpoint health changed");
+            }
+        }
+    }
+}
+
+fn timeout_error(elapsed: Elapsed, request_timeout: Duration) -> Error {
+    Error::new(elapsed).context(format!("RPC request timed out after {:?}", request_timeout))
+}
+
+/// Whether `error` means the endpoint could not be reached or did not answer properly, as
+/// opposed to an error returned by a working server, e.g. for an invalid request.
+fn is_endpoint_failure(error: &Error) -> bool {
+    match error.downcast_ref::<jsonrpsee::core::Error>() {
+        Some(jsonrpsee::core::Error::Call(_)) => false,
+        Some(_) => true,
+        // Errors raised by the client itself, e.g. decoding the transaction bytes, apart from
+        // our own timeouts.
+        None => error.is::<Elapsed>(),
+    }
+}
+
+#[async_trait]
+impl GatewayAPI for FailoverGatewayClient {
+    async fn execute_transaction(&self, tx: Transaction) -> Result<TransactionResponse, Error> {
+        // Executing a transaction twice is harmless, the authorities return the effects of the
+        // first execution.
+        self.call(|c| c.execute_transaction(tx.clone())).await
+    }
+
+    async fn public_transfer_object(
+        &self,
+        signer: SuiAddress,
+        object_id: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        recipient: SuiAddress,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| c.public_transfer_object(signer, object_id, gas, gas_budget, recipient))
+            .await
+    }
+
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| c.transfer_sui(signer, sui_object_id, gas_budget, recipient, amount))
+            .await
+    }
+
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| {
+            c.pay(
+                signer,
+                input_coins.clone(),
+                recipients.clone(),
+                amounts.clone(),
+                gas,
+                gas_budget,
+            )
+        })
+        .await
+    }
+
+    async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| c.pay_all(signer, input_coins.clone(), recipient, gas_budget))
+            .await
+    }
+
+    async fn sync_account_state(&self, account_addr: SuiAddress) -> Result<(), Error> {
+        self.call(|c| c.sync_account_state(account_addr)).await
+    }
+
+    async fn move_call(
+        &self,
+        signer: SuiAddress,
+        package_object_id: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<SuiTypeTag>,
+        arguments: Vec<SuiJsonValue>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| {
+            c.move_call(
+                signer,
+                package_object_id,
+                module.clone(),
+                function.clone(),
+                type_arguments.clone(),
+                arguments.clone(),
+                gas,
+                gas_budget,
+            )
+        })
+        .await
+    }
+
+    async fn publish(
+        &self,
+        signer: SuiAddress,
+        package_bytes: Vec<Vec<u8>>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| c.publish(signer, package_bytes.clone(), gas, gas_budget))
+            .await
+    }
+
+    async fn split_coin(
+        &self,
+        signer: SuiAddress,
+        coin_object_id: ObjectID,
+        split_amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| {
+            c.split_coin(
+                signer,
+                coin_object_id,
+                split_amounts.clone(),
+                gas,
+                gas_budget,
+            )
+        })
+        .await
+    }
+
+    async fn merge_coins(
+        &self,
+        signer: SuiAddress,
+        primary_coin: ObjectID,
+        coin_to_merge: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| c.merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget))
+            .await
+    }
+
+    async fn batch_transaction(
+        &self,
+        signer: SuiAddress,
+        single_transaction_params: Vec<RPCTransactionRequestParams>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        self.call(|c| {
+            c.batch_transaction(signer, single_transaction_params.clone(), gas, gas_budget)
+        })
+        .await
+    }
+
+    async fn estimate_gas(&self, tx_data: TransactionData) -> Result<SuiGasEstimate, Error> {
+        self.call(|c| c.estimate_gas(tx_data.clone())).await
+    }
+
+    async fn dry_run_transaction(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionPreview, Error> {
+        self.call(|c| c.dry_run_transaction(tx_data.clone())).await
+    }
+
+    async fn dry_run_publish(&self, tx_data: TransactionData) -> Result<SuiPublishPreview, Error> {
+        self.call(|c| c.dry_run_publish(tx_data.clone())).await
+    }
+
+    async fn get_object(&self, object_id: ObjectID) -> Result<GetObjectDataResponse, Error> {
+        self.read(|c| c.get_object(object_id)).await
+    }
+
+    async fn get_raw_object(&self, object_id: ObjectID) -> Result<GetRawObjectDataResponse, Error> {
+        self.read(|c| c.get_raw_object(object_id)).await
+    }
+
+    async fn get_raw_package(&self, package_id: ObjectID) -> Result<SuiRawMovePackage, Error> {
+        self.read(|c| c.get_raw_package(package_id)).await
+    }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, Error> {
+        self.read(|c| c.get_normalized_move_modules_by_package(package))
+            .await
+    }
+
+    async fn get_objects_owned_by_address(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Vec<SuiObjectInfo>, Error> {
+        self.read(|c| c.get_objects_owned_by_address(address)).await
+    }
+
+    async fn get_objects_owned_by_object(
+        &self,
+        object_id: ObjectID,
+    ) -> Result<Vec<SuiObjectInfo>, Error> {
+        self.read(|c| c.get_objects_owned_by_object(object_id))
+            .await
+    }
+
+    fn get_total_transaction_number(&self) -> Result<u64, Error> {
+        self.call_blocking(|c| c.get_total_transaction_number())
+    }
+
+    fn get_transactions_in_range(
+        &self,
+        start: GatewayTxSeqNumber,
+        end: GatewayTxSeqNumber,
+    ) -> Result<Vec<(GatewayTxSeqNumber, TransactionDigest)>, Error> {
+        self.call_blocking(|c| c.get_transactions_in_range(start, end))
+    }
+
+    fn get_recent_transactions(
+        &self,
+        count: u64,
+    ) -> Result<Vec<(GatewayTxSeqNumber, TransactionDigest)>, Error> {
+        self.call_blocking(|c| c.get_recent_transactions(count))
+    }
+
+    async fn get_transaction(
+        &self,
+        digest: TransactionDigest,
+    ) -> Result<TransactionEffectsResponse, Error> {
+        self.read(|c| c.get_transaction(digest)).await
+    }
+}
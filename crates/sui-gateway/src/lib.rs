@@ -4,12 +4,18 @@
 use crate::config::GatewayConfig;
 use anyhow::anyhow;
 use prometheus::Registry;
+use std::net::SocketAddr;
 use std::path::Path;
-use std::sync::Arc;
 use sui_config::PersistedConfig;
-use sui_core::gateway_state::{GatewayClient, GatewayState};
+use sui_core::gateway_state::GatewayClient;
+use sui_json_rpc::bcs_api::BcsApiImpl;
+use sui_json_rpc::gateway_api::{
+    GatewayReadApiImpl, GatewayWalletSyncApiImpl, RpcGatewayImpl, TransactionBuilderImpl,
+};
+use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
 
 pub mod config;
+pub mod failover_gateway_client;
 pub mod rpc_gateway_client;
 
 pub fn create_client(
@@ -23,12 +29,20 @@ pub fn create_client(
             e
         )
     })?;
-    let committee = config.make_committee()?;
-    let authority_clients = config.make_authority_clients();
-    Ok(Arc::new(GatewayState::new(
-        config.db_folder_path,
-        committee,
-        authority_clients,
-        prometheus_registry,
-    )?))
+    Ok(config.make_gateway(prometheus_registry)?)
+}
+
+/// Serve the JSON-RPC API of the gateway `client` on `address`, over HTTP.
+pub async fn start_rpc_gateway(
+    client: GatewayClient,
+    address: SocketAddr,
+    prometheus_registry: &Registry,
+) -> Result<ServerHandle, anyhow::Error> {
+    let mut server = JsonRpcServerBuilder::new(false, prometheus_registry)?;
+    server.register_module(RpcGatewayImpl::new(client.clone()))?;
+    server.register_module(GatewayReadApiImpl::new(client.clone()))?;
+    server.register_module(TransactionBuilderImpl::new(client.clone()))?;
+    server.register_module(BcsApiImpl::new_with_gateway(client.clone()))?;
+    server.register_module(GatewayWalletSyncApiImpl::new(client))?;
+    server.start(address).await
 }
@@ -3,16 +3,12 @@
 
 use clap::Parser;
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    net::{IpAddr, SocketAddr},
     path::PathBuf,
 };
 use sui_config::sui_config_dir;
 use sui_config::SUI_GATEWAY_CONFIG;
-use sui_gateway::create_client;
-use sui_json_rpc::bcs_api::BcsApiImpl;
-use sui_json_rpc::gateway_api::{GatewayReadApiImpl, TransactionBuilderImpl};
-use sui_json_rpc::gateway_api::{GatewayWalletSyncApiImpl, RpcGatewayImpl};
-use sui_json_rpc::JsonRpcServerBuilder;
+use sui_gateway::{create_client, start_rpc_gateway};
 use tracing::info;
 
 const DEFAULT_RPC_SERVER_PORT: &str = "5001";
@@ -37,7 +33,8 @@ struct RpcGatewayOpt {
     port: u16,
 
     #[clap(long, default_value = DEFAULT_RPC_SERVER_ADDR_IPV4)]
-    host: Ipv4Addr,
+    /// An IPv4 or IPv6 address, e.g. `::` to listen on both.
+    host: IpAddr,
 }
 
 #[tokio::main]
@@ -58,16 +55,8 @@ async fn main() -> anyhow::Result<()> {
 
     let client = create_client(&config_path, &prometheus_registry)?;
 
-    let address = SocketAddr::new(IpAddr::V4(options.host), options.port);
-    let mut server = JsonRpcServerBuilder::new(false, &prometheus_registry)?;
-    server.register_module(RpcGatewayImpl::new(client.clone()))?;
-    server.register_module(GatewayReadApiImpl::new(client.clone()))?;
-    server.register_module(TransactionBuilderImpl::new(client.clone()))?;
-    server.register_module(BcsApiImpl::new_with_gateway(client.clone()))?;
-    server.register_module(GatewayWalletSyncApiImpl::new(client))?;
-
-    let server_handle = server
-        .start(address)
+    let address = SocketAddr::new(options.host, options.port);
+    let server_handle = start_rpc_gateway(client, address, &prometheus_registry)
         .await?
         .into_http_server_handle()
         .expect("Expect a http server handle here");
@@ -1,6 +1,8 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use anyhow::Error;
 use async_trait::async_trait;
 
@@ -10,8 +12,10 @@ use tokio::runtime::Handle;
 use sui_core::gateway_state::{GatewayAPI, GatewayTxSeqNumber};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, GetRawObjectDataResponse, RPCTransactionRequestParams, SuiObjectInfo,
-    SuiTypeTag, TransactionBytes, TransactionEffectsResponse, TransactionResponse,
+    GetObjectDataResponse, GetRawObjectDataResponse, RPCTransactionRequestParams, SuiGasEstimate,
+    SuiMoveNormalizedModule, SuiObjectInfo, SuiPublishPreview, SuiRawMovePackage,
+    SuiTransactionPreview, SuiTypeTag, TransactionBytes, TransactionEffectsResponse,
+    TransactionResponse,
 };
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
 use sui_types::messages::{Transaction, TransactionData};
@@ -25,6 +29,12 @@ impl RpcGatewayClient {
             client: SuiClient::new_http_client(&server_url)?,
         })
     }
+
+    /// Check that the server answers requests.
+    pub async fn health_check(&self) -> Result<(), anyhow::Error> {
+        self.client.get_total_transaction_number().await?;
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -71,6 +81,36 @@ impl GatewayAPI for RpcGatewayClient {
         bytes.to_data()
     }
 
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        let bytes: TransactionBytes = self
+            .client
+            .pay(signer, input_coins, recipients, amounts, gas, gas_budget)
+            .await?;
+        bytes.to_data()
+    }
+
+    async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> Result<TransactionData, Error> {
+        let bytes: TransactionBytes = self
+            .client
+            .pay_all(signer, input_coins, recipient, gas_budget)
+            .await?;
+        bytes.to_data()
+    }
+
     async fn sync_account_state(&self, account_addr: SuiAddress) -> Result<(), Error> {
         self.client.sync_account_state(account_addr).await?;
         Ok(())
@@ -165,6 +205,24 @@ impl GatewayAPI for RpcGatewayClient {
         bytes.to_data()
     }
 
+    async fn estimate_gas(&self, tx_data: TransactionData) -> Result<SuiGasEstimate, Error> {
+        let tx_bytes = Base64::from_bytes(&tx_data.to_bytes());
+        Ok(self.client.estimate_gas(tx_bytes).await?)
+    }
+
+    async fn dry_run_transaction(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionPreview, Error> {
+        let tx_bytes = Base64::from_bytes(&tx_data.to_bytes());
+        Ok(self.client.dry_run_transaction(tx_bytes).await?)
+    }
+
+    async fn dry_run_publish(&self, tx_data: TransactionData) -> Result<SuiPublishPreview, Error> {
+        let tx_bytes = Base64::from_bytes(&tx_data.to_bytes());
+        Ok(self.client.dry_run_publish(tx_bytes).await?)
+    }
+
     async fn get_object(&self, object_id: ObjectID) -> Result<GetObjectDataResponse, Error> {
         Ok(self.client.get_object(object_id).await?)
     }
@@ -173,6 +231,20 @@ impl GatewayAPI for RpcGatewayClient {
         Ok(self.client.get_raw_object(object_id).await?)
     }
 
+    async fn get_raw_package(&self, package_id: ObjectID) -> Result<SuiRawMovePackage, Error> {
+        Ok(self.client.get_raw_package(package_id).await?)
+    }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, Error> {
+        Ok(self
+            .client
+            .get_normalized_move_modules_by_package(package)
+            .await?)
+    }
+
     async fn get_objects_owned_by_address(
         &self,
         address: SuiAddress,
@@ -4,8 +4,10 @@
 use move_package::BuildConfig;
 use std::{path::Path, str::FromStr};
 use sui_config::SUI_KEYSTORE_FILENAME;
-use sui_core::gateway_state::GatewayTxSeqNumber;
+use sui_core::gateway_state::{GatewayAPI, GatewayTxSeqNumber};
 use sui_framework::build_move_package_to_bytes;
+use sui_gateway::config::FailoverConfig;
+use sui_gateway::failover_gateway_client::FailoverGatewayClient;
 use sui_json::SuiJsonValue;
 use sui_json_rpc::api::{
     RpcGatewayApiClient, RpcReadApiClient, RpcTransactionBuilderClient, WalletSyncApiClient,
@@ -14,6 +16,8 @@ use sui_json_rpc_types::{
     GetObjectDataResponse, TransactionBytes, TransactionEffectsResponse, TransactionResponse,
 };
 use sui_sdk::crypto::{Keystore, SuiKeystore};
+use sui_sdk::transaction_builder::TransactionRequest;
+use sui_sdk::SuiClient;
 use sui_types::sui_serde::Base64;
 use sui_types::{
     base_types::{ObjectID, TransactionDigest},
@@ -34,6 +38,32 @@ async fn test_get_objects() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_failover_gateway_client() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let address = test_network.accounts.first().unwrap();
+    // Nothing listens on the first endpoint
+    let urls = vec![
+        "http://127.0.0.1:1".to_string(),
+        test_network.rpc_url.clone(),
+    ];
+
+    let client = FailoverGatewayClient::new(&FailoverConfig::new(urls.clone()))?;
+    client.sync_account_state(*address).await?;
+    assert_eq!(test_network.rpc_url, client.active_url());
+    let objects = client.get_objects_owned_by_address(*address).await?;
+    assert_eq!(5, objects.len());
+
+    let client = FailoverGatewayClient::new(&FailoverConfig {
+        race_reads: true,
+        ..FailoverConfig::new(urls)
+    })?;
+    let objects = client.get_objects_owned_by_address(*address).await?;
+    assert_eq!(5, objects.len());
+    assert_eq!(test_network.rpc_url, client.active_url());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_public_transfer_object() -> Result<(), anyhow::Error> {
     let test_network = start_rpc_test_network(None).await?;
@@ -176,6 +206,53 @@ async fn test_get_object_info() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_get_objects_batch() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let http_client = test_network.http_client;
+    let address = test_network.accounts.first().unwrap();
+    http_client.sync_account_state(*address).await?;
+    let objects = http_client.get_objects_owned_by_address(*address).await?;
+    let object_ids = objects.iter().map(|o| o.object_id).collect::<Vec<_>>();
+
+    let client = SuiClient::new_http_client(&test_network.rpc_url)?;
+    let results = client.get_objects(object_ids.clone()).await?;
+    assert_eq!(object_ids.len(), results.len());
+    for (object_id, result) in object_ids.iter().zip(results) {
+        assert_eq!(object_id, &result.into_object()?.id());
+    }
+    assert!(client.get_objects(vec![]).await?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sdk_transaction_builder() -> Result<(), anyhow::Error> {
+    let test_network = start_rpc_test_network(None).await?;
+    let address = test_network.accounts.first().unwrap();
+    let client = SuiClient::new_http_client(&test_network.rpc_url)?;
+    client.sync_account_state(*address).await?;
+
+    let objects = client.get_objects_owned_by_address(*address).await?;
+
+    let tx = client
+        .transaction_builder(*address)
+        .gas(objects.last().unwrap().object_id)
+        .build(TransactionRequest::TransferObject {
+            object_id: objects.first().unwrap().object_id,
+            recipient: *address,
+        })
+        .await?;
+    let keystore =
+        SuiKeystore::load_or_create(&test_network.network.dir().join(SUI_KEYSTORE_FILENAME))?;
+    let effects = client
+        .sign_and_execute(tx, &keystore)
+        .await?
+        .to_effect_response()?
+        .effects;
+    assert_eq!(2, effects.mutated.len());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_get_transaction() -> Result<(), anyhow::Error> {
     let test_network = start_rpc_test_network(None).await?;
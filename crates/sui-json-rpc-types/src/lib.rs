@@ -12,6 +12,11 @@ use std::fmt::{Display, Formatter};
 use colored::Colorize;
 use either::Either;
 use itertools::Itertools;
+use move_binary_format::file_format::{Ability, AbilitySet, StructTypeParameter, Visibility};
+use move_binary_format::normalized::{
+    Field as NormalizedField, Function as NormalizedFunction, Module as NormalizedModule,
+    Struct as NormalizedStruct, Type as NormalizedType,
+};
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{StructTag, TypeTag};
@@ -43,7 +48,10 @@ use sui_types::messages::{
 };
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use sui_types::move_package::disassemble_modules;
-use sui_types::object::{Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner};
+use sui_types::object::{
+    Data, MoveObject, Object, ObjectFormatOptions, ObjectRead, Owner, PastObjectRead,
+};
+use sui_types::object_change::{ObjectChange, ObjectChangeKind};
 use sui_types::sui_serde::{Base64, Encoding};
 
 #[cfg(test)]
@@ -52,6 +60,64 @@ mod rpc_types_tests;
 
 pub type GatewayTxSeqNumber = u64;
 
+/// A page of results from a cursor-based query. `next_cursor` is the inclusive start of the
+/// following page, and is `None` when there are no more results.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T, C> {
+    pub data: Vec<T>,
+    pub next_cursor: Option<C>,
+    pub has_more: bool,
+}
+
+impl<T, C> Page<T, C> {
+    /// Build a page from at most `limit + 1` items, the extra item (if any) only serves to
+    /// determine the `next_cursor`.
+    pub fn new(mut data: Vec<T>, limit: usize, cursor_of: impl Fn(&T) -> C) -> Self {
+        let next_cursor = if data.len() > limit {
+            let cursor = data.get(limit).map(&cursor_of);
+            data.truncate(limit);
+            cursor
+        } else {
+            None
+        };
+        Self {
+            data,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }
+}
+
+pub type ObjectsPage = Page<SuiObjectInfo, ObjectID>;
+pub type TransactionsPage = Page<(GatewayTxSeqNumber, TransactionDigest), GatewayTxSeqNumber>;
+pub type AddressTransactionsPage = Page<AddressTransaction, GatewayTxSeqNumber>;
+
+/// Which side of a transaction an address should be on for the transaction to be returned.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionDirection {
+    /// Transactions sent by the address
+    From,
+    /// Transactions that sent objects to the address
+    To,
+    /// Transactions either sent by or sending objects to the address
+    Any,
+}
+
+impl Default for TransactionDirection {
+    fn default() -> Self {
+        TransactionDirection::Any
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransaction {
+    pub sequence_number: GatewayTxSeqNumber,
+    pub digest: TransactionDigest,
+    pub timestamp_ms: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 pub struct TransactionEffectsResponse {
     pub certificate: SuiCertifiedTransaction,
@@ -410,6 +476,321 @@ impl SuiMoveObject for SuiRawMoveObject {
     }
 }
 
+/// The bytecode of the modules of a published package.
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
+#[serde(rename = "RawMovePackage", rename_all = "camelCase")]
+pub struct SuiRawMovePackage {
+    pub id: ObjectID,
+    /// The serialized modules, keyed by module name
+    #[serde_as(as = "BTreeMap<_, Base64>")]
+    #[schemars(with = "BTreeMap<String, Base64>")]
+    pub module_map: BTreeMap<String, Vec<u8>>,
+}
+
+impl TryFrom<ObjectRead> for SuiRawMovePackage {
+    type Error = anyhow::Error;
+
+    fn try_from(value: ObjectRead) -> Result<Self, Self::Error> {
+        let object = value.into_object()?;
+        match object.data {
+            Data::Package(package) => Ok(Self {
+                id: package.id(),
+                module_map: package.serialized_module_map().clone(),
+            }),
+            Data::Move(_) => Err(anyhow::anyhow!("Object {} is not a package", object.id())),
+        }
+    }
+}
+
+/// The state of an object as returned by the binary transport, which serves objects in their
+/// native BCS form instead of parsing their contents.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum BcsObjectRead {
+    Exists(Object),
+    NotExists(ObjectID),
+    Deleted(ObjectRef),
+}
+
+impl From<ObjectRead> for BcsObjectRead {
+    fn from(read: ObjectRead) -> Self {
+        match read {
+            ObjectRead::Exists(_, object, _) => Self::Exists(object),
+            ObjectRead::NotExists(id) => Self::NotExists(id),
+            ObjectRead::Deleted(oref) => Self::Deleted(oref),
+        }
+    }
+}
+
+/// The normalized form of a published module: its structs and the signatures of the functions
+/// that can be called from outside of it.
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveNormalizedModule", rename_all = "camelCase")]
+pub struct SuiMoveNormalizedModule {
+    pub file_format_version: u32,
+    pub address: String,
+    pub name: String,
+    pub friends: Vec<SuiMoveModuleId>,
+    pub structs: BTreeMap<String, SuiMoveNormalizedStruct>,
+    /// The public, friend and entry functions of the module
+    pub exposed_functions: BTreeMap<String, SuiMoveNormalizedFunction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveModuleId")]
+pub struct SuiMoveModuleId {
+    pub address: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveNormalizedStruct", rename_all = "camelCase")]
+pub struct SuiMoveNormalizedStruct {
+    pub abilities: SuiMoveAbilitySet,
+    pub type_parameters: Vec<SuiMoveStructTypeParameter>,
+    pub fields: Vec<SuiMoveNormalizedField>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveStructTypeParameter", rename_all = "camelCase")]
+pub struct SuiMoveStructTypeParameter {
+    pub constraints: SuiMoveAbilitySet,
+    pub is_phantom: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveNormalizedField")]
+pub struct SuiMoveNormalizedField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_: SuiMoveNormalizedType,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveNormalizedFunction", rename_all = "camelCase")]
+pub struct SuiMoveNormalizedFunction {
+    pub visibility: SuiMoveVisibility,
+    pub is_entry: bool,
+    /// The ability constraints of the type parameters of the function
+    pub type_parameters: Vec<SuiMoveAbilitySet>,
+    pub parameters: Vec<SuiMoveNormalizedType>,
+    #[serde(rename = "return")]
+    pub return_: Vec<SuiMoveNormalizedType>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename = "MoveVisibility")]
+pub enum SuiMoveVisibility {
+    Private,
+    Public,
+    Friend,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveAbilitySet")]
+pub struct SuiMoveAbilitySet {
+    pub abilities: Vec<SuiMoveAbility>,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename = "MoveAbility")]
+pub enum SuiMoveAbility {
+    Copy,
+    Drop,
+    Store,
+    Key,
+}
+
+#[derive(Serialize, Deserialize, Debug, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename = "MoveNormalizedType")]
+pub enum SuiMoveNormalizedType {
+    Bool,
+    U8,
+    U64,
+    U128,
+    Address,
+    Signer,
+    #[serde(rename_all = "camelCase")]
+    Struct {
+        address: String,
+        module: String,
+        name: String,
+        type_arguments: Vec<SuiMoveNormalizedType>,
+    },
+    Vector(Box<SuiMoveNormalizedType>),
+    /// The index of a type parameter of the enclosing function or struct
+    TypeParameter(u16),
+    Reference(Box<SuiMoveNormalizedType>),
+    MutableReference(Box<SuiMoveNormalizedType>),
+}
+
+impl From<&NormalizedModule> for SuiMoveNormalizedModule {
+    fn from(module: &NormalizedModule) -> Self {
+        Self {
+            file_format_version: module.file_format_version,
+            address: module.address.to_hex_literal(),
+            name: module.name.to_string(),
+            friends: module
+                .friends
+                .iter()
+                .map(|module_id| SuiMoveModuleId {
+                    address: module_id.address().to_hex_literal(),
+                    name: module_id.name().to_string(),
+                })
+                .collect(),
+            structs: module
+                .structs
+                .iter()
+                .map(|(name, normalized_struct)| (name.to_string(), normalized_struct.into()))
+                .collect(),
+            exposed_functions: module
+                .exposed_functions
+                .iter()
+                .map(|(name, function)| (name.to_string(), function.into()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&NormalizedStruct> for SuiMoveNormalizedStruct {
+    fn from(normalized_struct: &NormalizedStruct) -> Self {
+        Self {
+            abilities: normalized_struct.abilities.into(),
+            type_parameters: normalized_struct
+                .type_parameters
+                .iter()
+                .map(SuiMoveStructTypeParameter::from)
+                .collect(),
+            fields: normalized_struct
+                .fields
+                .iter()
+                .map(SuiMoveNormalizedField::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<&StructTypeParameter> for SuiMoveStructTypeParameter {
+    fn from(type_parameter: &StructTypeParameter) -> Self {
+        Self {
+            constraints: type_parameter.constraints.into(),
+            is_phantom: type_parameter.is_phantom,
+        }
+    }
+}
+
+impl From<&NormalizedField> for SuiMoveNormalizedField {
+    fn from(field: &NormalizedField) -> Self {
+        Self {
+            name: field.name.to_string(),
+            type_: (&field.type_).into(),
+        }
+    }
+}
+
+impl From<&NormalizedFunction> for SuiMoveNormalizedFunction {
+    fn from(function: &NormalizedFunction) -> Self {
+        Self {
+            visibility: function.visibility.into(),
+            is_entry: function.is_entry,
+            type_parameters: function
+                .type_parameters
+                .iter()
+                .map(|constraints| (*constraints).into())
+                .collect(),
+            parameters: function
+                .parameters
+                .iter()
+                .map(SuiMoveNormalizedType::from)
+                .collect(),
+            return_: function
+                .return_
+                .iter()
+                .map(SuiMoveNormalizedType::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<Visibility> for SuiMoveVisibility {
+    fn from(visibility: Visibility) -> Self {
+        match visibility {
+            Visibility::Private => SuiMoveVisibility::Private,
+            Visibility::Public => SuiMoveVisibility::Public,
+            Visibility::Friend => SuiMoveVisibility::Friend,
+        }
+    }
+}
+
+impl From<AbilitySet> for SuiMoveAbilitySet {
+    fn from(set: AbilitySet) -> Self {
+        Self {
+            abilities: set
+                .into_iter()
+                .map(|ability| match ability {
+                    Ability::Copy => SuiMoveAbility::Copy,
+                    Ability::Drop => SuiMoveAbility::Drop,
+                    Ability::Store => SuiMoveAbility::Store,
+                    Ability::Key => SuiMoveAbility::Key,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&NormalizedType> for SuiMoveNormalizedType {
+    fn from(type_: &NormalizedType) -> Self {
+        match type_ {
+            NormalizedType::Bool => SuiMoveNormalizedType::Bool,
+            NormalizedType::U8 => SuiMoveNormalizedType::U8,
+            NormalizedType::U64 => SuiMoveNormalizedType::U64,
+            NormalizedType::U128 => SuiMoveNormalizedType::U128,
+            NormalizedType::Address => SuiMoveNormalizedType::Address,
+            NormalizedType::Signer => SuiMoveNormalizedType::Signer,
+            NormalizedType::Struct {
+                address,
+                module,
+                name,
+                type_arguments,
+            } => SuiMoveNormalizedType::Struct {
+                address: address.to_hex_literal(),
+                module: module.to_string(),
+                name: name.to_string(),
+                type_arguments: type_arguments
+                    .iter()
+                    .map(SuiMoveNormalizedType::from)
+                    .collect(),
+            },
+            NormalizedType::Vector(inner) => {
+                SuiMoveNormalizedType::Vector(Box::new(inner.as_ref().into()))
+            }
+            NormalizedType::TypeParameter(index) => SuiMoveNormalizedType::TypeParameter(*index),
+            NormalizedType::Reference(inner) => {
+                SuiMoveNormalizedType::Reference(Box::new(inner.as_ref().into()))
+            }
+            NormalizedType::MutableReference(inner) => {
+                SuiMoveNormalizedType::MutableReference(Box::new(inner.as_ref().into()))
+            }
+        }
+    }
+}
+
+/// The normalized modules of the package read in `object_read`, keyed by module name.
+pub fn normalize_package(
+    object_read: ObjectRead,
+) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, anyhow::Error> {
+    let object = object_read.into_object()?;
+    match object.data {
+        Data::Package(package) => Ok(package
+            .normalize()?
+            .iter()
+            .map(|(name, module)| (name.clone(), module.into()))
+            .collect()),
+        Data::Move(_) => Err(anyhow::anyhow!("Object {} is not a package", object.id())),
+    }
+}
+
 impl TryFrom<&SuiParsedObject> for GasCoin {
     type Error = SuiError;
     fn try_from(object: &SuiParsedObject) -> Result<Self, Self::Error> {
@@ -516,6 +897,7 @@ impl Display for PublishResponse {
 
 pub type GetObjectDataResponse = SuiObjectRead<SuiParsedMoveObject>;
 pub type GetRawObjectDataResponse = SuiObjectRead<SuiRawMoveObject>;
+pub type GetPastObjectDataResponse = SuiPastObjectRead<SuiParsedMoveObject>;
 
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(tag = "status", content = "details", rename = "ObjectRead")]
@@ -565,6 +947,59 @@ impl<T: SuiMoveObject> TryFrom<ObjectRead> for SuiObjectRead<T> {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(tag = "status", content = "details", rename = "PastObjectRead")]
+pub enum SuiPastObjectRead<T: SuiMoveObject> {
+    /// The object at the version asked for, produced by its previous transaction
+    VersionFound(SuiObject<T>),
+    /// The object never existed
+    ObjectNotExists(ObjectID),
+    /// The object was deleted or wrapped at the version asked for, by this transaction
+    ObjectDeleted {
+        reference: SuiObjectRef,
+        transaction_digest: TransactionDigest,
+    },
+    /// The object never had the version asked for, or that version is no longer kept
+    VersionNotFound(ObjectID, SequenceNumber),
+    /// The object is not at the version asked for yet
+    VersionTooHigh {
+        object_id: ObjectID,
+        asked_version: SequenceNumber,
+        latest_version: SequenceNumber,
+    },
+}
+
+impl<T: SuiMoveObject> TryFrom<PastObjectRead> for SuiPastObjectRead<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PastObjectRead) -> Result<Self, Self::Error> {
+        Ok(match value {
+            PastObjectRead::ObjectNotExists(id) => SuiPastObjectRead::ObjectNotExists(id),
+            PastObjectRead::ObjectDeleted(oref, transaction_digest) => {
+                SuiPastObjectRead::ObjectDeleted {
+                    reference: oref.into(),
+                    transaction_digest,
+                }
+            }
+            PastObjectRead::VersionFound(_, o, layout) => {
+                SuiPastObjectRead::VersionFound(SuiObject::try_from(o, layout)?)
+            }
+            PastObjectRead::VersionNotFound(id, version) => {
+                SuiPastObjectRead::VersionNotFound(id, version)
+            }
+            PastObjectRead::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            } => SuiPastObjectRead::VersionTooHigh {
+                object_id,
+                asked_version,
+                latest_version,
+            },
+        })
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Eq, PartialEq)]
 #[serde(untagged, rename = "MoveValue")]
 pub enum SuiMoveValue {
@@ -832,6 +1267,13 @@ pub struct SuiMovePackage {
     disassembled: BTreeMap<String, Value>,
 }
 
+impl SuiMovePackage {
+    /// The disassembled modules of the package, keyed by module name.
+    pub fn disassembled(&self) -> &BTreeMap<String, Value> {
+        &self.disassembled
+    }
+}
+
 impl TryFrom<MoveModulePublish> for SuiMovePackage {
     type Error = anyhow::Error;
 
@@ -902,6 +1344,8 @@ pub enum SuiTransactionKind {
     TransferSui(SuiTransferSui),
     /// A system transaction that will update epoch information on-chain.
     ChangeEpoch(SuiChangeEpoch),
+    /// Pay several recipients at once with coins of any type
+    Pay(SuiPay),
     // .. more transaction types go here
 }
 
@@ -929,6 +1373,17 @@ impl Display for SuiTransactionKind {
                     writeln!(writer, "Amount: Full Balance")?;
                 }
             }
+            Self::Pay(p) => {
+                writeln!(writer, "Transaction Kind : Pay")?;
+                writeln!(writer, "Coins : ")?;
+                for coin in &p.coins {
+                    writeln!(writer, "{}", coin.object_id)?;
+                }
+                write!(writer, "Recipients : ")?;
+                for (recipient, amount) in p.recipients.iter().zip(&p.amounts) {
+                    write!(writer, "\n{}: {}", recipient, amount)?;
+                }
+            }
             Self::Publish(_p) => {
                 write!(writer, "Transaction Kind : Publish")?;
             }
@@ -968,6 +1423,11 @@ impl TryFrom<SingleTransactionKind> for SuiTransactionKind {
                 recipient: t.recipient,
                 amount: t.amount,
             }),
+            SingleTransactionKind::Pay(p) => Self::Pay(SuiPay {
+                coins: p.coins.into_iter().map(SuiObjectRef::from).collect(),
+                recipients: p.recipients,
+                amounts: p.amounts,
+            }),
             SingleTransactionKind::Publish(p) => Self::Publish(p.try_into()?),
             SingleTransactionKind::Call(c) => Self::Call(SuiMoveCall {
                 package: c.package.into(),
@@ -985,6 +1445,9 @@ impl TryFrom<SingleTransactionKind> for SuiTransactionKind {
                         CallArg::Object(ObjectArg::SharedObject(id)) => {
                             SuiJsonValue::new(Value::String(id.to_hex_literal()))
                         }
+                        CallArg::Object(ObjectArg::Result(result)) => {
+                            SuiJsonValue::new(Value::String(result.to_string()))
+                        }
                     })
                     .collect::<Result<Vec<_>, _>>()?,
             }),
@@ -1242,6 +1705,139 @@ impl From<GasCostSummary> for SuiGasCostSummary {
     }
 }
 
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "GasEstimate", rename_all = "camelCase")]
+pub struct SuiGasEstimate {
+    /// Gas charged by the dry run of the transaction
+    pub gas_used: SuiGasCostSummary,
+    /// Gas price the estimate is made at, the transaction gas price raised to the reference
+    /// gas price if it is lower
+    pub gas_price: u64,
+    /// Gas units consumed by the dry run
+    pub gas_units: u64,
+    /// Suggested gas budget, the consumed gas units plus a safety margin
+    pub recommended_budget: u64,
+}
+
+/// The outcome of a dry run of a transaction, to show what it does before it is signed.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "TransactionPreview", rename_all = "camelCase")]
+pub struct SuiTransactionPreview {
+    pub effects: SuiTransactionEffects,
+    /// The mutated objects whose owner changed, with their new owner
+    pub transferred: Vec<OwnedObjectRef>,
+    /// Coin balance changes of each address, including the gas payment
+    pub balance_changes: Vec<SuiBalanceChange>,
+    pub gas_estimate: SuiGasEstimate,
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "BalanceChange", rename_all = "camelCase")]
+pub struct SuiBalanceChange {
+    pub owner: SuiAddress,
+    pub coin_type: String,
+    /// Negative when the balance of the owner decreases
+    pub amount: i128,
+}
+
+impl Display for SuiTransactionPreview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "Status : {:?}", self.effects.status)?;
+        for (title, sign, objects) in [
+            ("Created", '+', &self.effects.created),
+            ("Unwrapped", '+', &self.effects.unwrapped),
+            ("Mutated", '~', &self.effects.mutated),
+            ("Transferred", '>', &self.transferred),
+        ] {
+            if !objects.is_empty() {
+                writeln!(writer, "{} Objects:", title)?;
+                for oref in objects {
+                    writeln!(
+                        writer,
+                        "  {} ID: {} , Owner: {}",
+                        sign, oref.reference.object_id, oref.owner
+                    )?;
+                }
+            }
+        }
+        for (title, objects) in [
+            ("Deleted", &self.effects.deleted),
+            ("Wrapped", &self.effects.wrapped),
+        ] {
+            if !objects.is_empty() {
+                writeln!(writer, "{} Objects:", title)?;
+                for oref in objects {
+                    writeln!(writer, "  - ID: {}", oref.object_id)?;
+                }
+            }
+        }
+        if !self.balance_changes.is_empty() {
+            writeln!(writer, "Balance Changes:")?;
+            for change in &self.balance_changes {
+                writeln!(
+                    writer,
+                    "  {:+} {} , Owner: {}",
+                    change.amount, change.coin_type, change.owner
+                )?;
+            }
+        }
+        write!(
+            writer,
+            "Estimated Gas : {} units, recommended budget {}",
+            self.gas_estimate.gas_units, self.gas_estimate.recommended_budget
+        )?;
+        write!(f, "{}", writer)
+    }
+}
+
+/// The outcome of a dry run of a package publication, the package is verified and its module
+/// initializers are run but nothing is committed.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "PublishPreview", rename_all = "camelCase")]
+pub struct SuiPublishPreview {
+    /// The reference the package would be published with
+    pub package: SuiObjectRef,
+    /// The objects the module initializers would create
+    pub created_objects: Vec<SuiObjectInfo>,
+    pub gas_estimate: SuiGasEstimate,
+}
+
+impl Display for SuiPublishPreview {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "{}", "----- Publish Preview ----".bold())?;
+        writeln!(
+            writer,
+            "{}",
+            format!(
+                "The package would be published with ID: {:?}\n",
+                self.package.object_id
+            )
+            .bold()
+        )?;
+        if !self.created_objects.is_empty() {
+            writeln!(
+                writer,
+                "List of objects the module initializers would create:"
+            )?;
+            for object in &self.created_objects {
+                writeln!(
+                    writer,
+                    "  + ID: {} , Type: {} , Owner: {}",
+                    object.object_id, object.type_, object.owner
+                )?;
+            }
+        }
+        write!(
+            writer,
+            "Estimated Gas : {} units, recommended budget {}",
+            self.gas_estimate.gas_units, self.gas_estimate.recommended_budget
+        )?;
+        write!(f, "{}", writer)
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "ObjectRef")]
 pub struct OwnedObjectRef {
@@ -1249,6 +1845,59 @@ pub struct OwnedObjectRef {
     pub reference: SuiObjectRef,
 }
 
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ObjectChangeKind")]
+pub enum SuiObjectChangeKind {
+    /// The object has a new version, with the same owner
+    Mutated,
+    /// The object has a new version and a new owner
+    Transferred,
+    /// The object was taken out of another object
+    Unwrapped,
+    /// The object was wrapped into another object
+    Wrapped,
+    /// The object was deleted
+    Deleted,
+}
+
+impl From<ObjectChangeKind> for SuiObjectChangeKind {
+    fn from(kind: ObjectChangeKind) -> Self {
+        match kind {
+            ObjectChangeKind::Mutated => Self::Mutated,
+            ObjectChangeKind::Transferred => Self::Transferred,
+            ObjectChangeKind::Unwrapped => Self::Unwrapped,
+            ObjectChangeKind::Wrapped => Self::Wrapped,
+            ObjectChangeKind::Deleted => Self::Deleted,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "ObjectChange", rename_all = "camelCase")]
+pub struct SuiObjectChange {
+    /// UTC timestamp in milliseconds since epoch (1/1/1970)
+    pub timestamp: u64,
+    /// Digest of the transaction that changed the object
+    pub tx_digest: TransactionDigest,
+    pub kind: SuiObjectChangeKind,
+    /// The new reference of the object, or its last reference if it was wrapped or deleted
+    pub reference: SuiObjectRef,
+    /// The new owner of the object, absent if it was wrapped or deleted
+    pub owner: Option<Owner>,
+}
+
+impl From<ObjectChange> for SuiObjectChange {
+    fn from(change: ObjectChange) -> Self {
+        Self {
+            timestamp: change.timestamp_ms,
+            tx_digest: change.tx_digest,
+            kind: change.kind.into(),
+            reference: change.object_ref.into(),
+            owner: change.owner,
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "EventEnvelope", rename_all = "camelCase")]
@@ -1414,6 +2063,14 @@ pub struct SuiTransferSui {
     pub amount: Option<u64>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename = "Pay", rename_all = "camelCase")]
+pub struct SuiPay {
+    pub coins: Vec<SuiObjectRef>,
+    pub recipients: Vec<SuiAddress>,
+    pub amounts: Vec<u64>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename = "InputObjectKind")]
 pub enum SuiInputObjectKind {
@@ -1497,6 +2154,7 @@ impl From<TypeTag> for SuiTypeTag {
 pub enum RPCTransactionRequestParams {
     TransferObjectRequestParams(TransferObjectParams),
     MoveCallRequestParams(MoveCallParams),
+    PublishRequestParams(PublishParams),
 }
 
 #[derive(Serialize, Deserialize, JsonSchema)]
@@ -1517,6 +2175,12 @@ pub struct MoveCallParams {
     pub arguments: Vec<SuiJsonValue>,
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishParams {
+    pub compiled_modules: Vec<Base64>,
+}
+
 #[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(rename = "EventFilter")]
 pub enum SuiEventFilter {
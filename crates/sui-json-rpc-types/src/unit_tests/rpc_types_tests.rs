@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
+use move_binary_format::normalized::Type as NormalizedType;
 use move_core_types::ident_str;
 use move_core_types::language_storage::StructTag;
 use move_core_types::value::{MoveStruct, MoveValue};
 
-use crate::{SuiMoveStruct, SuiMoveValue};
+use crate::{Page, SuiMoveNormalizedType, SuiMoveStruct, SuiMoveValue};
 use sui_types::base_types::SequenceNumber;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::gas_coin::GasCoin;
@@ -130,3 +131,44 @@ fn test_serde() {
         )
     }
 }
+
+#[test]
+fn test_normalized_type_to_sui_normalized_type() {
+    let coin = NormalizedType::Struct {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: ident_str!("coin").to_owned(),
+        name: ident_str!("Coin").to_owned(),
+        type_arguments: vec![NormalizedType::TypeParameter(0)],
+    };
+    let sui_type = SuiMoveNormalizedType::from(&NormalizedType::MutableReference(Box::new(coin)));
+
+    let json = serde_json::to_value(&sui_type).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "MutableReference": {
+                "Struct": {
+                    "address": "0x2",
+                    "module": "coin",
+                    "name": "Coin",
+                    "typeArguments": [{ "TypeParameter": 0 }]
+                }
+            }
+        })
+    );
+    let serde_type: SuiMoveNormalizedType = serde_json::from_value(json).unwrap();
+    assert_eq!(sui_type, serde_type);
+}
+
+#[test]
+fn test_page_next_cursor() {
+    let page = Page::new(vec![1u64, 2, 3, 4], 3, |i| *i);
+    assert_eq!(page.data, vec![1, 2, 3]);
+    assert_eq!(page.next_cursor, Some(4));
+    assert!(page.has_more);
+
+    let page = Page::new(vec![1u64, 2, 3], 3, |i| *i);
+    assert_eq!(page.data, vec![1, 2, 3]);
+    assert_eq!(page.next_cursor, None);
+    assert!(!page.has_more);
+}
@@ -1,16 +1,21 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_proc_macros::rpc;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    GatewayTxSeqNumber, GetObjectDataResponse, GetRawObjectDataResponse,
-    RPCTransactionRequestParams, SuiEventEnvelope, SuiEventFilter, SuiObjectInfo, SuiTypeTag,
-    TransactionBytes, TransactionEffectsResponse, TransactionResponse,
+    AddressTransactionsPage, GatewayTxSeqNumber, GetObjectDataResponse, GetPastObjectDataResponse,
+    GetRawObjectDataResponse, ObjectsPage, RPCTransactionRequestParams, SuiEventEnvelope,
+    SuiEventFilter, SuiGasEstimate, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct, SuiObjectChange, SuiObjectInfo, SuiPublishPreview, SuiRawMovePackage,
+    SuiTransactionPreview, SuiTypeTag, TransactionBytes, TransactionDirection,
+    TransactionEffectsResponse, TransactionResponse, TransactionsPage,
 };
 use sui_open_rpc_macros::open_rpc;
-use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::sui_serde::Base64;
 
 #[open_rpc(namespace = "sui", tag = "Gateway Transaction Execution API")]
@@ -27,6 +32,33 @@ pub trait RpcGatewayApi {
         /// signer's public key, as base-64 encoded string
         pub_key: Base64,
     ) -> RpcResult<TransactionResponse>;
+
+    /// Estimate the gas cost of the transaction by executing it on the gateway without submitting it.
+    #[method(name = "estimateGas")]
+    async fn estimate_gas(
+        &self,
+        /// transaction data bytes, as base-64 encoded string
+        tx_bytes: Base64,
+    ) -> RpcResult<SuiGasEstimate>;
+
+    /// Execute the transaction on the gateway without submitting it, and return its effects and
+    /// the coin balance changes it would cause.
+    #[method(name = "dryRunTransaction")]
+    async fn dry_run_transaction(
+        &self,
+        /// transaction data bytes, as base-64 encoded string
+        tx_bytes: Base64,
+    ) -> RpcResult<SuiTransactionPreview>;
+
+    /// Execute a package publication on the gateway without submitting it: the package is
+    /// verified and its module initializers are run. Return the ID the package would be
+    /// published with, the objects the initializers would create and the gas estimate.
+    #[method(name = "dryRunPublish")]
+    async fn dry_run_publish(
+        &self,
+        /// publish transaction data bytes, as base-64 encoded string
+        tx_bytes: Base64,
+    ) -> RpcResult<SuiPublishPreview>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Wallet Sync API")]
@@ -97,6 +129,48 @@ pub trait RpcReadApi {
         /// the ID of the queried object
         object_id: ObjectID,
     ) -> RpcResult<GetObjectDataResponse>;
+
+    /// Return the normalized form of the modules of a published package, keyed by module name.
+    #[method(name = "getNormalizedMoveModulesByPackage")]
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        /// the ID of the package
+        package: ObjectID,
+    ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>>;
+
+    /// Return the normalized form of a module of a published package.
+    #[method(name = "getNormalizedMoveModule")]
+    async fn get_normalized_move_module(
+        &self,
+        /// the ID of the package
+        package: ObjectID,
+        /// the name of the module
+        module_name: String,
+    ) -> RpcResult<SuiMoveNormalizedModule>;
+
+    /// Return the fields, abilities and type parameters of a struct of a published module.
+    #[method(name = "getNormalizedMoveStruct")]
+    async fn get_normalized_move_struct(
+        &self,
+        /// the ID of the package
+        package: ObjectID,
+        /// the name of the module
+        module_name: String,
+        /// the name of the struct
+        struct_name: String,
+    ) -> RpcResult<SuiMoveNormalizedStruct>;
+
+    /// Return the signature of a public, friend or entry function of a published module.
+    #[method(name = "getNormalizedMoveFunction")]
+    async fn get_normalized_move_function(
+        &self,
+        /// the ID of the package
+        package: ObjectID,
+        /// the name of the module
+        module_name: String,
+        /// the name of the function
+        function_name: String,
+    ) -> RpcResult<SuiMoveNormalizedFunction>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Full Node API")]
@@ -145,6 +219,103 @@ pub trait RpcFullNodeReadApi {
         /// the recipient's Sui address
         addr: SuiAddress,
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>>;
+
+    /// Return a page of objects owned by an address, ordered by object ID.
+    #[method(name = "getObjectsOwnedByAddressPage")]
+    async fn get_objects_owned_by_address_page(
+        &self,
+        /// the owner's Sui address
+        address: SuiAddress,
+        /// the object ID to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<ObjectID>,
+        /// maximum number of objects in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
+    /// Return a page of the objects of a Move struct type, ordered by object ID.
+    #[method(name = "getObjectsByType")]
+    async fn get_objects_by_type(
+        &self,
+        /// the type of the objects, e.g. `0x2::coin::Coin<0x2::sui::SUI>`
+        object_type: String,
+        /// the object ID to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<ObjectID>,
+        /// maximum number of objects in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
+    /// Return a page of the dynamic fields of an object, ordered by object ID. The dynamic fields
+    /// of an object are the objects it owns, e.g. the items of a `Bag`.
+    #[method(name = "getDynamicFields")]
+    async fn get_dynamic_fields(
+        &self,
+        /// the ID of the parent object
+        parent_object_id: ObjectID,
+        /// the object ID to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<ObjectID>,
+        /// maximum number of dynamic fields in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage>;
+
+    /// Return the dynamic field `name` of an object. Dynamic fields are named by the ID of the
+    /// child object.
+    #[method(name = "getDynamicFieldObject")]
+    async fn get_dynamic_field_object(
+        &self,
+        /// the ID of the parent object
+        parent_object_id: ObjectID,
+        /// the name of the dynamic field, i.e. the ID of the child object
+        name: ObjectID,
+    ) -> RpcResult<GetObjectDataResponse>;
+
+    /// Return the object information for a specified version, along with the transaction that
+    /// produced it. Versions that are no longer kept by the node are not found.
+    #[method(name = "tryGetPastObject")]
+    async fn try_get_past_object(
+        &self,
+        /// the ID of the queried object
+        object_id: ObjectID,
+        /// the version of the queried object
+        version: SequenceNumber,
+    ) -> RpcResult<GetPastObjectDataResponse>;
+
+    /// Return a page of transactions for a specified sender's Sui address, ordered by sequence number.
+    #[method(name = "getTransactionsFromAddressPage")]
+    async fn get_transactions_from_addr_page(
+        &self,
+        /// the sender's Sui address
+        addr: SuiAddress,
+        /// the sequence number to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<GatewayTxSeqNumber>,
+        /// maximum number of transactions in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionsPage>;
+
+    /// Return a page of transactions for a specified recipient's Sui address, ordered by sequence number.
+    #[method(name = "getTransactionsToAddressPage")]
+    async fn get_transactions_to_addr_page(
+        &self,
+        /// the recipient's Sui address
+        addr: SuiAddress,
+        /// the sequence number to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<GatewayTxSeqNumber>,
+        /// maximum number of transactions in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionsPage>;
+
+    /// Return a page of transactions sent and/or received by a Sui address, ordered by sequence number.
+    #[method(name = "getTransactionsForAddress")]
+    async fn get_transactions_for_address(
+        &self,
+        /// the Sui address
+        addr: SuiAddress,
+        /// the sequence number to start the page from (inclusive), the first page is returned if not provided
+        cursor: Option<GatewayTxSeqNumber>,
+        /// maximum number of transactions in the page, defaults to 50, capped at 1000
+        limit: Option<usize>,
+        /// whether to return transactions sent by the address, sending objects to the address, or both; defaults to both
+        direction: Option<TransactionDirection>,
+    ) -> RpcResult<AddressTransactionsPage>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Transaction Builder API")]
@@ -181,6 +352,38 @@ pub trait RpcTransactionBuilder {
         amount: Option<u64>,
     ) -> RpcResult<TransactionBytes>;
 
+    /// Create an unsigned transaction to pay several recipients at once out of coins of the same type.
+    #[method(name = "pay")]
+    async fn pay(
+        &self,
+        /// the transaction signer's Sui address
+        signer: SuiAddress,
+        /// the coins to pay with, all of the same type; they are merged into the first one
+        input_coins: Vec<ObjectID>,
+        /// the recipients' Sui addresses
+        recipients: Vec<SuiAddress>,
+        /// the amount paid to each recipient, in the same order as the recipients
+        amounts: Vec<u64>,
+        /// gas object to be used in this transaction, the gateway will pick one from the signer's possession if not provided
+        gas: Option<ObjectID>,
+        /// the gas budget, the transaction will fail if the gas cost exceed the budget
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes>;
+
+    /// Create an unsigned transaction to send all the SUI of the input coins to a Sui address. The first coin is used as the gas object, the recipient receives what is left of it once the gas is charged.
+    #[method(name = "payAll")]
+    async fn pay_all(
+        &self,
+        /// the transaction signer's Sui address
+        signer: SuiAddress,
+        /// the SUI coins to send, the first one pays for the gas
+        input_coins: Vec<ObjectID>,
+        /// the recipient's Sui address
+        recipient: SuiAddress,
+        /// the gas budget, the transaction will fail if the gas cost exceed the budget
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes>;
+
     /// Create an unsigned transaction to execute a Move call on the network, by calling the specified function in the module of a given package.
     #[method(name = "moveCall")]
     async fn move_call(
@@ -274,6 +477,14 @@ pub trait RpcBcsApi {
         /// the id of the object
         object_id: ObjectID,
     ) -> RpcResult<GetRawObjectDataResponse>;
+
+    /// Return the bytecode of the modules of a published package.
+    #[method(name = "getRawPackage")]
+    async fn get_raw_package(
+        &self,
+        /// the id of the package
+        package_id: ObjectID,
+    ) -> RpcResult<SuiRawMovePackage>;
 }
 
 #[open_rpc(namespace = "sui", tag = "Event Subscription")]
@@ -286,6 +497,15 @@ pub trait EventStreamingApi {
         /// the filter criteria of the event stream, see the [Sui docs](https://docs.sui.io/build/pubsub#event-filters) for detailed examples.
         filter: SuiEventFilter,
     );
+
+    /// Subscribe to the changes of a set of objects: new versions, transfers to a new owner,
+    /// wrapping and deletion
+    #[subscription(name = "subscribeObjectChanges", item = SuiObjectChange)]
+    fn subscribe_object_changes(
+        &self,
+        /// the IDs of the objects to watch
+        object_ids: Vec<ObjectID>,
+    );
 }
 
 #[open_rpc(namespace = "sui", tag = "Event Read API")]
@@ -11,7 +11,7 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::RpcModule;
 use sui_core::authority::AuthorityState;
 use sui_core::gateway_state::GatewayClient;
-use sui_json_rpc_types::GetRawObjectDataResponse;
+use sui_json_rpc_types::{GetRawObjectDataResponse, SuiRawMovePackage};
 use sui_open_rpc::Module;
 use sui_types::base_types::ObjectID;
 
@@ -52,6 +52,20 @@ impl ClientStateAdaptor {
                 .try_into(),
         }
     }
+
+    async fn get_raw_package(
+        &self,
+        package_id: ObjectID,
+    ) -> Result<SuiRawMovePackage, anyhow::Error> {
+        match self {
+            ClientStateAdaptor::Gateway(client) => client.get_raw_package(package_id).await,
+            ClientStateAdaptor::FullNode(client) => client
+                .get_object_read(&package_id)
+                .await
+                .map_err(|e| anyhow!("{e}"))?
+                .try_into(),
+        }
+    }
 }
 
 #[async_trait]
@@ -59,6 +73,10 @@ impl RpcBcsApiServer for BcsApiImpl {
     async fn get_raw_object(&self, object_id: ObjectID) -> RpcResult<GetRawObjectDataResponse> {
         Ok(self.client.get_raw_object(object_id).await?)
     }
+
+    async fn get_raw_package(&self, package_id: ObjectID) -> RpcResult<SuiRawMovePackage> {
+        Ok(self.client.get_raw_package(package_id).await?)
+    }
 }
 
 impl SuiRpcModule for BcsApiImpl {
@@ -3,6 +3,7 @@
 use crate::api::EventReadApiServer;
 use crate::api::EventStreamingApiServer;
 use crate::SuiRpcModule;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use futures::{StreamExt, TryStream};
 use jsonrpsee::core::RpcResult;
@@ -15,9 +16,11 @@ use std::fmt::Display;
 use std::sync::Arc;
 use sui_core::authority::AuthorityState;
 use sui_core::event_handler::EventHandler;
-use sui_json_rpc_types::{SuiEvent, SuiEventEnvelope, SuiEventFilter};
+use sui_json_rpc_types::{SuiEvent, SuiEventEnvelope, SuiEventFilter, SuiObjectChange};
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::error::SuiError;
+use sui_types::object_change::ObjectChangeFilter;
 use tracing::warn;
 
 pub struct EventStreamingApiImpl {
@@ -64,6 +67,26 @@ impl EventStreamingApiServer for EventStreamingApiImpl {
 
         Ok(())
     }
+
+    fn subscribe_object_changes(
+        &self,
+        mut sink: SubscriptionSink,
+        object_ids: Vec<ObjectID>,
+    ) -> SubscriptionResult {
+        if object_ids.is_empty() {
+            let e = jsonrpsee_core::Error::from(anyhow!("No object to subscribe to"));
+            warn!(error = ?e, "Rejecting subscription request.");
+            return Ok(sink.reject(e)?);
+        }
+
+        let stream = self
+            .event_handler
+            .subscribe_object_changes(ObjectChangeFilter::new(object_ids))
+            .map(|change| Ok::<_, SuiError>(SuiObjectChange::from(change)));
+        spawn_subscript(sink, stream);
+
+        Ok(())
+    }
 }
 
 fn spawn_subscript<S, T, E>(mut sink: SubscriptionSink, rx: S)
@@ -6,17 +6,21 @@ use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_core::server::rpc_module::RpcModule;
 use signature::Signature;
+use std::collections::BTreeMap;
 use tracing::debug;
 
 use crate::api::{
     RpcGatewayApiServer, RpcReadApiServer, RpcTransactionBuilderServer, WalletSyncApiServer,
 };
+use crate::read_api::{normalized_function, normalized_module, normalized_struct};
 use crate::SuiRpcModule;
 use sui_core::gateway_state::{GatewayClient, GatewayTxSeqNumber};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, RPCTransactionRequestParams, SuiObjectInfo, SuiTypeTag,
-    TransactionBytes, TransactionEffectsResponse, TransactionResponse,
+    GetObjectDataResponse, RPCTransactionRequestParams, SuiGasEstimate, SuiMoveNormalizedFunction,
+    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo, SuiPublishPreview,
+    SuiTransactionPreview, SuiTypeTag, TransactionBytes, TransactionEffectsResponse,
+    TransactionResponse,
 };
 use sui_open_rpc::Module;
 use sui_types::sui_serde::Base64;
@@ -84,6 +88,21 @@ impl RpcGatewayApiServer for RpcGatewayImpl {
             .await;
         Ok(result?)
     }
+
+    async fn estimate_gas(&self, tx_bytes: Base64) -> RpcResult<SuiGasEstimate> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        Ok(self.client.estimate_gas(data).await?)
+    }
+
+    async fn dry_run_transaction(&self, tx_bytes: Base64) -> RpcResult<SuiTransactionPreview> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        Ok(self.client.dry_run_transaction(data).await?)
+    }
+
+    async fn dry_run_publish(&self, tx_bytes: Base64) -> RpcResult<SuiPublishPreview> {
+        let data = TransactionData::from_signable_bytes(&tx_bytes.to_vec()?)?;
+        Ok(self.client.dry_run_publish(data).await?)
+    }
 }
 
 impl SuiRpcModule for RpcGatewayImpl {
@@ -162,6 +181,49 @@ impl RpcReadApiServer for GatewayReadApiImpl {
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>> {
         Ok(self.client.get_transactions_in_range(start, end)?)
     }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
+        Ok(self
+            .client
+            .get_normalized_move_modules_by_package(package)
+            .await?)
+    }
+
+    async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> RpcResult<SuiMoveNormalizedModule> {
+        let modules = self.get_normalized_move_modules_by_package(package).await?;
+        Ok(normalized_module(modules, &module_name)?)
+    }
+
+    async fn get_normalized_move_struct(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        struct_name: String,
+    ) -> RpcResult<SuiMoveNormalizedStruct> {
+        let module = self
+            .get_normalized_move_module(package, module_name)
+            .await?;
+        Ok(normalized_struct(module, &struct_name)?)
+    }
+
+    async fn get_normalized_move_function(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        function_name: String,
+    ) -> RpcResult<SuiMoveNormalizedFunction> {
+        let module = self
+            .get_normalized_move_module(package, module_name)
+            .await?;
+        Ok(normalized_function(module, &function_name)?)
+    }
 }
 
 impl SuiRpcModule for GatewayReadApiImpl {
@@ -206,6 +268,36 @@ impl RpcTransactionBuilderServer for TransactionBuilderImpl {
         Ok(TransactionBytes::from_data(data)?)
     }
 
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .client
+            .pay(signer, input_coins, recipients, amounts, gas, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
+    async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> RpcResult<TransactionBytes> {
+        let data = self
+            .client
+            .pay_all(signer, input_coins, recipient, gas_budget)
+            .await?;
+        Ok(TransactionBytes::from_data(data)?)
+    }
+
     async fn publish(
         &self,
         sender: SuiAddress,
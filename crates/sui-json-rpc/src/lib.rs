@@ -23,6 +23,9 @@ pub mod event_api;
 pub mod gateway_api;
 pub mod read_api;
 
+/// Maximum number of active subscriptions a single websocket connection can hold.
+pub const MAX_SUBSCRIPTIONS_PER_CONNECTION: u32 = 100;
+
 pub enum ServerBuilder<M = ()> {
     HttpBuilder(HttpServerBuilder<M>),
     WsBuilder(WsServerBuilder<M>),
@@ -96,6 +99,7 @@ impl JsonRpcServerBuilder {
             ServerBuilder::WsBuilder(
                 WsServerBuilder::default()
                     .set_access_control(acl)
+                    .max_subscriptions_per_connection(MAX_SUBSCRIPTIONS_PER_CONNECTION)
                     .set_middleware(ApiMetrics::WebsocketMetrics(WebsocketMetrics {})),
             )
         } else {
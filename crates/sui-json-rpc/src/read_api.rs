@@ -8,15 +8,25 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee_core::server::rpc_module::RpcModule;
+use move_core_types::parser::parse_struct_tag;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use sui_core::authority::AuthorityState;
 use sui_core::gateway_state::GatewayTxSeqNumber;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, SuiObjectInfo, SuiTransactionEffects, TransactionEffectsResponse,
+    normalize_package, AddressTransaction, AddressTransactionsPage, GetObjectDataResponse,
+    GetPastObjectDataResponse, ObjectsPage, Page, SuiMoveNormalizedFunction,
+    SuiMoveNormalizedModule, SuiMoveNormalizedStruct, SuiObjectInfo, SuiTransactionEffects,
+    TransactionDirection, TransactionEffectsResponse, TransactionsPage,
 };
 use sui_open_rpc::Module;
-use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
-use sui_types::object::Owner;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest};
+use sui_types::object::{ObjectRead, Owner};
+
+/// Page size used by the paginated queries when the caller does not specify a limit.
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+/// Largest page size the paginated queries will return.
+pub const MAX_PAGE_SIZE: usize = 1000;
 
 // An implementation of the read portion of the Gateway JSON-RPC interface intended for use in
 // Fullnodes.
@@ -107,6 +117,88 @@ impl RpcReadApiServer for ReadApi {
             timestamp_ms: self.state.get_timestamp_ms(&digest).await?,
         })
     }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> RpcResult<BTreeMap<String, SuiMoveNormalizedModule>> {
+        Ok(normalize_package(
+            self.state
+                .get_object_read(&package)
+                .await
+                .map_err(|e| anyhow!("{e}"))?,
+        )?)
+    }
+
+    async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> RpcResult<SuiMoveNormalizedModule> {
+        let modules = self.get_normalized_move_modules_by_package(package).await?;
+        Ok(normalized_module(modules, &module_name)?)
+    }
+
+    async fn get_normalized_move_struct(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        struct_name: String,
+    ) -> RpcResult<SuiMoveNormalizedStruct> {
+        let module = self
+            .get_normalized_move_module(package, module_name)
+            .await?;
+        Ok(normalized_struct(module, &struct_name)?)
+    }
+
+    async fn get_normalized_move_function(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        function_name: String,
+    ) -> RpcResult<SuiMoveNormalizedFunction> {
+        let module = self
+            .get_normalized_move_module(package, module_name)
+            .await?;
+        Ok(normalized_function(module, &function_name)?)
+    }
+}
+
+pub(crate) fn normalized_module(
+    mut modules: BTreeMap<String, SuiMoveNormalizedModule>,
+    module_name: &str,
+) -> Result<SuiMoveNormalizedModule, anyhow::Error> {
+    modules
+        .remove(module_name)
+        .ok_or_else(|| anyhow!("No module found with module name {module_name}"))
+}
+
+pub(crate) fn normalized_struct(
+    mut module: SuiMoveNormalizedModule,
+    struct_name: &str,
+) -> Result<SuiMoveNormalizedStruct, anyhow::Error> {
+    module.structs.remove(struct_name).ok_or_else(|| {
+        anyhow!(
+            "No struct found with struct name {struct_name} in module {}",
+            module.name
+        )
+    })
+}
+
+pub(crate) fn normalized_function(
+    mut module: SuiMoveNormalizedModule,
+    function_name: &str,
+) -> Result<SuiMoveNormalizedFunction, anyhow::Error> {
+    module
+        .exposed_functions
+        .remove(function_name)
+        .ok_or_else(|| {
+            anyhow!(
+                "No public, friend or entry function found with function name {function_name} in \
+             module {}",
+                module.name
+            )
+        })
 }
 
 impl SuiRpcModule for ReadApi {
@@ -163,6 +255,168 @@ impl RpcFullNodeReadApiServer for FullNodeApi {
     ) -> RpcResult<Vec<(GatewayTxSeqNumber, TransactionDigest)>> {
         Ok(self.state.get_transactions_to_addr(addr).await?)
     }
+
+    async fn get_objects_owned_by_address_page(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let limit = page_limit(limit)?;
+        let objects = self
+            .state
+            .get_owner_objects_page(Owner::AddressOwner(address), cursor, limit + 1)
+            .map_err(|e| anyhow!("{e}"))?
+            .into_iter()
+            .map(SuiObjectInfo::from)
+            .collect();
+        Ok(Page::new(objects, limit, |info| info.object_id))
+    }
+
+    async fn get_objects_by_type(
+        &self,
+        object_type: String,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let limit = page_limit(limit)?;
+        let object_type = parse_struct_tag(&object_type)?;
+        let objects = self
+            .state
+            .get_objects_by_type_page(&object_type, cursor, limit + 1)
+            .map_err(|e| anyhow!("{e}"))?
+            .into_iter()
+            .map(SuiObjectInfo::from)
+            .collect();
+        Ok(Page::new(objects, limit, |info| info.object_id))
+    }
+
+    async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        let limit = page_limit(limit)?;
+        let objects = self
+            .state
+            .get_owner_objects_page(
+                Owner::ObjectOwner(parent_object_id.into()),
+                cursor,
+                limit + 1,
+            )
+            .map_err(|e| anyhow!("{e}"))?
+            .into_iter()
+            .map(SuiObjectInfo::from)
+            .collect();
+        Ok(Page::new(objects, limit, |info| info.object_id))
+    }
+
+    async fn get_dynamic_field_object(
+        &self,
+        parent_object_id: ObjectID,
+        name: ObjectID,
+    ) -> RpcResult<GetObjectDataResponse> {
+        let object_read = self
+            .state
+            .get_object_read(&name)
+            .await
+            .map_err(|e| anyhow!("{e}"))?;
+        if let ObjectRead::Exists(_, object, _) = &object_read {
+            if object.owner != Owner::ObjectOwner(parent_object_id.into()) {
+                return Err(anyhow!(
+                    "Object {name} is not a dynamic field of object {parent_object_id}"
+                )
+                .into());
+            }
+        }
+        Ok(object_read.try_into()?)
+    }
+
+    async fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> RpcResult<GetPastObjectDataResponse> {
+        Ok(self
+            .state
+            .get_past_object_read(&object_id, version)
+            .await
+            .map_err(|e| anyhow!("{e}"))?
+            .try_into()?)
+    }
+
+    async fn get_transactions_from_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionsPage> {
+        let limit = page_limit(limit)?;
+        let txs = self
+            .state
+            .get_transactions_from_addr_page(addr, cursor, limit + 1)
+            .await?;
+        Ok(Page::new(txs, limit, |(seq, _)| *seq))
+    }
+
+    async fn get_transactions_to_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+    ) -> RpcResult<TransactionsPage> {
+        let limit = page_limit(limit)?;
+        let txs = self
+            .state
+            .get_transactions_to_addr_page(addr, cursor, limit + 1)
+            .await?;
+        Ok(Page::new(txs, limit, |(seq, _)| *seq))
+    }
+
+    async fn get_transactions_for_address(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+        direction: Option<TransactionDirection>,
+    ) -> RpcResult<AddressTransactionsPage> {
+        let limit = page_limit(limit)?;
+        let txs = match direction.unwrap_or_default() {
+            TransactionDirection::From => {
+                self.state
+                    .get_transactions_from_addr_page(addr, cursor, limit + 1)
+                    .await?
+            }
+            TransactionDirection::To => {
+                self.state
+                    .get_transactions_to_addr_page(addr, cursor, limit + 1)
+                    .await?
+            }
+            TransactionDirection::Any => {
+                self.state
+                    .get_transactions_for_addr_page(addr, cursor, limit + 1)
+                    .await?
+            }
+        };
+        let mut data = Vec::with_capacity(txs.len());
+        for (sequence_number, digest) in txs {
+            data.push(AddressTransaction {
+                sequence_number,
+                digest,
+                timestamp_ms: self.state.get_timestamp_ms(&digest).await?,
+            });
+        }
+        Ok(Page::new(data, limit, |tx| tx.sequence_number))
+    }
+}
+
+pub fn page_limit(limit: Option<usize>) -> Result<usize, anyhow::Error> {
+    match limit {
+        Some(0) => Err(anyhow!("Page limit must be greater than 0")),
+        Some(limit) => Ok(limit.min(MAX_PAGE_SIZE)),
+        None => Ok(DEFAULT_PAGE_SIZE),
+    }
 }
 
 impl SuiRpcModule for FullNodeApi {
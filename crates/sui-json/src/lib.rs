@@ -16,6 +16,7 @@ use serde_json::{json, Number, Value as JsonValue};
 use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
 use sui_types::base_types::{decode_bytes_hex, ObjectID, SuiAddress};
+use sui_types::messages::CommandResult;
 use sui_types::move_package::MovePackage;
 use sui_verifier::entry_points_verifier::is_tx_context;
 
@@ -28,6 +29,8 @@ mod tests;
 pub enum SuiJsonCallArg {
     // Needs to become an Object Ref or Object ID, depending on object type
     Object(ObjectID),
+    // an object created by an earlier transaction of the same batch
+    Result(CommandResult),
     // pure value, bcs encoded
     Pure(Vec<u8>),
 }
@@ -78,10 +81,14 @@ impl SuiJsonValue {
 
             // In constructor, we have already checked that the JSON number is unsigned int of at most U64
             // Hence it is okay to unwrap() numbers
-            (JsonValue::Number(n), MoveTypeLayout::U8) => {
-                MoveValue::U8(u8::try_from(n.as_u64().unwrap())?)
-            }
+            (JsonValue::Number(n), MoveTypeLayout::U8) => MoveValue::U8(
+                u8::try_from(n.as_u64().unwrap())
+                    .map_err(|_| anyhow!("{n} is out of range for u8"))?,
+            ),
             (JsonValue::Number(n), MoveTypeLayout::U64) => MoveValue::U64(n.as_u64().unwrap()),
+            (JsonValue::Number(n), MoveTypeLayout::U128) => {
+                MoveValue::U128(n.as_u64().unwrap() as u128)
+            }
 
             // u8, u64, u128 can be encoded as String
             (JsonValue::String(s), MoveTypeLayout::U8) => {
@@ -242,6 +249,7 @@ fn is_homogeneous_rec(curr_q: &mut VecDeque<&JsonValue>) -> bool {
 }
 
 fn resolve_primtive_arg(
+    idx: usize,
     arg: &SuiJsonValue,
     param: &SignatureToken,
 ) -> Result<Vec<u8>, anyhow::Error> {
@@ -250,7 +258,9 @@ fn resolve_primtive_arg(
     // Then return the serialized bcs value
     arg.to_bcs_bytes(&move_type_layout).map_err(|e| {
         anyhow!(
-            "Unable to parse arg at type {}. Got error: {:?}",
+            "Unable to parse arg {:?} at pos {} as type {}. Got error: {}",
+            arg,
+            idx,
             move_type_layout,
             e
         )
@@ -288,16 +298,21 @@ fn resolve_object_arg(idx: usize, arg: &SuiJsonValue) -> Result<ObjectID, anyhow
         JsonValue::String(s) => {
             let s = s.trim().to_lowercase();
             if !s.starts_with(HEX_PREFIX) {
-                return Err(anyhow!("ObjectID hex string must start with 0x.",));
+                return Err(anyhow!(
+                    "Unable to parse arg {s} as ObjectID at pos {idx}. ObjectID hex string must \
+                    start with 0x.",
+                ));
             }
-            Ok(ObjectID::from_hex_literal(&s)?)
+            ObjectID::from_hex_literal(&s).map_err(|e| {
+                anyhow!("Unable to parse arg {s} as ObjectID at pos {idx}. Got error: {e}")
+            })
         }
         _ => Err(anyhow!(
             "Unable to parse arg {:?} as ObjectID at pos {}. Expected {:?} byte hex string \
                 prefixed with 0x.",
-            ObjectID::LENGTH,
-            idx,
             arg.to_json_value(),
+            idx,
+            ObjectID::LENGTH,
         )),
     }
 }
@@ -313,15 +328,22 @@ fn resolve_call_arg(
         | SignatureToken::U64
         | SignatureToken::U128
         | SignatureToken::Address
-        | SignatureToken::Vector(_) => SuiJsonCallArg::Pure(resolve_primtive_arg(arg, param)?),
+        | SignatureToken::Vector(_) => SuiJsonCallArg::Pure(resolve_primtive_arg(idx, arg, param)?),
 
         SignatureToken::Struct(_)
         | SignatureToken::StructInstantiation(_, _)
         | SignatureToken::TypeParameter(_)
         | SignatureToken::Reference(_)
-        | SignatureToken::MutableReference(_) => {
-            SuiJsonCallArg::Object(resolve_object_arg(idx, arg)?)
-        }
+        | SignatureToken::MutableReference(_) => match arg.to_json_value() {
+            JsonValue::String(s) if s.trim().starts_with(CommandResult::PREFIX) => {
+                SuiJsonCallArg::Result(s.trim().parse().map_err(|e| {
+                    anyhow!(
+                        "Unable to parse arg {s} as command result at pos {idx}. Got error: {e}"
+                    )
+                })?)
+            }
+            _ => SuiJsonCallArg::Object(resolve_object_arg(idx, arg)?),
+        },
 
         SignatureToken::Signer => unreachable!(),
     })
@@ -383,9 +405,11 @@ pub fn resolve_move_function_args(
     };
     if combined_args_json.len() != expected_len {
         return Err(anyhow!(
-            "Expected {} args, found {}",
+            "Expected {} args, found {} when calling {}::{}",
             expected_len,
-            combined_args_json.len()
+            combined_args_json.len(),
+            module.self_id(),
+            function,
         ));
     }
 
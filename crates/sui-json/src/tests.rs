@@ -4,6 +4,7 @@
 use std::path::Path;
 use std::str::FromStr;
 
+use move_binary_format::file_format::{SignatureToken, StructHandleIndex};
 use move_core_types::{
     account_address::AccountAddress, identifier::Identifier, value::MoveTypeLayout,
 };
@@ -11,11 +12,12 @@ use serde_json::{json, Value};
 use test_fuzz::runtime::num_traits::ToPrimitive;
 
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::messages::CommandResult;
 use sui_types::object::Object;
 use sui_types::SUI_FRAMEWORK_ADDRESS;
 
 use super::{is_homogeneous, HEX_PREFIX};
-use super::{resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
+use super::{resolve_call_args, resolve_move_function_args, SuiJsonCallArg, SuiJsonValue};
 
 #[test]
 fn test_json_is_homogeneous() {
@@ -558,3 +560,54 @@ fn test_from_str() {
     assert!(test.0.is_string());
     assert_eq!("Some string", test.0.as_str().unwrap())
 }
+
+#[test]
+fn test_number_as_u128() {
+    let value = SuiJsonValue::new(Value::from(42u64)).unwrap();
+    assert_eq!(
+        value.to_bcs_bytes(&MoveTypeLayout::U128).unwrap(),
+        bcs::to_bytes(&42u128).unwrap()
+    );
+}
+
+#[test]
+fn test_call_args_error_reports_position() {
+    let args = vec![
+        SuiJsonValue::new(Value::from(1u64)).unwrap(),
+        SuiJsonValue::new(Value::from(300u64)).unwrap(),
+    ];
+    let err = resolve_call_args(&args, &[SignatureToken::U64, SignatureToken::U8]).unwrap_err();
+    let err = err.to_string();
+    assert!(err.contains("at pos 1"), "{err}");
+    assert!(err.contains("300 is out of range for u8"), "{err}");
+
+    let args = vec![SuiJsonValue::new(Value::from("1234")).unwrap()];
+    let err = resolve_call_args(&args, &[SignatureToken::Struct(StructHandleIndex(0))])
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("as ObjectID at pos 0"), "{err}");
+}
+
+#[test]
+fn test_command_result_arg() {
+    let params = [
+        SignatureToken::MutableReference(Box::new(SignatureToken::Struct(StructHandleIndex(0)))),
+        SignatureToken::U64,
+    ];
+    let args = vec![
+        SuiJsonValue::new(Value::from("result:1:2")).unwrap(),
+        SuiJsonValue::new(Value::from(10u64)).unwrap(),
+    ];
+    let resolved = resolve_call_args(&args, &params).unwrap();
+    assert_eq!(
+        resolved[0],
+        SuiJsonCallArg::Result(CommandResult::new(1, 2))
+    );
+
+    let args = vec![
+        SuiJsonValue::new(Value::from("result:1")).unwrap(),
+        SuiJsonValue::new(Value::from(10u64)).unwrap(),
+    ];
+    let err = resolve_call_args(&args, &params).unwrap_err().to_string();
+    assert!(err.contains("as command result at pos 0"), "{err}");
+}
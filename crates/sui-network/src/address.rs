@@ -0,0 +1,160 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Endpoints given as multiaddrs, e.g. `/ip6/::1/tcp/8080/http` or `/dns4/example.com/udp/8080/quic`.
+//!
+//! Hosts are IPv4 or IPv6 addresses, or names resolved to addresses of either family with
+//! `dns`, only to IPv4 addresses with `dns4` and only to IPv6 addresses with `dns6`. They are
+//! followed by a TCP or UDP port, and optionally by the protocols served over it.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+
+use multiaddr::{Multiaddr, Protocol};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    Any,
+    Ipv4,
+    Ipv6,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Host {
+    Ip(IpAddr),
+    Name(String, Family),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transport {
+    Tcp,
+    Udp,
+}
+
+/// The host and port of a multiaddr.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: Host,
+    pub transport: Transport,
+    pub port: u16,
+}
+
+impl Endpoint {
+    pub fn parse(address: &Multiaddr) -> io::Result<Self> {
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Invalid address {address}, expected /<ip4|ip6|dns|dns4|dns6>/<host>/<tcp|udp>/<port>"
+                ),
+            )
+        };
+        let mut protocols = address.iter();
+        let host = match protocols.next() {
+            Some(Protocol::Ip4(ip)) => Host::Ip(ip.into()),
+            Some(Protocol::Ip6(ip)) => Host::Ip(ip.into()),
+            Some(Protocol::Dns(name)) => Host::Name(name.to_string(), Family::Any),
+            Some(Protocol::Dns4(name)) => Host::Name(name.to_string(), Family::Ipv4),
+            Some(Protocol::Dns6(name)) => Host::Name(name.to_string(), Family::Ipv6),
+            _ => return Err(invalid()),
+        };
+        let (transport, port) = match protocols.next() {
+            Some(Protocol::Tcp(port)) => (Transport::Tcp, port),
+            Some(Protocol::Udp(port)) => (Transport::Udp, port),
+            _ => return Err(invalid()),
+        };
+        Ok(Self {
+            host,
+            transport,
+            port,
+        })
+    }
+
+    /// Resolve the endpoint, blocking while looking its name up.
+    pub fn resolve(&self) -> io::Result<SocketAddr> {
+        match &self.host {
+            Host::Ip(ip) => Ok(SocketAddr::new(*ip, self.port)),
+            Host::Name(name, family) => {
+                self.pick((name.as_str(), self.port).to_socket_addrs()?, *family)
+            }
+        }
+    }
+
+    pub async fn resolve_async(&self) -> io::Result<SocketAddr> {
+        match &self.host {
+            Host::Ip(ip) => Ok(SocketAddr::new(*ip, self.port)),
+            Host::Name(name, family) => self.pick(
+                tokio::net::lookup_host((name.as_str(), self.port)).await?,
+                *family,
+            ),
+        }
+    }
+
+    fn pick(
+        &self,
+        mut addresses: impl Iterator<Item = SocketAddr>,
+        family: Family,
+    ) -> io::Result<SocketAddr> {
+        addresses
+            .find(|address| match family {
+                Family::Any => true,
+                Family::Ipv4 => address.is_ipv4(),
+                Family::Ipv6 => address.is_ipv6(),
+            })
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("Cannot resolve {:?} to a {family:?} address", self.host),
+                )
+            })
+    }
+}
+
+/// The multiaddr of a socket address, followed by the protocols `suffix`, e.g. `/quic`.
+pub fn to_multiaddr(address: SocketAddr, transport: Transport, suffix: &[Protocol]) -> Multiaddr {
+    let mut multiaddr = Multiaddr::empty();
+    multiaddr.push(match address {
+        SocketAddr::V4(address) => Protocol::Ip4(*address.ip()),
+        SocketAddr::V6(address) => Protocol::Ip6(*address.ip()),
+    });
+    multiaddr.push(match transport {
+        Transport::Tcp => Protocol::Tcp(address.port()),
+        Transport::Udp => Protocol::Udp(address.port()),
+    });
+    for protocol in suffix {
+        multiaddr.push(protocol.clone());
+    }
+    multiaddr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    #[test]
+    fn parse_and_resolve() {
+        let endpoint = Endpoint::parse(&"/ip6/::1/tcp/8080/http".parse().unwrap()).unwrap();
+        assert_eq!(endpoint.transport, Transport::Tcp);
+        assert_eq!(
+            endpoint.resolve().unwrap(),
+            SocketAddr::new(Ipv6Addr::LOCALHOST.into(), 8080)
+        );
+
+        let endpoint = Endpoint::parse(&"/dns4/localhost/udp/8080/quic".parse().unwrap()).unwrap();
+        assert_eq!(endpoint.transport, Transport::Udp);
+        assert_eq!(
+            endpoint.resolve().unwrap(),
+            SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 8080)
+        );
+
+        assert!(Endpoint::parse(&"/ip4/127.0.0.1".parse().unwrap()).is_err());
+        assert!(Endpoint::parse(&"/tcp/8080".parse().unwrap()).is_err());
+
+        let address = SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 8080);
+        assert_eq!(
+            to_multiaddr(address, Transport::Udp, &[Protocol::Quic]),
+            "/ip6/::/udp/8080/quic".parse::<Multiaddr>().unwrap()
+        );
+    }
+}
@@ -1,10 +1,26 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-mod validator {
-    include!(concat!(env!("OUT_DIR"), "/sui.validator.Validator.rs"));
+//! The services served by nodes, generated from the files of `proto/`. See [`crate::convert`]
+//! for the conversions of their messages from and to the `sui_types` values they carry.
+
+pub mod validator {
+    include!(concat!(env!("OUT_DIR"), "/sui.validator.rs"));
+}
+
+pub mod discovery {
+    include!(concat!(env!("OUT_DIR"), "/sui.discovery.rs"));
 }
 
+/// The messages of the QUIC transport, which refer to the ones of `validator`.
+pub(crate) mod quic {
+    include!(concat!(env!("OUT_DIR"), "/sui.quic.rs"));
+}
+
+pub use discovery::{
+    discovery_client::DiscoveryClient,
+    discovery_server::{Discovery, DiscoveryServer},
+};
 pub use validator::{
     validator_client::ValidatorClient,
     validator_server::{Validator, ValidatorServer},
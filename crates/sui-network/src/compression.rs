@@ -0,0 +1,178 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression of the messages sent over QUIC, see [`crate::quic`].
+//!
+//! Peers offer the algorithms they support in the TLS handshake of a connection, as variants of
+//! its application protocol, and the server picks the one it prefers. When an algorithm was
+//! negotiated, every message of the connection starts with a byte telling whether the rest is
+//! compressed, so that small messages, which would barely shrink, are sent as they are.
+
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::quic::ALPN_PROTOCOL;
+
+const UNCOMPRESSED: u8 = 0;
+const COMPRESSED: u8 = 1;
+
+// Favors speed, most of the gain on certificates comes from the repeated bytecode of packages.
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compression {
+    Zstd,
+    Snappy,
+}
+
+impl Compression {
+    fn suffix(&self) -> &'static [u8] {
+        match self {
+            Compression::Zstd => b"+zstd",
+            Compression::Snappy => b"+snappy",
+        }
+    }
+
+    /// The application protocol of the connections compressing their messages with this
+    /// algorithm.
+    pub fn alpn_protocol(&self) -> Vec<u8> {
+        [ALPN_PROTOCOL, self.suffix()].concat()
+    }
+
+    /// The algorithm negotiated by a connection of the application `protocol`, if any.
+    pub fn negotiated(protocol: &[u8]) -> Option<Self> {
+        [Compression::Zstd, Compression::Snappy]
+            .into_iter()
+            .find(|compression| protocol == compression.alpn_protocol().as_slice())
+    }
+
+    fn compress(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::Zstd => zstd::bulk::compress(bytes, ZSTD_LEVEL),
+            Compression::Snappy => snap::raw::Encoder::new()
+                .compress_vec(bytes)
+                .map_err(invalid_data),
+        }
+    }
+
+    /// Decompress `bytes`, which must not decompress to more than `max_size` bytes.
+    fn decompress(&self, bytes: &[u8], max_size: usize) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::Zstd => zstd::bulk::decompress(bytes, max_size),
+            Compression::Snappy => {
+                let size = snap::raw::decompress_len(bytes).map_err(invalid_data)?;
+                if size > max_size {
+                    return Err(too_large(size, max_size));
+                }
+                snap::raw::Decoder::new()
+                    .decompress_vec(bytes)
+                    .map_err(invalid_data)
+            }
+        }
+    }
+}
+
+/// Encode a message for a connection which negotiated `compression`, if any. Messages of less
+/// than `threshold` bytes, or which would not shrink, are not compressed.
+pub fn encode(
+    compression: Option<Compression>,
+    threshold: usize,
+    bytes: Vec<u8>,
+) -> io::Result<Vec<u8>> {
+    let compression = match compression {
+        Some(compression) => compression,
+        None => return Ok(bytes),
+    };
+    if bytes.len() >= threshold {
+        let compressed = compression.compress(&bytes)?;
+        if compressed.len() < bytes.len() {
+            return Ok([&[COMPRESSED][..], &compressed].concat());
+        }
+    }
+    Ok([&[UNCOMPRESSED][..], &bytes].concat())
+}
+
+/// Decode a message received on a connection which negotiated `compression`, if any.
+pub fn decode(
+    compression: Option<Compression>,
+    bytes: Vec<u8>,
+    max_size: usize,
+) -> io::Result<Vec<u8>> {
+    let compression = match compression {
+        Some(compression) => compression,
+        None => return Ok(bytes),
+    };
+    match bytes.split_first() {
+        Some((&UNCOMPRESSED, message)) => Ok(message.to_vec()),
+        Some((&COMPRESSED, message)) => compression.decompress(message, max_size),
+        Some((flag, _)) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unknown message encoding {flag}"),
+        )),
+        None => Err(io::Error::from(io::ErrorKind::UnexpectedEof)),
+    }
+}
+
+/// The message encoded in `bytes` for a connection which negotiated `compression`, if any, or
+/// its start while it is being received, unless it is compressed.
+pub fn uncompressed(compression: Option<Compression>, bytes: &[u8]) -> Option<&[u8]> {
+    match (compression, bytes.split_first()) {
+        (None, _) => Some(bytes),
+        (Some(_), Some((&UNCOMPRESSED, message))) => Some(message),
+        (Some(_), _) => None,
+    }
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn too_large(size: usize, max_size: usize) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Message of {size} bytes exceeds the maximum message size of {max_size} bytes"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let large = b"sui".repeat(10_000);
+        for compression in [None, Some(Compression::Zstd), Some(Compression::Snappy)] {
+            for message in [b"small".to_vec(), large.clone()] {
+                let encoded = encode(compression, 1024, message.clone()).unwrap();
+                if compression.is_some() && message.len() > 1024 {
+                    assert!(encoded.len() < message.len());
+                }
+                assert_eq!(decode(compression, encoded, 1 << 20).unwrap(), message);
+            }
+        }
+
+        assert_eq!(
+            Compression::negotiated(&Compression::Snappy.alpn_protocol()),
+            Some(Compression::Snappy)
+        );
+        assert_eq!(Compression::negotiated(ALPN_PROTOCOL), None);
+
+        let compression = Some(Compression::Zstd);
+        let encoded = encode(compression, 1024, b"small".to_vec()).unwrap();
+        assert_eq!(uncompressed(compression, &encoded), Some(&b"small"[..]));
+        let encoded = encode(compression, 1024, large).unwrap();
+        assert_eq!(uncompressed(compression, &encoded), None);
+    }
+
+    #[test]
+    fn decompressed_size_is_bounded() {
+        let message = vec![0; 1 << 20];
+        for compression in [Compression::Zstd, Compression::Snappy] {
+            let encoded = encode(Some(compression), 1024, message.clone()).unwrap();
+            assert!(encoded.len() < 1 << 16);
+            assert!(decode(Some(compression), encoded, 1 << 16).is_err());
+        }
+    }
+}
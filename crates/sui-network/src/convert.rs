@@ -0,0 +1,907 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between the messages of the services, see `proto/`, and the `sui_types` values
+//! they carry.
+//!
+//! Values convert into messages with `From`, and messages decode into values with `TryFrom`,
+//! failing on missing or malformed fields. Decoding checks the lengths of keys, digests and
+//! signatures but no signature, which callers verify as for any value received from a peer.
+
+use std::fmt;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use sui_types::base_types::{
+    ChainId, ExecutionDigests, ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress,
+    TransactionDigest, TransactionEffectsDigest,
+};
+use sui_types::batch::{AuthorityBatch, SignedBatch, UpdateItem};
+use sui_types::crypto::{
+    AuthorityQuorumSignInfo, AuthoritySignInfo, AuthoritySignature, PublicKeyBytes, Signature,
+    ToFromBytes,
+};
+use sui_types::discovery::{PeerInfo, PeersRequest, PeersResponse, SignedPeerInfo};
+use sui_types::error::SuiError;
+use sui_types::handshake::Handshake;
+use sui_types::messages::{
+    AccountInfoRequest, AccountInfoResponse, BatchInfoRequest, BatchInfoResponseItem,
+    CertifiedTransaction, ObjectInfoRequest, ObjectInfoRequestKind, ObjectInfoResponse,
+    ObjectResponse, SignedTransaction, SignedTransactionEffects, Transaction,
+    TransactionInfoRequest, TransactionInfoResponse,
+};
+use sui_types::messages_checkpoint::{
+    AuthenticatedCheckpoint, AuthorityCheckpointInfo, CertifiedCheckpointSummary,
+    CheckpointRequest, CheckpointRequestType, CheckpointResponse, SignedCheckpointProposalSummary,
+    SignedCheckpointSummary,
+};
+use sui_types::object::ObjectFormatOptions;
+
+use crate::api::{discovery, validator};
+
+/// A message whose fields do not decode to the value it carries.
+#[derive(Clone, Debug)]
+pub struct DecodeError(String);
+
+impl DecodeError {
+    fn invalid<E: fmt::Display>(field: &str, error: E) -> Self {
+        Self(format!("Invalid {field}: {error}"))
+    }
+
+    fn missing(field: &str) -> Self {
+        Self(format!("Missing {field}"))
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for tonic::Status {
+    fn from(error: DecodeError) -> Self {
+        tonic::Status::invalid_argument(error.to_string())
+    }
+}
+
+impl From<DecodeError> for SuiError {
+    fn from(error: DecodeError) -> Self {
+        SuiError::RpcError(format!("Invalid response: {error}"))
+    }
+}
+
+fn bytes(value: impl AsRef<[u8]>) -> Vec<u8> {
+    value.as_ref().to_vec()
+}
+
+fn to_bcs<T: Serialize>(value: &T) -> Vec<u8> {
+    bcs::to_bytes(value).expect("Values carried by messages are serializable")
+}
+
+fn from_bcs<T: DeserializeOwned>(field: &str, bytes: &[u8]) -> Result<T, DecodeError> {
+    bcs::from_bytes(bytes).map_err(|e| DecodeError::invalid(field, e))
+}
+
+fn required<T>(field: &str, value: Option<T>) -> Result<T, DecodeError> {
+    value.ok_or_else(|| DecodeError::missing(field))
+}
+
+fn digest(field: &str, bytes: &[u8]) -> Result<[u8; 32], DecodeError> {
+    bytes.try_into().map_err(|e| DecodeError::invalid(field, e))
+}
+
+fn object_id(field: &str, bytes: &[u8]) -> Result<ObjectID, DecodeError> {
+    ObjectID::try_from(bytes).map_err(|e| DecodeError::invalid(field, e))
+}
+
+fn address(field: &str, bytes: &[u8]) -> Result<SuiAddress, DecodeError> {
+    SuiAddress::try_from(bytes).map_err(|e| DecodeError::invalid(field, e))
+}
+
+fn public_key(field: &str, bytes: &[u8]) -> Result<PublicKeyBytes, DecodeError> {
+    PublicKeyBytes::from_bytes(bytes).map_err(|e| DecodeError::invalid(field, e))
+}
+
+fn signature(field: &str, bytes: &[u8]) -> Result<Signature, DecodeError> {
+    <Signature as signature::Signature>::from_bytes(bytes)
+        .map_err(|e| DecodeError::invalid(field, e))
+}
+
+impl From<Handshake> for validator::Handshake {
+    fn from(handshake: Handshake) -> Self {
+        Self {
+            chain_id: bytes(handshake.chain_id.0),
+            min_protocol_version: handshake.min_protocol_version,
+            max_protocol_version: handshake.max_protocol_version,
+        }
+    }
+}
+
+impl TryFrom<validator::Handshake> for Handshake {
+    type Error = DecodeError;
+
+    fn try_from(handshake: validator::Handshake) -> Result<Self, DecodeError> {
+        Ok(Self {
+            chain_id: ChainId(digest("chain_id", &handshake.chain_id)?),
+            min_protocol_version: handshake.min_protocol_version,
+            max_protocol_version: handshake.max_protocol_version,
+        })
+    }
+}
+
+impl From<ObjectRef> for validator::ObjectRef {
+    fn from((object_id, version, digest): ObjectRef) -> Self {
+        Self {
+            object_id: bytes(object_id),
+            version: version.value(),
+            digest: bytes(digest),
+        }
+    }
+}
+
+impl TryFrom<validator::ObjectRef> for ObjectRef {
+    type Error = DecodeError;
+
+    fn try_from(object_ref: validator::ObjectRef) -> Result<Self, DecodeError> {
+        Ok((
+            object_id("object_id", &object_ref.object_id)?,
+            SequenceNumber::from_u64(object_ref.version),
+            ObjectDigest(digest("digest", &object_ref.digest)?),
+        ))
+    }
+}
+
+impl From<ExecutionDigests> for validator::ExecutionDigests {
+    fn from(digests: ExecutionDigests) -> Self {
+        Self {
+            transaction: bytes(digests.transaction),
+            effects: bytes(digests.effects.0),
+        }
+    }
+}
+
+impl TryFrom<validator::ExecutionDigests> for ExecutionDigests {
+    type Error = DecodeError;
+
+    fn try_from(digests: validator::ExecutionDigests) -> Result<Self, DecodeError> {
+        Ok(Self::new(
+            TransactionDigest::new(digest("transaction", &digests.transaction)?),
+            TransactionEffectsDigest(digest("effects", &digests.effects)?),
+        ))
+    }
+}
+
+impl From<AuthoritySignInfo> for validator::AuthoritySignInfo {
+    fn from(info: AuthoritySignInfo) -> Self {
+        Self {
+            epoch: info.epoch,
+            authority: bytes(info.authority),
+            signature: bytes(info.signature),
+        }
+    }
+}
+
+impl TryFrom<validator::AuthoritySignInfo> for AuthoritySignInfo {
+    type Error = DecodeError;
+
+    fn try_from(info: validator::AuthoritySignInfo) -> Result<Self, DecodeError> {
+        Ok(Self {
+            epoch: info.epoch,
+            authority: public_key("authority", &info.authority)?,
+            signature: AuthoritySignature::from_bytes(&info.signature)
+                .map_err(|e| DecodeError::invalid("signature", e))?,
+        })
+    }
+}
+
+impl<const STRONG_THRESHOLD: bool> From<AuthorityQuorumSignInfo<STRONG_THRESHOLD>>
+    for validator::AuthorityQuorumSignInfo
+{
+    fn from(info: AuthorityQuorumSignInfo<STRONG_THRESHOLD>) -> Self {
+        Self {
+            epoch: info.epoch,
+            signature: to_bcs(&info.signature),
+            signers: info.signers_map.iter().collect(),
+        }
+    }
+}
+
+impl<const STRONG_THRESHOLD: bool> TryFrom<validator::AuthorityQuorumSignInfo>
+    for AuthorityQuorumSignInfo<STRONG_THRESHOLD>
+{
+    type Error = DecodeError;
+
+    fn try_from(info: validator::AuthorityQuorumSignInfo) -> Result<Self, DecodeError> {
+        Ok(Self {
+            epoch: info.epoch,
+            signature: from_bcs("signature", &info.signature)?,
+            signers_map: info.signers.into_iter().collect(),
+        })
+    }
+}
+
+impl From<Transaction> for validator::Transaction {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            data: to_bcs(&transaction.data),
+            tx_signature: bytes(transaction.tx_signature),
+        }
+    }
+}
+
+impl TryFrom<validator::Transaction> for Transaction {
+    type Error = DecodeError;
+
+    fn try_from(transaction: validator::Transaction) -> Result<Self, DecodeError> {
+        Ok(Self::new(
+            from_bcs("data", &transaction.data)?,
+            signature("tx_signature", &transaction.tx_signature)?,
+        ))
+    }
+}
+
+impl From<SignedTransaction> for validator::SignedTransaction {
+    fn from(transaction: SignedTransaction) -> Self {
+        Self {
+            data: to_bcs(&transaction.data),
+            tx_signature: bytes(transaction.tx_signature),
+            auth_sign_info: Some(transaction.auth_sign_info.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::SignedTransaction> for SignedTransaction {
+    type Error = DecodeError;
+
+    fn try_from(transaction: validator::SignedTransaction) -> Result<Self, DecodeError> {
+        Ok(Self::new_from_parts(
+            from_bcs("data", &transaction.data)?,
+            signature("tx_signature", &transaction.tx_signature)?,
+            required("auth_sign_info", transaction.auth_sign_info)?.try_into()?,
+        ))
+    }
+}
+
+impl From<CertifiedTransaction> for validator::CertifiedTransaction {
+    fn from(certificate: CertifiedTransaction) -> Self {
+        Self {
+            data: to_bcs(&certificate.data),
+            tx_signature: bytes(certificate.tx_signature),
+            auth_sign_info: Some(certificate.auth_sign_info.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::CertifiedTransaction> for CertifiedTransaction {
+    type Error = DecodeError;
+
+    fn try_from(certificate: validator::CertifiedTransaction) -> Result<Self, DecodeError> {
+        Ok(Self::new_from_parts(
+            from_bcs("data", &certificate.data)?,
+            signature("tx_signature", &certificate.tx_signature)?,
+            required("auth_sign_info", certificate.auth_sign_info)?.try_into()?,
+        ))
+    }
+}
+
+impl From<SignedTransactionEffects> for validator::SignedTransactionEffects {
+    fn from(effects: SignedTransactionEffects) -> Self {
+        Self {
+            effects: to_bcs(&effects.effects),
+            auth_signature: Some(effects.auth_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::SignedTransactionEffects> for SignedTransactionEffects {
+    type Error = DecodeError;
+
+    fn try_from(effects: validator::SignedTransactionEffects) -> Result<Self, DecodeError> {
+        Ok(Self::new_from_parts(
+            from_bcs("effects", &effects.effects)?,
+            required("auth_signature", effects.auth_signature)?.try_into()?,
+        ))
+    }
+}
+
+impl From<TransactionInfoResponse> for validator::TransactionInfoResponse {
+    fn from(response: TransactionInfoResponse) -> Self {
+        Self {
+            signed_transaction: response.signed_transaction.map(Into::into),
+            certified_transaction: response.certified_transaction.map(Into::into),
+            signed_effects: response.signed_effects.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<validator::TransactionInfoResponse> for TransactionInfoResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: validator::TransactionInfoResponse) -> Result<Self, DecodeError> {
+        Ok(Self {
+            signed_transaction: response
+                .signed_transaction
+                .map(TryInto::try_into)
+                .transpose()?,
+            certified_transaction: response
+                .certified_transaction
+                .map(TryInto::try_into)
+                .transpose()?,
+            signed_effects: response.signed_effects.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl From<AccountInfoRequest> for validator::AccountInfoRequest {
+    fn from(request: AccountInfoRequest) -> Self {
+        Self {
+            account: bytes(request.account),
+        }
+    }
+}
+
+impl TryFrom<validator::AccountInfoRequest> for AccountInfoRequest {
+    type Error = DecodeError;
+
+    fn try_from(request: validator::AccountInfoRequest) -> Result<Self, DecodeError> {
+        Ok(Self {
+            account: address("account", &request.account)?,
+        })
+    }
+}
+
+impl From<AccountInfoResponse> for validator::AccountInfoResponse {
+    fn from(response: AccountInfoResponse) -> Self {
+        Self {
+            object_ids: response.object_ids.into_iter().map(Into::into).collect(),
+            owner: bytes(response.owner),
+        }
+    }
+}
+
+impl TryFrom<validator::AccountInfoResponse> for AccountInfoResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: validator::AccountInfoResponse) -> Result<Self, DecodeError> {
+        Ok(Self {
+            object_ids: response
+                .object_ids
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            owner: address("owner", &response.owner)?,
+        })
+    }
+}
+
+impl From<ObjectInfoRequest> for validator::ObjectInfoRequest {
+    fn from(request: ObjectInfoRequest) -> Self {
+        use validator::object_info_request::RequestKind;
+        let request_kind = match request.request_kind {
+            ObjectInfoRequestKind::LatestObjectInfo(format) => {
+                RequestKind::LatestObjectInfo(validator::LatestObjectInfo {
+                    format: format.map(|format| validator::ObjectFormatOptions {
+                        include_types: format.include_types(),
+                    }),
+                })
+            }
+            ObjectInfoRequestKind::PastObjectInfo(version) => {
+                RequestKind::PastObjectInfo(version.value())
+            }
+        };
+        Self {
+            object_id: bytes(request.object_id),
+            request_kind: Some(request_kind),
+        }
+    }
+}
+
+impl TryFrom<validator::ObjectInfoRequest> for ObjectInfoRequest {
+    type Error = DecodeError;
+
+    fn try_from(request: validator::ObjectInfoRequest) -> Result<Self, DecodeError> {
+        use validator::object_info_request::RequestKind;
+        let request_kind = match required("request_kind", request.request_kind)? {
+            RequestKind::LatestObjectInfo(latest) => ObjectInfoRequestKind::LatestObjectInfo(
+                latest
+                    .format
+                    .map(|format| ObjectFormatOptions::new(format.include_types)),
+            ),
+            RequestKind::PastObjectInfo(version) => {
+                ObjectInfoRequestKind::PastObjectInfo(SequenceNumber::from_u64(version))
+            }
+        };
+        Ok(Self {
+            object_id: object_id("object_id", &request.object_id)?,
+            request_kind,
+        })
+    }
+}
+
+impl From<ObjectResponse> for validator::ObjectResponse {
+    fn from(response: ObjectResponse) -> Self {
+        Self {
+            object: to_bcs(&response.object),
+            lock: response.lock.map(Into::into),
+            layout: response.layout.as_ref().map(to_bcs),
+        }
+    }
+}
+
+impl TryFrom<validator::ObjectResponse> for ObjectResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: validator::ObjectResponse) -> Result<Self, DecodeError> {
+        Ok(Self {
+            object: from_bcs("object", &response.object)?,
+            lock: response.lock.map(TryInto::try_into).transpose()?,
+            layout: response
+                .layout
+                .map(|layout| from_bcs("layout", &layout))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<ObjectInfoResponse> for validator::ObjectInfoResponse {
+    fn from(response: ObjectInfoResponse) -> Self {
+        Self {
+            parent_certificate: response.parent_certificate.map(Into::into),
+            requested_object_reference: response.requested_object_reference.map(Into::into),
+            object_and_lock: response.object_and_lock.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<validator::ObjectInfoResponse> for ObjectInfoResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: validator::ObjectInfoResponse) -> Result<Self, DecodeError> {
+        Ok(Self {
+            parent_certificate: response
+                .parent_certificate
+                .map(TryInto::try_into)
+                .transpose()?,
+            requested_object_reference: response
+                .requested_object_reference
+                .map(TryInto::try_into)
+                .transpose()?,
+            object_and_lock: response
+                .object_and_lock
+                .map(TryInto::try_into)
+                .transpose()?,
+        })
+    }
+}
+
+impl From<TransactionInfoRequest> for validator::TransactionInfoRequest {
+    fn from(request: TransactionInfoRequest) -> Self {
+        Self {
+            transaction_digest: bytes(request.transaction_digest),
+        }
+    }
+}
+
+impl TryFrom<validator::TransactionInfoRequest> for TransactionInfoRequest {
+    type Error = DecodeError;
+
+    fn try_from(request: validator::TransactionInfoRequest) -> Result<Self, DecodeError> {
+        let transaction_digest = digest("transaction_digest", &request.transaction_digest)?;
+        Ok(Self {
+            transaction_digest: TransactionDigest::new(transaction_digest),
+        })
+    }
+}
+
+impl From<BatchInfoRequest> for validator::BatchInfoRequest {
+    fn from(request: BatchInfoRequest) -> Self {
+        Self {
+            start: request.start,
+            length: request.length,
+        }
+    }
+}
+
+impl From<validator::BatchInfoRequest> for BatchInfoRequest {
+    fn from(request: validator::BatchInfoRequest) -> Self {
+        Self {
+            start: request.start,
+            length: request.length,
+        }
+    }
+}
+
+impl From<AuthorityBatch> for validator::AuthorityBatch {
+    fn from(batch: AuthorityBatch) -> Self {
+        Self {
+            next_sequence_number: batch.next_sequence_number,
+            initial_sequence_number: batch.initial_sequence_number,
+            size: batch.size,
+            previous_digest: batch.previous_digest.map(bytes),
+            transactions_digest: bytes(batch.transactions_digest),
+        }
+    }
+}
+
+impl TryFrom<validator::AuthorityBatch> for AuthorityBatch {
+    type Error = DecodeError;
+
+    fn try_from(batch: validator::AuthorityBatch) -> Result<Self, DecodeError> {
+        Ok(Self {
+            next_sequence_number: batch.next_sequence_number,
+            initial_sequence_number: batch.initial_sequence_number,
+            size: batch.size,
+            previous_digest: batch
+                .previous_digest
+                .map(|previous| digest("previous_digest", &previous))
+                .transpose()?,
+            transactions_digest: digest("transactions_digest", &batch.transactions_digest)?,
+        })
+    }
+}
+
+impl From<SignedBatch> for validator::SignedBatch {
+    fn from(batch: SignedBatch) -> Self {
+        Self {
+            batch: Some(batch.batch.into()),
+            auth_signature: Some(batch.auth_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::SignedBatch> for SignedBatch {
+    type Error = DecodeError;
+
+    fn try_from(batch: validator::SignedBatch) -> Result<Self, DecodeError> {
+        Ok(Self {
+            batch: required("batch", batch.batch)?.try_into()?,
+            auth_signature: required("auth_signature", batch.auth_signature)?.try_into()?,
+        })
+    }
+}
+
+impl From<BatchInfoResponseItem> for validator::BatchInfoResponseItem {
+    fn from(item: BatchInfoResponseItem) -> Self {
+        use validator::batch_info_response_item::Item;
+        let item = match item.0 {
+            UpdateItem::Transaction((sequence_number, digests)) => {
+                Item::Transaction(validator::SequencedTransaction {
+                    sequence_number,
+                    digests: Some(digests.into()),
+                })
+            }
+            UpdateItem::Batch(batch) => Item::Batch(batch.into()),
+        };
+        Self { item: Some(item) }
+    }
+}
+
+impl TryFrom<validator::BatchInfoResponseItem> for BatchInfoResponseItem {
+    type Error = DecodeError;
+
+    fn try_from(item: validator::BatchInfoResponseItem) -> Result<Self, DecodeError> {
+        use validator::batch_info_response_item::Item;
+        let item = match required("item", item.item)? {
+            Item::Transaction(transaction) => UpdateItem::Transaction((
+                transaction.sequence_number,
+                required("digests", transaction.digests)?.try_into()?,
+            )),
+            Item::Batch(batch) => UpdateItem::Batch(batch.try_into()?),
+        };
+        Ok(Self(item))
+    }
+}
+
+impl From<CheckpointRequest> for validator::CheckpointRequest {
+    fn from(request: CheckpointRequest) -> Self {
+        use validator::checkpoint_request::RequestType;
+        let request_type = match request.request_type {
+            CheckpointRequestType::AuthenticatedCheckpoint(sequence_number) => {
+                RequestType::AuthenticatedCheckpoint(validator::AuthenticatedCheckpointRequest {
+                    sequence_number,
+                })
+            }
+            CheckpointRequestType::CheckpointProposal => {
+                RequestType::CheckpointProposal(validator::CheckpointProposalRequest {})
+            }
+        };
+        Self {
+            request_type: Some(request_type),
+            detail: request.detail,
+        }
+    }
+}
+
+impl TryFrom<validator::CheckpointRequest> for CheckpointRequest {
+    type Error = DecodeError;
+
+    fn try_from(request: validator::CheckpointRequest) -> Result<Self, DecodeError> {
+        use validator::checkpoint_request::RequestType;
+        let request_type = match required("request_type", request.request_type)? {
+            RequestType::AuthenticatedCheckpoint(authenticated) => {
+                CheckpointRequestType::AuthenticatedCheckpoint(authenticated.sequence_number)
+            }
+            RequestType::CheckpointProposal(_) => CheckpointRequestType::CheckpointProposal,
+        };
+        Ok(Self {
+            request_type,
+            detail: request.detail,
+        })
+    }
+}
+
+impl From<SignedCheckpointSummary> for validator::SignedCheckpointSummary {
+    fn from(checkpoint: SignedCheckpointSummary) -> Self {
+        Self {
+            summary: to_bcs(&checkpoint.summary),
+            auth_signature: Some(checkpoint.auth_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::SignedCheckpointSummary> for SignedCheckpointSummary {
+    type Error = DecodeError;
+
+    fn try_from(checkpoint: validator::SignedCheckpointSummary) -> Result<Self, DecodeError> {
+        Ok(Self {
+            summary: from_bcs("summary", &checkpoint.summary)?,
+            auth_signature: required("auth_signature", checkpoint.auth_signature)?.try_into()?,
+        })
+    }
+}
+
+impl From<CertifiedCheckpointSummary> for validator::CertifiedCheckpointSummary {
+    fn from(checkpoint: CertifiedCheckpointSummary) -> Self {
+        Self {
+            summary: to_bcs(&checkpoint.summary),
+            auth_signature: Some(checkpoint.auth_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::CertifiedCheckpointSummary> for CertifiedCheckpointSummary {
+    type Error = DecodeError;
+
+    fn try_from(checkpoint: validator::CertifiedCheckpointSummary) -> Result<Self, DecodeError> {
+        Ok(Self {
+            summary: from_bcs("summary", &checkpoint.summary)?,
+            auth_signature: required("auth_signature", checkpoint.auth_signature)?.try_into()?,
+        })
+    }
+}
+
+impl From<SignedCheckpointProposalSummary> for validator::SignedCheckpointProposalSummary {
+    fn from(proposal: SignedCheckpointProposalSummary) -> Self {
+        Self {
+            summary: to_bcs(&proposal.summary),
+            auth_signature: Some(proposal.auth_signature.into()),
+        }
+    }
+}
+
+impl TryFrom<validator::SignedCheckpointProposalSummary> for SignedCheckpointProposalSummary {
+    type Error = DecodeError;
+
+    fn try_from(proposal: validator::SignedCheckpointProposalSummary) -> Result<Self, DecodeError> {
+        Ok(Self {
+            summary: from_bcs("summary", &proposal.summary)?,
+            auth_signature: required("auth_signature", proposal.auth_signature)?.try_into()?,
+        })
+    }
+}
+
+impl From<AuthenticatedCheckpoint> for validator::AuthenticatedCheckpoint {
+    fn from(checkpoint: AuthenticatedCheckpoint) -> Self {
+        use validator::authenticated_checkpoint::Checkpoint;
+        let checkpoint = match checkpoint {
+            AuthenticatedCheckpoint::Signed(signed) => Checkpoint::Signed(signed.into()),
+            AuthenticatedCheckpoint::Certified(certified) => {
+                Checkpoint::Certified(certified.into())
+            }
+        };
+        Self {
+            checkpoint: Some(checkpoint),
+        }
+    }
+}
+
+impl TryFrom<validator::AuthenticatedCheckpoint> for AuthenticatedCheckpoint {
+    type Error = DecodeError;
+
+    fn try_from(checkpoint: validator::AuthenticatedCheckpoint) -> Result<Self, DecodeError> {
+        use validator::authenticated_checkpoint::Checkpoint;
+        Ok(match required("checkpoint", checkpoint.checkpoint)? {
+            Checkpoint::Signed(signed) => AuthenticatedCheckpoint::Signed(signed.try_into()?),
+            Checkpoint::Certified(certified) => {
+                AuthenticatedCheckpoint::Certified(certified.try_into()?)
+            }
+        })
+    }
+}
+
+impl From<CheckpointResponse> for validator::CheckpointResponse {
+    fn from(response: CheckpointResponse) -> Self {
+        use validator::checkpoint_response::Info;
+        let info = match response.info {
+            AuthorityCheckpointInfo::AuthenticatedCheckpoint(checkpoint) => {
+                Info::AuthenticatedCheckpoint(validator::AuthenticatedCheckpointInfo {
+                    checkpoint: checkpoint.map(Into::into),
+                })
+            }
+            AuthorityCheckpointInfo::CheckpointProposal {
+                proposal,
+                prev_cert,
+            } => Info::CheckpointProposal(validator::CheckpointProposalInfo {
+                proposal: proposal.map(Into::into),
+                prev_cert: prev_cert.map(Into::into),
+            }),
+        };
+        Self {
+            info: Some(info),
+            detail: response.detail.as_ref().map(to_bcs),
+        }
+    }
+}
+
+impl TryFrom<validator::CheckpointResponse> for CheckpointResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: validator::CheckpointResponse) -> Result<Self, DecodeError> {
+        use validator::checkpoint_response::Info;
+        let info = match required("info", response.info)? {
+            Info::AuthenticatedCheckpoint(authenticated) => {
+                AuthorityCheckpointInfo::AuthenticatedCheckpoint(
+                    authenticated
+                        .checkpoint
+                        .map(TryInto::try_into)
+                        .transpose()?,
+                )
+            }
+            Info::CheckpointProposal(proposal) => AuthorityCheckpointInfo::CheckpointProposal {
+                proposal: proposal.proposal.map(TryInto::try_into).transpose()?,
+                prev_cert: proposal.prev_cert.map(TryInto::try_into).transpose()?,
+            },
+        };
+        Ok(Self {
+            info,
+            detail: response
+                .detail
+                .map(|detail| from_bcs("detail", &detail))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<SignedPeerInfo> for discovery::SignedPeerInfo {
+    fn from(record: SignedPeerInfo) -> Self {
+        Self {
+            info: Some(discovery::PeerInfo {
+                public_key: bytes(record.info.public_key),
+                address: record.info.address,
+                timestamp_ms: record.info.timestamp_ms,
+            }),
+            signature: bytes(record.signature),
+        }
+    }
+}
+
+impl TryFrom<discovery::SignedPeerInfo> for SignedPeerInfo {
+    type Error = DecodeError;
+
+    fn try_from(record: discovery::SignedPeerInfo) -> Result<Self, DecodeError> {
+        let info = required("info", record.info)?;
+        Ok(Self {
+            info: PeerInfo {
+                public_key: public_key("public_key", &info.public_key)?,
+                address: info.address,
+                timestamp_ms: info.timestamp_ms,
+            },
+            signature: signature("signature", &record.signature)?,
+        })
+    }
+}
+
+impl From<PeersRequest> for discovery::PeersRequest {
+    fn from(request: PeersRequest) -> Self {
+        Self {
+            sender: request.sender.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<discovery::PeersRequest> for PeersRequest {
+    type Error = DecodeError;
+
+    fn try_from(request: discovery::PeersRequest) -> Result<Self, DecodeError> {
+        Ok(Self {
+            sender: request.sender.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl From<PeersResponse> for discovery::PeersResponse {
+    fn from(response: PeersResponse) -> Self {
+        Self {
+            peers: response.peers.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<discovery::PeersResponse> for PeersResponse {
+    type Error = DecodeError;
+
+    fn try_from(response: discovery::PeersResponse) -> Result<Self, DecodeError> {
+        Ok(Self {
+            peers: response
+                .peers
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    fn round_trip<M, T>(value: T) -> T
+    where
+        M: Message + Default + From<T>,
+        T: TryFrom<M, Error = DecodeError>,
+    {
+        let bytes = M::from(value).encode_to_vec();
+        T::try_from(M::decode(bytes.as_slice()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_requests() {
+        let handshake = Handshake::new(ChainId([7; 32]));
+        assert_eq!(
+            round_trip::<validator::Handshake, _>(handshake.clone()),
+            handshake
+        );
+
+        for request in [
+            ObjectInfoRequest::latest_object_info_request(ObjectID::from_single_byte(1), None),
+            ObjectInfoRequest::latest_object_info_request(
+                ObjectID::from_single_byte(1),
+                Some(ObjectFormatOptions::new(true)),
+            ),
+            ObjectInfoRequest::past_object_info_request(
+                ObjectID::from_single_byte(1),
+                SequenceNumber::from_u64(3),
+            ),
+        ] {
+            assert_eq!(
+                round_trip::<validator::ObjectInfoRequest, _>(request.clone()),
+                request
+            );
+        }
+
+        let item = BatchInfoResponseItem(UpdateItem::Transaction((5, ExecutionDigests::random())));
+        assert_eq!(
+            round_trip::<validator::BatchInfoResponseItem, _>(item.clone()),
+            item
+        );
+    }
+
+    #[test]
+    fn reject_missing_and_malformed_fields() {
+        let request = validator::ObjectInfoRequest {
+            object_id: vec![1; ObjectID::LENGTH],
+            request_kind: None,
+        };
+        assert!(ObjectInfoRequest::try_from(request).is_err());
+
+        let handshake = validator::Handshake {
+            chain_id: vec![7; 31],
+            ..Handshake::new(ChainId([7; 32])).into()
+        };
+        assert!(Handshake::try_from(handshake).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Discovery of the nodes of the network.
+//!
+//! Nodes announce the address at which they can be reached with a record signed by their key,
+//! so that records can be gossiped by anyone without being forged. Every node keeps a table of
+//! the peers it knows, seeded with the validators of genesis, and periodically asks a few of
+//! them for their own peers, sending its record along. Peers gain score with every successful
+//! exchange and lose score with every failed one, the worst peers are dropped first.
+//!
+//! Discovery is served over gRPC only: nodes with a QUIC network address ask their peers but
+//! are not asked, and peers with a QUIC address are remembered but not asked. Peers are
+//! authenticated with the key of their record, seeds with any key, as the records they answer
+//! with are signed anyway.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use sui_types::crypto::{KeyPair, KeypairTraits, PublicKeyBytes};
+use sui_types::discovery::{PeerInfo, PeersRequest, PeersResponse, SignedPeerInfo};
+
+use crate::api::{discovery, Discovery, DiscoveryClient, DiscoveryServer};
+use crate::quic::is_quic_address;
+use crate::tcp;
+use crate::tls::NodeCertificate;
+
+const MAX_SCORE: i32 = 10;
+/// Peers whose score falls below this are dropped, unless they are validators of genesis.
+const MIN_SCORE: i32 = -10;
+const FAILURE_PENALTY: i32 = 3;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DiscoveryConfig {
+    /// The address announced to the other nodes. Validators announce their address of genesis
+    /// if not set, other nodes are not announced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_address: Option<Multiaddr>,
+    /// Nodes asked for peers when no peer can be asked, in addition to the validators of
+    /// genesis.
+    #[serde(default)]
+    pub seed_peers: Vec<Multiaddr>,
+    /// How often peers are asked for their peers.
+    #[serde(default = "default_interval")]
+    pub interval: Duration,
+    /// How many peers are asked at every round.
+    #[serde(default = "default_fanout")]
+    pub fanout: usize,
+    /// Maximum number of peers in the table.
+    #[serde(default = "default_max_peers")]
+    pub max_peers: usize,
+    /// Maximum number of records sent to a peer asking for peers.
+    #[serde(default = "default_max_peers_per_response")]
+    pub max_peers_per_response: usize,
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_fanout() -> usize {
+    3
+}
+
+fn default_max_peers() -> usize {
+    1_000
+}
+
+fn default_max_peers_per_response() -> usize {
+    64
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            external_address: None,
+            seed_peers: vec![],
+            interval: default_interval(),
+            fanout: default_fanout(),
+            max_peers: default_max_peers(),
+            max_peers_per_response: default_max_peers_per_response(),
+        }
+    }
+}
+
+/// The record announcing that the node with `key_pair` can be reached at `address`.
+pub fn sign_peer_info(key_pair: &KeyPair, address: &Multiaddr) -> SignedPeerInfo {
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default();
+    let info = PeerInfo {
+        public_key: key_pair.public().into(),
+        address: address.to_vec(),
+        timestamp_ms,
+    };
+    SignedPeerInfo::new(info, key_pair)
+}
+
+#[derive(Clone, Debug)]
+pub struct Peer {
+    pub public_key: PublicKeyBytes,
+    pub address: Multiaddr,
+    /// The record the address comes from, none for the address of genesis of a validator.
+    pub record: Option<SignedPeerInfo>,
+    /// Validators of genesis are never dropped from the table.
+    pub validator: bool,
+    pub score: i32,
+    last_asked: Option<Instant>,
+}
+
+impl Peer {
+    fn timestamp_ms(&self) -> u64 {
+        self.record
+            .as_ref()
+            .map(|record| record.info.timestamp_ms)
+            .unwrap_or_default()
+    }
+}
+
+/// The peers known to a node, by key.
+pub struct PeerTable {
+    own_key: PublicKeyBytes,
+    max_peers: usize,
+    peers: Mutex<BTreeMap<PublicKeyBytes, Peer>>,
+}
+
+impl PeerTable {
+    pub fn new(own_key: PublicKeyBytes, max_peers: usize) -> Self {
+        Self {
+            own_key,
+            max_peers,
+            peers: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Add a validator of genesis, at its address of genesis until it announces another one.
+    pub fn add_validator(&self, public_key: PublicKeyBytes, address: Multiaddr) {
+        if public_key == self.own_key {
+            return;
+        }
+        let mut peers = self.peers.lock().unwrap();
+        peers
+            .entry(public_key)
+            .or_insert_with(|| Peer {
+                public_key,
+                address,
+                record: None,
+                validator: false,
+                score: 0,
+                last_asked: None,
+            })
+            .validator = true;
+    }
+
+    /// Add the peer of `record`, or update its address if the record is newer than the known
+    /// one. Returns whether the table changed, records with an invalid signature or address are
+    /// ignored.
+    pub fn insert(&self, record: SignedPeerInfo) -> bool {
+        let public_key = record.info.public_key;
+        if public_key == self.own_key {
+            return false;
+        }
+        if let Err(error) = record.verify() {
+            debug!(peer =? public_key, "Ignoring peer record: {error}");
+            return false;
+        }
+        let address = match Multiaddr::try_from(record.info.address.clone()) {
+            Ok(address) => address,
+            Err(error) => {
+                debug!(peer =? public_key, "Ignoring peer record: {error}");
+                return false;
+            }
+        };
+
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(peer) = peers.get_mut(&public_key) {
+            if peer.record.is_some() && record.info.timestamp_ms <= peer.timestamp_ms() {
+                return false;
+            }
+            peer.address = address;
+            peer.record = Some(record);
+            return true;
+        }
+
+        if peers.len() >= self.max_peers {
+            let worst = peers
+                .values()
+                .filter(|peer| !peer.validator)
+                .min_by_key(|peer| peer.score)
+                .map(|peer| peer.public_key);
+            match worst {
+                Some(worst) => peers.remove(&worst),
+                None => return false,
+            };
+        }
+        peers.insert(
+            public_key,
+            Peer {
+                public_key,
+                address,
+                record: Some(record),
+                validator: false,
+                score: 0,
+                last_asked: None,
+            },
+        );
+        true
+    }
+
+    pub fn record_success(&self, public_key: &PublicKeyBytes) {
+        if let Some(peer) = self.peers.lock().unwrap().get_mut(public_key) {
+            peer.score = (peer.score + 1).min(MAX_SCORE);
+        }
+    }
+
+    pub fn record_failure(&self, public_key: &PublicKeyBytes) {
+        let mut peers = self.peers.lock().unwrap();
+        if let Some(peer) = peers.get_mut(public_key) {
+            peer.score = (peer.score - FAILURE_PENALTY).max(MIN_SCORE - 1);
+            if peer.score < MIN_SCORE && !peer.validator {
+                peers.remove(public_key);
+            }
+        }
+    }
+
+    pub fn get(&self, public_key: &PublicKeyBytes) -> Option<Peer> {
+        self.peers.lock().unwrap().get(public_key).cloned()
+    }
+
+    /// The known peers, the best scored first.
+    pub fn peers(&self) -> Vec<Peer> {
+        let mut peers: Vec<_> = self.peers.lock().unwrap().values().cloned().collect();
+        peers.sort_by_key(|peer| -peer.score);
+        peers
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The records of up to `limit` of the best scored peers.
+    fn records(&self, limit: usize) -> Vec<SignedPeerInfo> {
+        self.peers()
+            .into_iter()
+            .filter_map(|peer| peer.record)
+            .take(limit)
+            .collect()
+    }
+
+    /// Up to `count` peers to ask for peers, the ones asked the longest ago first.
+    fn next_to_ask(&self, count: usize) -> Vec<Peer> {
+        let now = Instant::now();
+        let mut peers = self.peers.lock().unwrap();
+        let mut candidates: Vec<_> = peers
+            .values()
+            .filter(|peer| !is_quic_address(&peer.address))
+            .map(|peer| (peer.last_asked, -peer.score, peer.public_key))
+            .collect();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(count)
+            .filter_map(|(_, _, public_key)| {
+                let peer = peers.get_mut(&public_key)?;
+                peer.last_asked = Some(now);
+                Some(peer.clone())
+            })
+            .collect()
+    }
+}
+
+/// The discovery service of a node, answering with its own record and the ones of its peers.
+pub struct DiscoveryService {
+    table: Arc<PeerTable>,
+    own_record: Option<SignedPeerInfo>,
+    max_peers_per_response: usize,
+}
+
+#[async_trait::async_trait]
+impl Discovery for DiscoveryService {
+    async fn get_peers(
+        &self,
+        request: tonic::Request<discovery::PeersRequest>,
+    ) -> Result<tonic::Response<discovery::PeersResponse>, tonic::Status> {
+        if let Some(sender) = PeersRequest::try_from(request.into_inner())?.sender {
+            self.table.insert(sender);
+        }
+        let mut peers: Vec<_> = self.own_record.iter().cloned().collect();
+        peers.extend(self.table.records(self.max_peers_per_response));
+        Ok(tonic::Response::new(PeersResponse { peers }.into()))
+    }
+}
+
+/// Periodically asks peers for their peers, and serves the peers of the node to others.
+pub struct PeerDiscovery {
+    config: DiscoveryConfig,
+    table: Arc<PeerTable>,
+    own_record: Option<SignedPeerInfo>,
+    net_config: mysten_network::config::Config,
+    certificate: NodeCertificate,
+}
+
+impl PeerDiscovery {
+    /// Discover peers with `table`, announcing the node with `own_record` if it can be reached,
+    /// and authenticating with `certificate` when asking peers.
+    pub fn new(
+        config: DiscoveryConfig,
+        table: Arc<PeerTable>,
+        own_record: Option<SignedPeerInfo>,
+        certificate: NodeCertificate,
+    ) -> Self {
+        let mut net_config = mysten_network::config::Config::new();
+        net_config.connect_timeout = Some(Duration::from_secs(5));
+        net_config.request_timeout = Some(Duration::from_secs(5));
+        Self {
+            config,
+            table,
+            own_record,
+            net_config,
+            certificate,
+        }
+    }
+
+    pub fn table(&self) -> Arc<PeerTable> {
+        self.table.clone()
+    }
+
+    pub fn service(&self) -> DiscoveryServer<DiscoveryService> {
+        DiscoveryServer::new(DiscoveryService {
+            table: self.table.clone(),
+            own_record: self.own_record.clone(),
+            max_peers_per_response: self.config.max_peers_per_response,
+        })
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.config.interval);
+            let mut ask_seeds = true;
+            loop {
+                interval.tick().await;
+                let asked = self.ask_peers().await;
+                // Fall back to the seeds whenever no peer answered
+                if ask_seeds || asked == 0 {
+                    self.ask_seeds().await;
+                    ask_seeds = false;
+                }
+                debug!("Knows {} peers", self.table.len());
+            }
+        })
+    }
+
+    /// Ask some of the peers of the table, returns how many of them answered.
+    async fn ask_peers(&self) -> usize {
+        let peers = self.table.next_to_ask(self.config.fanout);
+        let exchanges = peers
+            .iter()
+            .map(|peer| self.exchange(&peer.address, Some(peer.public_key)));
+        let results = futures::future::join_all(exchanges).await;
+
+        let mut answered = 0;
+        for (peer, result) in peers.iter().zip(results) {
+            match result {
+                Ok(records) => {
+                    answered += 1;
+                    self.table.record_success(&peer.public_key);
+                    self.insert_all(records);
+                }
+                Err(error) => {
+                    debug!(peer =? peer.public_key, "Failed to ask peer for peers: {error}");
+                    self.table.record_failure(&peer.public_key);
+                }
+            }
+        }
+        answered
+    }
+
+    async fn ask_seeds(&self) {
+        for seed in &self.config.seed_peers {
+            match self.exchange(seed, None).await {
+                Ok(records) => self.insert_all(records),
+                Err(error) => info!("Failed to ask seed peer {seed} for peers: {error}"),
+            }
+        }
+    }
+
+    fn insert_all(&self, records: Vec<SignedPeerInfo>) {
+        let limit = self.config.max_peers_per_response;
+        for record in records.into_iter().take(limit) {
+            self.table.insert(record);
+        }
+    }
+
+    /// Ask the node at `address` for its peers, if it authenticates with the `server` key, or
+    /// whatever its key if not set.
+    async fn exchange(
+        &self,
+        address: &Multiaddr,
+        server: Option<PublicKeyBytes>,
+    ) -> anyhow::Result<Vec<SignedPeerInfo>> {
+        let channel = tcp::connect_lazy(&self.net_config, address, &self.certificate, server)?;
+        let request = PeersRequest {
+            sender: self.own_record.clone(),
+        };
+        let response = DiscoveryClient::new(channel)
+            .get_peers(discovery::PeersRequest::from(request))
+            .await?;
+        Ok(PeersResponse::try_from(response.into_inner())?.peers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::crypto::get_key_pair;
+
+    fn address(port: u16) -> Multiaddr {
+        format!("/ip4/127.0.0.1/tcp/{port}").parse().unwrap()
+    }
+
+    #[test]
+    fn test_insert_signed_records() {
+        let (_, own_key_pair) = get_key_pair();
+        let table = PeerTable::new(own_key_pair.public().into(), 10);
+
+        // The own record of the node is not a peer
+        assert!(!table.insert(sign_peer_info(&own_key_pair, &address(1))));
+
+        let (_, key_pair) = get_key_pair();
+        let public_key: PublicKeyBytes = key_pair.public().into();
+        let old_record = sign_peer_info(&key_pair, &address(2));
+        assert!(table.insert(old_record.clone()));
+        assert!(!table.insert(old_record.clone()));
+
+        let mut new_record = sign_peer_info(&key_pair, &address(3));
+        new_record.info.timestamp_ms = old_record.info.timestamp_ms + 1;
+        let new_record = SignedPeerInfo::new(new_record.info, &key_pair);
+        assert!(table.insert(new_record.clone()));
+        assert!(!table.insert(old_record));
+        assert_eq!(table.get(&public_key).unwrap().address, address(3));
+
+        // Records cannot be forged by someone else
+        let (_, forger) = get_key_pair();
+        let mut forged = new_record.info;
+        forged.address = address(4).to_vec();
+        forged.timestamp_ms += 1;
+        assert!(!table.insert(SignedPeerInfo::new(forged, &forger)));
+        assert_eq!(table.get(&public_key).unwrap().address, address(3));
+    }
+
+    #[test]
+    fn test_scores_and_eviction() {
+        let table = PeerTable::new(get_key_pair().1.public().into(), 3);
+        let validator: PublicKeyBytes = get_key_pair().1.public().into();
+        table.add_validator(validator, address(1));
+
+        let (_, good) = get_key_pair();
+        let good_key: PublicKeyBytes = good.public().into();
+        assert!(table.insert(sign_peer_info(&good, &address(2))));
+        table.record_success(&good_key);
+        let (_, bad) = get_key_pair();
+        let bad_key: PublicKeyBytes = bad.public().into();
+        assert!(table.insert(sign_peer_info(&bad, &address(3))));
+
+        // The table is full, the worst peer which is not a validator makes room
+        let (_, other) = get_key_pair();
+        assert!(table.insert(sign_peer_info(&other, &address(4))));
+        assert!(table.get(&bad_key).is_none());
+        assert!(table.get(&good_key).is_some());
+        assert_eq!(table.len(), 3);
+
+        // Validators stay in the table whatever their score
+        for _ in 0..10 {
+            table.record_failure(&validator);
+        }
+        assert!(table.get(&validator).unwrap().score < MIN_SCORE);
+        let other_key: PublicKeyBytes = other.public().into();
+        for _ in 0..10 {
+            table.record_failure(&other_key);
+        }
+        assert!(table.get(&other_key).is_none());
+        assert_eq!(table.peers()[0].public_key, good_key);
+        assert_eq!(table.len(), 2);
+    }
+}
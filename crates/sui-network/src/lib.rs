@@ -1,6 +1,16 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address;
 pub mod api;
+pub mod compression;
+pub mod convert;
+pub mod discovery;
+pub mod metrics;
+pub mod pool;
+pub mod quic;
+pub mod tcp;
+pub mod tls;
 
+pub use prost;
 pub use tonic;
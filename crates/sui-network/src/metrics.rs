@@ -0,0 +1,190 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metrics of the requests sent to each peer, to find the slow or flaky ones.
+//!
+//! Sizes are the ones of the encoded request and response messages, as sent over gRPC and QUIC,
+//! without the framing and the headers of the transports. QUIC servers also count the
+//! requests of their peers rejected for exceeding the maximum size of their kind.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry, HistogramVec,
+    IntCounterVec, Registry,
+};
+use prost::Message;
+
+#[derive(Clone)]
+pub struct NetworkMetrics {
+    /// Bytes sent to a peer, peer is a label
+    bytes_sent: IntCounterVec,
+    /// Bytes received from a peer, peer is a label
+    bytes_received: IntCounterVec,
+    /// Requests sent to a peer, peer and method are labels
+    requests: IntCounterVec,
+    /// Latency of the requests to a peer, until the whole response or the first item of a
+    /// stream is received, peer and method are labels
+    request_latency: HistogramVec,
+    /// Failed requests to a peer, peer and method are labels
+    errors: IntCounterVec,
+    /// Requests from a peer rejected for their size, peer and kind are labels
+    oversized_requests: IntCounterVec,
+}
+
+impl NetworkMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        Self {
+            bytes_sent: register_int_counter_vec_with_registry!(
+                "network_peer_bytes_sent",
+                "Number of bytes sent to a peer",
+                &["peer"],
+                registry,
+            )
+            .unwrap(),
+            bytes_received: register_int_counter_vec_with_registry!(
+                "network_peer_bytes_received",
+                "Number of bytes received from a peer",
+                &["peer"],
+                registry,
+            )
+            .unwrap(),
+            requests: register_int_counter_vec_with_registry!(
+                "network_peer_requests",
+                "Number of requests sent to a peer by method",
+                &["peer", "method"],
+                registry,
+            )
+            .unwrap(),
+            request_latency: register_histogram_vec_with_registry!(
+                "network_peer_request_latency",
+                "Latency of the requests to a peer by method",
+                &["peer", "method"],
+                registry,
+            )
+            .unwrap(),
+            errors: register_int_counter_vec_with_registry!(
+                "network_peer_errors",
+                "Number of failed requests to a peer by method",
+                &["peer", "method"],
+                registry,
+            )
+            .unwrap(),
+            oversized_requests: register_int_counter_vec_with_registry!(
+                "network_peer_oversized_requests",
+                "Number of requests from a peer rejected for exceeding their maximum size by kind",
+                &["peer", "kind"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    pub fn new_for_tests() -> Self {
+        Self::new(&Registry::new())
+    }
+
+    /// Start measuring the request to `peer` for `method`, when `request` is sent.
+    pub fn start<T: Message>(
+        self: &Arc<Self>,
+        peer: String,
+        method: &'static str,
+        request: &T,
+    ) -> RequestObserver {
+        self.requests.with_label_values(&[&peer, method]).inc();
+        self.bytes_sent
+            .with_label_values(&[&peer])
+            .inc_by(encoded_size(request));
+        RequestObserver {
+            metrics: self.clone(),
+            peer,
+            method,
+            start: Instant::now(),
+        }
+    }
+
+    /// Record a request from `peer` rejected for its size, of an unknown kind if it was
+    /// rejected before its kind could be read.
+    pub fn record_oversized_request(&self, peer: &str, kind: Option<&str>) {
+        self.oversized_requests
+            .with_label_values(&[peer, kind.unwrap_or("unknown")])
+            .inc();
+    }
+}
+
+/// The measures of a request in flight.
+pub struct RequestObserver {
+    metrics: Arc<NetworkMetrics>,
+    peer: String,
+    method: &'static str,
+    start: Instant,
+}
+
+impl RequestObserver {
+    /// Record the outcome of the request, with the response if it succeeded.
+    pub fn finish<T: Message, E>(&self, result: &Result<T, E>) {
+        let labels = [self.peer.as_str(), self.method];
+        self.metrics
+            .request_latency
+            .with_label_values(&labels)
+            .observe(self.start.elapsed().as_secs_f64());
+        match result {
+            Ok(response) => self.record_received(response),
+            Err(_) => self.metrics.errors.with_label_values(&labels).inc(),
+        }
+    }
+
+    /// Record an item of a stream of responses.
+    pub fn record_received<T: Message>(&self, item: &T) {
+        self.metrics
+            .bytes_received
+            .with_label_values(&[&self.peer])
+            .inc_by(encoded_size(item));
+    }
+
+    /// Record a failed item of a stream of responses.
+    pub fn record_error(&self) {
+        self.metrics
+            .errors
+            .with_label_values(&[&self.peer, self.method])
+            .inc();
+    }
+}
+
+fn encoded_size<T: Message>(value: &T) -> u64 {
+    value.encoded_len() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_metrics() {
+        let metrics = Arc::new(NetworkMetrics::new_for_tests());
+        let peer = "validator".to_owned();
+
+        let observer = metrics.start(peer.clone(), "object_info", &vec![0u8; 10]);
+        observer.finish::<_, ()>(&Ok(vec![0u8; 100]));
+        let observer = metrics.start(peer.clone(), "object_info", &vec![0u8; 10]);
+        observer.finish::<Vec<u8>, _>(&Err(()));
+
+        let labels = [peer.as_str(), "object_info"];
+        assert_eq!(metrics.requests.with_label_values(&labels).get(), 2);
+        assert_eq!(metrics.errors.with_label_values(&labels).get(), 1);
+        // Bytes are encoded in a field, after a key and a length of a byte each
+        assert_eq!(metrics.bytes_sent.with_label_values(&[&peer]).get(), 24);
+        assert_eq!(
+            metrics.bytes_received.with_label_values(&[&peer]).get(),
+            102
+        );
+        assert_eq!(
+            metrics
+                .request_latency
+                .with_label_values(&labels)
+                .get_sample_count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Connections to the nodes of the network, shared by all the clients of a process.
+//!
+//! There is a single connection per node, over gRPC or QUIC depending on its address, which
+//! multiplexes all the requests in flight to the node: HTTP/2 streams for gRPC, QUIC streams
+//! otherwise. Both are authenticated with the certificate of the QUIC connector of the pool.
+//! Requests wait while too many requests are already in flight to the same node, and connections
+//! without any request for a while are closed.
+//!
+//! Pools configured with a handshake send it on every new connection before any other request,
+//! and fail the requests to the nodes of another chain or which speak another protocol.
+
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+use multiaddr::Multiaddr;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OnceCell, OwnedSemaphorePermit, Semaphore};
+use tonic::transport::Channel;
+use tracing::debug;
+
+use sui_types::crypto::PublicKeyBytes;
+use sui_types::handshake::Handshake;
+
+use crate::api::{validator, ValidatorClient};
+use crate::metrics::NetworkMetrics;
+use crate::quic::{is_quic_address, QuicClient, QuicConnector};
+use crate::tcp;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PoolConfig {
+    /// Maximum number of requests in flight to a node, others wait for one of them to complete.
+    #[serde(default = "default_max_in_flight_per_host")]
+    pub max_in_flight_per_host: usize,
+    /// Connections without any request for this long are closed.
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: Duration,
+}
+
+fn default_max_in_flight_per_host() -> usize {
+    512
+}
+
+fn default_idle_timeout() -> Duration {
+    Duration::from_secs(300)
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_in_flight_per_host: default_max_in_flight_per_host(),
+            idle_timeout: default_idle_timeout(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum Connection {
+    Grpc(Channel),
+    Quic(QuicClient),
+}
+
+/// A connection checked out of the pool for a request, which is in flight until this is dropped.
+pub struct PooledConnection {
+    connection: Connection,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl From<Connection> for PooledConnection {
+    /// A connection which is not pooled, without a limit on the requests in flight.
+    fn from(connection: Connection) -> Self {
+        Self {
+            connection,
+            _permit: None,
+        }
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        &self.connection
+    }
+}
+
+struct Host {
+    connection: Connection,
+    in_flight: Arc<Semaphore>,
+    last_used: Instant,
+    // The protocol version negotiated with the node, once the handshake succeeded.
+    protocol_version: Arc<OnceCell<u32>>,
+}
+
+pub struct ConnectionPool {
+    config: PoolConfig,
+    net_config: mysten_network::config::Config,
+    quic_connector: QuicConnector,
+    metrics: Option<Arc<NetworkMetrics>>,
+    handshake: Option<Handshake>,
+    // By address and key, as connections are authenticated with the key of the node.
+    hosts: Mutex<HashMap<(Multiaddr, PublicKeyBytes), Host>>,
+}
+
+impl ConnectionPool {
+    /// Connections are made over gRPC with `net_config`, or over QUIC with `quic_connector`,
+    /// and authenticated with the certificate of `quic_connector` either way.
+    pub fn new(
+        config: PoolConfig,
+        net_config: mysten_network::config::Config,
+        quic_connector: QuicConnector,
+    ) -> Self {
+        Self {
+            config,
+            net_config,
+            quic_connector,
+            metrics: None,
+            handshake: None,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record the requests to every node in `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<NetworkMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn metrics(&self) -> Option<&Arc<NetworkMetrics>> {
+        self.metrics.as_ref()
+    }
+
+    /// Send `handshake` to every node when connecting to it.
+    pub fn with_handshake(mut self, handshake: Handshake) -> Self {
+        self.handshake = Some(handshake);
+        self
+    }
+
+    /// The connection to the node at `address`, which must authenticate as `name`.
+    /// Waits while `max_in_flight_per_host` requests are in flight to the node, and for the
+    /// handshake on new connections.
+    pub async fn checkout(
+        &self,
+        address: &Multiaddr,
+        name: PublicKeyBytes,
+    ) -> anyhow::Result<PooledConnection> {
+        let (connection, in_flight, protocol_version) = {
+            let now = Instant::now();
+            let mut hosts = self.hosts.lock().unwrap();
+            self.evict_idle(&mut hosts, now);
+
+            let key = (address.clone(), name);
+            let host = match hosts.get_mut(&key) {
+                Some(host) => host,
+                None => {
+                    let connection = self.connect_lazy(address, name)?;
+                    let in_flight = Arc::new(Semaphore::new(self.config.max_in_flight_per_host));
+                    let host = Host {
+                        connection,
+                        in_flight,
+                        last_used: now,
+                        protocol_version: Arc::new(OnceCell::new()),
+                    };
+                    hosts.entry(key).or_insert(host)
+                }
+            };
+            host.last_used = now;
+            (
+                host.connection.clone(),
+                host.in_flight.clone(),
+                host.protocol_version.clone(),
+            )
+        };
+
+        let permit = in_flight.acquire_owned().await?;
+        if let Some(handshake) = &self.handshake {
+            // Tried again on the next request if it failed.
+            protocol_version
+                .get_or_try_init(|| shake_hands(&connection, address, handshake))
+                .await?;
+        }
+        Ok(PooledConnection {
+            connection,
+            _permit: Some(permit),
+        })
+    }
+
+    /// The number of nodes with an open connection.
+    pub fn len(&self) -> usize {
+        self.hosts.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn connect_lazy(
+        &self,
+        address: &Multiaddr,
+        name: PublicKeyBytes,
+    ) -> anyhow::Result<Connection> {
+        if is_quic_address(address) {
+            let client = self.quic_connector.connect_lazy(address, name)?;
+            Ok(Connection::Quic(client))
+        } else {
+            let certificate = self.quic_connector.certificate();
+            let channel = tcp::connect_lazy(&self.net_config, address, certificate, Some(name))?;
+            Ok(Connection::Grpc(channel))
+        }
+    }
+
+    /// The protocol version negotiated with the node, if already connected to it.
+    pub fn protocol_version(&self, address: &Multiaddr, name: PublicKeyBytes) -> Option<u32> {
+        let hosts = self.hosts.lock().unwrap();
+        let host = hosts.get(&(address.clone(), name))?;
+        host.protocol_version.get().copied()
+    }
+
+    // Closes the connections to the nodes without any request in flight nor recent request.
+    fn evict_idle(&self, hosts: &mut HashMap<(Multiaddr, PublicKeyBytes), Host>, now: Instant) {
+        let max_in_flight = self.config.max_in_flight_per_host;
+        hosts.retain(|(address, _), host| {
+            let idle = now.duration_since(host.last_used) >= self.config.idle_timeout
+                && host.in_flight.available_permits() == max_in_flight;
+            if idle {
+                debug!("Closing the idle connection to {address}");
+            }
+            !idle
+        });
+    }
+}
+
+async fn shake_hands(
+    connection: &Connection,
+    address: &Multiaddr,
+    handshake: &Handshake,
+) -> anyhow::Result<u32> {
+    let request = validator::Handshake::from(handshake.clone());
+    let response = match connection {
+        Connection::Grpc(channel) => ValidatorClient::new(channel.clone())
+            .handshake(request)
+            .await
+            .map(tonic::Response::into_inner),
+        Connection::Quic(client) => client.handshake(request).await,
+    }
+    .map_err(|status| anyhow!("Handshake with {address} failed: {}", status.message()))?;
+    let response = Handshake::try_from(response)
+        .map_err(|e| anyhow!("Handshake with {address} failed: {e}"))?;
+    let protocol_version = handshake
+        .negotiate(&response)
+        .map_err(|e| anyhow!("Handshake with {address} failed: {e}"))?;
+    debug!("Speaking protocol version {protocol_version} with {address}");
+    Ok(protocol_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::crypto::{get_key_pair, KeypairTraits};
+
+    fn pool(config: PoolConfig) -> ConnectionPool {
+        ConnectionPool::new(
+            config,
+            mysten_network::config::Config::new(),
+            QuicConnector::ephemeral(Default::default()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_connections_are_shared() {
+        let pool = pool(PoolConfig::default());
+        let name: PublicKeyBytes = get_key_pair().1.public().into();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/8080/http".parse().unwrap();
+        let quic_address: Multiaddr = "/ip4/127.0.0.1/udp/8080/quic".parse().unwrap();
+
+        let first = pool.checkout(&address, name).await.unwrap();
+        let second = pool.checkout(&address, name).await.unwrap();
+        assert!(matches!(*first, Connection::Grpc(_)));
+        assert!(matches!(*second, Connection::Grpc(_)));
+        assert_eq!(pool.len(), 1);
+
+        let quic = pool.checkout(&quic_address, name).await.unwrap();
+        assert!(matches!(*quic, Connection::Quic(_)));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_limit_and_idle_eviction() {
+        let pool = pool(PoolConfig {
+            max_in_flight_per_host: 1,
+            idle_timeout: Duration::from_millis(100),
+        });
+        let name: PublicKeyBytes = get_key_pair().1.public().into();
+        let address: Multiaddr = "/ip4/127.0.0.1/tcp/8080/http".parse().unwrap();
+        let other: Multiaddr = "/ip4/127.0.0.1/tcp/8081/http".parse().unwrap();
+
+        let in_flight = pool.checkout(&address, name).await.unwrap();
+        let waiting =
+            tokio::time::timeout(Duration::from_millis(50), pool.checkout(&address, name));
+        assert!(waiting.await.is_err());
+
+        // Connections with requests in flight are kept however long they have not been used
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let _other = pool.checkout(&other, name).await.unwrap();
+        assert_eq!(pool.len(), 2);
+
+        drop(in_flight);
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let _other = pool.checkout(&other, name).await.unwrap();
+        assert_eq!(pool.len(), 1);
+    }
+}
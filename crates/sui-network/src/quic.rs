@@ -0,0 +1,1005 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A QUIC transport for the validator interface, offered alongside gRPC over TCP.
+//!
+//! Every request is sent on its own bidirectional QUIC stream of a connection shared by all the
+//! requests to a validator: the client writes the request and finishes its side of the stream,
+//! the server answers with length-prefixed frames, a single one for unary requests and one per
+//! item for the batch stream, and finishes the stream. Requests and frames are the Protocol
+//! Buffers messages of `proto/quic.proto`, which carry the messages of the gRPC service. A lost
+//! packet only delays the requests of the streams it carries, unlike with HTTP/2 over a single TCP
+//! connection.
+//!
+//! Clients and servers authenticate each other with their key pairs, see [`crate::tls`]. Clients
+//! resume their sessions with 0-RTT when reconnecting and servers accept migrated connections,
+//! both can be turned off in [`QuicConfig`]. Nodes use this transport when their network address
+//! is a QUIC multiaddr, e.g. `/dns/localhost/udp/8080/quic`.
+//!
+//! Connections can also compress their large messages, when both peers are configured with a
+//! common algorithm, see [`crate::compression`].
+//!
+//! Servers read requests as they arrive and reject the ones exceeding the maximum size of their
+//! kind, configured in [`QuicConfig`], as soon as they do, answering with a resource exhausted
+//! status rather than buffering them whole.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{stream::BoxStream, StreamExt};
+use multiaddr::{Multiaddr, Protocol};
+use prost::Message;
+use quinn::{
+    ClientConfig, Connection, Endpoint, EndpointConfig, IdleTimeout, Incoming, NewConnection,
+    ReadExactError, RecvStream, SendStream, ServerConfig, TransportConfig, VarInt, WriteError,
+};
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol as SocketProtocol, Socket, Type};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use sui_types::crypto::PublicKeyBytes;
+
+use crate::address::Transport;
+use crate::api::quic::{frame, request, Frame, Request, Status as WireStatus};
+use crate::api::validator::{
+    AccountInfoRequest, AccountInfoResponse, BatchInfoRequest, BatchInfoResponseItem,
+    CertifiedTransaction, CheckpointRequest, CheckpointResponse, Handshake, ObjectInfoRequest,
+    ObjectInfoResponse, Transaction, TransactionInfoRequest, TransactionInfoResponse,
+};
+use crate::api::Validator;
+use crate::compression::{self, Compression};
+use crate::metrics::NetworkMetrics;
+use crate::tls::{self, AllowList, NodeCertificate, CERTIFICATE_NAME};
+
+/// The application protocol negotiated by the TLS handshake of the connections.
+pub const ALPN_PROTOCOL: &[u8] = b"sui-validator/2";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct QuicConfig {
+    /// Maximum number of requests in flight on a connection.
+    #[serde(default = "default_max_concurrent_streams")]
+    pub max_concurrent_streams: u32,
+    /// Connections without any traffic for this long are closed.
+    #[serde(default = "default_idle_timeout")]
+    pub idle_timeout: Duration,
+    #[serde(default = "default_keep_alive_interval")]
+    pub keep_alive_interval: Duration,
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: Duration,
+    /// Maximum size of a request or of a response frame, in bytes.
+    #[serde(default = "default_max_message_size")]
+    pub max_message_size: usize,
+    /// Maximum sizes of the requests by kind, in bytes, bounded by `max_message_size`. Kinds not
+    /// listed have the defaults of [`RequestKind::default_max_size`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub max_request_sizes: BTreeMap<RequestKind, usize>,
+    /// Send the first requests of a resumed session along with the handshake. Such requests can
+    /// be replayed by an attacker, which is harmless for the validator interface as all its
+    /// requests are idempotent.
+    #[serde(default = "default_true")]
+    pub enable_0rtt: bool,
+    /// Keep connections of clients whose address changed, e.g. after switching networks.
+    #[serde(default = "default_true")]
+    pub enable_migration: bool,
+    /// Compression algorithms to negotiate with peers, in order of preference. Clients offering
+    /// any of them always wait for the handshake to complete, as they cannot send compressed
+    /// requests with 0-RTT before knowing what the server accepted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compression: Vec<Compression>,
+    /// Messages smaller than this, in bytes, are sent uncompressed.
+    #[serde(default = "default_compression_threshold")]
+    pub compression_threshold: usize,
+}
+
+fn default_max_concurrent_streams() -> u32 {
+    1_000
+}
+
+fn default_idle_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_keep_alive_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_request_timeout() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_max_message_size() -> usize {
+    64 << 20
+}
+
+fn default_compression_threshold() -> usize {
+    16 << 10
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for QuicConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_streams: default_max_concurrent_streams(),
+            idle_timeout: default_idle_timeout(),
+            keep_alive_interval: default_keep_alive_interval(),
+            request_timeout: default_request_timeout(),
+            max_message_size: default_max_message_size(),
+            max_request_sizes: BTreeMap::new(),
+            enable_0rtt: true,
+            enable_migration: true,
+            compression: vec![],
+            compression_threshold: default_compression_threshold(),
+        }
+    }
+}
+
+impl QuicConfig {
+    fn transport_config(&self) -> TransportConfig {
+        let mut transport = TransportConfig::default();
+        let idle_timeout = u32::try_from(self.idle_timeout.as_millis()).unwrap_or(u32::MAX);
+        transport
+            .max_concurrent_bidi_streams(VarInt::from_u32(self.max_concurrent_streams))
+            .max_concurrent_uni_streams(VarInt::from_u32(0))
+            .max_idle_timeout(Some(IdleTimeout::from(VarInt::from_u32(idle_timeout))))
+            .keep_alive_interval(Some(self.keep_alive_interval));
+        transport
+    }
+
+    /// The application protocols to negotiate, the ones with compression first.
+    fn alpn_protocols(&self) -> Vec<Vec<u8>> {
+        self.compression
+            .iter()
+            .map(Compression::alpn_protocol)
+            .chain(std::iter::once(ALPN_PROTOCOL.to_vec()))
+            .collect()
+    }
+
+    /// The maximum size of the requests of `kind`, in bytes.
+    pub fn max_request_size(&self, kind: RequestKind) -> usize {
+        self.max_request_sizes
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_max_size(self.max_message_size))
+            .min(self.max_message_size)
+    }
+
+    fn framing(&self, compression: Option<Compression>) -> Framing {
+        Framing {
+            compression,
+            compression_threshold: self.compression_threshold,
+            max_message_size: self.max_message_size,
+            max_request_sizes: RequestKind::ALL.map(|kind| self.max_request_size(kind)),
+        }
+    }
+}
+
+/// How the messages of a connection are encoded.
+#[derive(Clone, Copy)]
+struct Framing {
+    compression: Option<Compression>,
+    compression_threshold: usize,
+    max_message_size: usize,
+    // Indexed by request kind.
+    max_request_sizes: [usize; RequestKind::ALL.len()],
+}
+
+impl Framing {
+    fn encode<T: Message>(&self, value: &T) -> io::Result<Vec<u8>> {
+        compression::encode(
+            self.compression,
+            self.compression_threshold,
+            value.encode_to_vec(),
+        )
+    }
+
+    fn decode<T: Message + Default>(&self, bytes: Vec<u8>) -> io::Result<T> {
+        let bytes = compression::decode(self.compression, bytes, self.max_message_size)?;
+        T::decode(bytes.as_slice()).map_err(invalid_data)
+    }
+
+    fn max_request_size(&self, kind: RequestKind) -> usize {
+        self.max_request_sizes[kind as usize]
+    }
+
+    fn check_request_size(&self, kind: RequestKind, size: usize) -> Result<(), MessageTooLarge> {
+        let max_size = self.max_request_size(kind);
+        if size > max_size {
+            return Err(MessageTooLarge {
+                kind: Some(kind),
+                size,
+                max_size,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The compression negotiated by the handshake of `connection`, if any.
+fn negotiated_compression(connection: &Connection) -> Option<Compression> {
+    connection
+        .handshake_data()
+        .and_then(|data| data.downcast::<quinn::crypto::rustls::HandshakeData>().ok())
+        .and_then(|data| data.protocol)
+        .and_then(|protocol| Compression::negotiated(&protocol))
+}
+
+/// Whether the validator at `address` is reached over QUIC rather than gRPC over TCP.
+pub fn is_quic_address(address: &Multiaddr) -> bool {
+    address
+        .iter()
+        .any(|protocol| matches!(protocol, Protocol::Quic))
+}
+
+/// Resolve a `/{ip4,ip6,dns,dns4,dns6}/<host>/udp/<port>/quic` address.
+pub async fn resolve_quic_address(address: &Multiaddr) -> io::Result<SocketAddr> {
+    let endpoint = crate::address::Endpoint::parse(address)?;
+    if endpoint.transport != Transport::Udp || !is_quic_address(address) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "Invalid QUIC address {address}, expected /<ip4|ip6|dns>/<host>/udp/<port>/quic"
+            ),
+        ));
+    }
+    endpoint.resolve_async().await
+}
+
+fn to_quic_address(address: SocketAddr) -> Multiaddr {
+    crate::address::to_multiaddr(address, Transport::Udp, &[Protocol::Quic])
+}
+
+/// A UDP socket bound to `address`. Sockets bound to the unspecified IPv6 address also receive
+/// the IPv4 traffic, whatever the default of the platform.
+fn bind_socket(address: SocketAddr) -> io::Result<std::net::UdpSocket> {
+    let socket = Socket::new(
+        Domain::for_address(address),
+        Type::DGRAM,
+        Some(SocketProtocol::UDP),
+    )?;
+    if let SocketAddr::V6(v6) = address {
+        if v6.ip().is_unspecified() {
+            socket.set_only_v6(false)?;
+        }
+    }
+    socket.bind(&address.into())?;
+    Ok(socket.into())
+}
+
+impl request::Body {
+    fn kind(&self) -> RequestKind {
+        match self {
+            request::Body::Transaction(_) => RequestKind::Transaction,
+            request::Body::Certificate(_) => RequestKind::Certificate,
+            request::Body::AccountInfo(_) => RequestKind::AccountInfo,
+            request::Body::ObjectInfo(_) => RequestKind::ObjectInfo,
+            request::Body::TransactionInfo(_) => RequestKind::TransactionInfo,
+            request::Body::Checkpoint(_) => RequestKind::Checkpoint,
+            request::Body::BatchInfo(_) => RequestKind::BatchInfo,
+            request::Body::Handshake(_) => RequestKind::Handshake,
+        }
+    }
+}
+
+/// The kinds of requests, in the order of the field numbers of `Request`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RequestKind {
+    Transaction,
+    Certificate,
+    AccountInfo,
+    ObjectInfo,
+    TransactionInfo,
+    Checkpoint,
+    BatchInfo,
+    Handshake,
+}
+
+impl RequestKind {
+    const ALL: [RequestKind; 8] = [
+        RequestKind::Transaction,
+        RequestKind::Certificate,
+        RequestKind::AccountInfo,
+        RequestKind::ObjectInfo,
+        RequestKind::TransactionInfo,
+        RequestKind::Checkpoint,
+        RequestKind::BatchInfo,
+        RequestKind::Handshake,
+    ];
+
+    /// Transactions and certificates can be as large as the packages they publish, the other
+    /// requests are a few references and are limited to 64 KiB.
+    pub fn default_max_size(&self, max_message_size: usize) -> usize {
+        match self {
+            RequestKind::Transaction | RequestKind::Certificate => max_message_size,
+            _ => 64 << 10,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequestKind::Transaction => "transaction",
+            RequestKind::Certificate => "certificate",
+            RequestKind::AccountInfo => "account_info",
+            RequestKind::ObjectInfo => "object_info",
+            RequestKind::TransactionInfo => "transaction_info",
+            RequestKind::Checkpoint => "checkpoint",
+            RequestKind::BatchInfo => "batch_info",
+            RequestKind::Handshake => "handshake",
+        }
+    }
+
+    /// The kind of the request starting with `bytes`, from the number of its field, which is
+    /// encoded first in the key of the field, a single byte for numbers below 16.
+    fn of_encoded(bytes: &[u8]) -> Option<Self> {
+        let key = *bytes.first()?;
+        // The bodies of requests are messages, of the length-delimited wire type.
+        if key & 0x7 != 2 {
+            return None;
+        }
+        let number = usize::from(key >> 3);
+        Self::ALL.get(number.checked_sub(1)?).copied()
+    }
+}
+
+impl fmt::Display for RequestKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request rejected for exceeding the maximum size of its kind. Requests rejected while being
+/// received are at least `size` bytes.
+#[derive(Clone, Debug)]
+pub struct MessageTooLarge {
+    pub kind: Option<RequestKind>,
+    pub size: usize,
+    pub max_size: usize,
+}
+
+impl fmt::Display for MessageTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Some(kind) => write!(f, "Request {kind}")?,
+            None => write!(f, "Request")?,
+        }
+        write!(
+            f,
+            " of {} bytes exceeds its maximum size of {} bytes",
+            self.size, self.max_size
+        )
+    }
+}
+
+impl std::error::Error for MessageTooLarge {}
+
+impl From<MessageTooLarge> for io::Error {
+    fn from(error: MessageTooLarge) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+impl From<MessageTooLarge> for tonic::Status {
+    fn from(error: MessageTooLarge) -> Self {
+        tonic::Status::resource_exhausted(error.to_string())
+    }
+}
+
+/// The `MessageTooLarge` error behind `error`, if any.
+fn message_too_large(error: &io::Error) -> Option<&MessageTooLarge> {
+    error.get_ref()?.downcast_ref()
+}
+
+impl From<tonic::Status> for WireStatus {
+    fn from(status: tonic::Status) -> Self {
+        Self {
+            code: status.code() as i32,
+            message: status.message().to_owned(),
+        }
+    }
+}
+
+impl From<WireStatus> for tonic::Status {
+    fn from(status: WireStatus) -> Self {
+        tonic::Status::new(tonic::Code::from_i32(status.code), status.message)
+    }
+}
+
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// The frame of the response of a request, or of the status it failed with.
+fn response_frame<T: Message>(response: Result<T, tonic::Status>) -> Frame {
+    let result = match response {
+        Ok(response) => frame::Result::Message(response.encode_to_vec()),
+        Err(status) => frame::Result::Status(status.into()),
+    };
+    Frame {
+        result: Some(result),
+    }
+}
+
+/// The response carried by `frame`, or the status the request failed with.
+fn frame_response<T: Message + Default>(frame: Frame) -> Result<T, tonic::Status> {
+    match frame.result {
+        Some(frame::Result::Message(bytes)) => {
+            T::decode(bytes.as_slice()).map_err(|e| tonic::Status::internal(e.to_string()))
+        }
+        Some(frame::Result::Status(status)) => Err(status.into()),
+        None => Err(tonic::Status::internal("Empty response frame")),
+    }
+}
+
+async fn write_frame(send: &mut SendStream, frame: &Frame, framing: Framing) -> io::Result<()> {
+    let bytes = framing.encode(frame)?;
+    let length = u32::try_from(bytes.len()).map_err(invalid_data)?;
+    send.write_all(&length.to_be_bytes()).await?;
+    send.write_all(&bytes).await?;
+    Ok(())
+}
+
+/// Read the next frame of `recv`, `None` once the stream is finished.
+async fn read_frame(recv: &mut RecvStream, framing: Framing) -> io::Result<Option<Frame>> {
+    let max_message_size = framing.max_message_size;
+    let mut length = [0; 4];
+    match recv.read_exact(&mut length).await {
+        Ok(()) => (),
+        Err(ReadExactError::FinishedEarly) => return Ok(None),
+        Err(ReadExactError::ReadError(e)) => return Err(e.into()),
+    }
+    let length = u32::from_be_bytes(length) as usize;
+    if length > max_message_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Frame of {length} bytes exceeds the maximum message size of {max_message_size} bytes"),
+        ));
+    }
+    let mut bytes = vec![0; length];
+    recv.read_exact(&mut bytes).await.map_err(|e| match e {
+        ReadExactError::FinishedEarly => io::Error::from(io::ErrorKind::UnexpectedEof),
+        ReadExactError::ReadError(e) => e.into(),
+    })?;
+    framing.decode(bytes).map(Some)
+}
+
+/// Read the request of a stream, rejecting it as soon as it exceeds the maximum size of its kind.
+async fn read_request(recv: &mut RecvStream, framing: Framing) -> io::Result<Request> {
+    let mut bytes = Vec::new();
+    let mut buffer = vec![0; 16 << 10];
+    let mut kind = None;
+    let mut max_size = framing.max_message_size;
+    while let Some(read) = recv.read(&mut buffer).await.map_err(invalid_data)? {
+        bytes.extend_from_slice(&buffer[..read]);
+        if kind.is_none() {
+            kind = compression::uncompressed(framing.compression, &bytes)
+                .and_then(RequestKind::of_encoded);
+            if let Some(kind) = kind {
+                max_size = framing.max_request_size(kind);
+            }
+        }
+        if bytes.len() > max_size {
+            return Err(MessageTooLarge {
+                kind,
+                size: bytes.len(),
+                max_size,
+            }
+            .into());
+        }
+    }
+    // The kind of compressed requests is only known once they are decompressed, which is bounded
+    // by the maximum message size.
+    let bytes = compression::decode(framing.compression, bytes, framing.max_message_size)?;
+    if let Some(kind) = RequestKind::of_encoded(&bytes) {
+        framing.check_request_size(kind, bytes.len())?;
+    }
+    Request::decode(bytes.as_slice()).map_err(invalid_data)
+}
+
+/// A QUIC endpoint serving the validator interface.
+pub struct QuicServer {
+    endpoint: Endpoint,
+    incoming: Incoming,
+    local_addr: Multiaddr,
+    config: QuicConfig,
+    metrics: Option<Arc<NetworkMetrics>>,
+}
+
+impl QuicServer {
+    /// Bind a server authenticated with `certificate`, accepting the `allowed_clients`.
+    pub async fn bind(
+        address: &Multiaddr,
+        config: &QuicConfig,
+        certificate: &NodeCertificate,
+        allowed_clients: AllowList,
+    ) -> io::Result<Self> {
+        let socket_address = resolve_quic_address(address).await?;
+
+        let mut crypto = tls::server_config(certificate, allowed_clients).map_err(invalid_data)?;
+        // The server picks the first of its protocols offered by the client.
+        crypto.alpn_protocols = config.alpn_protocols();
+        crypto.max_early_data_size = if config.enable_0rtt { u32::MAX } else { 0 };
+
+        let mut server_config = ServerConfig::with_crypto(Arc::new(crypto));
+        server_config.transport = Arc::new(config.transport_config());
+        server_config.migration(config.enable_migration);
+
+        let (endpoint, incoming) = Endpoint::new(
+            EndpointConfig::default(),
+            Some(server_config),
+            bind_socket(socket_address)?,
+        )?;
+        let local_addr = to_quic_address(endpoint.local_addr()?);
+        Ok(Self {
+            endpoint,
+            incoming,
+            local_addr,
+            config: config.clone(),
+            metrics: None,
+        })
+    }
+
+    /// Record the requests rejected for their size in `metrics`.
+    pub fn with_metrics(mut self, metrics: Arc<NetworkMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn local_addr(&self) -> &Multiaddr {
+        &self.local_addr
+    }
+
+    /// Serve `service` until the endpoint is closed. Nodes which do not run a validator service,
+    /// like fullnodes, answer every request with an unimplemented status.
+    pub async fn serve<V: Validator>(self, service: Option<V>) -> io::Result<()> {
+        let Self {
+            endpoint: _endpoint,
+            mut incoming,
+            local_addr,
+            config,
+            metrics,
+        } = self;
+        info!("Listening to QUIC traffic on {local_addr}");
+        let service = service.map(Arc::new);
+        while let Some(connecting) = incoming.next().await {
+            let service = service.clone();
+            let config = config.clone();
+            let metrics = metrics.clone();
+            tokio::spawn(async move {
+                // Requests sent with 0-RTT are only processed once the handshake authenticated
+                // the client.
+                match connecting.await {
+                    Ok(connection) => serve_connection(connection, service, config, metrics).await,
+                    Err(e) => debug!("QUIC handshake failed: {e}"),
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+async fn serve_connection<V: Validator>(
+    connection: NewConnection,
+    service: Option<Arc<V>>,
+    config: QuicConfig,
+    metrics: Option<Arc<NetworkMetrics>>,
+) {
+    let remote_address = connection.connection.remote_address();
+    let client = connection
+        .connection
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<rustls::Certificate>>().ok())
+        .and_then(|certificates| tls::peer_public_key(certificates.first()?).ok());
+    let framing = config.framing(negotiated_compression(&connection.connection));
+    debug!(
+        "QUIC connection from {remote_address}, authenticated as {client:?}, compression: {:?}",
+        framing.compression
+    );
+    let mut bi_streams = connection.bi_streams;
+    while let Some(stream) = bi_streams.next().await {
+        let (send, recv) = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                debug!("QUIC connection from {remote_address} closed: {e}");
+                return;
+            }
+        };
+        let service = service.clone();
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_stream(send, recv, service, framing).await {
+                debug!("QUIC request from {remote_address} failed: {e}");
+                if let (Some(metrics), Some(error)) = (&metrics, message_too_large(&e)) {
+                    let peer = match client {
+                        Some(client) => client.to_string(),
+                        None => remote_address.to_string(),
+                    };
+                    metrics.record_oversized_request(&peer, error.kind.map(|kind| kind.as_str()));
+                }
+            }
+        });
+    }
+}
+
+async fn serve_stream<V: Validator>(
+    mut send: SendStream,
+    mut recv: RecvStream,
+    service: Option<Arc<V>>,
+    framing: Framing,
+) -> io::Result<()> {
+    let request = match read_request(&mut recv, framing).await {
+        Ok(request) => request,
+        Err(e) => {
+            if let Some(error) = message_too_large(&e) {
+                // Stop the client sending the rest of the request, and tell it why.
+                let _ = recv.stop(VarInt::from_u32(0));
+                let status = tonic::Status::from(error.clone());
+                write_frame(&mut send, &response_frame::<()>(Err(status)), framing).await?;
+                send.finish().await?;
+            }
+            return Err(e);
+        }
+    };
+    let service = match service {
+        Some(service) => service,
+        None => {
+            let status = tonic::Status::unimplemented("Not a validator");
+            write_frame(&mut send, &response_frame::<()>(Err(status)), framing).await?;
+            return send.finish().await.map_err(Into::into);
+        }
+    };
+    let body = match request.body {
+        Some(body) => body,
+        None => {
+            let status = tonic::Status::invalid_argument("Empty request");
+            write_frame(&mut send, &response_frame::<()>(Err(status)), framing).await?;
+            return send.finish().await.map_err(Into::into);
+        }
+    };
+
+    match body {
+        request::Body::Handshake(handshake) => {
+            let response = service.handshake(tonic::Request::new(handshake)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::Transaction(transaction) => {
+            let response = service.transaction(tonic::Request::new(transaction)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::Certificate(certificate) => {
+            let response = service
+                .handle_certificate(tonic::Request::new(certificate))
+                .await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::AccountInfo(request) => {
+            let response = service.account_info(tonic::Request::new(request)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::ObjectInfo(request) => {
+            let response = service.object_info(tonic::Request::new(request)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::TransactionInfo(request) => {
+            let response = service.transaction_info(tonic::Request::new(request)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::Checkpoint(request) => {
+            let response = service.checkpoint(tonic::Request::new(request)).await;
+            write_response(&mut send, response, framing).await?;
+        }
+        request::Body::BatchInfo(request) => {
+            match service.batch_info(tonic::Request::new(request)).await {
+                Ok(response) => {
+                    let mut items = response.into_inner();
+                    while let Some(item) = items.next().await {
+                        write_frame(&mut send, &response_frame(item), framing).await?;
+                    }
+                }
+                Err(status) => {
+                    write_frame(&mut send, &response_frame::<()>(Err(status)), framing).await?;
+                }
+            }
+        }
+    }
+    send.finish().await.map_err(Into::into)
+}
+
+async fn write_response<T: Message>(
+    send: &mut SendStream,
+    response: Result<tonic::Response<T>, tonic::Status>,
+    framing: Framing,
+) -> io::Result<()> {
+    let frame = response_frame(response.map(tonic::Response::into_inner));
+    write_frame(send, &frame, framing).await
+}
+
+/// A client of the validator interface over QUIC, the counterpart of `ValidatorClient`.
+///
+/// The connection is established on the first request and re-established when it is lost.
+#[derive(Clone)]
+pub struct QuicClient {
+    inner: Arc<QuicClientInner>,
+}
+
+struct QuicClientInner {
+    address: Multiaddr,
+    config: QuicConfig,
+    client_config: ClientConfig,
+    state: Mutex<QuicClientState>,
+}
+
+#[derive(Default)]
+struct QuicClientState {
+    // Kept when reconnecting so that the client keeps its local address.
+    endpoint: Option<Endpoint>,
+    connection: Option<(Connection, Framing)>,
+}
+
+/// Creates the QUIC clients of a node, authenticated with its certificate.
+#[derive(Clone)]
+pub struct QuicConnector {
+    config: QuicConfig,
+    certificate: NodeCertificate,
+}
+
+impl QuicConnector {
+    pub fn new(config: QuicConfig, certificate: NodeCertificate) -> Self {
+        Self {
+            config,
+            certificate,
+        }
+    }
+
+    /// A connector authenticated with a key pair generated for it, for clients which are not
+    /// nodes, like gateways.
+    pub fn ephemeral(config: QuicConfig) -> Self {
+        Self::new(config, NodeCertificate::ephemeral())
+    }
+
+    /// The certificate the clients authenticate with, which they use over gRPC too.
+    pub fn certificate(&self) -> &NodeCertificate {
+        &self.certificate
+    }
+
+    /// A client of the validator at `address`, which must authenticate with the `server` key.
+    pub fn connect_lazy(
+        &self,
+        address: &Multiaddr,
+        server: PublicKeyBytes,
+    ) -> io::Result<QuicClient> {
+        if !is_quic_address(address) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{address} is not a QUIC address"),
+            ));
+        }
+        let mut crypto = tls::client_config(&self.certificate, server).map_err(invalid_data)?;
+        crypto.alpn_protocols = self.config.alpn_protocols();
+        crypto.enable_early_data = self.config.enable_0rtt;
+        let mut client_config = ClientConfig::new(Arc::new(crypto));
+        client_config.transport = Arc::new(self.config.transport_config());
+
+        Ok(QuicClient {
+            inner: Arc::new(QuicClientInner {
+                address: address.clone(),
+                config: self.config.clone(),
+                client_config,
+                state: Mutex::new(QuicClientState::default()),
+            }),
+        })
+    }
+
+    pub async fn connect(
+        &self,
+        address: &Multiaddr,
+        server: PublicKeyBytes,
+    ) -> io::Result<QuicClient> {
+        let client = self.connect_lazy(address, server)?;
+        client
+            .connection()
+            .await
+            .map_err(|status| io::Error::new(io::ErrorKind::ConnectionRefused, status.message()))?;
+        Ok(client)
+    }
+}
+
+impl QuicClient {
+    pub async fn handshake(&self, handshake: Handshake) -> Result<Handshake, tonic::Status> {
+        self.unary(request::Body::Handshake(handshake)).await
+    }
+
+    pub async fn transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<TransactionInfoResponse, tonic::Status> {
+        self.unary(request::Body::Transaction(transaction)).await
+    }
+
+    pub async fn handle_certificate(
+        &self,
+        certificate: CertifiedTransaction,
+    ) -> Result<TransactionInfoResponse, tonic::Status> {
+        self.unary(request::Body::Certificate(certificate)).await
+    }
+
+    pub async fn account_info(
+        &self,
+        request: AccountInfoRequest,
+    ) -> Result<AccountInfoResponse, tonic::Status> {
+        self.unary(request::Body::AccountInfo(request)).await
+    }
+
+    pub async fn object_info(
+        &self,
+        request: ObjectInfoRequest,
+    ) -> Result<ObjectInfoResponse, tonic::Status> {
+        self.unary(request::Body::ObjectInfo(request)).await
+    }
+
+    pub async fn transaction_info(
+        &self,
+        request: TransactionInfoRequest,
+    ) -> Result<TransactionInfoResponse, tonic::Status> {
+        self.unary(request::Body::TransactionInfo(request)).await
+    }
+
+    pub async fn checkpoint(
+        &self,
+        request: CheckpointRequest,
+    ) -> Result<CheckpointResponse, tonic::Status> {
+        self.unary(request::Body::Checkpoint(request)).await
+    }
+
+    pub async fn batch_info(
+        &self,
+        request: BatchInfoRequest,
+    ) -> Result<BoxStream<'static, Result<BatchInfoResponseItem, tonic::Status>>, tonic::Status>
+    {
+        let (send, recv, framing) = self.open_stream().await?;
+        let body = request::Body::BatchInfo(request);
+        let mut recv = send_request(send, recv, body, framing).await?;
+        // Errors come as the first frame, fail the call on them rather than the stream.
+        let first = read_response(&mut recv, framing).await?;
+        // The stream ends after the first error reading it.
+        let items = futures::stream::unfold(Some(recv), move |recv| async move {
+            let mut recv = recv?;
+            match read_frame(&mut recv, framing).await {
+                Ok(None) => None,
+                Ok(Some(frame)) => {
+                    Some((frame_response::<BatchInfoResponseItem>(frame), Some(recv)))
+                }
+                Err(e) => Some((Err(io_status(e)), None)),
+            }
+        });
+        Ok(Box::pin(futures::stream::iter(first.map(Ok)).chain(items)))
+    }
+
+    async fn unary<T: Message + Default>(&self, body: request::Body) -> Result<T, tonic::Status> {
+        let call = async {
+            let (send, recv, framing) = self.open_stream().await?;
+            let mut recv = send_request(send, recv, body, framing).await?;
+            read_response(&mut recv, framing)
+                .await?
+                .ok_or_else(|| tonic::Status::internal("Stream finished without a response"))
+        };
+        tokio::time::timeout(self.inner.config.request_timeout, call)
+            .await
+            .map_err(|_| tonic::Status::deadline_exceeded("Request timed out"))?
+    }
+
+    async fn open_stream(&self) -> Result<(SendStream, RecvStream, Framing), tonic::Status> {
+        let (connection, framing) = self.connection().await?;
+        match connection.open_bi().await {
+            Ok((send, recv)) => Ok((send, recv, framing)),
+            Err(e) => {
+                debug!("Reconnecting to {}: {e}", self.inner.address);
+                {
+                    let mut state = self.inner.state.lock().await;
+                    // Unless another request already replaced it.
+                    if let Some((current, _)) = &state.connection {
+                        if current.stable_id() == connection.stable_id() {
+                            state.connection = None;
+                        }
+                    }
+                }
+                let (connection, framing) = self.connection().await?;
+                let (send, recv) = connection.open_bi().await.map_err(unavailable)?;
+                Ok((send, recv, framing))
+            }
+        }
+    }
+
+    async fn connection(&self) -> Result<(Connection, Framing), tonic::Status> {
+        let mut state = self.inner.state.lock().await;
+        if let Some(connection) = &state.connection {
+            return Ok(connection.clone());
+        }
+
+        let remote = resolve_quic_address(&self.inner.address)
+            .await
+            .map_err(unavailable)?;
+        let endpoint = match &state.endpoint {
+            Some(endpoint) => endpoint.clone(),
+            None => {
+                let local: SocketAddr = if remote.is_ipv6() {
+                    (Ipv6Addr::UNSPECIFIED, 0).into()
+                } else {
+                    (Ipv4Addr::UNSPECIFIED, 0).into()
+                };
+                let endpoint = Endpoint::client(local).map_err(unavailable)?;
+                state.endpoint = Some(endpoint.clone());
+                endpoint
+            }
+        };
+        let connecting = endpoint
+            .connect_with(self.inner.client_config.clone(), remote, CERTIFICATE_NAME)
+            .map_err(unavailable)?;
+        let config = &self.inner.config;
+        let connecting = if config.enable_0rtt && config.compression.is_empty() {
+            // Only succeeds when resuming a session with the server.
+            connecting.into_0rtt().map(|(connection, _)| connection)
+        } else {
+            Err(connecting)
+        };
+        let connection = match connecting {
+            Ok(connection) => connection,
+            Err(connecting) => connecting.await.map_err(unavailable)?,
+        }
+        .connection;
+        let framing = config.framing(negotiated_compression(&connection));
+        state.connection = Some((connection.clone(), framing));
+        Ok((connection, framing))
+    }
+}
+
+async fn send_request(
+    mut send: SendStream,
+    recv: RecvStream,
+    body: request::Body,
+    framing: Framing,
+) -> Result<RecvStream, tonic::Status> {
+    let kind = body.kind();
+    let request = Request { body: Some(body) };
+    framing.check_request_size(kind, request.encoded_len())?;
+    let request = framing
+        .encode(&request)
+        .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+    match send.write_all(&request).await {
+        Ok(()) => send.finish().await.map_err(unavailable)?,
+        // The server rejected the request, its response tells why.
+        Err(WriteError::Stopped(_)) => (),
+        Err(e) => return Err(unavailable(e)),
+    }
+    Ok(recv)
+}
+
+async fn read_response<T: Message + Default>(
+    recv: &mut RecvStream,
+    framing: Framing,
+) -> Result<Option<T>, tonic::Status> {
+    match read_frame(recv, framing).await {
+        Ok(Some(frame)) => frame_response(frame).map(Some),
+        Ok(None) => Ok(None),
+        Err(e) => Err(io_status(e)),
+    }
+}
+
+fn unavailable<E: std::fmt::Display>(error: E) -> tonic::Status {
+    tonic::Status::unavailable(error.to_string())
+}
+
+fn io_status(error: io::Error) -> tonic::Status {
+    match error.kind() {
+        io::ErrorKind::InvalidData => tonic::Status::internal(error.to_string()),
+        _ => tonic::Status::unavailable(error.to_string()),
+    }
+}
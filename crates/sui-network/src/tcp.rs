@@ -0,0 +1,331 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! gRPC over TCP, authenticated with TLS.
+//!
+//! Clients and servers authenticate each other with their key pairs as over QUIC, see
+//! [`crate::tls`], then speak HTTP/2 over the TLS session. There is no plaintext transport:
+//! addresses keep their `/http` suffix, e.g. `/dns/localhost/tcp/8080/http`, but every connection
+//! to them is authenticated.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::Stream;
+use multiaddr::{Multiaddr, Protocol};
+use rustls::ServerName;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+use tonic::transport::server::{Connected, TcpConnectInfo};
+use tonic::transport::{Channel, Uri};
+use tracing::debug;
+
+use sui_types::crypto::PublicKeyBytes;
+
+use crate::address::{to_multiaddr, Endpoint, Transport};
+use crate::tls::{self, AllowList, NodeCertificate, CERTIFICATE_NAME};
+
+/// The application protocol negotiated by the TLS handshake, the one of HTTP/2.
+const ALPN_PROTOCOL: &[u8] = b"h2";
+
+/// Connections whose TLS handshake takes longer than this are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A TCP listener authenticating its connections with TLS, served with
+/// `tonic::transport::Server::serve_with_incoming`.
+pub struct TlsListener {
+    listener: TcpListener,
+    local_addr: Multiaddr,
+    acceptor: TlsAcceptor,
+}
+
+impl TlsListener {
+    /// Bind a listener authenticated with `certificate`, accepting the `allowed_clients`.
+    pub async fn bind(
+        address: &Multiaddr,
+        certificate: &NodeCertificate,
+        allowed_clients: AllowList,
+    ) -> io::Result<Self> {
+        let socket_address = tcp_endpoint(address)?.resolve_async().await?;
+        let listener = TcpListener::bind(socket_address).await?;
+        let local_addr = to_multiaddr(listener.local_addr()?, Transport::Tcp, &[Protocol::Http]);
+
+        let mut config = tls::server_config(certificate, allowed_clients).map_err(invalid_data)?;
+        config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+        Ok(Self {
+            listener,
+            local_addr,
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+        })
+    }
+
+    pub fn local_addr(&self) -> &Multiaddr {
+        &self.local_addr
+    }
+
+    /// The connections whose TLS handshake succeeded. Handshakes run concurrently, and the ones
+    /// failing only drop their connection. Connections are accepted until the stream is dropped.
+    pub fn incoming(self) -> impl Stream<Item = io::Result<TlsConnection>> {
+        let Self {
+            listener, acceptor, ..
+        } = self;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let accepted = tokio::select! {
+                    accepted = listener.accept() => accepted,
+                    _ = sender.closed() => return,
+                };
+                let (stream, remote_address) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        debug!("Failed to accept a TCP connection: {e}");
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = stream.set_nodelay(true) {
+                        debug!("Failed to disable Nagle's algorithm for {remote_address}: {e}");
+                    }
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = sender.send(Ok(TlsConnection(stream)));
+                        }
+                        Ok(Err(e)) => debug!("TLS handshake with {remote_address} failed: {e}"),
+                        Err(_) => debug!("TLS handshake with {remote_address} timed out"),
+                    }
+                });
+            }
+        });
+        futures::stream::unfold(receiver, |mut receiver| async move {
+            let connection = receiver.recv().await?;
+            Some((connection, receiver))
+        })
+    }
+}
+
+/// A TCP connection authenticated with TLS.
+pub struct TlsConnection(server::TlsStream<TcpStream>);
+
+impl Connected for TlsConnection {
+    type ConnectInfo = TcpConnectInfo;
+
+    fn connect_info(&self) -> TcpConnectInfo {
+        self.0.get_ref().0.connect_info()
+    }
+}
+
+impl AsyncRead for TlsConnection {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TlsConnection {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+    }
+}
+
+/// A channel to the node at `address`, which must authenticate with the `server` key, or with
+/// any key if not set. It connects on its first request, and reconnects when the connection is
+/// lost.
+pub fn connect_lazy(
+    net_config: &mysten_network::config::Config,
+    address: &Multiaddr,
+    certificate: &NodeCertificate,
+    server: Option<PublicKeyBytes>,
+) -> io::Result<Channel> {
+    let (endpoint, connector) = channel_endpoint(net_config, address, certificate, server)?;
+    endpoint
+        .connect_with_connector_lazy(connector)
+        .map_err(invalid_input)
+}
+
+/// A channel to the node at `address`, connected before returning, see [`connect_lazy`].
+pub async fn connect(
+    net_config: &mysten_network::config::Config,
+    address: &Multiaddr,
+    certificate: &NodeCertificate,
+    server: Option<PublicKeyBytes>,
+) -> io::Result<Channel> {
+    let (endpoint, connector) = channel_endpoint(net_config, address, certificate, server)?;
+    endpoint
+        .connect_with_connector(connector)
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::ConnectionRefused, e))
+}
+
+fn channel_endpoint(
+    net_config: &mysten_network::config::Config,
+    address: &Multiaddr,
+    certificate: &NodeCertificate,
+    server: Option<PublicKeyBytes>,
+) -> io::Result<(tonic::transport::Endpoint, Connector)> {
+    let endpoint = tcp_endpoint(address)?;
+    let mut config = match server {
+        Some(server) => tls::client_config(certificate, server),
+        None => tls::any_server_client_config(certificate),
+    }
+    .map_err(invalid_data)?;
+    config.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+    // The connector resolves the address, the URI only names the server in the requests.
+    let uri = format!("http://{CERTIFICATE_NAME}:{}", endpoint.port);
+    let mut channel = tonic::transport::Endpoint::from_shared(uri).map_err(invalid_input)?;
+    if let Some(timeout) = net_config.connect_timeout {
+        channel = channel.connect_timeout(timeout);
+    }
+    if let Some(timeout) = net_config.request_timeout {
+        channel = channel.timeout(timeout);
+    }
+    if let Some(interval) = net_config.http2_keepalive_interval {
+        channel = channel.http2_keep_alive_interval(interval);
+    }
+    let connector = Connector {
+        endpoint,
+        connector: TlsConnector::from(Arc::new(config)),
+    };
+    Ok((channel, connector))
+}
+
+/// Opens the TCP connections of a channel and authenticates them with TLS.
+#[derive(Clone)]
+struct Connector {
+    endpoint: Endpoint,
+    connector: TlsConnector,
+}
+
+impl tower::Service<Uri> for Connector {
+    type Response = client::TlsStream<TcpStream>;
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<Self::Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let Self {
+            endpoint,
+            connector,
+        } = self.clone();
+        Box::pin(async move {
+            let stream = TcpStream::connect(endpoint.resolve_async().await?).await?;
+            stream.set_nodelay(true)?;
+            let server_name =
+                ServerName::try_from(CERTIFICATE_NAME).expect("The certificate name is valid");
+            connector.connect(server_name, stream).await
+        })
+    }
+}
+
+/// Parse a `/{ip4,ip6,dns,dns4,dns6}/<host>/tcp/<port>` address.
+fn tcp_endpoint(address: &Multiaddr) -> io::Result<Endpoint> {
+    let endpoint = Endpoint::parse(address)?;
+    if endpoint.transport != Transport::Tcp {
+        return Err(invalid_input(format!(
+            "Invalid TCP address {address}, expected /<ip4|ip6|dns>/<host>/tcp/<port>"
+        )));
+    }
+    Ok(endpoint)
+}
+
+fn invalid_data(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn invalid_input(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{discovery, DiscoveryClient};
+    use crate::discovery::{DiscoveryConfig, PeerDiscovery, PeerTable};
+    use sui_types::discovery::PeersRequest;
+
+    async fn get_peers(
+        address: &Multiaddr,
+        certificate: &NodeCertificate,
+        server: Option<PublicKeyBytes>,
+    ) -> Result<(), tonic::Status> {
+        let net_config = mysten_network::config::Config::new();
+        let channel = connect_lazy(&net_config, address, certificate, server).unwrap();
+        let request = PeersRequest { sender: None };
+        DiscoveryClient::new(channel)
+            .get_peers(discovery::PeersRequest::from(request))
+            .await
+            .map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn test_mutual_authentication() {
+        let certificate = NodeCertificate::ephemeral();
+        let configured = NodeCertificate::ephemeral();
+        let member = NodeCertificate::ephemeral();
+        let member_key = member.public_key;
+        let allowed_clients =
+            AllowList::new([configured.public_key], move |client| *client == member_key);
+
+        let address = "/ip4/127.0.0.1/tcp/0/http".parse().unwrap();
+        let listener = TlsListener::bind(&address, &certificate, allowed_clients)
+            .await
+            .unwrap();
+        let address = listener.local_addr().clone();
+        let table = Arc::new(PeerTable::new(certificate.public_key, 10));
+        let discovery =
+            PeerDiscovery::new(DiscoveryConfig::default(), table, None, certificate.clone());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(discovery.service())
+                .serve_with_incoming(listener.incoming()),
+        );
+
+        // The configured clients and the ones the committee allows are accepted
+        get_peers(&address, &configured, Some(certificate.public_key))
+            .await
+            .unwrap();
+        get_peers(&address, &member, Some(certificate.public_key))
+            .await
+            .unwrap();
+
+        // Any other client is refused
+        let stranger = NodeCertificate::ephemeral();
+        assert!(get_peers(&address, &stranger, Some(certificate.public_key))
+            .await
+            .is_err());
+
+        // Clients only accept the server they expect, or any server if they expect none
+        let impostor = NodeCertificate::ephemeral().public_key;
+        assert!(get_peers(&address, &configured, Some(impostor))
+            .await
+            .is_err());
+        get_peers(&address, &configured, None).await.unwrap();
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutual TLS authentication of nodes with their Ed25519 key pairs.
+//!
+//! Every node presents a self-signed certificate for its own key pair, the TLS handshake proves
+//! that it holds the private key of the certificate. A client accepts a server only if its key is
+//! the one of the validator it meant to connect to, as known from the committee. Servers accept
+//! any authenticated client, or only the clients of an [`AllowList`] if one is configured.
+//!
+//! Both transports are authenticated this way, QUIC, see [`crate::quic`], and gRPC over TCP, see
+//! [`crate::tcp`].
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::server::{ClientCertVerified, ClientCertVerifier};
+use rustls::{Certificate, DistinguishedNames, PrivateKey, ServerName};
+use x509_parser::oid_registry::OID_SIG_ED25519;
+
+use sui_types::crypto::{KeyPair, KeypairTraits, PublicKeyBytes, ToFromBytes};
+
+/// The name certificates are issued for, peers are identified by their keys instead.
+pub const CERTIFICATE_NAME: &str = "sui";
+
+// The PKCS#8 encoding of an Ed25519 private key is this prefix followed by the key (RFC 8410).
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+
+/// A self-signed certificate for the key pair of a node, along with its private key.
+#[derive(Clone)]
+pub struct NodeCertificate {
+    pub public_key: PublicKeyBytes,
+    pub certificate: Certificate,
+    pub private_key: PrivateKey,
+}
+
+impl NodeCertificate {
+    pub fn new(key_pair: &KeyPair) -> io::Result<Self> {
+        let public_key: PublicKeyBytes = key_pair.public().into();
+        let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+        pkcs8.extend_from_slice(key_pair.copy().private().as_ref());
+        let invalid = |e: rcgen::RcgenError| io::Error::new(io::ErrorKind::InvalidInput, e);
+
+        let mut params = rcgen::CertificateParams::new(vec![CERTIFICATE_NAME.to_owned()]);
+        params.alg = &rcgen::PKCS_ED25519;
+        params.key_pair = Some(rcgen::KeyPair::from_der(&pkcs8).map_err(invalid)?);
+        let certificate = rcgen::Certificate::from_params(params).map_err(invalid)?;
+        Ok(Self {
+            public_key,
+            certificate: Certificate(certificate.serialize_der().map_err(invalid)?),
+            private_key: PrivateKey(certificate.serialize_private_key_der()),
+        })
+    }
+
+    /// A certificate for a key pair generated on the fly, for clients which are not nodes.
+    pub fn ephemeral() -> Self {
+        Self::new(&sui_types::crypto::get_key_pair().1)
+            .expect("Certificates can be issued for generated keys")
+    }
+}
+
+/// The key a peer authenticated with, as certified by `certificate`.
+pub fn peer_public_key(certificate: &Certificate) -> Result<PublicKeyBytes, rustls::Error> {
+    let (_, certificate) = x509_parser::parse_x509_certificate(&certificate.0)
+        .map_err(|_| rustls::Error::InvalidCertificateEncoding)?;
+    let public_key = certificate.public_key();
+    if public_key.algorithm.algorithm != OID_SIG_ED25519 {
+        return Err(rustls::Error::InvalidCertificateData(
+            "Expected a certificate for an Ed25519 key".to_owned(),
+        ));
+    }
+    PublicKeyBytes::from_bytes(public_key.subject_public_key.data.as_ref())
+        .map_err(|_| rustls::Error::InvalidCertificateData("Invalid Ed25519 public key".to_owned()))
+}
+
+/// The clients a server accepts.
+///
+/// Lists of configured keys also accept the validators of the committee of the current epoch,
+/// which they ask for on every connection rather than keeping a copy of it, so that they follow
+/// the epoch changes of the node.
+#[derive(Clone)]
+pub struct AllowList {
+    configured: Option<Arc<BTreeSet<PublicKeyBytes>>>,
+    in_committee: Arc<dyn Fn(&PublicKeyBytes) -> bool + Send + Sync>,
+}
+
+impl AllowList {
+    /// Accept any authenticated client.
+    pub fn any() -> Self {
+        Self {
+            configured: None,
+            in_committee: Arc::new(|_| true),
+        }
+    }
+
+    /// Accept the `configured` clients, and the ones `in_committee` tells are validators of the
+    /// current committee.
+    pub fn new(
+        configured: impl IntoIterator<Item = PublicKeyBytes>,
+        in_committee: impl Fn(&PublicKeyBytes) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            configured: Some(Arc::new(configured.into_iter().collect())),
+            in_committee: Arc::new(in_committee),
+        }
+    }
+
+    pub fn allows(&self, client: &PublicKeyBytes) -> bool {
+        match &self.configured {
+            Some(configured) => configured.contains(client) || (self.in_committee)(client),
+            None => true,
+        }
+    }
+}
+
+impl fmt::Debug for AllowList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.configured {
+            Some(configured) => write!(f, "the committee and {configured:?}"),
+            None => write!(f, "any client"),
+        }
+    }
+}
+
+/// The TLS configuration of a server authenticated with `certificate`. Clients must present a
+/// certificate too, for one of the keys of `allowed_clients`.
+pub fn server_config(
+    certificate: &NodeCertificate,
+    allowed_clients: AllowList,
+) -> Result<rustls::ServerConfig, rustls::Error> {
+    rustls::ServerConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_client_cert_verifier(Arc::new(ClientVerifier { allowed_clients }))
+        .with_single_cert(
+            vec![certificate.certificate.clone()],
+            certificate.private_key.clone(),
+        )
+}
+
+/// The TLS configuration of a client authenticated with `certificate`, connecting to the
+/// server with the `server` key.
+pub fn client_config(
+    certificate: &NodeCertificate,
+    server: PublicKeyBytes,
+) -> Result<rustls::ClientConfig, rustls::Error> {
+    let verifier = ServerVerifier {
+        server: Some(server),
+    };
+    verifying_client_config(certificate, verifier)
+}
+
+/// The TLS configuration of a client authenticated with `certificate`, connecting to a server
+/// whose key it does not know, like a seed peer. The server still proves it holds the key of its
+/// certificate, whatever that key is.
+pub fn any_server_client_config(
+    certificate: &NodeCertificate,
+) -> Result<rustls::ClientConfig, rustls::Error> {
+    let verifier = ServerVerifier { server: None };
+    verifying_client_config(certificate, verifier)
+}
+
+fn verifying_client_config(
+    certificate: &NodeCertificate,
+    verifier: ServerVerifier,
+) -> Result<rustls::ClientConfig, rustls::Error> {
+    rustls::ClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(&[&rustls::version::TLS13])?
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_single_cert(
+            vec![certificate.certificate.clone()],
+            certificate.private_key.clone(),
+        )
+}
+
+struct ServerVerifier {
+    server: Option<PublicKeyBytes>,
+}
+
+impl ServerCertVerifier for ServerVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let public_key = peer_public_key(end_entity)?;
+        match self.server {
+            Some(server) if public_key != server => Err(rustls::Error::InvalidCertificateData(
+                format!("Expected the server to be {server}, got {public_key}"),
+            )),
+            _ => Ok(ServerCertVerified::assertion()),
+        }
+    }
+}
+
+struct ClientVerifier {
+    allowed_clients: AllowList,
+}
+
+impl ClientCertVerifier for ClientVerifier {
+    fn client_auth_root_subjects(&self) -> Option<DistinguishedNames> {
+        Some(vec![])
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _now: SystemTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let public_key = peer_public_key(end_entity)?;
+        if !self.allowed_clients.allows(&public_key) {
+            return Err(rustls::Error::InvalidCertificateData(format!(
+                "Client {public_key} is not allowed"
+            )));
+        }
+        Ok(ClientCertVerified::assertion())
+    }
+}
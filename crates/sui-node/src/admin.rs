@@ -3,23 +3,71 @@
 
 use axum::{
     extract::Extension,
-    http::StatusCode,
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
     routing::{get, post},
-    Router,
+    Json, Router,
 };
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use telemetry_subscribers::FilterHandle;
+use std::path::PathBuf;
+use std::sync::Arc;
+use sui_config::{EpochConfig, TunableConfig};
+use sui_core::authority::{AuthorityState, StoreBackup};
 use tracing::info;
 
+use crate::logging::LogFilterHandle;
+use crate::reload::ConfigReloader;
+
 const LOGGING_ROUTE: &str = "/logging";
+const BACKUP_ROUTE: &str = "/backup";
+const PRUNE_ROUTE: &str = "/prune";
+const RELOAD_ROUTE: &str = "/reload-config";
+const EPOCH_ROUTE: &str = "/epoch-config";
+
+/// The token of the requests reloading the config or changing epochs, which are refused if the
+/// node has none.
+#[derive(Clone)]
+struct AdminToken(Option<String>);
+
+impl AdminToken {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), (StatusCode, String)> {
+        let token = self.0.as_ref().ok_or_else(|| {
+            (
+                StatusCode::FORBIDDEN,
+                "No admin-token is set in the config of the node".to_string(),
+            )
+        })?;
+        let bearer = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if bearer != Some(token.as_str()) {
+            return Err((StatusCode::UNAUTHORIZED, "Invalid admin token".to_string()));
+        }
+        Ok(())
+    }
+}
 
-pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
+pub fn start_admin_server(
+    port: u16,
+    admin_token: Option<String>,
+    filter_handle: LogFilterHandle,
+    state: Arc<AuthorityState>,
+    reloader: Arc<ConfigReloader>,
+) {
     let filter = filter_handle.get().unwrap();
 
     let app = Router::new()
         .route(LOGGING_ROUTE, get(get_filter))
         .route(LOGGING_ROUTE, post(set_filter))
-        .layer(Extension(filter_handle));
+        .route(BACKUP_ROUTE, post(create_backup))
+        .route(PRUNE_ROUTE, post(prune_transactions))
+        .route(RELOAD_ROUTE, post(reload_config))
+        .route(EPOCH_ROUTE, get(get_epoch_config))
+        .route(EPOCH_ROUTE, post(set_epoch_config))
+        .layer(Extension(filter_handle))
+        .layer(Extension(state))
+        .layer(Extension(reloader))
+        .layer(Extension(AdminToken(admin_token)));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
     info!(
@@ -36,7 +84,7 @@ pub fn start_admin_server(port: u16, filter_handle: FilterHandle) {
     });
 }
 
-async fn get_filter(Extension(filter_handle): Extension<FilterHandle>) -> (StatusCode, String) {
+async fn get_filter(Extension(filter_handle): Extension<LogFilterHandle>) -> (StatusCode, String) {
     match filter_handle.get() {
         Ok(filter) => (StatusCode::OK, filter),
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
@@ -44,7 +92,7 @@ async fn get_filter(Extension(filter_handle): Extension<FilterHandle>) -> (Statu
 }
 
 async fn set_filter(
-    Extension(filter_handle): Extension<FilterHandle>,
+    Extension(filter_handle): Extension<LogFilterHandle>,
     new_filter: String,
 ) -> (StatusCode, String) {
     match filter_handle.update(&new_filter) {
@@ -55,3 +103,84 @@ async fn set_filter(
         Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
     }
 }
+
+/// Back the authority store up into the directory given in the body of the request.
+async fn create_backup(
+    Extension(state): Extension<Arc<AuthorityState>>,
+    dir: String,
+) -> Result<Json<StoreBackup>, (StatusCode, String)> {
+    let dir = PathBuf::from(dir.trim());
+    if dir.as_os_str().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "No backup directory given".into()));
+    }
+    tokio::task::spawn_blocking(move || state.create_backup(&dir))
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .map(Json)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+/// Prune the transactions executed before the sequence number given in the body of the
+/// request, returning the number of transactions pruned.
+async fn prune_transactions(
+    Extension(state): Extension<Arc<AuthorityState>>,
+    seq: String,
+) -> Result<Json<usize>, (StatusCode, String)> {
+    let seq: u64 = seq.trim().parse().map_err(|err| {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid sequence number: {err}"),
+        )
+    })?;
+    tokio::task::spawn_blocking(move || state.prune_transactions(seq))
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .map(Json)
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))
+}
+
+/// Reload the tunable settings of the config file of the node, returning them.
+async fn reload_config(
+    Extension(reloader): Extension<Arc<ConfigReloader>>,
+    Extension(token): Extension<AdminToken>,
+    headers: HeaderMap,
+) -> Result<Json<TunableConfig>, (StatusCode, String)> {
+    token.authorize(&headers)?;
+    tokio::task::spawn_blocking(move || reloader.reload())
+        .await
+        .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+        .map(Json)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))
+}
+
+async fn get_epoch_config(
+    Extension(state): Extension<Arc<AuthorityState>>,
+) -> Result<Json<EpochConfig>, (StatusCode, String)> {
+    let checkpoints = state.checkpoints.as_ref().ok_or_else(no_epochs)?;
+    let config = checkpoints.lock().epoch_timer.config();
+    Ok(Json(config))
+}
+
+/// Override when the epochs of the validator end, from the current epoch on, such as for test
+/// networks changing epochs every few minutes. The parameters of genesis apply again on restart.
+async fn set_epoch_config(
+    Extension(state): Extension<Arc<AuthorityState>>,
+    Extension(token): Extension<AdminToken>,
+    headers: HeaderMap,
+    Json(config): Json<EpochConfig>,
+) -> Result<Json<EpochConfig>, (StatusCode, String)> {
+    token.authorize(&headers)?;
+    let checkpoints = state.checkpoints.as_ref().ok_or_else(no_epochs)?;
+    let mut checkpoints = checkpoints.lock();
+    checkpoints.epoch_timer.set_config(&config);
+    let config = checkpoints.epoch_timer.config();
+    info!(?config, "Epoch config overridden");
+    Ok(Json(config))
+}
+
+fn no_epochs() -> (StatusCode, String) {
+    (
+        StatusCode::NOT_FOUND,
+        "Only validators keep checkpoints and change epochs".to_string(),
+    )
+}
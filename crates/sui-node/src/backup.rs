@@ -0,0 +1,51 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use sui_core::authority::{AuthorityState, AuthorityStore};
+use sui_storage::backup_sink::{mirror_dir, BackupConfig, BackupSink, BackupSinkConfig};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// Back the authority store of `state` up every `config.interval_secs`, keeping the
+/// `config.keep_backups` latest backups, and mirror them to the sink of `config` if it has one.
+pub fn spawn_backups(state: Arc<AuthorityState>, config: BackupConfig) -> JoinHandle<()> {
+    let sink = config.sink.as_ref().map(BackupSinkConfig::build);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        loop {
+            interval.tick().await;
+            if let Err(e) = back_up(&state, &config, sink.as_deref()).await {
+                error!("Failed to back the node up: {e}");
+            }
+        }
+    })
+}
+
+async fn back_up(
+    state: &Arc<AuthorityState>,
+    config: &BackupConfig,
+    sink: Option<&dyn BackupSink>,
+) -> Result<()> {
+    let backup = {
+        let state = state.clone();
+        let backup_dir = config.backup_dir.clone();
+        let keep = config.keep_backups;
+        tokio::task::spawn_blocking(move || {
+            let backup = state.create_backup(&backup_dir)?;
+            AuthorityStore::purge_old_backups(&backup_dir, keep)?;
+            Ok::<_, anyhow::Error>(backup)
+        })
+        .await??
+    };
+    info!(?backup, "Backed the node up");
+
+    if let Some(sink) = sink {
+        let summary = mirror_dir(&config.backup_dir, sink).await?;
+        info!(?summary, "Mirrored the backups of the node");
+    }
+    Ok(())
+}
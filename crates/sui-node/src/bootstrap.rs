@@ -0,0 +1,104 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use multiaddr::Multiaddr;
+use sui_config::genesis::Genesis;
+use sui_core::authority::AuthorityState;
+use sui_types::committee::Committee;
+use sui_types::crypto::{PublicKeyBytes, ToFromBytes};
+use sui_types::sui_system_state::SuiSystemState;
+use tracing::{info, warn};
+
+/// A validator of the current epoch, as a node connects to it.
+#[derive(Clone, Debug)]
+pub struct EpochValidator {
+    pub public_key: PublicKeyBytes,
+    pub network_key: PublicKeyBytes,
+    pub address: Multiaddr,
+}
+
+/// The committee of the current epoch and the addresses of its validators.
+#[derive(Clone, Debug)]
+pub struct EpochValidators {
+    pub committee: Committee,
+    pub validators: Vec<EpochValidator>,
+}
+
+impl EpochValidators {
+    /// The validators of genesis.
+    pub fn from_genesis(genesis: &Genesis) -> Result<Self> {
+        let validators = genesis
+            .validator_set()
+            .iter()
+            .map(|validator| EpochValidator {
+                public_key: validator.public_key(),
+                network_key: validator.network_key(),
+                address: validator.network_address().clone(),
+            })
+            .collect();
+        Ok(Self {
+            committee: genesis.committee()?,
+            validators,
+        })
+    }
+
+    /// The active validators of `system_state`, weighted by their stake and delegations. The
+    /// network keys are only known for the validators of genesis, the others authenticate with
+    /// their protocol key.
+    pub fn from_system_state(system_state: &SuiSystemState, genesis: &Genesis) -> Result<Self> {
+        let network_keys: BTreeMap<_, _> = genesis
+            .validator_set()
+            .iter()
+            .map(|validator| (validator.public_key(), validator.network_key()))
+            .collect();
+        let mut validators = Vec::new();
+        for validator in &system_state.validators.active_validators {
+            let metadata = &validator.metadata;
+            let public_key = PublicKeyBytes::from_bytes(&metadata.pubkey_bytes)?;
+            let address = Multiaddr::try_from(metadata.net_address.clone())?;
+            validators.push(EpochValidator {
+                public_key,
+                network_key: network_keys.get(&public_key).copied().unwrap_or(public_key),
+                address,
+            });
+        }
+        Ok(Self {
+            committee: system_state.get_current_epoch_committee()?,
+            validators,
+        })
+    }
+
+    /// The validators of the epoch `state` is in on chain, or of genesis if the node has not
+    /// executed past the first epoch or its system state cannot be read.
+    pub async fn load(state: &AuthorityState, genesis: &Genesis) -> Result<Self> {
+        match state.get_sui_system_state_object().await {
+            Ok(system_state) if system_state.epoch > 0 => {
+                match Self::from_system_state(&system_state, genesis) {
+                    Ok(validators) => {
+                        info!(
+                            epoch = system_state.epoch,
+                            "Read the committee from the on-chain system state"
+                        );
+                        return Ok(validators);
+                    }
+                    Err(e) => warn!("Invalid validators in the on-chain system state: {e}"),
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to read the on-chain system state: {e}"),
+        }
+        Self::from_genesis(genesis)
+    }
+
+    /// Make the committee the one `state` verifies certificates with, if it is of a later epoch
+    /// than the one of `state`. Validators sign the committees of their epochs instead.
+    pub fn install(&self, state: &AuthorityState) {
+        if self.committee.epoch > state.epoch() {
+            state.committee.store(Arc::new(self.committee.clone()));
+        }
+    }
+}
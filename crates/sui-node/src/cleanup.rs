@@ -0,0 +1,57 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use parking_lot::Mutex;
+use sui_core::authority::AuthorityState;
+use sui_storage::follower_store::FollowerStore;
+use sui_storage::ttl::{expiry_ms, TtlConfig};
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+/// The times to live the cleanup runs with, replaced when the config of the node is reloaded.
+pub type SharedTtlConfig = Arc<Mutex<TtlConfig>>;
+
+/// Remove the expired transient rows of the stores of the node every
+/// `config.cleanup_interval_secs`, reading `config` again before each run.
+pub fn spawn_cleanup(
+    state: Arc<AuthorityState>,
+    follower_store: Arc<FollowerStore>,
+    config: SharedTtlConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let config = *config.lock();
+            let state = state.clone();
+            let follower_store = follower_store.clone();
+            match tokio::task::spawn_blocking(move || clean_up(&state, &follower_store, &config))
+                .await
+            {
+                Ok(Ok(())) => (),
+                Ok(Err(e)) => error!("Failed to remove the expired rows of the node: {e}"),
+                Err(e) => error!("Failed to remove the expired rows of the node: {e}"),
+            }
+            tokio::time::sleep(Duration::from_secs(config.cleanup_interval_secs)).await;
+        }
+    })
+}
+
+fn clean_up(
+    state: &AuthorityState,
+    follower_store: &FollowerStore,
+    config: &TtlConfig,
+) -> Result<()> {
+    let transactions =
+        state.remove_expired_transactions(expiry_ms(config.signed_transaction_ttl_secs))?;
+    let sequences = follower_store.remove_stale(expiry_ms(config.follower_state_ttl_secs))?;
+    if transactions > 0 || sequences > 0 {
+        info!(
+            transactions,
+            sequences, "Removed the expired signed transactions and follower sequence numbers"
+        );
+    }
+    Ok(())
+}
@@ -5,17 +5,20 @@ use anyhow::Result;
 use futures::TryFutureExt;
 use jsonrpsee::http_server::HttpServerHandle;
 use jsonrpsee::ws_server::WsServerHandle;
-use multiaddr::Multiaddr;
 use parking_lot::Mutex;
 use prometheus::Registry;
+use std::collections::BTreeMap;
 use std::option::Option::None;
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 use tracing::info;
 
+use bootstrap::EpochValidators;
+use sui_config::genesis::Genesis;
 use sui_config::NodeConfig;
 use sui_core::authority_active::checkpoint_driver::CheckpointMetrics;
 use sui_core::authority_aggregator::{AuthAggMetrics, AuthorityAggregator};
 use sui_core::authority_server::ValidatorService;
+use sui_core::serving_policy::ServingPolicy;
 use sui_core::{
     authority::{AuthorityState, AuthorityStore},
     authority_active::ActiveAuthority,
@@ -25,32 +28,57 @@ use sui_core::{
 use sui_json_rpc::bcs_api::BcsApiImpl;
 use sui_json_rpc::JsonRpcServerBuilder;
 use sui_network::api::ValidatorServer;
+use sui_network::discovery::{sign_peer_info, PeerDiscovery, PeerTable};
+use sui_network::metrics::NetworkMetrics;
+use sui_network::pool::{ConnectionPool, PoolConfig};
+use sui_network::quic::{is_quic_address, QuicConnector, QuicServer};
+use sui_network::tcp::TlsListener;
+use sui_network::tls::{AllowList, NodeCertificate};
+use sui_network::tonic;
 use sui_storage::{
+    db_metrics::{spawn_db_metrics, DbMetrics, DB_METRICS_INTERVAL},
     event_store::{EventStoreType, SqlEventStore},
     follower_store::FollowerStore,
     node_sync_store::NodeSyncStore,
     IndexStore,
 };
-use sui_types::crypto::ToFromBytes;
 
 use sui_json_rpc::event_api::EventReadApiImpl;
 use sui_json_rpc::event_api::EventStreamingApiImpl;
 use sui_json_rpc::read_api::FullNodeApi;
 use sui_json_rpc::read_api::ReadApi;
-use sui_types::crypto::{KeypairTraits, PublicKeyBytes};
+use sui_types::crypto::KeypairTraits;
+use sui_types::handshake::Handshake;
+use sui_types::protocol_config::ProtocolConfig;
 
 pub mod admin;
+pub mod backup;
+pub mod bootstrap;
+pub mod cleanup;
+pub mod logging;
 pub mod metrics;
+pub mod reload;
+pub mod rest;
+pub mod telemetry;
 
 pub struct SuiNode {
     grpc_server: tokio::task::JoinHandle<Result<()>>,
     _json_rpc_service: Option<HttpServerHandle>,
     _ws_subscription_service: Option<WsServerHandle>,
+    _rest_service: Option<tokio::task::JoinHandle<()>>,
     _batch_subsystem_handle: tokio::task::JoinHandle<Result<()>>,
     _post_processing_subsystem_handle: Option<tokio::task::JoinHandle<Result<()>>>,
     _gossip_handle: Option<tokio::task::JoinHandle<()>>,
     _execute_driver_handle: Option<tokio::task::JoinHandle<()>>,
     _checkpoint_process_handle: Option<tokio::task::JoinHandle<()>>,
+    _discovery_handle: Option<tokio::task::JoinHandle<()>>,
+    _db_metrics_handle: tokio::task::JoinHandle<()>,
+    _backup_handle: Option<tokio::task::JoinHandle<()>>,
+    _cleanup_handle: Option<tokio::task::JoinHandle<()>>,
+    _telemetry_handle: Option<tokio::task::JoinHandle<()>>,
+    ttl_config: Option<cleanup::SharedTtlConfig>,
+    serving_policy: Option<Arc<ServingPolicy>>,
+    peer_table: Option<Arc<PeerTable>>,
     state: Arc<AuthorityState>,
     active: Option<Arc<ActiveAuthority<NetworkAuthorityClient>>>,
 }
@@ -71,10 +99,28 @@ impl SuiNode {
         );
 
         let genesis = config.genesis()?;
+        info!(chain_id = %genesis.chain_id(), "Loaded genesis");
 
         let secret = Arc::pin(config.key_pair().copy());
         let committee = genesis.committee()?;
-        let store = Arc::new(AuthorityStore::open(config.db_path().join("store"), None));
+        let mut store = AuthorityStore::open_with_backend(
+            config.db_path().join("store"),
+            config.storage_backend(),
+            config.group_commit,
+            None,
+        );
+        if let Some(object_cache_size) = config.object_cache_size {
+            store = store.with_object_cache_size(object_cache_size);
+        }
+        let migration_config = config.migration_config.clone().unwrap_or_default();
+        let report = store.migrate(&migration_config)?;
+        if !report.migrations.is_empty() {
+            info!(?report, "Migrated the authority store");
+            if migration_config.dry_run {
+                anyhow::bail!("The authority store needs migrations, not run for a dry run");
+            }
+        }
+        let store = Arc::new(store);
 
         let checkpoint_store = Arc::new(Mutex::new(CheckpointStore::open(
             config.db_path().join("checkpoints"),
@@ -93,6 +139,16 @@ impl SuiNode {
             )))
         };
 
+        let mut databases = store.databases();
+        if let Some(index_store) = &index_store {
+            databases.push(("indexes", index_store.db()));
+        }
+        let db_metrics_handle = spawn_db_metrics(
+            DbMetrics::new(&prometheus_registry),
+            databases,
+            DB_METRICS_INTERVAL,
+        );
+
         let follower_store = Arc::new(FollowerStore::open(config.db_path().join("follower_db"))?);
 
         let event_store = if config.enable_event_processing {
@@ -104,8 +160,14 @@ impl SuiNode {
             None
         };
 
+        // The network runs at the version its genesis sets, which the binary must support
+        let protocol_config = ProtocolConfig::get_for_version(genesis.protocol_version())?;
+        info!(
+            version = protocol_config.version(),
+            "Executing at protocol version"
+        );
         let state = Arc::new(
-            AuthorityState::new(
+            AuthorityState::new_with_protocol_config(
                 committee,
                 config.public_key(),
                 secret,
@@ -115,16 +177,74 @@ impl SuiNode {
                 Some(checkpoint_store),
                 genesis,
                 &prometheus_registry,
+                protocol_config,
             )
             .await,
         );
 
+        // Epochs end when the genesis of the network sets
+        if let Some(checkpoints) = &state.checkpoints {
+            checkpoints
+                .lock()
+                .epoch_timer
+                .set_config(genesis.epoch_config());
+        }
+
+        let ttl_config = config
+            .ttl_config
+            .map(|ttl_config| Arc::new(Mutex::new(ttl_config)));
+        let cleanup_handle = ttl_config.clone().map(|ttl_config| {
+            cleanup::spawn_cleanup(state.clone(), follower_store.clone(), ttl_config)
+        });
+
         // TODO: maybe have a config enum that takes care of this for us.
         let is_validator = config.consensus_config().is_some();
         let is_node = !is_validator;
 
         let should_start_follower = is_node || config.enable_gossip;
 
+        // Full nodes follow the committee of the current epoch on chain, so that they keep
+        // working across epochs without edits of their genesis. Validators reconfigure into the
+        // committees they sign, and only connect to the validators on chain if they do.
+        let epoch_validators = if is_node || config.enable_reconfig {
+            EpochValidators::load(&state, &genesis).await?
+        } else {
+            EpochValidators::from_genesis(&genesis)?
+        };
+        if is_node {
+            epoch_validators.install(&state);
+        }
+
+        // Authenticates the node on the connections it serves or opens, over either transport
+        let certificate = NodeCertificate::new(config.network_key_pair())?;
+        let network_metrics = Arc::new(NetworkMetrics::new(&prometheus_registry));
+
+        // Discovers the peers of the node, starting from the validators of the current epoch
+        let discovery = config.discovery_config().map(|discovery_config| {
+            let table = Arc::new(PeerTable::new(
+                config.network_public_key(),
+                discovery_config.max_peers,
+            ));
+            for validator in &epoch_validators.validators {
+                table.add_validator(validator.network_key, validator.address.clone());
+            }
+            let own_address = discovery_config.external_address.clone().or_else(|| {
+                epoch_validators
+                    .validators
+                    .iter()
+                    .find(|validator| validator.public_key == config.public_key())
+                    .map(|validator| validator.address.clone())
+            });
+            let own_record =
+                own_address.map(|address| sign_peer_info(config.network_key_pair(), &address));
+            PeerDiscovery::new(
+                discovery_config.clone(),
+                table,
+                own_record,
+                certificate.clone(),
+            )
+        });
+
         let mut active = None;
 
         let (gossip_handle, execute_driver_handle, checkpoint_process_handle) =
@@ -134,32 +254,25 @@ impl SuiNode {
                 net_config.request_timeout = Some(Duration::from_secs(5));
                 net_config.http2_keepalive_interval = Some(Duration::from_secs(5));
 
-                let mut authority_clients = BTreeMap::new();
-
-                let sui_system_state = state.get_sui_system_state_object().await?;
-
-                if config.enable_reconfig && sui_system_state.epoch > 0 {
-                    // Create NetworkAuthorityClient with this epoch's network information
-                    let epoch_validators = &sui_system_state.validators.active_validators;
-
-                    for validator in epoch_validators {
-                        let address = Multiaddr::try_from(validator.metadata.net_address.clone())?;
-                        let channel = net_config.connect_lazy(&address)?;
-                        let client = NetworkAuthorityClient::new(channel);
-                        let name: &[u8] = &validator.metadata.name;
-                        let public_key_bytes = PublicKeyBytes::from_bytes(name)?;
-                        authority_clients.insert(public_key_bytes, client);
-                    }
-                } else {
-                    // Create NetworkAuthorityClient with the genesis set
-                    for validator in genesis.validator_set() {
-                        let channel = net_config
-                            .connect_lazy(validator.network_address())
-                            .unwrap();
-                        let client = NetworkAuthorityClient::new(channel);
-                        authority_clients.insert(validator.public_key(), client);
-                    }
-                }
+                let quic_connector = QuicConnector::new(config.quic_config(), certificate.clone());
+                let pool = Arc::new(
+                    ConnectionPool::new(PoolConfig::default(), net_config, quic_connector)
+                        .with_metrics(network_metrics.clone())
+                        .with_handshake(Handshake::new(state.chain_id)),
+                );
+                let authority_clients = epoch_validators
+                    .validators
+                    .iter()
+                    .map(|validator| {
+                        let client = NetworkAuthorityClient::pooled_with_network_key(
+                            pool.clone(),
+                            validator.address.clone(),
+                            validator.public_key,
+                            validator.network_key,
+                        );
+                        (validator.public_key, client)
+                    })
+                    .collect();
                 let net = AuthorityAggregator::new(
                     state.clone_committee(),
                     authority_clients,
@@ -235,31 +348,82 @@ impl SuiNode {
         } else {
             None
         };
+        let serving_policy = validator_service
+            .as_ref()
+            .map(ValidatorService::serving_policy);
+
+        let allowed_clients = allowed_clients(config, &state, genesis);
+        let grpc_server = if is_quic_address(config.network_address()) {
+            let server = QuicServer::bind(
+                config.network_address(),
+                &config.quic_config(),
+                &certificate,
+                allowed_clients,
+            )
+            .await?
+            .with_metrics(network_metrics);
+            tokio::spawn(server.serve(validator_service).map_err(Into::into))
+        } else {
+            let listener =
+                TlsListener::bind(config.network_address(), &certificate, allowed_clients).await?;
+            info!("Listening to traffic on {}", listener.local_addr());
+            let (_, health_service) = tonic_health::server::health_reporter();
+            let router = tonic::transport::Server::builder()
+                .add_service(health_service)
+                .add_optional_service(validator_service.map(ValidatorServer::new))
+                .add_optional_service(discovery.as_ref().map(PeerDiscovery::service));
+            tokio::spawn(
+                router
+                    .serve_with_incoming(listener.incoming())
+                    .map_err(Into::into),
+            )
+        };
 
-        let grpc_server = {
-            let mut server_builder = mysten_network::config::Config::new().server_builder();
+        let peer_table = discovery.as_ref().map(PeerDiscovery::table);
+        let discovery_handle = discovery.map(PeerDiscovery::spawn);
 
-            if let Some(validator_service) = validator_service {
-                server_builder =
-                    server_builder.add_service(ValidatorServer::new(validator_service));
-            }
+        let (json_rpc_service, ws_subscription_service) =
+            build_node_server(state.clone(), config, &prometheus_registry).await?;
 
-            let server = server_builder.bind(config.network_address()).await?;
-            let local_addr = server.local_addr();
-            info!("Listening to traffic on {local_addr}");
-            tokio::spawn(server.serve().map_err(Into::into))
+        // Validators do not expose the read APIs, over REST either
+        let rest_service = match config.rest_address {
+            Some(rest_address) if is_node => {
+                Some(rest::start_rest_server(rest_address, state.clone())?)
+            }
+            _ => None,
         };
 
-        let (json_rpc_service, ws_subscription_service) =
-            build_node_server(state.clone(), config, &prometheus_registry).await?;
+        let backup_handle = config
+            .backup_config
+            .clone()
+            .map(|backup_config| backup::spawn_backups(state.clone(), backup_config));
+
+        let telemetry_handle = config.telemetry_push.clone().map(|telemetry_config| {
+            let reporter = telemetry::HealthReporter::new(
+                is_validator,
+                state.clone(),
+                active.clone(),
+                peer_table.clone(),
+            );
+            telemetry::spawn_telemetry_push(telemetry_config, reporter)
+        });
 
         let node = Self {
             grpc_server,
             _json_rpc_service: json_rpc_service,
             _ws_subscription_service: ws_subscription_service,
+            _rest_service: rest_service,
             _gossip_handle: gossip_handle,
             _execute_driver_handle: execute_driver_handle,
             _checkpoint_process_handle: checkpoint_process_handle,
+            _discovery_handle: discovery_handle,
+            _db_metrics_handle: db_metrics_handle,
+            _backup_handle: backup_handle,
+            _cleanup_handle: cleanup_handle,
+            _telemetry_handle: telemetry_handle,
+            ttl_config,
+            serving_policy,
+            peer_table,
             _batch_subsystem_handle: batch_subsystem_handle,
             _post_processing_subsystem_handle: post_processing_subsystem_handle,
             state,
@@ -279,6 +443,21 @@ impl SuiNode {
         self.active.clone()
     }
 
+    /// The times to live of the cleanup of the node, if it removes expired rows.
+    pub fn ttl_config(&self) -> Option<cleanup::SharedTtlConfig> {
+        self.ttl_config.clone()
+    }
+
+    /// The policy the validator signs transactions under, if the node is a validator.
+    pub fn serving_policy(&self) -> Option<Arc<ServingPolicy>> {
+        self.serving_policy.clone()
+    }
+
+    /// The peers known to the node, if it discovers peers.
+    pub fn peer_table(&self) -> Option<Arc<PeerTable>> {
+        self.peer_table.clone()
+    }
+
     //TODO watch/wait on all the components
     pub async fn wait(self) -> Result<()> {
         self.grpc_server.await??;
@@ -287,6 +466,30 @@ impl SuiNode {
     }
 }
 
+/// The clients the node accepts: any client, or the configured ones along with the validators of
+/// the committee of the epoch the node is in when they connect. Validators of genesis
+/// authenticate with their network key, the ones which joined later with their protocol key.
+fn allowed_clients(
+    config: &NodeConfig,
+    state: &Arc<AuthorityState>,
+    genesis: &Genesis,
+) -> AllowList {
+    let allowed_clients = match &config.allowed_clients {
+        Some(allowed_clients) => allowed_clients,
+        None => return AllowList::any(),
+    };
+    let names: BTreeMap<_, _> = genesis
+        .validator_set()
+        .iter()
+        .map(|validator| (validator.network_key(), validator.public_key()))
+        .collect();
+    let state = state.clone();
+    AllowList::new(allowed_clients.iter().copied(), move |client| {
+        let name = names.get(client).unwrap_or(client);
+        state.committee.load().authority_exists(name)
+    })
+}
+
 pub async fn build_node_server(
     state: Arc<AuthorityState>,
     config: &NodeConfig,
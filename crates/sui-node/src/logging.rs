@@ -0,0 +1,165 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The logs of a node configured with a [`LoggingConfig`], written by the node itself rather
+//! than by `telemetry-subscribers`.
+//!
+//! JSON logs are bunyan records, one per line, carrying the fields of the spans the event is
+//! in: e.g. the events logged while a transaction is processed carry its `tx_digest`. Files are
+//! written from a thread of their own so that logging does not wait for the disk, and rotated
+//! on size and time as configured.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use sui_config::node::{LogFileConfig, LogFormat};
+use sui_config::LoggingConfig;
+use telemetry_subscribers::FilterHandle;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_bunyan_formatter::{BunyanFormattingLayer, JsonStorageLayer};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// The filter of the logs of the node, read and changed through the admin interface and when
+/// the config is reloaded.
+#[derive(Clone)]
+pub enum LogFilterHandle {
+    Telemetry(FilterHandle),
+    Node(reload::Handle<EnvFilter, Registry>),
+}
+
+impl LogFilterHandle {
+    pub fn get(&self) -> Result<String> {
+        match self {
+            Self::Telemetry(handle) => handle.get().map_err(|e| anyhow!("{e}")),
+            Self::Node(handle) => Ok(handle.with_current(|filter| filter.to_string())?),
+        }
+    }
+
+    pub fn update(&self, directives: &str) -> Result<()> {
+        match self {
+            Self::Telemetry(handle) => handle.update(directives).map_err(|e| anyhow!("{e}")),
+            Self::Node(handle) => Ok(handle.reload(EnvFilter::try_new(directives)?)?),
+        }
+    }
+}
+
+/// Install the subscriber writing the logs of `service_name` as `config` tells, filtered by
+/// `RUST_LOG` or at the info level. The returned guard flushes the logs when dropped.
+pub fn init(service_name: &str, config: &LoggingConfig) -> Result<(WorkerGuard, LogFilterHandle)> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+    let (writer, guard) = match &config.file {
+        Some(file_config) => tracing_appender::non_blocking(RotatingFile::open(file_config)?),
+        None => tracing_appender::non_blocking(io::stderr()),
+    };
+
+    let registry = tracing_subscriber::registry().with(filter);
+    match config.format {
+        LogFormat::Text => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_ansi(config.file.is_none())
+                    .with_writer(writer),
+            )
+            .try_init()?,
+        LogFormat::Json => registry
+            .with(JsonStorageLayer)
+            .with(BunyanFormattingLayer::new(service_name.to_string(), writer))
+            .try_init()?,
+    }
+    Ok((guard, LogFilterHandle::Node(handle)))
+}
+
+/// A log file rotated on size and time, removing the oldest rotated files beyond the limit.
+struct RotatingFile {
+    config: LogFileConfig,
+    path: PathBuf,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+}
+
+impl RotatingFile {
+    fn open(config: &LogFileConfig) -> io::Result<Self> {
+        fs::create_dir_all(&config.directory)?;
+        let path = config.directory.join(format!("{}.log", config.prefix));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            config: config.clone(),
+            path,
+            file,
+            size,
+            opened_at: Instant::now(),
+        })
+    }
+
+    fn should_rotate(&self, len: usize) -> bool {
+        let too_large = self
+            .config
+            .max_file_size_bytes
+            .map_or(false, |max| self.size > 0 && self.size + len as u64 > max);
+        let too_old = self.config.rotation_interval_secs.map_or(false, |secs| {
+            self.opened_at.elapsed() >= Duration::from_secs(secs)
+        });
+        too_large || too_old
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let rotated = self
+            .config
+            .directory
+            .join(format!("{}.{millis:013}.log", self.config.prefix));
+        fs::rename(&self.path, rotated)?;
+        *self = Self::open(&self.config)?;
+        self.remove_oldest()
+    }
+
+    fn remove_oldest(&self) -> io::Result<()> {
+        let max_files = match self.config.max_files {
+            Some(max_files) => max_files,
+            None => return Ok(()),
+        };
+        let active = format!("{}.log", self.config.prefix);
+        let prefix = format!("{}.", self.config.prefix);
+        let mut rotated = Vec::new();
+        for entry in fs::read_dir(&self.config.directory)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if name != active && name.starts_with(&prefix) && name.ends_with(".log") {
+                rotated.push(name);
+            }
+        }
+        // The timestamps of the names have the same width, the oldest sort first
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(max_files);
+        for name in &rotated[..excess] {
+            fs::remove_file(self.config.directory.join(name))?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_rotate(buf.len()) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
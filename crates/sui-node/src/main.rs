@@ -5,7 +5,10 @@ use anyhow::Result;
 use clap::Parser;
 use multiaddr::Multiaddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 use sui_config::{Config, NodeConfig};
+use sui_core::authority::AuthorityStore;
+use sui_node::logging::{self, LogFilterHandle};
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -15,6 +18,12 @@ struct Args {
 
     #[clap(long, help = "Specify address to listen on")]
     listen_address: Option<Multiaddr>,
+
+    #[clap(
+        long,
+        help = "Restore the authority store from the latest backup in this directory before starting"
+    )]
+    restore_backup: Option<PathBuf>,
 }
 
 // For memory profiling info see https://github.com/jemalloc/jemalloc/wiki/Use-Case%3A-Heap-Profiling
@@ -35,16 +44,29 @@ static GLOBAL: Jemalloc = Jemalloc;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    let (_guard, filter_handle) =
-        telemetry_subscribers::TelemetryConfig::new(env!("CARGO_BIN_NAME"))
-            .with_env()
-            .init();
-
     let args = Args::parse();
 
     let mut config = NodeConfig::load(&args.config_path)?;
 
+    // Initialize logging, flushed when the guards are dropped
+    let _telemetry_guard;
+    let _log_guard;
+    let filter_handle = match &config.logging_config {
+        Some(logging_config) => {
+            let (guard, filter_handle) = logging::init(env!("CARGO_BIN_NAME"), logging_config)?;
+            _log_guard = guard;
+            filter_handle
+        }
+        None => {
+            let (guard, filter_handle) =
+                telemetry_subscribers::TelemetryConfig::new(env!("CARGO_BIN_NAME"))
+                    .with_env()
+                    .init();
+            _telemetry_guard = guard;
+            LogFilterHandle::Telemetry(filter_handle)
+        }
+    };
+
     if let Some(listen_address) = args.listen_address {
         config.network_address = listen_address;
     }
@@ -74,9 +96,30 @@ async fn main() -> Result<()> {
         });
     }
 
-    sui_node::admin::start_admin_server(config.admin_interface_port, filter_handle);
+    if let Some(dir) = &args.restore_backup {
+        let backup = AuthorityStore::restore_backup(dir, &config.db_path().join("store"))?;
+        tracing::info!(?backup, "Restored the authority store");
+    }
 
     let node = sui_node::SuiNode::start(&config).await?;
+
+    // The tunable settings are applied again when the config is reloaded
+    let reloader = Arc::new(sui_node::reload::ConfigReloader::new(
+        args.config_path.clone(),
+        filter_handle.clone(),
+        &node,
+    )?);
+    reloader.apply(&config)?;
+    #[cfg(unix)]
+    let _hangup_handle = reloader.clone().spawn_on_hangup()?;
+
+    sui_node::admin::start_admin_server(
+        config.admin_interface_port,
+        config.admin_token.clone(),
+        filter_handle,
+        node.state(),
+        reloader,
+    );
     node.wait().await?;
 
     Ok(())
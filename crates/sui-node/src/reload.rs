@@ -0,0 +1,99 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reloading the [`TunableConfig`] of a running node from its config file, on SIGHUP or through
+//! the admin interface. The settings are applied to the running node, which keeps serving: the
+//! requests and certificates in flight are not dropped. The other changes to the config apply
+//! at the next start.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use sui_config::{Config, NodeConfig, TunableConfig};
+use sui_core::serving_policy::ServingPolicy;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::cleanup::SharedTtlConfig;
+use crate::logging::LogFilterHandle;
+use crate::SuiNode;
+
+pub struct ConfigReloader {
+    config_path: PathBuf,
+    filter_handle: LogFilterHandle,
+    /// The log filter the node started with, restored when the config sets none.
+    initial_filter: String,
+    serving_policy: Option<Arc<ServingPolicy>>,
+    ttl_config: Option<SharedTtlConfig>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        config_path: PathBuf,
+        filter_handle: LogFilterHandle,
+        node: &SuiNode,
+    ) -> Result<Self> {
+        let initial_filter = filter_handle
+            .get()
+            .map_err(|e| anyhow!("Failed to read the log filter: {e}"))?;
+        Ok(Self {
+            config_path,
+            filter_handle,
+            initial_filter,
+            serving_policy: node.serving_policy(),
+            ttl_config: node.ttl_config(),
+        })
+    }
+
+    /// Load the config file again and apply its tunable settings, returning them.
+    pub fn reload(&self) -> Result<TunableConfig> {
+        let config = NodeConfig::load(&self.config_path)?;
+        self.apply(&config)?;
+        let tunable_config = config.tunable_config();
+        info!(?tunable_config, path = ?self.config_path, "Reloaded the node config");
+        Ok(tunable_config)
+    }
+
+    /// Apply the tunable settings of `config` to the node. Nothing is applied if the log filter
+    /// is invalid.
+    pub fn apply(&self, config: &NodeConfig) -> Result<()> {
+        let tunable_config = config.tunable_config();
+        let filter = tunable_config
+            .log_filter
+            .as_deref()
+            .unwrap_or(&self.initial_filter);
+        self.filter_handle
+            .update(filter)
+            .map_err(|e| anyhow!("Invalid log filter {filter:?}: {e}"))?;
+        if let Some(serving_policy) = &self.serving_policy {
+            serving_policy.update(&tunable_config);
+        }
+        match (&self.ttl_config, config.ttl_config) {
+            (Some(current), Some(ttl_config)) => *current.lock() = ttl_config,
+            (None, None) => (),
+            _ => {
+                warn!("Enabling or disabling the cleanup of expired rows applies at the next start")
+            }
+        }
+        Ok(())
+    }
+
+    /// Reload the config each time the process receives SIGHUP.
+    #[cfg(unix)]
+    pub fn spawn_on_hangup(self: Arc<Self>) -> Result<JoinHandle<()>> {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangups = signal(SignalKind::hangup())?;
+        Ok(tokio::spawn(async move {
+            while hangups.recv().await.is_some() {
+                let reloader = self.clone();
+                match tokio::task::spawn_blocking(move || reloader.reload()).await {
+                    Ok(Ok(_)) => (),
+                    Ok(Err(e)) => error!("Failed to reload the node config: {e}"),
+                    Err(e) => error!("Failed to reload the node config: {e}"),
+                }
+            }
+        }))
+    }
+}
@@ -0,0 +1,632 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A REST facade over the read portion of the JSON-RPC API, for integrators who cannot easily
+//! speak JSON-RPC. The handlers return the types of the JSON-RPC API, converted from the
+//! authority state the same way, so that both transports return the same data, and the OpenAPI
+//! document is generated from the same JSON schemas used for the OpenRPC spec.
+//!
+//! Payloads are JSON by default. Clients that move large numbers of objects, like indexers and
+//! bridges, can ask for BCS instead with an `Accept: application/x-bcs` header, and send BCS
+//! request bodies with `Content-Type: application/x-bcs`. In BCS, objects, transactions and
+//! events are returned in their native form, as stored by the authorities, and are not parsed.
+
+use axum::{
+    body::Bytes,
+    extract::{Extension, Path, Query},
+    http::{header, HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use sui_core::authority::AuthorityState;
+use sui_json_rpc::read_api::page_limit;
+use sui_json_rpc_types::{
+    BcsObjectRead, GatewayTxSeqNumber, GetObjectDataResponse, ObjectsPage, Page, SuiEvent,
+    SuiObjectInfo, SuiTransactionEffects, TransactionEffectsResponse, TransactionsPage,
+};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::error::SuiError;
+use sui_types::messages_checkpoint::{
+    AuthorityCheckpointInfo, CheckpointRequest, CheckpointSequenceNumber, CheckpointSummary,
+};
+use sui_types::object::{ObjectRead, Owner};
+use sui_types::sui_serde::{Base64, Encoding};
+
+const OPENAPI_ROUTE: &str = "/openapi.json";
+/// The content type of BCS payloads.
+pub const BCS_CONTENT_TYPE: &str = "application/x-bcs";
+/// The maximum number of objects fetched by one request to the batch objects route.
+pub const MAX_OBJECTS_PER_REQUEST: usize = 1000;
+
+struct RestState {
+    state: Arc<AuthorityState>,
+}
+
+/// Serve the REST API at `address`, which is bound before returning so that the node fails to
+/// start if it is not available.
+pub fn start_rest_server(
+    address: SocketAddr,
+    state: Arc<AuthorityState>,
+) -> anyhow::Result<tokio::task::JoinHandle<()>> {
+    let server = axum::Server::try_bind(&address)?.serve(router(state).into_make_service());
+    info!("Starting REST server at {}", server.local_addr());
+
+    Ok(tokio::spawn(async move {
+        if let Err(e) = server.await {
+            error!("REST server failed: {e}");
+        }
+    }))
+}
+
+fn router(state: Arc<AuthorityState>) -> Router {
+    let rest_state = Arc::new(RestState { state });
+
+    Router::new()
+        .route(OPENAPI_ROUTE, get(openapi))
+        .route("/objects", post(get_objects))
+        .route("/objects/:object_id", get(get_object))
+        .route("/addresses/:address/objects", get(get_owned_objects))
+        .route(
+            "/addresses/:address/transactions/from",
+            get(get_transactions_from_address),
+        )
+        .route(
+            "/addresses/:address/transactions/to",
+            get(get_transactions_to_address),
+        )
+        .route("/transactions", get(get_transactions_in_range))
+        .route("/transactions/:digest", get(get_transaction))
+        .route("/transactions/:digest/events", get(get_transaction_events))
+        .route("/checkpoints/latest", get(get_latest_checkpoint))
+        .route("/checkpoints/:sequence_number", get(get_checkpoint))
+        .layer(Extension(rest_state))
+}
+
+/// An error returned to the REST client as a status code and a plain text message.
+pub struct RestError(StatusCode, String);
+
+impl RestError {
+    fn bad_request(e: impl ToString) -> Self {
+        Self(StatusCode::BAD_REQUEST, e.to_string())
+    }
+
+    fn not_found(e: impl ToString) -> Self {
+        Self(StatusCode::NOT_FOUND, e.to_string())
+    }
+
+    fn internal(e: impl ToString) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+/// The errors of missing transactions and objects are returned as not found, and the ones of
+/// invalid queries as bad requests. The others are internal errors of the node.
+impl From<SuiError> for RestError {
+    fn from(e: SuiError) -> Self {
+        match e {
+            SuiError::TransactionNotFound { .. } | SuiError::ObjectNotFound { .. } => {
+                Self::not_found(e)
+            }
+            SuiError::GatewayInvalidTxRangeQuery { .. } => Self::bad_request(e),
+            e => Self::internal(e),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RestError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<SuiError>() {
+            Ok(e) => e.into(),
+            Err(e) => Self::internal(e),
+        }
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+type RestResult = Result<Response, RestError>;
+
+/// The encoding of a payload, negotiated with the client through the `Content-Type` header of
+/// requests and the `Accept` header of responses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PayloadFormat {
+    Json,
+    Bcs,
+}
+
+impl PayloadFormat {
+    /// The encoding of the response, JSON unless the client accepts BCS.
+    fn accepted(headers: &HeaderMap) -> Self {
+        Self::from_header(headers, header::ACCEPT)
+    }
+
+    /// The encoding of the request body, JSON unless it is sent as BCS.
+    fn of_body(headers: &HeaderMap) -> Self {
+        Self::from_header(headers, header::CONTENT_TYPE)
+    }
+
+    fn from_header(headers: &HeaderMap, name: HeaderName) -> Self {
+        let is_bcs = headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|media_type| media_type.trim().starts_with(BCS_CONTENT_TYPE));
+        if is_bcs {
+            Self::Bcs
+        } else {
+            Self::Json
+        }
+    }
+
+    fn encode<T: Serialize>(self, value: &T) -> RestResult {
+        match self {
+            Self::Json => Ok(Json(value).into_response()),
+            Self::Bcs => {
+                let bytes = bcs::to_bytes(value).map_err(RestError::internal)?;
+                Ok(([(header::CONTENT_TYPE, BCS_CONTENT_TYPE)], bytes).into_response())
+            }
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(self, body: &[u8]) -> Result<T, RestError> {
+        match self {
+            Self::Json => serde_json::from_slice(body).map_err(RestError::bad_request),
+            Self::Bcs => bcs::from_bytes(body).map_err(RestError::bad_request),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ObjectsPageQuery {
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct TransactionsPageQuery {
+    cursor: Option<GatewayTxSeqNumber>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct RangeQuery {
+    start: GatewayTxSeqNumber,
+    end: GatewayTxSeqNumber,
+}
+
+fn parse_object_id(s: &str) -> Result<ObjectID, RestError> {
+    ObjectID::from_str(s).map_err(RestError::bad_request)
+}
+
+fn parse_address(s: &str) -> Result<SuiAddress, RestError> {
+    SuiAddress::from_str(s).map_err(RestError::bad_request)
+}
+
+fn parse_digest(s: &str) -> Result<TransactionDigest, RestError> {
+    let bytes = Base64::decode(s).map_err(RestError::bad_request)?;
+    TransactionDigest::try_from(&bytes[..]).map_err(RestError::bad_request)
+}
+
+impl RestState {
+    /// The object with the given ID, in the requested encoding, with how it was found.
+    async fn object(&self, object_id: ObjectID, format: PayloadFormat) -> RestResult {
+        let read = self.state.get_object_read(&object_id).await?;
+        let status = match read {
+            ObjectRead::NotExists(_) => StatusCode::NOT_FOUND,
+            _ => StatusCode::OK,
+        };
+        let response = match format {
+            PayloadFormat::Json => {
+                let object: GetObjectDataResponse = read.try_into().map_err(RestError::internal)?;
+                format.encode(&object)?
+            }
+            PayloadFormat::Bcs => format.encode(&BcsObjectRead::from(read))?,
+        };
+        Ok((status, response).into_response())
+    }
+
+    async fn bcs_object(&self, object_id: ObjectID) -> Result<BcsObjectRead, RestError> {
+        Ok(self
+            .state
+            .get_object_read(&object_id)
+            .await
+            .map(BcsObjectRead::from)?)
+    }
+}
+
+async fn get_object(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(object_id): Path<String>,
+) -> RestResult {
+    let object_id = parse_object_id(&object_id)?;
+    rest.object(object_id, PayloadFormat::accepted(&headers))
+        .await
+}
+
+/// Fetch the objects whose IDs are listed in the request body, in the same order.
+async fn get_objects(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> RestResult {
+    let object_ids: Vec<ObjectID> = PayloadFormat::of_body(&headers).decode(&body)?;
+    if object_ids.len() > MAX_OBJECTS_PER_REQUEST {
+        return Err(RestError::bad_request(format!(
+            "Too many objects requested, the maximum is {MAX_OBJECTS_PER_REQUEST}"
+        )));
+    }
+    let format = PayloadFormat::accepted(&headers);
+    match format {
+        PayloadFormat::Json => {
+            let mut objects: Vec<GetObjectDataResponse> = Vec::with_capacity(object_ids.len());
+            for object_id in object_ids {
+                let read = rest.state.get_object_read(&object_id).await?;
+                objects.push(read.try_into().map_err(RestError::internal)?);
+            }
+            format.encode(&objects)
+        }
+        PayloadFormat::Bcs => {
+            let mut objects = Vec::with_capacity(object_ids.len());
+            for object_id in object_ids {
+                objects.push(rest.bcs_object(object_id).await?);
+            }
+            format.encode(&objects)
+        }
+    }
+}
+
+async fn get_owned_objects(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Query(query): Query<ObjectsPageQuery>,
+) -> RestResult {
+    let address = parse_address(&address)?;
+    let cursor = query.cursor.as_deref().map(parse_object_id).transpose()?;
+    let limit = page_limit(query.limit).map_err(RestError::bad_request)?;
+    let objects = rest
+        .state
+        .get_owner_objects_page(Owner::AddressOwner(address), cursor, limit + 1)?
+        .into_iter()
+        .map(SuiObjectInfo::from)
+        .collect();
+    let page: ObjectsPage = Page::new(objects, limit, |info| info.object_id);
+    PayloadFormat::accepted(&headers).encode(&page)
+}
+
+async fn get_transactions_from_address(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Query(query): Query<TransactionsPageQuery>,
+) -> RestResult {
+    let address = parse_address(&address)?;
+    let limit = page_limit(query.limit).map_err(RestError::bad_request)?;
+    let transactions = rest
+        .state
+        .get_transactions_from_addr_page(address, query.cursor, limit + 1)
+        .await?;
+    let page: TransactionsPage = Page::new(transactions, limit, |(seq, _)| *seq);
+    PayloadFormat::accepted(&headers).encode(&page)
+}
+
+async fn get_transactions_to_address(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(address): Path<String>,
+    Query(query): Query<TransactionsPageQuery>,
+) -> RestResult {
+    let address = parse_address(&address)?;
+    let limit = page_limit(query.limit).map_err(RestError::bad_request)?;
+    let transactions = rest
+        .state
+        .get_transactions_to_addr_page(address, query.cursor, limit + 1)
+        .await?;
+    let page: TransactionsPage = Page::new(transactions, limit, |(seq, _)| *seq);
+    PayloadFormat::accepted(&headers).encode(&page)
+}
+
+async fn get_transactions_in_range(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Query(query): Query<RangeQuery>,
+) -> RestResult {
+    let transactions: Vec<(GatewayTxSeqNumber, TransactionDigest)> = rest
+        .state
+        .get_transactions_in_range(query.start, query.end)?;
+    PayloadFormat::accepted(&headers).encode(&transactions)
+}
+
+/// In BCS, the certificate and effects of the transaction are returned as a pair.
+async fn get_transaction(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(digest): Path<String>,
+) -> RestResult {
+    let digest = parse_digest(&digest)?;
+    let format = PayloadFormat::accepted(&headers);
+    match format {
+        PayloadFormat::Json => {
+            let (certificate, effects) = rest.state.get_transaction(digest).await?;
+            let response = TransactionEffectsResponse {
+                certificate: certificate.try_into().map_err(RestError::internal)?,
+                effects: SuiTransactionEffects::try_from(effects, &rest.state.module_cache)
+                    .map_err(RestError::internal)?,
+                timestamp_ms: rest.state.get_timestamp_ms(&digest).await?,
+            };
+            format.encode(&response)
+        }
+        PayloadFormat::Bcs => format.encode(&rest.state.get_transaction(digest).await?),
+    }
+}
+
+async fn get_transaction_events(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(digest): Path<String>,
+) -> RestResult {
+    let digest = parse_digest(&digest)?;
+    let format = PayloadFormat::accepted(&headers);
+    let (_, effects) = rest.state.get_transaction(digest).await?;
+    match format {
+        PayloadFormat::Json => {
+            let events: Vec<SuiEvent> =
+                SuiTransactionEffects::try_from(effects, &rest.state.module_cache)
+                    .map_err(RestError::internal)?
+                    .events;
+            format.encode(&events)
+        }
+        PayloadFormat::Bcs => format.encode(&effects.events),
+    }
+}
+
+async fn get_latest_checkpoint(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+) -> RestResult {
+    PayloadFormat::accepted(&headers).encode(&checkpoint_summary(&rest.state, None)?)
+}
+
+async fn get_checkpoint(
+    Extension(rest): Extension<Arc<RestState>>,
+    headers: HeaderMap,
+    Path(sequence_number): Path<CheckpointSequenceNumber>,
+) -> RestResult {
+    PayloadFormat::accepted(&headers)
+        .encode(&checkpoint_summary(&rest.state, Some(sequence_number))?)
+}
+
+fn checkpoint_summary(
+    state: &AuthorityState,
+    seq: Option<CheckpointSequenceNumber>,
+) -> Result<CheckpointSummary, RestError> {
+    let response =
+        state.handle_checkpoint_request(&CheckpointRequest::authenticated(seq, false))?;
+    match response.info {
+        AuthorityCheckpointInfo::AuthenticatedCheckpoint(Some(checkpoint)) => {
+            Ok(checkpoint.summary().clone())
+        }
+        _ => Err(RestError::not_found("Checkpoint not found")),
+    }
+}
+
+async fn openapi() -> Json<Value> {
+    Json(openapi_document())
+}
+
+/// Build the OpenAPI 3 document describing the routes served by [start_rest_server].
+pub fn openapi_document() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+
+    let object = schema_for::<GetObjectDataResponse>(&mut gen);
+    let objects = schema_for::<Vec<GetObjectDataResponse>>(&mut gen);
+    let object_ids = schema_for::<Vec<ObjectID>>(&mut gen);
+    let objects_page = schema_for::<ObjectsPage>(&mut gen);
+    let transactions_page = schema_for::<TransactionsPage>(&mut gen);
+    let transactions = schema_for::<Vec<(GatewayTxSeqNumber, TransactionDigest)>>(&mut gen);
+    let transaction = schema_for::<TransactionEffectsResponse>(&mut gen);
+    let events = schema_for::<Vec<SuiEvent>>(&mut gen);
+    let checkpoint = schema_for::<CheckpointSummary>(&mut gen);
+
+    let path_param = |name: &str, description: &str| {
+        json!({
+            "name": name,
+            "in": "path",
+            "required": true,
+            "description": description,
+            "schema": { "type": "string" },
+        })
+    };
+    let query_param = |name: &str, description: &str, schema: Value, required: bool| {
+        json!({
+            "name": name,
+            "in": "query",
+            "required": required,
+            "description": description,
+            "schema": schema,
+        })
+    };
+    let uint64 = json!({ "type": "integer", "format": "uint64", "minimum": 0 });
+    let limit = query_param(
+        "limit",
+        "maximum number of items in the page",
+        uint64.clone(),
+        false,
+    );
+    let seq_cursor = query_param(
+        "cursor",
+        "the sequence number to start the page from (inclusive)",
+        uint64.clone(),
+        false,
+    );
+    // BCS payloads are the native types of the objects, transactions and events.
+    let content = |schema: &Value| {
+        json!({
+            "application/json": { "schema": schema },
+            BCS_CONTENT_TYPE: { "schema": { "type": "string", "format": "binary" } },
+        })
+    };
+    let responses = |schema: &Value| {
+        json!({
+            "200": { "description": "OK", "content": content(schema) },
+            "400": { "description": "Invalid request" },
+            "404": { "description": "Not found" },
+            "500": { "description": "Internal error" },
+        })
+    };
+    let operation = |summary: &str, parameters: Vec<Value>, schema: &Value| {
+        json!({
+            "get": {
+                "summary": summary,
+                "parameters": parameters,
+                "responses": responses(schema),
+            }
+        })
+    };
+
+    let address = path_param("address", "the Sui address, as a hex string");
+    let digest = path_param(
+        "digest",
+        "the transaction digest, as a base-64 encoded string",
+    );
+
+    let mut paths = Map::new();
+    paths.insert(
+        "/objects".into(),
+        json!({
+            "post": {
+                "summary": format!(
+                    "Return the objects with the IDs listed in the request body, at most {} \
+                     per request",
+                    MAX_OBJECTS_PER_REQUEST
+                ),
+                "requestBody": { "required": true, "content": content(&object_ids) },
+                "responses": responses(&objects),
+            }
+        }),
+    );
+    paths.insert(
+        "/objects/{object_id}".into(),
+        operation(
+            "Return the object information for a specified object",
+            vec![path_param("object_id", "the ID of the queried object")],
+            &object,
+        ),
+    );
+    paths.insert(
+        "/addresses/{address}/objects".into(),
+        operation(
+            "Return a page of objects owned by an address, ordered by object ID",
+            vec![
+                address.clone(),
+                query_param(
+                    "cursor",
+                    "the object ID to start the page from (inclusive)",
+                    json!({ "type": "string" }),
+                    false,
+                ),
+                limit.clone(),
+            ],
+            &objects_page,
+        ),
+    );
+    paths.insert(
+        "/addresses/{address}/transactions/from".into(),
+        operation(
+            "Return a page of transactions sent by an address",
+            vec![address.clone(), seq_cursor.clone(), limit.clone()],
+            &transactions_page,
+        ),
+    );
+    paths.insert(
+        "/addresses/{address}/transactions/to".into(),
+        operation(
+            "Return a page of transactions received by an address",
+            vec![address, seq_cursor, limit],
+            &transactions_page,
+        ),
+    );
+    paths.insert(
+        "/transactions".into(),
+        operation(
+            "Return the transactions in the sequence number range [start, end)",
+            vec![
+                query_param("start", "the start of the range", uint64.clone(), true),
+                query_param("end", "the end of the range (exclusive)", uint64, true),
+            ],
+            &transactions,
+        ),
+    );
+    paths.insert(
+        "/transactions/{digest}".into(),
+        operation(
+            "Return the certificate and effects of a transaction",
+            vec![digest.clone()],
+            &transaction,
+        ),
+    );
+    paths.insert(
+        "/transactions/{digest}/events".into(),
+        operation(
+            "Return the events emitted by a transaction",
+            vec![digest],
+            &events,
+        ),
+    );
+    paths.insert(
+        "/checkpoints/latest".into(),
+        operation(
+            "Return the summary of the latest stored checkpoint",
+            vec![],
+            &checkpoint,
+        ),
+    );
+    paths.insert(
+        "/checkpoints/{sequence_number}".into(),
+        operation(
+            "Return the summary of a checkpoint",
+            vec![path_param(
+                "sequence_number",
+                "the sequence number of the checkpoint",
+            )],
+            &checkpoint,
+        ),
+    );
+
+    let schemas: Map<String, Value> = gen
+        .take_definitions()
+        .into_iter()
+        .map(|(name, schema)| (name, serde_json::to_value(schema).unwrap_or_default()))
+        .collect();
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Sui REST API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": { "schemas": schemas },
+    })
+}
+
+fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    serde_json::to_value(gen.subschema_for::<T>()).unwrap_or_default()
+}
@@ -0,0 +1,146 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in pushes of the health of a node to the operators of the network, see
+//! [`TelemetryPushConfig`].
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sui_config::node::TelemetryPushConfig;
+use sui_core::authority::AuthorityState;
+use sui_core::authority_active::checkpoint_driver::get_latest_checkpoint_from_all;
+use sui_core::authority_active::ActiveAuthority;
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_network::discovery::PeerTable;
+use sui_types::base_types::ChainId;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// How long pushing a report and learning the highest checkpoint of the network may take.
+const REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the checkpoints of the other validators are waited for once a quorum sent theirs.
+const EXTRA_TIME_AFTER_QUORUM: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    /// Drawn at random when the node starts, so that the reports of a run can be told apart.
+    pub instance_id: u64,
+    pub chain_id: ChainId,
+    pub version: &'static str,
+    pub validator: bool,
+    pub uptime_secs: u64,
+    /// The next checkpoint of the node, if it keeps checkpoints.
+    pub next_checkpoint: Option<u64>,
+    /// The checkpoints the node is behind the highest one certified by the validators, if the
+    /// node keeps checkpoints and a quorum of validators answered.
+    pub sync_lag: Option<u64>,
+    /// The peers known to the node if it discovers peers, the validators it follows otherwise.
+    pub peers: Option<usize>,
+}
+
+/// Gathers the [`HealthReport`]s of a node.
+pub struct HealthReporter {
+    instance_id: u64,
+    started_at: Instant,
+    validator: bool,
+    state: Arc<AuthorityState>,
+    active: Option<Arc<ActiveAuthority<NetworkAuthorityClient>>>,
+    peer_table: Option<Arc<PeerTable>>,
+}
+
+impl HealthReporter {
+    pub fn new(
+        validator: bool,
+        state: Arc<AuthorityState>,
+        active: Option<Arc<ActiveAuthority<NetworkAuthorityClient>>>,
+        peer_table: Option<Arc<PeerTable>>,
+    ) -> Self {
+        Self {
+            instance_id: rand::random(),
+            started_at: Instant::now(),
+            validator,
+            state,
+            active,
+            peer_table,
+        }
+    }
+
+    pub async fn report(&self) -> HealthReport {
+        let next_checkpoint = self
+            .state
+            .checkpoints()
+            .map(|checkpoints| checkpoints.lock().next_checkpoint());
+        let sync_lag = match (next_checkpoint, &self.active) {
+            (Some(next_checkpoint), Some(active)) => {
+                let highest = get_latest_checkpoint_from_all(
+                    active.net.load_full(),
+                    EXTRA_TIME_AFTER_QUORUM,
+                    REPORT_TIMEOUT,
+                )
+                .await;
+                match highest {
+                    Ok(highest) => Some(highest.map_or(0, |checkpoint| {
+                        (checkpoint.summary.sequence_number + 1).saturating_sub(next_checkpoint)
+                    })),
+                    Err(e) => {
+                        debug!("Failed to learn the highest checkpoint of the network: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        let peers = match (&self.peer_table, &self.active) {
+            (Some(peer_table), _) => Some(peer_table.len()),
+            (None, Some(active)) => Some(active.net.load().authority_clients.len()),
+            (None, None) => None,
+        };
+        HealthReport {
+            instance_id: self.instance_id,
+            chain_id: self.state.chain_id,
+            version: env!("CARGO_PKG_VERSION"),
+            validator: self.validator,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            next_checkpoint,
+            sync_lag,
+            peers,
+        }
+    }
+}
+
+/// Post a report of the health of the node to `config.endpoint` every `config.interval_secs`.
+/// Failed pushes are logged and not retried, the next report is pushed in time.
+pub fn spawn_telemetry_push(
+    config: TelemetryPushConfig,
+    reporter: HealthReporter,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(REPORT_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Failed to create the client pushing the telemetry of the node: {e}");
+                return;
+            }
+        };
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        loop {
+            interval.tick().await;
+            let report = reporter.report().await;
+            let pushed = client
+                .post(&config.endpoint)
+                .json(&report)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            match pushed {
+                Ok(_) => debug!(?report, "Pushed the health of the node"),
+                Err(e) => warn!(
+                    "Failed to push the health of the node to {}: {e}",
+                    config.endpoint
+                ),
+            }
+        }
+    })
+}
@@ -187,7 +187,10 @@ async fn create_package_object_response(
         package_path,
         build_config,
         gas: None,
-        gas_budget: 10000,
+        gas_budget: Some(10000),
+        estimate_gas: false,
+        skip_dependency_verification: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -210,10 +213,11 @@ async fn create_transfer_response(
     coins: &[SuiObjectInfo],
 ) -> Result<TransactionResponse, anyhow::Error> {
     let response = SuiClientCommands::Transfer {
-        to: address,
+        to: address.into(),
         coin_object_id: coins.first().unwrap().object_id,
         gas: None,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(context)
     .await?;
@@ -236,9 +240,10 @@ async fn create_transfer_sui_response(
     coins: &[SuiObjectInfo],
 ) -> Result<TransactionResponse, anyhow::Error> {
     let response = SuiClientCommands::TransferSui {
-        to: address,
+        to: address.into(),
         sui_coin_object_id: coins.first().unwrap().object_id,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
         amount: Some(10),
     }
     .execute(context)
@@ -269,7 +274,10 @@ async fn create_hero_response(
         package_path,
         gas: None,
         build_config,
-        gas_budget: 10000,
+        gas_budget: Some(10000),
+        estimate_gas: false,
+        skip_dependency_verification: false,
+        dry_run: false,
     }
     .execute(context)
     .await?;
@@ -290,7 +298,8 @@ async fn create_hero_response(
             type_args: vec![],
             args: vec![game_info, coin],
             gas: None,
-            gas_budget: 10000,
+            gas_budget: Some(10000),
+            estimate_gas: false,
         }
         .execute(context)
         .await?;
@@ -364,8 +373,10 @@ async fn create_coin_split_response(
     let result = SuiClientCommands::SplitCoin {
         coin_id: coins.first().unwrap().object_id,
         amounts: vec![20, 20, 20, 20, 20],
+        count: None,
         gas: None,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(context)
     .await?;
@@ -397,7 +408,8 @@ async fn get_nft_response(
         type_args: vec![],
         args,
         gas: None,
-        gas_budget: 10000,
+        gas_budget: Some(10000),
+        estimate_gas: false,
     }
     .execute(context)
     .await?;
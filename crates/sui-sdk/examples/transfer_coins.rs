@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::str::FromStr;
-use sui_sdk::crypto::{Keystore, SuiKeystore};
+use sui_sdk::crypto::SuiKeystore;
+use sui_sdk::transaction_builder::TransactionRequest;
 use sui_sdk::types::base_types::{ObjectID, SuiAddress};
-use sui_sdk::types::sui_serde::Base64;
 use sui_sdk::SuiClient;
 
 #[tokio::main]
@@ -23,20 +23,17 @@ async fn main() -> Result<(), anyhow::Error> {
 
     // Create a sui transfer transaction
     let transfer_tx = sui
-        .transfer_sui(my_address, gas_object_id, 1000, recipient, Some(1000))
+        .transaction_builder(my_address)
+        .gas_budget(1000)
+        .build(TransactionRequest::TransferSui {
+            sui_object_id: gas_object_id,
+            recipient,
+            amount: Some(1000),
+        })
         .await?;
 
-    // Sign the transaction
-    let signature = keystore.sign(&my_address, &transfer_tx.tx_bytes.to_vec()?)?;
-
-    // Execute the transaction
-    let transaction_response = sui
-        .execute_transaction(
-            transfer_tx.tx_bytes,
-            Base64::from_bytes(signature.signature_bytes()),
-            Base64::from_bytes(signature.public_key_bytes()),
-        )
-        .await?;
+    // Sign the transaction with the key of `my_address` and execute it
+    let transaction_response = sui.sign_and_execute(transfer_tx, &keystore).await?;
 
     println!("{:?}", transaction_response);
 
@@ -3,13 +3,14 @@
 
 use serde::{Deserialize, Serialize};
 use signature::Signer;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write;
 use std::fmt::{Display, Formatter};
 use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 
 use sui_types::base_types::SuiAddress;
@@ -26,6 +27,10 @@ pub trait Keystore: Send + Sync {
     fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error>;
     fn add_random_key(&mut self) -> Result<SuiAddress, anyhow::Error>;
     fn add_key(&mut self, keypair: KeyPair) -> Result<(), anyhow::Error>;
+    /// Track an address without its private key. Transactions can be built for it, but
+    /// they have to be signed outside of the keystore.
+    fn add_watch_only_address(&mut self, address: SuiAddress) -> Result<(), anyhow::Error>;
+    fn is_watch_only(&self, address: &SuiAddress) -> bool;
 }
 
 impl KeystoreType {
@@ -52,11 +57,17 @@ impl Display for KeystoreType {
 #[derive(Serialize, Deserialize, Default)]
 pub struct SuiKeystore {
     keys: BTreeMap<SuiAddress, KeyPair>,
+    watch_only: BTreeSet<SuiAddress>,
     path: Option<PathBuf>,
 }
 
 impl Keystore for SuiKeystore {
     fn sign(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        if self.watch_only.contains(address) {
+            return Err(signature::Error::from_source(format!(
+                "Address [{address}] is watch-only, its transactions must be signed externally"
+            )));
+        }
         self.keys
             .get(address)
             .ok_or_else(|| {
@@ -74,33 +85,49 @@ impl Keystore for SuiKeystore {
 
     fn add_key(&mut self, keypair: KeyPair) -> Result<(), anyhow::Error> {
         let address: SuiAddress = keypair.public().into();
+        self.watch_only.remove(&address);
         self.keys.insert(address, keypair);
         self.save()?;
         Ok(())
     }
+
+    fn add_watch_only_address(&mut self, address: SuiAddress) -> Result<(), anyhow::Error> {
+        if self.keys.contains_key(&address) {
+            return Err(anyhow::anyhow!(
+                "Address [{address}] is already in the keystore with its private key"
+            ));
+        }
+        self.watch_only.insert(address);
+        self.save()
+    }
+
+    fn is_watch_only(&self, address: &SuiAddress) -> bool {
+        self.watch_only.contains(address)
+    }
 }
 
 impl SuiKeystore {
     pub fn load_or_create(path: &Path) -> Result<Self, anyhow::Error> {
-        let keys: Vec<KeyPair> = if path.exists() {
+        let mut keys = BTreeMap::new();
+        let mut watch_only = BTreeSet::new();
+        if path.exists() {
             let reader = BufReader::new(File::open(path)?);
             let kp_strings: Vec<String> = serde_json::from_reader(reader)?;
-            kp_strings
-                .iter()
-                .map(|kpstr| KeyPair::decode_base64(kpstr))
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|_| anyhow::anyhow!("Invalid Keypair file"))?
-        } else {
-            Vec::new()
-        };
-
-        let keys = keys
-            .into_iter()
-            .map(|key| (key.public().into(), key))
-            .collect();
+            // Watch-only addresses are stored as hex addresses alongside the encoded keypairs.
+            for kpstr in kp_strings {
+                if let Ok(key) = KeyPair::decode_base64(&kpstr) {
+                    keys.insert(key.public().into(), key);
+                } else {
+                    let address = SuiAddress::from_str(&kpstr)
+                        .map_err(|_| anyhow::anyhow!("Invalid Keypair file"))?;
+                    watch_only.insert(address);
+                }
+            }
+        }
 
         Ok(Self {
             keys,
+            watch_only,
             path: Some(path.to_path_buf()),
         })
     }
@@ -116,6 +143,7 @@ impl SuiKeystore {
                     .keys
                     .values()
                     .map(|k| k.encode_base64())
+                    .chain(self.watch_only.iter().map(|address| address.to_string()))
                     .collect::<Vec<_>>(),
             )
             .unwrap();
@@ -133,6 +161,10 @@ impl SuiKeystore {
         self.keys.keys().cloned().collect()
     }
 
+    pub fn watch_only_addresses(&self) -> Vec<SuiAddress> {
+        self.watch_only.iter().cloned().collect()
+    }
+
     pub fn key_pairs(&self) -> Vec<&KeyPair> {
         self.keys.values().collect()
     }
@@ -2,11 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
-use futures::StreamExt;
+use futures::{stream, StreamExt, TryStreamExt};
 use futures_core::Stream;
-use jsonrpsee::core::client::Subscription;
+use jsonrpsee::core::client::{ClientT, Subscription};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::rpc_params;
 use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use std::collections::BTreeMap;
+use std::future::Future;
 use sui_json::SuiJsonValue;
 use sui_json_rpc::api::EventStreamingApiClient;
 use sui_json_rpc::api::RpcBcsApiClient;
@@ -16,13 +19,23 @@ use sui_json_rpc::api::RpcReadApiClient;
 use sui_json_rpc::api::RpcTransactionBuilderClient;
 use sui_json_rpc::api::WalletSyncApiClient;
 use sui_json_rpc_types::{
-    GatewayTxSeqNumber, GetObjectDataResponse, GetRawObjectDataResponse,
-    RPCTransactionRequestParams, SuiEventEnvelope, SuiEventFilter, SuiObjectInfo, SuiTypeTag,
-    TransactionBytes, TransactionEffectsResponse, TransactionResponse,
+    AddressTransaction, AddressTransactionsPage, GatewayTxSeqNumber, GetObjectDataResponse,
+    GetRawObjectDataResponse, ObjectsPage, Page, RPCTransactionRequestParams, SuiEventEnvelope,
+    SuiEventFilter, SuiGasEstimate, SuiMoveNormalizedFunction, SuiMoveNormalizedModule,
+    SuiMoveNormalizedStruct, SuiObjectChange, SuiObjectInfo, SuiPublishPreview, SuiRawMovePackage,
+    SuiTransactionPreview, SuiTypeTag, TransactionBytes, TransactionDirection,
+    TransactionEffectsResponse, TransactionResponse, TransactionsPage,
 };
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::crypto::SignableBytes;
+use sui_types::messages::TransactionData;
 use sui_types::sui_serde::Base64;
+
+use crate::crypto::Keystore;
+use crate::transaction_builder::TransactionBuilder;
+
 pub mod crypto;
+pub mod transaction_builder;
 
 // re-export essential sui crates
 pub use sui_json as json;
@@ -122,6 +135,29 @@ impl SuiClient {
         .await?)
     }
 
+    /// Fetch multiple objects in a single JSON-RPC batch request.
+    /// The responses are returned in the same order as `object_ids`.
+    pub async fn get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> anyhow::Result<Vec<GetObjectDataResponse>> {
+        if object_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let batch = object_ids
+            .into_iter()
+            .map(|id| ("sui_getObject", rpc_params![id]))
+            .collect::<Vec<_>>();
+        let responses: Vec<serde_json::Value> = match &self.client {
+            Client::Http(c) => c.batch_request(batch).await?,
+            Client::Ws(c) => c.batch_request(batch).await?,
+        };
+        responses
+            .into_iter()
+            .map(|response| Ok(serde_json::from_value(response)?))
+            .collect()
+    }
+
     pub async fn get_raw_object(
         &self,
         object_id: ObjectID,
@@ -133,6 +169,63 @@ impl SuiClient {
         .await?)
     }
 
+    pub async fn get_raw_package(&self, package_id: ObjectID) -> anyhow::Result<SuiRawMovePackage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_raw_package(package_id),
+            Client::Ws(c) => c.get_raw_package(package_id),
+        }
+        .await?)
+    }
+
+    pub async fn get_normalized_move_modules_by_package(
+        &self,
+        package: ObjectID,
+    ) -> anyhow::Result<BTreeMap<String, SuiMoveNormalizedModule>> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_normalized_move_modules_by_package(package),
+            Client::Ws(c) => c.get_normalized_move_modules_by_package(package),
+        }
+        .await?)
+    }
+
+    pub async fn get_normalized_move_module(
+        &self,
+        package: ObjectID,
+        module_name: String,
+    ) -> anyhow::Result<SuiMoveNormalizedModule> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_normalized_move_module(package, module_name),
+            Client::Ws(c) => c.get_normalized_move_module(package, module_name),
+        }
+        .await?)
+    }
+
+    pub async fn get_normalized_move_struct(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        struct_name: String,
+    ) -> anyhow::Result<SuiMoveNormalizedStruct> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_normalized_move_struct(package, module_name, struct_name),
+            Client::Ws(c) => c.get_normalized_move_struct(package, module_name, struct_name),
+        }
+        .await?)
+    }
+
+    pub async fn get_normalized_move_function(
+        &self,
+        package: ObjectID,
+        module_name: String,
+        function_name: String,
+    ) -> anyhow::Result<SuiMoveNormalizedFunction> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_normalized_move_function(package, module_name, function_name),
+            Client::Ws(c) => c.get_normalized_move_function(package, module_name, function_name),
+        }
+        .await?)
+    }
+
     pub async fn get_transactions_by_input_object(
         &self,
         object: ObjectID,
@@ -190,6 +283,84 @@ impl SuiClient {
         .await?)
     }
 
+    pub async fn get_objects_owned_by_address_page(
+        &self,
+        address: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<ObjectsPage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_objects_owned_by_address_page(address, cursor, limit),
+            Client::Ws(c) => c.get_objects_owned_by_address_page(address, cursor, limit),
+        }
+        .await?)
+    }
+
+    pub async fn get_dynamic_fields(
+        &self,
+        parent_object_id: ObjectID,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<ObjectsPage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_dynamic_fields(parent_object_id, cursor, limit),
+            Client::Ws(c) => c.get_dynamic_fields(parent_object_id, cursor, limit),
+        }
+        .await?)
+    }
+
+    pub async fn get_dynamic_field_object(
+        &self,
+        parent_object_id: ObjectID,
+        name: ObjectID,
+    ) -> anyhow::Result<GetObjectDataResponse> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_dynamic_field_object(parent_object_id, name),
+            Client::Ws(c) => c.get_dynamic_field_object(parent_object_id, name),
+        }
+        .await?)
+    }
+
+    pub async fn get_transactions_from_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<TransactionsPage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_transactions_from_addr_page(addr, cursor, limit),
+            Client::Ws(c) => c.get_transactions_from_addr_page(addr, cursor, limit),
+        }
+        .await?)
+    }
+
+    pub async fn get_transactions_to_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+    ) -> anyhow::Result<TransactionsPage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_transactions_to_addr_page(addr, cursor, limit),
+            Client::Ws(c) => c.get_transactions_to_addr_page(addr, cursor, limit),
+        }
+        .await?)
+    }
+
+    pub async fn get_transactions_for_address(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<GatewayTxSeqNumber>,
+        limit: Option<usize>,
+        direction: Option<TransactionDirection>,
+    ) -> anyhow::Result<AddressTransactionsPage> {
+        Ok(match &self.client {
+            Client::Http(c) => c.get_transactions_for_address(addr, cursor, limit, direction),
+            Client::Ws(c) => c.get_transactions_for_address(addr, cursor, limit, direction),
+        }
+        .await?)
+    }
+
     pub async fn execute_transaction(
         &self,
         tx_bytes: Base64,
@@ -203,6 +374,33 @@ impl SuiClient {
         .await?)
     }
 
+    pub async fn estimate_gas(&self, tx_bytes: Base64) -> anyhow::Result<SuiGasEstimate> {
+        Ok(match &self.client {
+            Client::Http(c) => c.estimate_gas(tx_bytes),
+            Client::Ws(c) => c.estimate_gas(tx_bytes),
+        }
+        .await?)
+    }
+
+    pub async fn dry_run_transaction(
+        &self,
+        tx_bytes: Base64,
+    ) -> anyhow::Result<SuiTransactionPreview> {
+        Ok(match &self.client {
+            Client::Http(c) => c.dry_run_transaction(tx_bytes),
+            Client::Ws(c) => c.dry_run_transaction(tx_bytes),
+        }
+        .await?)
+    }
+
+    pub async fn dry_run_publish(&self, tx_bytes: Base64) -> anyhow::Result<SuiPublishPreview> {
+        Ok(match &self.client {
+            Client::Http(c) => c.dry_run_publish(tx_bytes),
+            Client::Ws(c) => c.dry_run_publish(tx_bytes),
+        }
+        .await?)
+    }
+
     pub async fn transfer_object(
         &self,
         signer: SuiAddress,
@@ -313,6 +511,36 @@ impl SuiClient {
         .await?)
     }
 
+    pub async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionBytes> {
+        Ok(match &self.client {
+            Client::Http(c) => c.pay(signer, input_coins, recipients, amounts, gas, gas_budget),
+            Client::Ws(c) => c.pay(signer, input_coins, recipients, amounts, gas, gas_budget),
+        }
+        .await?)
+    }
+
+    pub async fn pay_all(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipient: SuiAddress,
+        gas_budget: u64,
+    ) -> anyhow::Result<TransactionBytes> {
+        Ok(match &self.client {
+            Client::Http(c) => c.pay_all(signer, input_coins, recipient, gas_budget),
+            Client::Ws(c) => c.pay_all(signer, input_coins, recipient, gas_budget),
+        }
+        .await?)
+    }
+
     pub async fn batch_transaction(
         &self,
         signer: SuiAddress,
@@ -339,6 +567,60 @@ impl SuiClient {
         .await?)
     }
 
+    /// Build transactions sent by `signer`.
+    pub fn transaction_builder(&self, signer: SuiAddress) -> TransactionBuilder<'_> {
+        TransactionBuilder::new(self, signer)
+    }
+
+    /// Sign `tx` with the key of its sender held by `keystore`, then execute it.
+    pub async fn sign_and_execute(
+        &self,
+        tx: TransactionBytes,
+        keystore: &dyn Keystore,
+    ) -> anyhow::Result<TransactionResponse> {
+        let tx_bytes = tx.tx_bytes.to_vec()?;
+        let sender = TransactionData::from_signable_bytes(&tx_bytes)?.signer();
+        let signature = keystore.sign(&sender, &tx_bytes)?;
+        self.execute_transaction(
+            tx.tx_bytes,
+            Base64::from_bytes(signature.signature_bytes()),
+            Base64::from_bytes(signature.public_key_bytes()),
+        )
+        .await
+    }
+
+    /// All the objects owned by `address`, fetched `page_size` objects at a time.
+    pub fn get_objects_owned_by_address_stream(
+        &self,
+        address: SuiAddress,
+        page_size: Option<usize>,
+    ) -> impl Stream<Item = anyhow::Result<SuiObjectInfo>> + '_ {
+        paginate(move |cursor| self.get_objects_owned_by_address_page(address, cursor, page_size))
+    }
+
+    /// All the dynamic fields of the object `parent_object_id`, fetched `page_size` fields at a
+    /// time.
+    pub fn get_dynamic_fields_stream(
+        &self,
+        parent_object_id: ObjectID,
+        page_size: Option<usize>,
+    ) -> impl Stream<Item = anyhow::Result<SuiObjectInfo>> + '_ {
+        paginate(move |cursor| self.get_dynamic_fields(parent_object_id, cursor, page_size))
+    }
+
+    /// All the transactions of `addr` in the given `direction`, or in both directions if
+    /// `None`, fetched `page_size` transactions at a time.
+    pub fn get_transactions_for_address_stream(
+        &self,
+        addr: SuiAddress,
+        direction: Option<TransactionDirection>,
+        page_size: Option<usize>,
+    ) -> impl Stream<Item = anyhow::Result<AddressTransaction>> + '_ {
+        paginate(move |cursor| {
+            self.get_transactions_for_address(addr, cursor, page_size, direction)
+        })
+    }
+
     pub async fn subscribe_event(
         &self,
         filter: SuiEventFilter,
@@ -354,7 +636,48 @@ impl SuiClient {
             )),
         }
     }
+
+    /// Subscribe to the changes of the objects `object_ids`.
+    pub async fn subscribe_object_changes(
+        &self,
+        object_ids: Vec<ObjectID>,
+    ) -> anyhow::Result<impl Stream<Item = Result<SuiObjectChange, anyhow::Error>>> {
+        match &self.client {
+            Client::Ws(c) => {
+                let subscription: Subscription<SuiObjectChange> =
+                    c.subscribe_object_changes(object_ids).await?;
+                Ok(subscription.map(|item| Ok(item?)))
+            }
+            _ => Err(anyhow!(
+                "Subscription only supported with web socket client."
+            )),
+        }
+    }
+}
+/// Flatten the pages returned by `get_page` into a stream of items, following the cursors
+/// until the last page.
+fn paginate<T, C, F, Fut>(get_page: F) -> impl Stream<Item = anyhow::Result<T>>
+where
+    F: Fn(Option<C>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Page<T, C>>>,
+{
+    // The state is the cursor of the next page to fetch, `None` once the last page was fetched.
+    stream::try_unfold(Some(None), move |cursor| {
+        let page = cursor.map(&get_page);
+        async move {
+            Ok(match page {
+                Some(page) => {
+                    let page = page.await?;
+                    Some((page.data, page.next_cursor.map(Some)))
+                }
+                None => None,
+            })
+        }
+    })
+    .map_ok(|data| stream::iter(data.into_iter().map(Ok::<T, anyhow::Error>)))
+    .try_flatten()
 }
+
 #[allow(clippy::large_enum_variant)]
 enum Client {
     Http(HttpClient),
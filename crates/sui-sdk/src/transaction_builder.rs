@@ -0,0 +1,173 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{RPCTransactionRequestParams, SuiTypeTag, TransactionBytes};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::Base64;
+
+use crate::SuiClient;
+
+/// The default gas budget of the transactions built by a [`TransactionBuilder`].
+pub const DEFAULT_GAS_BUDGET: u64 = 1000;
+
+/// A transaction to be built by the RPC server.
+pub enum TransactionRequest {
+    TransferObject {
+        object_id: ObjectID,
+        recipient: SuiAddress,
+    },
+    /// Transfer `amount` SUI, or the whole coin if `amount` is `None`. The coin also pays for
+    /// the gas, so [`TransactionBuilder::gas`] is ignored.
+    TransferSui {
+        sui_object_id: ObjectID,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    },
+    MoveCall {
+        package_object_id: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<SuiTypeTag>,
+        arguments: Vec<SuiJsonValue>,
+    },
+    Publish {
+        compiled_modules: Vec<Vec<u8>>,
+    },
+    SplitCoin {
+        coin_object_id: ObjectID,
+        split_amounts: Vec<u64>,
+    },
+    MergeCoin {
+        primary_coin: ObjectID,
+        coin_to_merge: ObjectID,
+    },
+    /// Pay `amounts` to `recipients` out of `input_coins`, which must all have the same type.
+    Pay {
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+    },
+    Batch(Vec<RPCTransactionRequestParams>),
+}
+
+/// Builds transactions sent by `signer`, sharing the gas settings between them.
+///
+/// ```ignore
+/// let tx = client
+///     .transaction_builder(sender)
+///     .gas_budget(10000)
+///     .build(TransactionRequest::TransferObject { object_id, recipient })
+///     .await?;
+/// let response = client.sign_and_execute(tx, &keystore).await?;
+/// ```
+pub struct TransactionBuilder<'a> {
+    client: &'a SuiClient,
+    signer: SuiAddress,
+    gas: Option<ObjectID>,
+    gas_budget: u64,
+}
+
+impl<'a> TransactionBuilder<'a> {
+    pub(crate) fn new(client: &'a SuiClient, signer: SuiAddress) -> Self {
+        Self {
+            client,
+            signer,
+            gas: None,
+            gas_budget: DEFAULT_GAS_BUDGET,
+        }
+    }
+
+    /// Pay for the gas with `gas`, instead of letting the server pick a gas object.
+    pub fn gas(mut self, gas: ObjectID) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    pub fn gas_budget(mut self, gas_budget: u64) -> Self {
+        self.gas_budget = gas_budget;
+        self
+    }
+
+    pub async fn build(&self, request: TransactionRequest) -> anyhow::Result<TransactionBytes> {
+        let (signer, gas, gas_budget) = (self.signer, self.gas, self.gas_budget);
+        match request {
+            TransactionRequest::TransferObject {
+                object_id,
+                recipient,
+            } => {
+                self.client
+                    .transfer_object(signer, object_id, gas, gas_budget, recipient)
+                    .await
+            }
+            TransactionRequest::TransferSui {
+                sui_object_id,
+                recipient,
+                amount,
+            } => {
+                self.client
+                    .transfer_sui(signer, sui_object_id, gas_budget, recipient, amount)
+                    .await
+            }
+            TransactionRequest::MoveCall {
+                package_object_id,
+                module,
+                function,
+                type_arguments,
+                arguments,
+            } => {
+                self.client
+                    .move_call(
+                        signer,
+                        package_object_id,
+                        module,
+                        function,
+                        type_arguments,
+                        arguments,
+                        gas,
+                        gas_budget,
+                    )
+                    .await
+            }
+            TransactionRequest::Publish { compiled_modules } => {
+                let compiled_modules = compiled_modules
+                    .iter()
+                    .map(|bytes| Base64::from_bytes(bytes))
+                    .collect();
+                self.client
+                    .publish(signer, compiled_modules, gas, gas_budget)
+                    .await
+            }
+            TransactionRequest::SplitCoin {
+                coin_object_id,
+                split_amounts,
+            } => {
+                self.client
+                    .split_coin(signer, coin_object_id, split_amounts, gas, gas_budget)
+                    .await
+            }
+            TransactionRequest::MergeCoin {
+                primary_coin,
+                coin_to_merge,
+            } => {
+                self.client
+                    .merge_coin(signer, primary_coin, coin_to_merge, gas, gas_budget)
+                    .await
+            }
+            TransactionRequest::Pay {
+                input_coins,
+                recipients,
+                amounts,
+            } => {
+                self.client
+                    .pay(signer, input_coins, recipients, amounts, gas, gas_budget)
+                    .await
+            }
+            TransactionRequest::Batch(params) => {
+                self.client
+                    .batch_transaction(signer, params, gas, gas_budget)
+                    .await
+            }
+        }
+    }
+}
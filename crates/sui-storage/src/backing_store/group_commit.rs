@@ -0,0 +1,246 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Group commit of the writes to a store.
+//!
+//! The batches written concurrently are coalesced into groups, each written to the store as a
+//! single batch synced to disk once. The first writer of a group leads it: it waits for others to
+//! join the group, up to the delay of the config or until the group holds enough operations,
+//! then writes the group while the writers that arrive meanwhile gather into the next one. A
+//! write returns once the group holding it is synced, so that the writes of a writer still
+//! happen in the order it makes them, and it fails if its group fails.
+//!
+//! With no delay, groups only gather the writes made while the previous group is being synced:
+//! writes wait no longer than they would for their own sync, and the more concurrent writes
+//! there are the larger the groups. Delays make larger groups at the cost of the latency of
+//! every write. Writes block the thread making them until their group is synced.
+
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use typed_store::rocks::TypedStoreError;
+
+use super::{storage_error, BackingStore, RawIter, Seek, WriteOp};
+use crate::backup::BackupInfo;
+use crate::db_metrics::Database;
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct GroupCommitConfig {
+    /// How long the leader of a group waits for other writes to join it, in microseconds.
+    #[serde(default)]
+    pub max_delay_micros: u64,
+    /// The number of operations after which a group is written without waiting any longer.
+    #[serde(default = "default_max_group_ops")]
+    pub max_group_ops: usize,
+}
+
+fn default_max_group_ops() -> usize {
+    10_000
+}
+
+impl Default for GroupCommitConfig {
+    fn default() -> Self {
+        Self {
+            max_delay_micros: 0,
+            max_group_ops: default_max_group_ops(),
+        }
+    }
+}
+
+/// The outcome of writing a group, set once it is written.
+#[derive(Default)]
+struct Group {
+    result: Option<Result<(), String>>,
+}
+
+struct State {
+    /// The operations of the group being gathered.
+    ops: Vec<WriteOp>,
+    group: Arc<Mutex<Group>>,
+    /// Whether a group is being gathered or written by its leader.
+    leading: bool,
+}
+
+/// A store whose writes are committed in groups, see the [module documentation](self).
+pub struct GroupCommitStore {
+    store: Arc<dyn BackingStore>,
+    config: GroupCommitConfig,
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+impl GroupCommitStore {
+    pub fn new(store: Arc<dyn BackingStore>, config: GroupCommitConfig) -> Self {
+        Self {
+            store,
+            config,
+            state: Mutex::new(State {
+                ops: vec![],
+                group: Default::default(),
+                leading: false,
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn group_result(group: &Mutex<Group>) -> Option<Result<(), TypedStoreError>> {
+        group
+            .lock()
+            .unwrap()
+            .result
+            .clone()
+            .map(|result| result.map_err(storage_error))
+    }
+}
+
+impl BackingStore for GroupCommitStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.store.get(table, key)
+    }
+
+    fn multi_get(
+        &self,
+        table: &str,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError> {
+        self.store.multi_get(table, keys)
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut state = self.state.lock().unwrap();
+        state.ops.extend(batch);
+        let group = state.group.clone();
+        if state.ops.len() >= self.config.max_group_ops {
+            self.changed.notify_all();
+        }
+
+        // Wait for the group to be written, unless there is no leader to write it.
+        while state.leading {
+            if let Some(result) = Self::group_result(&group) {
+                return result;
+            }
+            state = self.changed.wait(state).unwrap();
+        }
+        if let Some(result) = Self::group_result(&group) {
+            return result;
+        }
+
+        // Lead the group, letting other writes join it until it is full or the delay is over.
+        state.leading = true;
+        let deadline = Instant::now() + Duration::from_micros(self.config.max_delay_micros);
+        loop {
+            let now = Instant::now();
+            if now >= deadline || state.ops.len() >= self.config.max_group_ops {
+                break;
+            }
+            state = self.changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+        let ops = std::mem::take(&mut state.ops);
+        state.group = Default::default();
+        drop(state);
+
+        let result = self.store.write_sync(ops);
+
+        let mut state = self.state.lock().unwrap();
+        group.lock().unwrap().result = Some(result.as_ref().map(|_| ()).map_err(|e| e.to_string()));
+        state.leading = false;
+        self.changed.notify_all();
+        result
+    }
+
+    fn write_sync(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        self.write(batch)
+    }
+
+    fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError> {
+        self.store.iter(table, seek)
+    }
+
+    fn clear(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.store.clear(table)
+    }
+
+    fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        self.store.try_catch_up_with_primary()
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.store.compact(table)
+    }
+
+    fn rocksdb(&self) -> Option<Database> {
+        self.store.rocksdb()
+    }
+
+    fn create_backup(&self, dir: &Path) -> Result<BackupInfo, TypedStoreError> {
+        self.store.create_backup(dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backing_store::{MemoryStore, StoreMap};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the batches written to a memory store.
+    #[derive(Default)]
+    struct CountingStore {
+        store: MemoryStore,
+        writes: AtomicUsize,
+    }
+
+    impl BackingStore for CountingStore {
+        fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+            self.store.get(table, key)
+        }
+
+        fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+            self.store.write(batch)
+        }
+
+        fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError> {
+            self.store.iter(table, seek)
+        }
+
+        fn clear(&self, table: &str) -> Result<(), TypedStoreError> {
+            self.store.clear(table)
+        }
+    }
+
+    #[test]
+    fn concurrent_writes_are_grouped() {
+        let counting = Arc::new(CountingStore::default());
+        let store: Arc<dyn BackingStore> = Arc::new(GroupCommitStore::new(
+            counting.clone(),
+            GroupCommitConfig {
+                max_delay_micros: 10_000,
+                max_group_ops: 1000,
+            },
+        ));
+        let numbers: Arc<StoreMap<u64, u64>> = Arc::new(StoreMap::new(store, "numbers"));
+
+        let writers: Vec<_> = (0..8u64)
+            .map(|writer| {
+                let numbers = numbers.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100u64 {
+                        let key = writer * 100 + i;
+                        numbers.insert(&key, &key).unwrap();
+                        // The writes of a writer are done once they return
+                        assert_eq!(numbers.get(&key).unwrap(), Some(key));
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(numbers.keys().count(), 800);
+        assert!(counting.writes.load(Ordering::SeqCst) < 800);
+    }
+}
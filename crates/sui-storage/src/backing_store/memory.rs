@@ -0,0 +1,96 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::sync::RwLock;
+
+use typed_store::rocks::TypedStoreError;
+
+use super::{BackingStore, RawIter, Seek, WriteOp};
+
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Tables kept in memory, created on their first write.
+#[derive(Default)]
+pub struct MemoryStore {
+    tables: RwLock<BTreeMap<String, Table>>,
+}
+
+impl BackingStore for MemoryStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let tables = self.tables.read().unwrap();
+        Ok(tables.get(table).and_then(|table| table.get(key)).cloned())
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut tables = self.tables.write().unwrap();
+        for op in batch {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    tables
+                        .entry(table.to_owned())
+                        .or_default()
+                        .insert(key, value);
+                }
+                WriteOp::Delete { table, key } => {
+                    if let Some(table) = tables.get_mut(table) {
+                        table.remove(&key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError> {
+        let start = {
+            let tables = self.tables.read().unwrap();
+            let entries = tables.get(table);
+            match seek {
+                Seek::First => Some(Bound::Unbounded),
+                Seek::From(key) => Some(Bound::Included(key.to_vec())),
+                Seek::Last => entries
+                    .and_then(|entries| entries.keys().next_back())
+                    .map(|key| Bound::Included(key.clone())),
+                Seek::PriorTo(key) => entries
+                    .and_then(|entries| entries.range(..=key.to_vec()).next_back())
+                    .map(|(key, _)| Bound::Included(key.clone())),
+            }
+        };
+        Ok(Box::new(MemoryIter {
+            store: self,
+            table: table.to_owned(),
+            next: start,
+        }))
+    }
+
+    fn clear(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.tables.write().unwrap().remove(table);
+        Ok(())
+    }
+}
+
+/// Looks the next entry up on every step rather than holding the lock of the tables, writes
+/// made while iterating are seen if they come after the current key.
+struct MemoryIter<'a> {
+    store: &'a MemoryStore,
+    table: String,
+    // `None` once past the last entry.
+    next: Option<Bound<Vec<u8>>>,
+}
+
+impl<'a> Iterator for MemoryIter<'a> {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.next.take()?;
+        let tables = self.store.tables.read().unwrap();
+        let (key, value) = tables
+            .get(&self.table)?
+            .range((start, Bound::Unbounded))
+            .next()?;
+        self.next = Some(Bound::Excluded(key.clone()));
+        Some((key.clone(), value.clone()))
+    }
+}
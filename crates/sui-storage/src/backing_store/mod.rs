@@ -0,0 +1,492 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The key-value stores the tables of the authority store are kept in.
+//!
+//! A [`BackingStore`] holds named tables of byte keys and values, sorted by key, and writes
+//! batches of changes to them atomically. A [`StoreMap`] is a typed table of a store, with the
+//! API of the `DBMap` of `typed_store`: keys are encoded the same way, in big-endian so that they
+//! sort like the values they encode, and so are values, so that the RocksDB store reads the
+//! databases written with `DBMap`s.
+//!
+//! Besides RocksDB, tables can be kept in memory, for tests and simulations, or in sled, which
+//! builds on every platform Rust does. Any store can have its writes committed in groups, see
+//! [`group_commit`].
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tracing::error;
+use typed_store::rocks::TypedStoreError;
+
+use crate::backup::BackupInfo;
+use crate::db_metrics::Database;
+
+pub mod group_commit;
+pub mod memory;
+pub mod rocks;
+pub mod sled_store;
+
+pub use group_commit::{GroupCommitConfig, GroupCommitStore};
+pub use memory::MemoryStore;
+pub use rocks::RocksStore;
+pub use sled_store::SledStore;
+
+/// The store of the tables of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageBackend {
+    RocksDb,
+    Sled,
+    /// Lost when the node stops.
+    Memory,
+}
+
+impl Default for StorageBackend {
+    fn default() -> Self {
+        StorageBackend::RocksDb
+    }
+}
+
+impl StorageBackend {
+    /// Open the store at `path` with the `tables`, which only RocksDB needs to know beforehand,
+    /// with their options.
+    pub fn open(
+        &self,
+        path: &Path,
+        db_options: Option<rocksdb::Options>,
+        tables: &[(&str, &rocksdb::Options)],
+    ) -> Result<Arc<dyn BackingStore>, TypedStoreError> {
+        Ok(match self {
+            StorageBackend::RocksDb => Arc::new(RocksStore::open(path, db_options, tables)?),
+            StorageBackend::Sled => Arc::new(SledStore::open(path)?),
+            StorageBackend::Memory => Arc::new(MemoryStore::default()),
+        })
+    }
+}
+
+/// Where iterators over a table start, they then go forward.
+#[derive(Clone, Copy, Debug)]
+pub enum Seek<'a> {
+    First,
+    Last,
+    /// The first key at or after this one.
+    From(&'a [u8]),
+    /// The last key at or before this one.
+    PriorTo(&'a [u8]),
+}
+
+#[derive(Clone, Debug)]
+pub enum WriteOp {
+    Put {
+        table: &'static str,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        table: &'static str,
+        key: Vec<u8>,
+    },
+}
+
+pub type RawIter<'a> = Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'a>;
+
+/// Tables of byte keys and values, sorted by key.
+pub trait BackingStore: Send + Sync {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError>;
+
+    fn multi_get(
+        &self,
+        table: &str,
+        keys: &[Vec<u8>],
+    ) -> Result<Vec<Option<Vec<u8>>>, TypedStoreError> {
+        keys.iter().map(|key| self.get(table, key)).collect()
+    }
+
+    /// Apply all the operations of `batch`, or none of them.
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError>;
+
+    /// Apply all the operations of `batch`, or none of them, and sync them to disk.
+    fn write_sync(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        self.write(batch)
+    }
+
+    /// The entries of `table` in the order of their keys, from `seek`.
+    fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError>;
+
+    fn clear(&self, table: &str) -> Result<(), TypedStoreError>;
+
+    /// Catch up with the writes of the primary, for the stores opened as the secondary of
+    /// another one.
+    fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+
+    /// The RocksDB database the tables are kept in, for the stores keeping them in RocksDB.
+    fn rocksdb(&self) -> Option<Database> {
+        None
+    }
+
+    /// Compact `table`, dropping the entries deleted from it, and the ones its compaction
+    /// filter drops for the stores that have them.
+    fn compact(&self, _table: &str) -> Result<(), TypedStoreError> {
+        Ok(())
+    }
+
+    /// Back the store up into `dir`, see [`crate::backup`].
+    fn create_backup(&self, _dir: &Path) -> Result<BackupInfo, TypedStoreError> {
+        Err(storage_error("This store does not support backups"))
+    }
+}
+
+pub(crate) fn storage_error<E: std::fmt::Display>(error: E) -> TypedStoreError {
+    TypedStoreError::RocksDBError(error.to_string())
+}
+
+fn serialization_error<E: std::fmt::Display>(error: E) -> TypedStoreError {
+    TypedStoreError::SerializationError(error.to_string())
+}
+
+fn key_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_big_endian()
+        .with_fixint_encoding()
+}
+
+fn encode_key<K: Serialize + ?Sized>(key: &K) -> Result<Vec<u8>, TypedStoreError> {
+    key_options().serialize(key).map_err(serialization_error)
+}
+
+fn decode_key<K: DeserializeOwned>(bytes: &[u8]) -> Result<K, TypedStoreError> {
+    key_options()
+        .deserialize(bytes)
+        .map_err(serialization_error)
+}
+
+fn encode_value<V: Serialize + ?Sized>(value: &V) -> Result<Vec<u8>, TypedStoreError> {
+    bincode::serialize(value).map_err(serialization_error)
+}
+
+fn decode_value<V: DeserializeOwned>(bytes: &[u8]) -> Result<V, TypedStoreError> {
+    bincode::deserialize(bytes).map_err(serialization_error)
+}
+
+/// A table of a store, mapping keys of type `K` to values of type `V`.
+pub struct StoreMap<K, V> {
+    store: Arc<dyn BackingStore>,
+    table: &'static str,
+    _phantom: PhantomData<fn(K) -> V>,
+}
+
+impl<K, V> Clone for StoreMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            table: self.table,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> StoreMap<K, V> {
+    pub fn new(store: Arc<dyn BackingStore>, table: &'static str) -> Self {
+        Self {
+            store,
+            table,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> Result<bool, TypedStoreError> {
+        Ok(self.store.get(self.table, &encode_key(key)?)?.is_some())
+    }
+
+    pub fn get(&self, key: &K) -> Result<Option<V>, TypedStoreError> {
+        self.store
+            .get(self.table, &encode_key(key)?)?
+            .map(|value| decode_value(&value))
+            .transpose()
+    }
+
+    pub fn multi_get<J: Borrow<K>>(
+        &self,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<V>>, TypedStoreError> {
+        let keys = keys
+            .into_iter()
+            .map(|key| encode_key(key.borrow()))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.store
+            .multi_get(self.table, &keys)?
+            .into_iter()
+            .map(|value| value.map(|value| decode_value(&value)).transpose())
+            .collect()
+    }
+
+    pub fn insert(&self, key: &K, value: &V) -> Result<(), TypedStoreError> {
+        self.batch()
+            .insert_batch(self, std::iter::once((key, value)))?
+            .write()
+    }
+
+    pub fn remove(&self, key: &K) -> Result<(), TypedStoreError> {
+        self.batch()
+            .delete_batch(self, std::iter::once(key))?
+            .write()
+    }
+
+    pub fn clear(&self) -> Result<(), TypedStoreError> {
+        self.store.clear(self.table)
+    }
+
+    /// Compact the table, see [`BackingStore::compact`].
+    pub fn compact(&self) -> Result<(), TypedStoreError> {
+        self.store.compact(self.table)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// The entries of the table in the order of their keys, from the first one unless told
+    /// otherwise with the `skip` methods of the iterator.
+    pub fn iter(&self) -> StoreIter<'_, K, V> {
+        StoreIter {
+            map: self,
+            inner: None,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = V> + '_ {
+        self.iter().map(|(_, value)| value)
+    }
+
+    /// The entries of the table in the order of their keys, or the error decoding them. Unlike
+    /// [`Self::iter`], which stops at the first entry it cannot decode, goes on to the next ones.
+    pub fn checked_iter(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<(K, V), TypedStoreError>> + '_, TypedStoreError> {
+        Ok(self
+            .store
+            .iter(self.table, Seek::First)?
+            .map(|(key, value)| Ok((decode_key(&key)?, decode_value(&value)?))))
+    }
+
+    /// A batch of writes to the tables of the store of this table.
+    pub fn batch(&self) -> StoreBatch {
+        StoreBatch {
+            store: self.store.clone(),
+            ops: vec![],
+        }
+    }
+
+    pub fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        self.store.try_catch_up_with_primary()
+    }
+
+    /// The store the table is kept in.
+    pub fn store(&self) -> &Arc<dyn BackingStore> {
+        &self.store
+    }
+}
+
+pub struct StoreIter<'a, K, V> {
+    map: &'a StoreMap<K, V>,
+    inner: Option<RawIter<'a>>,
+}
+
+impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> StoreIter<'a, K, V> {
+    fn seek(mut self, seek: Seek<'_>) -> Result<Self, TypedStoreError> {
+        self.inner = Some(self.map.store.iter(self.map.table, seek)?);
+        Ok(self)
+    }
+
+    /// Start from `key`, or the first key after it.
+    pub fn skip_to(self, key: &K) -> Result<Self, TypedStoreError> {
+        self.seek(Seek::From(&encode_key(key)?))
+    }
+
+    /// Start from `key`, or the last key before it.
+    pub fn skip_prior_to(self, key: &K) -> Result<Self, TypedStoreError> {
+        self.seek(Seek::PriorTo(&encode_key(key)?))
+    }
+
+    /// Start from the last key.
+    pub fn skip_to_last(self) -> Self {
+        let map = self.map;
+        self.seek(Seek::Last).unwrap_or_else(|e| {
+            error!("Cannot seek to the last key of {}: {e}", map.table);
+            StoreIter {
+                map,
+                inner: Some(Box::new(std::iter::empty())),
+            }
+        })
+    }
+}
+
+impl<'a, K: Serialize + DeserializeOwned, V: Serialize + DeserializeOwned> Iterator
+    for StoreIter<'a, K, V>
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.inner.is_none() {
+            match self.map.store.iter(self.map.table, Seek::First) {
+                Ok(inner) => self.inner = Some(inner),
+                Err(e) => {
+                    error!("Cannot iterate over {}: {e}", self.map.table);
+                    self.inner = Some(Box::new(std::iter::empty()));
+                }
+            }
+        }
+        let (key, value) = self.inner.as_mut()?.next()?;
+        match (decode_key(&key), decode_value(&value)) {
+            (Ok(key), Ok(value)) => Some((key, value)),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("Cannot decode an entry of {}: {e}", self.map.table);
+                self.inner = Some(Box::new(std::iter::empty()));
+                None
+            }
+        }
+    }
+}
+
+/// Writes to the tables of a store, applied atomically.
+pub struct StoreBatch {
+    store: Arc<dyn BackingStore>,
+    ops: Vec<WriteOp>,
+}
+
+impl StoreBatch {
+    fn check_store<K, V>(&self, map: &StoreMap<K, V>) -> Result<(), TypedStoreError> {
+        // Compare the data pointers only, the vtables of a type may be duplicated.
+        if Arc::as_ptr(&self.store) as *const u8 != Arc::as_ptr(&map.store) as *const u8 {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        Ok(())
+    }
+
+    pub fn insert_batch<J: Borrow<K>, U: Borrow<V>, K: Serialize, V: Serialize>(
+        mut self,
+        map: &StoreMap<K, V>,
+        new_vals: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<Self, TypedStoreError> {
+        self.check_store(map)?;
+        for (key, value) in new_vals {
+            self.ops.push(WriteOp::Put {
+                table: map.table,
+                key: encode_key(key.borrow())?,
+                value: encode_value(value.borrow())?,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn delete_batch<J: Borrow<K>, K: Serialize, V>(
+        mut self,
+        map: &StoreMap<K, V>,
+        purged_vals: impl IntoIterator<Item = J>,
+    ) -> Result<Self, TypedStoreError> {
+        self.check_store(map)?;
+        for key in purged_vals {
+            self.ops.push(WriteOp::Delete {
+                table: map.table,
+                key: encode_key(key.borrow())?,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn write(self) -> Result<(), TypedStoreError> {
+        if self.ops.is_empty() {
+            return Ok(());
+        }
+        self.store.write(self.ops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMBERS: &str = "numbers";
+    const NAMES: &str = "names";
+
+    fn stores(path: &Path) -> Vec<Arc<dyn BackingStore>> {
+        let options = rocksdb::Options::default();
+        let tables = [(NUMBERS, &options), (NAMES, &options)];
+        [
+            StorageBackend::RocksDb,
+            StorageBackend::Sled,
+            StorageBackend::Memory,
+        ]
+        .iter()
+        .map(|backend| {
+            let path = path.join(format!("{backend:?}"));
+            backend.open(&path, None, &tables).unwrap()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn store_maps() {
+        let dir = tempfile::tempdir().unwrap();
+        for store in stores(dir.path()) {
+            let numbers: StoreMap<u64, String> = StoreMap::new(store.clone(), NUMBERS);
+            let names: StoreMap<String, u64> = StoreMap::new(store.clone(), NAMES);
+            assert!(numbers.is_empty());
+
+            // Keys sort like the numbers they encode
+            let batch = numbers
+                .batch()
+                .insert_batch(
+                    &numbers,
+                    [(1, "one"), (256, "two hundred fifty six"), (3, "three")]
+                        .map(|(k, v)| (k, v.to_owned())),
+                )
+                .unwrap()
+                .insert_batch(&names, [("one".to_owned(), 1)])
+                .unwrap();
+            batch.write().unwrap();
+            assert_eq!(numbers.keys().collect::<Vec<_>>(), vec![1, 3, 256]);
+            assert_eq!(names.get(&"one".to_owned()).unwrap(), Some(1));
+            assert_eq!(
+                numbers.multi_get([1, 2]).unwrap(),
+                vec![Some("one".to_owned()), None]
+            );
+
+            let first = |iter: StoreIter<u64, String>| iter.map(|(key, _)| key).next();
+            assert_eq!(first(numbers.iter().skip_to(&2).unwrap()), Some(3));
+            assert_eq!(first(numbers.iter().skip_to(&257).unwrap()), None);
+            assert_eq!(first(numbers.iter().skip_prior_to(&255).unwrap()), Some(3));
+            assert_eq!(first(numbers.iter().skip_prior_to(&0).unwrap()), None);
+            assert_eq!(first(numbers.iter().skip_to_last()), Some(256));
+
+            numbers.remove(&3).unwrap();
+            assert!(!numbers.contains_key(&3).unwrap());
+            numbers.clear().unwrap();
+            assert!(numbers.is_empty());
+            assert!(!names.is_empty());
+        }
+    }
+
+    #[test]
+    fn batches_stay_in_their_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let stores = stores(dir.path());
+        let first: StoreMap<u64, u64> = StoreMap::new(stores[0].clone(), NUMBERS);
+        let second: StoreMap<u64, u64> = StoreMap::new(stores[1].clone(), NUMBERS);
+        assert!(matches!(
+            first.batch().insert_batch(&second, [(1, 1)]),
+            Err(TypedStoreError::CrossDBBatch)
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::Path;
+use std::sync::Arc;
+
+use rocksdb::{BoundColumnFamily, DBWithThreadMode, Direction, IteratorMode, MultiThreaded};
+use typed_store::rocks::TypedStoreError;
+
+use super::{storage_error, BackingStore, RawIter, Seek, WriteOp};
+use crate::backup::{self, BackupInfo};
+use crate::db_metrics::Database;
+
+/// Tables kept in the column families of a RocksDB database.
+pub struct RocksStore {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl RocksStore {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    /// Open the database at `path` with the column families of `tables`.
+    pub fn open(
+        path: &Path,
+        db_options: Option<rocksdb::Options>,
+        tables: &[(&str, &rocksdb::Options)],
+    ) -> Result<Self, TypedStoreError> {
+        Ok(Self::new(typed_store::rocks::open_cf_opts(
+            path, db_options, tables,
+        )?))
+    }
+
+    /// Open the database at `path` as a secondary of its primary, keeping its own files at
+    /// `secondary_path`.
+    pub fn open_secondary(
+        path: &Path,
+        secondary_path: &Path,
+        db_options: Option<rocksdb::Options>,
+        tables: &[(&str, &rocksdb::Options)],
+    ) -> Result<Self, TypedStoreError> {
+        Ok(Self::new(typed_store::rocks::open_cf_opts_secondary(
+            path,
+            Some(secondary_path),
+            db_options,
+            tables,
+        )?))
+    }
+
+    fn cf(&self, table: &str) -> Result<Arc<BoundColumnFamily<'_>>, TypedStoreError> {
+        self.db
+            .cf_handle(table)
+            .ok_or_else(|| TypedStoreError::UnregisteredColumn(table.to_owned()))
+    }
+}
+
+impl BackingStore for RocksStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.db.get_cf(&self.cf(table)?, key).map_err(storage_error)
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut write_batch = rocksdb::WriteBatch::default();
+        for op in batch {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    write_batch.put_cf(&self.cf(table)?, key, value)
+                }
+                WriteOp::Delete { table, key } => write_batch.delete_cf(&self.cf(table)?, key),
+            }
+        }
+        self.db.write(write_batch).map_err(storage_error)
+    }
+
+    fn write_sync(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        let mut write_batch = rocksdb::WriteBatch::default();
+        for op in batch {
+            match op {
+                WriteOp::Put { table, key, value } => {
+                    write_batch.put_cf(&self.cf(table)?, key, value)
+                }
+                WriteOp::Delete { table, key } => write_batch.delete_cf(&self.cf(table)?, key),
+            }
+        }
+        let mut options = rocksdb::WriteOptions::default();
+        options.set_sync(true);
+        self.db
+            .write_opt(write_batch, &options)
+            .map_err(storage_error)
+    }
+
+    fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError> {
+        let cf = self.cf(table)?;
+        let start = match seek {
+            Seek::First => None,
+            Seek::From(key) => Some(key.to_vec()),
+            Seek::Last => match self.db.iterator_cf(&cf, IteratorMode::End).next() {
+                Some((key, _)) => Some(key.to_vec()),
+                None => return Ok(Box::new(std::iter::empty())),
+            },
+            // Seeks the last key at or before the given one.
+            Seek::PriorTo(key) => match self
+                .db
+                .iterator_cf(&cf, IteratorMode::From(key, Direction::Reverse))
+                .next()
+            {
+                Some((key, _)) => Some(key.to_vec()),
+                None => return Ok(Box::new(std::iter::empty())),
+            },
+        };
+        let mode = match &start {
+            Some(start) => IteratorMode::From(start, Direction::Forward),
+            None => IteratorMode::Start,
+        };
+        Ok(Box::new(
+            self.db
+                .iterator_cf(&cf, mode)
+                .map(|(key, value)| (key.to_vec(), value.to_vec())),
+        ))
+    }
+
+    fn clear(&self, table: &str) -> Result<(), TypedStoreError> {
+        let cf = self.cf(table)?;
+        let mut write_batch = rocksdb::WriteBatch::default();
+        for (key, _) in self.db.iterator_cf(&cf, IteratorMode::Start) {
+            write_batch.delete_cf(&cf, key);
+        }
+        self.db.write(write_batch).map_err(storage_error)
+    }
+
+    fn try_catch_up_with_primary(&self) -> Result<(), TypedStoreError> {
+        self.db.try_catch_up_with_primary().map_err(storage_error)
+    }
+
+    fn compact(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.db
+            .compact_range_cf(&self.cf(table)?, None::<&[u8]>, None::<&[u8]>);
+        Ok(())
+    }
+
+    fn rocksdb(&self) -> Option<Database> {
+        Some(self.db.clone())
+    }
+
+    fn create_backup(&self, dir: &Path) -> Result<BackupInfo, TypedStoreError> {
+        backup::create_backup(&self.db, dir)
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
+use typed_store::rocks::TypedStoreError;
+
+use super::{storage_error, BackingStore, RawIter, Seek, WriteOp};
+
+/// Tables kept in the trees of a sled database.
+pub struct SledStore {
+    db: sled::Db,
+}
+
+impl SledStore {
+    pub fn open(path: &Path) -> Result<Self, TypedStoreError> {
+        Ok(Self {
+            db: sled::open(path).map_err(storage_error)?,
+        })
+    }
+
+    fn tree(&self, table: &str) -> Result<sled::Tree, TypedStoreError> {
+        self.db.open_tree(table).map_err(storage_error)
+    }
+}
+
+impl BackingStore for SledStore {
+    fn get(&self, table: &str, key: &[u8]) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        Ok(self
+            .tree(table)?
+            .get(key)
+            .map_err(storage_error)?
+            .map(|value| value.to_vec()))
+    }
+
+    fn write(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        // A transaction over the trees of the batch, the position of their tree along the ops.
+        let mut positions = BTreeMap::new();
+        let mut trees = vec![];
+        let mut ops = vec![];
+        for op in &batch {
+            let table = match op {
+                WriteOp::Put { table, .. } | WriteOp::Delete { table, .. } => *table,
+            };
+            let position = match positions.get(table) {
+                Some(position) => *position,
+                None => {
+                    trees.push(self.tree(table)?);
+                    positions.insert(table, trees.len() - 1);
+                    trees.len() - 1
+                }
+            };
+            ops.push((position, op));
+        }
+
+        trees
+            .as_slice()
+            .transaction(|views| {
+                for (position, op) in &ops {
+                    match op {
+                        WriteOp::Put { key, value, .. } => {
+                            views[*position].insert(key.as_slice(), value.as_slice())?;
+                        }
+                        WriteOp::Delete { key, .. } => {
+                            views[*position].remove(key.as_slice())?;
+                        }
+                    }
+                }
+                Ok::<_, ConflictableTransactionError<()>>(())
+            })
+            .map_err(|e| match e {
+                TransactionError::Storage(e) => storage_error(e),
+                TransactionError::Abort(()) => storage_error("Transaction aborted"),
+            })
+    }
+
+    fn write_sync(&self, batch: Vec<WriteOp>) -> Result<(), TypedStoreError> {
+        self.write(batch)?;
+        self.db.flush().map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn iter(&self, table: &str, seek: Seek<'_>) -> Result<RawIter<'_>, TypedStoreError> {
+        let tree = self.tree(table)?;
+        let start = match seek {
+            Seek::First => return Ok(raw_iter(tree.iter())),
+            Seek::From(key) => Some(key.to_vec()),
+            Seek::Last => tree
+                .last()
+                .map_err(storage_error)?
+                .map(|(key, _)| key.to_vec()),
+            Seek::PriorTo(key) => tree
+                .range(..=key)
+                .next_back()
+                .transpose()
+                .map_err(storage_error)?
+                .map(|(key, _)| key.to_vec()),
+        };
+        match start {
+            Some(start) => Ok(raw_iter(tree.range(start..))),
+            None => Ok(Box::new(std::iter::empty())),
+        }
+    }
+
+    fn clear(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.tree(table)?.clear().map_err(storage_error)
+    }
+}
+
+/// The entries of `iter`, until the first error reading them.
+fn raw_iter(iter: sled::Iter) -> RawIter<'static> {
+    Box::new(iter.map_while(|entry| match entry {
+        Ok((key, value)) => Some((key.to_vec(), value.to_vec())),
+        Err(e) => {
+            tracing::error!("Cannot read from sled: {e}");
+            None
+        }
+    }))
+}
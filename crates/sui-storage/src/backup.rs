@@ -0,0 +1,139 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Online backups of RocksDB databases, taken with the RocksDB backup engine.
+//!
+//! The backups of a database are kept together in a directory. Each backup only copies the
+//! files that the previous backups kept in the same directory do not already share, so that
+//! backing a database up again is incremental. The engine keeps the checksum of every file it
+//! copies, and checks it back while restoring.
+
+use std::path::Path;
+
+use rocksdb::backup::{BackupEngine, BackupEngineOptions, RestoreOptions};
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use serde::{Deserialize, Serialize};
+use typed_store::rocks::TypedStoreError;
+
+use crate::backing_store::storage_error;
+
+/// A backup kept in a backup directory.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub backup_id: u32,
+    /// Seconds since the Unix epoch when the backup was taken.
+    pub timestamp: i64,
+    /// The size of the files of the backup, including the ones shared with other backups.
+    pub size: u64,
+    pub num_files: u32,
+}
+
+fn open_engine(dir: &Path) -> Result<BackupEngine, TypedStoreError> {
+    std::fs::create_dir_all(dir).map_err(storage_error)?;
+    BackupEngine::open(&BackupEngineOptions::default(), dir).map_err(storage_error)
+}
+
+fn backups(engine: &BackupEngine) -> Vec<BackupInfo> {
+    engine
+        .get_backup_info()
+        .into_iter()
+        .map(|info| BackupInfo {
+            backup_id: info.backup_id,
+            timestamp: info.timestamp,
+            size: info.size,
+            num_files: info.num_files,
+        })
+        .collect()
+}
+
+/// Back `db` up into `dir` while it is in use, flushing its memtables first. The new backup is
+/// verified before being returned.
+pub fn create_backup(
+    db: &DBWithThreadMode<MultiThreaded>,
+    dir: &Path,
+) -> Result<BackupInfo, TypedStoreError> {
+    let mut engine = open_engine(dir)?;
+    engine
+        .create_new_backup_flush(db, true)
+        .map_err(storage_error)?;
+    let backup = backups(&engine)
+        .into_iter()
+        .max_by_key(|backup| backup.backup_id)
+        .ok_or_else(|| storage_error(format!("No backup in {}", dir.display())))?;
+    engine
+        .verify_backup(backup.backup_id)
+        .map_err(storage_error)?;
+    Ok(backup)
+}
+
+/// Check that the files of every backup kept in `dir` exist and have the expected size.
+pub fn verify_backups(dir: &Path) -> Result<Vec<BackupInfo>, TypedStoreError> {
+    let engine = open_engine(dir)?;
+    let backups = backups(&engine);
+    for backup in &backups {
+        engine
+            .verify_backup(backup.backup_id)
+            .map_err(storage_error)?;
+    }
+    Ok(backups)
+}
+
+/// Delete all but the `keep` latest backups kept in `dir`, along with the files only they share.
+pub fn purge_old_backups(dir: &Path, keep: usize) -> Result<(), TypedStoreError> {
+    open_engine(dir)?
+        .purge_old_backups(keep)
+        .map_err(storage_error)
+}
+
+/// Restore the latest backup kept in `dir` into `db_dir`, replacing the database there. The
+/// database must not be open while it is being restored.
+pub fn restore_backup(dir: &Path, db_dir: &Path) -> Result<BackupInfo, TypedStoreError> {
+    let mut engine = open_engine(dir)?;
+    let backup = backups(&engine)
+        .into_iter()
+        .max_by_key(|backup| backup.backup_id)
+        .ok_or_else(|| storage_error(format!("No backup in {}", dir.display())))?;
+    engine
+        .verify_backup(backup.backup_id)
+        .map_err(storage_error)?;
+    std::fs::create_dir_all(db_dir).map_err(storage_error)?;
+    engine
+        .restore_from_backup(db_dir, db_dir, &RestoreOptions::default(), backup.backup_id)
+        .map_err(storage_error)?;
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backup_and_restore() {
+        let path = tempfile::tempdir().unwrap();
+        let backup_dir = path.path().join("backups");
+        let options = rocksdb::Options::default();
+        let db =
+            typed_store::rocks::open_cf_opts(&path.path().join("db"), None, &[("table", &options)])
+                .unwrap();
+
+        db.put_cf(&db.cf_handle("table").unwrap(), b"a", b"1")
+            .unwrap();
+        let first = create_backup(&db, &backup_dir).unwrap();
+        db.put_cf(&db.cf_handle("table").unwrap(), b"b", b"2")
+            .unwrap();
+        let second = create_backup(&db, &backup_dir).unwrap();
+        assert!(second.backup_id > first.backup_id);
+        assert_eq!(verify_backups(&backup_dir).unwrap().len(), 2);
+
+        purge_old_backups(&backup_dir, 1).unwrap();
+        assert_eq!(verify_backups(&backup_dir).unwrap(), vec![second]);
+
+        let restored_dir = path.path().join("restored");
+        restore_backup(&backup_dir, &restored_dir).unwrap();
+        let restored =
+            typed_store::rocks::open_cf_opts(&restored_dir, None, &[("table", &options)]).unwrap();
+        let table = restored.cf_handle("table").unwrap();
+        assert_eq!(restored.get_cf(&table, b"a").unwrap(), Some(b"1".to_vec()));
+        assert_eq!(restored.get_cf(&table, b"b").unwrap(), Some(b"2".to_vec()));
+    }
+}
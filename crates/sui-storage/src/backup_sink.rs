@@ -0,0 +1,366 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sinks the backups of a node are mirrored to, such as an object storage bucket.
+//!
+//! A backup directory is mirrored file by file, each file being put in the sink under its path
+//! relative to the directory. The files the backup engine shares between backups and the
+//! private files of each backup never change once written, so that they are only uploaded once:
+//! mirroring a directory again after a new backup only uploads the files of that backup. The
+//! sink keeps a manifest of the files it holds, which is how the files no longer kept locally,
+//! once old backups were purged, are found and deleted from the sink as well.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use typed_store::rocks::TypedStoreError;
+
+use crate::backing_store::storage_error;
+
+/// The key of the manifest of the files held by a sink, mapping their keys to their sizes.
+pub const MANIFEST_KEY: &str = "MANIFEST.json";
+
+/// The subdirectories of a backup directory whose files never change once written.
+const IMMUTABLE_DIRS: &[&str] = &["private", "shared", "shared_checksum"];
+
+/// Periodic backups of a node.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct BackupConfig {
+    /// The local directory the backups are taken into.
+    pub backup_dir: PathBuf,
+    /// How often the node is backed up.
+    #[serde(default = "default_backup_interval_secs")]
+    pub interval_secs: u64,
+    /// The number of latest backups kept, both locally and in the sink.
+    #[serde(default = "default_keep_backups")]
+    pub keep_backups: usize,
+    /// Where the backups are mirrored to, only kept locally if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sink: Option<BackupSinkConfig>,
+}
+
+fn default_backup_interval_secs() -> u64 {
+    60 * 60
+}
+
+fn default_keep_backups() -> usize {
+    24
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum BackupSinkConfig {
+    /// A directory, e.g. a bucket mounted with s3fs or gcsfuse.
+    Directory { path: PathBuf },
+    /// An S3 or GCS compatible object storage, the files being put at `url/key`, e.g. with
+    /// `https://storage.googleapis.com/<bucket>/<prefix>` as url.
+    ObjectStore {
+        url: String,
+        /// Sent as the bearer token of every request if set.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bearer_token: Option<String>,
+    },
+}
+
+impl BackupSinkConfig {
+    pub fn build(&self) -> Arc<dyn BackupSink> {
+        match self {
+            Self::Directory { path } => Arc::new(DirectorySink::new(path.clone())),
+            Self::ObjectStore { url, bearer_token } => {
+                Arc::new(ObjectStoreSink::new(url.clone(), bearer_token.clone()))
+            }
+        }
+    }
+}
+
+/// A store of files, keyed by their paths relative to the directory they are mirrored from.
+#[async_trait]
+pub trait BackupSink: Send + Sync {
+    /// Put the file at `path` under `key`, replacing any file already there.
+    async fn put_file(&self, key: &str, path: &Path) -> Result<(), TypedStoreError>;
+
+    /// Put `bytes` under `key`, replacing any file already there.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), TypedStoreError>;
+
+    /// The file under `key`, if any.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TypedStoreError>;
+
+    /// Delete the file under `key`, if any.
+    async fn delete(&self, key: &str) -> Result<(), TypedStoreError>;
+}
+
+/// What [`mirror_dir`] did.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MirrorSummary {
+    pub uploaded_files: usize,
+    pub uploaded_bytes: u64,
+    pub deleted_files: usize,
+}
+
+/// Mirror the files of `dir` into `sink`, uploading the ones the sink does not hold yet and
+/// deleting the ones it holds that are no longer in `dir`.
+pub async fn mirror_dir(
+    dir: &Path,
+    sink: &dyn BackupSink,
+) -> Result<MirrorSummary, TypedStoreError> {
+    let mut manifest: BTreeMap<String, u64> = match sink.get(MANIFEST_KEY).await? {
+        Some(bytes) => serde_json::from_slice(&bytes).map_err(storage_error)?,
+        None => BTreeMap::new(),
+    };
+    let files = list_files(dir)?;
+
+    let mut summary = MirrorSummary::default();
+    for (key, size) in &files {
+        let immutable = key
+            .split('/')
+            .any(|component| IMMUTABLE_DIRS.contains(&component));
+        if immutable && manifest.get(key) == Some(size) {
+            continue;
+        }
+        debug!(key, size, "Uploading a backup file");
+        sink.put_file(key, &dir.join(key)).await?;
+        manifest.insert(key.clone(), *size);
+        summary.uploaded_files += 1;
+        summary.uploaded_bytes += size;
+    }
+    // The manifest is written before the files are deleted, so that it never misses a file
+    // the sink holds.
+    sink.put(
+        MANIFEST_KEY,
+        serde_json::to_vec(&manifest).map_err(storage_error)?,
+    )
+    .await?;
+
+    let deleted: Vec<_> = manifest
+        .keys()
+        .filter(|key| !files.contains_key(*key))
+        .cloned()
+        .collect();
+    if !deleted.is_empty() {
+        for key in &deleted {
+            sink.delete(key).await?;
+            manifest.remove(key);
+        }
+        summary.deleted_files = deleted.len();
+        sink.put(
+            MANIFEST_KEY,
+            serde_json::to_vec(&manifest).map_err(storage_error)?,
+        )
+        .await?;
+    }
+    Ok(summary)
+}
+
+/// The files under `dir` with their sizes, keyed by their paths relative to it.
+fn list_files(dir: &Path) -> Result<BTreeMap<String, u64>, TypedStoreError> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(current) = dirs.pop() {
+        for entry in std::fs::read_dir(&current).map_err(storage_error)? {
+            let entry = entry.map_err(storage_error)?;
+            let metadata = entry.metadata().map_err(storage_error)?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                let path = entry.path();
+                let key = path
+                    .strip_prefix(dir)
+                    .map_err(storage_error)?
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.insert(key, metadata.len());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Files kept in a directory.
+pub struct DirectorySink {
+    path: PathBuf,
+}
+
+impl DirectorySink {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    async fn create_parent(&self, key: &str) -> Result<PathBuf, TypedStoreError> {
+        let path = self.path.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(storage_error)?;
+        }
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl BackupSink for DirectorySink {
+    async fn put_file(&self, key: &str, path: &Path) -> Result<(), TypedStoreError> {
+        let target = self.create_parent(key).await?;
+        tokio::fs::copy(path, target).await.map_err(storage_error)?;
+        Ok(())
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), TypedStoreError> {
+        let target = self.create_parent(key).await?;
+        tokio::fs::write(target, bytes).await.map_err(storage_error)
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        match tokio::fs::read(self.path.join(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), TypedStoreError> {
+        match tokio::fs::remove_file(self.path.join(key)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(storage_error(e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Files kept in an object storage bucket, put, read and deleted over HTTP. The files are
+/// streamed from disk while they are uploaded.
+pub struct ObjectStoreSink {
+    client: reqwest::Client,
+    url: String,
+    bearer_token: Option<String>,
+}
+
+impl ObjectStoreSink {
+    pub fn new(url: String, bearer_token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+            bearer_token,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, key: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}/{}", self.url, key));
+        match &self.bearer_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, TypedStoreError> {
+        request
+            .send()
+            .await
+            .map_err(storage_error)?
+            .error_for_status()
+            .map_err(storage_error)
+    }
+}
+
+#[async_trait]
+impl BackupSink for ObjectStoreSink {
+    async fn put_file(&self, key: &str, path: &Path) -> Result<(), TypedStoreError> {
+        let file = tokio::fs::File::open(path).await.map_err(storage_error)?;
+        let length = file.metadata().await.map_err(storage_error)?.len();
+        let body = reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(file));
+        Self::send(
+            self.request(reqwest::Method::PUT, key)
+                .header(reqwest::header::CONTENT_LENGTH, length)
+                .body(body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<(), TypedStoreError> {
+        Self::send(self.request(reqwest::Method::PUT, key).body(bytes)).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        let response = self
+            .request(reqwest::Method::GET, key)
+            .send()
+            .await
+            .map_err(storage_error)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let bytes = response
+            .error_for_status()
+            .map_err(storage_error)?
+            .bytes()
+            .await
+            .map_err(storage_error)?;
+        Ok(Some(bytes.to_vec()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), TypedStoreError> {
+        let response = self
+            .request(reqwest::Method::DELETE, key)
+            .send()
+            .await
+            .map_err(storage_error)?;
+        if response.status() != reqwest::StatusCode::NOT_FOUND {
+            response.error_for_status().map_err(storage_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mirror_backups_incrementally() {
+        let path = tempfile::tempdir().unwrap();
+        let backup_dir = path.path().join("backups");
+        let sink_dir = path.path().join("sink");
+        let sink = DirectorySink::new(sink_dir.clone());
+        let options = rocksdb::Options::default();
+        let db =
+            typed_store::rocks::open_cf_opts(&path.path().join("db"), None, &[("table", &options)])
+                .unwrap();
+        let table = db.cf_handle("table").unwrap();
+
+        db.put_cf(&table, b"a", b"1").unwrap();
+        crate::backup::create_backup(&db, &backup_dir).unwrap();
+        let first = mirror_dir(&backup_dir, &sink).await.unwrap();
+        assert!(first.uploaded_files > 0);
+        assert_eq!(first.deleted_files, 0);
+
+        // Only the files of the new backup, and the ones that change, are uploaded again
+        db.put_cf(&table, b"b", b"2").unwrap();
+        crate::backup::create_backup(&db, &backup_dir).unwrap();
+        let second = mirror_dir(&backup_dir, &sink).await.unwrap();
+        assert!(second.uploaded_files < list_files(&backup_dir).unwrap().len());
+        assert_eq!(second.deleted_files, 0);
+
+        // Purged backups are deleted from the sink as well
+        crate::backup::purge_old_backups(&backup_dir, 1).unwrap();
+        let third = mirror_dir(&backup_dir, &sink).await.unwrap();
+        assert!(third.deleted_files > 0);
+
+        // The sink holds a backup that can be restored
+        let mut sink_files = list_files(&sink_dir).unwrap();
+        sink_files.remove(MANIFEST_KEY);
+        assert_eq!(sink_files, list_files(&backup_dir).unwrap());
+        let restored_dir = path.path().join("restored");
+        crate::backup::restore_backup(&sink_dir, &restored_dir).unwrap();
+        let restored =
+            typed_store::rocks::open_cf_opts(&restored_dir, None, &[("table", &options)]).unwrap();
+        let table = restored.cf_handle("table").unwrap();
+        assert_eq!(restored.get_cf(&table, b"b").unwrap(), Some(b"2".to_vec()));
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compaction filters dropping the pruned entries of the tables keyed by transaction sequence
+//! numbers.
+//!
+//! Pruning the transactions executed before a sequence number raises the watermark of these
+//! tables rather than deleting their entries one by one: RocksDB drops the entries below the
+//! watermark as it compacts them, with no deletes written and no tombstones left behind. The
+//! watermark is only kept in memory, it has to be raised again when the database is reopened,
+//! the filters keeping every entry until then. Other stores have no compaction filters, their
+//! pruned entries have to be deleted.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use rocksdb::{CompactionDecision, Options};
+
+/// The sequence number below which the entries of the filtered tables are dropped.
+#[derive(Clone, Debug, Default)]
+pub struct PruneWatermark(Arc<AtomicU64>);
+
+impl PruneWatermark {
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Raise the watermark to `seq`, unless it is already higher.
+    pub fn raise(&self, seq: u64) {
+        self.0.fetch_max(seq, Ordering::Relaxed);
+    }
+
+    /// Whether the entry with `key`, starting with a big-endian sequence number as the keys of
+    /// the tables are encoded, is below the watermark.
+    fn is_pruned(&self, key: &[u8]) -> bool {
+        match key.get(..8) {
+            Some(seq) => u64::from_be_bytes(seq.try_into().unwrap()) < self.get(),
+            None => false,
+        }
+    }
+
+    /// Set the compaction filter of the table with `options`, named `name`, to drop the entries
+    /// below this watermark.
+    pub fn set_compaction_filter(&self, name: &str, options: &mut Options) {
+        let watermark = self.clone();
+        options.set_compaction_filter(name, move |_level: u32, key: &[u8], _value: &[u8]| {
+            if watermark.is_pruned(key) {
+                CompactionDecision::Remove
+            } else {
+                CompactionDecision::Keep
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compactions_drop_entries_below_watermark() {
+        let dir = tempfile::tempdir().unwrap();
+        let (options, _) = crate::default_db_options(None, None);
+        let watermark = PruneWatermark::default();
+        let mut table_options = options.clone();
+        watermark.set_compaction_filter("prune_watermark", &mut table_options);
+        let db = typed_store::rocks::open_cf_opts(
+            dir.path(),
+            Some(options),
+            &[("sequence", &table_options)],
+        )
+        .unwrap();
+        let cf = db.cf_handle("sequence").unwrap();
+        for seq in 0u64..100 {
+            db.put_cf(&cf, seq.to_be_bytes(), b"digest").unwrap();
+        }
+
+        // Nothing is dropped until the watermark is raised.
+        db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        assert_eq!(
+            db.iterator_cf(&cf, rocksdb::IteratorMode::Start).count(),
+            100
+        );
+
+        watermark.raise(60);
+        watermark.raise(40);
+        assert_eq!(watermark.get(), 60);
+        db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        let keys: Vec<_> = db
+            .iterator_cf(&cf, rocksdb::IteratorMode::Start)
+            .map(|(key, _)| u64::from_be_bytes(key[..8].try_into().unwrap()))
+            .collect();
+        assert_eq!(keys, (60..100).collect::<Vec<_>>());
+    }
+}
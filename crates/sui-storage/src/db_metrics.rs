@@ -0,0 +1,284 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metrics of the RocksDB databases of a node, sampled from their properties.
+//!
+//! Sizes, key counts, block cache usage and pending compactions are sampled for each column
+//! family, with the database and the column family as labels. The caches hits and misses and
+//! the bytes read and written by compactions are only kept for whole databases, from their
+//! statistics, and are the totals since the database was opened: the hit rate of a cache is
+//! the rate of its hits over the rate of its hits and misses.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use prometheus::{register_int_gauge_vec_with_registry, IntGaugeVec, Registry};
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use tracing::warn;
+
+pub type Database = Arc<DBWithThreadMode<MultiThreaded>>;
+
+/// How often the metrics of the databases are sampled.
+pub const DB_METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The properties sampled for every column family.
+const CF_PROPERTIES: &[&str] = &[
+    "rocksdb.total-sst-files-size",
+    "rocksdb.cur-size-all-mem-tables",
+    "rocksdb.estimate-num-keys",
+    "rocksdb.block-cache-usage",
+    "rocksdb.estimate-pending-compaction-bytes",
+    "rocksdb.num-files-at-level0",
+];
+
+#[derive(Clone)]
+pub struct DbMetrics {
+    /// Size of the SST files of a column family, db and cf are labels
+    cf_sst_size: IntGaugeVec,
+    /// Size of the memtables of a column family, db and cf are labels
+    cf_memtable_size: IntGaugeVec,
+    /// Estimated number of keys of a column family, db and cf are labels
+    cf_estimated_keys: IntGaugeVec,
+    /// Size of the entries of a column family in its block cache, db and cf are labels
+    cf_block_cache_usage: IntGaugeVec,
+    /// Estimated bytes compactions have to rewrite, db and cf are labels
+    cf_pending_compaction_bytes: IntGaugeVec,
+    /// Number of files at level 0, db and cf are labels
+    cf_level0_files: IntGaugeVec,
+    /// Number of compactions running, db is a label
+    running_compactions: IntGaugeVec,
+    /// Hits of a cache of a database, db and cache are labels
+    cache_hits: IntGaugeVec,
+    /// Misses of a cache of a database, db and cache are labels
+    cache_misses: IntGaugeVec,
+    /// Bytes read by the compactions of a database, db is a label
+    compaction_bytes_read: IntGaugeVec,
+    /// Bytes written by the compactions of a database, db is a label
+    compaction_bytes_written: IntGaugeVec,
+}
+
+impl DbMetrics {
+    pub fn new(registry: &Registry) -> Self {
+        let per_cf = |name: &str, help: &str| {
+            register_int_gauge_vec_with_registry!(name, help, &["db", "cf"], registry).unwrap()
+        };
+        Self {
+            cf_sst_size: per_cf(
+                "rocksdb_cf_sst_size_bytes",
+                "Size of the SST files of a column family",
+            ),
+            cf_memtable_size: per_cf(
+                "rocksdb_cf_memtable_size_bytes",
+                "Size of the memtables of a column family",
+            ),
+            cf_estimated_keys: per_cf(
+                "rocksdb_cf_estimated_keys",
+                "Estimated number of keys of a column family",
+            ),
+            cf_block_cache_usage: per_cf(
+                "rocksdb_cf_block_cache_usage_bytes",
+                "Size of the entries of a column family in its block cache",
+            ),
+            cf_pending_compaction_bytes: per_cf(
+                "rocksdb_cf_pending_compaction_bytes",
+                "Estimated number of bytes compactions have to rewrite in a column family",
+            ),
+            cf_level0_files: per_cf(
+                "rocksdb_cf_level0_files",
+                "Number of files at level 0 of a column family",
+            ),
+            running_compactions: register_int_gauge_vec_with_registry!(
+                "rocksdb_running_compactions",
+                "Number of compactions running in a database",
+                &["db"],
+                registry,
+            )
+            .unwrap(),
+            cache_hits: register_int_gauge_vec_with_registry!(
+                "rocksdb_cache_hits",
+                "Number of hits of a cache of a database since it was opened",
+                &["db", "cache"],
+                registry,
+            )
+            .unwrap(),
+            cache_misses: register_int_gauge_vec_with_registry!(
+                "rocksdb_cache_misses",
+                "Number of misses of a cache of a database since it was opened",
+                &["db", "cache"],
+                registry,
+            )
+            .unwrap(),
+            compaction_bytes_read: register_int_gauge_vec_with_registry!(
+                "rocksdb_compaction_bytes_read",
+                "Number of bytes read by the compactions of a database since it was opened",
+                &["db"],
+                registry,
+            )
+            .unwrap(),
+            compaction_bytes_written: register_int_gauge_vec_with_registry!(
+                "rocksdb_compaction_bytes_written",
+                "Number of bytes written by the compactions of a database since it was opened",
+                &["db"],
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+
+    fn cf_gauge(&self, property: &str) -> &IntGaugeVec {
+        match property {
+            "rocksdb.total-sst-files-size" => &self.cf_sst_size,
+            "rocksdb.cur-size-all-mem-tables" => &self.cf_memtable_size,
+            "rocksdb.estimate-num-keys" => &self.cf_estimated_keys,
+            "rocksdb.block-cache-usage" => &self.cf_block_cache_usage,
+            "rocksdb.estimate-pending-compaction-bytes" => &self.cf_pending_compaction_bytes,
+            "rocksdb.num-files-at-level0" => &self.cf_level0_files,
+            _ => unreachable!("Unknown column family property {property}"),
+        }
+    }
+
+    /// Sample the metrics of the database `db` named `name`.
+    pub fn sample(&self, name: &str, db: &DBWithThreadMode<MultiThreaded>) {
+        let cfs = match DBWithThreadMode::<MultiThreaded>::list_cf(
+            &rocksdb::Options::default(),
+            db.path(),
+        ) {
+            Ok(cfs) => cfs,
+            Err(e) => {
+                warn!("Cannot list the column families of {name}: {e}");
+                return;
+            }
+        };
+        for cf_name in cfs {
+            let cf = match db.cf_handle(&cf_name) {
+                Some(cf) => cf,
+                None => continue,
+            };
+            for property in CF_PROPERTIES {
+                match db.property_int_value_cf(&cf, property) {
+                    Ok(Some(value)) => self
+                        .cf_gauge(property)
+                        .with_label_values(&[name, &cf_name])
+                        .set(value as i64),
+                    Ok(None) => (),
+                    Err(e) => warn!("Cannot read {property} of {name}/{cf_name}: {e}"),
+                }
+            }
+        }
+
+        if let Ok(Some(value)) = db.property_int_value("rocksdb.num-running-compactions") {
+            self.running_compactions
+                .with_label_values(&[name])
+                .set(value as i64);
+        }
+
+        // The statistics are only kept by the databases opened with them enabled.
+        let statistics = match db.property_value("rocksdb.options-statistics") {
+            Ok(Some(statistics)) => parse_statistics(&statistics),
+            _ => return,
+        };
+        let ticker = |ticker: &str| statistics.get(ticker).copied().unwrap_or_default() as i64;
+        for cache in ["block", "row"] {
+            self.cache_hits
+                .with_label_values(&[name, cache])
+                .set(ticker(&format!("rocksdb.{cache}.cache.hit")));
+            self.cache_misses
+                .with_label_values(&[name, cache])
+                .set(ticker(&format!("rocksdb.{cache}.cache.miss")));
+        }
+        self.compaction_bytes_read
+            .with_label_values(&[name])
+            .set(ticker("rocksdb.compact.read.bytes"));
+        self.compaction_bytes_written
+            .with_label_values(&[name])
+            .set(ticker("rocksdb.compact.write.bytes"));
+    }
+}
+
+/// The tickers of the statistics of a database, given as lines like
+/// `rocksdb.block.cache.hit COUNT : 42`.
+fn parse_statistics(statistics: &str) -> HashMap<&str, u64> {
+    statistics
+        .lines()
+        .filter_map(|line| {
+            let (ticker, count) = line.split_once(" COUNT : ")?;
+            Some((ticker.trim(), count.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Sample the metrics of `databases`, with their names, every `interval`.
+pub fn spawn_db_metrics(
+    metrics: DbMetrics,
+    databases: Vec<(&'static str, Database)>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            for (name, db) in &databases {
+                metrics.sample(name, db);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_column_families() {
+        let dir = tempfile::tempdir().unwrap();
+        let (options, _) = crate::default_db_options(None, None);
+        let db = typed_store::rocks::open_cf_opts(
+            dir.path(),
+            Some(options.clone()),
+            &[("table", &options)],
+        )
+        .unwrap();
+        let cf = db.cf_handle("table").unwrap();
+        for i in 0u32..100 {
+            db.put_cf(&cf, i.to_be_bytes(), b"value").unwrap();
+        }
+        db.flush_cf(&cf).unwrap();
+        db.get_cf(&cf, 1u32.to_be_bytes()).unwrap();
+
+        let metrics = DbMetrics::new(&Registry::new());
+        metrics.sample("test", &db);
+        assert_eq!(
+            metrics
+                .cf_estimated_keys
+                .with_label_values(&["test", "table"])
+                .get(),
+            100
+        );
+        assert!(
+            metrics
+                .cf_sst_size
+                .with_label_values(&["test", "table"])
+                .get()
+                > 0
+        );
+        assert!(
+            metrics.cache_hits.with_label_values(&["test", "row"]).get()
+                + metrics
+                    .cache_misses
+                    .with_label_values(&["test", "row"])
+                    .get()
+                > 0
+        );
+    }
+
+    #[test]
+    fn parse_statistics_tickers() {
+        let statistics = "rocksdb.block.cache.miss COUNT : 7\n\
+            rocksdb.block.cache.hit COUNT : 42\n\
+            rocksdb.db.get.micros P50 : 1.5 P95 : 2.0 P99 : 3.0 P100 : 4.0 COUNT : 2 SUM : 6\n";
+        let tickers = parse_statistics(statistics);
+        assert_eq!(tickers["rocksdb.block.cache.hit"], 42);
+        assert_eq!(tickers["rocksdb.block.cache.miss"], 7);
+    }
+}
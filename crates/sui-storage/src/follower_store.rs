@@ -11,6 +11,7 @@ use typed_store::rocks::DBMap;
 use typed_store::{reopen, traits::Map};
 
 use crate::default_db_options;
+use crate::ttl::now_ms;
 
 use tracing::debug;
 
@@ -18,6 +19,9 @@ use tracing::debug;
 /// batch.
 pub struct FollowerStore {
     next_sequence: DBMap<AuthorityName, TxSequenceNumber>,
+    /// When the next sequence numbers were last recorded, in milliseconds since the Unix epoch,
+    /// to remove the ones of the authorities no longer followed.
+    recorded_at: DBMap<AuthorityName, u64>,
 }
 
 impl FollowerStore {
@@ -27,14 +31,21 @@ impl FollowerStore {
         let db = {
             let path = &path;
             let db_options = Some(options.clone());
-            let opt_cfs: &[(&str, &rocksdb::Options)] = &[("next_sequence", &options)];
+            let opt_cfs: &[(&str, &rocksdb::Options)] =
+                &[("next_sequence", &options), ("recorded_at", &options)];
             typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
         }
         .map_err(SuiError::StorageError)?;
 
-        let next_sequence = reopen!(&db, "next_sequence";<AuthorityName, TxSequenceNumber>);
+        let (next_sequence, recorded_at) = reopen!(&db,
+            "next_sequence";<AuthorityName, TxSequenceNumber>,
+            "recorded_at";<AuthorityName, u64>
+        );
 
-        Ok(Self { next_sequence })
+        Ok(Self {
+            next_sequence,
+            recorded_at,
+        })
     }
 
     pub fn get_next_sequence(&self, name: &AuthorityName) -> SuiResult<Option<TxSequenceNumber>> {
@@ -44,9 +55,35 @@ impl FollowerStore {
     pub fn record_next_sequence(&self, name: &AuthorityName, seq: TxSequenceNumber) -> SuiResult {
         debug!(peer = ?name, ?seq, "record_next_sequence");
         self.next_sequence
-            .insert(name, &seq)
+            .batch()
+            .insert_batch(&self.next_sequence, std::iter::once((name, seq)))?
+            .insert_batch(&self.recorded_at, std::iter::once((name, now_ms())))?
+            .write()
             .map_err(SuiError::StorageError)
     }
+
+    /// Remove the next sequence numbers last recorded before `recorded_before_ms`, returning
+    /// the number of them removed. The ones recorded before the times of recording were kept are
+    /// given the current time, to expire in turn if they are not recorded again.
+    pub fn remove_stale(&self, recorded_before_ms: u64) -> SuiResult<usize> {
+        let now = now_ms();
+        let mut stale = vec![];
+        let mut untimed = vec![];
+        for name in self.next_sequence.keys() {
+            match self.recorded_at.get(&name)? {
+                Some(recorded_at) if recorded_at < recorded_before_ms => stale.push(name),
+                Some(_) => (),
+                None => untimed.push((name, now)),
+            }
+        }
+        self.next_sequence
+            .batch()
+            .delete_batch(&self.next_sequence, stale.iter())?
+            .delete_batch(&self.recorded_at, stale.iter())?
+            .insert_batch(&self.recorded_at, untimed)?
+            .write()?;
+        Ok(stale.len())
+    }
 }
 
 #[cfg(test)]
@@ -86,4 +123,28 @@ mod test {
             .expect("read error");
         assert_eq!(seq.unwrap(), 43);
     }
+
+    #[test]
+    fn test_remove_stale_sequences() {
+        let working_dir = tempfile::tempdir().unwrap();
+        let follower_store = FollowerStore::open(&working_dir).expect("cannot open db");
+
+        let (_, key_pair) = get_key_pair();
+        let val_name = &key_pair.public().into();
+        follower_store.record_next_sequence(val_name, 42).unwrap();
+
+        // Recently recorded sequence numbers are kept
+        let recorded_before = crate::ttl::now_ms() - 60_000;
+        assert_eq!(follower_store.remove_stale(recorded_before).unwrap(), 0);
+        assert_eq!(
+            follower_store.get_next_sequence(val_name).unwrap(),
+            Some(42)
+        );
+
+        assert_eq!(follower_store.remove_stale(u64::MAX).unwrap(), 1);
+        assert!(follower_store
+            .get_next_sequence(val_name)
+            .unwrap()
+            .is_none());
+    }
 }
@@ -7,6 +7,7 @@
 use rocksdb::Options;
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::db_metrics::Database;
 use crate::default_db_options;
 use std::path::Path;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
@@ -42,6 +43,9 @@ pub struct IndexStore {
     /// on a node according to the local machine time, so it varies across nodes.
     /// The timestamping happens when the node sees a txn certificate for the first time.
     timestamps: DBMap<TransactionDigest, u64>,
+
+    /// The database of the indexes, for its metrics.
+    db: Database,
 }
 
 impl IndexStore {
@@ -87,9 +91,15 @@ impl IndexStore {
             transactions_by_mutated_object_id,
             transactions_by_move_function,
             timestamps,
+            db,
         }
     }
 
+    /// The database the indexes are kept in.
+    pub fn db(&self) -> Database {
+        self.db.clone()
+    }
+
     pub fn index_tx<'a>(
         &self,
         sender: SuiAddress,
@@ -147,6 +157,17 @@ impl IndexStore {
         Ok(())
     }
 
+    /// Remove all the transactions from the indexes, keeping their timestamps, before they are
+    /// indexed again.
+    pub fn clear_transaction_indexes(&self) -> SuiResult {
+        self.transactions_from_addr.clear()?;
+        self.transactions_to_addr.clear()?;
+        self.transactions_by_input_object_id.clear()?;
+        self.transactions_by_mutated_object_id.clear()?;
+        self.transactions_by_move_function.clear()?;
+        Ok(())
+    }
+
     /// Returns unix timestamp for a transaction if it exists
     pub fn get_timestamp_ms(
         &self,
@@ -161,11 +182,25 @@ impl IndexStore {
     >(
         index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
         object_id: KeyT,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        Self::get_transactions_by_object_page(index, object_id, None, usize::MAX)
+    }
+
+    /// Returns up to `limit` transactions for `object_id` in sequence order, starting at
+    /// `cursor` (inclusive) if given.
+    fn get_transactions_by_object_page<
+        KeyT: Clone + Serialize + DeserializeOwned + std::cmp::PartialEq,
+    >(
+        index: &DBMap<(KeyT, TxSequenceNumber), TransactionDigest>,
+        object_id: KeyT,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
     ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
         Ok(index
             .iter()
-            .skip_to(&(object_id.clone(), TxSequenceNumber::MIN))?
+            .skip_to(&(object_id.clone(), cursor.unwrap_or(TxSequenceNumber::MIN)))?
             .take_while(|((id, _), _)| *id == object_id)
+            .take(limit)
             .map(|((_, seq), digest)| (seq, digest))
             .collect())
     }
@@ -191,6 +226,15 @@ impl IndexStore {
         Self::get_transactions_by_object(&self.transactions_from_addr, addr)
     }
 
+    pub fn get_transactions_from_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        Self::get_transactions_by_object_page(&self.transactions_from_addr, addr, cursor, limit)
+    }
+
     pub fn get_transactions_by_move_function(
         &self,
         package: ObjectID,
@@ -221,4 +265,102 @@ impl IndexStore {
     ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
         Self::get_transactions_by_object(&self.transactions_to_addr, addr)
     }
+
+    pub fn get_transactions_to_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        Self::get_transactions_by_object_page(&self.transactions_to_addr, addr, cursor, limit)
+    }
+
+    /// Returns up to `limit` transactions sent or received by `addr` in sequence order, starting
+    /// at `cursor` (inclusive) if given. A transaction both sent and received by `addr` is only
+    /// returned once.
+    pub fn get_transactions_for_addr_page(
+        &self,
+        addr: SuiAddress,
+        cursor: Option<TxSequenceNumber>,
+        limit: usize,
+    ) -> SuiResult<Vec<(TxSequenceNumber, TransactionDigest)>> {
+        let start = (addr, cursor.unwrap_or(TxSequenceNumber::MIN));
+        let mut from = self
+            .transactions_from_addr
+            .iter()
+            .skip_to(&start)?
+            .take_while(|((a, _), _)| *a == addr)
+            .peekable();
+        let mut to = self
+            .transactions_to_addr
+            .iter()
+            .skip_to(&start)?
+            .take_while(|((a, _), _)| *a == addr)
+            .peekable();
+
+        let mut result = Vec::new();
+        while result.len() < limit {
+            let next = match (from.peek(), to.peek()) {
+                (Some(((_, f), _)), Some(((_, t), _))) if f == t => {
+                    to.next();
+                    from.next()
+                }
+                (Some(((_, f), _)), Some(((_, t), _))) if f < t => from.next(),
+                (Some(_), None) => from.next(),
+                _ => to.next(),
+            };
+            match next {
+                Some(((_, seq), digest)) => result.push((seq, digest)),
+                None => break,
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transactions_for_addr_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::open(dir.path(), None);
+        let addr = SuiAddress::random_for_testing_only();
+        let other = SuiAddress::random_for_testing_only();
+        let digests: Vec<_> = (0..5).map(|_| TransactionDigest::random()).collect();
+
+        let sent = [0, 2, 3];
+        let received = [1, 3, 4];
+        for seq in sent {
+            store
+                .transactions_from_addr
+                .insert(&(addr, seq), &digests[seq as usize])
+                .unwrap();
+        }
+        for seq in received {
+            store
+                .transactions_to_addr
+                .insert(&(addr, seq), &digests[seq as usize])
+                .unwrap();
+        }
+        store
+            .transactions_from_addr
+            .insert(&(other, 1), &TransactionDigest::random())
+            .unwrap();
+
+        let all: Vec<_> = (0..5).map(|seq| (seq, digests[seq as usize])).collect();
+        assert_eq!(
+            store
+                .get_transactions_for_addr_page(addr, None, usize::MAX)
+                .unwrap(),
+            all
+        );
+        assert_eq!(
+            store
+                .get_transactions_for_addr_page(addr, Some(2), 2)
+                .unwrap(),
+            all[2..4]
+        );
+    }
 }
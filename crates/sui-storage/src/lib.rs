@@ -7,10 +7,17 @@ pub use lock_service::LockService;
 pub mod indexes;
 pub use indexes::IndexStore;
 
+pub mod backing_store;
+pub mod backup;
+pub mod backup_sink;
+pub mod compaction;
+pub mod db_metrics;
 pub mod event_store;
 pub mod follower_store;
+pub mod migration;
 pub mod mutex_table;
 pub mod node_sync_store;
+pub mod ttl;
 pub mod write_ahead_log;
 
 use rocksdb::Options;
@@ -37,6 +44,8 @@ pub fn default_db_options(
     options.set_row_cache(&row_cache);
     options.set_table_cache_num_shard_bits(10);
     options.set_compression_type(rocksdb::DBCompressionType::None);
+    // Keeps the tickers of the caches and of the compactions sampled by the `db_metrics`.
+    options.enable_statistics();
 
     let mut point_lookup = options.clone();
     point_lookup.optimize_for_point_lookup(1024 * 1024);
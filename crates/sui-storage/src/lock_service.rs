@@ -16,7 +16,7 @@
 //! This allows reads to proceed without being blocked on writes.
 
 use futures::channel::oneshot;
-use rocksdb::Options;
+use rocksdb::{DBWithThreadMode, MultiThreaded, Options};
 use std::path::Path;
 use std::sync::Arc;
 use std::thread::JoinHandle;
@@ -29,6 +29,9 @@ use sui_types::base_types::{ObjectRef, TransactionDigest};
 use sui_types::batch::TxSequenceNumber;
 use sui_types::error::{SuiError, SuiResult};
 
+use crate::backing_store::storage_error;
+use crate::backup::{self, BackupInfo};
+use crate::db_metrics::Database;
 use crate::default_db_options;
 
 /// Commands to send to the LockService (for mutating lock state)
@@ -90,13 +93,21 @@ struct LockServiceImpl {
     /// records a total ordering among all processed certificates (which is naturally local
     /// to this authority).
     tx_sequence: DBMap<TransactionDigest, TxSequenceNumber>,
+
+    /// The database of the tables above, to back it up.
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
 }
 
 // TODO: Create method needs to make sure only one instance or thread of this is running per authority
 // If not for multiple authorities per process, it should really be one per process.
 impl LockServiceImpl {
-    /// Open or create a new LockService database
-    fn try_open_db<P: AsRef<Path>>(path: P, db_options: Option<Options>) -> Result<Self, SuiError> {
+    /// Open or create a new LockService database, or open it as the secondary of its primary,
+    /// keeping its own files at `secondary_path`, if given.
+    fn try_open_db<P: AsRef<Path>>(
+        path: P,
+        secondary_path: Option<&Path>,
+        db_options: Option<Options>,
+    ) -> Result<Self, SuiError> {
         let (options, point_lookup) = default_db_options(db_options, None);
 
         let db = {
@@ -106,7 +117,15 @@ impl LockServiceImpl {
                 ("transaction_lock", &point_lookup),
                 ("tx_sequence", &point_lookup),
             ];
-            typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
+            match secondary_path {
+                Some(secondary_path) => typed_store::rocks::open_cf_opts_secondary(
+                    path.as_ref(),
+                    Some(secondary_path),
+                    db_options,
+                    opt_cfs,
+                ),
+                None => typed_store::rocks::open_cf_opts(path, db_options, opt_cfs),
+            }
         }
         .map_err(SuiError::StorageError)?;
 
@@ -117,6 +136,7 @@ impl LockServiceImpl {
         Ok(Self {
             transaction_lock,
             tx_sequence,
+            db,
         })
     }
 
@@ -422,6 +442,7 @@ struct LockServiceInner {
     query_sender: Option<Sender<LockServiceQueries>>,
     run_command_loop: Option<JoinHandle<()>>,
     run_queries_loop: Option<JoinHandle<()>>,
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
 }
 
 impl LockServiceInner {
@@ -467,7 +488,27 @@ impl LockService {
     /// Create a new instance of LockService.  For now, the caller has to guarantee only one per data store -
     /// namely each SuiDataStore creates its own LockService.
     pub fn new<P: AsRef<Path>>(path: P, db_options: Option<Options>) -> Result<Self, SuiError> {
-        let inner_service = LockServiceImpl::try_open_db(path, db_options)?;
+        Ok(Self::start(LockServiceImpl::try_open_db(
+            path, None, db_options,
+        )?))
+    }
+
+    /// Open the lock database at `path` as a secondary of the one of a running service, keeping
+    /// its own files at `secondary_path`. The locks can be read, not written.
+    pub fn open_read_only<P: AsRef<Path>>(
+        path: P,
+        secondary_path: &Path,
+        db_options: Option<Options>,
+    ) -> Result<Self, SuiError> {
+        Ok(Self::start(LockServiceImpl::try_open_db(
+            path,
+            Some(secondary_path),
+            db_options,
+        )?))
+    }
+
+    fn start(inner_service: LockServiceImpl) -> Self {
+        let db = inner_service.db.clone();
 
         // Now, create a sync channel and spawn a thread
         let (sender, receiver) = channel(LOCKSERVICE_QUEUE_LEN);
@@ -481,14 +522,33 @@ impl LockService {
             inner_service.run_queries_loop(q_receiver);
         });
 
-        Ok(Self {
+        Self {
             inner: Arc::new(LockServiceInner {
                 sender: Some(sender),
                 query_sender: Some(q_sender),
                 run_command_loop: Some(run_command_loop),
                 run_queries_loop: Some(run_queries_loop),
+                db,
             }),
-        })
+        }
+    }
+
+    /// The database the locks are kept in.
+    pub fn db(&self) -> Database {
+        self.inner.db.clone()
+    }
+
+    /// Catch up with the writes of the primary, for the services opened read only.
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        self.inner
+            .db
+            .try_catch_up_with_primary()
+            .map_err(|e| SuiError::StorageError(storage_error(e)))
+    }
+
+    /// Back the lock database up into `dir` while it is in use, see [`crate::backup`].
+    pub fn create_backup(&self, dir: &Path) -> SuiResult<BackupInfo> {
+        backup::create_backup(&self.inner.db, dir).map_err(SuiError::StorageError)
     }
 
     /// Acquires a lock for a transaction on the given objects if they have all been initialized previously
@@ -0,0 +1,181 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioning of the schema of a store, and the migrations between its versions.
+//!
+//! A store records the version of the schema its tables follow. Each change of the layout of
+//! the tables, such as a new column family or a new encoding of some entries, comes with a
+//! migration bringing the tables of the previous version to the new one. When a store is opened,
+//! the migrations from its version to the latest one are run in order, its version being
+//! recorded after each of them, so that a store stopped while being migrated resumes from the
+//! migration that did not complete.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use sui_types::error::{SuiError, SuiResult};
+use tracing::info;
+
+/// How the stores of a node are migrated when it starts.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct MigrationConfig {
+    /// Only report the migrations the stores need, without running them. The node does not
+    /// start if any are needed.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Where the stores are backed up before they are migrated, if anywhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_dir: Option<PathBuf>,
+}
+
+/// A migration of the tables of a store of type `T` to `version`, from the previous version.
+pub struct Migration<T> {
+    pub version: u64,
+    pub description: &'static str,
+    pub migrate: fn(&T) -> SuiResult,
+}
+
+/// The migrations run, or to run for dry runs, by [`Migrations::run`].
+#[derive(Debug, Default, Serialize, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u64,
+    pub to_version: u64,
+    /// The versions migrated to, with the descriptions of their migrations.
+    pub migrations: Vec<(u64, &'static str)>,
+    pub dry_run: bool,
+}
+
+/// The migrations of a store, from the version of the stores that did not record theirs, 0, to
+/// the latest one.
+pub struct Migrations<T> {
+    migrations: Vec<Migration<T>>,
+}
+
+impl<T> Migrations<T> {
+    /// The `migrations` of a store, which must migrate to the versions following each other
+    /// from 1.
+    pub fn new(migrations: Vec<Migration<T>>) -> Self {
+        for (i, migration) in migrations.iter().enumerate() {
+            assert_eq!(
+                migration.version,
+                i as u64 + 1,
+                "Migrations must follow each other"
+            );
+        }
+        Self { migrations }
+    }
+
+    /// The version of the schema the migrations bring the stores to.
+    pub fn latest_version(&self) -> u64 {
+        self.migrations.len() as u64
+    }
+
+    /// The migrations from `version` to the latest one.
+    pub fn pending(&self, version: u64) -> SuiResult<&[Migration<T>]> {
+        self.migrations.get(version as usize..).ok_or_else(|| {
+            SuiError::GenericStorageError(format!(
+                "The store has version {version} of the schema, later than the latest known \
+                 one, {}",
+                self.latest_version()
+            ))
+        })
+    }
+
+    /// Migrate `store` from `version` to the latest version, calling `record_version` with the
+    /// version of each migration once it is run. Dry runs only report the migrations to run.
+    pub fn run(
+        &self,
+        store: &T,
+        version: u64,
+        dry_run: bool,
+        record_version: impl Fn(u64) -> SuiResult,
+    ) -> SuiResult<MigrationReport> {
+        let pending = self.pending(version)?;
+        let report = MigrationReport {
+            from_version: version,
+            to_version: self.latest_version(),
+            migrations: pending
+                .iter()
+                .map(|migration| (migration.version, migration.description))
+                .collect(),
+            dry_run,
+        };
+        if dry_run {
+            return Ok(report);
+        }
+        for migration in pending {
+            info!(
+                version = migration.version,
+                "Migrating the store: {}", migration.description
+            );
+            (migration.migrate)(store)?;
+            record_version(migration.version)?;
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn migrations() -> Migrations<Mutex<Vec<u64>>> {
+        Migrations::new(vec![
+            Migration {
+                version: 1,
+                description: "first",
+                migrate: |store| {
+                    store.lock().unwrap().push(1);
+                    Ok(())
+                },
+            },
+            Migration {
+                version: 2,
+                description: "second",
+                migrate: |store| {
+                    store.lock().unwrap().push(2);
+                    Ok(())
+                },
+            },
+        ])
+    }
+
+    #[test]
+    fn migrations_run_in_order_from_the_version() {
+        let migrations = migrations();
+        let store = Mutex::new(vec![]);
+        let recorded = Mutex::new(vec![]);
+        let record = |version| {
+            recorded.lock().unwrap().push(version);
+            Ok(())
+        };
+
+        let report = migrations.run(&store, 0, true, record).unwrap();
+        assert_eq!(report.migrations, vec![(1, "first"), (2, "second")]);
+        assert!(store.lock().unwrap().is_empty());
+
+        let report = migrations.run(&store, 1, false, record).unwrap();
+        assert_eq!(report.migrations, vec![(2, "second")]);
+        assert_eq!(*store.lock().unwrap(), vec![2]);
+        assert_eq!(*recorded.lock().unwrap(), vec![2]);
+
+        assert!(migrations
+            .run(&store, 2, false, record)
+            .unwrap()
+            .migrations
+            .is_empty());
+        assert!(migrations.run(&store, 3, false, record).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn migrations_must_follow_each_other() {
+        Migrations::<()>::new(vec![Migration {
+            version: 2,
+            description: "skips the first version",
+            migrate: |_| Ok(()),
+        }]);
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expiry of the transient rows of the stores of a node, which are not needed once they are
+//! older than their time to live: the transactions an authority signed that were never
+//! certified, and the sequence numbers a follower keeps for the authorities it stopped
+//! following.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TtlConfig {
+    /// How often the expired rows are removed.
+    #[serde(default = "default_cleanup_interval_secs")]
+    pub cleanup_interval_secs: u64,
+    /// How long the transactions signed by the authority are kept, unless they are certified
+    /// and executed before.
+    #[serde(default = "default_signed_transaction_ttl_secs")]
+    pub signed_transaction_ttl_secs: u64,
+    /// How long the next sequence number of an authority is kept once the follower stopped
+    /// recording it.
+    #[serde(default = "default_follower_state_ttl_secs")]
+    pub follower_state_ttl_secs: u64,
+}
+
+fn default_cleanup_interval_secs() -> u64 {
+    10 * 60
+}
+
+fn default_signed_transaction_ttl_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_follower_state_ttl_secs() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+impl Default for TtlConfig {
+    fn default() -> Self {
+        Self {
+            cleanup_interval_secs: default_cleanup_interval_secs(),
+            signed_transaction_ttl_secs: default_signed_transaction_ttl_secs(),
+            follower_state_ttl_secs: default_follower_state_ttl_secs(),
+        }
+    }
+}
+
+/// The current time, in milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// The time before which the rows with a time to live of `ttl_secs` are expired, in
+/// milliseconds since the Unix epoch.
+pub fn expiry_ms(ttl_secs: u64) -> u64 {
+    now_ms().saturating_sub(ttl_secs.saturating_mul(1000))
+}
@@ -9,10 +9,15 @@ use async_trait::async_trait;
 
 use serde::{de::DeserializeOwned, Serialize};
 
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use crate::{
+    backing_store::storage_error,
+    backup::{self, BackupInfo},
+    db_metrics::Database,
     default_db_options,
     mutex_table::{LockGuard, MutexTable},
 };
@@ -167,6 +172,9 @@ pub struct DBWriteAheadLog<C> {
 
     // Guards the get/set in begin_tx
     mutex_table: MutexTable<TransactionDigest>,
+
+    // The database of the tables, to back it up.
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
 }
 
 const MUTEX_TABLE_SIZE: usize = 1024;
@@ -176,6 +184,16 @@ where
     C: Serialize + DeserializeOwned,
 {
     pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self::open(path, None)
+    }
+
+    /// Open the log at `path` as a secondary of the one of a running node, keeping its own
+    /// files at `secondary_path`. The log can be read, not written.
+    pub fn open_read_only<P: AsRef<Path>>(path: P, secondary_path: &Path) -> Self {
+        Self::open(path, Some(secondary_path))
+    }
+
+    fn open<P: AsRef<Path>>(path: P, secondary_path: Option<&Path>) -> Self {
         let (options, _) = default_db_options(None, None);
         let db = {
             let path = &path;
@@ -184,7 +202,15 @@ where
                 ("tx_write_ahead_log", &options),
                 ("tx_retry_count", &options),
             ];
-            typed_store::rocks::open_cf_opts(path, db_options, opt_cfs)
+            match secondary_path {
+                Some(secondary_path) => typed_store::rocks::open_cf_opts_secondary(
+                    path.as_ref(),
+                    Some(secondary_path),
+                    db_options,
+                    opt_cfs,
+                ),
+                None => typed_store::rocks::open_cf_opts(path, db_options, opt_cfs),
+            }
         }
         .expect("Cannot open DB.");
 
@@ -207,9 +233,27 @@ where
             retry_count,
             recoverable_txes: Mutex::new(recoverable_txes),
             mutex_table: MutexTable::new(MUTEX_TABLE_SIZE),
+            db,
         }
     }
 
+    /// The database the log is kept in.
+    pub fn db(&self) -> Database {
+        self.db.clone()
+    }
+
+    /// Catch up with the writes of the primary, for the logs opened read only.
+    pub fn try_catch_up_with_primary(&self) -> SuiResult {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(|e| SuiError::StorageError(storage_error(e)))
+    }
+
+    /// Back the log up into `dir` while it is in use, see [`crate::backup`].
+    pub fn create_backup(&self, dir: &Path) -> SuiResult<BackupInfo> {
+        backup::create_backup(&self.db, dir).map_err(SuiError::StorageError)
+    }
+
     fn commit_tx(&self, tx: &TransactionDigest) -> SuiResult {
         debug!(digest = ?tx, "committing tx");
         let write_batch = self.log.batch();
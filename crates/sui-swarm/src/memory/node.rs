@@ -4,9 +4,12 @@
 use anyhow::Result;
 use futures::FutureExt;
 use std::thread;
+use std::time::Duration;
 use sui_config::NodeConfig;
+use sui_core::node_faults;
+use sui_network::{tcp, tls::NodeCertificate};
 use sui_node::SuiNode;
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{AuthorityName, SuiAddress};
 use tap::TapFallible;
 use tracing::{error, trace};
 
@@ -43,6 +46,11 @@ impl Node {
         self.config.sui_address()
     }
 
+    /// Return the name of the authority of this Node
+    pub fn authority_name(&self) -> AuthorityName {
+        self.config.public_key()
+    }
+
     pub fn json_rpc_address(&self) -> std::net::SocketAddr {
         self.config.json_rpc_address
     }
@@ -70,6 +78,25 @@ impl Node {
         self.thread = None;
     }
 
+    /// Stop this Node if it is running and start it again from its store, waiting until it is
+    /// completely started up.
+    pub async fn restart(&mut self) -> Result<()> {
+        self.stop();
+        self.start().await
+    }
+
+    /// Crash this Node the next time it reaches the crash `point`, one of the points of
+    /// [`node_faults`]. The Node stops as if the process running it had crashed there, and can
+    /// be started again.
+    pub fn crash_at(&self, point: &'static str) {
+        node_faults::arm_crash_point(self.authority_name(), point);
+    }
+
+    /// Delay every request served by this Node by `lag`, none if zero.
+    pub fn set_network_lag(&self, lag: Duration) {
+        node_faults::set_network_lag(self.authority_name(), lag);
+    }
+
     /// Perform a health check on this Node by:
     /// * Checking that the node is running
     /// * Calling the Node's gRPC Health service
@@ -79,10 +106,15 @@ impl Node {
             return Err(HealthCheckError::NotRunning);
         }
 
-        let channel = mysten_network::client::connect(self.config.network_address())
-            .await
-            .map_err(HealthCheckError::Failure)
-            .tap_err(|e| error!("error connecting to {}: {e}", self.name()))?;
+        let channel = tcp::connect(
+            &mysten_network::config::Config::new(),
+            self.config.network_address(),
+            &NodeCertificate::ephemeral(),
+            Some(self.config.network_public_key()),
+        )
+        .await
+        .map_err(|e| HealthCheckError::Failure(e.into()))
+        .tap_err(|e| error!("error connecting to {}: {e}", self.name()))?;
         let mut client = tonic_health::proto::health_client::HealthClient::new(channel);
         client
             .check(tonic_health::proto::HealthCheckRequest::default())
@@ -176,14 +208,20 @@ impl Container {
             };
             let runtime = builder.enable_all().build().unwrap();
 
+            let crash_listener = node_faults::crash_listener(config.public_key());
             runtime.block_on(async move {
                 let _server = SuiNode::start(&config).await.unwrap();
                 // Notify that we've successfully started the node
                 let _ = startup_sender.send(());
-                // run until canceled
-                cancel_reciever.map(|_| ()).await;
-
-                trace!("cancellation received; shutting down thread");
+                // run until canceled, or until the node crashes at a crash point
+                tokio::select! {
+                    _ = cancel_reciever.map(|_| ()) => {
+                        trace!("cancellation received; shutting down thread");
+                    }
+                    _ = crash_listener.notified() => {
+                        error!("node crashed at a crash point; shutting down thread");
+                    }
+                }
             });
         });
 
@@ -221,6 +259,8 @@ pub enum RuntimeType {
 #[cfg(test)]
 mod test {
     use crate::memory::Swarm;
+    use std::time::Duration;
+    use sui_core::node_faults;
 
     #[tokio::test]
     async fn start_and_stop() {
@@ -237,4 +277,29 @@ mod test {
         validator.start().await.unwrap();
         validator.health_check().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn restart_and_crash() {
+        telemetry_subscribers::init_for_testing();
+        let mut swarm = Swarm::builder().build();
+
+        let validator = swarm.validators_mut().next().unwrap();
+        validator.start().await.unwrap();
+        validator.restart().await.unwrap();
+        validator.health_check().await.unwrap();
+
+        // Reach the crash point as the node would
+        validator.crash_at(node_faults::BEFORE_COMMIT_CERTIFICATE);
+        let name = validator.authority_name();
+        let crash = std::panic::catch_unwind(|| {
+            node_faults::crash_point(&name, node_faults::BEFORE_COMMIT_CERTIFICATE);
+        });
+        assert!(crash.is_err());
+        while validator.health_check().await.is_ok() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        validator.start().await.unwrap();
+        validator.health_check().await.unwrap();
+    }
 }
@@ -13,7 +13,7 @@ use std::{
 };
 use sui_config::builder::ConfigBuilder;
 use sui_config::genesis_config::GenesisConfig;
-use sui_config::NetworkConfig;
+use sui_config::{NetworkConfig, NodeConfig};
 use sui_types::base_types::SuiAddress;
 use tempfile::TempDir;
 
@@ -97,7 +97,8 @@ impl<R: ::rand::RngCore + ::rand::CryptoRng> SwarmBuilder<R> {
         let network_config = config_builder
             .committee_size(self.committee_size)
             .rng(self.rng)
-            .build();
+            .build()
+            .expect("The swarm is configured with a valid genesis");
 
         let validators = network_config
             .validator_configs()
@@ -169,6 +170,15 @@ impl Swarm {
         Ok(())
     }
 
+    /// Start a Fullnode from `config`, usually derived from
+    /// [`NetworkConfig::generate_fullnode_config`], and add it to this Swarm.
+    pub async fn start_fullnode(&mut self, config: NodeConfig) -> Result<()> {
+        let mut node = Node::new(config);
+        node.start().await?;
+        self.fullnodes.insert(node.name(), node);
+        Ok(())
+    }
+
     /// Return the path to the directory where this Swarm's on-disk data is kept.
     pub fn dir(&self) -> &Path {
         self.dir.as_ref()
@@ -295,4 +305,15 @@ mod test {
             fullnode.health_check().await.unwrap();
         }
     }
+    #[tokio::test]
+    async fn start_fullnode() {
+        telemetry_subscribers::init_for_testing();
+        let mut swarm = Swarm::builder().build();
+        swarm.launch().await.unwrap();
+
+        let config = swarm.config().generate_fullnode_config();
+        let name = config.sui_address();
+        swarm.start_fullnode(config).await.unwrap();
+        swarm.fullnode(name).unwrap().health_check().await.unwrap();
+    }
 }
@@ -5,12 +5,16 @@ use anyhow::Result;
 use futures::future::join_all;
 use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use sui_config::genesis::Genesis;
 use sui_tool::db_tool::{execute_db_tool_command, print_db_all_tables, DbToolCommand};
 
 use sui_core::authority_client::{AuthorityAPI, NetworkAuthorityClient};
-use sui_types::{base_types::*, batch::*, messages::*, object::Owner};
+use sui_core::traffic_capture::{self, ReplayConfig, TrafficLog};
+use sui_network::pool::{ConnectionPool, PoolConfig};
+use sui_network::quic::{QuicConfig, QuicConnector};
+use sui_types::{base_types::*, batch::*, handshake::Handshake, messages::*, object::Owner};
 
 use futures::stream::StreamExt;
 
@@ -106,6 +110,45 @@ pub enum ToolCommand {
         #[clap(long = "genesis")]
         genesis: PathBuf,
     },
+
+    /// Print the modules, objects and validators which differ between two genesis.
+    #[clap(name = "diff-genesis")]
+    DiffGenesis {
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+
+        #[clap(long, help = "The genesis to compare with")]
+        other: PathBuf,
+    },
+
+    /// Replay the traffic captured by a validator into a fresh authority state starting from the
+    /// genesis of its network, and report how long it took.
+    #[clap(name = "replay-traffic")]
+    ReplayTraffic {
+        #[clap(
+            long,
+            help = "The traffic log, the traffic-capture-path of the validator"
+        )]
+        log: PathBuf,
+
+        #[clap(long = "genesis")]
+        genesis: PathBuf,
+
+        #[clap(
+            long,
+            help = "How many times faster than captured to replay, as fast as possible if not set"
+        )]
+        speed: Option<f64>,
+
+        #[clap(long, help = "Requests handled at once", default_value_t = 100)]
+        max_in_flight: usize,
+
+        #[clap(
+            long,
+            help = "Where to keep the store of the state, a temporary directory if not set"
+        )]
+        db_path: Option<PathBuf>,
+    },
 }
 
 fn make_clients(genesis: PathBuf) -> Result<BTreeMap<AuthorityName, NetworkAuthorityClient>> {
@@ -118,9 +161,18 @@ fn make_clients(genesis: PathBuf) -> Result<BTreeMap<AuthorityName, NetworkAutho
 
     let mut authority_clients = BTreeMap::new();
 
+    let quic_connector = QuicConnector::ephemeral(QuicConfig::default());
+    let pool = Arc::new(
+        ConnectionPool::new(PoolConfig::default(), net_config, quic_connector)
+            .with_handshake(Handshake::new(genesis.chain_id())),
+    );
     for validator in genesis.validator_set() {
-        let channel = net_config.connect_lazy(&validator.network_address)?;
-        let client = NetworkAuthorityClient::new(channel);
+        let client = NetworkAuthorityClient::pooled_with_network_key(
+            pool.clone(),
+            validator.network_address.clone(),
+            validator.public_key(),
+            validator.network_key(),
+        );
         let public_key_bytes = validator.public_key();
         authority_clients.insert(public_key_bytes, client);
     }
@@ -440,6 +492,40 @@ impl ToolCommand {
                 let genesis = Genesis::load(genesis).unwrap();
                 println!("{:#?}", genesis);
             }
+
+            ToolCommand::DiffGenesis { genesis, other } => {
+                let genesis = Genesis::load(genesis)?;
+                let other = Genesis::load(other)?;
+                print!("{}", genesis.diff(&other));
+            }
+
+            ToolCommand::ReplayTraffic {
+                log,
+                genesis,
+                speed,
+                max_in_flight,
+                db_path,
+            } => {
+                let genesis = Genesis::load(genesis)?;
+                let temp_dir = tempfile::tempdir()?;
+                let db_path = db_path.unwrap_or_else(|| temp_dir.path().to_owned());
+                let state = traffic_capture::replay_state(&genesis, &db_path).await?;
+                let log = TrafficLog::open(&log)?;
+                let config = ReplayConfig {
+                    speed,
+                    max_in_flight,
+                };
+                let stats = traffic_capture::replay(state, log, config).await?;
+                let requests = stats.transactions + stats.certificates;
+                println!(
+                    "Replayed {} transactions and {} certificates in {:?} ({:.0}/s), {} failed",
+                    stats.transactions,
+                    stats.certificates,
+                    stats.elapsed,
+                    requests as f64 / stats.elapsed.as_secs_f64(),
+                    stats.failed,
+                );
+            }
         };
         Ok(())
     }
@@ -0,0 +1,58 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::path::PathBuf;
+use sui_core::authority::authority_store_integrity::IntegrityReport;
+use sui_core::authority::authority_store_tables::StoreTables;
+use sui_storage::IndexStore;
+use sui_types::crypto::{AuthoritySignInfo, EmptySignInfo};
+use tracing::info;
+
+/// Check the integrity of the store at `path`. When repairing, the store is opened for
+/// writing, so that the node using it must be stopped, and the owner and type indexes are
+/// rebuilt if they are inconsistent, as are the transaction indexes at `index_path` if given.
+/// The report is the one of the store before it is repaired.
+pub fn check_store(
+    gateway: bool,
+    path: PathBuf,
+    repair: bool,
+    index_path: Option<PathBuf>,
+) -> anyhow::Result<IntegrityReport> {
+    if gateway {
+        check_tables::<EmptySignInfo>(path, repair, index_path)
+    } else {
+        check_tables::<AuthoritySignInfo>(path, repair, index_path)
+    }
+}
+
+fn check_tables<S: Eq + Debug + Serialize + for<'de> Deserialize<'de>>(
+    path: PathBuf,
+    repair: bool,
+    index_path: Option<PathBuf>,
+) -> anyhow::Result<IntegrityReport> {
+    if !repair {
+        let temp_dir = tempfile::tempdir()?.into_path();
+        let store: StoreTables<S> = StoreTables::open_read_only(path, temp_dir, None);
+        store.try_catch_up_with_primary()?;
+        return Ok(store.check_integrity()?);
+    }
+
+    let store: StoreTables<S> = StoreTables::open_read_write(path, None);
+    let report = store.check_integrity()?;
+    if report.owner_index_is_inconsistent() {
+        let indexed = store.rebuild_owner_index()?;
+        info!("Rebuilt the owner index of {indexed} objects");
+    }
+    if report.type_index_is_inconsistent() {
+        let indexed = store.rebuild_type_index()?;
+        info!("Rebuilt the type index of {indexed} objects");
+    }
+    if let Some(index_path) = index_path {
+        let indexes = IndexStore::open(index_path, None);
+        let indexed = store.rebuild_transaction_indexes(&indexes)?;
+        info!("Rebuilt the indexes of {indexed} transactions");
+    }
+    Ok(report)
+}
@@ -0,0 +1,21 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use sui_core::authority::{AuthorityStore, GatewayStore};
+use sui_storage::migration::{MigrationConfig, MigrationReport};
+
+/// Migrate the store at `path` to the latest version of its schema, or only report the
+/// migrations it needs for dry runs. The store is opened for writing, so that the node using it
+/// must be stopped, and backed up into `config.backup_dir` first if set.
+pub fn migrate_store(
+    gateway: bool,
+    path: PathBuf,
+    config: &MigrationConfig,
+) -> anyhow::Result<MigrationReport> {
+    Ok(if gateway {
+        GatewayStore::open(path, None).migrate(config)?
+    } else {
+        AuthorityStore::open(path, None).migrate(config)?
+    })
+}
@@ -1,17 +1,24 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use self::db_check::check_store;
 use self::db_dump::{dump_table, list_tables};
+use self::db_migrate::migrate_store;
 use clap::Parser;
 use std::path::PathBuf;
+use sui_storage::migration::MigrationConfig;
 
+pub mod db_check;
 pub mod db_dump;
+pub mod db_migrate;
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
 pub enum DbToolCommand {
     ListTables,
     Dump(Dump),
+    Check(Check),
+    Migrate(Migrate),
 }
 
 #[derive(Parser)]
@@ -24,10 +31,41 @@ pub struct Dump {
     table_name: String,
 }
 
+/// Check the store for entries that cannot be decoded, owner index entries that do not match
+/// the objects, and gaps in the versions of objects and in the sequence of executed transactions.
+#[derive(Parser)]
+pub struct Check {
+    /// If this is a gateway DB or authority DB
+    #[clap(long = "gateway")]
+    gateway: bool,
+    /// Rebuild the owner and type indexes if they are inconsistent. The node must be stopped.
+    #[clap(long = "repair")]
+    repair: bool,
+    /// When repairing, also rebuild the transaction indexes kept at this path
+    #[clap(long = "index_path", requires = "repair")]
+    index_path: Option<PathBuf>,
+}
+
+/// Migrate the store to the latest version of its schema. The node must be stopped.
+#[derive(Parser)]
+pub struct Migrate {
+    /// If this is a gateway DB or authority DB
+    #[clap(long = "gateway")]
+    gateway: bool,
+    /// Only report the migrations the store needs
+    #[clap(long = "dry_run")]
+    dry_run: bool,
+    /// Back the store up into this directory before migrating it
+    #[clap(long = "backup_dir")]
+    backup_dir: Option<PathBuf>,
+}
+
 pub fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> anyhow::Result<()> {
     match cmd {
         DbToolCommand::ListTables => print_db_all_tables(db_path),
         DbToolCommand::Dump(d) => print_all_entries(d.gateway, db_path, &d.table_name),
+        DbToolCommand::Check(c) => print_integrity_report(db_path, c),
+        DbToolCommand::Migrate(m) => print_migration_report(db_path, m),
     }
 }
 
@@ -42,3 +80,22 @@ pub fn print_all_entries(gateway: bool, path: PathBuf, table_name: &str) -> anyh
     }
     Ok(())
 }
+
+pub fn print_integrity_report(path: PathBuf, check: Check) -> anyhow::Result<()> {
+    let report = check_store(check.gateway, path, check.repair, check.index_path)?;
+    println!("{:#?}", report);
+    if !report.is_ok() {
+        anyhow::bail!("The store is inconsistent");
+    }
+    Ok(())
+}
+
+pub fn print_migration_report(path: PathBuf, migrate: Migrate) -> anyhow::Result<()> {
+    let config = MigrationConfig {
+        dry_run: migrate.dry_run,
+        backup_dir: migrate.backup_dir,
+    };
+    let report = migrate_store(migrate.gateway, path, &config)?;
+    println!("{:#?}", report);
+    Ok(())
+}
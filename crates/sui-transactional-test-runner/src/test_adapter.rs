@@ -43,7 +43,7 @@ use sui_core::execution_engine;
 use sui_framework::DEFAULT_FRAMEWORK_PATH;
 use sui_types::{
     base_types::{
-        ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
+        ChainId, ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
         SUI_ADDRESS_LENGTH,
     },
     crypto::{get_key_pair_from_rng, KeyPair, Signature},
@@ -51,6 +51,7 @@ use sui_types::{
     gas,
     messages::{ExecutionStatus, InputObjects, Transaction, TransactionData, TransactionEffects},
     object::{self, Object, ObjectFormatOptions, GAS_VALUE_FOR_TESTING},
+    protocol_config::ProtocolConfig,
     MOVE_STDLIB_ADDRESS, SUI_FRAMEWORK_ADDRESS,
 };
 
@@ -211,8 +212,14 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
             buf
         };
         let gas_budget = gas_budget.unwrap_or(GAS_VALUE_FOR_TESTING);
-        let data = |sender, gas_payment| {
-            TransactionData::new_module(sender, gas_payment, vec![module_bytes], gas_budget)
+        let data = |sender, gas_payment, chain_id| {
+            TransactionData::new_module(
+                sender,
+                gas_payment,
+                vec![module_bytes],
+                gas_budget,
+                chain_id,
+            )
         };
         let transaction = self.sign_txn(sender, data);
         let summary = self.execute_txn(transaction, gas_budget)?;
@@ -291,7 +298,7 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
         };
 
         let gas_budget = gas_budget.unwrap_or(GAS_VALUE_FOR_TESTING);
-        let data = |sender, gas_payment| {
+        let data = |sender, gas_payment, chain_id| {
             TransactionData::new_move_call(
                 sender,
                 package_ref,
@@ -301,6 +308,7 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
                 gas_payment,
                 arguments,
                 gas_budget,
+                chain_id,
             )
         };
         let transaction = self.sign_txn(sender, data);
@@ -411,8 +419,10 @@ impl<'a> MoveTestAdapter<'a> for SuiTestAdapter<'a> {
                     None => panic!("Unbound account {}", recipient),
                 };
                 let gas_budget = gas_budget.unwrap_or(GAS_VALUE_FOR_TESTING);
-                let transaction = self.sign_txn(sender, |sender, gas| {
-                    TransactionData::new_transfer(recipient, obj_ref, sender, gas, gas_budget)
+                let transaction = self.sign_txn(sender, |sender, gas, chain_id| {
+                    TransactionData::new_transfer(
+                        recipient, obj_ref, sender, gas, gas_budget, chain_id,
+                    )
                 });
                 let summary = self.execute_txn(transaction, gas_budget)?;
                 let output = self.object_summary_output(&summary, false);
@@ -426,7 +436,11 @@ impl<'a> SuiTestAdapter<'a> {
     fn sign_txn(
         &mut self,
         sender: Option<String>,
-        txn_data: impl FnOnce(/* sender */ SuiAddress, /* gas */ ObjectRef) -> TransactionData,
+        txn_data: impl FnOnce(
+            /* sender */ SuiAddress,
+            /* gas */ ObjectRef,
+            /* chain */ ChainId,
+        ) -> TransactionData,
     ) -> Transaction {
         let gas_object_id = ObjectID::new(self.rng.gen());
         assert!(!self.object_enumeration.contains_left(&gas_object_id));
@@ -447,7 +461,8 @@ impl<'a> SuiTestAdapter<'a> {
         let gas_payment = gas_object.compute_object_reference();
         let storage_mut = Arc::get_mut(&mut self.storage).unwrap();
         storage_mut.insert_object(gas_object);
-        let data = txn_data(sender, gas_payment);
+        // The transactions are executed directly, no validator checks the chain they are bound to
+        let data = txn_data(sender, gas_payment, ChainId::deterministic_for_testing(0));
         let signature = Signature::new(&data, sender_key);
         Transaction::new(data, signature)
     }
@@ -499,6 +514,7 @@ impl<'a> SuiTestAdapter<'a> {
             transaction_dependencies,
             &self.vm,
             &self.native_functions,
+            &ProtocolConfig::get_for_max_version(),
             gas_status,
             // TODO: Support different epochs in transactional tests.
             0,
@@ -0,0 +1,372 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Proptest strategies for the core types, for property tests to generate the values they check
+//! rather than build them by hand. Enabled in other crates by the `test-utils` feature.
+//!
+//! The values are realistic rather than arbitrary bytes: versions leave room to be incremented,
+//! objects are gas coins whose contents hold their id and version, transactions are the kinds
+//! clients send with budgets and prices in the usual ranges, and effects mutate their gas object.
+
+use move_core_types::identifier::Identifier;
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::base_types::{
+    ChainId, ObjectDigest, ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest,
+};
+use crate::gas::GasCostSummary;
+use crate::gas_coin::GasCoin;
+use crate::messages::{
+    CallArg, ExecutionFailureStatus, ExecutionStatus, ObjectArg, TransactionData,
+    TransactionEffects,
+};
+use crate::object::{MoveObject, Object, Owner};
+
+#[cfg(test)]
+#[path = "unit_tests/arbitrary_tests.rs"]
+mod arbitrary_tests;
+
+/// Versions stay far from the maximum, so that the objects can be mutated many times over.
+const MAX_VERSION: u64 = 1 << 32;
+const MAX_GAS_BUDGET: u64 = 100_000;
+const MAX_COIN_VALUE: u64 = 1_000_000_000;
+
+impl Arbitrary for ObjectID {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<[u8; ObjectID::LENGTH]>()
+            .prop_map(ObjectID::new)
+            .boxed()
+    }
+}
+
+impl Arbitrary for SuiAddress {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<ObjectID>().prop_map(SuiAddress::from).boxed()
+    }
+}
+
+impl Arbitrary for SequenceNumber {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0..MAX_VERSION).prop_map(SequenceNumber::from_u64).boxed()
+    }
+}
+
+impl Arbitrary for ObjectDigest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<[u8; 32]>().prop_map(ObjectDigest::new).boxed()
+    }
+}
+
+impl Arbitrary for TransactionDigest {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<[u8; 32]>().prop_map(TransactionDigest::new).boxed()
+    }
+}
+
+impl Arbitrary for ChainId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<[u8; 32]>().prop_map(ChainId).boxed()
+    }
+}
+
+/// Owners are mostly addresses, as most objects are.
+impl Arbitrary for Owner {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop_oneof![
+            4 => any::<SuiAddress>().prop_map(Owner::AddressOwner),
+            1 => any::<ObjectID>().prop_map(|id| Owner::ObjectOwner(id.into())),
+            1 => Just(Owner::Shared),
+            1 => Just(Owner::Immutable),
+        ]
+        .boxed()
+    }
+}
+
+pub fn object_ref_strategy() -> impl Strategy<Value = ObjectRef> + Clone {
+    (
+        any::<ObjectID>(),
+        any::<SequenceNumber>(),
+        any::<ObjectDigest>(),
+    )
+}
+
+/// Gas coins owned by `owner`.
+pub fn gas_coin_strategy(owner: impl Strategy<Value = Owner>) -> impl Strategy<Value = Object> {
+    (
+        any::<ObjectID>(),
+        any::<SequenceNumber>(),
+        0..MAX_COIN_VALUE,
+        owner,
+        any::<TransactionDigest>(),
+        0..1000u64,
+    )
+        .prop_map(
+            |(id, version, value, owner, previous_transaction, storage_rebate)| {
+                let coin =
+                    MoveObject::new_gas_coin(GasCoin::new(id, version, value).to_bcs_bytes());
+                let mut object = Object::new_move(coin, owner, previous_transaction);
+                object.storage_rebate = storage_rebate;
+                object
+            },
+        )
+}
+
+impl Arbitrary for Object {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        gas_coin_strategy(any::<Owner>()).boxed()
+    }
+}
+
+pub fn identifier_strategy() -> impl Strategy<Value = Identifier> {
+    "[a-z][a-z0-9_]{0,15}".prop_map(|name| Identifier::new(name).unwrap())
+}
+
+pub fn call_arg_strategy() -> impl Strategy<Value = CallArg> {
+    prop_oneof![
+        vec(any::<u8>(), 0..32).prop_map(CallArg::Pure),
+        object_ref_strategy()
+            .prop_map(|object_ref| { CallArg::Object(ObjectArg::ImmOrOwnedObject(object_ref)) }),
+        any::<ObjectID>().prop_map(|id| CallArg::Object(ObjectArg::SharedObject(id))),
+    ]
+}
+
+/// Transfers of objects and of SUI, payments and Move calls.
+impl Arbitrary for TransactionData {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let sender = any::<SuiAddress>();
+        let gas_payment = object_ref_strategy();
+        let gas_budget = 1..MAX_GAS_BUDGET;
+        let gas_price = 1..10u64;
+        let chain_id = any::<ChainId>();
+        let transfer = (
+            any::<SuiAddress>(),
+            object_ref_strategy(),
+            sender.clone(),
+            gas_payment.clone(),
+            gas_budget.clone(),
+            chain_id.clone(),
+        )
+            .prop_map(
+                |(recipient, object_ref, sender, gas_payment, gas_budget, chain_id)| {
+                    TransactionData::new_transfer(
+                        recipient,
+                        object_ref,
+                        sender,
+                        gas_payment,
+                        gas_budget,
+                        chain_id,
+                    )
+                },
+            );
+        let transfer_sui = (
+            any::<SuiAddress>(),
+            sender.clone(),
+            proptest::option::of(0..MAX_COIN_VALUE),
+            gas_payment.clone(),
+            gas_budget.clone(),
+            chain_id.clone(),
+        )
+            .prop_map(
+                |(recipient, sender, amount, gas_payment, gas_budget, chain_id)| {
+                    TransactionData::new_transfer_sui(
+                        recipient,
+                        sender,
+                        amount,
+                        gas_payment,
+                        gas_budget,
+                        chain_id,
+                    )
+                },
+            );
+        let pay = (
+            sender.clone(),
+            vec(object_ref_strategy(), 1..4),
+            vec((any::<SuiAddress>(), 0..MAX_COIN_VALUE), 1..4),
+            gas_payment.clone(),
+            gas_budget.clone(),
+            chain_id.clone(),
+        )
+            .prop_map(
+                |(sender, coins, payments, gas_payment, gas_budget, chain_id)| {
+                    let (recipients, amounts) = payments.into_iter().unzip();
+                    TransactionData::new_pay(
+                        sender,
+                        coins,
+                        recipients,
+                        amounts,
+                        gas_payment,
+                        gas_budget,
+                        chain_id,
+                    )
+                },
+            );
+        let move_call = (
+            sender,
+            object_ref_strategy(),
+            identifier_strategy(),
+            identifier_strategy(),
+            gas_payment,
+            vec(call_arg_strategy(), 0..4),
+            gas_budget,
+            chain_id,
+        )
+            .prop_map(
+                |(
+                    sender,
+                    package,
+                    module,
+                    function,
+                    gas_payment,
+                    arguments,
+                    gas_budget,
+                    chain_id,
+                )| {
+                    TransactionData::new_move_call(
+                        sender,
+                        package,
+                        module,
+                        function,
+                        vec![],
+                        gas_payment,
+                        arguments,
+                        gas_budget,
+                        chain_id,
+                    )
+                },
+            );
+        (
+            prop_oneof![transfer, transfer_sui, pay, move_call],
+            gas_price,
+        )
+            .prop_map(|(mut data, gas_price)| {
+                data.gas_price = gas_price;
+                data
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for GasCostSummary {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (0..MAX_GAS_BUDGET, 0..MAX_GAS_BUDGET, 0..MAX_GAS_BUDGET)
+            .prop_map(
+                |(computation_cost, storage_cost, storage_rebate)| GasCostSummary {
+                    computation_cost,
+                    storage_cost,
+                    storage_rebate,
+                },
+            )
+            .boxed()
+    }
+}
+
+/// Mostly successes, failures are among the errors of transfers and gas.
+impl Arbitrary for ExecutionStatus {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop_oneof![
+            4 => Just(ExecutionStatus::Success),
+            1 => prop_oneof![
+                Just(ExecutionFailureStatus::InsufficientGas),
+                Just(ExecutionFailureStatus::InvalidGasObject),
+                Just(ExecutionFailureStatus::InvalidTransferObject),
+                Just(ExecutionFailureStatus::InvalidTransferSuiInsufficientBalance),
+                Just(ExecutionFailureStatus::InvalidPayInsufficientBalance),
+            ]
+            .prop_map(ExecutionStatus::new_failure),
+        ]
+        .boxed()
+    }
+}
+
+fn owned_object_ref_strategy() -> impl Strategy<Value = (ObjectRef, Owner)> {
+    (object_ref_strategy(), any::<Owner>())
+}
+
+/// Effects without events, whose gas object is owned by an address and among the mutated
+/// objects.
+impl Arbitrary for TransactionEffects {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let objects = (
+            vec(owned_object_ref_strategy(), 0..4),
+            vec(owned_object_ref_strategy(), 0..4),
+            vec(owned_object_ref_strategy(), 0..2),
+            vec(object_ref_strategy(), 0..4),
+            vec(object_ref_strategy(), 0..2),
+        );
+        (
+            any::<ExecutionStatus>(),
+            any::<GasCostSummary>(),
+            vec(object_ref_strategy(), 0..2),
+            any::<TransactionDigest>(),
+            objects,
+            (object_ref_strategy(), any::<SuiAddress>()),
+            vec(any::<TransactionDigest>(), 0..4),
+        )
+            .prop_map(
+                |(
+                    status,
+                    gas_used,
+                    shared_objects,
+                    transaction_digest,
+                    (created, mut mutated, unwrapped, deleted, wrapped),
+                    (gas_object_ref, gas_owner),
+                    dependencies,
+                )| {
+                    let gas_object = (gas_object_ref, Owner::AddressOwner(gas_owner));
+                    mutated.push(gas_object);
+                    TransactionEffects {
+                        status,
+                        gas_used,
+                        shared_objects,
+                        transaction_digest,
+                        created,
+                        mutated,
+                        unwrapped,
+                        deleted,
+                        wrapped,
+                        gas_object,
+                        events: vec![],
+                        dependencies,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
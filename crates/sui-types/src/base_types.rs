@@ -137,6 +137,12 @@ impl SuiAddress {
         Self(random_bytes)
     }
 
+    /// An address that is the same on every run for the same `seed`, and differs across seeds.
+    pub fn deterministic_for_testing(seed: u64) -> Self {
+        let bytes = bytes_for_testing(b"SuiAddress", seed);
+        Self(bytes[..SUI_ADDRESS_LENGTH].try_into().unwrap())
+    }
+
     pub fn optional_address_as_hex<S>(
         key: &Option<SuiAddress>,
         serializer: S,
@@ -267,6 +273,28 @@ impl TransactionEffectsDigest {
     }
 }
 
+/// Identifies a Sui network, as the canonical digest of its genesis.
+#[serde_as]
+#[derive(Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct ChainId(
+    #[schemars(with = "Base64")]
+    #[serde_as(as = "Readable<Base64, Bytes>")]
+    pub [u8; 32],
+);
+
+impl ChainId {
+    /// The chain started from the genesis with the given canonical encoding, the one whose
+    /// objects are ordered by ID and validators by public key.
+    pub fn from_genesis_bytes(bytes: &[u8]) -> Self {
+        Self(Sha3_256::digest(bytes).into())
+    }
+
+    /// A chain that is the same on every run for the same `seed`, and differs across seeds.
+    pub fn deterministic_for_testing(seed: u64) -> Self {
+        Self(bytes_for_testing(b"ChainId", seed))
+    }
+}
+
 #[derive(
     Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Hash, Serialize, Deserialize, JsonSchema, Debug,
 )]
@@ -340,6 +368,11 @@ impl TxContext {
         id
     }
 
+    /// Number of `ObjectID`'s generated so far during execution of the current transaction
+    pub fn ids_created(&self) -> u64 {
+        self.ids_created
+    }
+
     /// Return the transaction digest, to include in new objects
     pub fn digest(&self) -> TransactionDigest {
         TransactionDigest::new(self.digest.clone().try_into().unwrap())
@@ -377,6 +410,16 @@ impl TxContext {
         )
     }
 
+    /// A context of the first epoch, with the sender and digest given by `seed`, so that the
+    /// IDs it generates are the same on every run.
+    pub fn deterministic_for_testing(seed: u64) -> Self {
+        Self::new(
+            &SuiAddress::deterministic_for_testing(seed),
+            &TransactionDigest::deterministic_for_testing(seed),
+            0,
+        )
+    }
+
     /// A function that lists all IDs created by this TXContext
     pub fn recreate_all_ids(&self) -> HashSet<ObjectID> {
         (0..self.ids_created)
@@ -418,6 +461,11 @@ impl TransactionDigest {
         Self::new(random_bytes)
     }
 
+    /// A digest that is the same on every run for the same `seed`, and differs across seeds.
+    pub fn deterministic_for_testing(seed: u64) -> Self {
+        Self::new(bytes_for_testing(b"TransactionDigest", seed))
+    }
+
     /// Translates digest into a Vec of bytes
     pub fn to_bytes(&self) -> Vec<u8> {
         self.0.to_vec()
@@ -552,6 +600,15 @@ pub fn dbg_object_id(name: u8) -> ObjectID {
     ObjectID::from_bytes([name; ObjectID::LENGTH]).unwrap()
 }
 
+/// The bytes of the `deterministic_for_testing` values of a type, hashed from its name and the
+/// seed so that values of different types or seeds do not share bytes.
+fn bytes_for_testing(kind: &[u8], seed: u64) -> [u8; 32] {
+    let mut hasher = Sha3_256::default();
+    hasher.update(kind);
+    hasher.update(seed.to_le_bytes());
+    hasher.finalize().into()
+}
+
 impl std::fmt::Debug for ObjectDigest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         let s = hex::encode(&self.0);
@@ -593,6 +650,18 @@ impl std::fmt::Debug for TransactionEffectsDigest {
     }
 }
 
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", base64ct::Base64::encode_string(&self.0))
+    }
+}
+
+impl fmt::Debug for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
 // TODO: rename to version
 impl SequenceNumber {
     pub const MIN: SequenceNumber = SequenceNumber(u64::MIN);
@@ -682,6 +751,12 @@ impl ObjectID {
         ObjectID::new(buf)
     }
 
+    /// An ID that is the same on every run for the same `seed`, and differs across seeds.
+    pub fn deterministic_for_testing(seed: u64) -> Self {
+        let bytes = bytes_for_testing(b"ObjectID", seed);
+        ObjectID::new(bytes[..Self::LENGTH].try_into().unwrap())
+    }
+
     pub const fn from_single_byte(byte: u8) -> ObjectID {
         let mut bytes = [0u8; Self::LENGTH];
         bytes[Self::LENGTH - 1] = byte;
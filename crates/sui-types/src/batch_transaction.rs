@@ -0,0 +1,140 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::TypeTag;
+
+use crate::base_types::{ChainId, ObjectRef, SuiAddress};
+use crate::coin;
+use crate::error::{SuiError, SuiResult};
+use crate::messages::{
+    CallArg, CommandResult, MoveCall, MoveModulePublish, ObjectArg, SingleTransactionKind,
+    TransactionData, TransactionKind, TransferObject,
+};
+
+/// Builds a batch transaction whose single transactions can use the objects created by the
+/// transactions before them. The whole batch is executed atomically with one gas payment.
+///
+/// ```ignore
+/// let mut builder = BatchTransactionBuilder::new();
+/// let split = builder.split_coin(framework, coin, coin_type.clone(), vec![100]);
+/// builder.transfer_coin(framework, builder.result(split, 0), coin_type, recipient);
+/// let data = builder.finish(sender, gas, gas_budget, chain_id)?;
+/// ```
+#[derive(Debug, Default)]
+pub struct BatchTransactionBuilder {
+    transactions: Vec<SingleTransactionKind>,
+}
+
+impl BatchTransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `index`-th object created by the transaction at position `command`, to be used as
+    /// an argument of a later transaction.
+    pub fn result(&self, command: u16, index: u16) -> CallArg {
+        CallArg::Object(ObjectArg::Result(CommandResult::new(command, index)))
+    }
+
+    /// Append a Move call, returning its position in the batch.
+    pub fn move_call(
+        &mut self,
+        package: ObjectRef,
+        module: Identifier,
+        function: Identifier,
+        type_arguments: Vec<TypeTag>,
+        arguments: Vec<CallArg>,
+    ) -> u16 {
+        self.push(SingleTransactionKind::Call(MoveCall {
+            package,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        }))
+    }
+
+    /// Append a transfer of an existing object, returning its position in the batch.
+    pub fn transfer_object(&mut self, object_ref: ObjectRef, recipient: SuiAddress) -> u16 {
+        self.push(SingleTransactionKind::TransferObject(TransferObject {
+            recipient,
+            object_ref,
+        }))
+    }
+
+    /// Append a publish of the given modules, returning its position in the batch.
+    pub fn publish(&mut self, modules: Vec<Vec<u8>>) -> u16 {
+        self.push(SingleTransactionKind::Publish(MoveModulePublish {
+            modules,
+        }))
+    }
+
+    /// Split `coin` into new coins of the given amounts. The new coins are the results of
+    /// the returned transaction, in the order of `split_amounts`.
+    pub fn split_coin(
+        &mut self,
+        framework: ObjectRef,
+        coin: CallArg,
+        coin_type: TypeTag,
+        split_amounts: Vec<u64>,
+    ) -> u16 {
+        self.move_call(
+            framework,
+            coin::COIN_MODULE_NAME.to_owned(),
+            coin::COIN_SPLIT_VEC_FUNC_NAME.to_owned(),
+            vec![coin_type],
+            vec![coin, CallArg::Pure(bcs::to_bytes(&split_amounts).unwrap())],
+        )
+    }
+
+    /// Transfer `coin`, which can be the result of an earlier transaction, to `recipient`.
+    pub fn transfer_coin(
+        &mut self,
+        framework: ObjectRef,
+        coin: CallArg,
+        coin_type: TypeTag,
+        recipient: SuiAddress,
+    ) -> u16 {
+        self.move_call(
+            framework,
+            coin::COIN_MODULE_NAME.to_owned(),
+            coin::COIN_TRANSFER_FUNC_NAME.to_owned(),
+            vec![coin_type],
+            vec![
+                coin,
+                CallArg::Pure(bcs::to_bytes(&AccountAddress::from(recipient)).unwrap()),
+            ],
+        )
+    }
+
+    pub fn finish(
+        self,
+        sender: SuiAddress,
+        gas_payment: ObjectRef,
+        gas_budget: u64,
+        chain_id: ChainId,
+    ) -> SuiResult<TransactionData> {
+        fp_ensure!(
+            !self.transactions.is_empty(),
+            SuiError::InvalidBatchTransaction {
+                error: "Batch Transaction cannot be empty".to_owned(),
+            }
+        );
+        let kind = TransactionKind::Batch(self.transactions);
+        kind.check_command_results()?;
+        Ok(TransactionData::new(
+            kind,
+            sender,
+            gas_payment,
+            gas_budget,
+            chain_id,
+        ))
+    }
+
+    fn push(&mut self, transaction: SingleTransactionKind) -> u16 {
+        self.transactions.push(transaction);
+        (self.transactions.len() - 1) as u16
+    }
+}
@@ -120,6 +120,10 @@ impl Committee {
         self.epoch
     }
 
+    pub fn names(&self) -> impl Iterator<Item = &AuthorityName> {
+        self.voting_rights.iter().map(|(name, _)| name)
+    }
+
     pub fn public_key(&self, authority: &AuthorityName) -> SuiResult<PublicKey> {
         match self.expanded_keys.get(authority) {
             // TODO: Check if this is unnecessary copying.
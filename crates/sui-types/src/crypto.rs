@@ -638,6 +638,8 @@ mod bcs_signable {
     impl BcsSignable for crate::messages::TransactionData {}
     impl BcsSignable for crate::messages::EpochInfo {}
     impl BcsSignable for crate::object::Object {}
+    impl BcsSignable for crate::intent::PersonalMessage {}
+    impl BcsSignable for crate::discovery::PeerInfo {}
 
     impl BcsSignable for super::bcs_signable_test::Foo {}
     #[cfg(test)]
@@ -0,0 +1,51 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::SuiAddress;
+use crate::crypto::{PublicKeyBytes, Signature};
+use crate::error::SuiResult;
+
+/// The address at which a node can be reached, as announced by the node itself.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    pub public_key: PublicKeyBytes,
+    /// The binary encoding of the multiaddr of the node.
+    pub address: Vec<u8>,
+    /// When the record was made, in milliseconds since the Unix epoch. Newer records of a peer
+    /// replace the older ones.
+    pub timestamp_ms: u64,
+}
+
+/// A peer record signed by the key of the peer, so that nodes can gossip the records of others
+/// without being able to forge them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedPeerInfo {
+    pub info: PeerInfo,
+    pub signature: Signature,
+}
+
+impl SignedPeerInfo {
+    pub fn new(info: PeerInfo, secret: &dyn signature::Signer<Signature>) -> Self {
+        let signature = Signature::new(&info, secret);
+        Self { info, signature }
+    }
+
+    pub fn verify(&self) -> SuiResult<()> {
+        let author = SuiAddress::from(&self.info.public_key);
+        self.signature.verify(&self.info, author)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeersRequest {
+    /// The record of the caller, for nodes which can be reached by others.
+    pub sender: Option<SignedPeerInfo>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeersResponse {
+    /// The record of the node answering, if any, followed by the records of some of its peers.
+    pub peers: Vec<SignedPeerInfo>,
+}
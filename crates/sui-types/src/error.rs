@@ -373,6 +373,19 @@ pub enum SuiError {
     #[error("Inconsistent state detected during epoch change: {:?}", error)]
     InconsistentEpochState { error: String },
 
+    // Errors of the handshake opening the connections between nodes.
+    #[error("Peer is on chain {actual}, expected chain {expected}")]
+    WrongChain { expected: ChainId, actual: ChainId },
+    #[error(
+        "Peer speaks protocol versions {actual_min} to {actual_max}, expected a version from {expected_min} to {expected_max}"
+    )]
+    UnsupportedProtocolVersion {
+        expected_min: u32,
+        expected_max: u32,
+        actual_min: u32,
+        actual_max: u32,
+    },
+
     // These are errors that occur when an RPC fails and is simply the utf8 message sent in a
     // Tonic::Status
     #[error("{0}")]
@@ -0,0 +1,53 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::ChainId;
+use crate::error::{SuiError, SuiResult};
+
+/// The version of the messages exchanged between nodes, bumped on every incompatible change.
+/// Version 2 adds Pay transactions, publishing packages in batches and the results of earlier
+/// transactions of a batch as arguments, see [`crate::protocol_config::ProtocolConfig`].
+pub const PROTOCOL_VERSION: u32 = 2;
+/// The oldest version of the messages nodes still understand.
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+
+/// What nodes tell each other when opening a connection, so that peers of another network or
+/// speaking another protocol are turned away before any of their messages is misread.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Handshake {
+    pub chain_id: ChainId,
+    pub min_protocol_version: u32,
+    pub max_protocol_version: u32,
+}
+
+impl Handshake {
+    pub fn new(chain_id: ChainId) -> Self {
+        Self {
+            chain_id,
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+        }
+    }
+
+    /// The version to speak with the peer which sent `peer`, the latest both support.
+    pub fn negotiate(&self, peer: &Handshake) -> SuiResult<u32> {
+        if peer.chain_id != self.chain_id {
+            return Err(SuiError::WrongChain {
+                expected: self.chain_id,
+                actual: peer.chain_id,
+            });
+        }
+        let version = self.max_protocol_version.min(peer.max_protocol_version);
+        if version < self.min_protocol_version.max(peer.min_protocol_version) {
+            return Err(SuiError::UnsupportedProtocolVersion {
+                expected_min: self.min_protocol_version,
+                expected_max: self.max_protocol_version,
+                actual_min: peer.min_protocol_version,
+                actual_max: peer.max_protocol_version,
+            });
+        }
+        Ok(version)
+    }
+}
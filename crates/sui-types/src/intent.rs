@@ -0,0 +1,49 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+/// What a signature requested from a wallet by another application authorizes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IntentScope {
+    /// The signature authorizes the execution of a transaction.
+    TransactionData,
+    /// The signature only attests that the signer saw a message, it cannot authorize
+    /// a transaction.
+    PersonalMessage,
+}
+
+impl Display for IntentScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntentScope::TransactionData => write!(f, "transaction-data"),
+            IntentScope::PersonalMessage => write!(f, "personal-message"),
+        }
+    }
+}
+
+impl FromStr for IntentScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transaction-data" => Ok(IntentScope::TransactionData),
+            "personal-message" => Ok(IntentScope::PersonalMessage),
+            _ => Err(anyhow!(
+                "Unknown intent scope {s}, expected transaction-data or personal-message"
+            )),
+        }
+    }
+}
+
+/// A message signed on behalf of an application. Its signable bytes are prefixed with its type
+/// name, like transaction data, so the signature of a message can never be a valid signature
+/// of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonalMessage {
+    pub message: Vec<u8>,
+}
@@ -13,24 +13,34 @@ use move_core_types::account_address::AccountAddress;
 #[macro_use]
 pub mod error;
 
+#[cfg(any(test, feature = "test-utils"))]
+pub mod arbitrary;
 pub mod balance;
 pub mod base_types;
 pub mod batch;
+pub mod batch_transaction;
 pub mod coin;
 pub mod committee;
 pub mod crypto;
+pub mod discovery;
 pub mod event;
 pub mod gas;
 pub mod gas_coin;
+pub mod handshake;
 pub mod id;
+pub mod intent;
 pub mod messages;
 pub mod messages_checkpoint;
 pub mod move_package;
 pub mod object;
+pub mod object_change;
+pub mod protocol_config;
 pub mod signature_seed;
 pub mod storage;
 pub mod sui_serde;
 pub mod sui_system_state;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 pub mod waypoint;
 
 pub mod event_filter;
@@ -57,6 +57,53 @@ pub enum ObjectArg {
     ImmOrOwnedObject(ObjectRef),
     // A Move object that's shared and mutable.
     SharedObject(ObjectID),
+    // A Move object created by an earlier transaction of the same batch.
+    Result(CommandResult),
+}
+
+/// Refers to the `index`-th object created by the `command`-th single transaction of a batch,
+/// so that the output of one transaction can be fed into the next one.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct CommandResult {
+    pub command: u16,
+    pub index: u16,
+}
+
+impl CommandResult {
+    /// Prefix of the string form of a command result, e.g. `result:0:1` for the second
+    /// object created by the first transaction of a batch.
+    pub const PREFIX: &'static str = "result:";
+
+    pub fn new(command: u16, index: u16) -> Self {
+        Self { command, index }
+    }
+}
+
+impl Display for CommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}:{}", Self::PREFIX, self.command, self.index)
+    }
+}
+
+impl std::str::FromStr for CommandResult {
+    type Err = SuiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SuiError::InvalidBatchTransaction {
+            error: format!(
+                "Invalid command result {s}, expected {}<command>:<index>",
+                Self::PREFIX
+            ),
+        };
+        let (command, index) = s
+            .strip_prefix(Self::PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            command: command.parse().map_err(|_| invalid())?,
+            index: index.parse().map_err(|_| invalid())?,
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -92,6 +139,17 @@ pub struct TransferSui {
     pub amount: Option<u64>,
 }
 
+/// Pay `amounts` to `recipients` out of `coins`, which must all have the same coin type.
+/// The coins are merged into the first one, which keeps the remaining balance, or is deleted
+/// if nothing remains.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+pub struct Pay {
+    pub coins: Vec<ObjectRef>,
+    pub recipients: Vec<SuiAddress>,
+    /// The amount paid to each recipient, in the same order as `recipients`.
+    pub amounts: Vec<u64>,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct ChangeEpoch {
     /// The next (to become) epoch ID.
@@ -121,6 +179,8 @@ pub enum SingleTransactionKind {
     /// A validator will not sign a transaction of this kind from outside. It only
     /// signs internally during epoch changes.
     ChangeEpoch(ChangeEpoch),
+    /// Pay several recipients at once with coins of any type
+    Pay(Pay),
     // .. more transaction types go here
 }
 
@@ -133,7 +193,9 @@ impl SingleTransactionKind {
         match &self {
             Self::Call(MoveCall { arguments, .. }) => {
                 Either::Left(arguments.iter().filter_map(|arg| match arg {
-                    CallArg::Pure(_) | CallArg::Object(ObjectArg::ImmOrOwnedObject(_)) => None,
+                    CallArg::Pure(_)
+                    | CallArg::Object(ObjectArg::ImmOrOwnedObject(_))
+                    | CallArg::Object(ObjectArg::Result(_)) => None,
                     CallArg::Object(ObjectArg::SharedObject(id)) => Some(id),
                 }))
             }
@@ -148,6 +210,19 @@ impl SingleTransactionKind {
         }
     }
 
+    /// Return the results of earlier transactions of the batch used as arguments.
+    pub fn command_results(&self) -> impl Iterator<Item = &CommandResult> {
+        match &self {
+            Self::Call(MoveCall { arguments, .. }) => {
+                Either::Left(arguments.iter().filter_map(|arg| match arg {
+                    CallArg::Object(ObjectArg::Result(result)) => Some(result),
+                    _ => None,
+                }))
+            }
+            _ => Either::Right(std::iter::empty()),
+        }
+    }
+
     /// Return the metadata of each of the input objects for the transaction.
     /// For a Move object, we attach the object reference;
     /// for a Move package, we provide the object id only since they never change on chain.
@@ -169,6 +244,8 @@ impl SingleTransactionKind {
                     CallArg::Object(ObjectArg::SharedObject(id)) => {
                         Some(InputObjectKind::SharedMoveObject(*id))
                     }
+                    // Objects created within the batch are not inputs of the transaction.
+                    CallArg::Object(ObjectArg::Result(_)) => None,
                 })
                 .chain([InputObjectKind::MovePackage(package.0)])
                 .collect(),
@@ -193,6 +270,10 @@ impl SingleTransactionKind {
             Self::TransferSui(_) => {
                 vec![]
             }
+            Self::Pay(Pay { coins, .. }) => coins
+                .iter()
+                .map(|object_ref| InputObjectKind::ImmOrOwnedMoveObject(*object_ref))
+                .collect(),
             Self::ChangeEpoch(_) => {
                 vec![InputObjectKind::SharedMoveObject(
                     SUI_SYSTEM_STATE_OBJECT_ID,
@@ -235,6 +316,17 @@ impl Display for SingleTransactionKind {
                     writeln!(writer, "Amount: Full Balance")?;
                 }
             }
+            Self::Pay(p) => {
+                writeln!(writer, "Transaction Kind : Pay")?;
+                writeln!(writer, "Coins : ")?;
+                for (object_id, seq, _) in &p.coins {
+                    writeln!(writer, "{}::{:?}", object_id, seq)?;
+                }
+                writeln!(writer, "Recipients : ")?;
+                for (recipient, amount) in p.recipients.iter().zip(&p.amounts) {
+                    writeln!(writer, "{}: {}", recipient, amount)?;
+                }
+            }
             Self::Publish(_p) => {
                 writeln!(writer, "Transaction Kind : Publish")?;
             }
@@ -296,6 +388,27 @@ impl TransactionKind {
             TransactionKind::Single(SingleTransactionKind::ChangeEpoch(_))
         )
     }
+
+    /// Check that every command result used as an argument refers to an earlier
+    /// transaction of the same batch, and is used at most once by each transaction.
+    pub fn check_command_results(&self) -> SuiResult {
+        for (position, kind) in self.single_transactions().enumerate() {
+            let mut used = HashSet::new();
+            for result in kind.command_results() {
+                fp_ensure!(used.insert(result), SuiError::DuplicateObjectRefInput);
+                fp_ensure!(
+                    (result.command as usize) < position,
+                    SuiError::InvalidBatchTransaction {
+                        error: format!(
+                            "Transaction {position} of the batch uses {result}, which does not \
+                            refer to an earlier transaction"
+                        ),
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Display for TransactionKind {
@@ -324,6 +437,9 @@ pub struct TransactionData {
     gas_payment: ObjectRef,
     pub gas_price: u64,
     pub gas_budget: u64,
+    /// The only chain the transaction executes on. Its signature signs the chain too, so that
+    /// it cannot be replayed on another network.
+    chain_id: ChainId,
 }
 
 impl TransactionData {
@@ -332,6 +448,7 @@ impl TransactionData {
         sender: SuiAddress,
         gas_payment: ObjectRef,
         gas_budget: u64,
+        chain_id: ChainId,
     ) -> Self {
         TransactionData {
             kind,
@@ -339,6 +456,7 @@ impl TransactionData {
             gas_price: 1,
             gas_payment,
             gas_budget,
+            chain_id,
         }
     }
 
@@ -348,6 +466,7 @@ impl TransactionData {
         gas_payment: ObjectRef,
         gas_budget: u64,
         gas_price: u64,
+        chain_id: ChainId,
     ) -> Self {
         TransactionData {
             kind,
@@ -355,9 +474,26 @@ impl TransactionData {
             gas_price,
             gas_payment,
             gas_budget,
+            chain_id,
         }
     }
 
+    pub fn chain_id(&self) -> ChainId {
+        self.chain_id
+    }
+
+    /// Check that the transaction executes on the chain `chain_id`.
+    pub fn check_chain_id(&self, chain_id: ChainId) -> SuiResult {
+        fp_ensure!(
+            self.chain_id == chain_id,
+            SuiError::WrongChain {
+                expected: chain_id,
+                actual: self.chain_id,
+            }
+        );
+        Ok(())
+    }
+
     pub fn new_move_call(
         sender: SuiAddress,
         package: ObjectRef,
@@ -367,6 +503,7 @@ impl TransactionData {
         gas_payment: ObjectRef,
         arguments: Vec<CallArg>,
         gas_budget: u64,
+        chain_id: ChainId,
     ) -> Self {
         let kind = TransactionKind::Single(SingleTransactionKind::Call(MoveCall {
             package,
@@ -375,7 +512,7 @@ impl TransactionData {
             type_arguments,
             arguments,
         }));
-        Self::new(kind, sender, gas_payment, gas_budget)
+        Self::new(kind, sender, gas_payment, gas_budget, chain_id)
     }
 
     pub fn new_transfer(
@@ -384,12 +521,13 @@ impl TransactionData {
         sender: SuiAddress,
         gas_payment: ObjectRef,
         gas_budget: u64,
+        chain_id: ChainId,
     ) -> Self {
         let kind = TransactionKind::Single(SingleTransactionKind::TransferObject(TransferObject {
             recipient,
             object_ref,
         }));
-        Self::new(kind, sender, gas_payment, gas_budget)
+        Self::new(kind, sender, gas_payment, gas_budget, chain_id)
     }
 
     pub fn new_transfer_sui(
@@ -398,12 +536,30 @@ impl TransactionData {
         amount: Option<u64>,
         gas_payment: ObjectRef,
         gas_budget: u64,
+        chain_id: ChainId,
     ) -> Self {
         let kind = TransactionKind::Single(SingleTransactionKind::TransferSui(TransferSui {
             recipient,
             amount,
         }));
-        Self::new(kind, sender, gas_payment, gas_budget)
+        Self::new(kind, sender, gas_payment, gas_budget, chain_id)
+    }
+
+    pub fn new_pay(
+        sender: SuiAddress,
+        coins: Vec<ObjectRef>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas_payment: ObjectRef,
+        gas_budget: u64,
+        chain_id: ChainId,
+    ) -> Self {
+        let kind = TransactionKind::Single(SingleTransactionKind::Pay(Pay {
+            coins,
+            recipients,
+            amounts,
+        }));
+        Self::new(kind, sender, gas_payment, gas_budget, chain_id)
     }
 
     pub fn new_module(
@@ -411,11 +567,12 @@ impl TransactionData {
         gas_payment: ObjectRef,
         modules: Vec<Vec<u8>>,
         gas_budget: u64,
+        chain_id: ChainId,
     ) -> Self {
         let kind = TransactionKind::Single(SingleTransactionKind::Publish(MoveModulePublish {
             modules,
         }));
-        Self::new(kind, sender, gas_payment, gas_budget)
+        Self::new(kind, sender, gas_payment, gas_budget, chain_id)
     }
 
     /// Returns the transaction kind as a &str (variant name, no fields)
@@ -448,20 +605,7 @@ impl TransactionData {
     pub fn move_calls(&self) -> SuiResult<Vec<&MoveCall>> {
         let move_calls = match &self.kind {
             TransactionKind::Single(s) => s.move_call().into_iter().collect(),
-            TransactionKind::Batch(b) => {
-                let mut result = vec![];
-                for kind in b {
-                    fp_ensure!(
-                        !matches!(kind, &SingleTransactionKind::Publish(..)),
-                        SuiError::InvalidBatchTransaction {
-                            error: "Publish transaction is not allowed in Batch Transaction"
-                                .to_owned(),
-                        }
-                    );
-                    result.extend(kind.move_call().into_iter());
-                }
-                result
-            }
+            TransactionKind::Batch(b) => b.iter().flat_map(|kind| kind.move_call()).collect(),
         };
         Ok(move_calls)
     }
@@ -472,19 +616,13 @@ impl TransactionData {
             TransactionKind::Batch(b) => {
                 let mut result = vec![];
                 for kind in b {
-                    fp_ensure!(
-                        !matches!(kind, &SingleTransactionKind::Publish(..)),
-                        SuiError::InvalidBatchTransaction {
-                            error: "Publish transaction is not allowed in Batch Transaction"
-                                .to_owned(),
-                        }
-                    );
                     let sub = kind.input_objects()?;
                     result.extend(sub);
                 }
                 result
             }
         };
+        self.kind.check_command_results()?;
         if !self.kind.is_system_tx() {
             inputs.push(InputObjectKind::ImmOrOwnedMoveObject(
                 *self.gas_payment_object_ref(),
@@ -524,6 +662,21 @@ pub struct TransactionEnvelope<S> {
 }
 
 impl<S> TransactionEnvelope<S> {
+    /// An envelope received from the network, whose signatures are not verified yet.
+    pub fn new_from_parts(
+        data: TransactionData,
+        tx_signature: Signature,
+        auth_sign_info: S,
+    ) -> Self {
+        Self {
+            transaction_digest: OnceCell::new(),
+            is_verified: false,
+            data,
+            tx_signature,
+            auth_sign_info,
+        }
+    }
+
     fn add_sender_sig_to_verification_obligation(
         &self,
         obligation: &mut VerificationObligation<AggregateAccountSignature>,
@@ -724,6 +877,7 @@ impl SignedTransaction {
         computation_charge: u64,
         authority: AuthorityName,
         secret: &dyn signature::Signer<AuthoritySignature>,
+        chain_id: ChainId,
     ) -> Self {
         let kind = TransactionKind::Single(SingleTransactionKind::ChangeEpoch(ChangeEpoch {
             epoch: next_epoch,
@@ -736,6 +890,7 @@ impl SignedTransaction {
             SuiAddress::default(),
             (ObjectID::ZERO, SequenceNumber::default(), ObjectDigest::MIN),
             0,
+            chain_id,
         );
         let signature = AuthoritySignature::new(&data, secret);
         Self {
@@ -1000,6 +1155,18 @@ pub enum ExecutionFailureStatus {
     MoveAbort(ModuleId, u64), // TODO func def + offset?
     VMVerificationOrDeserializationError,
     VMInvariantViolation,
+
+    //
+    // Pay errors
+    //
+    // Appended after the existing variants so their BCS indices stay stable.
+    InvalidPay,
+    InvalidPayInsufficientBalance,
+
+    //
+    // Limits, see ProtocolConfig
+    //
+    LimitExceeded(LimitExceeded),
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, Hash)]
@@ -1016,6 +1183,7 @@ pub enum EntryArgumentErrorKind {
     ObjectKindMismatch,
     UnsupportedPureArg,
     ArityMismatch,
+    InvalidCommandResult,
 }
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, Hash)]
@@ -1040,6 +1208,23 @@ pub struct InvalidSharedByValue {
     pub object: ObjectID,
 }
 
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, Hash)]
+pub struct LimitExceeded {
+    pub limit: ExecutionLimit,
+    pub max: u64,
+    pub actual: u64,
+}
+
+/// The resources a transaction is limited in.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Serialize, Deserialize, Hash)]
+pub enum ExecutionLimit {
+    CreatedObjects,
+    MutatedObjects,
+    Events,
+    EventSize,
+    TypeNesting,
+}
+
 impl ExecutionFailureStatus {
     pub fn entry_argument_error(argument_idx: LocalIndex, kind: EntryArgumentErrorKind) -> Self {
         EntryArgumentError { argument_idx, kind }.into()
@@ -1060,6 +1245,15 @@ impl ExecutionFailureStatus {
     pub fn invalid_shared_by_value(object: ObjectID) -> Self {
         InvalidSharedByValue { object }.into()
     }
+
+    pub fn limit_exceeded(limit: ExecutionLimit, max: usize, actual: usize) -> Self {
+        LimitExceeded {
+            limit,
+            max: max as u64,
+            actual: actual as u64,
+        }
+        .into()
+    }
 }
 
 impl std::fmt::Display for ExecutionFailureStatus {
@@ -1091,6 +1285,14 @@ impl std::fmt::Display for ExecutionFailureStatus {
             ExecutionFailureStatus::InvalidTransferSuiInsufficientBalance => {
                 write!(f, "Invalid Transfer SUI, Insufficient Balance.")
             }
+            ExecutionFailureStatus::InvalidPay => write!(
+                f,
+                "Invalid Pay. Possibly no coins, coins of different types, or not as many \
+                amounts as recipients."
+            ),
+            ExecutionFailureStatus::InvalidPayInsufficientBalance => {
+                write!(f, "Invalid Pay, Insufficient Balance.")
+            }
             ExecutionFailureStatus::NonEntryFunctionInvoked => write!(
                 f,
                 "Non Entry Function Invoked. Move Call must start with an entry function"
@@ -1148,6 +1350,9 @@ impl std::fmt::Display for ExecutionFailureStatus {
             ExecutionFailureStatus::VMInvariantViolation => {
                 write!(f, "MOVE VM INVARIANT VIOLATION.")
             }
+            ExecutionFailureStatus::LimitExceeded(data) => {
+                write!(f, "Limit Exceeded. {data}")
+            }
         }
     }
 }
@@ -1186,6 +1391,13 @@ impl Display for EntryArgumentErrorKind {
                     "Mismatch between the number of actual versus expected argument."
                 )
             }
+            EntryArgumentErrorKind::InvalidCommandResult => {
+                write!(
+                    f,
+                    "Command result does not refer to an available object created earlier \
+                    in the batch."
+                )
+            }
         }
     }
 }
@@ -1233,6 +1445,25 @@ impl Display for InvalidSharedByValue {
     }
 }
 
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let LimitExceeded { limit, max, actual } = self;
+        write!(f, "{limit} is {actual}, at most {max} is allowed.")
+    }
+}
+
+impl Display for ExecutionLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExecutionLimit::CreatedObjects => write!(f, "Number of created objects"),
+            ExecutionLimit::MutatedObjects => write!(f, "Number of mutated objects"),
+            ExecutionLimit::Events => write!(f, "Number of events"),
+            ExecutionLimit::EventSize => write!(f, "Size of an event in bytes"),
+            ExecutionLimit::TypeNesting => write!(f, "Nesting depth of a type"),
+        }
+    }
+}
+
 impl std::error::Error for ExecutionFailureStatus {}
 
 impl ExecutionStatus {
@@ -1297,6 +1528,12 @@ impl From<InvalidSharedByValue> for ExecutionFailureStatus {
     }
 }
 
+impl From<LimitExceeded> for ExecutionFailureStatus {
+    fn from(error: LimitExceeded) -> Self {
+        Self::LimitExceeded(error)
+    }
+}
+
 /// The response from processing a transaction or a certified transaction
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct TransactionEffects {
@@ -1452,6 +1689,15 @@ pub struct TransactionEffectsEnvelope<S> {
 }
 
 impl<S> TransactionEffectsEnvelope<S> {
+    /// An envelope received from the network, whose signature is not verified yet.
+    pub fn new_from_parts(effects: TransactionEffects, auth_signature: S) -> Self {
+        Self {
+            transaction_effects_digest: OnceCell::new(),
+            effects,
+            auth_signature,
+        }
+    }
+
     pub fn digest(&self) -> &TransactionEffectsDigest {
         self.transaction_effects_digest
             .get_or_init(|| self.effects.digest())
@@ -8,6 +8,7 @@ use crate::{
 use move_binary_format::access::ModuleAccess;
 use move_binary_format::binary_views::BinaryIndexedView;
 use move_binary_format::file_format::CompiledModule;
+use move_binary_format::normalized;
 use move_core_types::identifier::Identifier;
 use move_disassembler::disassembler::Disassembler;
 use move_ir_types::location::Spanned;
@@ -63,6 +64,29 @@ impl MovePackage {
     pub fn disassemble(&self) -> SuiResult<BTreeMap<String, Value>> {
         disassemble_modules(self.module_map.values())
     }
+
+    pub fn normalize(&self) -> SuiResult<BTreeMap<String, normalized::Module>> {
+        normalize_modules(self.module_map.values())
+    }
+}
+
+/// The normalized form of `modules`, keyed by module name: their structs and the signatures of
+/// the functions that can be called from outside of them.
+pub fn normalize_modules<'a, I>(modules: I) -> SuiResult<BTreeMap<String, normalized::Module>>
+where
+    I: Iterator<Item = &'a Vec<u8>>,
+{
+    let mut normalized_modules = BTreeMap::new();
+    for bytecode in modules {
+        let module = CompiledModule::deserialize(bytecode).map_err(|error| {
+            SuiError::ModuleDeserializationFailure {
+                error: error.to_string(),
+            }
+        })?;
+        let normalized_module = normalized::Module::new(&module);
+        normalized_modules.insert(normalized_module.name.to_string(), normalized_module);
+    }
+    Ok(normalized_modules)
 }
 
 pub fn disassemble_modules<'a, I>(modules: I) -> SuiResult<BTreeMap<String, Value>>
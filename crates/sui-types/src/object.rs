@@ -609,6 +609,16 @@ impl ObjectRead {
     }
 }
 
+impl ObjectFormatOptions {
+    pub fn new(include_types: bool) -> Self {
+        Self { include_types }
+    }
+
+    pub fn include_types(&self) -> bool {
+        self.include_types
+    }
+}
+
 impl Default for ObjectFormatOptions {
     fn default() -> Self {
         ObjectFormatOptions {
@@ -632,3 +642,36 @@ impl Display for ObjectRead {
         }
     }
 }
+
+/// The result of reading an object at a past version.
+#[derive(Debug)]
+pub enum PastObjectRead {
+    /// The object never existed.
+    ObjectNotExists(ObjectID),
+    /// The object was deleted or wrapped at this version, by the given transaction.
+    ObjectDeleted(ObjectRef, TransactionDigest),
+    /// The object at this version. The transaction that produced it is its previous
+    /// transaction.
+    VersionFound(ObjectRef, Object, Option<MoveStructLayout>),
+    /// The object exists but never had this version, or that version is no longer kept.
+    VersionNotFound(ObjectID, SequenceNumber),
+    /// The object is not at this version yet.
+    VersionTooHigh {
+        object_id: ObjectID,
+        asked_version: SequenceNumber,
+        latest_version: SequenceNumber,
+    },
+}
+
+impl PastObjectRead {
+    /// Returns the object at the version read if there is any, otherwise an Err.
+    pub fn into_object(self) -> Result<Object, SuiError> {
+        match self {
+            Self::ObjectDeleted(oref, _) => Err(SuiError::ObjectDeleted { object_ref: oref }),
+            Self::VersionFound(_, o, _) => Ok(o),
+            Self::ObjectNotExists(object_id)
+            | Self::VersionNotFound(object_id, _)
+            | Self::VersionTooHigh { object_id, .. } => Err(SuiError::ObjectNotFound { object_id }),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::base_types::{ObjectID, ObjectRef, TransactionDigest};
+use crate::event_filter::Filter;
+use crate::messages::TransactionEffects;
+use crate::object::Owner;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ObjectChangeKind {
+    /// The object has a new version, with the same owner.
+    Mutated,
+    /// The object has a new version and a new owner.
+    Transferred,
+    /// The object was taken out of another object.
+    Unwrapped,
+    /// The object was wrapped into another object.
+    Wrapped,
+    Deleted,
+}
+
+/// A change of an object made by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectChange {
+    pub timestamp_ms: u64,
+    pub tx_digest: TransactionDigest,
+    pub kind: ObjectChangeKind,
+    /// The new reference of the object, or its old reference for wrapped and deleted objects.
+    pub object_ref: ObjectRef,
+    /// The new owner of the object, `None` for wrapped and deleted objects.
+    pub owner: Option<Owner>,
+}
+
+impl ObjectChange {
+    /// The changes of the existing objects made by a transaction. `previous_owners` holds the
+    /// owners of the transaction's input objects before its execution, mutated objects missing
+    /// from it are assumed to keep their owner.
+    pub fn from_effects(
+        effects: &TransactionEffects,
+        previous_owners: &BTreeMap<ObjectID, Owner>,
+        timestamp_ms: u64,
+    ) -> Vec<Self> {
+        let change = |kind, object_ref: &ObjectRef, owner: Option<Owner>| ObjectChange {
+            timestamp_ms,
+            tx_digest: effects.transaction_digest,
+            kind,
+            object_ref: *object_ref,
+            owner,
+        };
+        let mutated = effects.mutated.iter().map(|(object_ref, owner)| {
+            let kind = match previous_owners.get(&object_ref.0) {
+                Some(previous_owner) if previous_owner != owner => ObjectChangeKind::Transferred,
+                _ => ObjectChangeKind::Mutated,
+            };
+            change(kind, object_ref, Some(*owner))
+        });
+        let unwrapped = effects.unwrapped.iter().map(|(object_ref, owner)| {
+            change(ObjectChangeKind::Unwrapped, object_ref, Some(*owner))
+        });
+        let wrapped = effects
+            .wrapped
+            .iter()
+            .map(|object_ref| change(ObjectChangeKind::Wrapped, object_ref, None));
+        let deleted = effects
+            .deleted
+            .iter()
+            .map(|object_ref| change(ObjectChangeKind::Deleted, object_ref, None));
+        mutated
+            .chain(unwrapped)
+            .chain(wrapped)
+            .chain(deleted)
+            .collect()
+    }
+
+    pub fn object_id(&self) -> ObjectID {
+        self.object_ref.0
+    }
+}
+
+/// Matches the changes of a set of objects.
+#[derive(Clone, Debug)]
+pub struct ObjectChangeFilter {
+    object_ids: BTreeSet<ObjectID>,
+}
+
+impl ObjectChangeFilter {
+    pub fn new(object_ids: impl IntoIterator<Item = ObjectID>) -> Self {
+        Self {
+            object_ids: object_ids.into_iter().collect(),
+        }
+    }
+}
+
+impl Filter<ObjectChange> for ObjectChangeFilter {
+    fn matches(&self, item: &ObjectChange) -> bool {
+        self.object_ids.contains(&item.object_id())
+    }
+}
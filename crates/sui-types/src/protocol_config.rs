@@ -0,0 +1,152 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! What each version of the protocol executes.
+//!
+//! The validators of a network are upgraded one at a time, and run binaries supporting different
+//! versions in the meantime. They only agree on the effects of transactions if they execute them
+//! at the same version, so the transaction kinds, natives and limits of a release only activate
+//! on networks whose genesis sets the version which introduced them.
+
+use crate::error::{SuiError, SuiResult};
+use crate::handshake::{MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use crate::messages::{SingleTransactionKind, TransactionKind};
+
+#[cfg(test)]
+#[path = "unit_tests/protocol_config_tests.rs"]
+mod protocol_config_tests;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProtocolConfig {
+    version: u32,
+    /// The most modules a package can be published with.
+    max_modules_in_publish: usize,
+    /// The most functions a published module can define.
+    max_function_definitions: usize,
+    /// The most objects a transaction can create, unwrapped objects counting as created.
+    max_created_objects: usize,
+    /// The most objects a transaction can mutate.
+    max_mutated_objects: usize,
+    /// The most events a transaction can emit.
+    max_events: usize,
+    /// The most bytes an event emitted by Move code can hold.
+    max_event_size: usize,
+    /// How deeply the types of the values passed to Move calls, written to objects or emitted
+    /// can nest, each vector and type argument nesting one level down.
+    max_type_nesting: usize,
+}
+
+impl ProtocolConfig {
+    /// The config of `version`, if this binary supports it.
+    pub fn get_for_version(version: u32) -> SuiResult<Self> {
+        if !(MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+            return Err(SuiError::UnsupportedProtocolVersion {
+                expected_min: MIN_PROTOCOL_VERSION,
+                expected_max: PROTOCOL_VERSION,
+                actual_min: version,
+                actual_max: version,
+            });
+        }
+        // Each version changes the config of the previous one, starting from the first.
+        Ok(Self {
+            version,
+            max_modules_in_publish: 64,
+            max_function_definitions: 1000,
+            max_created_objects: 2048,
+            max_mutated_objects: 2048,
+            max_events: 1024,
+            max_event_size: 256 * 1024,
+            max_type_nesting: 16,
+        })
+    }
+
+    /// The config of the latest version this binary supports.
+    pub fn get_for_max_version() -> Self {
+        Self::get_for_version(PROTOCOL_VERSION).expect("The latest version is supported")
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn max_modules_in_publish(&self) -> usize {
+        self.max_modules_in_publish
+    }
+
+    pub fn max_function_definitions(&self) -> usize {
+        self.max_function_definitions
+    }
+
+    pub fn max_created_objects(&self) -> usize {
+        self.max_created_objects
+    }
+
+    pub fn max_mutated_objects(&self) -> usize {
+        self.max_mutated_objects
+    }
+
+    pub fn max_events(&self) -> usize {
+        self.max_events
+    }
+
+    pub fn max_event_size(&self) -> usize {
+        self.max_event_size
+    }
+
+    pub fn max_type_nesting(&self) -> usize {
+        self.max_type_nesting
+    }
+
+    /// The version transactions of the kind of `kind` were introduced at.
+    fn transaction_kind_version(kind: &SingleTransactionKind) -> u32 {
+        match kind {
+            SingleTransactionKind::TransferObject(_)
+            | SingleTransactionKind::Publish(_)
+            | SingleTransactionKind::Call(_)
+            | SingleTransactionKind::TransferSui(_)
+            | SingleTransactionKind::ChangeEpoch(_) => 1,
+            SingleTransactionKind::Pay(_) => 2,
+        }
+    }
+
+    /// The version transactions of the kind of `kind` were first accepted in batches at.
+    fn batched_transaction_kind_version(kind: &SingleTransactionKind) -> u32 {
+        match kind {
+            SingleTransactionKind::Publish(_) => 2,
+            _ => Self::transaction_kind_version(kind),
+        }
+    }
+
+    /// Check that the transactions of `kind`, and the arguments they are called with, execute at
+    /// this version.
+    pub fn check_transaction_kind(&self, kind: &TransactionKind) -> SuiResult {
+        let batched = matches!(kind, TransactionKind::Batch(_));
+        for single in kind.single_transactions() {
+            if batched {
+                let since = Self::batched_transaction_kind_version(single);
+                self.check_feature_version("Batches of transactions of this kind", since)?;
+            } else {
+                let since = Self::transaction_kind_version(single);
+                self.check_feature_version("Transactions of this kind", since)?;
+            }
+            // Arguments referring to earlier transactions of the batch were added by version 2
+            if single.command_results().next().is_some() {
+                self.check_feature_version("The results of earlier transactions as arguments", 2)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_feature_version(&self, feature: &str, since: u32) -> SuiResult {
+        if since > self.version {
+            return Err(SuiError::UnsupportedFeatureError {
+                error: format!(
+                    "{feature} activate at protocol version {since}, the network runs at \
+                     version {}",
+                    self.version
+                ),
+            });
+        }
+        Ok(())
+    }
+}
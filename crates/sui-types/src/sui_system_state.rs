@@ -5,9 +5,13 @@ use move_core_types::{
     account_address::AccountAddress, ident_str, identifier::IdentStr, language_storage::StructTag,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 use crate::{
     balance::{Balance, Supply},
+    committee::Committee,
+    crypto::{PublicKeyBytes, ToFromBytes},
+    error::{SuiError, SuiResult},
     id::Info,
     SUI_FRAMEWORK_ADDRESS,
 };
@@ -90,4 +94,18 @@ impl SuiSystemState {
             type_params: vec![],
         }
     }
+
+    /// The committee of the current epoch: the active validators, voting with their stake and
+    /// the delegations to them.
+    pub fn get_current_epoch_committee(&self) -> SuiResult<Committee> {
+        let mut voting_rights = BTreeMap::new();
+        for validator in &self.validators.active_validators {
+            let name =
+                PublicKeyBytes::from_bytes(&validator.metadata.pubkey_bytes).map_err(|e| {
+                    SuiError::InvalidCommittee(format!("invalid validator public key: {e}"))
+                })?;
+            voting_rights.insert(name, validator.stake_amount + validator.delegation);
+        }
+        Committee::new(self.epoch, voting_rights)
+    }
 }
@@ -0,0 +1,62 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Objects and coins for tests, built from their Rust values instead of their BCS contents.
+
+use move_binary_format::file_format;
+use move_core_types::language_storage::StructTag;
+
+use crate::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use crate::coin::Coin;
+use crate::gas_coin::GasCoin;
+use crate::id::Info;
+use crate::object::{MoveObject, Object, Owner, OBJECT_START_VERSION};
+
+impl Object {
+    /// A gas coin of `owner` holding `balance`, with a new random ID.
+    pub fn with_owner_and_balance(owner: SuiAddress, balance: u64) -> Self {
+        Self::with_id_owner_gas_for_testing(ObjectID::random(), owner, balance)
+    }
+
+    /// A coin of `coin_type`, such as [`crate::gas_coin::GAS::type_`], owned by `owner` and
+    /// holding `balance`.
+    pub fn coin_for_testing(
+        id: ObjectID,
+        coin_type: StructTag,
+        owner: SuiAddress,
+        balance: u64,
+    ) -> Self {
+        let coin = Coin::new(Info::new(id, OBJECT_START_VERSION), balance);
+        // Coins have the `store` ability and are always publicly transferable.
+        let contents = unsafe {
+            MoveObject::new_from_execution(Coin::type_(coin_type), true, coin.to_bcs_bytes())
+        };
+        Self::new_move(
+            contents,
+            Owner::AddressOwner(owner),
+            TransactionDigest::genesis(),
+        )
+    }
+
+    /// A shared gas coin holding `balance`, at the first version of objects.
+    pub fn shared_for_testing(id: ObjectID, balance: u64) -> Self {
+        let coin = GasCoin::new(id, OBJECT_START_VERSION, balance);
+        Self::new_move(
+            coin.to_object(),
+            Owner::Shared,
+            TransactionDigest::genesis(),
+        )
+    }
+
+    /// An immutable package of `id` holding a single empty module, for tests that only need a
+    /// package to exist, such as to be referred to by transactions or read from stores.
+    pub fn package_stub_for_testing(id: ObjectID) -> Self {
+        let mut module = file_format::empty_module();
+        module.address_identifiers[0] = id.into();
+        Self::new_package(vec![module], TransactionDigest::genesis())
+    }
+}
+
+#[cfg(test)]
+#[path = "unit_tests/test_utils_tests.rs"]
+mod test_utils_tests;
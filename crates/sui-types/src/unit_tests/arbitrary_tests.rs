@@ -0,0 +1,34 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::crypto::{Signable, SignableBytes};
+
+proptest! {
+    #[test]
+    fn objects_are_gas_coins(object in any::<Object>()) {
+        let coin = GasCoin::try_from(&object).unwrap();
+        prop_assert_eq!(*coin.id(), object.id());
+        prop_assert_eq!(object.version(), coin.version());
+        let bytes = bcs::to_bytes(&object).unwrap();
+        prop_assert_eq!(bcs::from_bytes::<Object>(&bytes).unwrap(), object);
+    }
+
+    #[test]
+    fn transaction_data_round_trips(data in any::<TransactionData>()) {
+        let mut bytes = Vec::new();
+        data.write(&mut bytes);
+        prop_assert_eq!(TransactionData::from_signable_bytes(&bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn effects_mutate_their_gas_object(effects in any::<TransactionEffects>()) {
+        prop_assert!(effects.mutated.contains(&effects.gas_object));
+        prop_assert!(effects.gas_object.1.get_owner_address().is_ok());
+        let bytes = bcs::to_bytes(&effects).unwrap();
+        prop_assert_eq!(
+            bcs::from_bytes::<TransactionEffects>(&bytes).unwrap().digest(),
+            effects.digest()
+        );
+    }
+}
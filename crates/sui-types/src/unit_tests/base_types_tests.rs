@@ -253,6 +253,37 @@ fn test_address_serde_with_expected_value() {
     assert_eq!(address_vec, bcs_serialized);
 }
 
+#[test]
+fn test_deterministic_for_testing() {
+    assert_eq!(
+        TxContext::deterministic_for_testing(1),
+        TxContext::deterministic_for_testing(1)
+    );
+    assert_ne!(
+        TransactionDigest::deterministic_for_testing(1),
+        TransactionDigest::deterministic_for_testing(2)
+    );
+    assert_ne!(
+        ObjectID::deterministic_for_testing(1),
+        TransactionDigest::deterministic_for_testing(1).derive_id(0)
+    );
+
+    // The IDs generated by a context follow from its seed
+    let mut ctx = TxContext::deterministic_for_testing(1);
+    let ids: Vec<_> = (0..3).map(|_| ctx.fresh_id()).collect();
+    let mut again = TxContext::deterministic_for_testing(1);
+    assert_eq!(
+        ids,
+        vec![again.fresh_id(), again.fresh_id(), again.fresh_id()]
+    );
+    assert_eq!(
+        ctx.digest(),
+        TransactionDigest::deterministic_for_testing(1)
+    );
+    assert_eq!(ctx.sender(), SuiAddress::deterministic_for_testing(1));
+    assert_ne!(TxContext::deterministic_for_testing(2).fresh_id(), ids[0]);
+}
+
 #[test]
 fn test_transaction_digest_serde_not_human_readable() {
     let digest = TransactionDigest::random();
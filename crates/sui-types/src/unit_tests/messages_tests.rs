@@ -40,11 +40,25 @@ fn test_signed_values() {
     let committee = Committee::new(0, authorities).unwrap();
 
     let transaction = Transaction::from_data(
-        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        TransactionData::new_transfer(
+            a2,
+            random_object_ref(),
+            a1,
+            random_object_ref(),
+            10000,
+            ChainId::deterministic_for_testing(0),
+        ),
         &sec1,
     );
     let bad_transaction = Transaction::from_data(
-        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        TransactionData::new_transfer(
+            a2,
+            random_object_ref(),
+            a1,
+            random_object_ref(),
+            10000,
+            ChainId::deterministic_for_testing(0),
+        ),
         &sec2,
     );
 
@@ -99,11 +113,25 @@ fn test_certificates() {
     let committee = Committee::new(0, authorities).unwrap();
 
     let transaction = Transaction::from_data(
-        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        TransactionData::new_transfer(
+            a2,
+            random_object_ref(),
+            a1,
+            random_object_ref(),
+            10000,
+            ChainId::deterministic_for_testing(0),
+        ),
         &sec1,
     );
     let bad_transaction = Transaction::from_data(
-        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        TransactionData::new_transfer(
+            a2,
+            random_object_ref(),
+            a1,
+            random_object_ref(),
+            10000,
+            ChainId::deterministic_for_testing(0),
+        ),
         &sec2,
     );
 
@@ -339,7 +367,14 @@ fn test_digest_caching() {
     let committee = Committee::new(0, authorities).unwrap();
 
     let transaction = Transaction::from_data(
-        TransactionData::new_transfer(a2, random_object_ref(), a1, random_object_ref(), 10000),
+        TransactionData::new_transfer(
+            a2,
+            random_object_ref(),
+            a1,
+            random_object_ref(),
+            10000,
+            ChainId::deterministic_for_testing(0),
+        ),
         &sec1,
     );
 
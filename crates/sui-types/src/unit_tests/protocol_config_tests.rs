@@ -0,0 +1,92 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::{dbg_addr, dbg_object_id, ObjectDigest, SequenceNumber};
+use crate::messages::{
+    CallArg, CommandResult, MoveCall, MoveModulePublish, ObjectArg, Pay, TransferSui,
+};
+use move_core_types::ident_str;
+
+#[test]
+fn test_supported_versions() {
+    let config = ProtocolConfig::get_for_max_version();
+    assert_eq!(config.version(), PROTOCOL_VERSION);
+    assert_eq!(
+        ProtocolConfig::get_for_version(MIN_PROTOCOL_VERSION)
+            .unwrap()
+            .version(),
+        MIN_PROTOCOL_VERSION
+    );
+    assert!(matches!(
+        ProtocolConfig::get_for_version(PROTOCOL_VERSION + 1),
+        Err(SuiError::UnsupportedProtocolVersion { .. })
+    ));
+    assert!(ProtocolConfig::get_for_version(0).is_err());
+}
+
+#[test]
+fn test_transaction_kinds_of_first_version() {
+    let config = ProtocolConfig::get_for_version(MIN_PROTOCOL_VERSION).unwrap();
+    let kind = SingleTransactionKind::TransferSui(TransferSui {
+        recipient: dbg_addr(1),
+        amount: Some(10),
+    });
+    config
+        .check_transaction_kind(&TransactionKind::Single(kind))
+        .unwrap();
+}
+
+#[test]
+fn test_features_of_second_version() {
+    let coin = (
+        dbg_object_id(1),
+        SequenceNumber::new(),
+        ObjectDigest::new([0; 32]),
+    );
+    let pay = TransactionKind::Single(SingleTransactionKind::Pay(Pay {
+        coins: vec![coin],
+        recipients: vec![dbg_addr(1)],
+        amounts: vec![10],
+    }));
+    let publish = SingleTransactionKind::Publish(MoveModulePublish {
+        modules: vec![vec![]],
+    });
+    let batched_publish = TransactionKind::Batch(vec![publish.clone()]);
+    let call = |arguments| {
+        SingleTransactionKind::Call(MoveCall {
+            package: coin,
+            module: ident_str!("m").to_owned(),
+            function: ident_str!("f").to_owned(),
+            type_arguments: vec![],
+            arguments,
+        })
+    };
+    let result_argument = TransactionKind::Batch(vec![
+        call(vec![]),
+        call(vec![CallArg::Object(ObjectArg::Result(CommandResult {
+            command: 0,
+            index: 0,
+        }))]),
+    ]);
+
+    // The first version rejects them, but still publishes packages on their own
+    let first = ProtocolConfig::get_for_version(1).unwrap();
+    for kind in [&pay, &batched_publish, &result_argument] {
+        assert!(matches!(
+            first.check_transaction_kind(kind),
+            Err(SuiError::UnsupportedFeatureError { .. })
+        ));
+    }
+    first
+        .check_transaction_kind(&TransactionKind::Single(publish))
+        .unwrap();
+    first
+        .check_transaction_kind(&TransactionKind::Batch(vec![call(vec![])]))
+        .unwrap();
+
+    let second = ProtocolConfig::get_for_version(2).unwrap();
+    for kind in [&pay, &batched_publish, &result_argument] {
+        second.check_transaction_kind(kind).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::base_types::dbg_addr;
+use crate::gas_coin::GAS;
+
+#[test]
+fn test_object_factories() {
+    let owner = dbg_addr(1);
+    let coin = Object::with_owner_and_balance(owner, 500);
+    assert_eq!(coin.get_single_owner(), Some(owner));
+    assert_eq!(
+        GasCoin::try_from(coin.data.try_as_move().unwrap())
+            .unwrap()
+            .value(),
+        500
+    );
+
+    let id = ObjectID::deterministic_for_testing(1);
+    let coin = Object::coin_for_testing(id, GAS::type_(), owner, 500);
+    assert_eq!(coin.id(), id);
+    assert_eq!(coin.version(), OBJECT_START_VERSION);
+    assert_eq!(coin.type_(), Some(&GasCoin::type_()));
+
+    let shared = Object::shared_for_testing(id, 10);
+    assert!(shared.is_shared());
+    assert_eq!(shared.id(), id);
+
+    let package = Object::package_stub_for_testing(id);
+    assert!(package.is_immutable());
+    assert_eq!(package.id(), id);
+    assert!(package.is_package());
+}
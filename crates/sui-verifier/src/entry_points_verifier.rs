@@ -93,7 +93,10 @@ fn verify_init_not_called(
 }
 
 /// Checks if this module has a conformant `init`
-fn verify_init_function(module: &CompiledModule, fdef: &FunctionDefinition) -> Result<(), String> {
+pub fn verify_init_function(
+    module: &CompiledModule,
+    fdef: &FunctionDefinition,
+) -> Result<(), String> {
     let view = &BinaryIndexedView::Module(module);
 
     if fdef.visibility != Visibility::Private {
@@ -148,7 +151,8 @@ fn verify_init_function(module: &CompiledModule, fdef: &FunctionDefinition) -> R
     }
 }
 
-fn verify_entry_function_impl(
+/// Checks the parameters and return values of an entry function
+pub fn verify_entry_function_impl(
     module: &CompiledModule,
     func_def: &FunctionDefinition,
 ) -> Result<(), String> {
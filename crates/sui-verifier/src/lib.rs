@@ -6,6 +6,7 @@ pub mod verifier;
 pub mod entry_points_verifier;
 pub mod global_storage_access_verifier;
 pub mod id_leak_verifier;
+pub mod limits_verifier;
 pub mod private_generics;
 pub mod struct_with_key_verifier;
 
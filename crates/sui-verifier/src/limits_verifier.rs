@@ -0,0 +1,25 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This pass verifies that a module is within the limits of the protocol version it is
+//! published at.
+
+use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
+use sui_types::{error::ExecutionError, protocol_config::ProtocolConfig};
+
+use crate::verification_failure;
+
+pub fn verify_module(
+    module: &CompiledModule,
+    config: &ProtocolConfig,
+) -> Result<(), ExecutionError> {
+    let functions = module.function_defs.len();
+    if functions > config.max_function_definitions() {
+        return Err(verification_failure(format!(
+            "Module {} defines {functions} functions, at most {} are allowed",
+            module.self_id(),
+            config.max_function_definitions()
+        )));
+    }
+    Ok(())
+}
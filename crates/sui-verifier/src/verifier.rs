@@ -4,15 +4,19 @@
 //! This module contains the public APIs supported by the bytecode verifier.
 
 use move_binary_format::file_format::CompiledModule;
-use sui_types::error::ExecutionError;
+use sui_types::{error::ExecutionError, protocol_config::ProtocolConfig};
 
 use crate::{
-    entry_points_verifier, global_storage_access_verifier, id_leak_verifier, private_generics,
-    struct_with_key_verifier,
+    entry_points_verifier, global_storage_access_verifier, id_leak_verifier, limits_verifier,
+    private_generics, struct_with_key_verifier,
 };
 
-/// Helper for a "canonical" verification of a module.
-pub fn verify_module(module: &CompiledModule) -> Result<(), ExecutionError> {
+/// Helper for a "canonical" verification of a module, at the protocol version of `config`.
+pub fn verify_module(
+    module: &CompiledModule,
+    config: &ProtocolConfig,
+) -> Result<(), ExecutionError> {
+    limits_verifier::verify_module(module, config)?;
     struct_with_key_verifier::verify_module(module)?;
     global_storage_access_verifier::verify_module(module)?;
     id_leak_verifier::verify_module(module)?;
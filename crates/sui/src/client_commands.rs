@@ -3,40 +3,51 @@
 
 use core::fmt;
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     fmt::{Debug, Display, Formatter, Write},
     path::{Path, PathBuf},
+    sync::Arc,
     time::Instant,
 };
 
 use anyhow::anyhow;
 use clap::*;
 use colored::Colorize;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_core_types::{language_storage::TypeTag, parser::parse_type_tag};
 use move_package::BuildConfig;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sui_json_rpc_types::{
-    GetObjectDataResponse, MergeCoinResponse, PublishResponse, SplitCoinResponse, SuiObjectInfo,
-    SuiParsedObject,
+    GetObjectDataResponse, MergeCoinResponse, MoveCallParams, PublishResponse,
+    RPCTransactionRequestParams, SplitCoinResponse, SuiData, SuiMoveStruct, SuiMoveValue,
+    SuiObject, SuiObjectInfo, SuiParsedObject, SuiPublishPreview, SuiTypeTag,
 };
 use tracing::info;
 
 use sui_core::gateway_state::GatewayClient;
-use sui_framework::build_move_package_to_bytes;
+use sui_framework::{build_and_verify_package_with_deps, serialize_modules};
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{SuiCertifiedTransaction, SuiExecutionStatus, SuiTransactionEffects};
-use sui_sdk::crypto::Keystore;
+use sui_sdk::crypto::{Keystore, KeystoreType};
+use sui_types::coin::{COIN_JOIN_FUNC_NAME, COIN_MODULE_NAME, COIN_STRUCT_NAME};
+use sui_types::crypto::{KeypairTraits, SignableBytes, Signature};
+use sui_types::intent::IntentScope;
 use sui_types::object::Owner;
 use sui_types::sui_serde::{Base64, Encoding};
 use sui_types::{
     base_types::{ObjectID, SuiAddress},
     gas_coin::GasCoin,
-    messages::Transaction,
+    messages::{Transaction, TransactionData},
     SUI_FRAMEWORK_ADDRESS,
 };
 
-use crate::config::{Config, GatewayType, PersistedConfig, SuiClientConfig};
+use crate::config::{
+    Config, FailoverConfig, GatewayType, PersistedConfig, SuiClientConfig, SuiEnv,
+};
+use crate::keytool::read_keypair_from_file;
+use crate::name_service::{NameResolver, Recipient, RegistryNameResolver};
+use crate::signing_request::{prompt_approval, SigningRequest};
 
 pub const EXAMPLE_NFT_NAME: &str = "Example NFT";
 pub const EXAMPLE_NFT_DESCRIPTION: &str = "An NFT created by the Sui Command Line Tool";
@@ -57,6 +68,33 @@ pub enum SuiClientCommands {
         /// used for subsequent commands.
         #[clap(long, value_hint = ValueHint::Url)]
         gateway: Option<String>,
+        /// The alias of the environment (e.g., localnet, devnet) to be used for subsequent
+        /// commands, see `envs`.
+        #[clap(long, conflicts_with = "gateway")]
+        env: Option<String>,
+    },
+
+    /// List the environments of the client config
+    #[clap(name = "envs")]
+    Envs,
+
+    /// Add an environment to the client config
+    #[clap(name = "new-env")]
+    NewEnv {
+        /// Alias of the environment, e.g. localnet
+        #[clap(long)]
+        alias: String,
+        /// The gateway URL of the environment. Repeat it to list several endpoints, the
+        /// client then fails over to the next endpoint when one becomes unreachable
+        #[clap(long, value_hint = ValueHint::Url, multiple_occurrences = true, required = true)]
+        rpc: Vec<String>,
+        /// Send read requests to all the endpoints at once and use the first answer
+        #[clap(long)]
+        race_reads: bool,
+        /// Path to the keystore of the environment, the default keystore is used if not
+        /// provided
+        #[clap(long, parse(from_os_str))]
+        keystore: Option<PathBuf>,
     },
 
     /// Default address used for commands when none specified
@@ -89,13 +127,29 @@ pub enum SuiClientCommands {
         build_config: BuildConfig,
 
         /// ID of the gas object for gas payment, in 20 bytes Hex string
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for running module initializers
+        /// Gas budget for running module initializers, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
+
+        /// Do not check that the package's dependencies are published on-chain, with the bytecode
+        /// they are built to locally, before publishing
+        #[clap(long)]
+        skip_dependency_verification: bool,
+
+        /// Verify the package and run its module initializers against the current state without
+        /// publishing it, and show the package ID, created objects and gas it would result in
+        #[clap(long)]
+        dry_run: bool,
     },
 
     /// Call Move function
@@ -124,55 +178,116 @@ pub enum SuiClientCommands {
         args: Vec<SuiJsonValue>,
         /// ID of the gas object for gas payment, in 20 bytes Hex string
         #[clap(long)]
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
     },
 
     /// Transfer coin object
     #[clap(name = "transfer-coin")]
     Transfer {
-        /// Recipient address
+        /// Recipient address or name, e.g. alice.sui
         #[clap(long)]
-        to: SuiAddress,
+        to: Recipient,
 
         /// Coin to transfer, in 20 bytes Hex string
         #[clap(long)]
         coin_object_id: ObjectID,
 
         /// ID of the gas object for gas payment, in 20 bytes Hex string
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
     },
     /// Transfer SUI, and pay gas with the same SUI coin object.
     /// If amount is specified, only the amount is transferred; otherwise the entire object
     /// is transferred.
     #[clap(name = "transfer-sui")]
     TransferSui {
-        /// Recipient address
+        /// Recipient address or name, e.g. alice.sui
         #[clap(long)]
-        to: SuiAddress,
+        to: Recipient,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
         sui_coin_object_id: ObjectID,
 
-        /// Gas budget for this transfer
+        /// Gas budget for this transfer, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
 
         /// The amount to transfer, if not specified, the entire coin object will be transferred.
         #[clap(long)]
         amount: Option<u64>,
     },
+    /// Pay several recipients at once out of coins of the same type, merging the input coins
+    /// into the first one. Each recipient receives a new coin of the given amount.
+    #[clap(name = "pay")]
+    Pay {
+        /// Coins to pay with, all of the same type, IDs in 20 bytes Hex string
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        input_coins: Vec<ObjectID>,
+
+        /// Recipient addresses or names, e.g. alice.sui
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        recipients: Vec<Recipient>,
+
+        /// Amounts to pay, in the same order as the recipients
+        #[clap(long, multiple_occurrences = false, multiple_values = true)]
+        amounts: Vec<u64>,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction, estimated if not provided
+        #[clap(long)]
+        gas_budget: Option<u64>,
+
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
+    },
+
+    /// Send all the SUI of the active address to a recipient, in one transaction paying the gas
+    /// with the largest SUI coin.
+    #[clap(name = "pay-all")]
+    PayAll {
+        /// Recipient address or name, e.g. alice.sui
+        #[clap(long)]
+        to: Recipient,
+
+        /// Gas budget for this transaction, estimated if not provided
+        #[clap(long)]
+        gas_budget: Option<u64>,
+    },
+
     /// Synchronize client state with authorities.
     #[clap(name = "sync")]
     SyncClientState {
@@ -188,6 +303,15 @@ pub enum SuiClientCommands {
     #[clap(name = "new-address")]
     NewAddress,
 
+    /// Add an address without its private key. Its objects can be listed and transactions
+    /// can be built for it with `tx build`, but they must be signed outside of the wallet.
+    #[clap(name = "add-watch-address")]
+    AddWatchAddress {
+        /// The address to watch
+        #[clap(long)]
+        address: SuiAddress,
+    },
+
     /// Obtain all objects owned by the address.
     #[clap(name = "objects")]
     Objects {
@@ -204,6 +328,31 @@ pub enum SuiClientCommands {
         address: Option<SuiAddress>,
     },
 
+    /// Export the coin balances and the owned objects of an address, for accounting tools.
+    #[clap(name = "export")]
+    Export {
+        /// Address owning the objects, the active address is used if not provided
+        #[clap(long)]
+        address: Option<SuiAddress>,
+        /// Output format
+        #[clap(long, arg_enum, default_value = "json", ignore_case = true)]
+        format: ExportFormat,
+        /// File to write the export to, it is printed if not provided
+        #[clap(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+    },
+
+    /// Request gas coins from a faucet service.
+    #[clap(name = "faucet")]
+    Faucet {
+        /// Address to receive the coins, the active address is used if not provided
+        #[clap(long)]
+        address: Option<SuiAddress>,
+        /// URL of the faucet service
+        #[clap(long)]
+        url: String,
+    },
+
     /// Split a coin object into multiple coins.
     SplitCoin {
         /// Coin to Split, in 20 bytes Hex string
@@ -214,16 +363,25 @@ pub enum SuiClientCommands {
             long,
             multiple_occurrences = false,
             multiple_values = true,
-            required = true
+            required_unless_present = "count"
         )]
         amounts: Vec<u64>,
+        /// Split the coin into this many coins of equal value instead of splitting out
+        /// given amounts
+        #[clap(long, conflicts_with = "amounts")]
+        count: Option<u64>,
         /// ID of the gas object for gas payment, in 20 bytes Hex string
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for this call, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
     },
 
     /// Merge two coin objects into one coin
@@ -232,15 +390,55 @@ pub enum SuiClientCommands {
         #[clap(long)]
         primary_coin: ObjectID,
         /// Coin to be merged, in 20 bytes Hex string
+        #[clap(long, required_unless_present = "all")]
+        coin_to_merge: Option<ObjectID>,
+        /// Merge all the coins of the same type owned by the owner of the primary coin into
+        /// it, using as many transactions as needed. The gas coin is not merged.
+        #[clap(long, conflicts_with = "coin_to_merge")]
+        all: bool,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for this call, or for each transaction when merging all coins, estimated if not
+        /// provided
         #[clap(long)]
-        coin_to_merge: ObjectID,
+        gas_budget: Option<u64>,
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
+    },
+
+    /// Execute a batch of transactions atomically with one gas payment. Move call arguments
+    /// can refer to objects created by earlier transactions of the batch as
+    /// "result:<transaction index>:<created object index>".
+    #[clap(name = "batch")]
+    Batch {
+        /// Path to a JSON file containing the list of transactions of the batch
+        #[clap(long, parse(from_os_str))]
+        file: PathBuf,
         /// ID of the gas object for gas payment, in 20 bytes Hex string
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
-        /// Gas budget for this call
+        /// Gas budget for the whole batch, estimated if not provided
         #[clap(long)]
-        gas_budget: u64,
+        gas_budget: Option<u64>,
+        /// Dry-run the transaction first and lower the gas budget to the estimated cost,
+        /// the provided gas budget is then only used as an upper bound
+        #[clap(long)]
+        estimate_gas: bool,
+    },
+
+    /// Build, sign and submit a transaction in separate steps, so that it can be signed on a
+    /// machine without network access.
+    #[clap(name = "tx")]
+    Tx {
+        #[clap(subcommand)]
+        cmd: TxCommand,
     },
 
     /// Create an example NFT
@@ -259,7 +457,8 @@ pub enum SuiClientCommands {
         url: Option<String>,
 
         /// ID of the gas object for gas payment, in 20 bytes Hex string
-        /// If not provided, a gas object with at least gas_budget value will be selected
+        /// If not provided, the smallest gas coin covering the budget is selected, merging coins
+        /// if none is large enough
         #[clap(long)]
         gas: Option<ObjectID>,
 
@@ -267,6 +466,173 @@ pub enum SuiClientCommands {
         #[clap(long)]
         gas_budget: Option<u64>,
     },
+
+    /// Sign data on behalf of another application, once the request was shown and explicitly
+    /// approved
+    #[clap(name = "sign-request")]
+    SignRequest {
+        /// Where the request comes from, e.g. the URL of the requesting application
+        #[clap(long)]
+        origin: String,
+
+        /// What the signature authorizes: transaction-data or personal-message
+        #[clap(long, default_value = "transaction-data")]
+        scope: IntentScope,
+
+        /// Address to sign with. Defaults to the sender of the transaction data, or to the
+        /// active address for messages
+        #[clap(long)]
+        address: Option<SuiAddress>,
+
+        /// The data to sign, as a Base64 string
+        #[clap(long)]
+        data: String,
+    },
+}
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum TxCommand {
+    /// Build an unsigned transaction from a JSON file listing its transactions, in the same
+    /// format as the `batch` command, and write it to the output file.
+    Build {
+        /// Path to a JSON file containing the list of transactions
+        #[clap(long, parse(from_os_str))]
+        file: PathBuf,
+        /// Path of the unsigned transaction file to write
+        #[clap(long, parse(from_os_str))]
+        output: PathBuf,
+        /// Signer of the transaction, the active address is used if not provided
+        #[clap(long)]
+        sender: Option<SuiAddress>,
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+        /// Gas budget for the transaction
+        #[clap(long)]
+        gas_budget: u64,
+    },
+    /// Sign an unsigned transaction file. This does not require network access.
+    Sign {
+        /// Path of the unsigned transaction file
+        #[clap(long, parse(from_os_str))]
+        input: PathBuf,
+        /// Path of the signed transaction file to write
+        #[clap(long, parse(from_os_str))]
+        output: PathBuf,
+        /// Path to the keypair file of the signer, the wallet keystore is used if not provided
+        #[clap(long, parse(from_os_str))]
+        key: Option<PathBuf>,
+    },
+    /// Submit a signed transaction file for execution.
+    Submit {
+        /// Path of the signed transaction file
+        #[clap(long, parse(from_os_str))]
+        signed: PathBuf,
+    },
+}
+
+/// A transaction written to a file by `tx build`, and signed by `tx sign`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFile {
+    pub tx_bytes: Base64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+impl TransactionFile {
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow!("Invalid transaction file {}: {e}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn data(&self) -> Result<TransactionData, anyhow::Error> {
+        TransactionData::from_signable_bytes(&self.tx_bytes.to_vec()?)
+    }
+}
+
+impl TxCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<SuiClientCommandResult, anyhow::Error> {
+        Ok(match self {
+            TxCommand::Build {
+                file,
+                output,
+                sender,
+                gas,
+                gas_budget,
+            } => {
+                let transactions: Vec<RPCTransactionRequestParams> =
+                    serde_json::from_str(&std::fs::read_to_string(&file)?).map_err(|e| {
+                        anyhow!("Invalid transaction list file {}: {e}", file.display())
+                    })?;
+                let sender = match sender {
+                    Some(sender) => sender,
+                    None => context.active_address()?,
+                };
+                let data = context
+                    .gateway
+                    .batch_transaction(sender, transactions, gas, gas_budget)
+                    .await?;
+                TransactionFile {
+                    tx_bytes: Base64::from_bytes(&data.to_bytes()),
+                    signature: None,
+                }
+                .write(&output)?;
+                SuiClientCommandResult::TransactionFile(output)
+            }
+            TxCommand::Sign { input, output, key } => {
+                let mut file = TransactionFile::read(&input)?;
+                let data = file.data()?;
+                let signature = match key {
+                    Some(key) => {
+                        let keypair = read_keypair_from_file(key)?;
+                        let address = SuiAddress::from(keypair.public());
+                        if address != data.signer() {
+                            return Err(anyhow!(
+                                "Transaction must be signed by {}, but the key belongs to {}",
+                                data.signer(),
+                                address
+                            ));
+                        }
+                        context.confirm_transaction(&data).await?;
+                        Signature::new(&data, &keypair)
+                    }
+                    None => context.sign_transaction(&data).await?,
+                };
+                file.signature = Some(signature);
+                file.write(&output)?;
+                SuiClientCommandResult::TransactionFile(output)
+            }
+            TxCommand::Submit { signed } => {
+                let file = TransactionFile::read(&signed)?;
+                let signature = file.signature.clone().ok_or_else(|| {
+                    anyhow!("Transaction file {} is not signed", signed.display())
+                })?;
+                let response = context
+                    .gateway
+                    .execute_transaction(Transaction::new(file.data()?, signature))
+                    .await?
+                    .to_effect_response()?;
+                if matches!(response.effects.status, SuiExecutionStatus::Failure { .. }) {
+                    return Err(anyhow!(
+                        "Error executing signed transaction: {:#?}",
+                        response.effects.status
+                    ));
+                }
+                SuiClientCommandResult::Submit(response.certificate, response.effects)
+            }
+        })
+    }
 }
 
 impl SuiClientCommands {
@@ -280,16 +646,38 @@ impl SuiClientCommands {
                 gas,
                 build_config,
                 gas_budget,
+                estimate_gas,
+                skip_dependency_verification,
+                dry_run,
             } => {
                 let sender = context.try_get_object_owner(&gas).await?;
                 let sender = sender.unwrap_or(context.active_address()?);
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let (gas, gas_budget) = context
+                    .select_gas(sender, gas, gas_budget, &BTreeSet::new())
+                    .await?;
 
-                let compiled_modules = build_move_package_to_bytes(&package_path, build_config)?;
-                let data = context
+                // Build and verify locally so that bad bytecode is reported before paying for gas.
+                // Already published dependencies are stripped by the build, only the package's
+                // own modules are uploaded.
+                let (compiled_modules, dependencies) =
+                    build_and_verify_package_with_deps(&package_path, build_config)?;
+                if !skip_dependency_verification {
+                    context.verify_published_dependencies(&dependencies).await?;
+                }
+                let compiled_modules = serialize_modules(&compiled_modules);
+                let mut data = context
                     .gateway
-                    .publish(sender, compiled_modules, gas, gas_budget)
+                    .publish(sender, compiled_modules, Some(gas), gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&sender, &data.to_bytes())?;
+                if dry_run {
+                    let preview = context.gateway.dry_run_publish(data).await?;
+                    return Ok(SuiClientCommandResult::PublishPreview(preview));
+                }
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -311,10 +699,19 @@ impl SuiClientCommands {
                 type_args,
                 gas,
                 gas_budget,
+                estimate_gas,
                 args,
             } => {
                 let (cert, effects) = call_move(
-                    package, &module, &function, type_args, gas, gas_budget, args, context,
+                    package,
+                    &module,
+                    &function,
+                    type_args,
+                    gas,
+                    gas_budget,
+                    estimate_gas,
+                    args,
+                    context,
                 )
                 .await?;
                 SuiClientCommandResult::Call(cert, effects)
@@ -325,15 +722,24 @@ impl SuiClientCommands {
                 coin_object_id: object_id,
                 gas,
                 gas_budget,
+                estimate_gas,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
+                let to = context.resolve_recipient(&to).await?;
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let (gas, gas_budget) = context
+                    .select_gas(from, gas, gas_budget, &BTreeSet::from([object_id]))
+                    .await?;
                 let time_start = Instant::now();
 
-                let data = context
+                let mut data = context
                     .gateway
-                    .public_transfer_object(from, object_id, gas, gas_budget, to)
+                    .public_transfer_object(from, object_id, Some(gas), gas_budget, to)
                     .await?;
-                let signature = context.keystore.sign(&from, &data.to_bytes())?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -353,15 +759,25 @@ impl SuiClientCommands {
                 to,
                 sui_coin_object_id: object_id,
                 gas_budget,
+                estimate_gas,
                 amount,
             } => {
                 let from = context.get_object_owner(&object_id).await?;
+                let to = context.resolve_recipient(&to).await?;
+                // The transferred coin also pays for the gas.
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let (_, gas_budget) = context
+                    .select_gas(from, Some(object_id), gas_budget, &BTreeSet::new())
+                    .await?;
 
-                let data = context
+                let mut data = context
                     .gateway
                     .transfer_sui(from, object_id, gas_budget, to, amount)
                     .await?;
-                let signature = context.keystore.sign(&from, &data.to_bytes())?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -376,6 +792,91 @@ impl SuiClientCommands {
                 SuiClientCommandResult::TransferSui(cert, effects)
             }
 
+            SuiClientCommands::Pay {
+                input_coins,
+                recipients,
+                amounts,
+                gas,
+                gas_budget,
+                estimate_gas,
+            } => {
+                let first_coin = input_coins
+                    .first()
+                    .ok_or_else(|| anyhow!("At least one input coin must be provided"))?;
+                let signer = context.get_object_owner(first_coin).await?;
+                let mut resolved = Vec::with_capacity(recipients.len());
+                for recipient in &recipients {
+                    resolved.push(context.resolve_recipient(recipient).await?);
+                }
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let inputs = input_coins.iter().copied().collect();
+                let (gas, gas_budget) =
+                    context.select_gas(signer, gas, gas_budget, &inputs).await?;
+                let mut data = context
+                    .gateway
+                    .pay(
+                        signer,
+                        input_coins,
+                        resolved,
+                        amounts,
+                        Some(gas),
+                        gas_budget,
+                    )
+                    .await?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
+                let response = context
+                    .gateway
+                    .execute_transaction(Transaction::new(data, signature))
+                    .await?
+                    .to_effect_response()?;
+                let cert = response.certificate;
+                let effects = response.effects;
+
+                if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
+                    return Err(anyhow!("Error paying: {:#?}", effects.status));
+                }
+                SuiClientCommandResult::Pay(cert, effects)
+            }
+
+            SuiClientCommands::PayAll { to, gas_budget } => {
+                let signer = context.active_address()?;
+                let to = context.resolve_recipient(&to).await?;
+                let mut coins = context.gas_objects(signer).await?;
+                // Largest first, the first coin pays for the gas.
+                coins.sort_by(|(a, _), (b, _)| b.cmp(a));
+                let largest = coins
+                    .first()
+                    .map(|(value, _)| *value)
+                    .ok_or_else(|| anyhow!("Address {signer} has no SUI coin to send"))?;
+                let estimate_gas = gas_budget.is_none();
+                let gas_budget =
+                    gas_budget.unwrap_or_else(|| std::cmp::min(largest, MAX_AUTO_GAS_BUDGET));
+                let input_coins = coins.iter().map(|(_, object)| object.id()).collect();
+                let mut data = context
+                    .gateway
+                    .pay_all(signer, input_coins, to, gas_budget)
+                    .await?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
+                let response = context
+                    .gateway
+                    .execute_transaction(Transaction::new(data, signature))
+                    .await?
+                    .to_effect_response()?;
+                let cert = response.certificate;
+                let effects = response.effects;
+
+                if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
+                    return Err(anyhow!("Error sending all SUI: {:#?}", effects.status));
+                }
+                SuiClientCommandResult::PayAll(cert, effects)
+            }
+
             SuiClientCommands::Addresses => {
                 SuiClientCommandResult::Addresses(context.config.accounts.clone())
             }
@@ -406,6 +907,14 @@ impl SuiClientCommands {
                 context.config.save()?;
                 SuiClientCommandResult::NewAddress(address)
             }
+            SuiClientCommands::AddWatchAddress { address } => {
+                context.keystore.add_watch_only_address(address)?;
+                if !context.config.accounts.contains(&address) {
+                    context.config.accounts.push(address);
+                }
+                context.config.save()?;
+                SuiClientCommandResult::AddWatchAddress(address)
+            }
             SuiClientCommands::Gas { address } => {
                 let address = address.unwrap_or(context.active_address()?);
                 let coins = context
@@ -417,18 +926,58 @@ impl SuiClientCommands {
                     .collect();
                 SuiClientCommandResult::Gas(coins)
             }
+            SuiClientCommands::Export {
+                address,
+                format,
+                output,
+            } => {
+                let address = address.unwrap_or(context.active_address()?);
+                let export = context.export_account(address).await?;
+                let content = match format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&export)?,
+                    ExportFormat::Csv => export.to_csv()?,
+                };
+                if let Some(output) = &output {
+                    std::fs::write(output, &content)?;
+                }
+                SuiClientCommandResult::Export(output, content)
+            }
+            SuiClientCommands::Faucet { address, url } => {
+                let address = address.unwrap_or(context.active_address()?);
+                let coins = request_faucet_coins(&url, address).await?;
+                context.gateway.sync_account_state(address).await?;
+                SuiClientCommandResult::Faucet(coins)
+            }
             SuiClientCommands::SplitCoin {
                 coin_id,
                 amounts,
+                count,
                 gas,
                 gas_budget,
+                estimate_gas,
             } => {
                 let signer = context.get_object_owner(&coin_id).await?;
-                let data = context
+                let amounts = match count {
+                    Some(count) => {
+                        let coin = context.gateway.get_object(coin_id).await?.into_object()?;
+                        let (_, value) = coin_type_and_value(&coin)
+                            .ok_or_else(|| anyhow!("Object {coin_id} is not a coin"))?;
+                        equal_split_amounts(value, count)?
+                    }
+                    None => amounts,
+                };
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let (gas, gas_budget) = context
+                    .select_gas(signer, gas, gas_budget, &BTreeSet::from([coin_id]))
+                    .await?;
+                let mut data = context
                     .gateway
-                    .split_coin(signer, coin_id, amounts, gas, gas_budget)
+                    .split_coin(signer, coin_id, amounts, Some(gas), gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&signer, &data.to_bytes())?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -436,18 +985,46 @@ impl SuiClientCommands {
                     .to_split_coin_response()?;
                 SuiClientCommandResult::SplitCoin(response)
             }
+            SuiClientCommands::MergeCoin {
+                primary_coin,
+                all: true,
+                gas,
+                gas_budget,
+                estimate_gas,
+                ..
+            } => SuiClientCommandResult::MergeAllCoins(
+                context
+                    .merge_all_coins(
+                        primary_coin,
+                        gas,
+                        gas_budget.unwrap_or(MAX_AUTO_GAS_BUDGET),
+                        estimate_gas || gas_budget.is_none(),
+                    )
+                    .await?,
+            ),
             SuiClientCommands::MergeCoin {
                 primary_coin,
                 coin_to_merge,
+                all: false,
                 gas,
                 gas_budget,
+                estimate_gas,
             } => {
+                let coin_to_merge = coin_to_merge
+                    .ok_or_else(|| anyhow!("Either --coin-to-merge or --all must be provided"))?;
                 let signer = context.get_object_owner(&primary_coin).await?;
-                let data = context
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let inputs = BTreeSet::from([primary_coin, coin_to_merge]);
+                let (gas, gas_budget) =
+                    context.select_gas(signer, gas, gas_budget, &inputs).await?;
+                let mut data = context
                     .gateway
-                    .merge_coins(signer, primary_coin, coin_to_merge, gas, gas_budget)
+                    .merge_coins(signer, primary_coin, coin_to_merge, Some(gas), gas_budget)
                     .await?;
-                let signature = context.keystore.sign(&signer, &data.to_bytes())?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
                 let response = context
                     .gateway
                     .execute_transaction(Transaction::new(data, signature))
@@ -456,7 +1033,61 @@ impl SuiClientCommands {
 
                 SuiClientCommandResult::MergeCoin(response)
             }
-            SuiClientCommands::Switch { address, gateway } => {
+            SuiClientCommands::Batch {
+                file,
+                gas,
+                gas_budget,
+                estimate_gas,
+            } => {
+                let transactions: Vec<RPCTransactionRequestParams> =
+                    serde_json::from_str(&std::fs::read_to_string(&file)?).map_err(|e| {
+                        anyhow!("Invalid batch transaction file {}: {e}", file.display())
+                    })?;
+                let signer = context.active_address()?;
+                let estimate_gas = estimate_gas || gas_budget.is_none();
+                let inputs = transactions
+                    .iter()
+                    .flat_map(|transaction| match transaction {
+                        RPCTransactionRequestParams::TransferObjectRequestParams(params) => {
+                            vec![params.object_id]
+                        }
+                        RPCTransactionRequestParams::MoveCallRequestParams(params) => {
+                            object_arguments(&params.arguments)
+                        }
+                        RPCTransactionRequestParams::PublishRequestParams(_) => vec![],
+                    })
+                    .collect();
+                let (gas, gas_budget) =
+                    context.select_gas(signer, gas, gas_budget, &inputs).await?;
+                let mut data = context
+                    .gateway
+                    .batch_transaction(signer, transactions, Some(gas), gas_budget)
+                    .await?;
+                if estimate_gas {
+                    context.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = context.sign_transaction(&data).await?;
+                let response = context
+                    .gateway
+                    .execute_transaction(Transaction::new(data, signature))
+                    .await?
+                    .to_effect_response()?;
+                let cert = response.certificate;
+                let effects = response.effects;
+                if matches!(effects.status, SuiExecutionStatus::Failure { .. }) {
+                    return Err(anyhow!(
+                        "Error executing batch transaction: {:#?}",
+                        effects.status
+                    ));
+                }
+                SuiClientCommandResult::Batch(cert, effects)
+            }
+            SuiClientCommands::Tx { cmd } => cmd.execute(context).await?,
+            SuiClientCommands::Switch {
+                address,
+                gateway,
+                env,
+            } => {
                 if let Some(addr) = address {
                     if !context.config.accounts.contains(&addr) {
                         return Err(anyhow!("Address {} not managed by wallet", addr));
@@ -468,16 +1099,72 @@ impl SuiClientCommands {
                 if let Some(gateway) = &gateway {
                     // TODO: handle embedded gateway
                     context.config.gateway = GatewayType::RPC(gateway.clone());
+                    context.config.active_env = None;
+                    context.config.save()?;
+                }
+
+                if let Some(env) = &env {
+                    if context.config.get_env(env).is_none() {
+                        return Err(anyhow!("Environment {} not found in the config", env));
+                    }
+                    context.config.active_env = Some(env.clone());
                     context.config.save()?;
                 }
 
-                if Option::is_none(&address) && Option::is_none(&gateway) {
+                if Option::is_none(&address) && Option::is_none(&gateway) && Option::is_none(&env) {
                     return Err(anyhow!(
-                        "No address or gateway specified. Please Specify one."
+                        "No address, gateway or env specified. Please Specify one."
                     ));
                 }
 
-                SuiClientCommandResult::Switch(SwitchResponse { address, gateway })
+                SuiClientCommandResult::Switch(SwitchResponse {
+                    address,
+                    gateway,
+                    env,
+                })
+            }
+            SuiClientCommands::Envs => {
+                let active_env = context.config.active_env.as_ref();
+                let envs = context
+                    .config
+                    .envs
+                    .iter()
+                    .map(|env| EnvSummary {
+                        alias: env.alias.clone(),
+                        gateway: match &env.gateway {
+                            GatewayType::RPC(url) => url.clone(),
+                            GatewayType::Failover(config) => config.urls.join(", "),
+                            GatewayType::Embedded(_) => "embedded".to_string(),
+                        },
+                        active: active_env == Some(&env.alias),
+                    })
+                    .collect();
+                SuiClientCommandResult::Envs(envs)
+            }
+            SuiClientCommands::NewEnv {
+                alias,
+                mut rpc,
+                race_reads,
+                keystore,
+            } => {
+                if context.config.get_env(&alias).is_some() {
+                    return Err(anyhow!("Environment {} already exists", alias));
+                }
+                let gateway = if rpc.len() == 1 && !race_reads {
+                    GatewayType::RPC(rpc.remove(0))
+                } else {
+                    GatewayType::Failover(FailoverConfig {
+                        race_reads,
+                        ..FailoverConfig::new(rpc)
+                    })
+                };
+                context.config.envs.push(SuiEnv {
+                    alias: alias.clone(),
+                    gateway,
+                    keystore: keystore.map(KeystoreType::File),
+                });
+                context.config.save()?;
+                SuiClientCommandResult::NewEnv(alias)
             }
             SuiClientCommands::ActiveAddress => {
                 SuiClientCommandResult::ActiveAddress(context.active_address().ok())
@@ -504,7 +1191,8 @@ impl SuiClientCommands {
                     "mint",
                     vec![],
                     gas,
-                    gas_budget.unwrap_or(3000),
+                    gas_budget,
+                    false,
                     args,
                     context,
                 )
@@ -518,6 +1206,26 @@ impl SuiClientCommands {
                 let object_read = context.gateway.get_object(nft_id).await?;
                 SuiClientCommandResult::CreateExampleNFT(object_read)
             }
+            SuiClientCommands::SignRequest {
+                origin,
+                scope,
+                address,
+                data,
+            } => {
+                let data = Base64::decode(&data).map_err(|e| anyhow!(e))?;
+                let signer = match (address, scope) {
+                    (Some(address), _) => address,
+                    (None, IntentScope::TransactionData) => {
+                        TransactionData::from_signable_bytes(&data)
+                            .map_err(|e| anyhow!("Invalid transaction data: {e}"))?
+                            .signer()
+                    }
+                    (None, IntentScope::PersonalMessage) => context.active_address()?,
+                };
+                let request = SigningRequest::new(origin, scope, signer, &data)?;
+                let signature = context.sign_request(&request)?;
+                SuiClientCommandResult::SignRequest(SignRequestResponse::new(signer, &signature))
+            }
         });
         ret
     }
@@ -527,9 +1235,67 @@ pub struct WalletContext {
     pub config: PersistedConfig<SuiClientConfig>,
     pub keystore: Box<dyn Keystore>,
     pub gateway: GatewayClient,
+    /// Dry-run each transaction and ask for confirmation of its preview before signing it
+    pub preview_transactions: bool,
+    /// Resolves recipient names, set when the config has a name registry
+    pub name_resolver: Option<Arc<dyn NameResolver>>,
 }
 
 impl WalletContext {
+    /// Check that each of the `dependencies` of a package being published, as compiled locally,
+    /// is a module of a package that exists on-chain with the same bytecode.
+    pub async fn verify_published_dependencies(
+        &self,
+        dependencies: &[CompiledModule],
+    ) -> Result<(), anyhow::Error> {
+        let mut modules_by_package: BTreeMap<ObjectID, Vec<&CompiledModule>> = BTreeMap::new();
+        for module in dependencies {
+            modules_by_package
+                .entry(ObjectID::from(*module.self_id().address()))
+                .or_default()
+                .push(module);
+        }
+        for (package_id, modules) in modules_by_package {
+            let package = match self.gateway.get_object(package_id).await?.into_object() {
+                Ok(SuiObject {
+                    data: SuiData::Package(package),
+                    ..
+                }) => package,
+                Ok(_) => return Err(anyhow!("Dependency {package_id} is not a Move package")),
+                Err(_) => {
+                    return Err(anyhow!(
+                        "Dependency package {package_id} is not published on-chain, publish it \
+                        first and set its address in the Move.toml of the package"
+                    ))
+                }
+            };
+            if let Some(missing) = modules
+                .iter()
+                .map(|module| module.self_id().name().to_string())
+                .find(|name| !package.disassembled().contains_key(name))
+            {
+                return Err(anyhow!(
+                    "Module {missing} not found in the published dependency package {package_id}"
+                ));
+            }
+            let published = self.gateway.get_raw_package(package_id).await?;
+            for module in modules {
+                let name = module.self_id().name().to_string();
+                let mut bytes = Vec::new();
+                module
+                    .serialize(&mut bytes)
+                    .map_err(|e| anyhow!("Cannot serialize module {}: {e}", module.self_id()))?;
+                if published.module_map.get(&name) != Some(&bytes) {
+                    return Err(anyhow!(
+                        "Module {name} of the dependency package {package_id} does not match its \
+                        published bytecode, its local source may be out of date"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn new(config_path: &Path) -> Result<Self, anyhow::Error> {
         let config: SuiClientConfig = PersistedConfig::read(config_path).map_err(|err| {
             err.context(format!(
@@ -538,15 +1304,63 @@ impl WalletContext {
             ))
         })?;
         let config = config.persisted(config_path);
-        let keystore = config.keystore.init()?;
-        let gateway = config.gateway.init()?;
+        let keystore = config.active_keystore().init()?;
+        let gateway = config.active_gateway().init()?;
+        let name_resolver = config.name_registry.map(|registry| {
+            Arc::new(RegistryNameResolver::new(gateway.clone(), registry)) as Arc<dyn NameResolver>
+        });
         let context = Self {
             config,
             keystore,
             gateway,
+            preview_transactions: false,
+            name_resolver,
         };
         Ok(context)
     }
+
+    /// The address of `recipient`, resolving its name through the name registry if needed.
+    pub async fn resolve_recipient(
+        &self,
+        recipient: &Recipient,
+    ) -> Result<SuiAddress, anyhow::Error> {
+        match recipient {
+            Recipient::Address(address) => Ok(*address),
+            Recipient::Name(name) => {
+                let resolver = self.name_resolver.as_ref().ok_or_else(|| {
+                    anyhow!("Cannot resolve {name}, no name registry is set in the wallet config")
+                })?;
+                resolver
+                    .resolve(name)
+                    .await?
+                    .ok_or_else(|| anyhow!("Name {name} is not registered"))
+            }
+        }
+    }
+
+    /// Print the names of the addresses involved in `result`, if a name registry is set.
+    pub async fn print_names(&self, result: &SuiClientCommandResult) {
+        let resolver = match &self.name_resolver {
+            Some(resolver) => resolver,
+            None => return,
+        };
+        let addresses = result.addresses();
+        if addresses.is_empty() {
+            return;
+        }
+        match resolver.reverse_lookup(&addresses).await {
+            Ok(names) if !names.is_empty() => {
+                println!("{}", "----- Names ----".bold());
+                for (address, name) in names {
+                    println!("{address}: {name}");
+                }
+            }
+            Ok(_) => {}
+            // Names are only informative, the command itself succeeded.
+            Err(e) => info!("Cannot look up the names of the addresses: {e}"),
+        }
+    }
+
     pub fn active_address(&mut self) -> Result<SuiAddress, anyhow::Error> {
         if self.config.accounts.is_empty() {
             return Err(anyhow!(
@@ -590,6 +1404,149 @@ impl WalletContext {
         Ok(values_objects)
     }
 
+    /// Merge all the coins owned by the owner of `primary_coin` that have the same type into
+    /// it. A coin can only be used by one transaction of a batch, so coins are merged
+    /// pairwise in rounds, with at most `MERGE_ALL_BATCH_SIZE` merges per batch so that each
+    /// transaction stays within `gas_budget`.
+    pub async fn merge_all_coins(
+        &mut self,
+        primary_coin: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        estimate_gas: bool,
+    ) -> Result<MergeAllCoinsResponse, anyhow::Error> {
+        let signer = self.get_object_owner(&primary_coin).await?;
+        let primary = self.gateway.get_object(primary_coin).await?.into_object()?;
+        let (coin_type, _) = coin_type_and_value(&primary)
+            .ok_or_else(|| anyhow!("Object {primary_coin} is not a coin"))?;
+
+        // The gas coin cannot be merged by the transactions paying with it.
+        let gas = match gas {
+            Some(gas) => gas,
+            None => self
+                .gas_objects(signer)
+                .await?
+                .into_iter()
+                .filter(|(_, o)| o.id() != primary_coin)
+                .max_by_key(|(value, _)| *value)
+                .map(|(_, o)| o.id())
+                .ok_or_else(|| anyhow!("No gas coin available besides the primary coin"))?,
+        };
+
+        let mut coins = Vec::new();
+        for info in self.gateway.get_objects_owned_by_address(signer).await? {
+            if info.object_id == primary_coin || info.object_id == gas {
+                continue;
+            }
+            if let GetObjectDataResponse::Exists(o) =
+                self.gateway.get_object(info.object_id).await?
+            {
+                if let Some((type_, value)) = coin_type_and_value(&o) {
+                    if type_ == coin_type {
+                        coins.push((value, info.object_id));
+                    }
+                }
+            }
+        }
+        // Largest coins first, so that the primary coin absorbs them in the first round.
+        coins.sort_by(|a, b| b.cmp(a));
+        let mut coins: Vec<_> = std::iter::once(primary_coin)
+            .chain(coins.into_iter().map(|(_, id)| id))
+            .collect();
+
+        let type_tag = SuiTypeTag::from(parse_type_tag(&coin_type)?);
+        let mut merged_coins = 0;
+        let mut transactions = 0;
+        while coins.len() > 1 {
+            let merges = coins
+                .chunks(2)
+                .filter_map(|pair| match pair {
+                    [primary, coin] => Some((*primary, *coin)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for batch in merges.chunks(MERGE_ALL_BATCH_SIZE) {
+                let params = batch
+                    .iter()
+                    .map(|(primary, coin)| coin_join_params(&type_tag, *primary, *coin))
+                    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+                let mut data = self
+                    .gateway
+                    .batch_transaction(signer, params, Some(gas), gas_budget)
+                    .await?;
+                if estimate_gas {
+                    self.apply_gas_estimate(&mut data).await?;
+                }
+                let signature = self.sign_transaction(&data).await?;
+                let effects = self
+                    .gateway
+                    .execute_transaction(Transaction::new(data, signature))
+                    .await?
+                    .to_effect_response()?
+                    .effects;
+                if let SuiExecutionStatus::Failure { error } = effects.status {
+                    return Err(anyhow!(
+                        "Error merging coins after {merged_coins} merges: {error}"
+                    ));
+                }
+                merged_coins += batch.len();
+                transactions += 1;
+            }
+            coins = coins.chunks(2).map(|pair| pair[0]).collect();
+        }
+
+        let updated_coin = self.gateway.get_object(primary_coin).await?.into_object()?;
+        Ok(MergeAllCoinsResponse {
+            updated_coin,
+            merged_coins,
+            transactions,
+        })
+    }
+
+    /// Collect the balance of each coin type owned by `address`, and all of its objects.
+    pub async fn export_account(
+        &self,
+        address: SuiAddress,
+    ) -> Result<AccountExport, anyhow::Error> {
+        let object_refs = self.gateway.get_objects_owned_by_address(address).await?;
+        let mut balances: BTreeMap<String, CoinBalance> = BTreeMap::new();
+        let mut objects = Vec::with_capacity(object_refs.len());
+        for info in object_refs {
+            let coin = match self.gateway.get_object(info.object_id).await? {
+                GetObjectDataResponse::Exists(o) => coin_type_and_value(&o),
+                _ => None,
+            };
+            if let Some((coin_type, value)) = &coin {
+                let balance = balances
+                    .entry(coin_type.clone())
+                    .or_insert_with(|| CoinBalance {
+                        coin_type: coin_type.clone(),
+                        balance: 0,
+                        coin_count: 0,
+                    });
+                balance.balance += *value as u128;
+                balance.coin_count += 1;
+            }
+            let (coin_type, balance) = match coin {
+                Some((coin_type, value)) => (Some(coin_type), Some(value)),
+                None => (None, None),
+            };
+            objects.push(ExportedObject {
+                object_id: info.object_id,
+                version: info.version.value(),
+                digest: Base64::encode(info.digest),
+                type_: info.type_,
+                coin_type,
+                balance,
+            });
+        }
+        Ok(AccountExport {
+            address,
+            balances: balances.into_values().collect(),
+            objects,
+        })
+    }
+
     pub async fn get_object_owner(&self, id: &ObjectID) -> Result<SuiAddress, anyhow::Error> {
         let object = self.gateway.get_object(*id).await?.into_object()?;
         Ok(object.owner.get_owner_address()?)
@@ -606,6 +1563,183 @@ impl WalletContext {
         }
     }
 
+    /// Dry-run the transaction on the gateway and lower its gas budget to the estimated cost.
+    /// The budget is never raised above the one the transaction was built with, while the gas
+    /// price is raised to the one of the estimate, the reference gas price, if it is lower.
+    pub async fn apply_gas_estimate(
+        &self,
+        data: &mut TransactionData,
+    ) -> Result<(), anyhow::Error> {
+        let estimate = self.gateway.estimate_gas(data.clone()).await?;
+        info!(
+            gas_units = estimate.gas_units,
+            recommended_budget = estimate.recommended_budget,
+            "Estimated gas for transaction"
+        );
+        data.gas_budget = std::cmp::min(estimate.recommended_budget, data.gas_budget);
+        data.gas_price = std::cmp::max(estimate.gas_price, data.gas_price);
+        Ok(())
+    }
+
+    /// Show the preview of the dry run of `data` and ask the user to confirm it, if
+    /// `preview_transactions` is set.
+    pub async fn confirm_transaction(&self, data: &TransactionData) -> Result<(), anyhow::Error> {
+        if !self.preview_transactions {
+            return Ok(());
+        }
+        let preview = self.gateway.dry_run_transaction(data.clone()).await?;
+        println!("{}", "----- Transaction Preview ----".bold());
+        println!("{}", preview);
+        print!("Sign and execute this transaction [yN]? ");
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        if line.trim().to_lowercase() != "y" {
+            return Err(anyhow!("Transaction cancelled"));
+        }
+        Ok(())
+    }
+
+    /// Show a signing request from another application and sign it once the user approved it.
+    /// Requests are always confirmed, whether `preview_transactions` is set or not.
+    pub fn sign_request(&self, request: &SigningRequest) -> Result<Signature, anyhow::Error> {
+        self.sign_request_with(request, prompt_approval)
+    }
+
+    /// Sign `request` with the keystore key of its signer if `approve` accepts it.
+    pub fn sign_request_with(
+        &self,
+        request: &SigningRequest,
+        approve: impl FnOnce(&SigningRequest) -> Result<bool, anyhow::Error>,
+    ) -> Result<Signature, anyhow::Error> {
+        if !approve(request)? {
+            return Err(anyhow!("Signing request from {} rejected", request.origin));
+        }
+        Ok(self
+            .keystore
+            .sign(&request.signer, &request.signable_bytes())?)
+    }
+
+    /// Sign `data` with the keystore key of its sender, once the user confirmed it.
+    pub async fn sign_transaction(
+        &self,
+        data: &TransactionData,
+    ) -> Result<Signature, anyhow::Error> {
+        self.confirm_transaction(data).await?;
+        Ok(self.keystore.sign(&data.signer(), &data.to_bytes())?)
+    }
+
+    /// The gas coin and budget of a transaction sent by `signer`.
+    ///
+    /// Without `gas`, the smallest gas coin of `signer` covering the budget is picked, skipping
+    /// the `input_objects` of the transaction. If none is large enough, the other coins are
+    /// merged into the largest one first. Without `gas_budget`, the budget is the value of the
+    /// gas coin capped at `MAX_AUTO_GAS_BUDGET`, to be lowered by a gas estimate.
+    pub async fn select_gas(
+        &self,
+        signer: SuiAddress,
+        gas: Option<ObjectID>,
+        gas_budget: Option<u64>,
+        input_objects: &BTreeSet<ObjectID>,
+    ) -> Result<(ObjectID, u64), anyhow::Error> {
+        if let Some(gas) = gas {
+            let budget = match gas_budget {
+                Some(budget) => budget,
+                None => {
+                    let coin = self.gateway.get_object(gas).await?.into_object()?;
+                    let (_, value) = coin_type_and_value(&coin)
+                        .ok_or_else(|| anyhow!("Gas object {gas} is not a coin"))?;
+                    std::cmp::min(value, MAX_AUTO_GAS_BUDGET)
+                }
+            };
+            return Ok((gas, budget));
+        }
+
+        let mut coins = self
+            .gas_objects(signer)
+            .await?
+            .into_iter()
+            .map(|(value, object)| (value, object.id()))
+            .filter(|(_, id)| !input_objects.contains(id))
+            .collect::<Vec<_>>();
+        coins.sort();
+        let needed = gas_budget.unwrap_or(MAX_AUTO_GAS_BUDGET);
+        if let Some((_, id)) = coins.iter().find(|(value, _)| *value >= needed) {
+            return Ok((*id, needed));
+        }
+        match (gas_budget, coins.last().copied()) {
+            (_, None) => Err(anyhow!("Address {signer} has no gas coin to pay for gas")),
+            // Any coin will do, the estimate sets the actual budget.
+            (None, Some((value, id))) => Ok((id, value)),
+            (Some(budget), Some(_)) => {
+                Ok((self.merge_gas_coins(signer, coins, budget).await?, budget))
+            }
+        }
+    }
+
+    /// Merge gas `coins` of `signer`, sorted by value, into the largest one until it holds at
+    /// least `budget`. The merges are paid with the smallest coin.
+    async fn merge_gas_coins(
+        &self,
+        signer: SuiAddress,
+        coins: Vec<(u64, ObjectID)>,
+        budget: u64,
+    ) -> Result<ObjectID, anyhow::Error> {
+        let (payer_value, payer) = match coins.first() {
+            Some(coin) if coins.len() > 2 => *coin,
+            _ => {
+                return Err(anyhow!(
+                    "No gas coin of {signer} holds the gas budget {budget}, and there are not \
+                    enough coins to merge"
+                ))
+            }
+        };
+        let mut coins = coins[1..].iter().rev();
+        // Cannot fail, there are at least two coins besides the payer.
+        let (mut value, primary) = *coins.next().unwrap();
+        let mut merged = Vec::new();
+        for (coin_value, coin) in coins {
+            if value >= budget {
+                break;
+            }
+            value += coin_value;
+            merged.push(*coin);
+        }
+        if value < budget {
+            return Err(anyhow!(
+                "The gas coins of {signer} hold {value} in total without the coin paying for \
+                the merge, less than the gas budget {budget}"
+            ));
+        }
+
+        let type_tag = SuiTypeTag::from(GasCoin::type_().type_params[0].clone());
+        let params = merged
+            .iter()
+            .map(|coin| coin_join_params(&type_tag, primary, *coin))
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let merge_budget = std::cmp::min(payer_value, MAX_AUTO_GAS_BUDGET);
+        let mut data = self
+            .gateway
+            .batch_transaction(signer, params, Some(payer), merge_budget)
+            .await?;
+        self.apply_gas_estimate(&mut data).await?;
+        let signature = self.sign_transaction(&data).await?;
+        let effects = self
+            .gateway
+            .execute_transaction(Transaction::new(data, signature))
+            .await?
+            .to_effect_response()?
+            .effects;
+        if let SuiExecutionStatus::Failure { error } = effects.status {
+            return Err(anyhow!("Error merging gas coins: {error}"));
+        }
+        info!(
+            merged_coins = merged.len(),
+            "Merged gas coins into {primary} to cover the gas budget"
+        );
+        Ok(primary)
+    }
+
     /// Find a gas object which fits the budget
     pub async fn gas_for_owner_budget(
         &self,
@@ -631,6 +1765,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Publish(response) => {
                 write!(writer, "{}", response)?;
             }
+            SuiClientCommandResult::PublishPreview(preview) => {
+                write!(writer, "{}", preview)?;
+            }
             SuiClientCommandResult::Object(object_read) => {
                 let object = unwrap_err_to_string(|| Ok(object_read.object()?));
                 writeln!(writer, "{}", object)?;
@@ -645,6 +1782,15 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::TransferSui(cert, effects) => {
                 write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
             }
+            SuiClientCommandResult::Pay(cert, effects)
+            | SuiClientCommandResult::PayAll(cert, effects)
+            | SuiClientCommandResult::Batch(cert, effects)
+            | SuiClientCommandResult::Submit(cert, effects) => {
+                write!(writer, "{}", write_cert_and_effects(cert, effects)?)?;
+            }
+            SuiClientCommandResult::TransactionFile(path) => {
+                writeln!(writer, "Transaction written to {}", path.display())?;
+            }
             SuiClientCommandResult::Addresses(addresses) => {
                 writeln!(writer, "Showing {} results.", addresses.len())?;
                 for address in addresses {
@@ -683,6 +1829,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::NewAddress(address) => {
                 writeln!(writer, "Created new keypair for address : {}", &address)?;
             }
+            SuiClientCommandResult::AddWatchAddress(address) => {
+                writeln!(writer, "Watching address : {}", &address)?;
+            }
             SuiClientCommandResult::Gas(gases) => {
                 // TODO: generalize formatting of CLI
                 writeln!(
@@ -704,6 +1853,24 @@ impl Display for SuiClientCommandResult {
                     )?;
                 }
             }
+            SuiClientCommandResult::MergeAllCoins(response) => {
+                write!(writer, "{}", response)?;
+            }
+            SuiClientCommandResult::Export(output, content) => match output {
+                Some(output) => writeln!(writer, "Account exported to {}", output.display())?,
+                None => write!(writer, "{}", content)?,
+            },
+            SuiClientCommandResult::Faucet(coins) => {
+                writeln!(writer, "{}", "Received coins from the faucet:".bold())?;
+                writeln!(writer, " {0: ^42} | {1: ^11}", "Object ID", "Gas Value")?;
+                writeln!(
+                    writer,
+                    "----------------------------------------------------------"
+                )?;
+                for coin in coins {
+                    writeln!(writer, " {0: ^42} | {1: ^11}", coin.id, coin.amount)?;
+                }
+            }
             SuiClientCommandResult::SplitCoin(response) => {
                 write!(writer, "{}", response)?;
             }
@@ -713,6 +1880,25 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::Switch(response) => {
                 write!(writer, "{}", response)?;
             }
+            SuiClientCommandResult::Envs(envs) => {
+                writeln!(
+                    writer,
+                    " {0: ^3} | {1: ^15} | {2: ^40}",
+                    "", "Alias", "Gateway"
+                )?;
+                writeln!(writer, "{}", ["-"; 64].join(""))?;
+                for env in envs {
+                    let active = if env.active { "*" } else { "" };
+                    writeln!(
+                        writer,
+                        " {0: ^3} | {1: ^15} | {2: ^40}",
+                        active, env.alias, env.gateway
+                    )?;
+                }
+            }
+            SuiClientCommandResult::NewEnv(alias) => {
+                writeln!(writer, "Added new environment : {}", alias)?;
+            }
             SuiClientCommandResult::ActiveAddress(response) => {
                 match response {
                     Some(r) => write!(writer, "{}", r)?,
@@ -725,6 +1911,11 @@ impl Display for SuiClientCommandResult {
                 writeln!(writer, "{}\n", "Successfully created an ExampleNFT:".bold())?;
                 writeln!(writer, "{}", object)?;
             }
+            SuiClientCommandResult::SignRequest(response) => {
+                writeln!(writer, "Address : {}", response.address)?;
+                writeln!(writer, "Public Key Base64 : {}", response.public_key)?;
+                writeln!(writer, "Signature : {}", response.signature)?;
+            }
         }
         write!(f, "{}", writer.trim_end_matches('\n'))
     }
@@ -736,14 +1927,18 @@ pub async fn call_move(
     function: &str,
     type_args: Vec<TypeTag>,
     gas: Option<ObjectID>,
-    gas_budget: u64,
+    gas_budget: Option<u64>,
+    estimate_gas: bool,
     args: Vec<SuiJsonValue>,
     context: &mut WalletContext,
 ) -> Result<(SuiCertifiedTransaction, SuiTransactionEffects), anyhow::Error> {
     let gas_owner = context.try_get_object_owner(&gas).await?;
     let sender = gas_owner.unwrap_or(context.active_address()?);
+    let estimate_gas = estimate_gas || gas_budget.is_none();
+    let inputs = object_arguments(&args).into_iter().collect();
+    let (gas, gas_budget) = context.select_gas(sender, gas, gas_budget, &inputs).await?;
 
-    let data = context
+    let mut data = context
         .gateway
         .move_call(
             sender,
@@ -755,11 +1950,14 @@ pub async fn call_move(
                 .map(|arg| arg.try_into())
                 .collect::<Result<Vec<_>, _>>()?,
             args,
-            gas,
+            Some(gas),
             gas_budget,
         )
         .await?;
-    let signature = context.keystore.sign(&sender, &data.to_bytes())?;
+    if estimate_gas {
+        context.apply_gas_estimate(&mut data).await?;
+    }
+    let signature = context.sign_transaction(&data).await?;
     let transaction = Transaction::new(data, signature);
     let response = context
         .gateway
@@ -775,6 +1973,36 @@ pub async fn call_move(
     Ok((cert, effects))
 }
 
+/// The objects passed by ID in the arguments of a Move call.
+fn object_arguments(args: &[SuiJsonValue]) -> Vec<ObjectID> {
+    args.iter()
+        .filter_map(|arg| match arg.to_json_value() {
+            serde_json::Value::String(s) => ObjectID::from_hex_literal(&s).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A Move call of `0x2::coin::join` merging `coin` into `primary`.
+fn coin_join_params(
+    type_tag: &SuiTypeTag,
+    primary: ObjectID,
+    coin: ObjectID,
+) -> Result<RPCTransactionRequestParams, anyhow::Error> {
+    Ok(RPCTransactionRequestParams::MoveCallRequestParams(
+        MoveCallParams {
+            package_object_id: ObjectID::from(SUI_FRAMEWORK_ADDRESS),
+            module: COIN_MODULE_NAME.to_string(),
+            function: COIN_JOIN_FUNC_NAME.to_string(),
+            type_arguments: vec![type_tag.clone()],
+            arguments: vec![
+                SuiJsonValue::new(json!(primary.to_hex_literal()))?,
+                SuiJsonValue::new(json!(coin.to_hex_literal()))?,
+            ],
+        },
+    ))
+}
+
 fn unwrap_or<'a>(val: &'a Option<String>, default: &'a str) -> &'a str {
     match val {
         Some(v) => v,
@@ -796,13 +2024,7 @@ fn write_cert_and_effects(
 
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let s = unwrap_err_to_string(|| match self {
-            SuiClientCommandResult::Object(object_read) => {
-                let object = object_read.object()?;
-                Ok(serde_json::to_string_pretty(&object)?)
-            }
-            _ => Ok(serde_json::to_string_pretty(self)?),
-        });
+        let s = unwrap_err_to_string(|| Ok(serde_json::to_string_pretty(self)?));
         write!(f, "{}", s)
     }
 }
@@ -815,6 +2037,36 @@ fn unwrap_err_to_string<T: Display, F: FnOnce() -> Result<T, anyhow::Error>>(fun
 }
 
 impl SuiClientCommandResult {
+    /// The addresses involved in the result: object owners and transaction senders and
+    /// recipients.
+    pub fn addresses(&self) -> Vec<SuiAddress> {
+        let mut addresses = BTreeSet::new();
+        match self {
+            SuiClientCommandResult::Object(GetObjectDataResponse::Exists(object)) => {
+                addresses.extend(object.owner.get_owner_address().ok());
+            }
+            SuiClientCommandResult::Call(cert, effects)
+            | SuiClientCommandResult::Transfer(_, cert, effects)
+            | SuiClientCommandResult::TransferSui(cert, effects)
+            | SuiClientCommandResult::Pay(cert, effects)
+            | SuiClientCommandResult::PayAll(cert, effects)
+            | SuiClientCommandResult::Batch(cert, effects)
+            | SuiClientCommandResult::Submit(cert, effects) => {
+                addresses.insert(cert.data.sender);
+                addresses.extend(
+                    effects
+                        .created
+                        .iter()
+                        .chain(&effects.mutated)
+                        .chain(&effects.unwrapped)
+                        .filter_map(|object| object.owner.get_owner_address().ok()),
+                );
+            }
+            _ => {}
+        }
+        addresses.into_iter().collect()
+    }
+
     pub fn print(&self, pretty: bool) {
         let line = if pretty {
             format!("{self}")
@@ -830,10 +2082,12 @@ impl SuiClientCommandResult {
     }
 }
 
+/// The output of a client command, serialized as `{"kind": ..., "result": ...}` in JSON mode.
 #[derive(Serialize)]
-#[serde(untagged)]
+#[serde(tag = "kind", content = "result", rename_all = "camelCase")]
 pub enum SuiClientCommandResult {
     Publish(PublishResponse),
+    PublishPreview(SuiPublishPreview),
     Object(GetObjectDataResponse),
     Call(SuiCertifiedTransaction, SuiTransactionEffects),
     Transfer(
@@ -843,16 +2097,251 @@ pub enum SuiClientCommandResult {
         SuiTransactionEffects,
     ),
     TransferSui(SuiCertifiedTransaction, SuiTransactionEffects),
+    Pay(SuiCertifiedTransaction, SuiTransactionEffects),
+    PayAll(SuiCertifiedTransaction, SuiTransactionEffects),
     Addresses(Vec<SuiAddress>),
     Objects(Vec<SuiObjectInfo>),
     SyncClientState,
     NewAddress(SuiAddress),
+    AddWatchAddress(SuiAddress),
     Gas(Vec<GasCoin>),
+    Export(#[serde(skip)] Option<PathBuf>, String),
+    Faucet(Vec<FaucetCoinInfo>),
     SplitCoin(SplitCoinResponse),
     MergeCoin(MergeCoinResponse),
+    MergeAllCoins(MergeAllCoinsResponse),
+    Batch(SuiCertifiedTransaction, SuiTransactionEffects),
+    TransactionFile(PathBuf),
+    Submit(SuiCertifiedTransaction, SuiTransactionEffects),
     Switch(SwitchResponse),
+    Envs(Vec<EnvSummary>),
+    NewEnv(String),
     ActiveAddress(Option<SuiAddress>),
     CreateExampleNFT(GetObjectDataResponse),
+    SignRequest(SignRequestResponse),
+}
+
+/// Upper bound of the gas budget of a transaction built without one, before it is lowered by
+/// a gas estimate.
+pub const MAX_AUTO_GAS_BUDGET: u64 = 10_000;
+
+/// Maximum number of coin merges put in one batch transaction by `merge-coin --all`.
+pub const MERGE_ALL_BATCH_SIZE: usize = 50;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequestResponse {
+    pub address: SuiAddress,
+    /// Base64 encoded public key
+    pub public_key: String,
+    /// Base64 encoded signature
+    pub signature: String,
+}
+
+impl SignRequestResponse {
+    pub fn new(address: SuiAddress, signature: &Signature) -> Self {
+        Self {
+            address,
+            public_key: Base64::encode(signature.public_key_bytes()),
+            signature: Base64::encode(signature.signature_bytes()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeAllCoinsResponse {
+    /// The primary coin after all the merges
+    pub updated_coin: SuiParsedObject,
+    /// Number of coins merged into other coins
+    pub merged_coins: usize,
+    /// Number of transactions executed
+    pub transactions: usize,
+}
+
+impl Display for MergeAllCoinsResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(
+            writer,
+            "{}",
+            format!(
+                "Merged {} coins in {} transactions",
+                self.merged_coins, self.transactions
+            )
+            .bold()
+        )?;
+        writeln!(writer, "{}", "----- Updated Coin -----".bold())?;
+        writeln!(writer, "{}", self.updated_coin)?;
+        write!(f, "{}", writer)
+    }
+}
+
+/// Amounts to split out of a coin of value `value` to end up with `count` coins of equal
+/// value, any remainder staying in the original coin.
+fn equal_split_amounts(value: u64, count: u64) -> Result<Vec<u64>, anyhow::Error> {
+    if count < 2 {
+        return Err(anyhow!("Coin must be split into at least 2 coins"));
+    }
+    let amount = value / count;
+    if amount == 0 {
+        return Err(anyhow!(
+            "Coin value {value} is too small to split into {count} coins"
+        ));
+    }
+    Ok(vec![amount; (count - 1) as usize])
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+/// The coin balances and owned objects of an address, as exported by the `export` command.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountExport {
+    pub address: SuiAddress,
+    pub balances: Vec<CoinBalance>,
+    pub objects: Vec<ExportedObject>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalance {
+    pub coin_type: String,
+    pub balance: u128,
+    pub coin_count: usize,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedObject {
+    pub object_id: ObjectID,
+    pub version: u64,
+    pub digest: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Type of the coin and its value, if the object is a coin
+    pub coin_type: Option<String>,
+    pub balance: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct CsvRecord<'a> {
+    kind: &'static str,
+    object_id: Option<ObjectID>,
+    version: Option<u64>,
+    digest: Option<&'a str>,
+    #[serde(rename = "type")]
+    type_: Option<&'a str>,
+    coin_type: Option<&'a str>,
+    balance: Option<u128>,
+    coin_count: Option<usize>,
+}
+
+impl AccountExport {
+    /// Render the export as a single CSV table, with one `balance` row per coin type followed
+    /// by one `object` row per owned object.
+    pub fn to_csv(&self) -> Result<String, anyhow::Error> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        for balance in &self.balances {
+            writer.serialize(CsvRecord {
+                kind: "balance",
+                object_id: None,
+                version: None,
+                digest: None,
+                type_: None,
+                coin_type: Some(&balance.coin_type),
+                balance: Some(balance.balance),
+                coin_count: Some(balance.coin_count),
+            })?;
+        }
+        for object in &self.objects {
+            writer.serialize(CsvRecord {
+                kind: "object",
+                object_id: Some(object.object_id),
+                version: Some(object.version),
+                digest: Some(&object.digest),
+                type_: Some(&object.type_),
+                coin_type: object.coin_type.as_deref(),
+                balance: object.balance.map(u128::from),
+                coin_count: None,
+            })?;
+        }
+        Ok(String::from_utf8(writer.into_inner()?)?)
+    }
+}
+
+/// Return the coin type and value of `object` if it is a `0x2::coin::Coin`.
+fn coin_type_and_value(object: &SuiParsedObject) -> Option<(String, u64)> {
+    let move_object = match &object.data {
+        SuiData::MoveObject(o) => o,
+        SuiData::Package(_) => return None,
+    };
+    let coin_type = match parse_type_tag(&move_object.type_).ok()? {
+        TypeTag::Struct(tag)
+            if tag.address == SUI_FRAMEWORK_ADDRESS
+                && tag.module.as_ident_str() == COIN_MODULE_NAME
+                && tag.name.as_ident_str() == COIN_STRUCT_NAME =>
+        {
+            tag.type_params.first()?.to_string()
+        }
+        _ => return None,
+    };
+    match &move_object.fields {
+        SuiMoveStruct::WithFields(fields) | SuiMoveStruct::WithTypes { fields, .. } => {
+            match fields.get("balance") {
+                Some(SuiMoveValue::Number(value)) => Some((coin_type, *value)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// A coin sent by the faucet, as reported in its `/gas` response.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FaucetCoinInfo {
+    pub amount: u64,
+    pub id: ObjectID,
+}
+
+#[derive(Deserialize)]
+struct FaucetResponse {
+    transferred_gas_objects: Vec<FaucetCoinInfo>,
+    error: Option<String>,
+}
+
+/// Ask the faucet at `url` to send gas coins to `recipient`.
+pub(crate) async fn request_faucet_coins(
+    url: &str,
+    recipient: SuiAddress,
+) -> Result<Vec<FaucetCoinInfo>, anyhow::Error> {
+    let gas_url = format!("{}/gas", url.trim_end_matches('/'));
+    let request = json!({ "FixedAmountRequest": { "recipient": recipient } });
+    let response = reqwest::Client::new()
+        .post(&gas_url)
+        .json(&request)
+        .send()
+        .await?;
+    let status = response.status();
+    let text = response.text().await?;
+    let response: FaucetResponse = serde_json::from_str(&text)
+        .map_err(|_| anyhow!("Faucet request failed with status {status}: {text}"))?;
+    if let Some(error) = response.error {
+        return Err(anyhow!("Faucet request failed: {error}"));
+    }
+    Ok(response.transferred_gas_objects)
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvSummary {
+    pub alias: String,
+    /// The gateway URLs, or "embedded" for an embedded gateway
+    pub gateway: String,
+    pub active: bool,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -860,6 +2349,7 @@ pub struct SwitchResponse {
     /// Active address
     pub address: Option<SuiAddress>,
     pub gateway: Option<String>,
+    pub env: Option<String>,
 }
 
 impl Display for SwitchResponse {
@@ -871,6 +2361,9 @@ impl Display for SwitchResponse {
         if let Some(gateway) = &self.gateway {
             writeln!(writer, "Active gateway switched to {}", gateway)?;
         }
+        if let Some(env) = &self.env {
+            writeln!(writer, "Active environment switched to {}", env)?;
+        }
         write!(f, "{}", writer)
     }
 }
@@ -13,7 +13,7 @@ pub use sui_config::PersistedConfig;
 
 pub use sui_config::utils;
 
-pub use sui_gateway::config::{GatewayConfig, GatewayType};
+pub use sui_gateway::config::{FailoverConfig, GatewayConfig, GatewayType};
 
 #[serde_as]
 #[derive(Serialize, Deserialize)]
@@ -23,10 +23,57 @@ pub struct SuiClientConfig {
     pub keystore: KeystoreType,
     pub gateway: GatewayType,
     pub active_address: Option<SuiAddress>,
+    /// Named environments the client can switch between, see `sui client switch --env`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub envs: Vec<SuiEnv>,
+    /// Alias of the active environment. The gateway and keystore of the config are used when
+    /// no environment is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_env: Option<String>,
+    /// On-chain registry object used to resolve recipient names such as `alice.sui`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_registry: Option<ObjectID>,
 }
 
 impl Config for SuiClientConfig {}
 
+impl SuiClientConfig {
+    pub fn get_env(&self, alias: &str) -> Option<&SuiEnv> {
+        self.envs.iter().find(|env| env.alias == alias)
+    }
+
+    pub fn get_active_env(&self) -> Option<&SuiEnv> {
+        self.active_env
+            .as_ref()
+            .and_then(|alias| self.get_env(alias))
+    }
+
+    /// The gateway of the active environment, or the config gateway if there is none.
+    pub fn active_gateway(&self) -> &GatewayType {
+        self.get_active_env()
+            .map(|env| &env.gateway)
+            .unwrap_or(&self.gateway)
+    }
+
+    /// The keystore of the active environment, or the config keystore if there is none or if
+    /// the environment does not have its own keystore.
+    pub fn active_keystore(&self) -> &KeystoreType {
+        self.get_active_env()
+            .and_then(|env| env.keystore.as_ref())
+            .unwrap_or(&self.keystore)
+    }
+}
+
+/// A named network environment (e.g. localnet, devnet), so that working against one network
+/// does not overwrite the settings of another.
+#[derive(Serialize, Deserialize)]
+pub struct SuiEnv {
+    pub alias: String,
+    pub gateway: GatewayType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<KeystoreType>,
+}
+
 impl Display for SuiClientConfig {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut writer = String::new();
@@ -37,8 +84,11 @@ impl Display for SuiClientConfig {
             Some(r) => writeln!(writer, "{}", r)?,
             None => writeln!(writer, "None")?,
         };
-        writeln!(writer, "{}", self.keystore)?;
-        write!(writer, "{}", self.gateway)?;
+        if let Some(env) = &self.active_env {
+            writeln!(writer, "Active environment : {}", env)?;
+        }
+        writeln!(writer, "{}", self.active_keystore())?;
+        write!(writer, "{}", self.active_gateway())?;
 
         write!(f, "{}", writer)
     }
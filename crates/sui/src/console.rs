@@ -12,8 +12,13 @@ use clap::CommandFactory;
 use clap::FromArgMatches;
 use clap::Parser;
 use colored::Colorize;
+use std::collections::BTreeMap;
 use std::io::{stderr, Write};
 use std::ops::Deref;
+
+/// Name of the console history file, kept next to the client config.
+const SUI_CONSOLE_HISTORY: &str = "console_history";
+
 const SUI: &str = "   _____       _    ______                       __   
   / ___/__  __(_)  / ____/___  ____  _________  / /__ 
   \\__ \\/ / / / /  / /   / __ \\/ __ \\/ ___/ __ \\/ / _ \\
@@ -28,6 +33,9 @@ pub struct ConsoleOpts {
     /// Returns command outputs in JSON format.
     #[clap(long, global = true)]
     pub json: bool,
+    /// Sign transactions without showing their preview and asking for confirmation.
+    #[clap(long, short = 'y', global = true)]
+    pub yes: bool,
 }
 
 pub async fn start_console(
@@ -51,16 +59,85 @@ pub async fn start_console(
     writeln!(out, "Welcome to the Sui interactive console.")?;
     writeln!(out)?;
 
+    let history_file = context
+        .config
+        .path()
+        .parent()
+        .map(|dir| dir.join(SUI_CONSOLE_HISTORY));
+    let completion_values = initial_completion_values(&context).await;
+
     let mut shell = Shell::new(
         "sui>-$ ",
         context,
         ClientCommandHandler,
         CommandStructure::from_clap(&install_shell_plugins(app)),
     );
+    if let Some(history_file) = history_file {
+        shell = shell.with_history_file(history_file);
+    }
+    if let Ok(mut cache) = shell.completion_cache().write() {
+        *cache = completion_values;
+    }
 
     shell.run_async(out, err).await
 }
 
+/// Completion values for the managed addresses, the environment aliases and the objects the
+/// gateway knows of for these addresses.
+async fn initial_completion_values(context: &WalletContext) -> BTreeMap<CacheKey, Vec<String>> {
+    let aliases = context
+        .config
+        .envs
+        .iter()
+        .map(|env| env.alias.clone())
+        .collect::<Vec<_>>();
+    let mut objects = Vec::new();
+    for address in &context.config.accounts {
+        if let Ok(refs) = context.gateway.get_objects_owned_by_address(*address).await {
+            objects.extend(refs);
+        }
+    }
+    let mut cache = BTreeMap::new();
+    cache.insert(CacheKey::flag("--env"), aliases);
+    update_completion_cache(
+        &mut cache,
+        &SuiClientCommandResult::Addresses(context.config.accounts.clone()),
+    );
+    update_completion_cache(&mut cache, &SuiClientCommandResult::Objects(objects));
+    cache
+}
+
+// TODO: Completion data are keyed by strings, are there ways to make it more error proof?
+fn update_completion_cache(
+    cache: &mut BTreeMap<CacheKey, Vec<String>>,
+    result: &SuiClientCommandResult,
+) {
+    match result {
+        SuiClientCommandResult::Addresses(addresses) => {
+            let addresses = addresses
+                .iter()
+                .map(|addr| format!("{addr}"))
+                .collect::<Vec<_>>();
+            cache.insert(CacheKey::flag("--address"), addresses.clone());
+            cache.insert(CacheKey::flag("--to"), addresses);
+        }
+        SuiClientCommandResult::Objects(objects) => {
+            let objects = objects
+                .iter()
+                .map(|oref| format!("{}", oref.object_id))
+                .collect::<Vec<_>>();
+            cache.insert(CacheKey::new("object", "--id"), objects.clone());
+            cache.insert(CacheKey::flag("--gas"), objects.clone());
+            cache.insert(CacheKey::flag("--coin-object-id"), objects);
+        }
+        SuiClientCommandResult::Envs(envs) => {
+            let aliases = envs.iter().map(|env| env.alias.clone()).collect();
+            cache.insert(CacheKey::flag("--env"), aliases);
+        }
+        _ => {}
+    }
+}
+
 struct ClientCommandHandler;
 
 #[async_trait]
@@ -94,33 +171,17 @@ async fn handle_command(
     completion_cache: CompletionCache,
 ) -> Result<bool, anyhow::Error> {
     let wallet_opts = wallet_opts?;
+    context.preview_transactions = !wallet_opts.yes;
     let result = wallet_opts.command.execute(context).await?;
 
     // Update completion cache
-    // TODO: Completion data are keyed by strings, are there ways to make it more error proof?
     if let Ok(mut cache) = completion_cache.write() {
-        match result {
-            SuiClientCommandResult::Addresses(ref addresses) => {
-                let addresses = addresses
-                    .iter()
-                    .map(|addr| format!("{addr}"))
-                    .collect::<Vec<_>>();
-                cache.insert(CacheKey::flag("--address"), addresses.clone());
-                cache.insert(CacheKey::flag("--to"), addresses);
-            }
-            SuiClientCommandResult::Objects(ref objects) => {
-                let objects = objects
-                    .iter()
-                    .map(|oref| format!("{}", oref.object_id))
-                    .collect::<Vec<_>>();
-                cache.insert(CacheKey::new("object", "--id"), objects.clone());
-                cache.insert(CacheKey::flag("--gas"), objects.clone());
-                cache.insert(CacheKey::flag("--coin-object-id"), objects);
-            }
-            _ => {}
-        }
+        update_completion_cache(&mut cache, &result);
     }
     result.print(!wallet_opts.json);
+    if !wallet_opts.json {
+        context.print_names(&result).await;
+    }
 
     // Quit shell after gateway switch
     if matches!(
@@ -128,7 +189,7 @@ async fn handle_command(
         SuiClientCommandResult::Switch(SwitchResponse {
             gateway: Some(_),
             ..
-        })
+        }) | SuiClientCommandResult::Switch(SwitchResponse { env: Some(_), .. })
     ) {
         println!("Gateway switch completed, please restart Sui console.");
         return Ok(true);
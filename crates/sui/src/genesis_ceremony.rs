@@ -43,8 +43,20 @@ pub enum CeremonyCommand {
     AddValidator {
         #[clap(long)]
         name: String,
+        /// The protocol keypair of the validator
         #[clap(long)]
         key_file: PathBuf,
+        /// The keypair the validator authenticates with on the network, its protocol key if
+        /// not given
+        #[clap(long)]
+        network_key_file: Option<PathBuf>,
+        /// The keypair of the consensus workers of the validator, its protocol key if not given
+        #[clap(long)]
+        worker_key_file: Option<PathBuf>,
+        /// The keypair of the account holding the stake of the validator, its protocol key if
+        /// not given. Only its public key is read into genesis
+        #[clap(long)]
+        account_key_file: Option<PathBuf>,
         #[clap(long)]
         network_address: Multiaddr,
         #[clap(long)]
@@ -95,6 +107,9 @@ pub fn run(cmd: Ceremony) -> Result<()> {
         CeremonyCommand::AddValidator {
             name,
             key_file,
+            network_key_file,
+            worker_key_file,
+            account_key_file,
             network_address,
             narwhal_primary_to_primary,
             narwhal_worker_to_primary,
@@ -104,9 +119,16 @@ pub fn run(cmd: Ceremony) -> Result<()> {
         } => {
             let mut builder = Builder::load(&dir)?;
             let keypair = read_keypair_from_file(key_file)?;
+            let public_key_of = |file: Option<PathBuf>| -> Result<Option<PublicKeyBytes>> {
+                file.map(|file| Ok(read_keypair_from_file(file)?.public().into()))
+                    .transpose()
+            };
             builder = builder.add_validator(sui_config::ValidatorInfo {
                 name,
                 public_key: keypair.public().into(),
+                network_key: public_key_of(network_key_file)?,
+                worker_key: public_key_of(worker_key_file)?,
+                account_key: public_key_of(account_key_file)?,
                 stake: 1,
                 delegation: 0,
                 network_address,
@@ -136,7 +158,7 @@ pub fn run(cmd: Ceremony) -> Result<()> {
         CeremonyCommand::Build => {
             let builder = Builder::load(&dir)?;
 
-            let genesis = builder.build();
+            let genesis = builder.build()?;
 
             genesis.save(dir.join(SUI_GENESIS_FILENAME))?;
         }
@@ -148,7 +170,7 @@ pub fn run(cmd: Ceremony) -> Result<()> {
 
             let builder = Builder::load(&dir)?;
 
-            let built_genesis = builder.build();
+            let built_genesis = builder.build()?;
             let built_genesis_bytes = built_genesis.to_bytes();
 
             if built_genesis != loaded_genesis || built_genesis_bytes != loaded_genesis_bytes {
@@ -237,9 +259,13 @@ mod test {
         let validators = (0..10)
             .map(|i| {
                 let keypair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
+                let network_keypair = get_key_pair_from_rng(&mut rand::rngs::OsRng).1;
                 let info = ValidatorInfo {
                     name: format!("validator-{i}"),
                     public_key: PublicKeyBytes::from(keypair.public()),
+                    network_key: Some(PublicKeyBytes::from(network_keypair.public())),
+                    worker_key: None,
+                    account_key: None,
                     stake: 1,
                     delegation: 0,
                     network_address: utils::new_network_address(),
@@ -251,7 +277,9 @@ mod test {
                 };
                 let key_file = dir.path().join(format!("{}.key", info.name));
                 write_keypair_to_file(&keypair, &key_file).unwrap();
-                (key_file, info)
+                let network_key_file = dir.path().join(format!("{}-network.key", info.name));
+                write_keypair_to_file(&network_keypair, &network_key_file).unwrap();
+                (key_file, network_key_file, info)
             })
             .collect::<Vec<_>>();
 
@@ -263,12 +291,15 @@ mod test {
         command.run()?;
 
         // Add the validators
-        for (key_file, validator) in &validators {
+        for (key_file, network_key_file, validator) in &validators {
             let command = Ceremony {
                 path: Some(dir.path().into()),
                 command: CeremonyCommand::AddValidator {
                     name: validator.name().to_owned(),
                     key_file: key_file.into(),
+                    network_key_file: Some(network_key_file.into()),
+                    worker_key_file: None,
+                    account_key_file: None,
                     network_address: validator.network_address().to_owned(),
                     narwhal_primary_to_primary: validator.narwhal_primary_to_primary.clone(),
                     narwhal_worker_to_primary: validator.narwhal_worker_to_primary.clone(),
@@ -286,9 +317,19 @@ mod test {
             command: CeremonyCommand::Build,
         };
         command.run()?;
+        // Genesis carries the network keys of the validators
+        let genesis = Builder::load(Utf8PathBuf::try_from(dir.path().to_owned())?)?.build()?;
+        for (_, _, validator) in &validators {
+            let info = genesis
+                .validator_set()
+                .iter()
+                .find(|info| info.public_key() == validator.public_key())
+                .unwrap();
+            assert_eq!(info.network_key(), validator.network_key());
+        }
 
         // Have all the validators verify and sign genesis
-        for (key, _validator) in &validators {
+        for (key, _, _validator) in &validators {
             let command = Ceremony {
                 path: Some(dir.path().into()),
                 command: CeremonyCommand::VerifyAndSign {
@@ -3,15 +3,22 @@
 
 use anyhow::anyhow;
 use clap::*;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use sui_config::{Config, NodeConfig};
 use sui_sdk::crypto::{Keystore, SuiKeystore};
-use sui_types::base_types::{decode_bytes_hex, encode_bytes_hex};
-use sui_types::crypto::KeypairTraits;
+use sui_types::base_types::{decode_bytes_hex, encode_bytes_hex, SUI_ADDRESS_LENGTH};
+use sui_types::crypto::{KeypairTraits, PrivateKey, PublicKey, ToFromBytes};
 use sui_types::sui_serde::{Base64, Encoding};
 use sui_types::{
     base_types::SuiAddress,
-    crypto::{get_key_pair, EncodeDecodeBase64, KeyPair},
+    crypto::{get_key_pair, get_key_pair_from_bytes, EncodeDecodeBase64, KeyPair},
 };
 use tracing::info;
 
@@ -19,8 +26,27 @@ use tracing::info;
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
 pub enum KeyToolCommand {
-    /// Generate a new keypair
-    Generate,
+    /// Generate new keypairs, each written to a `<public key>.key` file by default
+    Generate {
+        /// The number of keypairs to generate
+        #[clap(long, default_value = "1")]
+        count: usize,
+        #[clap(long, arg_enum, default_value = "ed25519", ignore_case = true)]
+        scheme: SignatureScheme,
+        /// Only generate keypairs whose address starts with this Hex prefix
+        #[clap(long)]
+        vanity_prefix: Option<String>,
+        /// The number of threads searching for vanity addresses, the number of CPUs by default
+        #[clap(long, requires = "vanity-prefix")]
+        threads: Option<usize>,
+        /// Add the keypairs to the keystore instead of writing them to files
+        #[clap(long, conflicts_with = "output")]
+        add_to_keystore: bool,
+        /// Write all the keypairs to this file instead, one line `<address> <keypair>` per
+        /// keypair, with the keypair Base64 encoded
+        #[clap(long)]
+        output: Option<PathBuf>,
+    },
     Show {
         file: PathBuf,
     },
@@ -37,41 +63,167 @@ pub enum KeyToolCommand {
         #[clap(long)]
         data: String,
     },
+    /// Add a key to the keystore from a mnemonic phrase, or from a Hex, Base64 or PEM encoded
+    /// private key
+    Import {
+        /// The mnemonic phrase (quoted), or the encoded private key
+        input: String,
+        #[clap(long, arg_enum, default_value = "ed25519", ignore_case = true)]
+        scheme: SignatureScheme,
+    },
+    /// Print the private key of an address in the keystore
+    Export {
+        #[clap(long, parse(try_from_str = decode_bytes_hex))]
+        address: SuiAddress,
+        /// Do not ask for confirmation before printing the key
+        #[clap(long)]
+        yes: bool,
+    },
+    /// Print the Hex, Base64 and PEM encodings of a key and its address. Private keys are read
+    /// as by `import`, public keys are Hex, Base64 or PEM encoded. Mnemonic phrases cannot be
+    /// printed back, they are only derived from
+    Convert {
+        /// The mnemonic phrase (quoted), or the encoded key
+        input: String,
+        /// The input is a public key, ed25519 public and private keys having the same length
+        #[clap(long)]
+        public: bool,
+        #[clap(long, arg_enum, default_value = "ed25519", ignore_case = true)]
+        scheme: SignatureScheme,
+    },
+    /// Generate a new network, worker or account key of a validator into its node config,
+    /// splitting it off the protocol key if it was not yet. The replaced keypair is written to a
+    /// `<kind>-<public key>.key` file next to the config, and so is the keypair of a new
+    /// account, which is to be moved off the node. The protocol key cannot be rotated yet, the
+    /// committee keeps naming the validator by the key it has in genesis
+    RotateValidatorKey {
+        #[clap(long)]
+        config: PathBuf,
+        #[clap(long, arg_enum)]
+        kind: ValidatorKeyKind,
+    },
 }
 
-impl KeyToolCommand {
-    pub fn execute(self, keystore: SuiKeystore) -> Result<(), anyhow::Error> {
+/// The keys of a validator, see [`NodeConfig`].
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidatorKeyKind {
+    /// Signs the votes and checkpoints of the validator, and names it in the committee
+    Protocol,
+    /// Authenticates the validator on the network
+    Network,
+    /// Names the consensus workers of the validator
+    Worker,
+    /// Owns the stake of the validator
+    Account,
+}
+
+impl Display for ValidatorKeyKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            KeyToolCommand::Generate => {
-                let (_address, keypair) = get_key_pair();
+            ValidatorKeyKind::Protocol => write!(f, "protocol"),
+            ValidatorKeyKind::Network => write!(f, "network"),
+            ValidatorKeyKind::Worker => write!(f, "worker"),
+            ValidatorKeyKind::Account => write!(f, "account"),
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum SignatureScheme {
+    Ed25519,
+}
+
+/// The BIP-44 path of the first Sui account (coin type 784), all the levels are hardened as
+/// required by SLIP-0010 for ed25519.
+pub const DERIVATION_PATH: [u32; 5] = [44, 784, 0, 0, 0];
 
-                let hex = encode_bytes_hex(keypair.public());
-                let file_name = format!("{hex}.key");
-                write_keypair_to_file(&keypair, &file_name)?;
-                println!("Ed25519 key generated and saved to '{file_name}'");
+impl KeyToolCommand {
+    pub fn execute(self, mut keystore: SuiKeystore) -> Result<KeyToolCommandResult, anyhow::Error> {
+        Ok(match self {
+            KeyToolCommand::Generate {
+                count,
+                scheme,
+                vanity_prefix,
+                threads,
+                add_to_keystore,
+                output,
+            } => {
+                match scheme {
+                    SignatureScheme::Ed25519 => {}
+                }
+                let keypairs = match vanity_prefix {
+                    Some(prefix) => {
+                        let threads = match threads {
+                            Some(threads) => threads,
+                            None => thread::available_parallelism()?.get(),
+                        };
+                        generate_vanity_key_pairs(&prefix, count, threads)?
+                    }
+                    None => (0..count).map(|_| get_key_pair().1).collect(),
+                };
+
+                let mut keys = Vec::with_capacity(keypairs.len());
+                let mut lines = String::new();
+                for keypair in keypairs {
+                    let address: SuiAddress = keypair.public().into();
+                    let public_key = encode_bytes_hex(keypair.public());
+                    let file = if add_to_keystore {
+                        keystore.add_key(keypair)?;
+                        None
+                    } else if output.is_some() {
+                        lines.push_str(&format!("{address} {}\n", keypair.encode_base64()));
+                        output.clone()
+                    } else {
+                        let file = PathBuf::from(format!("{public_key}.key"));
+                        write_keypair_to_file(&keypair, &file)?;
+                        Some(file)
+                    };
+                    keys.push(GeneratedKey {
+                        address,
+                        public_key,
+                        file,
+                    });
+                }
+                if let Some(output) = output {
+                    fs::write(output, lines)?;
+                }
+                KeyToolCommandResult::Generate(keys)
             }
 
             KeyToolCommand::Show { file } => {
                 let keypair = read_keypair_from_file(file)?;
-                println!("Public Key: {}", encode_bytes_hex(keypair.public()));
+                KeyToolCommandResult::Show {
+                    public_key: encode_bytes_hex(keypair.public()),
+                }
             }
 
             KeyToolCommand::Unpack { keypair } => {
-                store_and_print_keypair(keypair.public().into(), keypair)
+                let address = keypair.public().into();
+                KeyToolCommandResult::Unpack {
+                    address,
+                    file: store_keypair(address, keypair)?,
+                }
             }
             KeyToolCommand::List => {
-                println!(
-                    " {0: ^42} | {1: ^45} ",
-                    "Sui Address", "Public Key (Base64)"
+                let mut keys = keystore
+                    .key_pairs()
+                    .into_iter()
+                    .map(|keypair| KeyInfo {
+                        address: keypair.public().into(),
+                        public_key: Some(Base64::encode(keypair.public().as_ref())),
+                    })
+                    .collect::<Vec<_>>();
+                keys.extend(
+                    keystore
+                        .watch_only_addresses()
+                        .into_iter()
+                        .map(|address| KeyInfo {
+                            address,
+                            public_key: None,
+                        }),
                 );
-                println!("{}", ["-"; 91].join(""));
-                for keypair in keystore.key_pairs() {
-                    println!(
-                        " {0: ^42} | {1: ^45} ",
-                        Into::<SuiAddress>::into(keypair.public()),
-                        Base64::encode(keypair.public().as_ref()),
-                    );
-                }
+                KeyToolCommandResult::List(keys)
             }
             KeyToolCommand::Sign { address, data } => {
                 info!("Data to sign : {}", data);
@@ -89,22 +241,492 @@ impl KeyToolCommand {
                     .ok_or_else(|| anyhow!("Error creating signature."))?;
                 info!("Public Key Base64: {}", pub_key);
                 info!("Signature : {}", signature);
+                KeyToolCommandResult::Sign {
+                    address,
+                    public_key: pub_key.to_string(),
+                    signature: signature.to_string(),
+                }
             }
+            KeyToolCommand::Import { input, scheme } => {
+                let keypair = import_keypair(&input, scheme)?;
+                let address: SuiAddress = keypair.public().into();
+                keystore.add_key(keypair)?;
+                KeyToolCommandResult::Import { address }
+            }
+            KeyToolCommand::Export { address, yes } => {
+                let keypair = keystore
+                    .key_pairs()
+                    .into_iter()
+                    .find(|keypair| SuiAddress::from(keypair.public()) == address)
+                    .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))?;
+                if !yes {
+                    print!(
+                        "Anyone with the private key of [{address}] controls its objects. \
+                        Print it [yN]? "
+                    );
+                    io::stdout().flush()?;
+                    let mut line = String::new();
+                    io::stdin().read_line(&mut line)?;
+                    if line.trim().to_lowercase() != "y" {
+                        return Ok(KeyToolCommandResult::Export(None));
+                    }
+                }
+                KeyToolCommandResult::Export(Some(ExportedKey {
+                    private_key: encode_bytes_hex(&private_key_bytes(&keypair)),
+                    keypair: keypair.encode_base64(),
+                }))
+            }
+            KeyToolCommand::Convert {
+                input,
+                public,
+                scheme,
+            } => KeyToolCommandResult::Convert(convert_key(&input, public, scheme)?),
+            KeyToolCommand::RotateValidatorKey { config, kind } => {
+                rotate_validator_key(&config, kind)?
+            }
+        })
+    }
+}
+
+fn rotate_validator_key(
+    path: &Path,
+    kind: ValidatorKeyKind,
+) -> Result<KeyToolCommandResult, anyhow::Error> {
+    let mut config = NodeConfig::load(path)?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let write_key = |keypair: &KeyPair| -> Result<PathBuf, anyhow::Error> {
+        let file = dir.join(format!("{kind}-{}.key", encode_bytes_hex(keypair.public())));
+        write_keypair_to_file(keypair, &file)?;
+        Ok(file)
+    };
+
+    let keypair = get_key_pair().1;
+    let public_key = encode_bytes_hex(keypair.public());
+    let file = match kind {
+        ValidatorKeyKind::Protocol => {
+            return Err(anyhow!(
+                "The protocol key of a validator cannot be rotated, the committee has no way yet \
+                to change the key it names the validator by"
+            ))
         }
+        ValidatorKeyKind::Network => config
+            .network_key_pair
+            .replace(Arc::new(keypair))
+            .map(|previous| write_key(previous.as_ref()))
+            .transpose()?,
+        ValidatorKeyKind::Worker => config
+            .worker_key_pair
+            .replace(Arc::new(keypair))
+            .map(|previous| write_key(previous.as_ref()))
+            .transpose()?,
+        ValidatorKeyKind::Account => {
+            config.account_key = Some(keypair.public().into());
+            Some(write_key(&keypair)?)
+        }
+    };
+    config.save(path)?;
+    Ok(KeyToolCommandResult::RotateValidatorKey {
+        kind,
+        public_key,
+        file,
+    })
+}
+
+/// The output of a keytool command, serialized as `{"kind": ..., "result": ...}` in JSON mode.
+#[derive(Serialize, Debug)]
+#[serde(tag = "kind", content = "result", rename_all = "camelCase")]
+pub enum KeyToolCommandResult {
+    Generate(Vec<GeneratedKey>),
+    #[serde(rename_all = "camelCase")]
+    Show {
+        /// Hex encoded public key
+        public_key: String,
+    },
+    Unpack {
+        address: SuiAddress,
+        file: PathBuf,
+    },
+    List(Vec<KeyInfo>),
+    #[serde(rename_all = "camelCase")]
+    Sign {
+        address: SuiAddress,
+        /// Base64 encoded public key
+        public_key: String,
+        /// Base64 encoded signature
+        signature: String,
+    },
+    Import {
+        address: SuiAddress,
+    },
+    /// `None` if the export was cancelled
+    Export(Option<ExportedKey>),
+    Convert(ConvertedKey),
+    #[serde(rename_all = "camelCase")]
+    RotateValidatorKey {
+        kind: ValidatorKeyKind,
+        /// Hex encoded public key of the new key
+        public_key: String,
+        /// The file the replaced keypair, or the keypair of a new account, was written to
+        file: Option<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedKey {
+    pub address: SuiAddress,
+    /// Hex encoded public key
+    pub public_key: String,
+    /// The file the keypair was written to, `None` if it was added to the keystore
+    pub file: Option<PathBuf>,
+}
+
+/// A key of the keystore.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyInfo {
+    pub address: SuiAddress,
+    /// Base64 encoded public key, `None` for watch-only addresses
+    pub public_key: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedKey {
+    /// Base64 encoded keypair, as stored in the keystore
+    pub keypair: String,
+    /// Hex encoded private key
+    pub private_key: String,
+}
+
+/// The encodings of a key, as printed by `convert`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertedKey {
+    pub address: SuiAddress,
+    pub public_key: EncodedKey,
+    /// `None` if a public key was converted
+    pub private_key: Option<EncodedKey>,
+}
 
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct EncodedKey {
+    pub hex: String,
+    pub base64: String,
+    pub pem: String,
+}
+
+impl EncodedKey {
+    fn new(bytes: &[u8], kind: PemKind) -> Self {
+        Self {
+            hex: encode_bytes_hex(bytes),
+            base64: Base64::encode(bytes),
+            pem: encode_pem(bytes, kind),
+        }
+    }
+}
+
+impl KeyToolCommandResult {
+    pub fn print(&self, json: bool) -> Result<(), anyhow::Error> {
+        if json {
+            println!("{}", serde_json::to_string_pretty(self)?);
+        } else {
+            print!("{self}");
+        }
         Ok(())
     }
 }
 
-fn store_and_print_keypair(address: SuiAddress, keypair: KeyPair) {
-    let path_str = format!("{}.key", address).to_lowercase();
-    let path = Path::new(&path_str);
+impl Display for KeyToolCommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyToolCommandResult::Generate(keys) => {
+                for key in keys {
+                    match &key.file {
+                        Some(file) => writeln!(
+                            f,
+                            "Ed25519 key for address [{}] generated and saved to '{}'",
+                            key.address,
+                            file.display()
+                        )?,
+                        None => writeln!(
+                            f,
+                            "Ed25519 key for address [{}] generated and added to the keystore",
+                            key.address
+                        )?,
+                    }
+                }
+                Ok(())
+            }
+            KeyToolCommandResult::Show { public_key } => writeln!(f, "Public Key: {public_key}"),
+            KeyToolCommandResult::Unpack { file, .. } => {
+                writeln!(f, "Address and keypair written to {}", file.display())
+            }
+            KeyToolCommandResult::List(keys) => {
+                writeln!(
+                    f,
+                    " {0: ^42} | {1: ^45} ",
+                    "Sui Address", "Public Key (Base64)"
+                )?;
+                writeln!(f, "{}", ["-"; 91].join(""))?;
+                for key in keys {
+                    let public_key = key.public_key.as_deref().unwrap_or("(watch-only)");
+                    writeln!(f, " {0: ^42} | {1: ^45} ", key.address, public_key)?;
+                }
+                Ok(())
+            }
+            // Signatures are only logged.
+            KeyToolCommandResult::Sign { .. } => Ok(()),
+            KeyToolCommandResult::Import { address } => {
+                writeln!(f, "Key imported for address [{address}]")
+            }
+            KeyToolCommandResult::Export(None) => writeln!(f, "Export cancelled"),
+            KeyToolCommandResult::Export(Some(key)) => {
+                writeln!(f, "Keypair (Base64) : {}", key.keypair)?;
+                writeln!(f, "Private Key (Hex) : {}", key.private_key)
+            }
+            KeyToolCommandResult::Convert(key) => {
+                writeln!(f, "Address : {}", key.address)?;
+                let keys = [
+                    ("Public", Some(&key.public_key)),
+                    ("Private", key.private_key.as_ref()),
+                ];
+                for (name, encoded) in keys {
+                    if let Some(encoded) = encoded {
+                        writeln!(f, "{name} Key (Hex) : {}", encoded.hex)?;
+                        writeln!(f, "{name} Key (Base64) : {}", encoded.base64)?;
+                        write!(f, "{name} Key (PEM) :\n{}", encoded.pem)?;
+                    }
+                }
+                Ok(())
+            }
+            KeyToolCommandResult::RotateValidatorKey {
+                kind,
+                public_key,
+                file,
+            } => {
+                writeln!(f, "New {kind} key of the validator: {public_key}")?;
+                match (kind, file) {
+                    (ValidatorKeyKind::Account, Some(file)) => writeln!(
+                        f,
+                        "Account keypair saved to '{}', move it off the node",
+                        file.display()
+                    ),
+                    (_, Some(file)) => {
+                        writeln!(f, "Replaced keypair saved to '{}'", file.display())
+                    }
+                    (_, None) => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Make a keypair from a mnemonic phrase, derived at `DERIVATION_PATH`, or from a Hex, Base64
+/// or PEM encoded private key. Hex and Base64 keys are optionally followed by their public key.
+pub fn import_keypair(input: &str, scheme: SignatureScheme) -> Result<KeyPair, anyhow::Error> {
+    match scheme {
+        SignatureScheme::Ed25519 => {}
+    }
+    let input = input.trim();
+    let bytes = if input.starts_with("-----BEGIN") {
+        decode_pem(input, PemKind::PrivateKey)?
+    } else if input.split_whitespace().count() > 1 {
+        let mnemonic = bip39::Mnemonic::parse_normalized(input)
+            .map_err(|e| anyhow!("Invalid mnemonic phrase: {e}"))?;
+        slip10_ed25519::derive_ed25519_private_key(&mnemonic.to_seed(""), &DERIVATION_PATH).to_vec()
+    } else {
+        decode_key_bytes(input)?
+    };
+
+    let priv_length = <KeyPair as KeypairTraits>::PrivKey::LENGTH;
+    let pub_length = <KeyPair as KeypairTraits>::PubKey::LENGTH;
+    if bytes.len() == priv_length + pub_length {
+        Ok(get_key_pair_from_bytes(&bytes)?.1)
+    } else if bytes.len() == priv_length {
+        let private = PrivateKey::from_bytes(&bytes).map_err(|e| anyhow!(e))?;
+        Ok(private.into())
+    } else {
+        Err(anyhow!(
+            "Private key must be {priv_length} bytes long, found {}",
+            bytes.len()
+        ))
+    }
+}
+
+/// Read a public key from its Hex, Base64 or PEM encoding.
+pub fn import_public_key(input: &str, scheme: SignatureScheme) -> Result<PublicKey, anyhow::Error> {
+    match scheme {
+        SignatureScheme::Ed25519 => {}
+    }
+    let input = input.trim();
+    let bytes = if input.starts_with("-----BEGIN") {
+        decode_pem(input, PemKind::PublicKey)?
+    } else {
+        decode_key_bytes(input)?
+    };
+    PublicKey::from_bytes(&bytes).map_err(|e| anyhow!("Invalid public key: {e}"))
+}
+
+fn convert_key(
+    input: &str,
+    public: bool,
+    scheme: SignatureScheme,
+) -> Result<ConvertedKey, anyhow::Error> {
+    let (public_key, private_key) = if public {
+        (import_public_key(input, scheme)?, None)
+    } else {
+        let keypair = import_keypair(input, scheme)?;
+        let private_key = EncodedKey::new(&private_key_bytes(&keypair), PemKind::PrivateKey);
+        (keypair.public().clone(), Some(private_key))
+    };
+    Ok(ConvertedKey {
+        address: (&public_key).into(),
+        public_key: EncodedKey::new(public_key.as_ref(), PemKind::PublicKey),
+        private_key,
+    })
+}
+
+/// Decode a Hex or Base64 encoded key, Hex if it can be.
+fn decode_key_bytes(input: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+    if hex.len() % 2 == 0 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        decode_bytes_hex::<Vec<u8>>(hex)
+    } else {
+        Base64::decode(input).map_err(|_| anyhow!("Key is neither Hex nor Base64"))
+    }
+}
+
+fn private_key_bytes(keypair: &KeyPair) -> Vec<u8> {
+    let priv_length = <KeyPair as KeypairTraits>::PrivKey::LENGTH;
+    // The Base64 encoding of a keypair is its private key followed by its public key
+    let bytes = Base64::decode(&keypair.encode_base64()).expect("Keypairs encode to Base64");
+    bytes[..priv_length].to_vec()
+}
+
+/// The PEM documents of ed25519 keys, as read and written by OpenSSL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PemKind {
+    /// A PKCS #8 `PRIVATE KEY`
+    PrivateKey,
+    /// A SubjectPublicKeyInfo `PUBLIC KEY`
+    PublicKey,
+}
+
+impl PemKind {
+    fn label(&self) -> &'static str {
+        match self {
+            PemKind::PrivateKey => "PRIVATE KEY",
+            PemKind::PublicKey => "PUBLIC KEY",
+        }
+    }
+
+    /// The DER encoding of the structure wrapping a key, up to the key itself, which ends it.
+    fn der_prefix(&self) -> &'static [u8] {
+        match self {
+            PemKind::PrivateKey => &[
+                0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22,
+                0x04, 0x20,
+            ],
+            PemKind::PublicKey => &[
+                0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+            ],
+        }
+    }
+}
+
+/// Encode the 32 bytes of an ed25519 key as a PEM document of `kind`.
+pub fn encode_pem(key: &[u8], kind: PemKind) -> String {
+    let mut der = kind.der_prefix().to_vec();
+    der.extend_from_slice(key);
+    let base64 = Base64::encode(&der);
+    let mut pem = format!("-----BEGIN {}-----\n", kind.label());
+    // Lines of PEM documents are at most 64 characters long
+    for line in base64.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("Base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", kind.label()));
+    pem
+}
+
+/// Decode the ed25519 key of a PEM document of `kind`.
+pub fn decode_pem(pem: &str, kind: PemKind) -> Result<Vec<u8>, anyhow::Error> {
+    let begin = format!("-----BEGIN {}-----", kind.label());
+    let end = format!("-----END {}-----", kind.label());
+    let body = pem
+        .trim()
+        .strip_prefix(&begin)
+        .and_then(|body| body.strip_suffix(&end))
+        .ok_or_else(|| anyhow!("Expected a PEM encoded {}", kind.label().to_lowercase()))?;
+    let body: String = body.split_whitespace().collect();
+    let der = Base64::decode(&body).map_err(|e| anyhow!("Invalid PEM document: {e}"))?;
+    der.strip_prefix(kind.der_prefix())
+        .map(|key| key.to_vec())
+        .ok_or_else(|| anyhow!("The PEM document does not hold an ed25519 key"))
+}
+
+/// Generate `count` keypairs whose address starts with the Hex `prefix`, searching on `threads`
+/// threads. Each Hex digit of the prefix makes the search 16 times longer.
+pub fn generate_vanity_key_pairs(
+    prefix: &str,
+    count: usize,
+    threads: usize,
+) -> Result<Vec<KeyPair>, anyhow::Error> {
+    let prefix = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+    if !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("Vanity prefix {prefix} is not Hex encoded"));
+    }
+    if prefix.len() > SUI_ADDRESS_LENGTH * 2 {
+        return Err(anyhow!(
+            "Vanity prefix {prefix} is longer than an address ({} Hex digits)",
+            SUI_ADDRESS_LENGTH * 2
+        ));
+    }
+    if threads == 0 {
+        return Err(anyhow!(
+            "At least one thread is needed to search for addresses"
+        ));
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let done = Arc::new(AtomicBool::new(false));
+    let searchers = (0..threads)
+        .map(|_| {
+            let sender = sender.clone();
+            let done = done.clone();
+            let prefix = prefix.clone();
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let (address, keypair) = get_key_pair();
+                    if format!("{address:x}").starts_with(&prefix) && sender.send(keypair).is_err()
+                    {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let keypairs = receiver.iter().take(count).collect::<Vec<_>>();
+    done.store(true, Ordering::Relaxed);
+    for searcher in searchers {
+        searcher
+            .join()
+            .map_err(|_| anyhow!("Vanity address search thread panicked"))?;
+    }
+    Ok(keypairs)
+}
+
+fn store_keypair(address: SuiAddress, keypair: KeyPair) -> Result<PathBuf, anyhow::Error> {
+    let path = PathBuf::from(format!("{}.key", address).to_lowercase());
     let address = format!("{}", address);
     let kp = keypair.encode_base64();
     let kp = &kp[1..kp.len() - 1];
     let out_str = format!("address: {}\nkeypair: {}", address, kp);
-    fs::write(path, out_str).unwrap();
-    println!("Address and keypair written to {}", path.to_str().unwrap());
+    fs::write(&path, out_str)?;
+    Ok(path)
 }
 
 pub fn write_keypair_to_file<P: AsRef<std::path::Path>>(
@@ -6,8 +6,18 @@ pub mod client_commands;
 pub mod config;
 pub mod console;
 pub mod keytool;
+pub mod name_service;
 pub mod shell;
+pub mod signing_request;
 pub mod sui_commands;
 pub mod sui_move;
+pub mod verify_source;
 
 pub mod genesis_ceremony;
+
+#[cfg(test)]
+#[path = "unit_tests/mock_gateway.rs"]
+mod mock_gateway;
+#[cfg(test)]
+#[path = "unit_tests/wallet_tests.rs"]
+mod wallet_tests;
@@ -4,7 +4,7 @@ extern crate core;
 
 use clap::*;
 use colored::Colorize;
-use sui::sui_commands::SuiCommand;
+use sui::sui_commands::{print_json, CommandError, SuiCli, SuiCommand};
 use sui_types::exit_main;
 use tracing::debug;
 #[cfg(test)]
@@ -17,14 +17,21 @@ async fn main() {
     colored::control::set_virtual_terminal(true).unwrap();
 
     let bin_name = env!("CARGO_BIN_NAME");
-    let cmd: SuiCommand = SuiCommand::parse();
-    let _guard = match cmd {
+    let cli: SuiCli = SuiCli::parse();
+    let _guard = match cli.command {
         SuiCommand::Console { .. } | SuiCommand::Client { .. } => {
             telemetry_subscribers::TelemetryConfig::new(bin_name)
                 .with_log_file(&format!("{bin_name}.log"))
                 .with_env()
                 .init()
         }
+        SuiCommand::Start {
+            log_level: Some(ref log_level),
+            ..
+        } => telemetry_subscribers::TelemetryConfig::new(bin_name)
+            .with_log_level(log_level)
+            .with_env()
+            .init(),
         _ => telemetry_subscribers::TelemetryConfig::new(bin_name)
             .with_env()
             .init(),
@@ -33,5 +40,12 @@ async fn main() {
     if let Some(git_rev) = option_env!("GIT_REVISION") {
         debug!("Sui CLI built at git revision {git_rev}");
     }
-    exit_main!(cmd.execute().await);
+    let json = cli.json;
+    match cli.execute().await {
+        Err(err) if json => {
+            print_json(&CommandError::from(&err)).unwrap();
+            std::process::exit(1);
+        }
+        result => exit_main!(result),
+    }
 }
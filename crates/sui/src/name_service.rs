@@ -0,0 +1,139 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use serde_json::Value;
+
+use sui_core::gateway_state::GatewayClient;
+use sui_json_rpc_types::SuiData;
+use sui_types::base_types::{ObjectID, SuiAddress};
+
+/// Top level domain of the names resolved by the wallet.
+pub const NAME_SUFFIX: &str = ".sui";
+
+/// Resolves names such as `alice.sui` to addresses, and addresses back to their name.
+#[async_trait]
+pub trait NameResolver: Send + Sync {
+    async fn resolve(&self, name: &str) -> Result<Option<SuiAddress>, Error>;
+
+    /// The names of those of `addresses` that have one.
+    async fn reverse_lookup(
+        &self,
+        addresses: &[SuiAddress],
+    ) -> Result<BTreeMap<SuiAddress, String>, Error>;
+}
+
+/// A [`NameResolver`] reading the records of an on-chain registry object. The registry is
+/// expected to have a `records` field mapping names to addresses, of type
+/// `0x2::vec_map::VecMap<0x1::string::String, address>` or any other type with the same layout.
+pub struct RegistryNameResolver {
+    gateway: GatewayClient,
+    registry: ObjectID,
+}
+
+impl RegistryNameResolver {
+    pub fn new(gateway: GatewayClient, registry: ObjectID) -> Self {
+        Self { gateway, registry }
+    }
+
+    async fn records(&self) -> Result<BTreeMap<String, SuiAddress>, Error> {
+        let object = self
+            .gateway
+            .get_object(self.registry)
+            .await?
+            .into_object()?;
+        let fields = match object.data {
+            SuiData::MoveObject(object) => object.fields.to_json_value()?,
+            SuiData::Package(_) => {
+                return Err(anyhow!("Name registry {} is a package", self.registry))
+            }
+        };
+        let entries = fields
+            .pointer("/records/contents")
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Name registry {} has no `records` map of names to addresses",
+                    self.registry
+                )
+            })?;
+        entries
+            .iter()
+            .map(|entry| {
+                match (
+                    entry.get("key").and_then(Value::as_str),
+                    entry.get("value").and_then(Value::as_str),
+                ) {
+                    (Some(name), Some(address)) => {
+                        Ok((name.to_string(), SuiAddress::from_str(address)?))
+                    }
+                    _ => Err(anyhow!("Invalid name registry record: {entry}")),
+                }
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl NameResolver for RegistryNameResolver {
+    async fn resolve(&self, name: &str) -> Result<Option<SuiAddress>, Error> {
+        Ok(self.records().await?.get(name).copied())
+    }
+
+    async fn reverse_lookup(
+        &self,
+        addresses: &[SuiAddress],
+    ) -> Result<BTreeMap<SuiAddress, String>, Error> {
+        let mut names = BTreeMap::new();
+        // Several names can point to the same address, keep the first one in name order.
+        for (name, address) in self.records().await? {
+            if addresses.contains(&address) {
+                names.entry(address).or_insert(name);
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// The recipient of a transfer, given by its address or by a name to resolve.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    Address(SuiAddress),
+    Name(String),
+}
+
+impl FromStr for Recipient {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(address) = SuiAddress::from_str(s) {
+            Ok(Recipient::Address(address))
+        } else if s.len() > NAME_SUFFIX.len() && s.ends_with(NAME_SUFFIX) {
+            Ok(Recipient::Name(s.to_string()))
+        } else {
+            Err(anyhow!(
+                "Invalid recipient {s}, expected an address or a name ending with {NAME_SUFFIX}"
+            ))
+        }
+    }
+}
+
+impl From<SuiAddress> for Recipient {
+    fn from(address: SuiAddress) -> Self {
+        Recipient::Address(address)
+    }
+}
+
+impl Display for Recipient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recipient::Address(address) => write!(f, "{address}"),
+            Recipient::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
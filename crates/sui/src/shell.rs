@@ -9,6 +9,7 @@ use std::collections::BTreeMap;
 use std::env;
 use std::fmt::Display;
 use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
 use async_trait::async_trait;
@@ -33,6 +34,8 @@ pub struct Shell<P: Display, S, H> {
     state: S,
     handler: H,
     command: CommandStructure,
+    completion_cache: CompletionCache,
+    history_file: Option<PathBuf>,
 }
 
 impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
@@ -40,6 +43,7 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
         // Add help to auto complete
         let help = CommandStructure {
             name: "help".to_string(),
+            help: Some("Print the help of a command".to_string()),
             completions: command.completions.clone(),
             children: vec![],
         };
@@ -51,9 +55,22 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
             state,
             handler,
             command,
+            completion_cache: Arc::new(RwLock::new(BTreeMap::new())),
+            history_file: None,
         }
     }
 
+    /// Load the history from `path` when the shell starts, and keep it saved there.
+    pub fn with_history_file(mut self, path: PathBuf) -> Self {
+        self.history_file = Some(path);
+        self
+    }
+
+    /// The completion values, which can be filled before the shell starts.
+    pub fn completion_cache(&self) -> CompletionCache {
+        self.completion_cache.clone()
+    }
+
     pub async fn run_async(
         &mut self,
         out: &mut dyn Write,
@@ -66,8 +83,12 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
             .build();
 
         let mut rl = Editor::with_config(config);
+        if let Some(history_file) = &self.history_file {
+            // The history file does not exist on the first run.
+            let _ = rl.load_history(history_file);
+        }
 
-        let completion_cache = Arc::new(RwLock::new(BTreeMap::new()));
+        let completion_cache = self.completion_cache.clone();
 
         rl.set_helper(Some(ShellHelper {
             command: self.command.clone(),
@@ -83,6 +104,12 @@ impl<P: Display, S: Send, H: AsyncHandler<S>> Shell<P, S, H> {
                 Err(err) => return Err(err.into()),
             };
 
+            if let Some(history_file) = &self.history_file {
+                if let Err(e) = rl.save_history(history_file) {
+                    writeln!(err, "Cannot save history to {:?}: {}", history_file, e)?;
+                }
+            }
+
             let line = substitute_env_variables(line);
 
             // Runs the line
@@ -193,6 +220,30 @@ struct ShellHelper {
 
 impl Hinter for ShellHelper {
     type Hint = String;
+
+    /// Hint the rest of the word being typed when a single completion matches it, or the help
+    /// of the command when only the command has been typed.
+    fn hint(&self, line: &str, pos: usize, ctx: &Context<'_>) -> Option<String> {
+        if pos < line.len() {
+            return None;
+        }
+        let mut tokens = line.split_whitespace();
+        if let (Some(name), None, true) = (tokens.next(), tokens.next(), line.ends_with(' ')) {
+            return self
+                .command
+                .get_child(name)
+                .and_then(|command| command.help.clone());
+        }
+
+        let (start, candidates) = self.complete(line, pos, ctx).ok()?;
+        let typed = &line[start..];
+        match candidates.as_slice() {
+            [candidate] if !typed.is_empty() && candidate.replacement.starts_with(typed) => {
+                Some(candidate.replacement[typed.len()..].to_string())
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Highlighter for ShellHelper {
@@ -203,6 +254,10 @@ impl Highlighter for ShellHelper {
     ) -> Cow<'b, str> {
         Owned(prompt.bold().green().to_string())
     }
+
+    fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
+        Owned(hint.dimmed().to_string())
+    }
 }
 
 impl Validator for ShellHelper {}
@@ -265,6 +320,8 @@ impl Completer for ShellHelper {
 #[derive(Clone)]
 pub struct CommandStructure {
     pub name: String,
+    /// One line description of the command, hinted once the command is typed
+    pub help: Option<String>,
     pub completions: Vec<String>,
     pub children: Vec<CommandStructure>,
 }
@@ -278,6 +335,7 @@ impl CommandStructure {
                 let name = it.get_name();
                 CommandStructure {
                     name: name.to_string(),
+                    help: it.get_about().map(|about| about.to_string()),
                     completions: it
                         .get_opts()
                         .map(|it| match it.get_long() {
@@ -300,6 +358,7 @@ impl CommandStructure {
             .collect();
         Self {
             name: name.to_string(),
+            help: None,
             completions,
             children,
         }
@@ -0,0 +1,179 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter, Write};
+
+use anyhow::anyhow;
+use colored::Colorize;
+
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::SignableBytes;
+use sui_types::intent::{IntentScope, PersonalMessage};
+use sui_types::messages::{SingleTransactionKind, TransactionData};
+use sui_types::sui_serde::{Base64, Encoding};
+
+/// The data an application asks the wallet to sign.
+#[derive(Debug, Clone)]
+pub enum SigningPayload {
+    Transaction(TransactionData),
+    PersonalMessage(PersonalMessage),
+}
+
+/// A request to sign with a key of the wallet on behalf of another application, e.g. a dApp
+/// talking to the wallet through a local endpoint.
+///
+/// Requests are always shown to the user, with what the signature would authorize and who asks
+/// for it, and are only signed once the user explicitly approved them.
+#[derive(Debug, Clone)]
+pub struct SigningRequest {
+    /// Where the request comes from, as reported by the requesting application.
+    pub origin: String,
+    pub signer: SuiAddress,
+    pub payload: SigningPayload,
+}
+
+impl SigningRequest {
+    /// Decode the `data` to sign for the given `scope`. The signer of transaction data must
+    /// be `signer`, and system transactions are never signed.
+    pub fn new(
+        origin: String,
+        scope: IntentScope,
+        signer: SuiAddress,
+        data: &[u8],
+    ) -> Result<Self, anyhow::Error> {
+        let payload = match scope {
+            IntentScope::TransactionData => {
+                let tx_data = TransactionData::from_signable_bytes(data)
+                    .map_err(|e| anyhow!("Invalid transaction data: {e}"))?;
+                if tx_data.signer() != signer {
+                    return Err(anyhow!(
+                        "The transaction is sent by {}, it cannot be signed by {signer}",
+                        tx_data.signer()
+                    ));
+                }
+                if tx_data.kind.is_system_tx() {
+                    return Err(anyhow!("System transactions cannot be signed by a wallet"));
+                }
+                SigningPayload::Transaction(tx_data)
+            }
+            IntentScope::PersonalMessage => SigningPayload::PersonalMessage(PersonalMessage {
+                message: data.to_vec(),
+            }),
+        };
+        Ok(Self {
+            origin,
+            signer,
+            payload,
+        })
+    }
+
+    pub fn scope(&self) -> IntentScope {
+        match self.payload {
+            SigningPayload::Transaction(_) => IntentScope::TransactionData,
+            SigningPayload::PersonalMessage(_) => IntentScope::PersonalMessage,
+        }
+    }
+
+    /// The bytes to sign, prefixed with the type name of the payload so that the signature
+    /// of a message cannot be used as the signature of a transaction.
+    pub fn signable_bytes(&self) -> Vec<u8> {
+        match &self.payload {
+            SigningPayload::Transaction(tx_data) => tx_data.to_bytes(),
+            SigningPayload::PersonalMessage(message) => message.to_bytes(),
+        }
+    }
+
+    /// One line per action the signature would authorize.
+    fn targets(&self) -> Vec<String> {
+        let tx_data = match &self.payload {
+            SigningPayload::Transaction(tx_data) => tx_data,
+            SigningPayload::PersonalMessage(_) => return vec![],
+        };
+        tx_data
+            .kind
+            .single_transactions()
+            .flat_map(|kind| match kind {
+                SingleTransactionKind::Call(call) => {
+                    let type_arguments = if call.type_arguments.is_empty() {
+                        String::new()
+                    } else {
+                        let type_arguments = call
+                            .type_arguments
+                            .iter()
+                            .map(|type_| type_.to_string())
+                            .collect::<Vec<_>>();
+                        format!("<{}>", type_arguments.join(", "))
+                    };
+                    vec![format!(
+                        "Call {}::{}::{}{type_arguments}",
+                        call.package.0, call.module, call.function
+                    )]
+                }
+                SingleTransactionKind::Publish(publish) => {
+                    vec![format!(
+                        "Publish a package of {} module(s)",
+                        publish.modules.len()
+                    )]
+                }
+                SingleTransactionKind::TransferObject(transfer) => vec![format!(
+                    "Transfer object {} to {}",
+                    transfer.object_ref.0, transfer.recipient
+                )],
+                SingleTransactionKind::TransferSui(transfer) => vec![match transfer.amount {
+                    Some(amount) => format!("Transfer {amount} SUI to {}", transfer.recipient),
+                    None => format!("Transfer the whole gas coin to {}", transfer.recipient),
+                }],
+                SingleTransactionKind::Pay(pay) => pay
+                    .recipients
+                    .iter()
+                    .zip(&pay.amounts)
+                    .map(|(recipient, amount)| format!("Pay {amount} to {recipient}"))
+                    .collect(),
+                SingleTransactionKind::ChangeEpoch(change) => {
+                    vec![format!("Change to epoch {}", change.epoch)]
+                }
+            })
+            .collect()
+    }
+}
+
+impl Display for SigningRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "{}", "----- Signing Request ----".bold())?;
+        writeln!(writer, "Origin : {}", self.origin.yellow().bold())?;
+        writeln!(writer, "Intent : {}", self.scope())?;
+        writeln!(writer, "Signer : {}", self.signer)?;
+        match &self.payload {
+            SigningPayload::Transaction(tx_data) => {
+                writeln!(writer, "Chain : {}", tx_data.chain_id())?;
+                writeln!(writer, "Actions :")?;
+                for target in self.targets() {
+                    writeln!(writer, "  - {target}")?;
+                }
+                write!(writer, "Gas Budget : {}", tx_data.gas_budget)?;
+            }
+            SigningPayload::PersonalMessage(message) => match std::str::from_utf8(&message.message)
+            {
+                Ok(text) => write!(writer, "Message : {text}")?,
+                Err(_) => write!(
+                    writer,
+                    "Message (Base64) : {}",
+                    Base64::encode(&message.message)
+                )?,
+            },
+        }
+        write!(f, "{}", writer)
+    }
+}
+
+/// Show `request` and ask the user to approve it on the terminal. Anything but an explicit
+/// "y" rejects the request.
+pub fn prompt_approval(request: &SigningRequest) -> Result<bool, anyhow::Error> {
+    println!("{}", request);
+    print!("Sign this request from {} [yN]? ", request.origin);
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_lowercase() == "y")
+}
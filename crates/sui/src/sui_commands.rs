@@ -2,30 +2,41 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::client_commands::{SuiClientCommands, WalletContext};
-use crate::config::{GatewayConfig, GatewayType, SuiClientConfig};
+use crate::config::{GatewayConfig, GatewayType, SuiClientConfig, SuiEnv};
 use crate::console::start_console;
 use crate::genesis_ceremony::{run, Ceremony};
 use crate::keytool::KeyToolCommand;
 use crate::sui_move::{self, execute_move_command};
+use crate::verify_source::verify_source;
 use anyhow::{anyhow, bail};
 use clap::*;
 use move_package::BuildConfig;
+use multiaddr::Multiaddr;
+use prometheus::Registry;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
 use std::io::{stderr, stdout, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 use sui_config::{builder::ConfigBuilder, NetworkConfig, SUI_DEV_NET_URL, SUI_KEYSTORE_FILENAME};
-use sui_config::{genesis_config::GenesisConfig, SUI_GENESIS_FILENAME};
+use sui_config::{genesis_config::GenesisConfig, testbed::TestbedConfig, SUI_GENESIS_FILENAME};
 use sui_config::{
     sui_config_dir, Config, PersistedConfig, SUI_CLIENT_CONFIG, SUI_FULLNODE_CONFIG,
     SUI_GATEWAY_CONFIG, SUI_NETWORK_CONFIG,
 };
+use sui_gateway::{create_client, start_rpc_gateway};
+use sui_json_rpc::ServerHandle;
 use sui_sdk::crypto::{KeystoreType, SuiKeystore};
 use sui_sdk::SuiClient;
 use sui_swarm::memory::Swarm;
+use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::crypto::KeypairTraits;
 use tracing::info;
 
+const DEFAULT_EPHEMERAL_COMMITTEE_SIZE: usize = 4;
+
 #[derive(Parser)]
 #[clap(
     name = "sui",
@@ -34,12 +45,45 @@ use tracing::info;
     author,
     version
 )]
+pub struct SuiCli {
+    /// Print the output of the command, and its error if it fails, as JSON.
+    #[clap(long, global = true)]
+    pub json: bool,
+    #[clap(subcommand)]
+    pub command: SuiCommand,
+}
+
+impl SuiCli {
+    pub async fn execute(self) -> Result<(), anyhow::Error> {
+        self.command.execute(self.json).await
+    }
+}
+
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
 pub enum SuiCommand {
-    /// Start sui network.
+    /// Start sui network, until interrupted with Ctrl-C.
     #[clap(name = "start")]
     Start {
         #[clap(long = "network.config")]
         config: Option<PathBuf>,
+        /// Start a network generated on the fly in a temporary directory, removed when the
+        /// network stops, instead of the network created by `sui genesis`.
+        #[clap(long, conflicts_with = "config")]
+        ephemeral: bool,
+        /// The number of validators of an ephemeral network, 4 by default.
+        #[clap(long, requires = "ephemeral")]
+        committee_size: Option<NonZeroUsize>,
+        /// Also start a fullnode serving the JSON-RPC API on this port.
+        #[clap(long)]
+        fullnode_port: Option<u16>,
+        /// Also start a gateway serving the JSON-RPC API on this port.
+        #[clap(long)]
+        gateway_port: Option<u16>,
+        /// The log level of the nodes, such as `info` or `sui_core=debug,info`. `RUST_LOG` takes
+        /// precedence when set.
+        #[clap(long)]
+        log_level: Option<String>,
     },
     #[clap(name = "network")]
     Network {
@@ -64,6 +108,19 @@ pub enum SuiCommand {
         force: bool,
     },
     GenesisCeremony(Ceremony),
+    /// Generate the genesis, the validator configs and the committee of a test network of
+    /// validators on remote hosts, with the configs of a client connected to it.
+    #[clap(name = "testbed")]
+    Testbed {
+        /// The hosts of the validators, see `sui-config/data/testbed-template.yaml`
+        #[clap(long)]
+        config: PathBuf,
+        /// Where to write the files, to be copied to the working directory of the validators
+        #[clap(long)]
+        output_dir: PathBuf,
+        #[clap(short, long, help = "Forces overwriting existing files")]
+        force: bool,
+    },
     /// Sui keystore tool.
     #[clap(name = "keytool")]
     KeyTool {
@@ -88,9 +145,9 @@ pub enum SuiCommand {
         config: Option<PathBuf>,
         #[clap(subcommand)]
         cmd: Option<SuiClientCommands>,
-        /// Return command outputs in json format.
-        #[clap(long, global = true)]
-        json: bool,
+        /// Sign transactions without showing their preview and asking for confirmation.
+        #[clap(long, short = 'y', global = true)]
+        yes: bool,
     },
 
     /// Tool to build and test Move applications.
@@ -106,36 +163,118 @@ pub enum SuiCommand {
         #[clap(subcommand)]
         cmd: sui_move::Command,
     },
+
+    /// Check that a published package was built from a local source, by building it and
+    /// comparing its bytecode with the published modules.
+    #[clap(name = "verify-source")]
+    VerifySource {
+        /// Path to the source of the package
+        #[clap(long = "package", parse(from_os_str))]
+        package_path: PathBuf,
+        /// ID of the published package
+        #[clap(long)]
+        on_chain: ObjectID,
+        /// Sets the file storing the state of our user accounts (an empty one will be created if missing)
+        #[clap(long = "client.config")]
+        config: Option<PathBuf>,
+        /// Package build options, which must be the ones the package was published with
+        #[clap(flatten)]
+        build_config: BuildConfig,
+    },
 }
 
 impl SuiCommand {
-    pub async fn execute(self) -> Result<(), anyhow::Error> {
+    /// Run the command, printing its output as JSON if `json` is set.
+    pub async fn execute(self, json: bool) -> Result<(), anyhow::Error> {
         match self {
-            SuiCommand::Start { config } => {
-                // Load the config of the Sui authority.
-                let network_config_path = config
-                    .clone()
-                    .unwrap_or(sui_config_dir()?.join(SUI_NETWORK_CONFIG));
-                let network_config: NetworkConfig = PersistedConfig::read(&network_config_path)
-                    .map_err(|err| {
-                        err.context(format!(
-                            "Cannot open Sui network config file at {:?}",
-                            network_config_path
-                        ))
-                    })?;
-
-                let mut swarm =
-                    Swarm::builder().from_network_config(sui_config_dir()?, network_config);
+            SuiCommand::Start {
+                config,
+                ephemeral,
+                committee_size,
+                fullnode_port,
+                gateway_port,
+                // Applied when setting up the logs.
+                log_level: _,
+            } => {
+                let (mut swarm, config_dir) = if ephemeral {
+                    let committee_size = committee_size.unwrap_or_else(|| {
+                        NonZeroUsize::new(DEFAULT_EPHEMERAL_COMMITTEE_SIZE).unwrap()
+                    });
+                    let swarm = Swarm::builder().committee_size(committee_size).build();
+                    let config_dir = swarm.dir().to_path_buf();
+                    write_client_configs(&config_dir, swarm.config())?;
+                    println!(
+                        "Ephemeral network of {committee_size} validators generated in {:?}, \
+                        connect to it with `sui client --client.config {:?}`",
+                        config_dir,
+                        config_dir.join(SUI_CLIENT_CONFIG)
+                    );
+                    (swarm, config_dir)
+                } else {
+                    // Load the config of the Sui authority.
+                    let network_config_path = config
+                        .clone()
+                        .unwrap_or(sui_config_dir()?.join(SUI_NETWORK_CONFIG));
+                    let network_config: NetworkConfig = PersistedConfig::read(&network_config_path)
+                        .map_err(|err| {
+                            err.context(format!(
+                                "Cannot open Sui network config file at {:?}, \
+                                run `sui genesis` first or start an ephemeral network",
+                                network_config_path
+                            ))
+                        })?;
+                    let config_dir = match network_config_path.parent() {
+                        Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+                        _ => PathBuf::from("."),
+                    };
+                    (
+                        Swarm::builder().from_network_config(sui_config_dir()?, network_config),
+                        config_dir,
+                    )
+                };
                 swarm.launch().await?;
 
+                if let Some(port) = fullnode_port {
+                    let mut fullnode_config = swarm.config().generate_fullnode_config();
+                    fullnode_config.json_rpc_address.set_port(port);
+                    fullnode_config.websocket_address = None;
+                    let address = fullnode_config.json_rpc_address;
+                    swarm.start_fullnode(fullnode_config).await?;
+                    println!("Fullnode JSON-RPC API served on {address}");
+                }
+
+                let gateway = match gateway_port {
+                    Some(port) => {
+                        let registry = Registry::default();
+                        let client =
+                            create_client(&config_dir.join(SUI_GATEWAY_CONFIG), &registry)?;
+                        let address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+                        let handle = start_rpc_gateway(client, address, &registry).await?;
+                        println!("Gateway JSON-RPC API served on {address}");
+                        Some(handle)
+                    }
+                    None => None,
+                };
+
                 let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
                 loop {
-                    for node in swarm.validators_mut() {
-                        node.health_check().await?;
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => break,
+                        _ = interval.tick() => {
+                            for node in swarm.validators().chain(swarm.fullnodes()) {
+                                node.health_check().await?;
+                            }
+                        }
                     }
+                }
 
-                    interval.tick().await;
+                info!("Stopping the network");
+                if let Some(handle) = gateway.and_then(ServerHandle::into_http_server_handle) {
+                    handle.stop()?;
                 }
+                // Stops the nodes and, for an ephemeral network, removes its directory.
+                drop(swarm);
+                Ok(())
             }
             SuiCommand::Network {
                 config,
@@ -150,12 +289,20 @@ impl SuiCommand {
                 })?;
 
                 if dump_addresses {
-                    for validator in config.validator_configs() {
-                        println!(
-                            "{} - {}",
-                            validator.network_address(),
-                            validator.sui_address()
-                        );
+                    let validators = config
+                        .validator_configs()
+                        .iter()
+                        .map(|validator| ValidatorAddresses {
+                            network_address: validator.network_address().clone(),
+                            sui_address: validator.sui_address(),
+                        })
+                        .collect::<Vec<_>>();
+                    if json {
+                        print_json(&validators)?;
+                    } else {
+                        for validator in validators {
+                            println!("{validator}");
+                        }
                     }
                 }
                 Ok(())
@@ -216,8 +363,6 @@ impl SuiCommand {
                 let client_path = sui_config_dir.join(SUI_CLIENT_CONFIG);
                 let gateway_path = sui_config_dir.join(SUI_GATEWAY_CONFIG);
                 let keystore_path = sui_config_dir.join(SUI_KEYSTORE_FILENAME);
-                let db_folder_path = sui_config_dir.join("client_db");
-                let gateway_db_folder_path = sui_config_dir.join("gateway_client_db");
 
                 let mut genesis_conf = match from_config {
                     Some(path) => PersistedConfig::read(&path)?,
@@ -227,6 +372,12 @@ impl SuiCommand {
                 if let Some(path) = write_config {
                     let persisted = genesis_conf.persisted(&path);
                     persisted.save()?;
+                    if json {
+                        print_json(&GenesisOutput {
+                            genesis_config: Some(path),
+                            ..Default::default()
+                        })?;
+                    }
                     return Ok(());
                 }
 
@@ -234,23 +385,14 @@ impl SuiCommand {
                 let mut network_config = if let Some(validators) = validator_info {
                     ConfigBuilder::new(sui_config_dir)
                         .initial_accounts_config(genesis_conf)
-                        .build_with_validators(validators)
+                        .build_with_validators(validators)?
                 } else {
                     ConfigBuilder::new(sui_config_dir)
                         .committee_size(NonZeroUsize::new(genesis_conf.committee_size).unwrap())
                         .initial_accounts_config(genesis_conf)
-                        .build()
+                        .build()?
                 };
 
-                let mut accounts = Vec::new();
-                let mut keystore = SuiKeystore::default();
-
-                for key in &network_config.account_keys {
-                    let address = key.public().into();
-                    accounts.push(address);
-                    keystore.add_key(address, key.copy())?;
-                }
-
                 network_config.genesis.save(&genesis_path)?;
                 for validator in &mut network_config.validator_configs {
                     validator.genesis = sui_config::node::Genesis::new_from_file(&genesis_path);
@@ -260,61 +402,83 @@ impl SuiCommand {
                 network_config.save(&network_path)?;
                 info!("Network config file is stored in {:?}.", network_path);
 
-                keystore.set_path(&keystore_path);
-                keystore.save()?;
-                info!("Client keystore is stored in {:?}.", keystore_path);
-
-                // Use the first address if any
-                let active_address = accounts.get(0).copied();
-
-                let validator_set = network_config.validator_set();
-
-                GatewayConfig {
-                    db_folder_path: gateway_db_folder_path,
-                    validator_set: validator_set.to_owned(),
-                    ..Default::default()
-                }
-                .save(&gateway_path)?;
-                info!("Gateway config file is stored in {:?}.", gateway_path);
-
-                let wallet_gateway_config = GatewayConfig {
-                    db_folder_path,
-                    validator_set: validator_set.to_owned(),
-                    ..Default::default()
-                };
-
-                let wallet_config = SuiClientConfig {
-                    accounts,
-                    keystore: KeystoreType::File(keystore_path),
-                    gateway: GatewayType::Embedded(wallet_gateway_config),
-                    active_address,
-                };
-
-                wallet_config.save(&client_path)?;
-                info!("Client config file is stored in {:?}.", client_path);
+                let accounts = write_client_configs(sui_config_dir, &network_config)?;
 
                 let mut fullnode_config = network_config.generate_fullnode_config();
                 fullnode_config.json_rpc_address = sui_config::node::default_json_rpc_address();
                 fullnode_config.websocket_address = sui_config::node::default_websocket_address();
                 fullnode_config.save(sui_config_dir.join(SUI_FULLNODE_CONFIG))?;
 
+                let mut validator_configs = Vec::new();
                 for (i, validator) in network_config
                     .into_validator_configs()
                     .into_iter()
                     .enumerate()
                 {
                     let path = sui_config_dir.join(format!("validator-config-{}.yaml", i));
-                    validator.save(path)?;
+                    validator.save(&path)?;
+                    validator_configs.push(path);
                 }
 
+                if json {
+                    print_json(&GenesisOutput {
+                        genesis_config: None,
+                        network_config: Some(network_path),
+                        genesis: Some(genesis_path),
+                        client_config: Some(client_path),
+                        gateway_config: Some(gateway_path),
+                        fullnode_config: Some(sui_config_dir.join(SUI_FULLNODE_CONFIG)),
+                        keystore: Some(keystore_path),
+                        validator_configs,
+                        accounts,
+                    })?;
+                }
                 Ok(())
             }
             SuiCommand::GenesisCeremony(cmd) => run(cmd),
+            SuiCommand::Testbed {
+                config,
+                output_dir,
+                force,
+            } => {
+                let non_empty = output_dir
+                    .read_dir()
+                    .map_or(false, |mut entries| entries.next().is_some());
+                if non_empty && !force {
+                    bail!("Cannot generate a testbed in non-empty directory {:?}, please use --force/-f option to overwrite it", output_dir);
+                }
+                let testbed =
+                    PersistedConfig::<TestbedConfig>::read(&config)?.build(rand::rngs::OsRng)?;
+                testbed.save(&output_dir)?;
+                write_client_configs(&output_dir, &testbed.network_config)?;
+                info!("Testbed configs are stored in {:?}.", output_dir);
+
+                let validators = &testbed.committee.validators;
+                if json {
+                    print_json(validators)?;
+                } else {
+                    for validator in validators {
+                        println!(
+                            "{} ({}): {} - {}",
+                            validator.name,
+                            validator.region.as_deref().unwrap_or("no region"),
+                            validator.network_address,
+                            validator.sui_address
+                        );
+                    }
+                }
+                Ok(())
+            }
             SuiCommand::KeyTool { keystore_path, cmd } => {
                 let keystore_path =
                     keystore_path.unwrap_or(sui_config_dir()?.join(SUI_KEYSTORE_FILENAME));
                 let keystore = SuiKeystore::load_or_create(&keystore_path)?;
-                cmd.execute(keystore)
+                let import = matches!(cmd, KeyToolCommand::Import { .. });
+                cmd.execute(keystore)?.print(json)?;
+                if import {
+                    add_keystore_accounts(&keystore_path)?;
+                }
+                Ok(())
             }
             SuiCommand::Console { config } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
@@ -323,10 +487,11 @@ impl SuiCommand {
                 sync_accounts(&mut context).await?;
                 start_console(context, &mut stdout(), &mut stderr()).await
             }
-            SuiCommand::Client { config, cmd, json } => {
+            SuiCommand::Client { config, cmd, yes } => {
                 let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
                 prompt_if_no_config(&config)?;
                 let mut context = WalletContext::new(&config)?;
+                context.preview_transactions = !yes;
 
                 if let Some(cmd) = cmd {
                     // Do not sync if command is a gateway switch, as the current gateway might be unreachable and causes sync to panic.
@@ -335,14 +500,18 @@ impl SuiCommand {
                         SuiClientCommands::Switch {
                             gateway: Some(_),
                             ..
-                        }
+                        } | SuiClientCommands::Switch { env: Some(_), .. }
                     ) {
                         sync_accounts(&mut context).await?;
                     }
-                    cmd.execute(&mut context).await?.print(!json);
+                    let result = cmd.execute(&mut context).await?;
+                    result.print(!json);
+                    if !json {
+                        context.print_names(&result).await;
+                    }
                 } else {
                     // Print help
-                    let mut app: Command = SuiCommand::command();
+                    let mut app: Command = SuiCli::command();
                     app.build();
                     app.find_subcommand_mut("client").unwrap().print_help()?;
                 }
@@ -353,10 +522,138 @@ impl SuiCommand {
                 build_config,
                 cmd,
             } => execute_move_command(package_path, build_config, cmd),
+            SuiCommand::VerifySource {
+                package_path,
+                on_chain,
+                config,
+                build_config,
+            } => {
+                let config = config.unwrap_or(sui_config_dir()?.join(SUI_CLIENT_CONFIG));
+                prompt_if_no_config(&config)?;
+                let context = WalletContext::new(&config)?;
+                let verification =
+                    verify_source(&context.gateway, &package_path, build_config, on_chain).await?;
+                if json {
+                    print_json(&verification)?;
+                } else {
+                    println!("{verification}");
+                }
+                if !verification.is_verified() {
+                    bail!("Package {on_chain} does not match the source at {package_path:?}");
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Write the keystore holding the accounts of `network_config`, and the configs of a gateway and
+/// of a client connected to its validators, to `dir`. Returns the accounts.
+fn write_client_configs(
+    dir: &Path,
+    network_config: &NetworkConfig,
+) -> Result<Vec<SuiAddress>, anyhow::Error> {
+    let client_path = dir.join(SUI_CLIENT_CONFIG);
+    let gateway_path = dir.join(SUI_GATEWAY_CONFIG);
+    let keystore_path = dir.join(SUI_KEYSTORE_FILENAME);
+
+    let mut accounts = Vec::new();
+    let mut keystore = SuiKeystore::default();
+    for key in &network_config.account_keys {
+        let address = key.public().into();
+        accounts.push(address);
+        keystore.add_key(address, key.copy())?;
+    }
+    keystore.set_path(&keystore_path);
+    keystore.save()?;
+    info!("Client keystore is stored in {:?}.", keystore_path);
+
+    // Use the first address if any
+    let active_address = accounts.get(0).copied();
+
+    let validator_set = network_config.validator_set();
+    let chain_id = network_config.genesis.chain_id();
+
+    GatewayConfig::new(
+        validator_set.to_owned(),
+        chain_id,
+        dir.join("gateway_client_db"),
+    )
+    .save(&gateway_path)?;
+    info!("Gateway config file is stored in {:?}.", gateway_path);
+
+    let wallet_gateway_config =
+        GatewayConfig::new(validator_set.to_owned(), chain_id, dir.join("client_db"));
+
+    let wallet_config = SuiClientConfig {
+        accounts: accounts.clone(),
+        keystore: KeystoreType::File(keystore_path),
+        gateway: GatewayType::Embedded(wallet_gateway_config),
+        active_address,
+        envs: vec![],
+        active_env: None,
+        name_registry: None,
+    };
+
+    wallet_config.save(&client_path)?;
+    info!("Client config file is stored in {:?}.", client_path);
+    Ok(accounts)
+}
+
+/// The addresses of a validator, printed by `sui network --dump-addresses`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorAddresses {
+    pub network_address: Multiaddr,
+    pub sui_address: SuiAddress,
+}
+
+impl Display for ValidatorAddresses {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} - {}", self.network_address, self.sui_address)
+    }
+}
+
+/// The files written by `sui genesis`, and the accounts created, in JSON mode.
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisOutput {
+    /// Set when only the genesis config was written, with `--write-config`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genesis_config: Option<PathBuf>,
+    pub network_config: Option<PathBuf>,
+    pub genesis: Option<PathBuf>,
+    pub client_config: Option<PathBuf>,
+    pub gateway_config: Option<PathBuf>,
+    pub fullnode_config: Option<PathBuf>,
+    pub keystore: Option<PathBuf>,
+    pub validator_configs: Vec<PathBuf>,
+    pub accounts: Vec<SuiAddress>,
+}
+
+/// The JSON output of a failed command: the error and the errors that caused it, outermost
+/// first.
+#[derive(Serialize, Debug)]
+pub struct CommandError {
+    pub error: String,
+    pub causes: Vec<String>,
+}
+
+impl From<&anyhow::Error> for CommandError {
+    fn from(error: &anyhow::Error) -> Self {
+        Self {
+            error: error.to_string(),
+            causes: error.chain().skip(1).map(ToString::to_string).collect(),
         }
     }
 }
 
+/// Print `value` as pretty JSON on stdout.
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), anyhow::Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
 // Sync all accounts on start up.
 async fn sync_accounts(context: &mut WalletContext) -> Result<(), anyhow::Error> {
     for address in context.config.accounts.clone() {
@@ -369,6 +666,27 @@ async fn sync_accounts(context: &mut WalletContext) -> Result<(), anyhow::Error>
     Ok(())
 }
 
+/// Add the addresses of the keystore at `keystore_path` to the accounts of the default client
+/// config, if the client uses that keystore.
+fn add_keystore_accounts(keystore_path: &Path) -> Result<(), anyhow::Error> {
+    let config_path = sui_config_dir()?.join(SUI_CLIENT_CONFIG);
+    if !config_path.exists() {
+        return Ok(());
+    }
+    let config: SuiClientConfig = PersistedConfig::read(&config_path)?;
+    let mut config = config.persisted(&config_path);
+    if !matches!(config.active_keystore(), KeystoreType::File(path) if path == keystore_path) {
+        return Ok(());
+    }
+    let keystore = SuiKeystore::load_or_create(keystore_path)?;
+    for address in keystore.addresses() {
+        if !config.accounts.contains(&address) {
+            config.accounts.push(address);
+        }
+    }
+    config.save()
+}
+
 fn prompt_if_no_config(wallet_conf_path: &Path) -> Result<(), anyhow::Error> {
     // Prompt user for connect to gateway if config not exists.
     if !wallet_conf_path.exists() {
@@ -403,11 +721,23 @@ fn prompt_if_no_config(wallet_conf_path: &Path) -> Result<(), anyhow::Error> {
                 .join(SUI_KEYSTORE_FILENAME);
             let keystore = KeystoreType::File(keystore_path);
             let new_address = keystore.init()?.add_random_key()?;
+            let alias = if url == SUI_DEV_NET_URL {
+                "devnet"
+            } else {
+                "custom"
+            };
             SuiClientConfig {
                 accounts: vec![new_address],
                 keystore,
-                gateway: GatewayType::RPC(url),
+                gateway: GatewayType::RPC(url.clone()),
                 active_address: Some(new_address),
+                envs: vec![SuiEnv {
+                    alias: alias.to_string(),
+                    gateway: GatewayType::RPC(url),
+                    keystore: None,
+                }],
+                active_env: Some(alias.to_string()),
+                name_registry: None,
             }
             .persisted(wallet_conf_path)
             .save()?;
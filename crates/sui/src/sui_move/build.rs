@@ -1,10 +1,14 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use super::lint::{self, Severity};
+use super::manifest::SuiPackage;
+use anyhow::anyhow;
 use clap::Parser;
 use move_cli::base::{self, build};
 use move_package::BuildConfig;
 use std::path::{Path, PathBuf};
+use sui_types::sui_serde::{Base64, Encoding};
 
 #[derive(Parser)]
 pub struct Build {
@@ -13,25 +17,53 @@ pub struct Build {
     /// Whether we are printing in base64.
     #[clap(long, global = true)]
     pub dump_bytecode_as_base64: bool,
+    /// Do not run the Sui lints after the build.
+    #[clap(long, global = true)]
+    pub skip_lints: bool,
 }
 
 impl Build {
     pub fn execute(&self, path: Option<PathBuf>, build_config: BuildConfig) -> anyhow::Result<()> {
         let rerooted_path = base::reroot_path(path)?;
-        Self::execute_internal(&rerooted_path, build_config, self.dump_bytecode_as_base64)
+        let package = SuiPackage::prepare(&rerooted_path)?;
+        Self::execute_internal(
+            package.path(),
+            package.build_config(build_config),
+            self.dump_bytecode_as_base64,
+            !self.skip_lints,
+        )
+        .map_err(|error| package.remap_error(error))
     }
+
+    /// Build the package at `rerooted_path`, which must already depend on the Sui framework,
+    /// then lint its modules and run the Sui bytecode verifier on them.
     pub fn execute_internal(
         rerooted_path: &Path,
         build_config: BuildConfig,
         dump_bytecode_as_base64: bool,
+        run_lints: bool,
     ) -> anyhow::Result<()> {
-        // find manifest file directory from a given path or (if missing) from current dir
+        let modules = sui_framework::build_move_package(rerooted_path, build_config)?;
+        if run_lints {
+            let diagnostics = lint::lint_package(&modules);
+            for diagnostic in &diagnostics {
+                eprintln!("{diagnostic}");
+            }
+            let errors = diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == Severity::Error)
+                .count();
+            if errors > 0 {
+                return Err(anyhow!("Sui lints failed with {errors} error(s)"));
+            }
+        }
+        sui_framework::verify_modules(&modules)?;
         if dump_bytecode_as_base64 {
-            let compiled_modules =
-                sui_framework::build_move_package_to_base64(rerooted_path, build_config)?;
+            let compiled_modules = sui_framework::serialize_modules(&modules)
+                .iter()
+                .map(Base64::encode)
+                .collect::<Vec<_>>();
             println!("{:?}", compiled_modules);
-        } else {
-            sui_framework::build_and_verify_package(rerooted_path, build_config)?;
         }
         Ok(())
     }
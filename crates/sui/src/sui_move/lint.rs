@@ -0,0 +1,158 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+use colored::Colorize;
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::binary_views::BinaryIndexedView;
+use move_binary_format::file_format::{Bytecode, StructDefinitionIndex};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::ModuleId;
+
+use sui_verifier::entry_points_verifier::{
+    is_tx_context, verify_entry_function_impl, verify_init_function, INIT_FN_NAME,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    /// The package would be rejected by the Sui bytecode verifier.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    /// A module defines objects that none of its functions create, which usually means its
+    /// `init` function is missing.
+    MissingInit,
+    /// The signature of an `init` or entry function is not accepted by Sui.
+    EntryFunctionSignature,
+}
+
+/// A Sui-specific diagnostic about a module of a built package.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub lint: Lint,
+    pub module: ModuleId,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning".yellow().bold(),
+            Severity::Error => "error".red().bold(),
+        };
+        write!(
+            f,
+            "{severity}[{:?}]: {}: {}",
+            self.lint,
+            self.module.name(),
+            self.message
+        )
+    }
+}
+
+/// Lint the modules of the root package among `modules`, the modules of its dependencies are
+/// already published and are skipped.
+pub fn lint_package(modules: &[CompiledModule]) -> Vec<Diagnostic> {
+    modules
+        .iter()
+        .filter(|module| module.self_id().address() == &AccountAddress::ZERO)
+        .flat_map(lint_module)
+        .collect()
+}
+
+pub fn lint_module(module: &CompiledModule) -> Vec<Diagnostic> {
+    let mut diagnostics = check_entry_functions(module);
+    diagnostics.extend(check_missing_init(module));
+    diagnostics
+}
+
+/// Unlike the bytecode verifier, which stops at the first invalid function, report every
+/// `init` and entry function with an invalid signature.
+fn check_entry_functions(module: &CompiledModule) -> Vec<Diagnostic> {
+    let view = &BinaryIndexedView::Module(module);
+    let mut diagnostics = vec![];
+    for func_def in &module.function_defs {
+        let handle = module.function_handle_at(func_def.function);
+        let name = module.identifier_at(handle.name);
+        let result = if name == INIT_FN_NAME {
+            verify_init_function(module, func_def)
+        } else if func_def.is_entry {
+            let params = &view.signature_at(handle.parameters).0;
+            let misplaced_context = params
+                .iter()
+                .rev()
+                .skip(1)
+                .any(|param| is_tx_context(view, param));
+            if misplaced_context {
+                Err(format!(
+                    "The TxContext parameter of entry function {name} must be its last parameter"
+                ))
+            } else {
+                verify_entry_function_impl(module, func_def)
+            }
+        } else {
+            continue;
+        };
+        if let Err(message) = result {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                lint: Lint::EntryFunctionSignature,
+                module: module.self_id(),
+                message,
+            });
+        }
+    }
+    diagnostics
+}
+
+fn check_missing_init(module: &CompiledModule) -> Vec<Diagnostic> {
+    let packed: BTreeSet<StructDefinitionIndex> = module
+        .function_defs
+        .iter()
+        .filter_map(|func_def| func_def.code.as_ref())
+        .flat_map(|code| code.code.iter())
+        .filter_map(|instruction| match instruction {
+            Bytecode::Pack(idx) => Some(*idx),
+            Bytecode::PackGeneric(idx) => Some(module.struct_instantiation_at(*idx).def),
+            _ => None,
+        })
+        .collect();
+    let has_init = module.function_defs.iter().any(|func_def| {
+        module.identifier_at(module.function_handle_at(func_def.function).name) == INIT_FN_NAME
+    });
+
+    module
+        .struct_defs
+        .iter()
+        .enumerate()
+        .filter(|(idx, struct_def)| {
+            let handle = module.struct_handle_at(struct_def.struct_handle);
+            handle.abilities.has_key() && !packed.contains(&StructDefinitionIndex(*idx as u16))
+        })
+        .map(|(_, struct_def)| {
+            let handle = module.struct_handle_at(struct_def.struct_handle);
+            let name = module.identifier_at(handle.name);
+            let message = if has_init {
+                format!("Objects of type {name} are never created by the module")
+            } else {
+                format!(
+                    "Objects of type {name} are never created by the module, did you forget to \
+                     create them in an '{INIT_FN_NAME}' function?"
+                )
+            };
+            Diagnostic {
+                severity: Severity::Warning,
+                lint: Lint::MissingInit,
+                module: module.self_id(),
+                message,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,194 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use move_package::BuildConfig;
+use tempfile::TempDir;
+use toml::value::Table;
+use toml::Value;
+
+use super::new::{SUI_PKG_NAME, SUI_PKG_PATH};
+
+const MANIFEST_FILE_NAME: &str = "Move.toml";
+const BUILD_DIR_NAME: &str = "build";
+const DEPENDENCY_SECTIONS: [&str; 2] = ["dependencies", "dev-dependencies"];
+/// The address assigned to the modules of a package before publication.
+const UNPUBLISHED_ADDRESS: &str = "0x0";
+
+/// A Move package ready to be built by the Sui toolchain.
+///
+/// Packages that do not depend on the Sui framework, or that leave some of their addresses
+/// unassigned, are copied to a staging directory with a rewritten manifest: the framework is
+/// added to their dependencies and their unassigned addresses are set to 0x0, which is what
+/// they are replaced with at publication. Other packages are built in place.
+///
+/// Staged packages still get their build artifacts in their own `build` directory, see
+/// [`SuiPackage::build_config`], and the errors of their build refer to their own files, see
+/// [`SuiPackage::remap_error`].
+pub struct SuiPackage {
+    path: PathBuf,
+    package_path: PathBuf,
+    // Removed along with the package when dropped.
+    staging: Option<TempDir>,
+}
+
+impl SuiPackage {
+    pub fn prepare(package_path: &Path) -> Result<Self, anyhow::Error> {
+        let manifest_path = package_path.join(MANIFEST_FILE_NAME);
+        let manifest = fs::read_to_string(&manifest_path)
+            .with_context(|| format!("Cannot read {}", manifest_path.display()))?;
+        let mut manifest: Value = toml::from_str(&manifest)
+            .with_context(|| format!("Cannot parse {}", manifest_path.display()))?;
+
+        if !inject_sui_framework(&mut manifest)? {
+            return Ok(Self {
+                path: package_path.to_path_buf(),
+                package_path: package_path.to_path_buf(),
+                staging: None,
+            });
+        }
+
+        // Relative local dependencies would not resolve from the staging directory.
+        let package_path = package_path
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve {}", package_path.display()))?;
+        absolutize_local_dependencies(&mut manifest, &package_path)?;
+
+        let staging = tempfile::tempdir()?;
+        copy_package(&package_path, staging.path())?;
+        fs::write(
+            staging.path().join(MANIFEST_FILE_NAME),
+            toml::to_string(&manifest)?,
+        )?;
+        Ok(Self {
+            path: staging.path().to_path_buf(),
+            package_path,
+            staging: Some(staging),
+        })
+    }
+
+    /// The directory to build the package from.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the package is built from a staging directory with a rewritten manifest.
+    pub fn is_staged(&self) -> bool {
+        self.staging.is_some()
+    }
+
+    /// `build_config` installing the build artifacts of a staged package in the `build`
+    /// directory of the package rather than of its staging directory, unless another install
+    /// directory is set.
+    pub fn build_config(&self, mut build_config: BuildConfig) -> BuildConfig {
+        if self.is_staged() && build_config.install_dir.is_none() {
+            build_config.install_dir = Some(self.package_path.clone());
+        }
+        build_config
+    }
+
+    /// `error` with the paths of the staging directory replaced by the ones of the package,
+    /// so that the compiler diagnostics of a staged package point at its own sources.
+    pub fn remap_error(&self, error: anyhow::Error) -> anyhow::Error {
+        if !self.is_staged() {
+            return error;
+        }
+        anyhow!(format!("{error:#}").replace(
+            &self.path.display().to_string(),
+            &self.package_path.display().to_string(),
+        ))
+    }
+}
+
+/// Add the Sui framework to the dependencies of `manifest` and assign 0x0 to its unassigned
+/// addresses. Returns whether the manifest was modified.
+pub fn inject_sui_framework(manifest: &mut Value) -> Result<bool, anyhow::Error> {
+    let manifest = manifest
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("Invalid manifest, expected a table"))?;
+    let mut modified = false;
+
+    let dependencies = table_entry(manifest, "dependencies")?;
+    if !dependencies.contains_key(SUI_PKG_NAME) {
+        dependencies.insert(SUI_PKG_NAME.to_string(), sui_framework_dependency()?);
+        modified = true;
+    }
+
+    let addresses = table_entry(manifest, "addresses")?;
+    for value in addresses.values_mut() {
+        if value.as_str() == Some("_") {
+            *value = Value::String(UNPUBLISHED_ADDRESS.to_string());
+            modified = true;
+        }
+    }
+    Ok(modified)
+}
+
+/// A local copy of the framework when the binary was built from a checkout of this repository,
+/// the same git dependency `sui move new` adds otherwise.
+fn sui_framework_dependency() -> Result<Value, anyhow::Error> {
+    let local = Path::new(sui_framework::DEFAULT_FRAMEWORK_PATH);
+    if local.join(MANIFEST_FILE_NAME).exists() {
+        let mut dependency = Table::new();
+        dependency.insert(
+            "local".to_string(),
+            Value::String(local.display().to_string()),
+        );
+        return Ok(Value::Table(dependency));
+    }
+    let dependency: Table = toml::from_str(&format!("{SUI_PKG_NAME} = {SUI_PKG_PATH}"))?;
+    dependency
+        .get(SUI_PKG_NAME)
+        .cloned()
+        .ok_or_else(|| anyhow!("Invalid Sui framework dependency"))
+}
+
+fn absolutize_local_dependencies(
+    manifest: &mut Value,
+    package_path: &Path,
+) -> Result<(), anyhow::Error> {
+    let manifest = manifest
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("Invalid manifest, expected a table"))?;
+    for section in DEPENDENCY_SECTIONS {
+        if let Some(dependencies) = manifest.get_mut(section).and_then(Value::as_table_mut) {
+            for dependency in dependencies.values_mut() {
+                if let Some(Value::String(local)) = dependency.get_mut("local") {
+                    if Path::new(local.as_str()).is_relative() {
+                        *local = package_path.join(&*local).display().to_string();
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn table_entry<'a>(manifest: &'a mut Table, key: &str) -> Result<&'a mut Table, anyhow::Error> {
+    manifest
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Table(Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("Invalid manifest, expected [{key}] to be a table"))
+}
+
+/// Copy the package at `from` to `to`, without its build directory.
+fn copy_package(from: &Path, to: &Path) -> Result<(), anyhow::Error> {
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            if entry.file_name() == BUILD_DIR_NAME && from.join(MANIFEST_FILE_NAME).exists() {
+                continue;
+            }
+            fs::create_dir_all(&target)?;
+            copy_package(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
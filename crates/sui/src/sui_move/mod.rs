@@ -10,6 +10,8 @@ use std::path::PathBuf;
 pub mod build;
 pub mod coverage;
 pub mod disassemble;
+pub mod lint;
+pub mod manifest;
 pub mod new;
 pub mod prove;
 pub mod unit_test;
@@ -5,8 +5,8 @@ use clap::Parser;
 use move_cli::base::new;
 use std::path::PathBuf;
 
-const SUI_PKG_NAME: &str = "Sui";
-const SUI_PKG_PATH: &str = "{ git = \"https://github.com/MystenLabs/sui.git\", subdir = \"crates/sui-framework\", rev = \"main\" }";
+pub(crate) const SUI_PKG_NAME: &str = "Sui";
+pub(crate) const SUI_PKG_PATH: &str = "{ git = \"https://github.com/MystenLabs/sui.git\", subdir = \"crates/sui-framework\", rev = \"main\" }";
 
 #[derive(Parser)]
 pub struct New {
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::build;
+use super::manifest::SuiPackage;
 use clap::Parser;
 use move_cli::base::{
     self,
@@ -15,6 +16,9 @@ use std::path::PathBuf;
 pub struct Test {
     #[clap(flatten)]
     pub test: test::Test,
+    /// Do not run the Sui lints on the package before the tests.
+    #[clap(long)]
+    pub skip_lints: bool,
 }
 impl Test {
     pub fn execute(
@@ -25,18 +29,23 @@ impl Test {
     ) -> anyhow::Result<UnitTestResult> {
         // find manifest file directory from a given path or (if missing) from current dir
         let rerooted_path = base::reroot_path(path)?;
+        let package = SuiPackage::prepare(&rerooted_path)?;
+        let build_config = package.build_config(build_config);
         // pre build for Sui-specific verifications
         let dump_bytecode_as_base64 = false;
         build::Build::execute_internal(
-            &rerooted_path,
+            package.path(),
             build_config.clone(),
             dump_bytecode_as_base64,
-        )?;
+            !self.skip_lints,
+        )
+        .map_err(|error| package.remap_error(error))?;
         sui_framework::run_move_unit_tests(
-            &rerooted_path,
+            package.path(),
             build_config,
             Some(unit_test_config),
             self.test.compute_coverage,
         )
+        .map_err(|error| package.remap_error(error))
     }
 }
@@ -8,21 +8,47 @@ use move_package::BuildConfig;
 use serde_json::json;
 
 use sui::client_commands::SwitchResponse;
+use sui::keytool::{
+    decode_pem, encode_pem, generate_vanity_key_pairs, import_keypair, import_public_key,
+    read_keypair_from_file, write_keypair_to_file, KeyToolCommand, KeyToolCommandResult, PemKind,
+    SignatureScheme, ValidatorKeyKind,
+};
+use sui::sui_move::{
+    lint::{lint_package, Lint, Severity},
+    manifest::SuiPackage,
+};
+use sui::verify_source::{verify_source, ModuleVerification};
 use sui::{
-    client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext},
+    client_commands::{
+        ExportFormat, SuiClientCommandResult, SuiClientCommands, TxCommand, WalletContext,
+        MAX_AUTO_GAS_BUDGET,
+    },
     config::{GatewayConfig, GatewayType, SuiClientConfig},
-    sui_commands::SuiCommand,
+    name_service::Recipient,
+    signing_request::SigningRequest,
+    sui_commands::{CommandError, SuiCommand},
 };
 use sui_config::genesis_config::{AccountConfig, GenesisConfig, ObjectConfig};
 use sui_config::{
-    Config, NetworkConfig, PersistedConfig, ValidatorInfo, SUI_CLIENT_CONFIG, SUI_FULLNODE_CONFIG,
-    SUI_GATEWAY_CONFIG, SUI_GENESIS_FILENAME, SUI_KEYSTORE_FILENAME, SUI_NETWORK_CONFIG,
+    Config, NetworkConfig, NodeConfig, PersistedConfig, ValidatorInfo, SUI_CLIENT_CONFIG,
+    SUI_FULLNODE_CONFIG, SUI_GATEWAY_CONFIG, SUI_GENESIS_FILENAME, SUI_KEYSTORE_FILENAME,
+    SUI_NETWORK_CONFIG,
 };
 use sui_json::SuiJsonValue;
-use sui_json_rpc_types::{GetObjectDataResponse, SuiParsedObject, SuiTransactionEffects};
-use sui_sdk::crypto::KeystoreType;
-use sui_types::crypto::KeypairTraits;
-use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
+use sui_json_rpc_types::{
+    GetObjectDataResponse, SuiExecutionStatus, SuiParsedObject, SuiTransactionEffects,
+};
+use sui_sdk::crypto::{KeystoreType, SuiKeystore};
+use sui_types::crypto::{EncodeDecodeBase64, KeypairTraits, PublicKeyBytes, SignableBytes};
+use sui_types::intent::{IntentScope, PersonalMessage};
+use sui_types::sui_serde::{Base64, Encoding};
+use sui_types::{
+    base_types::{encode_bytes_hex, ChainId, ObjectID, SuiAddress},
+    crypto::get_key_pair,
+    gas_coin::GasCoin,
+    object::Owner,
+    SUI_FRAMEWORK_OBJECT_ID,
+};
 
 use test_utils::network::{setup_network_and_wallet, start_test_network};
 
@@ -38,7 +64,7 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
     let start = SuiCommand::Start {
         config: Some(config),
     }
-    .execute()
+    .execute(false)
     .await;
     assert!(matches!(start, Err(..)));
     // Genesis
@@ -48,7 +74,7 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
         force: false,
         from_config: None,
     }
-    .execute()
+    .execute(false)
     .await?;
 
     // Get all the new file names
@@ -90,7 +116,7 @@ async fn test_genesis() -> Result<(), anyhow::Error> {
         force: false,
         from_config: None,
     }
-    .execute()
+    .execute(false)
     .await;
     assert!(matches!(result, Err(..)));
 
@@ -106,11 +132,13 @@ async fn test_addresses_command() -> Result<(), anyhow::Error> {
     let wallet_config = SuiClientConfig {
         accounts: vec![],
         keystore: KeystoreType::File(working_dir.join(SUI_KEYSTORE_FILENAME)),
-        gateway: GatewayType::Embedded(GatewayConfig {
-            db_folder_path: working_dir.join("client_db"),
-            validator_set: vec![ValidatorInfo {
+        gateway: GatewayType::Embedded(GatewayConfig::new(
+            vec![ValidatorInfo {
                 name: "0".into(),
                 public_key: get_key_pair().1.public().into(),
+                network_key: None,
+                worker_key: None,
+                account_key: None,
                 stake: 1,
                 delegation: 1,
                 network_address: sui_config::utils::new_network_address(),
@@ -120,9 +148,13 @@ async fn test_addresses_command() -> Result<(), anyhow::Error> {
                 narwhal_worker_to_worker: sui_config::utils::new_network_address(),
                 narwhal_consensus_address: sui_config::utils::new_network_address(),
             }],
-            ..Default::default()
-        }),
+            ChainId::deterministic_for_testing(0),
+            working_dir.join("client_db"),
+        )),
         active_address: None,
+        envs: vec![],
+        active_env: None,
+        name_registry: None,
     };
     let wallet_conf_path = working_dir.join(SUI_CLIENT_CONFIG);
     let mut wallet_config = wallet_config.persisted(&wallet_conf_path);
@@ -207,7 +239,7 @@ async fn test_custom_genesis() -> Result<(), anyhow::Error> {
     config.accounts.push(AccountConfig {
         address: None,
         gas_objects: vec![ObjectConfig {
-            object_id,
+            object_id: Some(object_id),
             gas_value: 500,
         }],
         gas_object_ranges: None,
@@ -283,10 +315,11 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Send an object
     SuiClientCommands::Transfer {
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: object_to_send,
         gas: Some(object_id),
-        gas_budget: 50000,
+        gas_budget: Some(50000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -354,7 +387,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args,
         gas: None,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -394,7 +428,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await;
@@ -422,7 +457,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await;
@@ -449,7 +485,8 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
         type_args: vec![],
         args: args.to_vec(),
         gas: Some(gas),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -478,7 +515,10 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
         package_path,
         build_config,
         gas: Some(gas_obj_id),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
+        skip_dependency_verification: false,
+        dry_run: false,
     }
     .execute(&mut context)
     .await?;
@@ -519,6 +559,106 @@ async fn test_package_publish_command() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_publish_dry_run() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let gas_obj_id = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?
+        .first()
+        .unwrap()
+        .object_id;
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let publish = |dry_run| SuiClientCommands::Publish {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        gas: Some(gas_obj_id),
+        gas_budget: Some(1000),
+        estimate_gas: false,
+        skip_dependency_verification: false,
+        dry_run,
+    };
+
+    let preview = match publish(true).execute(&mut context).await? {
+        SuiClientCommandResult::PublishPreview(preview) => preview,
+        _ => panic!("Command failed"),
+    };
+    assert!(!preview.created_objects.is_empty());
+    assert!(preview.gas_estimate.gas_units > 0);
+
+    // Nothing is published by the dry run.
+    let resp = SuiClientCommands::Object {
+        id: preview.package.object_id,
+    }
+    .execute(&mut context)
+    .await?;
+    assert!(matches!(
+        resp,
+        SuiClientCommandResult::Object(GetObjectDataResponse::NotExists(..))
+    ));
+
+    // The same transaction publishes the package with the previewed ID.
+    let response = match publish(false).execute(&mut context).await? {
+        SuiClientCommandResult::Publish(response) => response,
+        _ => panic!("Command failed"),
+    };
+    assert_eq!(response.package.object_id, preview.package.object_id);
+    assert_eq!(
+        response.created_objects.len(),
+        preview.created_objects.len()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_source() -> Result<(), anyhow::Error> {
+    let (_network, mut context, _) = setup_network_and_wallet().await?;
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_publish");
+    let resp = SuiClientCommands::Publish {
+        package_path: package_path.clone(),
+        build_config: BuildConfig::default(),
+        gas: None,
+        gas_budget: Some(1000),
+        estimate_gas: false,
+        skip_dependency_verification: false,
+        dry_run: false,
+    }
+    .execute(&mut context)
+    .await?;
+    let package_id = match resp {
+        SuiClientCommandResult::Publish(response) => response.package.object_id,
+        _ => panic!("Command failed"),
+    };
+
+    let verification = verify_source(
+        &context.gateway,
+        &package_path,
+        BuildConfig::default(),
+        package_id,
+    )
+    .await?;
+    assert!(verification.is_verified());
+    assert!(!verification.modules.is_empty());
+
+    // The same source does not match another package.
+    let verification = verify_source(
+        &context.gateway,
+        &package_path,
+        BuildConfig::default(),
+        SUI_FRAMEWORK_OBJECT_ID,
+    )
+    .await?;
+    assert!(!verification.is_verified());
+    assert!(verification
+        .modules
+        .values()
+        .any(|module| *module == ModuleVerification::MissingLocally));
+    Ok(())
+}
+
 #[allow(clippy::assertions_on_constants)]
 #[tokio::test]
 async fn test_native_transfer() -> Result<(), anyhow::Error> {
@@ -536,9 +676,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: Some(gas_obj_id),
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: obj_id,
-        gas_budget: 50000,
+        gas_budget: Some(50000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -625,9 +766,10 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: None,
-        to: recipient,
+        to: recipient.into(),
         coin_object_id: obj_id,
-        gas_budget: 50000,
+        gas_budget: Some(50000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -707,6 +849,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     let resp = SuiClientCommands::Switch {
         address: Some(addr2),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -718,7 +861,8 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
             "{}",
             SuiClientCommandResult::Switch(SwitchResponse {
                 address: Some(addr2),
-                gateway: None
+                gateway: None,
+                env: None
             })
         )
     );
@@ -742,6 +886,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     let resp = SuiClientCommands::Switch {
         address: Some(new_addr),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -752,10 +897,86 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
             "{}",
             SuiClientCommandResult::Switch(SwitchResponse {
                 address: Some(new_addr),
-                gateway: None
+                gateway: None,
+                env: None
+            })
+        )
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_switch_env() -> Result<(), anyhow::Error> {
+    let (_network, mut context, _address) = setup_network_and_wallet().await?;
+    let dir = tempfile::tempdir()?;
+    let keystore = dir.path().join(SUI_KEYSTORE_FILENAME);
+
+    SuiClientCommands::NewEnv {
+        alias: "localtest".to_string(),
+        rpc: vec!["http://127.0.0.1:9000".to_string()],
+        race_reads: false,
+        keystore: Some(keystore.clone()),
+    }
+    .execute(&mut context)
+    .await?;
+
+    // Aliases are unique
+    assert!(SuiClientCommands::NewEnv {
+        alias: "localtest".to_string(),
+        rpc: vec!["http://127.0.0.1:9001".to_string()],
+        race_reads: false,
+        keystore: None,
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    let resp = SuiClientCommands::Envs.execute(&mut context).await?;
+    let envs = if let SuiClientCommandResult::Envs(envs) = resp {
+        envs
+    } else {
+        panic!("Command failed")
+    };
+    assert_eq!(envs.len(), 1);
+    assert_eq!(envs[0].alias, "localtest");
+    assert!(!envs[0].active);
+
+    let resp = SuiClientCommands::Switch {
+        address: None,
+        gateway: None,
+        env: Some("localtest".to_string()),
+    }
+    .execute(&mut context)
+    .await?;
+    assert_eq!(
+        format!("{resp}"),
+        format!(
+            "{}",
+            SuiClientCommandResult::Switch(SwitchResponse {
+                address: None,
+                gateway: None,
+                env: Some("localtest".to_string())
             })
         )
     );
+
+    // The environment settings are used without overwriting the default ones
+    let config: SuiClientConfig = PersistedConfig::read(context.config.path())?;
+    assert_eq!(config.active_env.as_deref(), Some("localtest"));
+    assert!(matches!(config.gateway, GatewayType::Embedded(_)));
+    assert!(
+        matches!(config.active_gateway(), GatewayType::RPC(url) if url == "http://127.0.0.1:9000")
+    );
+    assert!(matches!(config.active_keystore(), KeystoreType::File(path) if path == &keystore));
+
+    assert!(SuiClientCommands::Switch {
+        address: None,
+        gateway: None,
+        env: Some("unknown".to_string()),
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
     Ok(())
 }
 
@@ -795,6 +1016,7 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
     let resp = SuiClientCommands::Switch {
         address: Some(addr2),
         gateway: None,
+        env: None,
     }
     .execute(&mut context)
     .await?;
@@ -804,7 +1026,8 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
             "{}",
             SuiClientCommandResult::Switch(SwitchResponse {
                 address: Some(addr2),
-                gateway: None
+                gateway: None,
+                env: None
             })
         )
     );
@@ -844,9 +1067,11 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
     // Test with gas specified
     let resp = SuiClientCommands::MergeCoin {
         primary_coin,
-        coin_to_merge,
+        coin_to_merge: Some(coin_to_merge),
+        all: false,
         gas: Some(gas),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -883,9 +1108,11 @@ async fn test_merge_coin() -> Result<(), anyhow::Error> {
     // Test with no gas specified
     let resp = SuiClientCommands::MergeCoin {
         primary_coin,
-        coin_to_merge,
+        coin_to_merge: Some(coin_to_merge),
+        all: false,
         gas: None,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
     }
     .execute(&mut context)
     .await?;
@@ -923,9 +1150,11 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: Some(gas),
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
         coin_id: coin,
         amounts: vec![1000, 10],
+        count: None,
     }
     .execute(&mut context)
     .await?;
@@ -964,9 +1193,11 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     // Test with no gas specified
     let resp = SuiClientCommands::SplitCoin {
         gas: None,
-        gas_budget: 1000,
+        gas_budget: Some(1000),
+        estimate_gas: false,
         coin_id: coin,
         amounts: vec![1000, 10],
+        count: None,
     }
     .execute(&mut context)
     .await?;
@@ -983,3 +1214,825 @@ async fn test_split_coin() -> Result<(), anyhow::Error> {
     assert!((get_gas_value(&g.new_coins[1]) == 1000) || (get_gas_value(&g.new_coins[1]) == 10));
     Ok(())
 }
+
+#[tokio::test]
+async fn test_automatic_gas_selection() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let object_to_send = object_refs.first().unwrap().object_id;
+
+    // Neither gas coin nor budget: another coin pays for the gas, with an estimated budget.
+    let resp = SuiClientCommands::Transfer {
+        to: recipient.into(),
+        coin_object_id: object_to_send,
+        gas: None,
+        gas_budget: None,
+        estimate_gas: false,
+    }
+    .execute(&mut context)
+    .await?;
+    let (cert, effects) = match resp {
+        SuiClientCommandResult::Transfer(_, cert, effects) => (cert, effects),
+        _ => panic!("Command failed"),
+    };
+    assert!(matches!(effects.status, SuiExecutionStatus::Success));
+    assert_ne!(effects.gas_object.reference.object_id, object_to_send);
+    assert!(cert.data.gas_budget <= MAX_AUTO_GAS_BUDGET);
+
+    // A budget larger than any coin merges coins into the gas coin first.
+    let largest = context
+        .gas_objects(address)
+        .await?
+        .iter()
+        .map(|(value, _)| *value)
+        .max()
+        .unwrap();
+    let (gas, budget) = context
+        .select_gas(address, None, Some(largest + 1), &Default::default())
+        .await?;
+    assert_eq!(budget, largest + 1);
+    let gas_value = get_gas_value(&get_object(gas, &mut context).await.unwrap());
+    assert!(gas_value > largest);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pay_and_pay_all() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let input_coins = vec![object_refs[1].object_id, object_refs[2].object_id];
+
+    let resp = SuiClientCommands::Pay {
+        input_coins: input_coins.clone(),
+        recipients: vec![recipient.into(), recipient.into()],
+        amounts: vec![10, 20],
+        gas: Some(object_refs[0].object_id),
+        gas_budget: Some(1000),
+        estimate_gas: false,
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = match resp {
+        SuiClientCommandResult::Pay(_, effects) => effects,
+        _ => panic!("Command failed"),
+    };
+    assert!(matches!(effects.status, SuiExecutionStatus::Success));
+    assert_eq!(effects.created.len(), 2);
+    assert!(effects
+        .created
+        .iter()
+        .all(|object| object.owner == Owner::AddressOwner(recipient)));
+    // The second input coin was merged into the first one.
+    assert_eq!(get_object(input_coins[1], &mut context).await, None);
+
+    let received_before = context.gas_objects(recipient).await?.len();
+    let sender_coins = context.gas_objects(address).await?.len();
+    let resp = SuiClientCommands::PayAll {
+        to: recipient.into(),
+        gas_budget: None,
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = match resp {
+        SuiClientCommandResult::PayAll(_, effects) => effects,
+        _ => panic!("Command failed"),
+    };
+    assert!(matches!(effects.status, SuiExecutionStatus::Success));
+    // The gas coin is transferred, the other coins are merged into one new coin.
+    assert!(context.gas_objects(address).await?.is_empty());
+    let expected = if sender_coins > 1 { 2 } else { 1 };
+    assert_eq!(
+        context.gas_objects(recipient).await?.len(),
+        received_before + expected
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_merge_all_coins() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+
+    let gas = object_refs.first().unwrap().object_id;
+    let primary_coin = object_refs.get(1).unwrap().object_id;
+    let mut total_value = 0;
+    for o in object_refs.iter().skip(1) {
+        total_value += get_gas_value(&get_object(o.object_id, &mut context).await.unwrap());
+    }
+
+    let resp = SuiClientCommands::MergeCoin {
+        primary_coin,
+        coin_to_merge: None,
+        all: true,
+        gas: Some(gas),
+        gas_budget: Some(10000),
+        estimate_gas: false,
+    }
+    .execute(&mut context)
+    .await?;
+
+    let r = if let SuiClientCommandResult::MergeAllCoins(r) = resp {
+        r
+    } else {
+        panic!("Command failed")
+    };
+
+    // All coins but the gas coin end up in the primary coin
+    assert_eq!(r.merged_coins, object_refs.len() - 2);
+    assert_eq!(get_gas_value(&r.updated_coin), total_value);
+    for o in object_refs.iter().skip(2) {
+        assert_eq!(get_object(o.object_id, &mut context).await, None);
+    }
+    assert!(get_object(gas, &mut context).await.is_some());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_split_coin_count() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+
+    let gas = object_refs.first().unwrap().object_id;
+    let coin = object_refs.get(1).unwrap().object_id;
+    let orig_value = get_gas_value(&get_object(coin, &mut context).await.unwrap());
+
+    let resp = SuiClientCommands::SplitCoin {
+        gas: Some(gas),
+        gas_budget: Some(1000),
+        estimate_gas: false,
+        coin_id: coin,
+        amounts: vec![],
+        count: Some(3),
+    }
+    .execute(&mut context)
+    .await?;
+
+    let g = if let SuiClientCommandResult::SplitCoin(r) = resp {
+        r
+    } else {
+        panic!("Command failed")
+    };
+
+    // The original coin keeps the remainder of the division
+    assert_eq!(g.new_coins.len(), 2);
+    for new_coin in &g.new_coins {
+        assert_eq!(get_gas_value(new_coin), orig_value / 3);
+    }
+    assert_eq!(
+        get_gas_value(&g.updated_coin),
+        orig_value - 2 * (orig_value / 3)
+    );
+
+    // Splitting into a single coin is rejected
+    let result = SuiClientCommands::SplitCoin {
+        gas: Some(gas),
+        gas_budget: Some(1000),
+        estimate_gas: false,
+        coin_id: coin,
+        amounts: vec![],
+        count: Some(1),
+    }
+    .execute(&mut context)
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_offline_signing() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas = object_refs.first().unwrap().object_id;
+    let object_to_send = object_refs.get(1).unwrap().object_id;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+
+    let dir = tempfile::tempdir()?;
+    let transactions = dir.path().join("transactions.json");
+    let unsigned = dir.path().join("unsigned.json");
+    let signed = dir.path().join("signed.json");
+    std::fs::write(
+        &transactions,
+        json!([{
+            "transferObjectRequestParams": {
+                "recipient": recipient,
+                "objectId": object_to_send,
+            }
+        }])
+        .to_string(),
+    )?;
+
+    SuiClientCommands::Tx {
+        cmd: TxCommand::Build {
+            file: transactions,
+            output: unsigned.clone(),
+            sender: Some(address),
+            gas: Some(gas),
+            gas_budget: 1000,
+        },
+    }
+    .execute(&mut context)
+    .await?;
+
+    // Submitting an unsigned transaction fails
+    assert!(SuiClientCommands::Tx {
+        cmd: TxCommand::Submit {
+            signed: unsigned.clone(),
+        },
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+
+    SuiClientCommands::Tx {
+        cmd: TxCommand::Sign {
+            input: unsigned,
+            output: signed.clone(),
+            key: None,
+        },
+    }
+    .execute(&mut context)
+    .await?;
+
+    let resp = SuiClientCommands::Tx {
+        cmd: TxCommand::Submit { signed },
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = if let SuiClientCommandResult::Submit(_, effects) = resp {
+        effects
+    } else {
+        panic!("Command failed")
+    };
+    assert!(matches!(effects.status, SuiExecutionStatus::Success));
+    assert!(effects
+        .mutated
+        .iter()
+        .any(|o| o.reference.object_id == object_to_send));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sign_request() -> Result<(), anyhow::Error> {
+    let (_network, context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas = object_refs.first().unwrap().object_id;
+    let object_to_send = object_refs.get(1).unwrap().object_id;
+    let recipient = context.config.accounts.get(1).cloned().unwrap();
+    let data = context
+        .gateway
+        .public_transfer_object(address, object_to_send, Some(gas), 1000, recipient)
+        .await?;
+
+    let origin = "https://example.app".to_string();
+    let request = SigningRequest::new(
+        origin.clone(),
+        IntentScope::TransactionData,
+        address,
+        &data.to_bytes(),
+    )?;
+    let display = request.to_string();
+    assert!(display.contains(&origin));
+    assert!(display.contains(&format!("Transfer object {object_to_send} to {recipient}")));
+
+    // Rejected requests are not signed
+    assert!(context.sign_request_with(&request, |_| Ok(false)).is_err());
+    let signature = context.sign_request_with(&request, |_| Ok(true))?;
+    signature.verify(&data, address)?;
+
+    // Transactions can only be signed by their sender
+    assert!(SigningRequest::new(
+        origin.clone(),
+        IntentScope::TransactionData,
+        recipient,
+        &data.to_bytes(),
+    )
+    .is_err());
+
+    // The signature of a message is not valid for a transaction with the same bytes
+    let request = SigningRequest::new(
+        origin,
+        IntentScope::PersonalMessage,
+        address,
+        &data.to_bytes(),
+    )?;
+    let signature = context.sign_request_with(&request, |_| Ok(true))?;
+    signature.verify(
+        &PersonalMessage {
+            message: data.to_bytes(),
+        },
+        address,
+    )?;
+    assert!(signature.verify(&data, address).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watch_only_address() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let gas = object_refs.first().unwrap().object_id;
+    let (watched, keypair) = get_key_pair();
+
+    SuiClientCommands::AddWatchAddress { address: watched }
+        .execute(&mut context)
+        .await?;
+    assert!(context.config.accounts.contains(&watched));
+    assert!(context.keystore.is_watch_only(&watched));
+
+    // Fund the watched address with a gas coin and an object to send
+    let watched_gas = object_refs.get(1).unwrap().object_id;
+    let object_to_send = object_refs.get(2).unwrap().object_id;
+    for object in [watched_gas, object_to_send] {
+        SuiClientCommands::Transfer {
+            to: watched.into(),
+            coin_object_id: object,
+            gas: Some(gas),
+            gas_budget: Some(50000),
+            estimate_gas: false,
+        }
+        .execute(&mut context)
+        .await?;
+    }
+
+    let resp = SuiClientCommands::Objects {
+        address: Some(watched),
+    }
+    .execute(&mut context)
+    .await?;
+    let objects = if let SuiClientCommandResult::Objects(objects) = resp {
+        objects
+    } else {
+        panic!("Command failed")
+    };
+    assert_eq!(objects.len(), 2);
+
+    // Transactions of the watched address cannot be signed by the wallet
+    let resp = SuiClientCommands::Transfer {
+        to: address.into(),
+        coin_object_id: object_to_send,
+        gas: Some(watched_gas),
+        gas_budget: Some(1000),
+        estimate_gas: false,
+    }
+    .execute(&mut context)
+    .await;
+    assert!(resp.unwrap_err().to_string().contains("watch-only"));
+
+    // But they can be built by the wallet and signed externally
+    let dir = tempfile::tempdir()?;
+    let transactions = dir.path().join("transactions.json");
+    let unsigned = dir.path().join("unsigned.json");
+    let signed = dir.path().join("signed.json");
+    let key_file = dir.path().join("watched.key");
+    write_keypair_to_file(&keypair, &key_file)?;
+    std::fs::write(
+        &transactions,
+        json!([{
+            "transferObjectRequestParams": {
+                "recipient": address,
+                "objectId": object_to_send,
+            }
+        }])
+        .to_string(),
+    )?;
+    SuiClientCommands::Tx {
+        cmd: TxCommand::Build {
+            file: transactions,
+            output: unsigned.clone(),
+            sender: Some(watched),
+            gas: Some(watched_gas),
+            gas_budget: 1000,
+        },
+    }
+    .execute(&mut context)
+    .await?;
+    assert!(SuiClientCommands::Tx {
+        cmd: TxCommand::Sign {
+            input: unsigned.clone(),
+            output: signed.clone(),
+            key: None,
+        },
+    }
+    .execute(&mut context)
+    .await
+    .is_err());
+    SuiClientCommands::Tx {
+        cmd: TxCommand::Sign {
+            input: unsigned,
+            output: signed.clone(),
+            key: Some(key_file),
+        },
+    }
+    .execute(&mut context)
+    .await?;
+    let resp = SuiClientCommands::Tx {
+        cmd: TxCommand::Submit { signed },
+    }
+    .execute(&mut context)
+    .await?;
+    let effects = if let SuiClientCommandResult::Submit(_, effects) = resp {
+        effects
+    } else {
+        panic!("Command failed")
+    };
+    assert!(matches!(effects.status, SuiExecutionStatus::Success));
+    Ok(())
+}
+
+#[test]
+fn test_json_output() -> Result<(), anyhow::Error> {
+    let address = SuiAddress::random_for_testing_only();
+    assert_eq!(
+        serde_json::to_value(&SuiClientCommandResult::Addresses(vec![address]))?,
+        json!({ "kind": "addresses", "result": [address] })
+    );
+    assert_eq!(
+        serde_json::to_value(&SuiClientCommandResult::SyncClientState)?,
+        json!({ "kind": "syncClientState" })
+    );
+    assert_eq!(
+        serde_json::to_value(&KeyToolCommandResult::Import { address })?,
+        json!({ "kind": "import", "result": { "address": address } })
+    );
+
+    let error = anyhow!("Connection refused").context("Cannot reach the gateway");
+    assert_eq!(
+        serde_json::to_value(&CommandError::from(&error))?,
+        json!({ "error": "Cannot reach the gateway", "causes": ["Connection refused"] })
+    );
+    Ok(())
+}
+
+#[test]
+fn test_move_build_lints() -> Result<(), anyhow::Error> {
+    let path = PathBuf::from(TEST_DATA_DIR).join("move_lints");
+    // The package neither depends on the Sui framework nor assigns its address.
+    let package = SuiPackage::prepare(&path)?;
+    assert!(package.is_staged());
+
+    let modules = sui_framework::build_move_package(package.path(), BuildConfig::default())?;
+    let mut diagnostics = lint_package(&modules)
+        .into_iter()
+        .map(|diagnostic| (diagnostic.severity, diagnostic.lint))
+        .collect::<Vec<_>>();
+    diagnostics.sort_by_key(|(severity, _)| *severity);
+    assert_eq!(
+        diagnostics,
+        vec![
+            (Severity::Warning, Lint::MissingInit),
+            (Severity::Error, Lint::EntryFunctionSignature),
+        ]
+    );
+
+    // Packages that already depend on the framework are built in place.
+    let path = PathBuf::from(TEST_DATA_DIR).join("dummy_modules_publish");
+    assert!(!SuiPackage::prepare(&path)?.is_staged());
+    Ok(())
+}
+
+#[test]
+fn test_move_build_staged_package() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().canonicalize()?;
+    std::fs::write(
+        path.join("Move.toml"),
+        "[package]\nname = \"Staged\"\nversion = \"0.0.1\"\n\n[addresses]\nstaged = \"_\"\n",
+    )?;
+    std::fs::create_dir(path.join("sources"))?;
+    let source = path.join("sources").join("m.move");
+    std::fs::write(&source, "module staged::m { public fun f(): u64 { 1 } }\n")?;
+
+    // The artifacts of a staged package are installed in its own build directory
+    let package = SuiPackage::prepare(&path)?;
+    assert!(package.is_staged());
+    sui_framework::build_move_package(
+        package.path(),
+        package.build_config(BuildConfig::default()),
+    )?;
+    assert!(path.join("build").join("Staged").exists());
+
+    // And its build errors point at its own sources
+    std::fs::write(
+        &source,
+        "module staged::m { public fun f(): u64 { true } }\n",
+    )?;
+    let package = SuiPackage::prepare(&path)?;
+    let error = sui_framework::build_move_package(
+        package.path(),
+        package.build_config(BuildConfig::default()),
+    )
+    .map_err(|error| package.remap_error(error.into()))
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains(&source.display().to_string()), "{error}");
+    assert!(
+        !error.contains(&package.path().display().to_string()),
+        "{error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_keytool_import_export() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let keystore_path = dir.path().join(SUI_KEYSTORE_FILENAME);
+    let (address, keypair) = get_key_pair();
+
+    // Import the Hex encoded private key
+    let bytes = Base64::decode(&keypair.encode_base64()).map_err(|e| anyhow!(e))?;
+    KeyToolCommand::Import {
+        input: format!("0x{}", encode_bytes_hex(&bytes[..32])),
+        scheme: SignatureScheme::Ed25519,
+    }
+    .execute(SuiKeystore::load_or_create(&keystore_path)?)?;
+    let keystore = SuiKeystore::load_or_create(&keystore_path)?;
+    assert_eq!(keystore.addresses(), vec![address]);
+
+    // The Base64 keypair, as stored in the keystore, gives the same key
+    let imported = import_keypair(&keypair.encode_base64(), SignatureScheme::Ed25519)?;
+    assert_eq!(SuiAddress::from(imported.public()), address);
+
+    // Mnemonics are derived deterministically
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+        abandon abandon about";
+    let first = import_keypair(mnemonic, SignatureScheme::Ed25519)?;
+    let second = import_keypair(mnemonic, SignatureScheme::Ed25519)?;
+    assert_eq!(first.public(), second.public());
+    assert!(import_keypair("abandon abandon", SignatureScheme::Ed25519).is_err());
+
+    KeyToolCommand::Export { address, yes: true }.execute(keystore)?;
+    assert!(KeyToolCommand::Export {
+        address: get_key_pair().0,
+        yes: true,
+    }
+    .execute(SuiKeystore::load_or_create(&keystore_path)?)
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_keytool_convert() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let keystore_path = dir.path().join(SUI_KEYSTORE_FILENAME);
+    let convert = |input: String, public: bool| -> Result<_, anyhow::Error> {
+        match (KeyToolCommand::Convert {
+            input,
+            public,
+            scheme: SignatureScheme::Ed25519,
+        })
+        .execute(SuiKeystore::load_or_create(&keystore_path)?)?
+        {
+            KeyToolCommandResult::Convert(key) => Ok(key),
+            result => Err(anyhow!("Unexpected result {result:?}")),
+        }
+    };
+    let (address, keypair) = get_key_pair();
+    let bytes = Base64::decode(&keypair.encode_base64()).map_err(|e| anyhow!(e))?;
+
+    // Each encoding of the private key converts back to the same encodings
+    let key = convert(format!("0x{}", encode_bytes_hex(&bytes[..32])), false)?;
+    assert_eq!(key.address, address);
+    let private_key = key.private_key.unwrap();
+    assert_eq!(Base64::decode(&private_key.base64)?, bytes[..32].to_vec());
+    for input in [&private_key.base64, &private_key.pem] {
+        let converted = convert(input.clone(), false)?;
+        assert_eq!(converted.address, address);
+        assert_eq!(converted.private_key.as_ref(), Some(&private_key));
+        assert_eq!(converted.public_key, key.public_key);
+    }
+    let imported = import_keypair(&private_key.pem, SignatureScheme::Ed25519)?;
+    assert_eq!(imported.public(), keypair.public());
+
+    // And so does each encoding of the public key, into the same address
+    assert_eq!(
+        key.public_key.base64,
+        Base64::encode(keypair.public().as_ref())
+    );
+    for input in [
+        &key.public_key.hex,
+        &key.public_key.base64,
+        &key.public_key.pem,
+    ] {
+        let converted = convert(input.clone(), true)?;
+        assert_eq!(converted.address, address);
+        assert_eq!(converted.public_key, key.public_key);
+        assert!(converted.private_key.is_none());
+    }
+    assert_eq!(
+        &import_public_key(&key.public_key.pem, SignatureScheme::Ed25519)?,
+        keypair.public()
+    );
+
+    // PEM documents are read as the kind of key they are written as
+    let pem = encode_pem(&bytes[..32], PemKind::PrivateKey);
+    assert_eq!(decode_pem(&pem, PemKind::PrivateKey)?, bytes[..32].to_vec());
+    assert!(decode_pem(&pem, PemKind::PublicKey).is_err());
+    assert!(convert(key.public_key.pem.clone(), false).is_err());
+
+    // Mnemonics convert to the key they are imported as
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+        abandon abandon about";
+    let derived = import_keypair(mnemonic, SignatureScheme::Ed25519)?;
+    let key = convert(mnemonic.to_string(), false)?;
+    assert_eq!(key.address, SuiAddress::from(derived.public()));
+    let converted = convert(key.private_key.unwrap().hex, false)?;
+    assert_eq!(converted.address, key.address);
+    Ok(())
+}
+
+#[test]
+fn test_keytool_generate() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let keystore_path = dir.path().join(SUI_KEYSTORE_FILENAME);
+
+    KeyToolCommand::Generate {
+        count: 3,
+        scheme: SignatureScheme::Ed25519,
+        vanity_prefix: None,
+        threads: None,
+        add_to_keystore: true,
+        output: None,
+    }
+    .execute(SuiKeystore::load_or_create(&keystore_path)?)?;
+    assert_eq!(
+        SuiKeystore::load_or_create(&keystore_path)?
+            .addresses()
+            .len(),
+        3
+    );
+
+    let output = dir.path().join("keys.txt");
+    let result = KeyToolCommand::Generate {
+        count: 2,
+        scheme: SignatureScheme::Ed25519,
+        vanity_prefix: Some("0xa".to_string()),
+        threads: Some(2),
+        add_to_keystore: false,
+        output: Some(output.clone()),
+    }
+    .execute(SuiKeystore::load_or_create(&keystore_path)?)?;
+    let keys = match result {
+        KeyToolCommandResult::Generate(keys) => keys,
+        _ => panic!("Command failed with unexpected result {result:?}"),
+    };
+    assert_eq!(keys.len(), 2);
+    assert!(keys
+        .iter()
+        .all(|key| key.address.to_string().starts_with("0xa")));
+    let lines = std::fs::read_to_string(output)?;
+    assert_eq!(lines.lines().count(), 2);
+    assert!(lines.starts_with(&keys[0].address.to_string()));
+
+    assert!(generate_vanity_key_pairs("0xzz", 1, 1).is_err());
+    assert!(generate_vanity_key_pairs(&"a".repeat(41), 1, 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_keytool_rotate_validator_key() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let keystore_path = dir.path().join(SUI_KEYSTORE_FILENAME);
+    let config_path = dir.path().join("validator.yaml");
+    NetworkConfig::generate(dir.path(), 1).validator_configs[0].save(&config_path)?;
+    let initial = NodeConfig::load(&config_path)?;
+    let rotate = |kind| {
+        KeyToolCommand::RotateValidatorKey {
+            config: config_path.clone(),
+            kind,
+        }
+        .execute(SuiKeystore::load_or_create(&keystore_path)?)
+    };
+
+    // The network key is split off the protocol key
+    rotate(ValidatorKeyKind::Network)?;
+    let config = NodeConfig::load(&config_path)?;
+    assert_eq!(config.public_key(), initial.public_key());
+    assert_ne!(config.network_public_key(), initial.public_key());
+
+    // The worker key is split off the protocol key, the network key is left as it is
+    rotate(ValidatorKeyKind::Worker)?;
+    let rotated = NodeConfig::load(&config_path)?;
+    assert_eq!(rotated.public_key(), initial.public_key());
+    assert_eq!(rotated.network_public_key(), config.network_public_key());
+    assert_ne!(rotated.worker_public_key(), initial.public_key());
+
+    // The previous worker key is written out when it is rotated again
+    let result = rotate(ValidatorKeyKind::Worker)?;
+    match result {
+        KeyToolCommandResult::RotateValidatorKey {
+            file: Some(file), ..
+        } => assert_eq!(
+            PublicKeyBytes::from(read_keypair_from_file(file)?.public()),
+            rotated.worker_public_key()
+        ),
+        _ => panic!("Command failed with unexpected result {result:?}"),
+    }
+
+    // The committee cannot follow a new protocol key, the config is left as it is
+    assert!(rotate(ValidatorKeyKind::Protocol).is_err());
+    assert_eq!(
+        NodeConfig::load(&config_path)?.public_key(),
+        initial.public_key()
+    );
+
+    // Only the public key of the account is kept in the config
+    let result = rotate(ValidatorKeyKind::Account)?;
+    let rotated = NodeConfig::load(&config_path)?;
+    match result {
+        KeyToolCommandResult::RotateValidatorKey {
+            file: Some(file), ..
+        } => assert_eq!(
+            Some(PublicKeyBytes::from(read_keypair_from_file(file)?.public())),
+            rotated.account_key
+        ),
+        _ => panic!("Command failed with unexpected result {result:?}"),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_command() -> Result<(), anyhow::Error> {
+    let (_network, mut context, address) = setup_network_and_wallet().await?;
+    let object_refs = context
+        .gateway
+        .get_objects_owned_by_address(address)
+        .await?;
+    let total_value: u128 = context
+        .gas_objects(address)
+        .await?
+        .iter()
+        .map(|(value, _)| *value as u128)
+        .sum();
+
+    let export = context.export_account(address).await?;
+    assert_eq!(export.objects.len(), object_refs.len());
+    let sui_balance = export
+        .balances
+        .iter()
+        .find(|b| b.coin_type == GasCoin::type_().type_params[0].to_string())
+        .unwrap();
+    assert_eq!(sui_balance.balance, total_value);
+
+    let dir = tempfile::tempdir()?;
+    let output = dir.path().join("export.csv");
+    SuiClientCommands::Export {
+        address: Some(address),
+        format: ExportFormat::Csv,
+        output: Some(output.clone()),
+    }
+    .execute(&mut context)
+    .await?;
+    let csv = std::fs::read_to_string(output)?;
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("kind,object_id,version,digest,type,coin_type,balance,coin_count")
+    );
+    assert_eq!(
+        lines.filter(|line| line.starts_with("object,")).count(),
+        object_refs.len()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_name_recipient() -> Result<(), anyhow::Error> {
+    let (_network, context, address) = setup_network_and_wallet().await?;
+
+    let recipient: Recipient = address.to_string().parse()?;
+    assert_eq!(recipient, Recipient::Address(address));
+    assert_eq!(context.resolve_recipient(&recipient).await?, address);
+
+    let recipient: Recipient = "alice.sui".parse()?;
+    assert_eq!(recipient, Recipient::Name("alice.sui".to_string()));
+    assert!(".sui".parse::<Recipient>().is_err());
+    assert!("alice".parse::<Recipient>().is_err());
+
+    // No name registry is set in the test wallet config.
+    assert!(context.name_resolver.is_none());
+    assert!(context.resolve_recipient(&recipient).await.is_err());
+    Ok(())
+}
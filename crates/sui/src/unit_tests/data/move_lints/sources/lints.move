@@ -0,0 +1,16 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/// A package without a Sui framework dependency, failing the Sui lints
+module lints::lints {
+    use sui::object::Info;
+    use sui::tx_context::TxContext;
+
+    /// Never created by the module
+    struct Registry has key {
+        info: Info,
+    }
+
+    /// The TxContext comes first instead of last
+    public entry fun misplaced_context(_ctx: &mut TxContext, _value: u64) {}
+}
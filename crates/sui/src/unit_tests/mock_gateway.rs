@@ -0,0 +1,970 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-memory gateway, to test the logic of the wallet without starting validators.
+//!
+//! A [`MockGateway`] keeps a ledger of objects. It serves their reads, and builds and executes
+//! the transfers, payments and coin merges of the wallet against it: each transaction is
+//! charged a fixed gas cost, and the objects it changes get the next version. Any call can be
+//! given scripted responses instead, returned in their order in place of the ledger's, to check
+//! how the wallet handles errors and responses the ledger would not give. The calls the ledger
+//! has no answer to, such as publishing packages or reading past transactions, fail unless
+//! scripted.
+
+use std::any::{type_name, Any};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail};
+use async_trait::async_trait;
+use move_core_types::identifier::Identifier;
+use move_core_types::value::MoveStructLayout;
+use serde_json::Value as JsonValue;
+use sui_core::gateway_state::{GatewayAPI, GatewayTxSeqNumber};
+use sui_json::SuiJsonValue;
+use sui_json_rpc_types::{
+    GetObjectDataResponse, GetRawObjectDataResponse, OwnedObjectRef, RPCTransactionRequestParams,
+    SuiBalanceChange, SuiCertifiedTransaction, SuiExecutionStatus, SuiGasCostSummary,
+    SuiGasEstimate, SuiMoveNormalizedModule, SuiObject, SuiObjectInfo, SuiObjectRef,
+    SuiParsedObject, SuiPublishPreview, SuiRawMovePackage, SuiTransactionEffects,
+    SuiTransactionPreview, SuiTypeTag, TransactionEffectsResponse, TransactionResponse,
+};
+use sui_sdk::crypto::{Keystore, KeystoreType, SuiKeystore};
+use sui_types::base_types::{
+    ChainId, ObjectDigest, ObjectID, ObjectInfo, ObjectRef, SequenceNumber, SuiAddress,
+    TransactionDigest,
+};
+use sui_types::coin::{COIN_JOIN_FUNC_NAME, COIN_MODULE_NAME};
+use sui_types::crypto::{KeyPair, KeypairTraits};
+use sui_types::gas_coin::GasCoin;
+use sui_types::messages::{
+    CallArg, CertifiedTransaction, ExecutionFailureStatus, InputObjectKind, MoveCall, ObjectArg,
+    Pay, SingleTransactionKind, Transaction, TransactionData, TransactionKind, TransferObject,
+    TransferSui,
+};
+use sui_types::object::{MoveObject, Object, Owner};
+
+use crate::client_commands::WalletContext;
+use crate::config::{Config, GatewayType, SuiClientConfig};
+
+/// The gas charged to each transaction executed on the ledger, unless set otherwise.
+pub const DEFAULT_GAS_COST: u64 = 100;
+/// The margin of the recommended budget of a gas estimate above the gas cost.
+const GAS_ESTIMATE_MARGIN_PERCENT: u64 = 20;
+
+/// The chain the transactions built by the gateway are bound to.
+pub fn mock_chain_id() -> ChainId {
+    ChainId::deterministic_for_testing(0)
+}
+
+type ScriptedResponse = Box<dyn Any + Send>;
+
+/// A gateway answering from an in-memory ledger, see the [module documentation](self).
+pub struct MockGateway {
+    state: Mutex<MockState>,
+}
+
+struct MockState {
+    objects: BTreeMap<ObjectID, Object>,
+    /// The layouts of the objects other than gas coins, to render them.
+    layouts: BTreeMap<ObjectID, MoveStructLayout>,
+    gas_cost: u64,
+    responses: HashMap<&'static str, VecDeque<ScriptedResponse>>,
+    calls: Vec<&'static str>,
+    executed: Vec<TransactionData>,
+}
+
+impl MockGateway {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            state: Mutex::new(MockState {
+                objects: BTreeMap::new(),
+                layouts: BTreeMap::new(),
+                gas_cost: DEFAULT_GAS_COST,
+                responses: HashMap::new(),
+                calls: Vec::new(),
+                executed: Vec::new(),
+            }),
+        })
+    }
+
+    /// Add a gas coin of `value` owned by `owner` to the ledger.
+    pub fn add_gas_coin(&self, owner: SuiAddress, value: u64) -> ObjectID {
+        let id = ObjectID::random();
+        let coin = new_gas_coin(id, value, owner, TransactionDigest::genesis());
+        self.state.lock().unwrap().objects.insert(id, coin);
+        id
+    }
+
+    /// Add `object` to the ledger, rendered with `layout` if it is a Move object.
+    pub fn add_object(&self, object: Object, layout: Option<MoveStructLayout>) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(layout) = layout {
+            state.layouts.insert(object.id(), layout);
+        }
+        state.objects.insert(object.id(), object);
+    }
+
+    pub fn set_gas_cost(&self, gas_cost: u64) {
+        self.state.lock().unwrap().gas_cost = gas_cost;
+    }
+
+    /// Answer the next call to `method` with `response` rather than from the ledger. Responses
+    /// scripted for the same method are returned in the order they were scripted.
+    ///
+    /// Panics when the method is called if `T` is not the type it returns.
+    pub fn respond<T: Send + 'static>(
+        &self,
+        method: &'static str,
+        response: Result<T, anyhow::Error>,
+    ) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(method)
+            .or_default()
+            .push_back(Box::new(response));
+    }
+
+    /// Fail the next call to `method` with `error`, whatever it returns.
+    pub fn fail(&self, method: &'static str, error: &str) {
+        let error = anyhow!("{error}");
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(method)
+            .or_default()
+            .push_back(Box::new(ScriptedError(error)));
+    }
+
+    /// The methods called so far, in order.
+    pub fn calls(&self) -> Vec<&'static str> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// The transactions executed on the ledger so far, in order.
+    pub fn executed(&self) -> Vec<TransactionData> {
+        self.state.lock().unwrap().executed.clone()
+    }
+
+    pub fn object(&self, id: &ObjectID) -> Option<Object> {
+        self.state.lock().unwrap().objects.get(id).cloned()
+    }
+
+    /// The value of gas coin `id`, if it is in the ledger.
+    pub fn coin_value(&self, id: &ObjectID) -> Option<u64> {
+        self.object(id).as_ref().and_then(gas_coin_value)
+    }
+
+    /// The values of the gas coins of `owner`, from the smallest.
+    pub fn coin_values(&self, owner: SuiAddress) -> Vec<u64> {
+        let state = self.state.lock().unwrap();
+        let mut values: Vec<_> = state
+            .objects
+            .values()
+            .filter(|object| object.owner == Owner::AddressOwner(owner))
+            .filter_map(gas_coin_value)
+            .collect();
+        values.sort_unstable();
+        values
+    }
+
+    /// Record a call to `method`, and take the response scripted for it if there is one.
+    fn scripted<T: 'static>(&self, method: &'static str) -> Option<Result<T, anyhow::Error>> {
+        let mut state = self.state.lock().unwrap();
+        state.calls.push(method);
+        let response = state.responses.get_mut(method)?.pop_front()?;
+        let response = match response.downcast::<ScriptedError>() {
+            Ok(error) => return Some(Err(error.0)),
+            Err(response) => response,
+        };
+        match response.downcast::<Result<T, anyhow::Error>>() {
+            Ok(response) => Some(*response),
+            Err(_) => panic!(
+                "The response scripted for {method} is not a {}",
+                type_name::<Result<T, anyhow::Error>>()
+            ),
+        }
+    }
+
+    /// The scripted response of a method the ledger has no answer to.
+    fn scripted_only<T: 'static>(&self, method: &'static str) -> Result<T, anyhow::Error> {
+        self.scripted(method)
+            .unwrap_or_else(|| Err(anyhow!("No response scripted for {method}")))
+    }
+
+    fn object_ref(&self, id: &ObjectID) -> Result<ObjectRef, anyhow::Error> {
+        self.state.lock().unwrap().object_ref(id)
+    }
+
+    /// The reference of `gas`, or of the largest gas coin of `signer` if not set, out of the
+    /// `input_objects` of the transaction.
+    fn gas_ref(
+        &self,
+        signer: SuiAddress,
+        gas: Option<ObjectID>,
+        input_objects: &[ObjectID],
+    ) -> Result<ObjectRef, anyhow::Error> {
+        let state = self.state.lock().unwrap();
+        if let Some(gas) = gas {
+            return state.object_ref(&gas);
+        }
+        state
+            .objects
+            .values()
+            .filter(|object| object.owner == Owner::AddressOwner(signer))
+            .filter(|object| !input_objects.contains(&object.id()))
+            .filter_map(|object| Some((gas_coin_value(object)?, object)))
+            .max_by_key(|(value, _)| *value)
+            .map(|(_, object)| object.compute_object_reference())
+            .ok_or_else(|| anyhow!("No gas coin of {signer} to pay for the transaction"))
+    }
+
+    fn move_call_kind(
+        &self,
+        package: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<SuiTypeTag>,
+        arguments: Vec<SuiJsonValue>,
+    ) -> Result<SingleTransactionKind, anyhow::Error> {
+        let package = self.object_ref(&package).unwrap_or((
+            package,
+            SequenceNumber::from_u64(1),
+            ObjectDigest::MIN,
+        ));
+        let type_arguments = type_arguments
+            .into_iter()
+            .map(|tag| tag.try_into())
+            .collect::<Result<_, _>>()?;
+        let mut call_args = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            let id = match argument.to_json_value() {
+                JsonValue::String(value) => value.parse::<ObjectID>().ok(),
+                _ => None,
+            };
+            let id = id.ok_or_else(|| {
+                anyhow!("The mock gateway only takes objects as arguments of Move calls")
+            })?;
+            call_args.push(CallArg::Object(ObjectArg::ImmOrOwnedObject(
+                self.object_ref(&id)?,
+            )));
+        }
+        Ok(SingleTransactionKind::Call(MoveCall {
+            package,
+            module: Identifier::new(module)?,
+            function: Identifier::new(function)?,
+            type_arguments,
+            arguments: call_args,
+        }))
+    }
+}
+
+/// A scripted error, for a method whatever it returns.
+struct ScriptedError(anyhow::Error);
+
+impl MockState {
+    fn object_ref(&self, id: &ObjectID) -> Result<ObjectRef, anyhow::Error> {
+        self.objects
+            .get(id)
+            .map(|object| object.compute_object_reference())
+            .ok_or_else(|| anyhow!("Object {id} not found"))
+    }
+
+    fn sui_object(&self, object: &Object) -> Result<SuiParsedObject, anyhow::Error> {
+        let layout = match gas_coin_value(object) {
+            Some(_) => Some(GasCoin::layout()),
+            None => self.layouts.get(&object.id()).cloned(),
+        };
+        SuiObject::try_from(object.clone(), layout)
+    }
+
+    fn owned_objects(&self, owner: Owner) -> Vec<SuiObjectInfo> {
+        self.objects
+            .values()
+            .filter(|object| object.owner == owner)
+            .map(|object| ObjectInfo::new(&object.compute_object_reference(), object).into())
+            .collect()
+    }
+
+    /// Execute `data` on a copy of the ledger, returning the objects after it and its effects.
+    fn execute(
+        &self,
+        data: &TransactionData,
+        digest: TransactionDigest,
+    ) -> Result<(BTreeMap<ObjectID, Object>, SuiTransactionEffects), anyhow::Error> {
+        let sender = data.signer();
+        for input in data.input_objects()? {
+            let object_ref = match input {
+                InputObjectKind::ImmOrOwnedMoveObject(object_ref) => object_ref,
+                // Packages are not in the ledger, the calls to the framework are simulated.
+                InputObjectKind::MovePackage(_) => continue,
+                InputObjectKind::SharedMoveObject(id) => {
+                    bail!("The mock gateway does not support shared object {id}")
+                }
+            };
+            let current = self.object_ref(&object_ref.0)?;
+            if current != object_ref {
+                bail!(
+                    "Object {} is at version {}, not {}",
+                    object_ref.0,
+                    current.1,
+                    object_ref.1
+                );
+            }
+            let owner = self.objects[&object_ref.0].owner;
+            if owner != Owner::AddressOwner(sender) {
+                bail!("Object {} is owned by {owner}, not {sender}", object_ref.0);
+            }
+        }
+        for kind in data.kind.single_transactions() {
+            check_supported(kind)?;
+        }
+
+        let gas_id = data.gas().0;
+        let gas_value = gas_coin_value(&self.objects[&gas_id])
+            .ok_or_else(|| anyhow!("Gas object {gas_id} is not a gas coin"))?;
+        if gas_value < data.gas_budget {
+            bail!(
+                "Gas balance is {gas_value}, less than the gas budget {}",
+                data.gas_budget
+            );
+        }
+        let charged = std::cmp::min(self.gas_cost, data.gas_budget);
+        let mut charged_objects = self.objects.clone();
+        set_coin_value(
+            charged_objects.get_mut(&gas_id).unwrap(),
+            gas_value - charged,
+        );
+
+        let mut objects = charged_objects.clone();
+        let status = if self.gas_cost > data.gas_budget {
+            Err(ExecutionFailureStatus::InsufficientGas)
+        } else {
+            data.kind
+                .single_transactions()
+                .try_for_each(|kind| apply(&mut objects, kind, gas_id, digest))
+        };
+        let status = match status {
+            Ok(()) => SuiExecutionStatus::Success,
+            Err(error) => {
+                // A failed transaction is only charged for gas.
+                objects = charged_objects;
+                SuiExecutionStatus::Failure {
+                    error: format!("{error:?}"),
+                }
+            }
+        };
+
+        let mut created = Vec::new();
+        let mut mutated = Vec::new();
+        for (id, object) in objects.iter_mut() {
+            match self.objects.get(id) {
+                Some(before) if before == object => continue,
+                Some(_) => mutated.push(*id),
+                None => created.push(*id),
+            }
+            object.data.try_as_move_mut().unwrap().increment_version();
+            object.previous_transaction = digest;
+        }
+        let owned_ref = |id: &ObjectID| OwnedObjectRef {
+            owner: objects[id].owner,
+            reference: objects[id].compute_object_reference().into(),
+        };
+        let effects = SuiTransactionEffects {
+            status,
+            gas_used: SuiGasCostSummary {
+                computation_cost: charged,
+                storage_cost: 0,
+                storage_rebate: 0,
+            },
+            shared_objects: vec![],
+            transaction_digest: digest,
+            created: created.iter().map(owned_ref).collect(),
+            mutated: mutated.iter().map(owned_ref).collect(),
+            unwrapped: vec![],
+            deleted: self
+                .objects
+                .iter()
+                .filter(|(id, _)| !objects.contains_key(id))
+                .map(|(_, object)| SuiObjectRef::from(object.compute_object_reference()))
+                .collect(),
+            wrapped: vec![],
+            gas_object: owned_ref(&gas_id),
+            events: vec![],
+            dependencies: vec![],
+        };
+        Ok((objects, effects))
+    }
+}
+
+/// Fail the transactions the ledger cannot execute, rather than simulate them wrongly.
+fn check_supported(kind: &SingleTransactionKind) -> Result<(), anyhow::Error> {
+    match kind {
+        SingleTransactionKind::TransferObject(_)
+        | SingleTransactionKind::TransferSui(_)
+        | SingleTransactionKind::Pay(_) => Ok(()),
+        SingleTransactionKind::Call(MoveCall {
+            module,
+            function,
+            arguments,
+            ..
+        }) if module.as_str() == COIN_MODULE_NAME
+            && function.as_str() == COIN_JOIN_FUNC_NAME
+            && arguments.len() == 2 =>
+        {
+            Ok(())
+        }
+        SingleTransactionKind::Call(MoveCall {
+            module, function, ..
+        }) => bail!("The mock gateway cannot execute {module}::{function}"),
+        _ => bail!("The mock gateway cannot execute the transaction {kind}"),
+    }
+}
+
+/// Apply `kind` to `objects`, changing the objects without their versions.
+fn apply(
+    objects: &mut BTreeMap<ObjectID, Object>,
+    kind: &SingleTransactionKind,
+    gas_id: ObjectID,
+    digest: TransactionDigest,
+) -> Result<(), ExecutionFailureStatus> {
+    match kind {
+        SingleTransactionKind::TransferObject(TransferObject {
+            recipient,
+            object_ref,
+        }) => {
+            objects
+                .get_mut(&object_ref.0)
+                .ok_or(ExecutionFailureStatus::InvalidTransferObject)?
+                .transfer_without_version_change(*recipient);
+        }
+        SingleTransactionKind::TransferSui(TransferSui { recipient, amount }) => {
+            let gas = objects.get_mut(&gas_id).unwrap();
+            match amount {
+                None => gas.transfer_without_version_change(*recipient),
+                Some(amount) => {
+                    let value = gas_coin_value(gas).unwrap();
+                    if *amount > value {
+                        return Err(ExecutionFailureStatus::InvalidTransferSuiInsufficientBalance);
+                    }
+                    set_coin_value(gas, value - amount);
+                    let id = ObjectID::random();
+                    objects.insert(id, new_gas_coin(id, *amount, *recipient, digest));
+                }
+            }
+        }
+        SingleTransactionKind::Pay(Pay {
+            coins,
+            recipients,
+            amounts,
+        }) => {
+            let total: u64 = coins
+                .iter()
+                .map(|coin| objects.get(&coin.0).and_then(gas_coin_value))
+                .sum::<Option<u64>>()
+                .ok_or(ExecutionFailureStatus::InvalidPayInsufficientBalance)?;
+            let paid: u64 = amounts.iter().sum();
+            if coins.is_empty() || total < paid || recipients.len() != amounts.len() {
+                return Err(ExecutionFailureStatus::InvalidPayInsufficientBalance);
+            }
+            for coin in &coins[1..] {
+                objects.remove(&coin.0);
+            }
+            if total == paid {
+                objects.remove(&coins[0].0);
+            } else {
+                set_coin_value(objects.get_mut(&coins[0].0).unwrap(), total - paid);
+            }
+            for (recipient, amount) in recipients.iter().zip(amounts) {
+                let id = ObjectID::random();
+                objects.insert(id, new_gas_coin(id, *amount, *recipient, digest));
+            }
+        }
+        // A call to coin::join, as checked before.
+        SingleTransactionKind::Call(MoveCall { arguments, .. }) => {
+            let ids = arguments
+                .iter()
+                .map(|argument| match argument {
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject((id, _, _))) => Ok(*id),
+                    _ => Err(ExecutionFailureStatus::InvalidTransactionUpdate),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let (primary, coin) = (ids[0], ids[1]);
+            let value = objects
+                .remove(&coin)
+                .as_ref()
+                .and_then(gas_coin_value)
+                .ok_or(ExecutionFailureStatus::InvalidTransactionUpdate)?;
+            let primary = objects
+                .get_mut(&primary)
+                .ok_or(ExecutionFailureStatus::InvalidTransactionUpdate)?;
+            let primary_value =
+                gas_coin_value(primary).ok_or(ExecutionFailureStatus::InvalidTransactionUpdate)?;
+            set_coin_value(primary, primary_value + value);
+        }
+        _ => unreachable!("Checked to be supported"),
+    }
+    Ok(())
+}
+
+fn new_gas_coin(
+    id: ObjectID,
+    value: u64,
+    owner: SuiAddress,
+    previous_transaction: TransactionDigest,
+) -> Object {
+    let coin = GasCoin::new(id, SequenceNumber::new(), value);
+    Object::new_move(
+        MoveObject::new_gas_coin(coin.to_bcs_bytes()),
+        Owner::AddressOwner(owner),
+        previous_transaction,
+    )
+}
+
+fn gas_coin_value(object: &Object) -> Option<u64> {
+    let coin = GasCoin::try_from(object.data.try_as_move()?).ok()?;
+    Some(coin.value())
+}
+
+fn set_coin_value(object: &mut Object, value: u64) {
+    let coin = object.data.try_as_move_mut().unwrap();
+    let contents = GasCoin::new(coin.id(), coin.version(), value).to_bcs_bytes();
+    coin.update_contents_without_version_change(contents);
+}
+
+/// The change of the SUI balance of each address from `before` to `after`.
+fn balance_changes(
+    before: &BTreeMap<ObjectID, Object>,
+    after: &BTreeMap<ObjectID, Object>,
+) -> Vec<SuiBalanceChange> {
+    let mut balances: BTreeMap<SuiAddress, i128> = BTreeMap::new();
+    let coins = |objects: &BTreeMap<ObjectID, Object>| {
+        objects
+            .values()
+            .filter_map(|object| Some((object.get_single_owner()?, gas_coin_value(object)?)))
+            .collect::<Vec<_>>()
+    };
+    for (owner, value) in coins(after) {
+        *balances.entry(owner).or_default() += value as i128;
+    }
+    for (owner, value) in coins(before) {
+        *balances.entry(owner).or_default() -= value as i128;
+    }
+    let coin_type = GasCoin::type_().type_params[0].to_string();
+    balances
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|(owner, amount)| SuiBalanceChange {
+            owner,
+            coin_type: coin_type.clone(),
+            amount,
+        })
+        .collect()
+}
+
+/// A wallet whose gateway is `gateway`, managing the addresses of `keys`, the first one being
+/// active. Its config is persisted in `dir` when a command saves it.
+pub fn mock_wallet(gateway: Arc<MockGateway>, keys: Vec<KeyPair>, dir: &Path) -> WalletContext {
+    let mut keystore = SuiKeystore::default();
+    let mut accounts = Vec::new();
+    for key in keys {
+        accounts.push(SuiAddress::from(key.public()));
+        keystore.add_key(key).unwrap();
+    }
+    let config = SuiClientConfig {
+        active_address: accounts.first().copied(),
+        accounts,
+        keystore: KeystoreType::File(dir.join("mock.keystore")),
+        gateway: GatewayType::RPC("http://127.0.0.1:5001".to_owned()),
+        envs: vec![],
+        active_env: None,
+        name_registry: None,
+    };
+    WalletContext {
+        config: config.persisted(&dir.join("client.yaml")),
+        keystore: Box::new(keystore),
+        gateway,
+        preview_transactions: false,
+        name_resolver: None,
+    }
+}
+
+#[async_trait]
+impl GatewayAPI for MockGateway {
+    async fn execute_transaction(
+        &self,
+        tx: Transaction,
+    ) -> Result<TransactionResponse, anyhow::Error> {
+        if let Some(response) = self.scripted("execute_transaction") {
+            return response;
+        }
+        tx.verify()?;
+        let digest = *tx.digest();
+        let mut state = self.state.lock().unwrap();
+        let (objects, effects) = state.execute(&tx.data, digest)?;
+        state.objects = objects;
+        state.executed.push(tx.data.clone());
+        drop(state);
+        Ok(TransactionResponse::EffectResponse(
+            TransactionEffectsResponse {
+                certificate: SuiCertifiedTransaction::try_from(CertifiedTransaction::new(0, tx))?,
+                effects,
+                timestamp_ms: None,
+            },
+        ))
+    }
+
+    async fn public_transfer_object(
+        &self,
+        signer: SuiAddress,
+        object_id: ObjectID,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+        recipient: SuiAddress,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if let Some(response) = self.scripted("public_transfer_object") {
+            return response;
+        }
+        let object_ref = self.object_ref(&object_id)?;
+        let gas = self.gas_ref(signer, gas, &[object_id])?;
+        Ok(TransactionData::new_transfer(
+            recipient,
+            object_ref,
+            signer,
+            gas,
+            gas_budget,
+            mock_chain_id(),
+        ))
+    }
+
+    async fn transfer_sui(
+        &self,
+        signer: SuiAddress,
+        sui_object_id: ObjectID,
+        gas_budget: u64,
+        recipient: SuiAddress,
+        amount: Option<u64>,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if let Some(response) = self.scripted("transfer_sui") {
+            return response;
+        }
+        let gas = self.object_ref(&sui_object_id)?;
+        Ok(TransactionData::new_transfer_sui(
+            recipient,
+            signer,
+            amount,
+            gas,
+            gas_budget,
+            mock_chain_id(),
+        ))
+    }
+
+    async fn pay(
+        &self,
+        signer: SuiAddress,
+        input_coins: Vec<ObjectID>,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if let Some(response) = self.scripted("pay") {
+            return response;
+        }
+        let coins = input_coins
+            .iter()
+            .map(|coin| self.object_ref(coin))
+            .collect::<Result<_, _>>()?;
+        let gas = self.gas_ref(signer, gas, &input_coins)?;
+        Ok(TransactionData::new_pay(
+            signer,
+            coins,
+            recipients,
+            amounts,
+            gas,
+            gas_budget,
+            mock_chain_id(),
+        ))
+    }
+
+    async fn pay_all(
+        &self,
+        _signer: SuiAddress,
+        _input_coins: Vec<ObjectID>,
+        _recipient: SuiAddress,
+        _gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        self.scripted_only("pay_all")
+    }
+
+    async fn sync_account_state(&self, _account_addr: SuiAddress) -> Result<(), anyhow::Error> {
+        self.scripted("sync_account_state").unwrap_or(Ok(()))
+    }
+
+    async fn move_call(
+        &self,
+        signer: SuiAddress,
+        package_object_id: ObjectID,
+        module: String,
+        function: String,
+        type_arguments: Vec<SuiTypeTag>,
+        arguments: Vec<SuiJsonValue>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if let Some(response) = self.scripted("move_call") {
+            return response;
+        }
+        let kind = self.move_call_kind(
+            package_object_id,
+            module,
+            function,
+            type_arguments,
+            arguments,
+        )?;
+        let inputs: Vec<_> = input_object_ids(&kind);
+        let gas = self.gas_ref(signer, gas, &inputs)?;
+        Ok(TransactionData::new(
+            TransactionKind::Single(kind),
+            signer,
+            gas,
+            gas_budget,
+            mock_chain_id(),
+        ))
+    }
+
+    async fn publish(
+        &self,
+        _signer: SuiAddress,
+        _package_bytes: Vec<Vec<u8>>,
+        _gas: Option<ObjectID>,
+        _gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        self.scripted_only("publish")
+    }
+
+    async fn split_coin(
+        &self,
+        _signer: SuiAddress,
+        _coin_object_id: ObjectID,
+        _split_amounts: Vec<u64>,
+        _gas: Option<ObjectID>,
+        _gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        self.scripted_only("split_coin")
+    }
+
+    async fn merge_coins(
+        &self,
+        _signer: SuiAddress,
+        _primary_coin: ObjectID,
+        _coin_to_merge: ObjectID,
+        _gas: Option<ObjectID>,
+        _gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        self.scripted_only("merge_coins")
+    }
+
+    async fn batch_transaction(
+        &self,
+        signer: SuiAddress,
+        single_transaction_params: Vec<RPCTransactionRequestParams>,
+        gas: Option<ObjectID>,
+        gas_budget: u64,
+    ) -> Result<TransactionData, anyhow::Error> {
+        if let Some(response) = self.scripted("batch_transaction") {
+            return response;
+        }
+        let mut kinds = Vec::with_capacity(single_transaction_params.len());
+        for params in single_transaction_params {
+            kinds.push(match params {
+                RPCTransactionRequestParams::TransferObjectRequestParams(params) => {
+                    SingleTransactionKind::TransferObject(TransferObject {
+                        recipient: params.recipient,
+                        object_ref: self.object_ref(&params.object_id)?,
+                    })
+                }
+                RPCTransactionRequestParams::MoveCallRequestParams(params) => self.move_call_kind(
+                    params.package_object_id,
+                    params.module,
+                    params.function,
+                    params.type_arguments,
+                    params.arguments,
+                )?,
+                RPCTransactionRequestParams::PublishRequestParams(_) => {
+                    bail!("The mock gateway cannot publish packages")
+                }
+            });
+        }
+        let inputs: Vec<_> = kinds.iter().flat_map(input_object_ids).collect();
+        let gas = self.gas_ref(signer, gas, &inputs)?;
+        Ok(TransactionData::new(
+            TransactionKind::Batch(kinds),
+            signer,
+            gas,
+            gas_budget,
+            mock_chain_id(),
+        ))
+    }
+
+    async fn estimate_gas(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiGasEstimate, anyhow::Error> {
+        if let Some(response) = self.scripted("estimate_gas") {
+            return response;
+        }
+        let gas_cost = self.state.lock().unwrap().gas_cost;
+        Ok(gas_estimate(gas_cost, &tx_data))
+    }
+
+    async fn dry_run_transaction(
+        &self,
+        tx_data: TransactionData,
+    ) -> Result<SuiTransactionPreview, anyhow::Error> {
+        if let Some(response) = self.scripted("dry_run_transaction") {
+            return response;
+        }
+        let state = self.state.lock().unwrap();
+        let (objects, effects) = state.execute(&tx_data, TransactionDigest::random())?;
+        let transferred = effects
+            .mutated
+            .iter()
+            .filter(|changed| state.objects[&changed.reference.object_id].owner != changed.owner)
+            .cloned()
+            .collect();
+        Ok(SuiTransactionPreview {
+            effects,
+            transferred,
+            balance_changes: balance_changes(&state.objects, &objects),
+            gas_estimate: gas_estimate(state.gas_cost, &tx_data),
+        })
+    }
+
+    async fn dry_run_publish(
+        &self,
+        _tx_data: TransactionData,
+    ) -> Result<SuiPublishPreview, anyhow::Error> {
+        self.scripted_only("dry_run_publish")
+    }
+
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+    ) -> Result<GetObjectDataResponse, anyhow::Error> {
+        if let Some(response) = self.scripted("get_object") {
+            return response;
+        }
+        let state = self.state.lock().unwrap();
+        Ok(match state.objects.get(&object_id) {
+            Some(object) => GetObjectDataResponse::Exists(state.sui_object(object)?),
+            None => GetObjectDataResponse::NotExists(object_id),
+        })
+    }
+
+    async fn get_raw_object(
+        &self,
+        _object_id: ObjectID,
+    ) -> Result<GetRawObjectDataResponse, anyhow::Error> {
+        self.scripted_only("get_raw_object")
+    }
+
+    async fn get_raw_package(
+        &self,
+        package_id: ObjectID,
+    ) -> Result<SuiRawMovePackage, anyhow::Error> {
+        if let Some(response) = self.scripted("get_raw_package") {
+            return response;
+        }
+        let state = self.state.lock().unwrap();
+        let package = state
+            .objects
+            .get(&package_id)
+            .and_then(|object| object.data.try_as_package())
+            .ok_or_else(|| anyhow!("Package {package_id} not found"))?;
+        Ok(SuiRawMovePackage {
+            id: package_id,
+            module_map: package.serialized_module_map().clone(),
+        })
+    }
+
+    async fn get_normalized_move_modules_by_package(
+        &self,
+        _package: ObjectID,
+    ) -> Result<BTreeMap<String, SuiMoveNormalizedModule>, anyhow::Error> {
+        self.scripted_only("get_normalized_move_modules_by_package")
+    }
+
+    async fn get_objects_owned_by_address(
+        &self,
+        account_addr: SuiAddress,
+    ) -> Result<Vec<SuiObjectInfo>, anyhow::Error> {
+        if let Some(response) = self.scripted("get_objects_owned_by_address") {
+            return response;
+        }
+        let state = self.state.lock().unwrap();
+        Ok(state.owned_objects(Owner::AddressOwner(account_addr)))
+    }
+
+    async fn get_objects_owned_by_object(
+        &self,
+        object_id: ObjectID,
+    ) -> Result<Vec<SuiObjectInfo>, anyhow::Error> {
+        if let Some(response) = self.scripted("get_objects_owned_by_object") {
+            return response;
+        }
+        let state = self.state.lock().unwrap();
+        Ok(state.owned_objects(Owner::ObjectOwner(object_id.into())))
+    }
+
+    fn get_total_transaction_number(&self) -> Result<u64, anyhow::Error> {
+        self.scripted_only("get_total_transaction_number")
+    }
+
+    fn get_transactions_in_range(
+        &self,
+        _start: GatewayTxSeqNumber,
+        _end: GatewayTxSeqNumber,
+    ) -> Result<Vec<(GatewayTxSeqNumber, TransactionDigest)>, anyhow::Error> {
+        self.scripted_only("get_transactions_in_range")
+    }
+
+    fn get_recent_transactions(
+        &self,
+        _count: u64,
+    ) -> Result<Vec<(GatewayTxSeqNumber, TransactionDigest)>, anyhow::Error> {
+        self.scripted_only("get_recent_transactions")
+    }
+
+    async fn get_transaction(
+        &self,
+        _digest: TransactionDigest,
+    ) -> Result<TransactionEffectsResponse, anyhow::Error> {
+        self.scripted_only("get_transaction")
+    }
+}
+
+fn gas_estimate(gas_cost: u64, data: &TransactionData) -> SuiGasEstimate {
+    let gas_units = gas_cost / std::cmp::max(data.gas_price, 1);
+    SuiGasEstimate {
+        gas_used: SuiGasCostSummary {
+            computation_cost: gas_cost,
+            storage_cost: 0,
+            storage_rebate: 0,
+        },
+        gas_price: data.gas_price,
+        gas_units,
+        recommended_budget: gas_units * (100 + GAS_ESTIMATE_MARGIN_PERCENT) / 100,
+    }
+}
+
+fn input_object_ids(kind: &SingleTransactionKind) -> Vec<ObjectID> {
+    match kind.input_objects() {
+        Ok(inputs) => inputs.iter().map(|input| input.object_id()).collect(),
+        Err(_) => vec![],
+    }
+}
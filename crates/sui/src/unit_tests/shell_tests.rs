@@ -6,6 +6,7 @@ use std::env;
 use std::sync::Arc;
 
 use rustyline::completion::Completer;
+use rustyline::hint::Hinter;
 use rustyline::history::History;
 use rustyline::Context;
 
@@ -59,10 +60,12 @@ fn test_completer() {
     let helper = ShellHelper {
         command: CommandStructure {
             name: "test".to_string(),
+            help: None,
             completions: vec!["command1".to_string(), "command2".to_string()],
             children: vec![
                 CommandStructure {
                     name: "command1".to_string(),
+                    help: None,
                     completions: vec![
                         "--command_1_flag1".to_string(),
                         "--command_1_flag2".to_string(),
@@ -72,6 +75,7 @@ fn test_completer() {
                 },
                 CommandStructure {
                     name: "command2".to_string(),
+                    help: None,
                     completions: vec![
                         "--command_2_flag1".to_string(),
                         "--command_2_flag2".to_string(),
@@ -142,10 +146,12 @@ fn test_completer_with_cache() {
     let helper = ShellHelper {
         command: CommandStructure {
             name: "test".to_string(),
+            help: None,
             completions: vec!["command1".to_string(), "command2".to_string()],
             children: vec![
                 CommandStructure {
                     name: "command1".to_string(),
+                    help: None,
                     completions: vec![
                         "--address".to_string(),
                         "--gas".to_string(),
@@ -155,6 +161,7 @@ fn test_completer_with_cache() {
                 },
                 CommandStructure {
                     name: "command2".to_string(),
+                    help: None,
                     completions: vec!["--address".to_string(), "--gas".to_string()],
                     children: vec![],
                 },
@@ -218,6 +225,41 @@ fn test_completer_with_cache() {
     assert_eq!(vec!["--gas"], candidates);
 }
 
+#[test]
+fn test_hinter() {
+    let helper = ShellHelper {
+        command: CommandStructure {
+            name: "test".to_string(),
+            help: None,
+            completions: vec!["command1".to_string(), "other".to_string()],
+            children: vec![CommandStructure {
+                name: "command1".to_string(),
+                help: Some("The first command".to_string()),
+                completions: vec!["--address".to_string(), "--gas".to_string()],
+                children: vec![],
+            }],
+        },
+        completion_cache: Arc::new(Default::default()),
+    };
+    let history = History::new();
+    let ctx = Context::new(&history);
+
+    // The rest of the only matching completion
+    assert_eq!(Some("mand1".to_string()), helper.hint("com", 3, &ctx));
+    assert_eq!(
+        Some("ddress".to_string()),
+        helper.hint("command1 --a", 12, &ctx)
+    );
+    // The help of a command once it is typed
+    assert_eq!(
+        Some("The first command".to_string()),
+        helper.hint("command1 ", 9, &ctx)
+    );
+    // No hint when several completions match, or in the middle of the line
+    assert_eq!(None, helper.hint("command1 --", 11, &ctx));
+    assert_eq!(None, helper.hint("com", 1, &ctx));
+}
+
 #[test]
 fn test_split_line() {
     let test = "create-example-nft --name \"test 1\" --description \"t e s t 2\"";
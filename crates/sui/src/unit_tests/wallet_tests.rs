@@ -0,0 +1,381 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use axum::{http::StatusCode, routing::post, Json, Router};
+use clap::Parser;
+use move_binary_format::access::ModuleAccess;
+use move_core_types::ident_str;
+use serde_json::{json, Value};
+use sui_json_rpc_types::{SuiExecutionStatus, SuiGasCostSummary, SuiGasEstimate};
+use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::crypto::get_key_pair;
+use sui_types::object::Object;
+use sui_types::sui_serde::Base64;
+use sui_types::SUI_FRAMEWORK_ADDRESS;
+use tempfile::TempDir;
+
+use super::mock_gateway::{mock_wallet, MockGateway, DEFAULT_GAS_COST};
+use crate::client_commands::{
+    request_faucet_coins, SuiClientCommandResult, SuiClientCommands, TransactionFile, TxCommand,
+    WalletContext,
+};
+
+fn wallet(gateway: &Arc<MockGateway>, dir: &TempDir) -> (WalletContext, SuiAddress) {
+    let (address, key) = get_key_pair();
+    (mock_wallet(gateway.clone(), vec![key], dir.path()), address)
+}
+
+#[tokio::test]
+async fn test_select_gas_picks_smallest_coin_covering_budget() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (context, address) = wallet(&gateway, &dir);
+    gateway.add_gas_coin(address, 100);
+    let coin = gateway.add_gas_coin(address, 5_000);
+    let larger = gateway.add_gas_coin(address, 20_000);
+
+    let (gas, budget) = context
+        .select_gas(address, None, Some(1_000), &BTreeSet::new())
+        .await?;
+    assert_eq!((gas, budget), (coin, 1_000));
+
+    // Input objects of the transaction do not pay for its gas
+    let (gas, _) = context
+        .select_gas(address, None, Some(1_000), &BTreeSet::from([coin]))
+        .await?;
+    assert_eq!(gas, larger);
+    assert!(gateway.executed().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_select_gas_merges_coins_below_budget() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (context, address) = wallet(&gateway, &dir);
+    for value in [300, 400, 500] {
+        gateway.add_gas_coin(address, value);
+    }
+    let largest = gateway.add_gas_coin(address, 600);
+
+    let (gas, budget) = context
+        .select_gas(address, None, Some(1_000), &BTreeSet::new())
+        .await?;
+    assert_eq!((gas, budget), (largest, 1_000));
+    // The largest coins are merged until they cover the budget, paid by the smallest one
+    assert_eq!(gateway.coin_value(&largest), Some(1_100));
+    assert_eq!(
+        gateway.coin_values(address),
+        vec![300 - DEFAULT_GAS_COST, 400, 1_100]
+    );
+    let calls = gateway.calls();
+    assert!(calls.contains(&"batch_transaction") && calls.contains(&"estimate_gas"));
+
+    // Not enough coins left to merge
+    let err = context
+        .select_gas(address, None, Some(5_000), &BTreeSet::new())
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("less than the gas budget 5000"),
+        "{err}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_sui_command() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let coin = gateway.add_gas_coin(address, 10_000);
+    let (recipient, _) = get_key_pair();
+
+    let command = SuiClientCommands::try_parse_from([
+        "client",
+        "transfer-sui",
+        "--to",
+        &recipient.to_string(),
+        "--sui-coin-object-id",
+        &coin.to_string(),
+        "--gas-budget",
+        "500",
+        "--amount",
+        "1000",
+    ])?;
+    let effects = match command.execute(&mut context).await? {
+        SuiClientCommandResult::TransferSui(_, effects) => effects,
+        _ => panic!("Command returned an unexpected response"),
+    };
+    assert_eq!(effects.status, SuiExecutionStatus::Success);
+    assert_eq!(effects.created.len(), 1);
+    assert_eq!(gateway.coin_values(recipient), vec![1_000]);
+    assert_eq!(
+        gateway.coin_value(&coin),
+        Some(10_000 - 1_000 - DEFAULT_GAS_COST)
+    );
+    // The given budget is kept without an estimate
+    assert_eq!(gateway.executed()[0].gas_budget, 500);
+    assert!(!gateway.calls().contains(&"estimate_gas"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_without_budget_uses_estimate() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let object = gateway.add_gas_coin(address, 1_000);
+    let gas = gateway.add_gas_coin(address, 10_000);
+    let (recipient, _) = get_key_pair();
+
+    let command = SuiClientCommands::try_parse_from([
+        "client",
+        "transfer",
+        "--to",
+        &recipient.to_string(),
+        "--coin-object-id",
+        &object.to_string(),
+    ])?;
+    command.execute(&mut context).await?;
+
+    let data = &gateway.executed()[0];
+    assert_eq!(data.gas().0, gas);
+    // The budget is lowered from the value of the gas coin to the estimate
+    let estimate = context.gateway.estimate_gas(data.clone()).await?;
+    assert_eq!(data.gas_budget, estimate.recommended_budget);
+    assert_eq!(gateway.coin_values(recipient), vec![1_000]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_estimate_raises_gas_price_to_reference() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let object = gateway.add_gas_coin(address, 1_000);
+    gateway.add_gas_coin(address, 10_000);
+    let (recipient, _) = get_key_pair();
+    gateway.respond(
+        "estimate_gas",
+        Ok(SuiGasEstimate {
+            gas_used: SuiGasCostSummary {
+                computation_cost: 50,
+                storage_cost: 0,
+                storage_rebate: 0,
+            },
+            gas_price: 5,
+            gas_units: 10,
+            recommended_budget: 12,
+        }),
+    );
+
+    let command = SuiClientCommands::try_parse_from([
+        "client",
+        "transfer",
+        "--to",
+        &recipient.to_string(),
+        "--coin-object-id",
+        &object.to_string(),
+    ])?;
+    command.execute(&mut context).await?;
+
+    // The transaction is built at the default gas price, below the reference one
+    let data = &gateway.executed()[0];
+    assert_eq!(data.gas_price, 5);
+    assert_eq!(data.gas_budget, 12);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_command_errors() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let coin = gateway.add_gas_coin(address, 10_000);
+    let (recipient, _) = get_key_pair();
+    let transfer = || SuiClientCommands::TransferSui {
+        to: recipient.into(),
+        sui_coin_object_id: coin,
+        gas_budget: Some(500),
+        estimate_gas: false,
+        amount: Some(1_000),
+    };
+
+    // Errors of the gateway are returned as they are
+    gateway.fail("execute_transaction", "Connection refused");
+    let err = transfer().execute(&mut context).await.unwrap_err();
+    assert_eq!(err.to_string(), "Connection refused");
+    assert_eq!(gateway.coin_value(&coin), Some(10_000));
+
+    // Failed transactions are reported with their status
+    gateway.set_gas_cost(1_000);
+    let err = transfer().execute(&mut context).await.unwrap_err();
+    assert!(
+        err.to_string().starts_with("Error transferring SUI"),
+        "{err}"
+    );
+    assert!(err.to_string().contains("InsufficientGas"), "{err}");
+    assert_eq!(gateway.coin_value(&coin), Some(10_000 - 500));
+
+    // Calls the ledger has no answer to fail unless scripted
+    let err = context.gateway.get_total_transaction_number().unwrap_err();
+    assert!(err.to_string().contains("get_total_transaction_number"));
+    gateway.respond("get_total_transaction_number", Ok(42u64));
+    assert_eq!(context.gateway.get_total_transaction_number()?, 42);
+    Ok(())
+}
+
+/// Serve `response` with `status` on the `/gas` route of a local faucet, returning the URL of the
+/// faucet and the requests it receives.
+async fn fake_faucet(status: StatusCode, response: Value) -> (String, Arc<Mutex<Vec<Value>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = requests.clone();
+    let app = Router::new().route(
+        "/gas",
+        post(move |Json(request): Json<Value>| async move {
+            received.lock().unwrap().push(request);
+            (status, Json(response))
+        }),
+    );
+    let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap()).serve(app.into_make_service());
+    let url = format!("http://{}", server.local_addr());
+    tokio::spawn(server);
+    (url, requests)
+}
+
+#[tokio::test]
+async fn test_faucet_command() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let coin = ObjectID::random();
+    let (url, requests) = fake_faucet(
+        StatusCode::CREATED,
+        json!({ "transferred_gas_objects": [{ "amount": 50_000, "id": coin }], "error": null }),
+    )
+    .await;
+
+    let command = SuiClientCommands::try_parse_from(["client", "faucet", "--url", &url])?;
+    let coins = match command.execute(&mut context).await? {
+        SuiClientCommandResult::Faucet(coins) => coins,
+        _ => panic!("Command returned an unexpected response"),
+    };
+    assert_eq!(coins.len(), 1);
+    assert_eq!((coins[0].id, coins[0].amount), (coin, 50_000));
+    // The coins are sent to the active address, whose objects are then synced
+    assert_eq!(
+        requests.lock().unwrap().as_slice(),
+        [json!({ "FixedAmountRequest": { "recipient": address } })]
+    );
+    assert!(gateway.calls().contains(&"sync_account_state"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_faucet_coins_errors() -> Result<(), anyhow::Error> {
+    let (recipient, _) = get_key_pair();
+
+    // Errors reported by the faucet are returned, a trailing slash of its URL being ignored
+    let (faucet, _) = fake_faucet(
+        StatusCode::TOO_MANY_REQUESTS,
+        json!({ "transferred_gas_objects": [], "error": "Too many requests, retry in 60s" }),
+    )
+    .await;
+    let err = request_faucet_coins(&format!("{faucet}/"), recipient)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Faucet request failed: Too many requests, retry in 60s"
+    );
+
+    // Responses which are not faucet responses are reported with their status
+    let (faucet, _) = fake_faucet(StatusCode::SERVICE_UNAVAILABLE, json!("overloaded")).await;
+    let err = request_faucet_coins(&faucet, recipient).await.unwrap_err();
+    assert!(err.to_string().contains("status 503"), "{err}");
+    assert!(err.to_string().contains("overloaded"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_failed_transaction() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (mut context, address) = wallet(&gateway, &dir);
+    let coin = gateway.add_gas_coin(address, 10_000);
+    let (recipient, _) = get_key_pair();
+
+    let data = context
+        .gateway
+        .transfer_sui(address, coin, 500, recipient, Some(1_000))
+        .await?;
+    let signed = dir.path().join("signed.json");
+    TransactionFile {
+        tx_bytes: Base64::from_bytes(&data.to_bytes()),
+        signature: Some(context.sign_transaction(&data).await?),
+    }
+    .write(&signed)?;
+
+    // Failed transactions are reported with their status
+    gateway.set_gas_cost(1_000);
+    let err = TxCommand::Submit { signed }
+        .execute(&mut context)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Error executing signed transaction"),
+        "{err}"
+    );
+    assert!(err.to_string().contains("InsufficientGas"), "{err}");
+    assert_eq!(gateway.coin_value(&coin), Some(10_000 - 500));
+    assert!(gateway.coin_values(recipient).is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_verify_published_dependencies() -> Result<(), anyhow::Error> {
+    let dir = tempfile::tempdir()?;
+    let gateway = MockGateway::new();
+    let (context, _) = wallet(&gateway, &dir);
+    let framework = sui_framework::get_sui_framework();
+    let package_id = ObjectID::from(SUI_FRAMEWORK_ADDRESS);
+
+    // A dependency which is not on chain
+    let err = context
+        .verify_published_dependencies(&framework)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with(&format!("Dependency package {package_id} is not published")),
+        "{err}"
+    );
+
+    gateway.add_object(
+        Object::new_package(framework.clone(), TransactionDigest::genesis()),
+        None,
+    );
+    context.verify_published_dependencies(&framework).await?;
+
+    // A dependency built from a different source than the one published
+    let mut changed = framework[0].clone();
+    changed.identifiers.push(ident_str!("changed").to_owned());
+    let err = context
+        .verify_published_dependencies(&[changed.clone()])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Module {} of the dependency package {package_id} does not match its published \
+            bytecode, its local source may be out of date",
+            changed.self_id().name()
+        )
+    );
+    Ok(())
+}
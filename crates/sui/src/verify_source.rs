@@ -0,0 +1,133 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Write};
+use std::path::Path;
+
+use anyhow::anyhow;
+use colored::Colorize;
+use move_binary_format::access::ModuleAccess;
+use move_package::BuildConfig;
+use serde::Serialize;
+
+use sui_adapter::adapter::substitute_package_id;
+use sui_core::gateway_state::GatewayClient;
+use sui_framework::build_and_verify_package;
+use sui_types::base_types::ObjectID;
+
+/// The outcome of the comparison of a module built locally with its published version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ModuleVerification {
+    /// The published bytecode is identical to the local build.
+    Match,
+    /// The published bytecode differs from the local build.
+    Mismatch,
+    /// The module is built locally but was not published in the package.
+    MissingOnChain,
+    /// The module was published in the package but is not built locally.
+    MissingLocally,
+}
+
+/// The result of the comparison of a local package with a published package, module by module.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceVerification {
+    pub package_id: ObjectID,
+    pub modules: BTreeMap<String, ModuleVerification>,
+}
+
+impl SourceVerification {
+    /// Compare the serialized modules of a local build, with addresses already rewritten to the
+    /// package ID, with the published ones.
+    pub fn compare(
+        package_id: ObjectID,
+        local: &BTreeMap<String, Vec<u8>>,
+        on_chain: &BTreeMap<String, Vec<u8>>,
+    ) -> Self {
+        let mut modules = BTreeMap::new();
+        for (name, bytes) in local {
+            let verification = match on_chain.get(name) {
+                Some(published) if published == bytes => ModuleVerification::Match,
+                Some(_) => ModuleVerification::Mismatch,
+                None => ModuleVerification::MissingOnChain,
+            };
+            modules.insert(name.clone(), verification);
+        }
+        for name in on_chain.keys() {
+            modules
+                .entry(name.clone())
+                .or_insert(ModuleVerification::MissingLocally);
+        }
+        Self {
+            package_id,
+            modules,
+        }
+    }
+
+    /// Whether every module of the package matches the local build.
+    pub fn is_verified(&self) -> bool {
+        self.modules
+            .values()
+            .all(|verification| *verification == ModuleVerification::Match)
+    }
+}
+
+impl Display for SourceVerification {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut writer = String::new();
+        writeln!(writer, "Package {}", self.package_id)?;
+        for (name, verification) in &self.modules {
+            let status = match verification {
+                ModuleVerification::Match => "match".green(),
+                ModuleVerification::Mismatch => "MISMATCH".red(),
+                ModuleVerification::MissingOnChain => "not published".red(),
+                ModuleVerification::MissingLocally => "not built locally".red(),
+            };
+            writeln!(writer, "  {name}: {status}")?;
+        }
+        if self.is_verified() {
+            write!(
+                writer,
+                "{}",
+                "The published package matches the source.".green()
+            )?;
+        } else {
+            write!(
+                writer,
+                "{}",
+                "The published package does not match the source.".red()
+            )?;
+        }
+        write!(f, "{}", writer)
+    }
+}
+
+/// Build the package at `package_path` with `build_config`, and compare its bytecode with the
+/// modules published at `package_id`.
+///
+/// Modules are published with the 0x0 address, rewritten to the ID of the package on chain.
+/// The local build goes through the same rewriting before the bytes are compared.
+pub async fn verify_source(
+    gateway: &GatewayClient,
+    package_path: &Path,
+    build_config: BuildConfig,
+    package_id: ObjectID,
+) -> Result<SourceVerification, anyhow::Error> {
+    let mut modules = build_and_verify_package(package_path, build_config)?;
+    substitute_package_id(&mut modules, package_id)?;
+    let mut local = BTreeMap::new();
+    for module in &modules {
+        let mut bytes = Vec::new();
+        module
+            .serialize(&mut bytes)
+            .map_err(|e| anyhow!("Cannot serialize module {}: {e}", module.self_id()))?;
+        local.insert(module.self_id().name().to_string(), bytes);
+    }
+    let on_chain = gateway.get_raw_package(package_id).await?;
+    Ok(SourceVerification::compare(
+        package_id,
+        &local,
+        &on_chain.module_map,
+    ))
+}
@@ -186,11 +186,11 @@ async fn end_to_end() {
     let total_transactions = 3;
     let mut rng = StdRng::from_seed([0; 32]);
     let keys = (0..total_transactions).map(|_| get_key_pair_from_rng(&mut rng).1);
-    let (transactions, input_objects) = test_transactions(keys);
+    let configs = test_authority_configs();
+    let (transactions, input_objects) = test_transactions(keys, configs.genesis.chain_id());
     let transaction_digests: HashSet<_> = transactions.iter().map(|x| *x.digest()).collect();
 
     // Spawn a quorum of authorities.
-    let configs = test_authority_configs();
     let handles = spawn_test_authorities(input_objects, &configs).await;
 
     // Make an authority's aggregator.
@@ -210,11 +210,11 @@ async fn end_to_end_with_one_byzantine() {
     let total_transactions = 3;
     let mut rng = StdRng::from_seed([0; 32]);
     let keys = (0..total_transactions).map(|_| get_key_pair_from_rng(&mut rng).1);
-    let (transactions, input_objects) = test_transactions(keys);
+    let configs = test_authority_configs();
+    let (transactions, input_objects) = test_transactions(keys, configs.genesis.chain_id());
     let transaction_digests: HashSet<_> = transactions.iter().map(|x| *x.digest()).collect();
 
     // Spawn a quorum of authorities.
-    let configs = test_authority_configs();
     let handles = spawn_test_authorities(input_objects, &configs).await;
     let (_first, rest) = handles[..].split_at(1);
 
@@ -241,10 +241,11 @@ async fn checkpoint_with_shared_objects() {
     let total_transactions = 3;
     let mut rng = StdRng::from_seed([0; 32]);
     let keys = (0..total_transactions).map(|_| get_key_pair_from_rng(&mut rng).1);
-    let (transactions, input_objects) = test_transactions(keys);
+    let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
+    let (transactions, input_objects) = test_transactions(keys, chain_id);
 
     // Spawn a quorum of authorities.
-    let configs = test_authority_configs();
     let initialization_objects = input_objects.into_iter().chain(gas_objects.iter().cloned());
     let handles = spawn_test_authorities(initialization_objects, &configs).await;
 
@@ -256,7 +257,7 @@ async fn checkpoint_with_shared_objects() {
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
     let gas = gas_objects.pop().unwrap();
-    let package_ref = publish_counter_package(gas, configs.validator_set()).await;
+    let package_ref = publish_counter_package(gas, configs.validator_set(), chain_id).await;
 
     // Make a transaction to create a counter.
     tokio::task::yield_now().await;
@@ -266,6 +267,7 @@ async fn checkpoint_with_shared_objects() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     let (_, effects) = aggregator
         .execute_transaction(&create_counter_transaction)
@@ -285,6 +287,7 @@ async fn checkpoint_with_shared_objects() {
         "increment",
         package_ref,
         vec![CallArg::Object(ObjectArg::SharedObject(counter_id))],
+        chain_id,
     );
     let replies = submit_shared_object_transaction(
         increment_counter_transaction.clone(),
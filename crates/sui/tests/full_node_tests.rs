@@ -18,16 +18,21 @@ use tracing::info;
 use sui::client_commands::{SuiClientCommandResult, SuiClientCommands, WalletContext};
 use sui_core::test_utils::{wait_for_all_txes, wait_for_tx};
 use sui_json_rpc_types::{
-    SplitCoinResponse, SuiEvent, SuiEventEnvelope, SuiEventFilter, SuiMoveStruct, SuiMoveValue,
-    SuiObjectInfo, SuiObjectRead,
+    BcsObjectRead, GetObjectDataResponse, ObjectsPage, SplitCoinResponse, SuiEvent,
+    SuiEventEnvelope, SuiEventFilter, SuiMoveStruct, SuiMoveValue, SuiObjectChange,
+    SuiObjectChangeKind, SuiObjectInfo, SuiObjectRead, TransactionEffectsResponse,
 };
+use sui_node::rest::{openapi_document, BCS_CONTENT_TYPE};
 use sui_node::SuiNode;
 use sui_swarm::memory::Swarm;
 use sui_types::{
     base_types::{ObjectID, SuiAddress, TransactionDigest},
     messages::TransactionInfoRequest,
+    object::Owner,
+    sui_serde::{Base64, Encoding},
 };
 use test_utils::network::setup_network_and_wallet;
+use test_utils::test_cluster::TestCluster;
 
 async fn transfer_coin(
     context: &mut WalletContext,
@@ -44,10 +49,11 @@ async fn transfer_coin(
         object_to_send, sender, receiver
     );
     let res = SuiClientCommands::Transfer {
-        to: receiver,
+        to: receiver.into(),
         coin_object_id: object_to_send,
         gas: None,
-        gas_budget: 50000,
+        gas_budget: Some(50000),
+        estimate_gas: false,
     }
     .execute(context)
     .await?;
@@ -101,10 +107,13 @@ async fn emit_move_events(
 async fn test_full_node_follows_txes() -> Result<(), anyhow::Error> {
     telemetry_subscribers::init_for_testing();
 
-    let (swarm, mut context, _) = setup_network_and_wallet().await?;
-
-    let config = swarm.config().generate_fullnode_config();
-    let node = SuiNode::start(&config).await?;
+    let TestCluster {
+        swarm: _swarm,
+        wallet: mut context,
+        fullnode_handle,
+        ..
+    } = TestCluster::builder().build().await?;
+    let node = fullnode_handle.unwrap().sui_node;
 
     let (transfered_object, _, receiver, digest) = transfer_coin(&mut context).await?;
     wait_for_tx(digest, node.state().clone()).await;
@@ -126,10 +135,13 @@ async fn test_full_node_follows_txes() -> Result<(), anyhow::Error> {
 async fn test_full_node_shared_objects() -> Result<(), anyhow::Error> {
     telemetry_subscribers::init_for_testing();
 
-    let (swarm, context, _) = setup_network_and_wallet().await?;
-
-    let config = swarm.config().generate_fullnode_config();
-    let node = SuiNode::start(&config).await?;
+    let TestCluster {
+        swarm: _swarm,
+        wallet: context,
+        fullnode_handle,
+        ..
+    } = TestCluster::builder().build().await?;
+    let node = fullnode_handle.unwrap().sui_node;
 
     let sender = context.config.accounts.get(0).cloned().unwrap();
 
@@ -146,10 +158,13 @@ const HOUR_MS: u64 = 3_600_000;
 
 #[tokio::test]
 async fn test_full_node_move_function_index() -> Result<(), anyhow::Error> {
-    let (swarm, context, _) = setup_network_and_wallet().await?;
-
-    let config = swarm.config().generate_fullnode_config();
-    let node = SuiNode::start(&config).await?;
+    let TestCluster {
+        swarm: _swarm,
+        wallet: context,
+        fullnode_handle,
+        ..
+    } = TestCluster::builder().build().await?;
+    let node = fullnode_handle.unwrap().sui_node;
     let sender = context.config.accounts.get(0).cloned().unwrap();
     let (package_ref, counter_id) = publish_basics_package_and_make_counter(&context, sender).await;
     let effects = increment_counter(&context, sender, None, package_ref, counter_id).await;
@@ -193,10 +208,13 @@ async fn test_full_node_move_function_index() -> Result<(), anyhow::Error> {
 #[tokio::test]
 async fn test_full_node_indexes() -> Result<(), anyhow::Error> {
     telemetry_subscribers::init_for_testing();
-    let (swarm, mut context, _) = setup_network_and_wallet().await?;
-
-    let config = swarm.config().generate_fullnode_config();
-    let node = SuiNode::start(&config).await?;
+    let TestCluster {
+        swarm: _swarm,
+        wallet: mut context,
+        fullnode_handle,
+        ..
+    } = TestCluster::builder().build().await?;
+    let node = fullnode_handle.unwrap().sui_node;
 
     let (transfered_object, sender, receiver, digest) = transfer_coin(&mut context).await?;
 
@@ -333,9 +351,11 @@ async fn test_full_node_sync_flood() -> Result<(), anyhow::Error> {
                     let context = &mut context.lock().await;
                     SuiClientCommands::SplitCoin {
                         amounts: vec![1],
+                        count: None,
                         coin_id: object_to_split.0,
                         gas: gas_object,
-                        gas_budget: 50000,
+                        gas_budget: Some(50000),
+                        estimate_gas: false,
                     }
                     .execute(context)
                     .await
@@ -470,6 +490,316 @@ async fn test_full_node_sub_to_move_event_ok() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_full_node_sub_to_object_changes_ok() -> Result<(), anyhow::Error> {
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+    // Pass in an unique port for each test case otherwise they may interfere with one another.
+    let (node, ws_client) = set_up_subscription(6668, &swarm).await?;
+
+    // `transfer_coin` sends the second object of the first account
+    let (_, objects) = get_account_and_objects(&mut context).await?;
+    let watched = objects.get(1).unwrap().object_id;
+
+    let mut sub: Subscription<SuiObjectChange> = ws_client
+        .subscribe(
+            "sui_subscribeObjectChanges",
+            rpc_params![vec![watched]],
+            "sui_unsubscribeObjectChanges",
+        )
+        .await
+        .unwrap();
+
+    let (transferred_object, _, receiver, digest) = transfer_coin(&mut context).await?;
+    assert_eq!(watched, transferred_object);
+    wait_for_tx(digest, node.state().clone()).await;
+
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Ok(Some(Ok(change))) => {
+            assert_eq!(change.tx_digest, digest);
+            assert_eq!(change.kind, SuiObjectChangeKind::Transferred);
+            assert_eq!(change.reference.object_id, watched);
+            assert_eq!(change.owner, Some(Owner::AddressOwner(receiver)));
+        }
+        other => panic!("Failed to get SuiObjectChange, but {:?}", other),
+    }
+
+    // Changes of other objects are not sent
+    let (_, _, _, digest) = transfer_coin(&mut context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+    match timeout(Duration::from_secs(5), sub.next()).await {
+        Err(_) => (),
+        other => panic!(
+            "Expect to time out because the watched object did not change. Got {:?}",
+            other
+        ),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_dynamic_fields() -> Result<(), anyhow::Error> {
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+    let (_node, jsonrpc_client) = set_up_jsonrpc(6669, &swarm).await?;
+
+    let (_, objects) = get_account_and_objects(&mut context).await?;
+    let (parent, other) = (objects[0].object_id, objects[1].object_id);
+
+    // Gas coins have no dynamic fields
+    let page: ObjectsPage = jsonrpc_client
+        .request(
+            "sui_getDynamicFields",
+            rpc_params![parent, None::<ObjectID>, 10],
+        )
+        .await?;
+    assert!(page.data.is_empty());
+    assert_eq!(page.next_cursor, None);
+
+    // `other` is owned by an address, not by `parent`
+    let response: Result<GetObjectDataResponse, _> = jsonrpc_client
+        .request("sui_getDynamicFieldObject", rpc_params![parent, other])
+        .await;
+    assert!(response.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_rest_bcs() -> Result<(), anyhow::Error> {
+    use reqwest::header::{ACCEPT, CONTENT_TYPE};
+
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+    // Pass in an unique port for each test case otherwise they may interfere with one another.
+    let rest_address: SocketAddr = "127.0.0.1:6670".parse().unwrap();
+    let mut config = swarm.config().generate_fullnode_config();
+    config.rest_address = Some(rest_address);
+    let node = SuiNode::start(&config).await?;
+
+    let (transferred_object, _, receiver, digest) = transfer_coin(&mut context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    let client = reqwest::Client::new();
+    let object_url = format!("http://{rest_address}/objects/{transferred_object}");
+    let response = client
+        .get(&object_url)
+        .header(ACCEPT, BCS_CONTENT_TYPE)
+        .send()
+        .await?;
+    assert_eq!(response.headers()[CONTENT_TYPE], BCS_CONTENT_TYPE);
+    let object = match bcs::from_bytes(&response.bytes().await?)? {
+        BcsObjectRead::Exists(object) => object,
+        read => panic!("Unexpected object read {read:?}"),
+    };
+    assert_eq!(object.owner.get_owner_address().unwrap(), receiver);
+
+    // The body of the batch request is BCS too, objects are returned in the requested order.
+    let missing = ObjectID::random();
+    let response = client
+        .post(format!("http://{rest_address}/objects"))
+        .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+        .header(ACCEPT, BCS_CONTENT_TYPE)
+        .body(bcs::to_bytes(&vec![transferred_object, missing])?)
+        .send()
+        .await?;
+    let objects: Vec<BcsObjectRead> = bcs::from_bytes(&response.bytes().await?)?;
+    assert_eq!(
+        objects,
+        vec![
+            BcsObjectRead::Exists(object),
+            BcsObjectRead::NotExists(missing)
+        ]
+    );
+
+    // Without the Accept header the response is JSON.
+    let response: GetObjectDataResponse = client.get(&object_url).send().await?.json().await?;
+    assert_eq!(response.object()?.id(), transferred_object);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_rest_routes() -> Result<(), anyhow::Error> {
+    use reqwest::StatusCode;
+
+    let (swarm, mut context, _) = setup_network_and_wallet().await?;
+    // Pass in an unique port for each test case otherwise they may interfere with one another.
+    let rest_address: SocketAddr = "127.0.0.1:6671".parse().unwrap();
+    let mut config = swarm.config().generate_fullnode_config();
+    config.rest_address = Some(rest_address);
+    let node = SuiNode::start(&config).await?;
+
+    // The address of the REST server is bound when the node starts
+    let mut config = swarm.config().generate_fullnode_config();
+    config.rest_address = Some(rest_address);
+    assert!(SuiNode::start(&config).await.is_err());
+
+    let (transferred_object, _, receiver, digest) = transfer_coin(&mut context).await?;
+    wait_for_tx(digest, node.state().clone()).await;
+
+    let client = reqwest::Client::new();
+    let status = |path: String| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .get(format!("http://{rest_address}{path}"))
+                .send()
+                .await?;
+            Ok::<_, anyhow::Error>(response.status())
+        }
+    };
+    // Digests are base-64 encoded, which is escaped in paths
+    let path_digest = |digest: &TransactionDigest| {
+        Base64::encode(digest)
+            .replace('+', "%2B")
+            .replace('/', "%2F")
+            .replace('=', "%3D")
+    };
+
+    let object = client
+        .get(format!(
+            "http://{rest_address}/objects/{transferred_object}"
+        ))
+        .send()
+        .await?;
+    assert_eq!(object.status(), StatusCode::OK);
+    let object: GetObjectDataResponse = object.json().await?;
+    assert_eq!(object.object()?.id(), transferred_object);
+    let missing = ObjectID::random();
+    assert_eq!(
+        status(format!("/objects/{missing}")).await?,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status("/objects/not-an-id".to_string()).await?,
+        StatusCode::BAD_REQUEST
+    );
+
+    let transaction = client
+        .get(format!(
+            "http://{rest_address}/transactions/{}",
+            path_digest(&digest)
+        ))
+        .send()
+        .await?;
+    assert_eq!(transaction.status(), StatusCode::OK);
+    let transaction: TransactionEffectsResponse = transaction.json().await?;
+    assert_eq!(transaction.certificate.transaction_digest, digest);
+    assert_eq!(
+        status(format!("/transactions/{}/events", path_digest(&digest))).await?,
+        StatusCode::OK
+    );
+    let unknown = TransactionDigest::random();
+    assert_eq!(
+        status(format!("/transactions/{}", path_digest(&unknown))).await?,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status(format!("/transactions/{}/events", path_digest(&unknown))).await?,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        status("/transactions/not-a-digest".to_string()).await?,
+        StatusCode::BAD_REQUEST
+    );
+
+    let page: ObjectsPage = client
+        .get(format!(
+            "http://{rest_address}/addresses/{receiver}/objects?limit=10"
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert!(page
+        .data
+        .iter()
+        .any(|info| info.object_id == transferred_object));
+    assert_eq!(
+        status(format!("/addresses/{receiver}/objects?limit=0")).await?,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        status("/addresses/not-an-address/objects".to_string()).await?,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        status(format!("/addresses/{receiver}/transactions/to?limit=1")).await?,
+        StatusCode::OK
+    );
+    assert_eq!(
+        status("/transactions?start=0&end=1".to_string()).await?,
+        StatusCode::OK
+    );
+    // Invalid ranges and queries are rejected as bad requests rather than failing
+    assert_eq!(
+        status("/transactions?start=2&end=1".to_string()).await?,
+        StatusCode::BAD_REQUEST
+    );
+    assert_eq!(
+        status("/transactions?start=zero&end=1".to_string()).await?,
+        StatusCode::BAD_REQUEST
+    );
+
+    let document: serde_json::Value = client
+        .get(format!("http://{rest_address}/openapi.json"))
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(document, openapi_document());
+    Ok(())
+}
+
+#[test]
+fn test_rest_openapi_document() {
+    let document = openapi_document();
+    assert_eq!(document["openapi"], "3.0.3");
+    let paths = document["paths"].as_object().unwrap();
+    for path in [
+        "/objects",
+        "/objects/{object_id}",
+        "/addresses/{address}/objects",
+        "/addresses/{address}/transactions/from",
+        "/addresses/{address}/transactions/to",
+        "/transactions",
+        "/transactions/{digest}",
+        "/transactions/{digest}/events",
+        "/checkpoints/latest",
+        "/checkpoints/{sequence_number}",
+    ] {
+        assert!(paths.contains_key(path), "{path} is not documented");
+    }
+
+    // Every schema referenced is defined
+    fn refs<'a>(value: &'a serde_json::Value, found: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(serde_json::Value::String(target)) = map.get("$ref") {
+                    found.push(target);
+                }
+                map.values().for_each(|value| refs(value, found));
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|value| refs(value, found)),
+            _ => (),
+        }
+    }
+    let mut found = Vec::new();
+    refs(&document, &mut found);
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+    for target in found {
+        let name = target.strip_prefix("#/components/schemas/").unwrap();
+        assert!(schemas.contains_key(name), "{target} is not defined");
+    }
+
+    let checkpoint = &schemas["CheckpointSummary"]["properties"];
+    for field in [
+        "epoch",
+        "sequence_number",
+        "content_digest",
+        "previous_digest",
+    ] {
+        assert!(checkpoint.get(field).is_some(), "{field} is not documented");
+    }
+}
+
 // A test placeholder to verify event read APIs
 // TODO: add real tests when event store integration is done
 #[tokio::test]
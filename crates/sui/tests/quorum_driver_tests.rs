@@ -26,7 +26,11 @@ async fn setup() -> (
     let configs = test_authority_configs();
     let handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
     let clients = test_authority_aggregator(&configs);
-    let tx = make_transfer_sui_transaction(gas_objects.pop().unwrap(), SuiAddress::default());
+    let tx = make_transfer_sui_transaction(
+        gas_objects.pop().unwrap(),
+        SuiAddress::default(),
+        configs.genesis.chain_id(),
+    );
     (handles, clients, tx)
 }
 
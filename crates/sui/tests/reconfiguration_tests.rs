@@ -45,6 +45,7 @@ async fn reconfig_end_to_end_tests() {
                 next_transaction_sequence: 0,
                 no_more_fragments: true,
                 current_proposal: None,
+                epoch_progress: Default::default(),
             })
             .unwrap();
 
@@ -80,19 +81,22 @@ async fn reconfig_end_to_end_tests() {
             active.start_epoch_change().await.unwrap();
             tokio::time::sleep(Duration::from_millis(100)).await;
 
-            node.state()
-                .checkpoints
-                .as_ref()
-                .unwrap()
-                .lock()
-                .set_locals_for_testing(CheckpointLocals {
-                    next_checkpoint: CHECKPOINT_COUNT_PER_EPOCH + 1,
-                    proposal_next_transaction: None,
-                    next_transaction_sequence: 0,
-                    no_more_fragments: true,
-                    current_proposal: None,
-                })
-                .unwrap();
+            // The change of epoch finishes at the checkpoint after the one it started at
+            {
+                let state = node.state();
+                let mut checkpoints = state.checkpoints.as_ref().unwrap().lock();
+                let epoch_progress = checkpoints.get_locals().epoch_progress;
+                checkpoints
+                    .set_locals_for_testing(CheckpointLocals {
+                        next_checkpoint: CHECKPOINT_COUNT_PER_EPOCH + 1,
+                        proposal_next_transaction: None,
+                        next_transaction_sequence: 0,
+                        no_more_fragments: true,
+                        current_proposal: None,
+                        epoch_progress,
+                    })
+                    .unwrap();
+            }
 
             active.finish_epoch_change().await.unwrap();
         })
@@ -152,6 +156,7 @@ pub async fn create_and_register_new_validator(
             CallArg::Pure(bcs::to_bytes(&new_validator.network_address).unwrap()),
             CallArg::Object(ObjectArg::ImmOrOwnedObject(gas_obj)),
         ],
+        state.chain_id,
     );
     submit_single_owner_transaction(validator_tx, validator_info).await
 }
@@ -162,6 +167,9 @@ pub fn get_new_validator() -> ValidatorInfo {
     ValidatorInfo {
         name: "".to_string(),
         public_key: keypair.1.public().into(),
+        network_key: None,
+        worker_key: None,
+        account_key: None,
         stake: 1,
         delegation: 0,
         network_address: sui_config::utils::new_network_address(),
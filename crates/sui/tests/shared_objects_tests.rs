@@ -27,10 +27,11 @@ async fn shared_object_transaction() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(objects, &configs).await;
 
     // Make a test shared object certificate.
-    let transaction = test_shared_object_transactions().pop().unwrap();
+    let transaction = test_shared_object_transactions(chain_id).pop().unwrap();
 
     // Submit the transaction. Note that this transaction is random and we do not expect
     // it to be successfully executed by the Move execution engine.
@@ -49,11 +50,12 @@ async fn many_shared_object_transactions() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(objects, &configs).await;
 
     // Make a test shared object certificate.
     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-    let transaction = test_shared_object_transactions().pop().unwrap();
+    let transaction = test_shared_object_transactions(chain_id).pop().unwrap();
 
     // Submit the transaction. Note that this transaction is random and we do not expect
     // it to be successfully executed by the Move execution engine.
@@ -72,12 +74,17 @@ async fn call_shared_object_contract() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-    let package_ref =
-        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+    let package_ref = publish_counter_package(
+        gas_objects.pop().unwrap(),
+        configs.validator_set(),
+        chain_id,
+    )
+    .await;
 
     // Make a transaction to create a counter.
     tokio::task::yield_now().await;
@@ -87,6 +94,7 @@ async fn call_shared_object_contract() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     let effects = submit_single_owner_transaction(transaction, configs.validator_set()).await;
     assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
@@ -103,6 +111,7 @@ async fn call_shared_object_contract() {
             CallArg::Object(ObjectArg::SharedObject(counter_id)),
             CallArg::Pure(0u64.to_le_bytes().to_vec()),
         ],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, &configs.validator_set()[0..1])
         .await
@@ -117,6 +126,7 @@ async fn call_shared_object_contract() {
         "increment",
         package_ref,
         vec![CallArg::Object(ObjectArg::SharedObject(counter_id))],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, &configs.validator_set()[0..1])
         .await
@@ -134,6 +144,7 @@ async fn call_shared_object_contract() {
             CallArg::Object(ObjectArg::SharedObject(counter_id)),
             CallArg::Pure(1u64.to_le_bytes().to_vec()),
         ],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, &configs.validator_set()[0..1])
         .await
@@ -151,12 +162,17 @@ async fn shared_object_flood() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
 
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
-    let package_ref =
-        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+    let package_ref = publish_counter_package(
+        gas_objects.pop().unwrap(),
+        configs.validator_set(),
+        chain_id,
+    )
+    .await;
 
     // Make a transaction to create a counter.
     tokio::task::yield_now().await;
@@ -166,6 +182,7 @@ async fn shared_object_flood() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     let effects = submit_single_owner_transaction(transaction, configs.validator_set()).await;
     assert!(matches!(effects.status, ExecutionStatus::Success { .. }));
@@ -182,6 +199,7 @@ async fn shared_object_flood() {
             CallArg::Object(ObjectArg::SharedObject(counter_id)),
             CallArg::Pure(0u64.to_le_bytes().to_vec()),
         ],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, configs.validator_set())
         .await
@@ -196,6 +214,7 @@ async fn shared_object_flood() {
         "increment",
         package_ref,
         vec![CallArg::Object(ObjectArg::SharedObject(counter_id))],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, configs.validator_set())
         .await
@@ -213,6 +232,7 @@ async fn shared_object_flood() {
             CallArg::Object(ObjectArg::SharedObject(counter_id)),
             CallArg::Pure(1u64.to_le_bytes().to_vec()),
         ],
+        chain_id,
     );
     let effects = submit_shared_object_transaction(transaction, configs.validator_set())
         .await
@@ -227,12 +247,17 @@ async fn shared_object_sync() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
 
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
-    let package_ref =
-        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+    let package_ref = publish_counter_package(
+        gas_objects.pop().unwrap(),
+        configs.validator_set(),
+        chain_id,
+    )
+    .await;
 
     // Send a transaction to create a counter, but only to one authority.
     tokio::task::yield_now().await;
@@ -242,6 +267,7 @@ async fn shared_object_sync() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     let effects = submit_single_owner_transaction(
         create_counter_transaction.clone(),
@@ -281,6 +307,7 @@ async fn shared_object_sync() {
         "increment",
         package_ref,
         vec![CallArg::Object(ObjectArg::SharedObject(counter_id))],
+        chain_id,
     );
 
     // Let's submit the transaction to the first authority (the only one up-to-date).
@@ -328,12 +355,17 @@ async fn replay_shared_object_transaction() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
 
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
-    let package_ref =
-        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+    let package_ref = publish_counter_package(
+        gas_objects.pop().unwrap(),
+        configs.validator_set(),
+        chain_id,
+    )
+    .await;
 
     // Send a transaction to create a counter (only to one authority) -- twice.
     tokio::task::yield_now().await;
@@ -343,6 +375,7 @@ async fn replay_shared_object_transaction() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     for _ in 0..2 {
         let effects = submit_single_owner_transaction(
@@ -366,17 +399,28 @@ async fn shared_object_on_gateway() {
     // Get the authority configs and spawn them. Note that it is important to not drop
     // the handles (or the authorities will stop).
     let configs = test_authority_configs();
+    let chain_id = configs.genesis.chain_id();
     let _handles = spawn_test_authorities(gas_objects.clone(), &configs).await;
     let clients = test_authority_aggregator(&configs);
     let path = tempfile::tempdir().unwrap().into_path();
     let gateway = Arc::new(
-        GatewayState::new_with_authorities(path, clients, GatewayMetrics::new_for_tests()).unwrap(),
+        GatewayState::new_with_authorities(
+            path,
+            clients,
+            GatewayMetrics::new_for_tests(),
+            chain_id,
+        )
+        .unwrap(),
     );
 
     // Publish the move package to all authorities and get the new package ref.
     tokio::task::yield_now().await;
-    let package_ref =
-        publish_counter_package(gas_objects.pop().unwrap(), configs.validator_set()).await;
+    let package_ref = publish_counter_package(
+        gas_objects.pop().unwrap(),
+        configs.validator_set(),
+        chain_id,
+    )
+    .await;
 
     // Send a transaction to create a counter.
     tokio::task::yield_now().await;
@@ -386,6 +430,7 @@ async fn shared_object_on_gateway() {
         "create",
         package_ref,
         /* arguments */ Vec::default(),
+        chain_id,
     );
     let resp = gateway
         .execute_transaction(create_counter_transaction)
@@ -416,6 +461,7 @@ async fn shared_object_on_gateway() {
                     package_ref,
                     /* arguments */
                     vec![CallArg::Object(ObjectArg::SharedObject(shared_object_id))],
+                    chain_id,
                 );
                 async move { g.execute_transaction(increment_counter_transaction).await }
             })
@@ -444,6 +490,7 @@ async fn shared_object_on_gateway() {
             CallArg::Object(ObjectArg::SharedObject(shared_object_id)),
             CallArg::Pure((increment_amount as u64).to_le_bytes().to_vec()),
         ],
+        chain_id,
     );
 
     // Same problem may happen here (consensus may drop transactions).
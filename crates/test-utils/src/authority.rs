@@ -7,16 +7,20 @@ use std::sync::Arc;
 use std::time::Duration;
 use sui_config::{NetworkConfig, ValidatorInfo};
 use sui_core::{
+    authority::StableSyncAuthoritySigner,
     authority_active::{
         checkpoint_driver::{CheckpointMetrics, CheckpointProcessControl},
         ActiveAuthority,
     },
     authority_aggregator::{AuthAggMetrics, AuthorityAggregator},
     authority_client::{AuthorityAPI, NetworkAuthorityClient},
+    byzantine::{ByzantineAuthorityClient, ByzantineConfig},
 };
 use sui_node::SuiNode;
 use sui_types::{
+    base_types::AuthorityName,
     committee::Committee,
+    crypto::KeypairTraits,
     error::SuiResult,
     messages::{Transaction, TransactionInfoResponse},
     object::Object,
@@ -107,7 +111,11 @@ pub fn test_authority_aggregator(
         .map(|config| {
             (
                 config.public_key(),
-                NetworkAuthorityClient::connect_lazy(config.network_address()).unwrap(),
+                NetworkAuthorityClient::connect_lazy(
+                    config.network_address(),
+                    config.network_key(),
+                )
+                .unwrap(),
             )
         })
         .collect();
@@ -115,9 +123,37 @@ pub fn test_authority_aggregator(
     AuthorityAggregator::new(committee, clients, metrics)
 }
 
+/// Create a test authority aggregator whose clients misbehave as told by the configs of their
+/// authorities in `byzantine`, the other authorities being honest.
+pub fn test_byzantine_authority_aggregator(
+    config: &NetworkConfig,
+    byzantine: &BTreeMap<AuthorityName, ByzantineConfig>,
+) -> AuthorityAggregator<ByzantineAuthorityClient<NetworkAuthorityClient>> {
+    let committee = config.committee();
+    let clients: BTreeMap<_, _> = config
+        .validator_configs()
+        .iter()
+        .map(|validator| {
+            let name = validator.public_key();
+            let client = NetworkAuthorityClient::connect_lazy(
+                &validator.network_address,
+                validator.network_public_key(),
+            )
+            .unwrap();
+            let secret: StableSyncAuthoritySigner = Arc::pin(validator.key_pair().copy());
+            let config = byzantine.get(&name).cloned().unwrap_or_default();
+            let client =
+                ByzantineAuthorityClient::new(name, committee.epoch, secret, client, config);
+            (name, client)
+        })
+        .collect();
+    let metrics = AuthAggMetrics::new(&prometheus::Registry::new());
+    AuthorityAggregator::new(committee, clients, metrics)
+}
+
 /// Get a network client to communicate with the consensus.
 pub fn get_client(config: &ValidatorInfo) -> NetworkAuthorityClient {
-    NetworkAuthorityClient::connect_lazy(config.network_address()).unwrap()
+    NetworkAuthorityClient::connect_lazy(config.network_address(), config.network_key()).unwrap()
 }
 
 /// Submit a certificate containing only owned-objects to all authorities.
@@ -8,8 +8,8 @@ use move_core_types::ident_str;
 use move_package::BuildConfig;
 use std::path::PathBuf;
 use sui_adapter::genesis;
-use sui_types::base_types::ObjectID;
 use sui_types::base_types::ObjectRef;
+use sui_types::base_types::{ChainId, ObjectID};
 use sui_types::crypto::KeypairTraits;
 use sui_types::messages::{
     CertifiedTransaction, ObjectArg, SignatureAggregator, SignedTransaction, Transaction,
@@ -22,8 +22,9 @@ use sui_types::{crypto::KeyPair, messages::CallArg};
 /// The maximum gas per transaction.
 pub const MAX_GAS: u64 = 10_000;
 
-/// Make a few different single-writer test transactions owned by specific addresses.
-pub fn test_transactions<K>(keys: K) -> (Vec<Transaction>, Vec<Object>)
+/// Make a few different single-writer test transactions owned by specific addresses, on the
+/// chain `chain_id`.
+pub fn test_transactions<K>(keys: K, chain_id: ChainId) -> (Vec<Transaction>, Vec<Object>)
 where
     K: Iterator<Item = KeyPair>,
 {
@@ -56,6 +57,7 @@ where
             /* sender */ o1.owner.get_owner_address().unwrap(),
             /* gas_object_ref */ o2.compute_object_reference(),
             MAX_GAS,
+            chain_id,
         );
         let signature = Signature::new(&data, &keypair);
         transactions.push(Transaction::new(data, signature));
@@ -63,8 +65,9 @@ where
     (transactions, gas_objects)
 }
 
-/// Make a few different test transaction containing the same shared object.
-pub fn test_shared_object_transactions() -> Vec<Transaction> {
+/// Make a few different test transaction containing the same shared object, on the chain
+/// `chain_id`.
+pub fn test_shared_object_transactions(chain_id: ChainId) -> Vec<Transaction> {
     // Helper function to load genesis packages.
     fn get_genesis_package_by_module(genesis_objects: &[Object], module: &str) -> ObjectRef {
         genesis_objects
@@ -108,6 +111,7 @@ pub fn test_shared_object_transactions() -> Vec<Transaction> {
                 CallArg::Pure(bcs::to_bytes(&AccountAddress::from(sender)).unwrap()),
             ],
             MAX_GAS,
+            chain_id,
         );
         let signature = Signature::new(&data, &keypair);
         transactions.push(Transaction::new(data, signature));
@@ -116,7 +120,11 @@ pub fn test_shared_object_transactions() -> Vec<Transaction> {
 }
 
 /// Make a transaction to publish a test move contracts package.
-pub fn create_publish_move_package_transaction(gas_object: Object, path: PathBuf) -> Transaction {
+pub fn create_publish_move_package_transaction(
+    gas_object: Object,
+    path: PathBuf,
+    chain_id: ChainId,
+) -> Transaction {
     let build_config = BuildConfig::default();
     let modules = sui_framework::build_move_package(&path, build_config).unwrap();
 
@@ -131,12 +139,17 @@ pub fn create_publish_move_package_transaction(gas_object: Object, path: PathBuf
 
     let gas_object_ref = gas_object.compute_object_reference();
     let (sender, keypair) = test_keys().pop().unwrap();
-    let data = TransactionData::new_module(sender, gas_object_ref, all_module_bytes, MAX_GAS);
+    let data =
+        TransactionData::new_module(sender, gas_object_ref, all_module_bytes, MAX_GAS, chain_id);
     let signature = Signature::new(&data, &keypair);
     Transaction::new(data, signature)
 }
 
-pub fn make_transfer_sui_transaction(gas_object: Object, recipient: SuiAddress) -> Transaction {
+pub fn make_transfer_sui_transaction(
+    gas_object: Object,
+    recipient: SuiAddress,
+    chain_id: ChainId,
+) -> Transaction {
     let (sender, keypair) = test_keys().pop().unwrap();
     let data = TransactionData::new_transfer_sui(
         recipient,
@@ -144,6 +157,7 @@ pub fn make_transfer_sui_transaction(gas_object: Object, recipient: SuiAddress)
         None,
         gas_object.compute_object_reference(),
         MAX_GAS,
+        chain_id,
     );
     let signature = Signature::new(&data, &keypair);
     Transaction::new(data, signature)
@@ -155,34 +169,73 @@ pub fn make_transfer_object_transaction(
     sender: SuiAddress,
     keypair: &KeyPair,
     recipient: SuiAddress,
+    chain_id: ChainId,
 ) -> Transaction {
-    let data = TransactionData::new_transfer(recipient, object_ref, sender, gas_object, MAX_GAS);
+    let data =
+        TransactionData::new_transfer(recipient, object_ref, sender, gas_object, MAX_GAS, chain_id);
     let signature = Signature::new(&data, keypair);
     Transaction::new(data, signature)
 }
 
-pub fn make_publish_basics_transaction(gas_object: ObjectRef) -> Transaction {
-    let (sender, keypair) = test_keys().pop().unwrap();
+/// The bytes of the modules of the basics example package.
+pub fn compile_basics_package() -> Vec<Vec<u8>> {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("../../sui_programmability/examples/basics");
     let build_config = BuildConfig::default();
     let modules = sui_framework::build_move_package(&path, build_config).unwrap();
-    let all_module_bytes = modules
+    modules
         .iter()
         .map(|m| {
             let mut module_bytes = Vec::new();
             m.serialize(&mut module_bytes).unwrap();
             module_bytes
         })
-        .collect();
-    let data = TransactionData::new_module(sender, gas_object, all_module_bytes, MAX_GAS);
-    let signature = Signature::new(&data, &keypair);
+        .collect()
+}
+
+pub fn make_publish_basics_transaction(gas_object: ObjectRef, chain_id: ChainId) -> Transaction {
+    let (sender, keypair) = test_keys().pop().unwrap();
+    make_publish_transaction(
+        gas_object,
+        compile_basics_package(),
+        sender,
+        &keypair,
+        chain_id,
+    )
+}
+
+pub fn make_publish_transaction(
+    gas_object: ObjectRef,
+    modules: Vec<Vec<u8>>,
+    sender: SuiAddress,
+    keypair: &KeyPair,
+    chain_id: ChainId,
+) -> Transaction {
+    let data = TransactionData::new_module(sender, gas_object, modules, MAX_GAS, chain_id);
+    let signature = Signature::new(&data, keypair);
+    Transaction::new(data, signature)
+}
+
+pub fn make_pay_transaction(
+    coins: Vec<ObjectRef>,
+    gas_object: ObjectRef,
+    sender: SuiAddress,
+    keypair: &KeyPair,
+    recipients: Vec<SuiAddress>,
+    amounts: Vec<u64>,
+    chain_id: ChainId,
+) -> Transaction {
+    let data = TransactionData::new_pay(
+        sender, coins, recipients, amounts, gas_object, MAX_GAS, chain_id,
+    );
+    let signature = Signature::new(&data, keypair);
     Transaction::new(data, signature)
 }
 
 pub fn make_counter_create_transaction(
     gas_object: ObjectRef,
     package_ref: ObjectRef,
+    chain_id: ChainId,
 ) -> Transaction {
     let (sender, keypair) = test_keys().pop().unwrap();
     let data = TransactionData::new_move_call(
@@ -194,6 +247,7 @@ pub fn make_counter_create_transaction(
         gas_object,
         vec![],
         MAX_GAS,
+        chain_id,
     );
     let signature = Signature::new(&data, &keypair);
     Transaction::new(data, signature)
@@ -203,6 +257,7 @@ pub fn make_counter_increment_transaction(
     gas_object: ObjectRef,
     package_ref: ObjectRef,
     counter_id: ObjectID,
+    chain_id: ChainId,
 ) -> Transaction {
     let (sender, keypair) = test_keys().pop().unwrap();
     let data = TransactionData::new_move_call(
@@ -214,6 +269,7 @@ pub fn make_counter_increment_transaction(
         gas_object,
         vec![CallArg::Object(ObjectArg::SharedObject(counter_id))],
         MAX_GAS,
+        chain_id,
     );
     let signature = Signature::new(&data, &keypair);
     Transaction::new(data, signature)
@@ -226,6 +282,7 @@ pub fn move_transaction(
     function: &'static str,
     package_ref: ObjectRef,
     arguments: Vec<CallArg>,
+    chain_id: ChainId,
 ) -> Transaction {
     // The key pair of the sender of the transaction.
     let (sender, keypair) = test_keys().pop().unwrap();
@@ -240,6 +297,7 @@ pub fn move_transaction(
         gas_object.compute_object_reference(),
         arguments,
         MAX_GAS,
+        chain_id,
     );
     let signature = Signature::new(&data, &keypair);
     Transaction::new(data, signature)
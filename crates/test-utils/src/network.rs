@@ -1,6 +1,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::test_cluster::TestCluster;
 use jsonrpsee_http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee_http_server::{HttpServerBuilder, HttpServerHandle, RpcModule};
 use std::net::SocketAddr;
@@ -8,7 +9,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
 use sui::{
-    client_commands::{SuiClientCommands, WalletContext},
+    client_commands::WalletContext,
     config::{GatewayConfig, GatewayType, SuiClientConfig},
 };
 use sui_config::genesis_config::GenesisConfig;
@@ -39,9 +40,17 @@ pub async fn start_test_network(
 pub async fn start_test_network_with_fullnodes(
     genesis_config: Option<GenesisConfig>,
     fullnode_count: usize,
+) -> Result<Swarm, anyhow::Error> {
+    start_test_network_with_committee_size(genesis_config, fullnode_count, NUM_VALIDAOTR).await
+}
+
+pub async fn start_test_network_with_committee_size(
+    genesis_config: Option<GenesisConfig>,
+    fullnode_count: usize,
+    committee_size: usize,
 ) -> Result<Swarm, anyhow::Error> {
     let mut builder: SwarmBuilder = Swarm::builder()
-        .committee_size(NonZeroUsize::new(NUM_VALIDAOTR).unwrap())
+        .committee_size(NonZeroUsize::new(committee_size).unwrap())
         .with_fullnode_count(fullnode_count);
     if let Some(genesis_config) = genesis_config {
         builder = builder.initial_accounts_config(genesis_config);
@@ -73,25 +82,20 @@ pub async fn start_test_network_with_fullnodes(
     keystore.save()?;
 
     let validators = swarm.config().validator_set().to_owned();
+    let chain_id = swarm.config().genesis.chain_id();
     let active_address = accounts.get(0).copied();
 
-    GatewayConfig {
-        db_folder_path: db_folder_path.clone(),
-        validator_set: validators.clone(),
-        ..Default::default()
-    }
-    .save(gateway_path)?;
+    GatewayConfig::new(validators.clone(), chain_id, db_folder_path.clone()).save(gateway_path)?;
 
     // Create wallet config with stated authorities port
     SuiClientConfig {
         accounts,
         keystore: KeystoreType::File(keystore_path),
-        gateway: GatewayType::Embedded(GatewayConfig {
-            db_folder_path,
-            validator_set: validators,
-            ..Default::default()
-        }),
+        gateway: GatewayType::Embedded(GatewayConfig::new(validators, chain_id, db_folder_path)),
         active_address,
+        envs: vec![],
+        active_env: None,
+        name_registry: None,
     }
     .save(&wallet_path)?;
 
@@ -101,20 +105,9 @@ pub async fn start_test_network_with_fullnodes(
 
 pub async fn setup_network_and_wallet() -> Result<(Swarm, WalletContext, SuiAddress), anyhow::Error>
 {
-    let swarm = start_test_network(None).await?;
-
-    // Create Wallet context.
-    let wallet_conf = swarm.dir().join(SUI_CLIENT_CONFIG);
-    let mut context = WalletContext::new(&wallet_conf)?;
-    let address = context.config.accounts.first().cloned().unwrap();
-
-    // Sync client to retrieve objects from the network.
-    SuiClientCommands::SyncClientState {
-        address: Some(address),
-    }
-    .execute(&mut context)
-    .await?;
-    Ok((swarm, context, address))
+    let cluster = TestCluster::builder().without_fullnode().build().await?;
+    let address = cluster.get_address_0();
+    Ok((cluster.swarm, cluster.wallet, address))
 }
 
 async fn start_rpc_gateway(
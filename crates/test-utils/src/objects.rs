@@ -1,9 +1,8 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use crate::test_keys;
-use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
-use sui_types::gas_coin::GasCoin;
-use sui_types::object::{MoveObject, Object, Owner, OBJECT_START_VERSION};
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::object::Object;
 
 /// Make a few test gas objects (all with the same owner).
 pub fn test_gas_objects() -> Vec<Object> {
@@ -65,7 +64,5 @@ where
 pub fn test_shared_object() -> Object {
     let seed = "0x6666666666666660";
     let shared_object_id = ObjectID::from_hex_literal(seed).unwrap();
-    let content = GasCoin::new(shared_object_id, OBJECT_START_VERSION, 10);
-    let obj = MoveObject::new_gas_coin(content.to_bcs_bytes());
-    Object::new_move(obj, Owner::Shared, TransactionDigest::genesis())
+    Object::shared_for_testing(shared_object_id, 10)
 }
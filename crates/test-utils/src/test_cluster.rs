@@ -0,0 +1,139 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use jsonrpsee_http_client::{HttpClient, HttpClientBuilder};
+use sui::client_commands::{SuiClientCommands, WalletContext};
+use sui_config::genesis_config::GenesisConfig;
+use sui_config::SUI_CLIENT_CONFIG;
+use sui_node::SuiNode;
+use sui_swarm::memory::Swarm;
+use sui_types::base_types::SuiAddress;
+
+use crate::network::start_test_network_with_committee_size;
+
+const NUM_VALIDATORS: usize = 4;
+
+/// A fullnode following a test cluster, with a client of its JSON-RPC server.
+pub struct FullNodeHandle {
+    pub sui_node: SuiNode,
+    pub rpc_client: HttpClient,
+    pub rpc_url: String,
+}
+
+/// A network of validators, and a fullnode unless built without, running in process, with the
+/// wallet of its funded test accounts. Its configs and stores are kept in a temporary directory,
+/// cleaned up when the cluster is dropped.
+pub struct TestCluster {
+    pub swarm: Swarm,
+    pub accounts: Vec<SuiAddress>,
+    pub wallet: WalletContext,
+    pub fullnode_handle: Option<FullNodeHandle>,
+}
+
+impl TestCluster {
+    pub fn builder() -> TestClusterBuilder {
+        TestClusterBuilder::new()
+    }
+
+    pub fn swarm(&self) -> &Swarm {
+        &self.swarm
+    }
+
+    pub fn wallet_mut(&mut self) -> &mut WalletContext {
+        &mut self.wallet
+    }
+
+    /// The first of the funded test accounts, the active address of the wallet.
+    pub fn get_address_0(&self) -> SuiAddress {
+        self.accounts[0]
+    }
+
+    pub fn fullnode(&self) -> Option<&FullNodeHandle> {
+        self.fullnode_handle.as_ref()
+    }
+
+    /// The JSON-RPC client of the fullnode, panics if the cluster was built without one.
+    pub fn rpc_client(&self) -> &HttpClient {
+        &self
+            .fullnode_handle
+            .as_ref()
+            .expect("The test cluster has no fullnode")
+            .rpc_client
+    }
+}
+
+pub struct TestClusterBuilder {
+    num_validators: usize,
+    genesis_config: Option<GenesisConfig>,
+    fullnode: bool,
+}
+
+impl TestClusterBuilder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            num_validators: NUM_VALIDATORS,
+            genesis_config: None,
+            fullnode: true,
+        }
+    }
+
+    /// Defaults to 4 validators.
+    pub fn with_num_validators(mut self, num_validators: usize) -> Self {
+        self.num_validators = num_validators;
+        self
+    }
+
+    /// The accounts and objects of the genesis, defaults to a few accounts funded with gas
+    /// coins.
+    pub fn set_genesis_config(mut self, genesis_config: GenesisConfig) -> Self {
+        self.genesis_config = Some(genesis_config);
+        self
+    }
+
+    /// Build the cluster without a fullnode.
+    pub fn without_fullnode(mut self) -> Self {
+        self.fullnode = false;
+        self
+    }
+
+    /// Start the validators and the fullnode, and sync the wallet with the objects of its active
+    /// address.
+    pub async fn build(self) -> Result<TestCluster, anyhow::Error> {
+        let swarm = start_test_network_with_committee_size(
+            self.genesis_config,
+            /* fullnode_count */ 0,
+            self.num_validators,
+        )
+        .await?;
+
+        let mut wallet = WalletContext::new(&swarm.dir().join(SUI_CLIENT_CONFIG))?;
+        let accounts = wallet.config.accounts.clone();
+        SuiClientCommands::SyncClientState {
+            address: Some(accounts[0]),
+        }
+        .execute(&mut wallet)
+        .await?;
+
+        let fullnode_handle = if self.fullnode {
+            let config = swarm.config().generate_fullnode_config();
+            let sui_node = SuiNode::start(&config).await?;
+            let rpc_url = format!("http://{}", config.json_rpc_address);
+            let rpc_client = HttpClientBuilder::default().build(&rpc_url)?;
+            Some(FullNodeHandle {
+                sui_node,
+                rpc_client,
+                rpc_url,
+            })
+        } else {
+            None
+        };
+
+        Ok(TestCluster {
+            swarm,
+            accounts,
+            wallet,
+            fullnode_handle,
+        })
+    }
+}
@@ -12,7 +12,7 @@ use sui_core::authority_client::AuthorityAPI;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{TransactionEffectsResponse, TransactionResponse};
 use sui_types::base_types::ObjectRef;
-use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::base_types::{ChainId, ObjectID, SuiAddress};
 use sui_types::error::SuiResult;
 use sui_types::messages::{Transaction, TransactionEffects, TransactionInfoResponse};
 use sui_types::object::{Object, Owner};
@@ -22,17 +22,22 @@ pub async fn publish_package(
     gas_object: Object,
     path: PathBuf,
     configs: &[ValidatorInfo],
+    chain_id: ChainId,
 ) -> ObjectRef {
-    let transaction = create_publish_move_package_transaction(gas_object, path);
+    let transaction = create_publish_move_package_transaction(gas_object, path, chain_id);
     let effects = submit_single_owner_transaction(transaction, configs).await;
     parse_package_ref(&effects).unwrap()
 }
 
 /// Helper function to publish the move package of a simple shared counter.
-pub async fn publish_counter_package(gas_object: Object, configs: &[ValidatorInfo]) -> ObjectRef {
+pub async fn publish_counter_package(
+    gas_object: Object,
+    configs: &[ValidatorInfo],
+    chain_id: ChainId,
+) -> ObjectRef {
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     path.push("../../sui_programmability/examples/basics");
-    publish_package(gas_object, path, configs).await
+    publish_package(gas_object, path, configs, chain_id).await
 }
 
 /// A helper function to publish basic package using gateway API
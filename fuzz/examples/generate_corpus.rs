@@ -0,0 +1,159 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Write the seeds of the corpus of every fuzz target to `corpus/<target>/`, or to the directory
+//! given as argument: the modules of the Sui framework and of the Move standard library, and
+//! valid transactions and messages, for the fuzzer to start from inputs which go past decoding.
+//!
+//! Run with `cargo run --example generate_corpus` from this directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rand::{rngs::StdRng, SeedableRng};
+use sui_network::api::validator;
+use sui_network::compression::{self, Compression};
+use sui_network::prost::Message;
+use sui_types::base_types::{ChainId, ObjectDigest, ObjectID, SequenceNumber};
+use sui_types::crypto::{get_key_pair_from_rng, Signable, Signature};
+use sui_types::handshake::Handshake;
+use sui_types::messages::*;
+use sui_types::messages_checkpoint::CheckpointRequest;
+
+fn write_seed(dir: &Path, target: &str, name: &str, bytes: &[u8]) -> std::io::Result<()> {
+    let dir = dir.join(target);
+    fs::create_dir_all(&dir)?;
+    fs::write(dir.join(name), bytes)
+}
+
+/// A message of the `network_messages` target, whose first byte picks the type of the message.
+fn network_message(dir: &Path, name: &str, selector: u8, message: Vec<u8>) -> std::io::Result<()> {
+    write_seed(
+        dir,
+        "network_messages",
+        name,
+        &[&[selector][..], &message].concat(),
+    )?;
+    let compressed = compression::encode(Some(Compression::Zstd), 0, message)?;
+    write_seed(
+        dir,
+        "network_messages",
+        &format!("{name}-zstd"),
+        &[&[selector | 1 << 5][..], &compressed].concat(),
+    )
+}
+
+/// The message of `value`, encoded as on the wire.
+fn encode<M: Message + From<T>, T>(value: T) -> Vec<u8> {
+    M::from(value).encode_to_vec()
+}
+
+fn main() -> std::io::Result<()> {
+    let dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("corpus"));
+
+    let modules = sui_framework::get_move_stdlib()
+        .into_iter()
+        .map(|module| ("std", module))
+        .chain(
+            sui_framework::get_sui_framework()
+                .into_iter()
+                .map(|module| ("sui", module)),
+        );
+    for (package, module) in modules {
+        let mut bytes = Vec::new();
+        module.serialize(&mut bytes).unwrap();
+        let name = format!("{package}-{}", module.self_id().name());
+        write_seed(&dir, "compiled_module", &name, &bytes)?;
+        write_seed(&dir, "verifier", &name, &bytes)?;
+    }
+
+    let mut rng = StdRng::from_seed([0; 32]);
+    let (sender, key_pair) = get_key_pair_from_rng(&mut rng);
+    let (recipient, _) = get_key_pair_from_rng(&mut rng);
+    let object_ref = |byte| {
+        (
+            ObjectID::from_single_byte(byte),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::new([byte; 32]),
+        )
+    };
+    let data = TransactionData::new_transfer(
+        recipient,
+        object_ref(1),
+        sender,
+        object_ref(2),
+        1000,
+        ChainId([0; 32]),
+    );
+    let mut signable = Vec::new();
+    data.write(&mut signable);
+    let transaction = Transaction::new(data.clone(), Signature::new(&data, &key_pair));
+    write_seed(&dir, "transaction_envelope", "signable_transfer", &signable)?;
+    write_seed(
+        &dir,
+        "transaction_envelope",
+        "transfer_bcs",
+        &bcs::to_bytes(&transaction).unwrap(),
+    )?;
+    write_seed(
+        &dir,
+        "transaction_envelope",
+        "transfer_protobuf",
+        &encode::<validator::Transaction, _>(transaction.clone()),
+    )?;
+
+    network_message(
+        &dir,
+        "handshake",
+        0,
+        encode::<validator::Handshake, _>(Handshake::new(ChainId([0; 32]))),
+    )?;
+    network_message(
+        &dir,
+        "transaction",
+        1,
+        encode::<validator::Transaction, _>(transaction.clone()),
+    )?;
+    network_message(
+        &dir,
+        "account_info",
+        3,
+        encode::<validator::AccountInfoRequest, _>(AccountInfoRequest { account: sender }),
+    )?;
+    network_message(
+        &dir,
+        "object_info",
+        4,
+        encode::<validator::ObjectInfoRequest, _>(ObjectInfoRequest::latest_object_info_request(
+            ObjectID::from_single_byte(1),
+            None,
+        )),
+    )?;
+    network_message(
+        &dir,
+        "transaction_info",
+        5,
+        encode::<validator::TransactionInfoRequest, _>(TransactionInfoRequest::from(
+            *transaction.digest(),
+        )),
+    )?;
+    network_message(
+        &dir,
+        "checkpoint",
+        6,
+        encode::<validator::CheckpointRequest, _>(CheckpointRequest::proposal(true)),
+    )?;
+    network_message(
+        &dir,
+        "batch_info",
+        7,
+        encode::<validator::BatchInfoRequest, _>(BatchInfoRequest {
+            start: Some(0),
+            length: 10,
+        }),
+    )?;
+    Ok(())
+}
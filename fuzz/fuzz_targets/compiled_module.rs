@@ -0,0 +1,22 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The deserialization of the modules of published packages, from their bytes in the publish
+//! transaction or in the package object.
+//!
+//! Packages keep their modules as serialized bytes, deserialized with
+//! `CompiledModule::deserialize` on publishing and on every call, so that is what this target
+//! fuzzes: there is no `SerdeCompiledModule` wrapper in this tree.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use move_binary_format::CompiledModule;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(module) = CompiledModule::deserialize(bytes) {
+        let mut serialized = Vec::new();
+        module.serialize(&mut serialized).unwrap();
+        assert_eq!(CompiledModule::deserialize(&serialized).unwrap(), module);
+    }
+});
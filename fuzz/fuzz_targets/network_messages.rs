@@ -0,0 +1,59 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The messages of the validator interface, encoded as on the wire, then converted to the values
+//! they carry.
+//!
+//! The first byte of the input picks the type of the message and whether it is decoded the way
+//! connections which negotiated compression receive them, the rest is the message.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sui_network::api::validator;
+use sui_network::compression::{self, Compression};
+use sui_network::prost::Message;
+use sui_types::handshake::Handshake;
+use sui_types::messages::*;
+use sui_types::messages_checkpoint::{CheckpointRequest, CheckpointResponse};
+
+/// The largest message decompressed, to avoid reporting decompression bombs as crashes.
+const MAX_MESSAGE_SIZE: usize = 1 << 20;
+
+fn decode<M: Message + Default, T: TryFrom<M>>(bytes: &[u8]) {
+    if let Ok(message) = M::decode(bytes) {
+        let _ = T::try_from(message);
+    }
+}
+
+fuzz_target!(|bytes: &[u8]| {
+    let (selector, message) = match bytes.split_first() {
+        Some((selector, message)) => (*selector, message),
+        None => return,
+    };
+    let compression = match selector >> 5 {
+        0 => None,
+        1 => Some(Compression::Zstd),
+        _ => Some(Compression::Snappy),
+    };
+    let message = match compression::decode(compression, message.to_vec(), MAX_MESSAGE_SIZE) {
+        Ok(message) => message,
+        Err(_) => return,
+    };
+    match selector & 0x1f {
+        0 => decode::<validator::Handshake, Handshake>(&message),
+        1 => decode::<validator::Transaction, Transaction>(&message),
+        2 => decode::<validator::CertifiedTransaction, CertifiedTransaction>(&message),
+        3 => decode::<validator::AccountInfoRequest, AccountInfoRequest>(&message),
+        4 => decode::<validator::ObjectInfoRequest, ObjectInfoRequest>(&message),
+        5 => decode::<validator::TransactionInfoRequest, TransactionInfoRequest>(&message),
+        6 => decode::<validator::CheckpointRequest, CheckpointRequest>(&message),
+        7 => decode::<validator::BatchInfoRequest, BatchInfoRequest>(&message),
+        8 => decode::<validator::TransactionInfoResponse, TransactionInfoResponse>(&message),
+        9 => decode::<validator::AccountInfoResponse, AccountInfoResponse>(&message),
+        10 => decode::<validator::ObjectInfoResponse, ObjectInfoResponse>(&message),
+        11 => decode::<validator::CheckpointResponse, CheckpointResponse>(&message),
+        12 => decode::<validator::BatchInfoResponseItem, BatchInfoResponseItem>(&message),
+        _ => (),
+    }
+});
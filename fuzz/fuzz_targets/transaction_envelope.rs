@@ -0,0 +1,42 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transactions as they reach nodes: signed envelopes, in BCS and in the protobuf message of the
+//! validator interface, and the signable bytes of transaction data the gateway is sent.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use sui_network::api::validator;
+use sui_network::prost::Message;
+use sui_types::crypto::{Signable, SignableBytes};
+use sui_types::messages::{Transaction, TransactionData};
+
+fn check(transaction: Transaction) {
+    // Verifying and hashing arbitrary transactions must fail cleanly, never panic.
+    let _ = transaction.verify();
+    let _ = transaction.digest();
+
+    let bytes = bcs::to_bytes(&transaction).unwrap();
+    let decoded: Transaction = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, transaction);
+}
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(transaction) = bcs::from_bytes::<Transaction>(bytes) {
+        check(transaction);
+    }
+    if let Ok(message) = validator::Transaction::decode(bytes) {
+        if let Ok(transaction) = Transaction::try_from(message) {
+            check(transaction);
+        }
+    }
+    if let Ok(data) = TransactionData::from_signable_bytes(bytes) {
+        let mut signable = Vec::new();
+        data.write(&mut signable);
+        assert_eq!(
+            TransactionData::from_signable_bytes(&signable).unwrap(),
+            data
+        );
+    }
+});
@@ -0,0 +1,20 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The verification of the modules of published packages: the Move bytecode verifier, then the
+//! Sui verifier on the modules the Move one accepts, as when publishing.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use move_binary_format::CompiledModule;
+
+fuzz_target!(|bytes: &[u8]| {
+    let module = match CompiledModule::deserialize(bytes) {
+        Ok(module) => module,
+        Err(_) => return,
+    };
+    if move_bytecode_verifier::verify_module(&module).is_ok() {
+        let _ = sui_verifier::verifier::verify_module(&module);
+    }
+});
@@ -20,18 +20,26 @@ export type SuiChangeEpoch = {
   computation_charge: number;
 };
 
+export type SuiPay = {
+  coins: SuiObjectRef[];
+  recipients: SuiAddress[];
+  amounts: number[];
+};
+
 export type TransactionKindName =
   | 'TransferObject'
   | 'Publish'
   | 'Call'
   | 'TransferSui'
-  | 'ChangeEpoch';
+  | 'ChangeEpoch'
+  | 'Pay';
 export type SuiTransactionKind =
   | { TransferObject: TransferObject }
   | { Publish: SuiMovePackage }
   | { Call: MoveCall }
   | { TransferSui: SuiTransferSui }
-  | { ChangeEpoch: SuiChangeEpoch };
+  | { ChangeEpoch: SuiChangeEpoch }
+  | { Pay: SuiPay };
 export type TransactionData = {
   transactions: SuiTransactionKind[];
   sender: SuiAddress;
@@ -251,6 +259,12 @@ export function getChangeEpochTransaction(
   return 'ChangeEpoch' in data ? data.ChangeEpoch : undefined;
 }
 
+export function getPayTransaction(
+  data: SuiTransactionKind
+): SuiPay | undefined {
+  return 'Pay' in data ? data.Pay : undefined;
+}
+
 export function getTransactions(
   data: CertifiedTransaction
 ): SuiTransactionKind[] {